@@ -0,0 +1,178 @@
+use std::{
+    collections::BTreeMap,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use overlay::{
+    FontAtlasBuilder,
+    UnicodeTextRenderer,
+};
+
+use crate::{
+    settings::AppSettings,
+    view::ViewController,
+    Application,
+};
+
+/// Display size of the offscreen imgui context used to render the enhancements
+const BENCHMARK_DISPLAY_SIZE: [f32; 2] = [1920.0, 1080.0];
+
+#[derive(Default)]
+struct EnhancementSamples {
+    /// Update times in milliseconds
+    update: Vec<f32>,
+    /// Render times in milliseconds
+    render: Vec<f32>,
+}
+
+/// Samples of every frame of a benchmark run
+#[derive(Default)]
+struct BenchmarkSamples {
+    enhancements: BTreeMap<&'static str, EnhancementSamples>,
+    /// Total frame times in milliseconds
+    frame_times: Vec<f32>,
+    read_calls: Vec<f32>,
+}
+
+/// Value at the given percentile (0.0 - 1.0) of the samples
+fn percentile(samples: &mut [f32], percentile: f32) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    samples.sort_by(f32::total_cmp);
+    let index = ((samples.len() - 1) as f32 * percentile).round() as usize;
+    samples[index]
+}
+
+fn format_percentiles(samples: &mut [f32], precision: usize) -> String {
+    format!(
+        "{:>9.precision$} {:>9.precision$} {:>9.precision$} {:>9.precision$}",
+        percentile(samples, 0.5),
+        percentile(samples, 0.95),
+        percentile(samples, 0.99),
+        percentile(samples, 1.0),
+        precision = precision
+    )
+}
+
+impl BenchmarkSamples {
+    fn print_report(&mut self, duration: Duration) {
+        println!(
+            "Benchmarked {} frames within {:.1}s",
+            self.frame_times.len(),
+            duration.as_secs_f32()
+        );
+        println!();
+        println!(
+            "{:<28} {:>9} {:>9} {:>9} {:>9}",
+            "Percentile (ms)", "p50", "p95", "p99", "max"
+        );
+
+        for (name, samples) in self.enhancements.iter_mut() {
+            println!("{}", name);
+            println!(
+                "{:<28} {}",
+                "  update",
+                format_percentiles(&mut samples.update, 3)
+            );
+            println!(
+                "{:<28} {}",
+                "  render",
+                format_percentiles(&mut samples.render, 3)
+            );
+        }
+
+        println!();
+        println!(
+            "{:<28} {}",
+            "Frame time (ms)",
+            format_percentiles(&mut self.frame_times, 3)
+        );
+        println!(
+            "{:<28} {}",
+            "Read calls per frame",
+            format_percentiles(&mut self.read_calls, 0)
+        );
+    }
+}
+
+/// Run the update and render pipeline of all enhancements for the given duration
+/// without creating the overlay window and print the timing percentiles afterwards.
+/// The enhancements render into an offscreen imgui context which is never drawn.
+/// Combined with `--replay` the benchmark runs against a memory recording, otherwise against
+/// a synthetic backend which only measures the overhead of the pipeline itself.
+pub fn run_benchmark(mut app: Application, duration: Duration) -> anyhow::Result<()> {
+    {
+        /* skipped enhancements would distort the timings */
+        let mut settings = app
+            .app_state
+            .get_mut::<AppSettings>(())
+            .expect("app settings to be present");
+        settings.frame_budget_watchdog = false;
+    }
+
+    let mut imgui = imgui::Context::create();
+    imgui.set_ini_filename(None);
+    imgui.io_mut().display_size = BENCHMARK_DISPLAY_SIZE;
+    imgui.fonts().build_rgba32_texture();
+    let mut font_builder = FontAtlasBuilder::new();
+
+    app.settings_visible = false;
+    app.settings_ui.get_mut().skip_intro();
+    app.is_initialized
+        .store(true, std::sync::atomic::Ordering::Relaxed);
+
+    log::info!(
+        "Running benchmark for {}s. Please wait...",
+        duration.as_secs()
+    );
+
+    let mut samples = BenchmarkSamples::default();
+    let benchmark_start = Instant::now();
+    let mut last_frame = Instant::now();
+    while benchmark_start.elapsed() < duration {
+        let frame_start = Instant::now();
+        imgui.io_mut().delta_time = frame_start
+            .duration_since(last_frame)
+            .as_secs_f32()
+            .max(f32::EPSILON);
+        last_frame = frame_start;
+
+        let read_calls_start = app.cs2.total_read_calls();
+        if let Err(err) = app.update_headless() {
+            log::warn!("Benchmark update failed: {:#}", err);
+        }
+
+        if let Ok(mut view_controller) = app.app_state.resolve_mut::<ViewController>(()) {
+            view_controller
+                .update_screen_bounds(mint::Vector2::from_slice(&BENCHMARK_DISPLAY_SIZE));
+        }
+
+        {
+            let ui = imgui.new_frame();
+            let unicode_text = UnicodeTextRenderer::new(ui, &mut font_builder);
+            app.render(ui, &unicode_text);
+        }
+        imgui.render();
+
+        samples
+            .frame_times
+            .push(frame_start.elapsed().as_secs_f32() * 1000.0);
+        samples
+            .read_calls
+            .push((app.cs2.total_read_calls() - read_calls_start) as f32);
+
+        for (name, update_time, render_time) in app.profiler.borrow().current_frame() {
+            let entry = samples.enhancements.entry(name).or_default();
+            entry.update.push(update_time.as_secs_f32() * 1000.0);
+            entry.render.push(render_time.as_secs_f32() * 1000.0);
+        }
+    }
+
+    samples.print_report(benchmark_start.elapsed());
+    Ok(())
+}
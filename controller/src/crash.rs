@@ -0,0 +1,301 @@
+use std::{
+    fs::{
+        self,
+        File,
+    },
+    os::windows::io::AsRawHandle,
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::Command,
+    sync::{
+        atomic::{
+            AtomicBool,
+            AtomicU64,
+            Ordering,
+        },
+        OnceLock,
+    },
+    thread::{
+        self,
+        ThreadId,
+    },
+};
+
+use anyhow::Context;
+use obfstr::obfstr;
+use serde::Serialize;
+use windows::{
+    core::PCWSTR,
+    Win32::{
+        Foundation::HANDLE,
+        Graphics::Gdi::{
+            EnumDisplayDevicesW,
+            DISPLAY_DEVICEW,
+        },
+        System::{
+            Diagnostics::Debug::{
+                MiniDumpWithIndirectlyReferencedMemory,
+                MiniDumpWithThreadInfo,
+                MiniDumpWriteDump,
+                SetUnhandledExceptionFilter,
+                EXCEPTION_POINTERS,
+                MINIDUMP_EXCEPTION_INFORMATION,
+                MINIDUMP_TYPE,
+            },
+            Threading::{
+                GetCurrentProcess,
+                GetCurrentProcessId,
+                GetCurrentThreadId,
+            },
+        },
+    },
+};
+
+use crate::{
+    dialog,
+    settings::{
+        get_crashes_dir,
+        AppSettings,
+    },
+    utils::try_recent_log_entries,
+    winver::version_info,
+};
+
+/// Terminate the process after the unhandled exception filter returns
+const EXCEPTION_EXECUTE_HANDLER: i32 = 1;
+
+/// Names of the graphic adapters, resolved when installing the crash handlers
+static GPU_NAMES: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Names of all features in the order of `AppSettings::feature_states`
+static FEATURE_NAMES: OnceLock<Vec<&'static str>> = OnceLock::new();
+
+/// Bit set of the enabled features, indexed like `FEATURE_NAMES`.
+/// An atomic so the crash handler never has to wait for a lock.
+static ENABLED_FEATURES: AtomicU64 = AtomicU64::new(0);
+
+/// Thread which installed the crash handlers, the only one showing a dialog
+static MAIN_THREAD: OnceLock<ThreadId> = OnceLock::new();
+
+/// Set while a crash is handled to not report follow up panics
+static CRASHING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Serialize)]
+struct CrashMetadata<'a> {
+    reason: &'a str,
+    timestamp: String,
+    version: &'static str,
+    git_hash: &'static str,
+    build_time: &'static str,
+    windows_build: Option<u32>,
+    gpu: &'a [String],
+    enabled_features: Vec<&'static str>,
+    /// The minidump could not be written
+    minidump_error: Option<String>,
+}
+
+fn list_gpus() -> Vec<String> {
+    let mut names = Vec::<String>::new();
+    for index in 0.. {
+        let mut device = DISPLAY_DEVICEW {
+            cb: std::mem::size_of::<DISPLAY_DEVICEW>() as u32,
+            ..Default::default()
+        };
+        if !unsafe { EnumDisplayDevicesW(PCWSTR::null(), index, &mut device, 0) }.as_bool() {
+            break;
+        }
+
+        let length = device
+            .DeviceString
+            .iter()
+            .position(|char| *char == 0)
+            .unwrap_or(device.DeviceString.len());
+        let name = String::from_utf16_lossy(&device.DeviceString[..length]);
+
+        /* every output of an adapter is listed as its own device */
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    names
+}
+
+fn enabled_features() -> Vec<&'static str> {
+    let enabled = ENABLED_FEATURES.load(Ordering::Relaxed);
+    FEATURE_NAMES
+        .get()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| enabled & (1 << index) != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+fn write_minidump(path: &Path, exception: Option<*const EXCEPTION_POINTERS>) -> anyhow::Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+
+    let exception_info = exception.map(|exception| MINIDUMP_EXCEPTION_INFORMATION {
+        ThreadId: unsafe { GetCurrentThreadId() },
+        ExceptionPointers: exception as *mut EXCEPTION_POINTERS,
+        ClientPointers: false.into(),
+    });
+
+    let success = unsafe {
+        MiniDumpWriteDump(
+            GetCurrentProcess(),
+            GetCurrentProcessId(),
+            HANDLE(file.as_raw_handle() as isize),
+            MINIDUMP_TYPE(MiniDumpWithIndirectlyReferencedMemory.0 | MiniDumpWithThreadInfo.0),
+            exception_info
+                .as_ref()
+                .map(|info| info as *const MINIDUMP_EXCEPTION_INFORMATION),
+            None,
+            None,
+        )
+    };
+    if !success.as_bool() {
+        anyhow::bail!("MiniDumpWriteDump: {}", windows::core::Error::from_win32());
+    }
+
+    Ok(())
+}
+
+/// Write the minidump, the recent log entries and the crash metadata into a new crash folder
+fn write_crash_report(
+    reason: &str,
+    exception: Option<*const EXCEPTION_POINTERS>,
+) -> anyhow::Result<PathBuf> {
+    let now = chrono::Local::now();
+    let report_dir = get_crashes_dir()?.join(now.format("%Y-%m-%d_%H-%M-%S").to_string());
+    fs::create_dir_all(&report_dir)
+        .with_context(|| format!("failed to create {}", report_dir.display()))?;
+
+    /* the log and the metadata are still helpful without the dump */
+    let minidump_error = write_minidump(&report_dir.join("crash.dmp"), exception)
+        .err()
+        .map(|err| format!("{:#}", err));
+
+    /* the crashing thread might hold the log buffer lock, never wait for it */
+    let log = match try_recent_log_entries() {
+        Some(entries) => entries
+            .into_iter()
+            .map(|entry| {
+                format!(
+                    "{} {:<5} [{}] {}\n",
+                    entry.time, entry.level, entry.target, entry.message
+                )
+            })
+            .collect::<String>(),
+        None => "The log buffer was locked while crashing\n".to_string(),
+    };
+    fs::write(report_dir.join("log.txt"), log).context("failed to write the log")?;
+
+    let metadata = CrashMetadata {
+        reason,
+        timestamp: now.to_rfc3339(),
+        version: env!("CARGO_PKG_VERSION"),
+        git_hash: env!("GIT_HASH"),
+        build_time: env!("BUILD_TIME"),
+        windows_build: version_info().ok().map(|info| info.dwBuildNumber),
+        gpu: GPU_NAMES.get().map(Vec::as_slice).unwrap_or_default(),
+        enabled_features: enabled_features(),
+        minidump_error,
+    };
+    fs::write(
+        report_dir.join("metadata.json"),
+        serde_json::to_string_pretty(&metadata)?,
+    )
+    .context("failed to write the metadata")?;
+
+    Ok(report_dir)
+}
+
+fn handle_crash(reason: &str, exception: Option<*const EXCEPTION_POINTERS>) {
+    if CRASHING.swap(true, Ordering::SeqCst) {
+        eprintln!("{}", reason);
+        return;
+    }
+
+    report_crash(reason, exception);
+
+    /* the process keeps running after a background thread panicked */
+    CRASHING.store(false, Ordering::SeqCst);
+}
+
+/// Write the crash report and offer to open it.
+/// The logger is not used as the crashing thread, or any other thread, might hold its locks.
+fn report_crash(reason: &str, exception: Option<*const EXCEPTION_POINTERS>) {
+    /* a modal dialog would block background threads, which panic without terminating the process */
+    let show_dialog = MAIN_THREAD.get() == Some(&thread::current().id());
+
+    eprintln!("{}", reason);
+    let report_dir = match write_crash_report(reason, exception) {
+        Ok(report_dir) => report_dir,
+        Err(err) => {
+            eprintln!("Failed to write crash report: {:#}", err);
+            if show_dialog {
+                overlay::show_error_message(obfstr!("LABH Crash"), reason);
+            }
+            return;
+        }
+    };
+    eprintln!("Crash report written to {}", report_dir.display());
+
+    if !show_dialog {
+        return;
+    }
+
+    let message = format!(
+        "{}\n\nA crash report has been written to\n{}\n\nDo you want to open the crash folder?",
+        reason,
+        report_dir.display()
+    );
+    if dialog::show_yes_no(obfstr!("LABH Crash"), &message, true) {
+        if let Err(err) = Command::new("explorer").arg(&report_dir).spawn() {
+            eprintln!("Failed to open the crash folder: {}", err);
+        }
+    }
+}
+
+unsafe extern "system" fn unhandled_exception_filter(exception: *const EXCEPTION_POINTERS) -> i32 {
+    let code = exception
+        .as_ref()
+        .and_then(|exception| exception.ExceptionRecord.as_ref())
+        .map_or(0, |record| record.ExceptionCode.0 as u32);
+
+    handle_crash(
+        &format!("Unhandled exception {:#010X}", code),
+        (!exception.is_null()).then_some(exception),
+    );
+    EXCEPTION_EXECUTE_HANDLER
+}
+
+/// Install a panic hook and an unhandled exception filter which write a crash report
+/// to LABHConfig/crashes and offer to open it
+pub fn install() {
+    GPU_NAMES.get_or_init(list_gpus);
+    MAIN_THREAD.get_or_init(|| thread::current().id());
+
+    std::panic::set_hook(Box::new(|info| handle_crash(&format!("{}", info), None)));
+    unsafe { SetUnhandledExceptionFilter(Some(unhandled_exception_filter)) };
+}
+
+/// Record the enabled features for the crash metadata.
+/// Must be called at startup and whenever the settings have been changed.
+pub fn update_enabled_features(settings: &AppSettings) {
+    let features = settings.feature_states();
+    FEATURE_NAMES.get_or_init(|| features.iter().map(|(name, _)| *name).collect());
+
+    let enabled = features
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, enabled))| *enabled)
+        .fold(0, |bits, (index, _)| bits | 1 << index);
+    ENABLED_FEATURES.store(enabled, Ordering::Relaxed);
+}
@@ -1,16 +1,31 @@
 use std::ffi::CString;
 
 use windows::{
-    core::PCSTR,
+    core::{
+        HSTRING,
+        PCSTR,
+    },
     Win32::{
-        Foundation::HWND,
+        Foundation::{
+            BOOL,
+            HWND,
+            LPARAM,
+        },
         UI::WindowsAndMessaging::{
+            EnumWindows,
+            GetWindowTextW,
+            GetWindowThreadProcessId,
+            IsWindowVisible,
             MessageBoxA,
+            MessageBoxW,
+            IDCANCEL,
             IDYES,
             MB_DEFBUTTON1,
             MB_DEFBUTTON2,
             MB_ICONERROR,
+            MB_ICONQUESTION,
             MB_YESNO,
+            MB_YESNOCANCEL,
         },
     },
 };
@@ -35,3 +50,70 @@ pub fn show_yes_no(title: &str, content: &str, default_value: bool) -> bool {
     };
     result == IDYES
 }
+
+unsafe extern "system" fn collect_window_title(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let (process_id, titles) = &mut *(lparam.0 as *mut (u32, Vec<String>));
+
+    let mut window_process_id = 0;
+    GetWindowThreadProcessId(hwnd, Some(&mut window_process_id as *mut u32));
+    if window_process_id == *process_id && IsWindowVisible(hwnd).as_bool() {
+        let mut buffer = [0u16; 256];
+        let length = GetWindowTextW(hwnd, &mut buffer);
+        if length > 0 {
+            titles.push(String::from_utf16_lossy(&buffer[..length as usize]));
+        }
+    }
+
+    true.into()
+}
+
+fn process_window_titles(process_id: u32) -> Vec<String> {
+    let mut context = (process_id, Vec::<String>::new());
+    unsafe {
+        let _ = EnumWindows(
+            Some(collect_window_title),
+            LPARAM(&mut context as *mut _ as isize),
+        );
+    }
+    context.1
+}
+
+/// Let the user pick one of multiple running processes.
+/// Every process is offered in its own dialog until one has been accepted.
+/// Returns the index of the selected process or None if the selection has been cancelled.
+pub fn pick_process(process_name: &str, process_ids: &[u32]) -> Option<usize> {
+    let title = HSTRING::from(process_name);
+    loop {
+        for (index, process_id) in process_ids.iter().enumerate() {
+            let titles = process_window_titles(*process_id);
+            let process = if titles.is_empty() {
+                format!("PID {}", process_id)
+            } else {
+                format!("PID {} - {}", process_id, titles.join(", "))
+            };
+            let content = format!(
+                "Found {} running {} processes.\n\nAttach to process {} of {}?\n{}\n\nYes attaches to this process, No shows the next one.",
+                process_ids.len(),
+                process_name,
+                index + 1,
+                process_ids.len(),
+                process
+            );
+
+            /* window titles may contain characters which are not representable in the ANSI code page */
+            let result = unsafe {
+                MessageBoxW(
+                    HWND::default(),
+                    &HSTRING::from(content),
+                    &title,
+                    MB_ICONQUESTION | MB_DEFBUTTON1 | MB_YESNOCANCEL,
+                )
+            };
+            if result == IDYES {
+                return Some(index);
+            } else if result == IDCANCEL {
+                return None;
+            }
+        }
+    }
+}
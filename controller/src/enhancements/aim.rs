@@ -1,29 +1,25 @@
 use anyhow::Context;
 use cs2::{
-    schema::ConVar,
+    CS2Handle,
     MouseState,
     StateCS2Memory,
+    StateConVar,
     StateEntityList,
     StateLocalPlayerController,
 };
 use overlay::UnicodeTextRenderer;
-use raw_struct::Reference;
 
 use super::Enhancement;
 use crate::settings::AppSettings;
 
 pub struct AntiAimPunsh {
-    mouse_sensitivity: Reference<dyn ConVar>,
-
     mouse_adjustment_x: i32,
     mouse_adjustment_y: i32,
 }
 
 impl AntiAimPunsh {
-    pub fn new(mouse_sensitivity: Reference<dyn ConVar>) -> Self {
+    pub fn new() -> Self {
         Self {
-            mouse_sensitivity,
-
             mouse_adjustment_x: 0,
             mouse_adjustment_y: 0,
         }
@@ -31,6 +27,17 @@ impl AntiAimPunsh {
 }
 
 impl Enhancement for AntiAimPunsh {
+    fn emits_input(&self) -> bool {
+        true
+    }
+
+    fn release_input(&mut self, _cs2: &CS2Handle) -> anyhow::Result<()> {
+        /* the compensation of the current spray must not be reverted after the panic mode */
+        self.mouse_adjustment_x = 0;
+        self.mouse_adjustment_y = 0;
+        Ok(())
+    }
+
     fn update(&mut self, ctx: &crate::UpdateContext) -> anyhow::Result<()> {
         let memory = ctx.states.resolve::<StateCS2Memory>(())?;
         let settings = ctx.states.resolve::<AppSettings>(())?;
@@ -57,7 +64,11 @@ impl Enhancement for AntiAimPunsh {
             return Ok(());
         }
 
-        let mouse_sensitivity = self.mouse_sensitivity.fl_value()?;
+        let mouse_sensitivity = ctx
+            .states
+            .resolve::<StateConVar>("sensitivity")?
+            .instance
+            .fl_value()?;
         let punch_angle = nalgebra::Vector4::from_row_slice(&local_pawn.m_aimPunchAngle()?) * 2.0;
 
         let mouse_x = (punch_angle.y / (mouse_sensitivity * 0.022)).round() as i32;
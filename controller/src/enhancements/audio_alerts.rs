@@ -0,0 +1,419 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Cursor,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::Arc,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::Result;
+use cs2::{
+    CEntityIdentityEx,
+    ClassNameCache,
+    PlayerPawnState,
+    StateCS2Memory,
+    StateEntityList,
+    StateLocalPlayerController,
+    StatePawnInfo,
+};
+use cs2_schema_generated::cs2::client::CCSPlayerController;
+use nalgebra::Vector3;
+use overlay::UnicodeTextRenderer;
+use rodio::{
+    source::SineWave,
+    Decoder,
+    OutputStream,
+    OutputStreamHandle,
+    Source,
+};
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use super::Enhancement;
+use crate::{
+    settings::{
+        AppSettings,
+        AudioAlertSettings,
+    },
+    UpdateContext,
+};
+
+/// Minimum time between two plays of the same cue
+const CUE_COOLDOWN: Duration = Duration::from_millis(500);
+
+/// Duration of the built in tones
+const TONE_DURATION: Duration = Duration::from_millis(150);
+
+/// Approximate height of the eyes relative to the player origin
+const EYE_HEIGHT: f32 = 64.0;
+/// Approximate height of the chest relative to the player origin
+const CHEST_HEIGHT: f32 = 40.0;
+/// Radius around the chest which counts as being aimed at
+const AIM_TARGET_RADIUS: f32 = 20.0;
+
+/// Events which can be mapped to a sound cue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioEvent {
+    EnemySpotted,
+    EnemyAiming,
+    BombPlanted,
+    TriggerBotFired,
+}
+
+impl AudioEvent {
+    pub const ALL: [AudioEvent; 4] = [
+        AudioEvent::EnemySpotted,
+        AudioEvent::EnemyAiming,
+        AudioEvent::BombPlanted,
+        AudioEvent::TriggerBotFired,
+    ];
+
+    /// Name of the event used as key for the cue settings
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::EnemySpotted => "enemy_spotted",
+            Self::EnemyAiming => "enemy_aiming",
+            Self::BombPlanted => "bomb_planted",
+            Self::TriggerBotFired => "trigger_bot_fired",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::EnemySpotted => "Enemy spotted",
+            Self::EnemyAiming => "Enemy aiming at you",
+            Self::BombPlanted => "Bomb planted",
+            Self::TriggerBotFired => "Trigger bot fired",
+        }
+    }
+
+    /// Frequency of the built in tone used when no sound file has been set
+    fn tone_frequency(&self) -> f32 {
+        match self {
+            Self::EnemySpotted => 880.0,
+            Self::EnemyAiming => 1320.0,
+            Self::BombPlanted => 440.0,
+            Self::TriggerBotFired => 660.0,
+        }
+    }
+}
+
+/// Audio events emitted by the enhancements during the current frame.
+/// The events are played and cleared by the [AudioAlerts] enhancement.
+#[derive(Default)]
+pub struct StateAudioEvents {
+    pending: Vec<AudioEvent>,
+    /// Events which should be played regardless of the cue being enabled
+    previews: Vec<AudioEvent>,
+}
+
+impl State for StateAudioEvents {
+    type Parameter = ();
+
+    fn create(_states: &StateRegistry, _param: Self::Parameter) -> Result<Self> {
+        Ok(Default::default())
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
+
+impl StateAudioEvents {
+    pub fn emit(&mut self, event: AudioEvent) {
+        if !self.pending.contains(&event) {
+            self.pending.push(event);
+        }
+    }
+
+    /// Play the cue of the event once, e.g. to test the selected sound
+    pub fn preview(&mut self, event: AudioEvent) {
+        if !self.previews.contains(&event) {
+            self.previews.push(event);
+        }
+    }
+
+    fn take(&mut self) -> (Vec<AudioEvent>, Vec<AudioEvent>) {
+        (
+            std::mem::take(&mut self.pending),
+            std::mem::take(&mut self.previews),
+        )
+    }
+}
+
+/// Values of the last update used to detect enemies becoming spotted or aiming at the local player
+#[derive(Default)]
+struct EnemyObservation {
+    spotted: bool,
+    aiming: bool,
+}
+
+fn view_direction(eye_angles: [f32; 3]) -> Vector3<f32> {
+    let (pitch, yaw) = (eye_angles[0].to_radians(), eye_angles[1].to_radians());
+    Vector3::new(
+        pitch.cos() * yaw.cos(),
+        pitch.cos() * yaw.sin(),
+        -pitch.sin(),
+    )
+}
+
+/// Plays the sound cues of the [AudioEvent]s emitted by the other enhancements.
+/// Detects spotted enemies and enemies aiming at the local player by itself.
+pub struct AudioAlerts {
+    output: Option<(OutputStream, OutputStreamHandle)>,
+    /// Opening the audio output failed, to avoid retrying every frame
+    output_failed: bool,
+
+    /// Loaded sound files (None if the file could not be read)
+    sound_files: HashMap<PathBuf, Option<Arc<[u8]>>>,
+    last_played: HashMap<AudioEvent, Instant>,
+    enemies: HashMap<u32, EnemyObservation>,
+}
+
+impl AudioAlerts {
+    pub fn new() -> Self {
+        Self {
+            output: None,
+            output_failed: false,
+
+            sound_files: Default::default(),
+            last_played: Default::default(),
+            enemies: Default::default(),
+        }
+    }
+
+    fn output_handle(&mut self) -> Option<OutputStreamHandle> {
+        if self.output.is_none() && !self.output_failed {
+            match OutputStream::try_default() {
+                Ok(output) => self.output = Some(output),
+                Err(err) => {
+                    log::error!("Failed to open the audio output: {:#}", err);
+                    self.output_failed = true;
+                }
+            }
+        }
+
+        self.output.as_ref().map(|(_, handle)| handle.clone())
+    }
+
+    fn load_sound_file(&mut self, path: &Path) -> Option<Arc<[u8]>> {
+        self.sound_files
+            .entry(path.to_path_buf())
+            .or_insert_with(|| match fs::read(path) {
+                Ok(data) => Some(data.into()),
+                Err(err) => {
+                    log::warn!("Failed to read sound file {}: {:#}", path.display(), err);
+                    None
+                }
+            })
+            .clone()
+    }
+
+    /// Play the cue of an event.
+    /// Failures (e.g. an invalid sound file) are logged so the other cues still play.
+    fn play_cue(&mut self, settings: &AudioAlertSettings, event: AudioEvent) {
+        if let Err(err) = self.try_play_cue(settings, event) {
+            log::warn!("Failed to play the {} cue: {:#}", event.name(), err);
+        }
+    }
+
+    fn try_play_cue(&mut self, settings: &AudioAlertSettings, event: AudioEvent) -> Result<()> {
+        let Some(handle) = self.output_handle() else {
+            return Ok(());
+        };
+
+        let cue = settings.cue(event.name());
+        let volume = (settings.volume * cue.volume).clamp(0.0, 1.0);
+        let sound_file = cue
+            .sound_file
+            .as_deref()
+            .and_then(|path| self.load_sound_file(path));
+
+        match sound_file {
+            Some(data) => {
+                let source = Decoder::new(Cursor::new(data))?;
+                handle.play_raw(source.amplify(volume).convert_samples())?;
+            }
+            None => {
+                let source = SineWave::new(event.tone_frequency())
+                    .take_duration(TONE_DURATION)
+                    .amplify(volume);
+                handle.play_raw(source)?;
+            }
+        }
+
+        self.last_played.insert(event, Instant::now());
+        Ok(())
+    }
+
+    fn detect_enemy_events(&mut self, states: &StateRegistry) -> Result<()> {
+        let entities = states.resolve::<StateEntityList>(())?;
+        let class_name_cache = states.resolve::<ClassNameCache>(())?;
+        let memory = states.resolve::<StateCS2Memory>(())?;
+        let local_controller = states.resolve::<StateLocalPlayerController>(())?;
+
+        let Some(local_controller) = local_controller.instance.value_reference(memory.view_arc())
+        else {
+            self.enemies.clear();
+            return Ok(());
+        };
+
+        let local_pawn_handle = local_controller.m_hPlayerPawn()?;
+        if !local_pawn_handle.is_valid()
+            || *states.resolve::<PlayerPawnState>(local_pawn_handle)? != PlayerPawnState::Alive
+        {
+            self.enemies.clear();
+            return Ok(());
+        }
+
+        let local_pawn_info = states.resolve::<StatePawnInfo>(local_pawn_handle)?;
+        let local_team_id = local_pawn_info.team_id;
+        let local_chest = local_pawn_info.position + Vector3::new(0.0, 0.0, CHEST_HEIGHT);
+
+        let mut events = Vec::new();
+        let mut seen_enemies = Vec::new();
+        for entity_identity in entities.entities() {
+            let entity_class = class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
+            if !entity_class
+                .map(|name| *name == "CCSPlayerController")
+                .unwrap_or(false)
+            {
+                continue;
+            }
+
+            let controller_handle = entity_identity.handle::<dyn CCSPlayerController>()?;
+            let Some(controller) = entities
+                .entity_from_handle(&controller_handle)
+                .and_then(|controller| controller.value_reference(memory.view_arc()))
+            else {
+                continue;
+            };
+
+            let pawn_handle = controller.m_hPlayerPawn()?;
+            if !pawn_handle.is_valid()
+                || *states.resolve::<PlayerPawnState>(pawn_handle)? != PlayerPawnState::Alive
+            {
+                continue;
+            }
+
+            let Ok(pawn_info) = states.resolve::<StatePawnInfo>(pawn_handle) else {
+                continue;
+            };
+            if pawn_info.team_id == local_team_id {
+                continue;
+            }
+
+            let Some(pawn) = entities
+                .entity_from_handle(&pawn_handle)
+                .and_then(|pawn| pawn.value_reference(memory.view_arc()))
+            else {
+                continue;
+            };
+
+            let spotted = pawn.m_entitySpottedState()?.m_bSpotted()?;
+
+            let eye_position = pawn_info.position + Vector3::new(0.0, 0.0, EYE_HEIGHT);
+            let target = local_chest - eye_position;
+            let distance = target.norm();
+            let aiming = distance > 0.0
+                && view_direction(pawn.m_angEyeAngles()?).angle(&target)
+                    < (AIM_TARGET_RADIUS / distance).atan();
+
+            let entity_index = pawn_handle.get_entity_index();
+            let observation = self.enemies.entry(entity_index).or_default();
+            if spotted && !observation.spotted {
+                events.push(AudioEvent::EnemySpotted);
+            }
+            if aiming && !observation.aiming {
+                events.push(AudioEvent::EnemyAiming);
+            }
+
+            observation.spotted = spotted;
+            observation.aiming = aiming;
+            seen_enemies.push(entity_index);
+        }
+        self.enemies
+            .retain(|entity_index, _| seen_enemies.contains(entity_index));
+
+        let mut audio_events = states.resolve_mut::<StateAudioEvents>(())?;
+        for event in events {
+            audio_events.emit(event);
+        }
+
+        Ok(())
+    }
+}
+
+impl Enhancement for AudioAlerts {
+    fn update(&mut self, ctx: &UpdateContext) -> Result<()> {
+        let (enabled, settings) = {
+            let settings = ctx.states.resolve::<AppSettings>(())?;
+            (
+                settings.audio_alerts,
+                settings.audio_alerts_settings.clone(),
+            )
+        };
+
+        if enabled {
+            let detect_enemies = [AudioEvent::EnemySpotted, AudioEvent::EnemyAiming]
+                .iter()
+                .any(|event| settings.cue(event.name()).enabled);
+            if detect_enemies {
+                self.detect_enemy_events(ctx.states)?;
+            } else {
+                self.enemies.clear();
+            }
+        } else {
+            /* retry opening the audio output when enabled again */
+            self.output_failed = false;
+            self.enemies.clear();
+        }
+
+        let (events, previews) = ctx.states.resolve_mut::<StateAudioEvents>(())?.take();
+        for event in previews {
+            self.play_cue(&settings, event);
+        }
+
+        if !enabled {
+            return Ok(());
+        }
+
+        for event in events {
+            if !settings.cue(event.name()).enabled {
+                continue;
+            }
+
+            let cooling_down = self
+                .last_played
+                .get(&event)
+                .map(|last_played| last_played.elapsed() < CUE_COOLDOWN)
+                .unwrap_or(false);
+            if cooling_down {
+                continue;
+            }
+
+            self.play_cue(&settings, event);
+        }
+
+        Ok(())
+    }
+
+    fn render(
+        &mut self,
+        _states: &StateRegistry,
+        _ui: &imgui::Ui,
+        _unicode_text: &UnicodeTextRenderer,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
@@ -8,15 +8,24 @@ use cs2::{
     PlantedC4State,
     StateCS2Memory,
     StateEntityList,
+    StateLocalPlayerController,
+    StatePawnInfo,
 };
 use cs2_schema_generated::cs2::client::{
+    CCSPlayerController,
     C_BaseEntity,
     C_C4,
 };
 use imgui::ImColor32;
+use nalgebra::Vector3;
 use overlay::UnicodeTextRenderer;
 
-use super::Enhancement;
+use super::{
+    AudioEvent,
+    Enhancement,
+    RenderLayer,
+    StateAudioEvents,
+};
 use crate::{
     settings::AppSettings,
     utils::{
@@ -26,11 +35,143 @@ use crate::{
     view::ViewController,
 };
 
-pub struct BombInfoIndicator {}
+pub struct BombInfoIndicator {
+    bomb_planted: bool,
+}
+
 impl BombInfoIndicator {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            bomb_planted: false,
+        }
+    }
+}
+
+/// Resolve the pawn info of the local player
+fn resolve_local_pawn_info(states: &utils_state::StateRegistry) -> Option<StatePawnInfo> {
+    let memory = states.resolve::<StateCS2Memory>(()).ok()?;
+    let local_controller = states.resolve::<StateLocalPlayerController>(()).ok()?;
+    let local_pawn_handle = local_controller
+        .instance
+        .value_reference(memory.view_arc())?
+        .m_hPlayerPawn()
+        .ok()?;
+
+    states
+        .resolve::<StatePawnInfo>(local_pawn_handle)
+        .ok()
+        .map(|info| info.clone())
+}
+
+/// Render rings around the planted bomb showing the lethal and the maximum damage radius
+fn render_damage_rings(
+    ui: &imgui::Ui,
+    view: &ViewController,
+    bomb_position: &Vector3<f32>,
+    armor: i32,
+    health: i32,
+) {
+    const SEGMENTS: usize = 64;
+
+    let draw = ui.get_window_draw_list();
+    let rings = [
+        (
+            bomb_damage_radius(health as f32, armor),
+            ImColor32::from_rgba(201, 28, 28, 200),
+        ),
+        (
+            bomb_damage_radius(1.0, armor),
+            ImColor32::from_rgba(255, 165, 0, 150),
+        ),
+    ];
+
+    for (radius, color) in rings {
+        if radius <= 0.0 {
+            continue;
+        }
+
+        let points = (0..=SEGMENTS).map(|index| {
+            let angle = index as f32 * std::f32::consts::TAU / SEGMENTS as f32;
+            let point =
+                bomb_position + Vector3::new(angle.cos() * radius, angle.sin() * radius, 0.0);
+
+            view.world_to_screen(&point, true)
+                .map(|point| [point.x, point.y])
+        });
+
+        /* connecting the points around a gap would draw a line across the screen */
+        for run in split_at_gaps(points) {
+            draw.add_polyline(run, color).thickness(2.0).build();
+        }
+    }
+}
+
+/// Split the projected points of a polyline at every point which could not be projected.
+/// Runs with less than two points are dropped as they can not be drawn.
+fn split_at_gaps(points: impl IntoIterator<Item = Option<[f32; 2]>>) -> Vec<Vec<[f32; 2]>> {
+    let mut runs = Vec::new();
+    let mut run = Vec::new();
+    for point in points {
+        match point {
+            Some(point) => run.push(point),
+            None if run.len() >= 2 => runs.push(std::mem::take(&mut run)),
+            None => run.clear(),
+        }
+    }
+
+    if run.len() >= 2 {
+        runs.push(run);
+    }
+    runs
+}
+
+/// Base damage of the C4 explosion
+const BOMB_DAMAGE: f32 = 500.0;
+
+/// Default C4 explosion radius (can be overwritten by the map)
+const BOMB_RADIUS: f32 = BOMB_DAMAGE * 3.5;
+
+/// Estimate the damage a player takes from the C4 explosion
+/// at the given distance (in game units).
+pub fn bomb_damage(distance: f32, armor: i32) -> f32 {
+    const ARMOR_RATIO: f32 = 0.5;
+    const ARMOR_BONUS: f32 = 0.5;
+
+    let sigma = BOMB_RADIUS / 3.0;
+    let damage = BOMB_DAMAGE * (-(distance * distance) / (2.0 * sigma * sigma)).exp();
+    if armor <= 0 {
+        return damage;
+    }
+
+    let mut damage_new = damage * ARMOR_RATIO;
+    let armor_damage = (damage - damage_new) * ARMOR_BONUS;
+    if armor_damage > armor as f32 {
+        /* armor will be depleted */
+        damage_new = damage - armor as f32 * (1.0 / ARMOR_BONUS);
     }
+
+    damage_new
+}
+
+/// Calculate the radius in which the C4 explosion deals at least the target damage
+pub fn bomb_damage_radius(target_damage: f32, armor: i32) -> f32 {
+    if bomb_damage(0.0, armor) < target_damage {
+        return 0.0;
+    }
+
+    /* The damage is monotonically decreasing, hence a binary search is sufficient */
+    let mut lower = 0.0;
+    let mut upper = BOMB_RADIUS * 2.0;
+    for _ in 0..32 {
+        let center = (lower + upper) / 2.0;
+        if bomb_damage(center, armor) >= target_damage {
+            lower = center;
+        } else {
+            upper = center;
+        }
+    }
+
+    lower
 }
 
 /// % of the screens height
@@ -40,7 +181,19 @@ const PLAYER_AVATAR_TOP_OFFSET: f32 = 0.004;
 const PLAYER_AVATAR_SIZE: f32 = 0.05;
 
 impl Enhancement for BombInfoIndicator {
-    fn update(&mut self, _ctx: &crate::UpdateContext) -> anyhow::Result<()> {
+    fn update(&mut self, ctx: &crate::UpdateContext) -> anyhow::Result<()> {
+        let bomb_planted = ctx
+            .states
+            .resolve::<PlantedC4>(())
+            .map(|bomb| matches!(bomb.state, PlantedC4State::Active { .. }))
+            .unwrap_or(false);
+        if bomb_planted && !self.bomb_planted {
+            ctx.states
+                .resolve_mut::<StateAudioEvents>(())?
+                .emit(AudioEvent::BombPlanted);
+        }
+
+        self.bomb_planted = bomb_planted;
         Ok(())
     }
 
@@ -61,7 +214,24 @@ impl Enhancement for BombInfoIndicator {
             return Ok(());
         }
 
+        let local_pawn_info = if settings.bomb_damage {
+            resolve_local_pawn_info(states)
+        } else {
+            None
+        };
+
+        let damage_info = local_pawn_info
+            .as_ref()
+            .filter(|_| settings.bomb_damage)
+            .filter(|_| matches!(bomb_state.state, PlantedC4State::Active { .. }))
+            .map(|pawn_info| {
+                let distance = (pawn_info.position - bomb_state.position).norm();
+                let damage = bomb_damage(distance, pawn_info.player_armor);
+                (damage, damage >= pawn_info.player_health as f32)
+            });
+
         let line_count = match &bomb_state.state {
+            PlantedC4State::Active { .. } if damage_info.is_some() => 4,
             PlantedC4State::Active { .. } => 3,
             PlantedC4State::Defused | PlantedC4State::Detonated => 2,
             PlantedC4State::NotPlanted => unreachable!(),
@@ -95,13 +265,25 @@ impl Enhancement for BombInfoIndicator {
 
                         let defuse_text = format!(
                             "Defused in {:.3} by {}",
-                            defuser.time_remaining, defuser.player_name
+                            defuser.time_remaining,
+                            utils::display_player_name(states, &defuser.player_name)
                         );
 
                         ui.unicode_text_colored_with_shadow(unicode_text, color, &defuse_text);
                     } else {
                         ui.text_with_shadow("Not defusing");
                     }
+
+                    if let Some((damage, lethal)) = damage_info {
+                        if lethal {
+                            ui.text_colored_with_shadow(
+                                ImColor32::from_rgba(201, 28, 28, 255),
+                                &format!("Damage: {:.0} (lethal)", damage),
+                            );
+                        } else {
+                            ui.text_with_shadow(&format!("Damage: {:.0}", damage));
+                        }
+                    }
                 }
                 PlantedC4State::Defused => {
                     ui.text_with_shadow("Bomb has been defused");
@@ -147,6 +329,10 @@ impl BombLabelIndicator {
 }
 
 impl Enhancement for BombLabelIndicator {
+    fn render_layer(&self) -> RenderLayer {
+        RenderLayer::World
+    }
+
     fn update(&mut self, _ctx: &crate::UpdateContext) -> anyhow::Result<()> {
         Ok(())
     }
@@ -162,6 +348,22 @@ impl Enhancement for BombLabelIndicator {
         let bomb_carrier = states.resolve::<BombCarrierInfo>(())?;
         let view = states.resolve::<ViewController>(())?;
 
+        /* the damage rings are drawn into the world, therefore not part of the bomb info panel */
+        if settings.bomb_timer
+            && settings.bomb_damage_rings
+            && matches!(bomb_state.state, PlantedC4State::Active { .. })
+        {
+            if let Some(pawn_info) = resolve_local_pawn_info(states) {
+                render_damage_rings(
+                    ui,
+                    &view,
+                    &bomb_state.position,
+                    pawn_info.player_armor,
+                    pawn_info.player_health,
+                );
+            }
+        }
+
         if !settings.bomb_label {
             return Ok(());
         }
@@ -226,4 +428,34 @@ impl Enhancement for BombLabelIndicator {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::split_at_gaps;
+
+    #[test]
+    fn test_split_at_gaps() {
+        let points = [
+            Some([0.0, 0.0]),
+            Some([1.0, 0.0]),
+            None,
+            Some([2.0, 0.0]),
+            None,
+            None,
+            Some([3.0, 0.0]),
+            Some([4.0, 0.0]),
+            Some([5.0, 0.0]),
+        ];
+
+        /* the single point between the gaps can not be drawn */
+        assert_eq!(
+            split_at_gaps(points),
+            vec![
+                vec![[0.0, 0.0], [1.0, 0.0]],
+                vec![[3.0, 0.0], [4.0, 0.0], [5.0, 0.0]],
+            ]
+        );
+        assert!(split_at_gaps([None, Some([0.0, 0.0]), None]).is_empty());
+    }
+}
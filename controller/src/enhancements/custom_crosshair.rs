@@ -0,0 +1,318 @@
+use anyhow::Context;
+use cs2::LocalCameraControllerTarget;
+use imgui::DrawListMut;
+use overlay::UnicodeTextRenderer;
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    settings::{
+        AppSettings,
+        Color,
+        CrosshairSettings,
+    },
+    UpdateContext,
+};
+
+/// Characters used by the CS2 share codes, the index is the digit value
+const SHARE_CODE_DICTIONARY: &[u8] = b"ABCDEFGHJKLMNOPQRSTUVWXYZabcdefhijkmnopqrstuvwxyz23456789";
+
+/// Amount of characters of a share code without the prefix and dashes
+const SHARE_CODE_LENGTH: usize = 25;
+
+/// Gap the game adds to cl_crosshairgap for the static crosshair styles
+const CROSSHAIR_BASE_GAP: f32 = 4.0;
+
+/// Predefined crosshair colors selectable by cl_crosshaircolor 0-4
+const CROSSHAIR_PRESET_COLORS: [[u8; 3]; 5] = [
+    [250, 50, 50],
+    [50, 250, 50],
+    [250, 250, 50],
+    [50, 50, 250],
+    [50, 250, 250],
+];
+
+fn share_code_bytes(share_code: &str) -> anyhow::Result<[u8; 18]> {
+    let share_code = share_code
+        .trim()
+        .strip_prefix("CSGO-")
+        .context("share code must start with CSGO-")?;
+
+    let digits = share_code
+        .bytes()
+        .filter(|value| *value != b'-')
+        .map(|value| {
+            SHARE_CODE_DICTIONARY
+                .iter()
+                .position(|digit| *digit == value)
+                .with_context(|| format!("invalid share code character {}", value as char))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    if digits.len() != SHARE_CODE_LENGTH {
+        anyhow::bail!(
+            "share code must contain {} characters but has {}",
+            SHARE_CODE_LENGTH,
+            digits.len()
+        );
+    }
+
+    /* the code is a big number with the least significant digit first, stored as 18 big endian bytes */
+    let mut bytes = [0u8; 18];
+    for digit in digits.into_iter().rev() {
+        let mut carry = digit as u32;
+        for byte in bytes.iter_mut().rev() {
+            let value = *byte as u32 * SHARE_CODE_DICTIONARY.len() as u32 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+
+        if carry > 0 {
+            anyhow::bail!("share code value out of range");
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Decode a CS2 crosshair share code (e.g. CSGO-XXXXX-XXXXX-XXXXX-XXXXX-XXXXX).
+pub fn decode_crosshair_share_code(share_code: &str) -> anyhow::Result<CrosshairSettings> {
+    let bytes = share_code_bytes(share_code)?;
+
+    let checksum = bytes[1..]
+        .iter()
+        .fold(0u8, |checksum, value| checksum.wrapping_add(*value));
+    if checksum != bytes[0] {
+        anyhow::bail!("share code checksum mismatch");
+    }
+
+    let flags = bytes[13] >> 4;
+    let alpha = if flags & 0x04 > 0 { bytes[7] } else { 255 };
+    let [red, green, blue] = CROSSHAIR_PRESET_COLORS
+        .get((bytes[10] & 0x07) as usize)
+        .copied()
+        .unwrap_or([bytes[4], bytes[5], bytes[6]]);
+
+    let size = (((bytes[15] & 0x1F) as u16) << 8) | bytes[14] as u16;
+    Ok(CrosshairSettings {
+        size: size as f32 / 10.0,
+        thickness: bytes[12] as f32 / 10.0,
+        gap: bytes[2] as i8 as f32 / 10.0,
+        outline: bytes[10] & 0x08 > 0,
+        outline_thickness: bytes[3] as f32 / 2.0,
+        color: Color::from_u8([red, green, blue, alpha]),
+        dot: flags & 0x01 > 0,
+        t_style: flags & 0x08 > 0,
+    })
+}
+
+/// Draw a crosshair centered at `center`.
+/// The crosshair values are scaled with the screen height like the game does.
+pub fn draw_crosshair(
+    draw_list: &DrawListMut,
+    center: [f32; 2],
+    screen_height: f32,
+    settings: &CrosshairSettings,
+) {
+    let scale = screen_height / 480.0;
+    let length = (settings.size * scale).round();
+    let thickness = (settings.thickness * scale).round().max(1.0);
+    let gap = ((settings.gap + CROSSHAIR_BASE_GAP) * screen_height / 1200.0).round();
+
+    let center = [center[0].round(), center[1].round()];
+    let half_thickness = thickness / 2.0;
+
+    let mut rects = Vec::with_capacity(5);
+    if length > 0.0 {
+        /* left, right, bottom and top */
+        rects.push((
+            [center[0] - gap - length, center[1] - half_thickness],
+            [center[0] - gap, center[1] + half_thickness],
+        ));
+        rects.push((
+            [center[0] + gap, center[1] - half_thickness],
+            [center[0] + gap + length, center[1] + half_thickness],
+        ));
+        rects.push((
+            [center[0] - half_thickness, center[1] + gap],
+            [center[0] + half_thickness, center[1] + gap + length],
+        ));
+        if !settings.t_style {
+            rects.push((
+                [center[0] - half_thickness, center[1] - gap - length],
+                [center[0] + half_thickness, center[1] - gap],
+            ));
+        }
+    }
+
+    if settings.dot {
+        rects.push((
+            [center[0] - half_thickness, center[1] - half_thickness],
+            [center[0] + half_thickness, center[1] + half_thickness],
+        ));
+    }
+
+    let color = settings.color.as_f32();
+    if settings.outline {
+        let outline = settings.outline_thickness.max(0.0);
+        for (min, max) in &rects {
+            draw_list
+                .add_rect(
+                    [min[0] - outline, min[1] - outline],
+                    [max[0] + outline, max[1] + outline],
+                    [0.0, 0.0, 0.0, color[3]],
+                )
+                .filled(true)
+                .build();
+        }
+    }
+
+    for (min, max) in rects {
+        draw_list.add_rect(min, max, color).filled(true).build();
+    }
+}
+
+/// Permanent crosshair independent of the weapon and the in game crosshair
+pub struct CustomCrosshair;
+
+impl CustomCrosshair {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Enhancement for CustomCrosshair {
+    fn update(&mut self, _ctx: &UpdateContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn render(
+        &mut self,
+        states: &StateRegistry,
+        ui: &imgui::Ui,
+        _unicode_text: &UnicodeTextRenderer,
+    ) -> anyhow::Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+        if !settings.custom_crosshair {
+            return Ok(());
+        }
+
+        let view_target = states.resolve::<LocalCameraControllerTarget>(())?;
+        if view_target.target_entity_id.is_none() {
+            return Ok(());
+        }
+
+        let [screen_width, screen_height] = ui.io().display_size;
+        draw_crosshair(
+            &ui.get_window_draw_list(),
+            [screen_width / 2.0, screen_height / 2.0],
+            screen_height,
+            &settings.custom_crosshair_settings,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        decode_crosshair_share_code,
+        SHARE_CODE_DICTIONARY,
+        SHARE_CODE_LENGTH,
+    };
+
+    /// Encode the crosshair bytes (without the checksum) like the game does
+    fn encode_share_code(mut bytes: [u8; 18]) -> String {
+        bytes[0] = bytes[1..]
+            .iter()
+            .fold(0u8, |checksum, value| checksum.wrapping_add(*value));
+
+        /* repeatedly divide the big endian number by the dictionary length, least significant digit first */
+        let mut digits = Vec::with_capacity(SHARE_CODE_LENGTH);
+        for _ in 0..SHARE_CODE_LENGTH {
+            let mut remainder = 0u32;
+            for byte in bytes.iter_mut() {
+                let value = (remainder << 8) | *byte as u32;
+                *byte = (value / SHARE_CODE_DICTIONARY.len() as u32) as u8;
+                remainder = value % SHARE_CODE_DICTIONARY.len() as u32;
+            }
+            digits.push(SHARE_CODE_DICTIONARY[remainder as usize] as char);
+        }
+
+        let groups = digits
+            .chunks(5)
+            .map(|chunk| chunk.iter().collect::<String>())
+            .collect::<Vec<_>>();
+        format!("CSGO-{}", groups.join("-"))
+    }
+
+    fn crosshair_bytes() -> [u8; 18] {
+        let mut bytes = [0u8; 18];
+        bytes[2] = (-30i8) as u8; // gap -3.0
+        bytes[3] = 3; // outline thickness 1.5
+        bytes[4..8].copy_from_slice(&[10, 20, 30, 128]);
+        bytes[10] = 0x05 | 0x08; // custom color with outline
+        bytes[12] = 6; // thickness 0.6
+        bytes[13] = (0x01 | 0x04) << 4; // dot and alpha enabled
+        bytes[14] = 0x2C; // size 30.0
+        bytes[15] = 0x01;
+        bytes
+    }
+
+    #[test]
+    fn decode_round_trip() {
+        let share_code = encode_share_code(crosshair_bytes());
+        assert_eq!(share_code.len(), "CSGO-".len() + SHARE_CODE_LENGTH + 4);
+
+        let crosshair = decode_crosshair_share_code(&share_code).unwrap();
+        assert_eq!(crosshair.size, 30.0);
+        assert_eq!(crosshair.thickness, 0.6);
+        assert_eq!(crosshair.gap, -3.0);
+        assert!(crosshair.outline);
+        assert_eq!(crosshair.outline_thickness, 1.5);
+        assert_eq!(crosshair.color.as_u8(), [10, 20, 30, 128]);
+        assert!(crosshair.dot);
+        assert!(!crosshair.t_style);
+
+        /* surrounding whitespace is ignored */
+        assert!(decode_crosshair_share_code(&format!("  {}\n", share_code)).is_ok());
+    }
+
+    #[test]
+    fn decode_preset_color() {
+        let mut bytes = crosshair_bytes();
+        bytes[10] = 0x01; // green preset without outline
+        bytes[13] = 0x08 << 4; // t style without alpha
+
+        let crosshair = decode_crosshair_share_code(&encode_share_code(bytes)).unwrap();
+        assert_eq!(crosshair.color.as_u8(), [50, 250, 50, 255]);
+        assert!(!crosshair.outline);
+        assert!(!crosshair.dot);
+        assert!(crosshair.t_style);
+    }
+
+    #[test]
+    fn decode_malformed() {
+        let share_code = encode_share_code(crosshair_bytes());
+        let without_prefix = share_code.strip_prefix("CSGO-").unwrap();
+
+        /* missing prefix */
+        assert!(decode_crosshair_share_code(without_prefix).is_err());
+        /* too short and too long */
+        assert!(decode_crosshair_share_code(&share_code[..share_code.len() - 1]).is_err());
+        assert!(decode_crosshair_share_code(&format!("{}A", share_code)).is_err());
+        /* characters which are not part of the dictionary */
+        assert!(decode_crosshair_share_code(&share_code.replacen('-', "0", 2)).is_err());
+        assert!(decode_crosshair_share_code(&format!("CSGO-{}", "l".repeat(25))).is_err());
+        /* value exceeding 18 bytes */
+        assert!(decode_crosshair_share_code(&format!("CSGO-{}", "9".repeat(25))).is_err());
+        assert!(decode_crosshair_share_code("").is_err());
+
+        /* checksum mismatch, the first character is the least significant digit */
+        let mut digits = share_code.into_bytes();
+        digits[5] = if digits[5] == b'A' { b'B' } else { b'A' };
+        let share_code = String::from_utf8(digits).unwrap();
+        let error = decode_crosshair_share_code(&share_code).unwrap_err();
+        assert!(error.to_string().contains("checksum"));
+    }
+}
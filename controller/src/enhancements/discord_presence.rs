@@ -0,0 +1,364 @@
+use std::{
+    fs::{
+        File,
+        OpenOptions,
+    },
+    io::{
+        Read,
+        Write,
+    },
+    sync::mpsc::{
+        self,
+        Receiver,
+        Sender,
+    },
+    thread,
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+use cs2::{
+    PlayerPawnState,
+    StateCS2Memory,
+    StateCurrentMap,
+    StateGameRules,
+    StateLocalPlayerController,
+};
+use overlay::UnicodeTextRenderer;
+use serde_json::{
+    json,
+    Value,
+};
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    settings::AppSettings,
+    UpdateContext,
+};
+
+/// Discord rate limits activity updates to five per 20 seconds
+const PRESENCE_UPDATE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Discord listens on the first free pipe of discord-ipc-0 to discord-ipc-9
+const DISCORD_IPC_PIPE_COUNT: usize = 10;
+
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+const OP_CLOSE: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Activity {
+    details: String,
+    state: String,
+    /// Unix timestamp in seconds
+    start_timestamp: u64,
+    privacy_mode: bool,
+}
+
+impl Activity {
+    fn to_json(&self) -> Value {
+        let mut activity = json!({
+            "details": self.details,
+            "state": self.state,
+            "timestamps": { "start": self.start_timestamp },
+        });
+
+        if !self.privacy_mode {
+            activity["assets"] = json!({
+                "large_image": "labh",
+                "large_text": format!("LABH v{}", env!("CARGO_PKG_VERSION")),
+            });
+        }
+
+        activity
+    }
+}
+
+enum PresenceCommand {
+    /// Set or clear the current activity
+    Update(Option<Activity>),
+    Shutdown,
+}
+
+/// Minimal client for the local Discord IPC
+struct DiscordIpc {
+    pipe: File,
+    nonce: u64,
+}
+
+impl DiscordIpc {
+    fn connect(client_id: &str) -> Result<Self> {
+        for index in 0..DISCORD_IPC_PIPE_COUNT {
+            let Ok(pipe) = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(format!(r"\\.\pipe\discord-ipc-{}", index))
+            else {
+                continue;
+            };
+
+            let mut ipc = Self { pipe, nonce: 0 };
+            ipc.send(OP_HANDSHAKE, &json!({ "v": 1, "client_id": client_id }))?;
+            ipc.receive().context("handshake")?;
+            return Ok(ipc);
+        }
+
+        anyhow::bail!("Discord is not running")
+    }
+
+    fn send(&mut self, opcode: u32, payload: &Value) -> Result<()> {
+        let payload = serde_json::to_vec(payload)?;
+
+        let mut frame = Vec::with_capacity(8 + payload.len());
+        frame.extend_from_slice(&opcode.to_le_bytes());
+        frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&payload);
+        self.pipe.write_all(&frame)?;
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<Value> {
+        let mut header = [0u8; 8];
+        self.pipe.read_exact(&mut header)?;
+
+        let opcode = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+
+        let mut payload = vec![0u8; length as usize];
+        self.pipe.read_exact(&mut payload)?;
+        let payload = serde_json::from_slice::<Value>(&payload)?;
+
+        if opcode == OP_CLOSE {
+            anyhow::bail!("connection closed by Discord: {}", payload["message"]);
+        }
+
+        Ok(payload)
+    }
+
+    fn set_activity(&mut self, activity: Option<&Activity>) -> Result<()> {
+        self.nonce += 1;
+        self.send(
+            OP_FRAME,
+            &json!({
+                "cmd": "SET_ACTIVITY",
+                "args": {
+                    "pid": std::process::id(),
+                    "activity": activity.map(Activity::to_json),
+                },
+                "nonce": self.nonce.to_string(),
+            }),
+        )?;
+
+        let response = self.receive()?;
+        if response["evt"] == "ERROR" {
+            anyhow::bail!("{}", response["data"]["message"]);
+        }
+
+        Ok(())
+    }
+}
+
+fn run_presence_client(client_id: String, commands: Receiver<PresenceCommand>) {
+    let mut ipc: Option<DiscordIpc> = None;
+
+    while let Ok(command) = commands.recv() {
+        let activity = match command {
+            PresenceCommand::Update(activity) => activity,
+            PresenceCommand::Shutdown => {
+                if let Some(ipc) = ipc.as_mut() {
+                    let _ = ipc.set_activity(None);
+                }
+                break;
+            }
+        };
+
+        if ipc.is_none() {
+            match DiscordIpc::connect(&client_id) {
+                Ok(connection) => {
+                    log::info!("Connected to Discord");
+                    ipc = Some(connection);
+                }
+                Err(err) => {
+                    /* retried with the next update */
+                    log::debug!("Failed to connect to Discord: {:#}", err);
+                    continue;
+                }
+            }
+        }
+
+        if let Some(connection) = ipc.as_mut() {
+            if let Err(err) = connection.set_activity(activity.as_ref()) {
+                log::warn!("Failed to update the Discord presence: {:#}", err);
+                ipc = None;
+            }
+        }
+    }
+}
+
+struct PresenceClient {
+    client_id: String,
+    commands: Sender<PresenceCommand>,
+}
+
+/// Publishes the current map, score and alive state as Discord Rich Presence.
+/// The privacy mode leaves out any reference to the overlay.
+pub struct DiscordPresence {
+    client: Option<PresenceClient>,
+
+    started: u64,
+    last_activity: Option<Activity>,
+    last_update: Option<Instant>,
+}
+
+impl DiscordPresence {
+    pub fn new() -> Self {
+        Self {
+            client: None,
+
+            started: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.as_secs())
+                .unwrap_or_default(),
+            last_activity: None,
+            last_update: None,
+        }
+    }
+
+    fn stop_client(&mut self) {
+        if let Some(client) = self.client.take() {
+            /* not joining the thread as it might be blocked waiting on Discord */
+            let _ = client.commands.send(PresenceCommand::Shutdown);
+        }
+
+        self.last_activity = None;
+        self.last_update = None;
+    }
+
+    fn create_activity(states: &StateRegistry, privacy_mode: bool, start_timestamp: u64) -> Result<Activity> {
+        let map = states
+            .resolve::<StateCurrentMap>(())
+            .ok()
+            .and_then(|map| map.current_map.clone());
+        let Some(map) = map else {
+            return Ok(Activity {
+                details: "In the main menu".to_string(),
+                state: String::new(),
+                start_timestamp,
+                privacy_mode,
+            });
+        };
+
+        let details = match states.resolve::<StateGameRules>(()) {
+            Ok(game_rules) if game_rules.warmup_period => format!("{} - Warmup", map),
+            Ok(game_rules) => format!(
+                "{} - CT {} : {} T",
+                map, game_rules.score_ct, game_rules.score_t
+            ),
+            Err(_) => map,
+        };
+
+        let memory = states.resolve::<StateCS2Memory>(())?;
+        let local_controller = states.resolve::<StateLocalPlayerController>(())?;
+        let state = match local_controller.instance.value_reference(memory.view_arc()) {
+            Some(local_controller) => {
+                let pawn_handle = local_controller.m_hPlayerPawn()?;
+                if pawn_handle.is_valid()
+                    && *states.resolve::<PlayerPawnState>(pawn_handle)? == PlayerPawnState::Alive
+                {
+                    "Alive"
+                } else {
+                    "Dead"
+                }
+            }
+            None => "Spectating",
+        };
+
+        Ok(Activity {
+            details,
+            state: state.to_string(),
+            start_timestamp,
+            privacy_mode,
+        })
+    }
+}
+
+impl Enhancement for DiscordPresence {
+    fn update(&mut self, ctx: &UpdateContext) -> Result<()> {
+        let (enabled, client_id, privacy_mode) = {
+            let settings = ctx.states.resolve::<AppSettings>(())?;
+            (
+                settings.discord_presence,
+                settings.discord_presence_client_id.trim().to_string(),
+                settings.discord_presence_privacy,
+            )
+        };
+
+        if !enabled || client_id.is_empty() {
+            self.stop_client();
+            return Ok(());
+        }
+
+        if self.client.as_ref().map(|client| &client.client_id) != Some(&client_id) {
+            self.stop_client();
+
+            let (commands, receiver) = mpsc::channel();
+            thread::Builder::new()
+                .name("discord-presence".to_string())
+                .spawn({
+                    let client_id = client_id.clone();
+                    move || run_presence_client(client_id, receiver)
+                })?;
+
+            self.client = Some(PresenceClient {
+                client_id,
+                commands,
+            });
+        }
+
+        if self
+            .last_update
+            .map(|last_update| last_update.elapsed() < PRESENCE_UPDATE_INTERVAL)
+            .unwrap_or(false)
+        {
+            return Ok(());
+        }
+        self.last_update = Some(Instant::now());
+
+        let activity = Self::create_activity(ctx.states, privacy_mode, self.started)?;
+        if self.last_activity.as_ref() == Some(&activity) {
+            return Ok(());
+        }
+
+        if let Some(client) = &self.client {
+            let _ = client
+                .commands
+                .send(PresenceCommand::Update(Some(activity.clone())));
+        }
+        self.last_activity = Some(activity);
+        Ok(())
+    }
+
+    fn render(
+        &mut self,
+        _states: &StateRegistry,
+        _ui: &imgui::Ui,
+        _unicode_text: &UnicodeTextRenderer,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for DiscordPresence {
+    fn drop(&mut self) {
+        self.stop_client();
+    }
+}
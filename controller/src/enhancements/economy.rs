@@ -0,0 +1,142 @@
+use anyhow::Result;
+use imgui::Condition;
+use overlay::UnicodeTextRenderer;
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    settings::AppSettings,
+    update_thread::StateGameSnapshot,
+    utils::{
+        display_player_name,
+        UnicodeTextWithShadowUi,
+    },
+    UpdateContext,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuyType {
+    Eco,
+    HalfBuy,
+    FullBuy,
+}
+
+impl BuyType {
+    pub fn from_equipment_value(value: i32) -> Self {
+        if value >= 3_500 {
+            Self::FullBuy
+        } else if value >= 1_500 {
+            Self::HalfBuy
+        } else {
+            Self::Eco
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Eco => "Eco",
+            Self::HalfBuy => "Half Buy",
+            Self::FullBuy => "Full Buy",
+        }
+    }
+}
+
+struct PlayerEconomy {
+    name: String,
+    team_id: u8,
+    money: i32,
+    equipment_value: i32,
+}
+
+/// Window summarizing the money and equipment value of both teams
+pub struct TeamEconomy {
+    players: Vec<PlayerEconomy>,
+}
+
+impl TeamEconomy {
+    pub fn new() -> Self {
+        Self {
+            players: Vec::new(),
+        }
+    }
+
+    fn render_team(&self, states: &StateRegistry, ui: &imgui::Ui, unicode_text: &UnicodeTextRenderer, team_id: u8, label: &str) {
+        let players = self
+            .players
+            .iter()
+            .filter(|player| player.team_id == team_id)
+            .collect::<Vec<_>>();
+
+        let total_money = players.iter().map(|player| player.money).sum::<i32>();
+        let total_equipment = players.iter().map(|player| player.equipment_value).sum::<i32>();
+        ui.text(&format!(
+            "{}: $ {} (equipment $ {})",
+            label, total_money, total_equipment
+        ));
+
+        for player in players {
+            ui.unicode_text_with_shadow(
+                unicode_text,
+                &format!(
+                    "  {}: $ {} ({})",
+                    display_player_name(states, &player.name),
+                    player.money,
+                    BuyType::from_equipment_value(player.equipment_value).display_name()
+                ),
+            );
+        }
+    }
+}
+
+impl Enhancement for TeamEconomy {
+    fn update(&mut self, ctx: &UpdateContext) -> Result<()> {
+        self.players.clear();
+
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if !settings.team_economy {
+            return Ok(());
+        }
+
+        /* the players are read by the update thread */
+        let snapshot = ctx.states.resolve::<StateGameSnapshot>(())?;
+        for player in snapshot.0.players.iter().filter(|player| player.alive) {
+            let Some(pawn_info) = &player.info else {
+                continue;
+            };
+
+            self.players.push(PlayerEconomy {
+                name: pawn_info.player_name.clone().unwrap_or_else(|| "unknown".to_string()),
+                team_id: pawn_info.team_id,
+                money: pawn_info.player_money.unwrap_or_default(),
+                equipment_value: pawn_info.player_equipment_value,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn render(
+        &mut self,
+        states: &StateRegistry,
+        ui: &imgui::Ui,
+        unicode_text: &UnicodeTextRenderer,
+    ) -> Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+        if !settings.team_economy || self.players.is_empty() {
+            return Ok(());
+        }
+
+        ui.window("Team Economy")
+            .position([20.0, 200.0], Condition::FirstUseEver)
+            .size([280.0, 300.0], Condition::FirstUseEver)
+            .collapsible(false)
+            .bg_alpha(0.6)
+            .build(|| {
+                self.render_team(states, ui, unicode_text, 2, "T");
+                ui.separator();
+                self.render_team(states, ui, unicode_text, 3, "CT");
+            });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::{
+    Duration,
+    Instant,
+};
+
+/// Amount of consecutive frames an enhancement has to exceed its budget before it gets degraded
+const OVER_BUDGET_FRAME_THRESHOLD: u32 = 30;
+
+/// How long an enhancement stays degraded
+const DEGRADE_DURATION: Duration = Duration::from_secs(5);
+
+/// A degraded enhancement only runs every n-th frame
+const DEGRADED_FRAME_INTERVAL: u32 = 4;
+
+#[derive(Default)]
+struct EnhancementBudget {
+    /// The enhancement has been skipped for the current frame
+    skipped: bool,
+
+    over_budget_frames: u32,
+    degraded_until: Option<Instant>,
+    degraded_frame: u32,
+}
+
+/// Evaluates the time each enhancement spends in update and render,
+/// as measured by the [EnhancementProfiler](super::EnhancementProfiler).
+/// Enhancements exceeding their budget for several consecutive frames only
+/// run every few frames for a while and a notification gets shown.
+/// Enhancements emitting input keep updating every frame, only their rendering is skipped.
+pub struct FrameBudgetWatchdog {
+    budgets: Vec<EnhancementBudget>,
+    notifications: Vec<String>,
+}
+
+impl FrameBudgetWatchdog {
+    pub fn new() -> Self {
+        Self {
+            budgets: Vec::new(),
+            notifications: Vec::new(),
+        }
+    }
+
+    /// Evaluate the time the enhancement spent during the last frame and decide
+    /// whether it should run this frame. Passing no budget disables the watchdog.
+    pub fn begin_frame(
+        &mut self,
+        index: usize,
+        name: &str,
+        frame_time: Duration,
+        budget: Option<Duration>,
+    ) -> bool {
+        if self.budgets.len() <= index {
+            self.budgets.resize_with(index + 1, Default::default);
+        }
+
+        let entry = &mut self.budgets[index];
+        let Some(budget) = budget else {
+            *entry = Default::default();
+            return true;
+        };
+
+        if !entry.skipped {
+            if frame_time > budget {
+                entry.over_budget_frames += 1;
+            } else {
+                entry.over_budget_frames = 0;
+            }
+        }
+
+        if entry.over_budget_frames >= OVER_BUDGET_FRAME_THRESHOLD && entry.degraded_until.is_none() {
+            entry.over_budget_frames = 0;
+            entry.degraded_until = Some(Instant::now() + DEGRADE_DURATION);
+
+            self.notifications.push(format!(
+                "{} exceeded its frame budget of {:.2}ms ({:.2}ms), skipping frames",
+                name,
+                budget.as_secs_f32() * 1000.0,
+                frame_time.as_secs_f32() * 1000.0
+            ));
+        }
+
+        let entry = &mut self.budgets[index];
+        entry.skipped = match entry.degraded_until {
+            Some(degraded_until) if degraded_until > Instant::now() => {
+                entry.degraded_frame = entry.degraded_frame.wrapping_add(1);
+                entry.degraded_frame % DEGRADED_FRAME_INTERVAL != 0
+            }
+            Some(_) => {
+                entry.degraded_until = None;
+                false
+            }
+            None => false,
+        };
+
+        !entry.skipped
+    }
+
+    /// The enhancement has been skipped for the current frame
+    pub fn is_skipped(&self, index: usize) -> bool {
+        self.budgets
+            .get(index)
+            .map(|entry| entry.skipped)
+            .unwrap_or(false)
+    }
+
+    /// Notifications about degraded enhancements since the last call
+    pub fn take_notifications(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.notifications)
+    }
+}
@@ -0,0 +1,414 @@
+use std::{
+    net::SocketAddr,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use anyhow::Result;
+use cs2::{
+    CEntityIdentityEx,
+    ClassNameCache,
+    PlantedC4,
+    PlantedC4State,
+    PlayerPawnState,
+    StateCS2Memory,
+    StateCurrentMap,
+    StateEntityList,
+    StateLocalPlayerController,
+    StatePawnInfo,
+};
+use cs2_schema_generated::cs2::client::CCSPlayerController;
+use futures_util::{
+    SinkExt,
+    StreamExt,
+};
+use overlay::UnicodeTextRenderer;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use tokio::{
+    sync::watch,
+    task::JoinHandle,
+};
+use utils_state::StateRegistry;
+use warp::{
+    ws::{
+        Message,
+        WebSocket,
+    },
+    Filter,
+};
+
+use super::Enhancement;
+use crate::{
+    settings::AppSettings,
+    utils::display_player_name,
+    UpdateContext,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerSnapshot {
+    pub name: String,
+    pub steam_id: Option<u64>,
+    pub team_id: u8,
+    pub alive: bool,
+    pub health: i32,
+    pub armor: i32,
+    pub has_helmet: bool,
+    pub has_defuser: bool,
+    pub has_bomb: bool,
+    pub weapon: String,
+    pub money: Option<i32>,
+    pub position: [f32; 3],
+    pub rotation: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum BombSnapshot {
+    NotPlanted,
+    Active {
+        site: u8,
+        position: [f32; 3],
+        time_detonation: f32,
+        defuse_time_remaining: Option<f32>,
+    },
+    Defused {
+        site: u8,
+    },
+    Detonated {
+        site: u8,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GameStateSnapshot {
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    pub map: Option<String>,
+    pub local_player: Option<PlayerSnapshot>,
+    pub players: Vec<PlayerSnapshot>,
+    pub bomb: Option<BombSnapshot>,
+}
+
+async fn serve_stream(mut socket: WebSocket, mut snapshots: watch::Receiver<GameStateSnapshot>) {
+    loop {
+        let message = {
+            let snapshot = snapshots.borrow_and_update();
+            match serde_json::to_string(&*snapshot) {
+                Ok(message) => message,
+                Err(err) => {
+                    log::warn!("Failed to serialize game state snapshot: {:#}", err);
+                    return;
+                }
+            }
+        };
+
+        if socket.send(Message::text(message)).await.is_err() {
+            /* client disconnected */
+            return;
+        }
+
+        tokio::select! {
+            result = snapshots.changed() => {
+                if result.is_err() {
+                    /* api has been stopped */
+                    let _ = socket.close().await;
+                    return;
+                }
+            }
+            message = socket.next() => {
+                if !matches!(message, Some(Ok(_))) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// The request has not been sent by a local client and might originate from a web page
+#[derive(Debug)]
+struct ForeignRequest;
+
+impl warp::reject::Reject for ForeignRequest {}
+
+/// Host names of the API, any other host indicates a DNS rebinding attack
+fn is_local_host(host: &str) -> bool {
+    let name = match host.rsplit_once(':') {
+        Some((name, port)) if port.bytes().all(|char| char.is_ascii_digit()) => name,
+        _ => host,
+    };
+    name == "127.0.0.1" || name.eq_ignore_ascii_case("localhost")
+}
+
+/// Reject requests which do not address the local host
+fn local_host_only() -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("host")
+        .and_then(|host: Option<String>| async move {
+            match host {
+                Some(host) if is_local_host(&host) => Ok(()),
+                _ => Err(warp::reject::custom(ForeignRequest)),
+            }
+        })
+        .untuple_one()
+}
+
+/// Reject requests sent by a browser.
+/// Browsers do not apply CORS to WebSockets, but always send the origin of the page.
+fn without_origin() -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("origin")
+        .and_then(|origin: Option<String>| async move {
+            match origin {
+                Some(_) => Err(warp::reject::custom(ForeignRequest)),
+                None => Ok(()),
+            }
+        })
+        .untuple_one()
+}
+
+async fn handle_rejection(rejection: warp::Rejection) -> Result<impl warp::Reply, warp::Rejection> {
+    if rejection.find::<ForeignRequest>().is_some() {
+        return Ok(warp::reply::with_status(
+            "forbidden",
+            warp::http::StatusCode::FORBIDDEN,
+        ));
+    }
+
+    Err(rejection)
+}
+
+fn spawn_server(port: u16, snapshots: watch::Receiver<GameStateSnapshot>) -> Result<JoinHandle<()>> {
+    let with_snapshot = {
+        let snapshots = snapshots.clone();
+        warp::any().map(move || snapshots.borrow().clone())
+    };
+
+    let state = warp::path("state")
+        .and(warp::path::end())
+        .and(with_snapshot.clone())
+        .map(|snapshot: GameStateSnapshot| warp::reply::json(&snapshot));
+    let players = warp::path("players")
+        .and(warp::path::end())
+        .and(with_snapshot.clone())
+        .map(|snapshot: GameStateSnapshot| warp::reply::json(&snapshot.players));
+    let local_player = warp::path("localplayer")
+        .and(warp::path::end())
+        .and(with_snapshot.clone())
+        .map(|snapshot: GameStateSnapshot| warp::reply::json(&snapshot.local_player));
+    let bomb = warp::path("bomb")
+        .and(warp::path::end())
+        .and(with_snapshot)
+        .map(|snapshot: GameStateSnapshot| warp::reply::json(&snapshot.bomb));
+    let stream = warp::path("stream")
+        .and(warp::path::end())
+        .and(without_origin())
+        .and(warp::ws())
+        .map(move |ws: warp::ws::Ws| {
+            let snapshots = snapshots.clone();
+            ws.on_upgrade(move |socket| serve_stream(socket, snapshots))
+        });
+
+    let routes = warp::get()
+        .and(local_host_only())
+        .and(state.or(players).or(local_player).or(bomb).or(stream))
+        .recover(handle_rejection);
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    let (address, future) = warp::serve(routes).try_bind_ephemeral(address)?;
+    log::info!("Game state API listening on http://{}", address);
+
+    Ok(tokio::spawn(future))
+}
+
+/// Local HTTP API exposing the current game state as JSON
+/// and a WebSocket stream of the snapshots.
+pub struct GameStateApi {
+    server: Option<(u16, JoinHandle<()>)>,
+    /// Port the server failed to bind to, to avoid retrying every frame
+    failed_port: Option<u16>,
+
+    snapshot_sender: watch::Sender<GameStateSnapshot>,
+}
+
+impl GameStateApi {
+    pub fn new() -> Self {
+        let (snapshot_sender, _) = watch::channel(GameStateSnapshot::default());
+        Self {
+            server: None,
+            failed_port: None,
+
+            snapshot_sender,
+        }
+    }
+
+    fn stop_server(&mut self) {
+        if let Some((_, server)) = self.server.take() {
+            server.abort();
+            log::info!("Game state API stopped");
+        }
+    }
+}
+
+/// Collect the players and the bomb state from the current game state
+pub fn create_game_state_snapshot(states: &StateRegistry) -> Result<GameStateSnapshot> {
+    let entities = states.resolve::<StateEntityList>(())?;
+    let class_name_cache = states.resolve::<ClassNameCache>(())?;
+    let memory = states.resolve::<StateCS2Memory>(())?;
+
+    let local_controller_address = states
+        .resolve::<StateLocalPlayerController>(())?
+        .instance
+        .address;
+
+    let mut snapshot = GameStateSnapshot {
+        timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64,
+        map: states
+            .resolve::<StateCurrentMap>(())
+            .ok()
+            .and_then(|map| map.current_map.clone()),
+        ..Default::default()
+    };
+
+    for entity_identity in entities.entities() {
+        let entity_class = class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
+        if !entity_class
+            .map(|name| *name == "CCSPlayerController")
+            .unwrap_or(false)
+        {
+            continue;
+        }
+
+        let controller_handle = entity_identity.handle::<dyn CCSPlayerController>()?;
+        let Some(controller_ptr) = entities.entity_from_handle(&controller_handle) else {
+            continue;
+        };
+        let Some(controller) = controller_ptr.value_reference(memory.view_arc()) else {
+            continue;
+        };
+
+        let pawn_handle = controller.m_hPlayerPawn()?;
+        if !pawn_handle.is_valid() {
+            continue;
+        }
+
+        let alive = *states.resolve::<PlayerPawnState>(pawn_handle)? == PlayerPawnState::Alive;
+        let Ok(pawn_info) = states.resolve::<StatePawnInfo>(pawn_handle) else {
+            continue;
+        };
+
+        let player = PlayerSnapshot {
+            name: display_player_name(
+                states,
+                pawn_info.player_name.as_deref().unwrap_or("unknown"),
+            ),
+            steam_id: pawn_info.player_steam_id,
+            team_id: pawn_info.team_id,
+            alive,
+            health: pawn_info.player_health,
+            armor: pawn_info.player_armor,
+            has_helmet: pawn_info.player_has_helmet,
+            has_defuser: pawn_info.player_has_defuser,
+            has_bomb: pawn_info.player_has_bomb,
+            weapon: pawn_info.weapon.name().to_string(),
+            money: pawn_info.player_money,
+            position: pawn_info.position.into(),
+            rotation: pawn_info.rotation,
+        };
+
+        if controller_ptr.address == local_controller_address {
+            snapshot.local_player = Some(player.clone());
+        }
+        snapshot.players.push(player);
+    }
+
+    if let Ok(bomb) = states.resolve::<PlantedC4>(()) {
+        snapshot.bomb = Some(match &bomb.state {
+            PlantedC4State::NotPlanted => BombSnapshot::NotPlanted,
+            PlantedC4State::Active { time_detonation } => BombSnapshot::Active {
+                site: bomb.bomb_site,
+                position: bomb.position.into(),
+                time_detonation: *time_detonation,
+                defuse_time_remaining: bomb
+                    .defuser
+                    .as_ref()
+                    .map(|defuser| defuser.time_remaining),
+            },
+            PlantedC4State::Defused => BombSnapshot::Defused {
+                site: bomb.bomb_site,
+            },
+            PlantedC4State::Detonated => BombSnapshot::Detonated {
+                site: bomb.bomb_site,
+            },
+        });
+    }
+
+    Ok(snapshot)
+}
+
+impl Enhancement for GameStateApi {
+    fn update(&mut self, ctx: &UpdateContext) -> Result<()> {
+        let (enabled, port) = {
+            let settings = ctx.states.resolve::<AppSettings>(())?;
+            (settings.game_state_api, settings.game_state_api_port)
+        };
+
+        if !enabled {
+            self.stop_server();
+            self.failed_port = None;
+            return Ok(());
+        }
+
+        if self.server.as_ref().map(|(server_port, _)| *server_port) != Some(port) {
+            self.stop_server();
+            if self.failed_port != Some(port) {
+                match spawn_server(port, self.snapshot_sender.subscribe()) {
+                    Ok(server) => {
+                        self.server = Some((port, server));
+                        self.failed_port = None;
+                    }
+                    Err(err) => {
+                        log::error!("Failed to start game state API on port {}: {:#}", port, err);
+                        self.failed_port = Some(port);
+                    }
+                }
+            }
+        }
+
+        if self.server.is_none() {
+            return Ok(());
+        }
+
+        let snapshot = create_game_state_snapshot(ctx.states)?;
+        self.snapshot_sender.send_replace(snapshot);
+        Ok(())
+    }
+
+    fn render(
+        &mut self,
+        _states: &StateRegistry,
+        _ui: &imgui::Ui,
+        _unicode_text: &UnicodeTextRenderer,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::is_local_host;
+
+    #[test]
+    fn test_local_host() {
+        assert!(is_local_host("127.0.0.1:8787"));
+        assert!(is_local_host("localhost:8787"));
+        assert!(is_local_host("LocalHost"));
+
+        /* DNS rebinding resolves a foreign name to the local address */
+        assert!(!is_local_host("attacker.example:8787"));
+        assert!(!is_local_host("localhost.attacker.example:8787"));
+        assert!(!is_local_host("127.0.0.1.nip.io"));
+        assert!(!is_local_host(""));
+    }
+}
@@ -17,7 +17,10 @@ use cs2_schema_generated::cs2::client::{
     C_CSPlayerPawn,
     C_CSPlayerPawnBase,
 };
-use imgui::Condition;
+use imgui::{
+    Condition,
+    DrawListMut,
+};
 use nalgebra::{
     Vector2,
     Vector3,
@@ -30,13 +33,28 @@ use utils_state::{
     StateRegistry,
 };
 
-use super::Enhancement;
+use super::{
+    Enhancement,
+    RenderLayer,
+};
 use crate::{
+    notifications::{
+        notify,
+        NotificationLevel,
+    },
     settings::{
         AppSettings,
+        FeatureToggle,
+        GrenadeSettings,
         GrenadeSpotInfo,
+        GrenadeThrowTechnique,
+        GrenadeType,
+    },
+    utils::ImguiComboEnum,
+    view::{
+        StateActiveToggles,
+        ViewController,
     },
-    view::ViewController,
 };
 
 #[derive(Default)]
@@ -44,6 +62,23 @@ struct GrenadeState {
     display_opacity: f32,
     position_held: bool,
     angle_held: bool,
+
+    /// Horizontal distance between the local eye position and the lineup
+    distance: f32,
+    /// Offset from the local eye position to the lineup eye position
+    position_delta: Vector3<f32>,
+    /// Pitch and yaw which need to be added to the local view angles
+    angle_delta: Vector2<f32>,
+}
+
+/// Angle difference in degrees which corresponds to the end of the alignment bars
+const GUIDANCE_ANGLE_RANGE: f32 = 10.0;
+const GUIDANCE_BAR_LENGTH: f32 = 160.0;
+const GUIDANCE_BAR_THICKNESS: f32 = 6.0;
+
+/// Difference between two angles in degrees, wrapped into [-180; 180)
+fn angle_difference(target: f32, current: f32) -> f32 {
+    (target - current + 180.0).rem_euclid(360.0) - 180.0
 }
 
 impl GrenadeSpotInfo {
@@ -56,12 +91,116 @@ impl GrenadeSpotInfo {
     }
 }
 
+/// Lineup which has been captured in game and is being edited in the overlay
+struct LineupEditor {
+    map: String,
+    spot: GrenadeSpotInfo,
+    /// Comma separated tags as entered by the user
+    tags: String,
+}
+
+impl LineupEditor {
+    fn render(&mut self, ui: &imgui::Ui) -> LineupEditorAction {
+        let mut action = LineupEditorAction::None;
+        ui.window("Grenade Lineup Editor")
+            .size([360.0, 0.0], Condition::FirstUseEver)
+            .always_auto_resize(true)
+            .build(|| {
+                ui.text(format!("Map: {}", self.map));
+                ui.text_disabled(format!(
+                    "Position: {:.1}, {:.1}, {:.1}  Angles: {:.2}, {:.2}",
+                    self.spot.eye_position[0],
+                    self.spot.eye_position[1],
+                    self.spot.eye_position[2],
+                    self.spot.eye_direction[0],
+                    self.spot.eye_direction[1]
+                ));
+                ui.text_disabled("Press the capture key again to retake the position");
+                ui.separator();
+
+                ui.input_text("Name##lineup_editor", &mut self.spot.name)
+                    .build();
+                ui.input_text("Tags##lineup_editor", &mut self.tags)
+                    .hint("e.g. a-site, one-way")
+                    .build();
+                ui.input_text_multiline(
+                    "Description##lineup_editor",
+                    &mut self.spot.description,
+                    [0.0, 60.0],
+                )
+                .build();
+
+                ui.combo_enum(
+                    "Throw##lineup_editor",
+                    &GrenadeThrowTechnique::ALL,
+                    &mut self.spot.throw_technique,
+                );
+
+                ui.text("Grenade Types (none = all)");
+                for grenade_type in [
+                    GrenadeType::Smoke,
+                    GrenadeType::Molotov,
+                    GrenadeType::Flashbang,
+                    GrenadeType::Explosive,
+                ] {
+                    let mut enabled = self.spot.grenade_types.contains(&grenade_type);
+                    if ui.checkbox(
+                        format!("{}##lineup_editor_type", grenade_type.display_name()),
+                        &mut enabled,
+                    ) {
+                        if enabled {
+                            self.spot.grenade_types.push(grenade_type);
+                        } else {
+                            self.spot
+                                .grenade_types
+                                .retain(|value| *value != grenade_type);
+                        }
+                    }
+                }
+
+                ui.separator();
+                if ui.button("Save") {
+                    action = LineupEditorAction::Save;
+                }
+                ui.same_line();
+                if ui.button("Cancel") {
+                    action = LineupEditorAction::Cancel;
+                }
+            });
+
+        action
+    }
+
+    fn into_spot(mut self) -> (String, GrenadeSpotInfo) {
+        self.spot.tags = self
+            .tags
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(ToString::to_string)
+            .collect();
+        (self.map, self.spot)
+    }
+}
+
+enum LineupEditorAction {
+    None,
+    Save,
+    Cancel,
+}
+
 pub const DEFAULT_EYE_HEIGHT: Vector3<f32> = Vector3::new(0.0, 0.0, 64.093811);
 pub struct GrenadeHelper {
     grenade_states: BTreeMap<usize, GrenadeState>,
     current_map: String,
 
     eye_height: Vector3<f32>,
+    /// Local view yaw in degrees as of the last update
+    local_yaw: f32,
+
+    lineup_editor: Option<LineupEditor>,
+    /// Lineup which has been saved in the editor and needs to be added to the settings
+    saved_lineup: Option<(String, GrenadeSpotInfo)>,
 }
 
 impl GrenadeHelper {
@@ -71,7 +210,60 @@ impl GrenadeHelper {
             current_map: "<empty>".to_string(),
 
             eye_height: DEFAULT_EYE_HEIGHT,
+            local_yaw: 0.0,
+
+            lineup_editor: None,
+            saved_lineup: None,
+        }
+    }
+
+    fn capture_lineup(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
+        let StateGrenadeHelperPlayerLocation::Valid {
+            eye_position,
+            eye_direction,
+        } = *states.resolve(())?
+        else {
+            notify(
+                states,
+                NotificationLevel::Warning,
+                "Can not capture a lineup while not playing",
+            );
+            return Ok(());
+        };
+
+        match &mut self.lineup_editor {
+            Some(editor) if editor.map == self.current_map => {
+                editor.spot.eye_position = [eye_position.x, eye_position.y, eye_position.z];
+                editor.spot.eye_direction = [eye_direction.x, eye_direction.y];
+            }
+            _ => {
+                let spot_count = states
+                    .resolve::<AppSettings>(())?
+                    .grenade_helper
+                    .map_spots
+                    .get(&self.current_map)
+                    .map_or(0, Vec::len);
+
+                self.lineup_editor = Some(LineupEditor {
+                    map: self.current_map.clone(),
+                    spot: GrenadeSpotInfo {
+                        id: GrenadeSpotInfo::new_id(),
+                        name: format!("Lineup {}", spot_count + 1),
+                        eye_position: [eye_position.x, eye_position.y, eye_position.z],
+                        eye_direction: [eye_direction.x, eye_direction.y],
+                        ..Default::default()
+                    },
+                    tags: String::new(),
+                });
+            }
         }
+
+        notify(
+            states,
+            NotificationLevel::Info,
+            "Lineup captured, open the menu to name and save it",
+        );
+        Ok(())
     }
 }
 
@@ -139,10 +331,19 @@ impl State for StateGrenadeHelperPlayerLocation {
 }
 
 impl Enhancement for GrenadeHelper {
+    fn render_layer(&self) -> RenderLayer {
+        RenderLayer::World
+    }
+
     fn update(&mut self, ctx: &crate::UpdateContext) -> anyhow::Result<()> {
         let settings = ctx.states.resolve::<AppSettings>(())?;
         let settings = &settings.grenade_helper;
-        if !settings.active {
+        if !settings.active
+            || !ctx
+                .states
+                .resolve::<StateActiveToggles>(())?
+                .feature_active(FeatureToggle::GrenadeHelper)
+        {
             return Ok(());
         }
 
@@ -160,6 +361,12 @@ impl Enhancement for GrenadeHelper {
                 .unwrap_or_else(|| "<empty>".to_string());
         }
 
+        if let Some(capture_key) = &settings.capture_key {
+            if ctx.input.is_key_pressed(capture_key.0, false) {
+                self.capture_lineup(ctx.states)?;
+            }
+        }
+
         let Some(map_grenades) = settings.map_spots.get(&self.current_map) else {
             return Ok(());
         };
@@ -172,6 +379,7 @@ impl Enhancement for GrenadeHelper {
             /* The error message contains, why we do not have a position but this is ignorable */
             return Ok(());
         };
+        self.local_yaw = local_direction.y;
 
         for grenade in map_grenades {
             let state = self
@@ -199,14 +407,38 @@ impl Enhancement for GrenadeHelper {
             state.position_held = dist_xy < settings.circle_radius
                 && (grenade.eye_position().z - local_position.z).abs() < 100.0;
 
-            let direction_diff = (grenade.eye_direction() - local_direction).abs();
-            state.angle_held = direction_diff.x < settings.angle_threshold_pitch
-                && direction_diff.y < settings.angle_threshold_yaw;
+            state.distance = dist_xy;
+            state.position_delta = grenade.eye_position() - local_position;
+            state.angle_delta = Vector2::new(
+                grenade.eye_direction().x - local_direction.x,
+                angle_difference(grenade.eye_direction().y, local_direction.y),
+            );
+            state.angle_held = state.angle_delta.x.abs() < settings.angle_threshold_pitch
+                && state.angle_delta.y.abs() < settings.angle_threshold_yaw;
         }
 
         Ok(())
     }
 
+    fn update_settings(
+        &mut self,
+        _ui: &imgui::Ui,
+        settings: &mut AppSettings,
+    ) -> anyhow::Result<bool> {
+        let Some((map, spot)) = self.saved_lineup.take() else {
+            return Ok(false);
+        };
+
+        log::info!("Saved grenade lineup {} for {}", spot.name, map);
+        settings
+            .grenade_helper
+            .map_spots
+            .entry(map)
+            .or_default()
+            .push(spot);
+        Ok(true)
+    }
+
     fn render(
         &mut self,
         states: &StateRegistry,
@@ -216,10 +448,24 @@ impl Enhancement for GrenadeHelper {
         let view = states.resolve::<ViewController>(())?;
         let settings = states.resolve::<AppSettings>(())?;
         let settings = &settings.grenade_helper;
-        if !settings.active {
+        if !settings.active
+            || !states
+                .resolve::<StateActiveToggles>(())?
+                .feature_active(FeatureToggle::GrenadeHelper)
+        {
             return Ok(());
         }
 
+        if let Some(editor) = &mut self.lineup_editor {
+            match editor.render(ui) {
+                LineupEditorAction::None => {}
+                LineupEditorAction::Save => {
+                    self.saved_lineup = self.lineup_editor.take().map(LineupEditor::into_spot);
+                }
+                LineupEditorAction::Cancel => self.lineup_editor = None,
+            }
+        }
+
         let Some(grenades) = settings.map_spots.get(&self.current_map) else {
             return Ok(());
         };
@@ -340,12 +586,193 @@ impl Enhancement for GrenadeHelper {
                         .begin()
                     {
                         ui.text_colored(color.as_f32(), &grenade.name);
+                        if !grenade.tags.is_empty() {
+                            ui.text_disabled(grenade.tags.join(", "));
+                        }
                         ui.text_colored(color.as_f32(), &grenade.description);
                     }
                 }
             }
         }
 
+        if settings.alignment_guidance {
+            let closest_grenade = grenades
+                .iter()
+                .filter_map(|grenade| Some((grenade, self.grenade_states.get(&grenade.id)?)))
+                .filter(|(_, state)| state.display_opacity > 0.0)
+                .min_by(|(_, a), (_, b)| a.distance.total_cmp(&b.distance));
+
+            if let Some((grenade, state)) = closest_grenade {
+                self.render_alignment_guidance(ui, &draw_list, settings, grenade, state);
+            }
+        }
+
         Ok(())
     }
+}
+
+impl GrenadeHelper {
+    fn render_alignment_guidance(
+        &self,
+        ui: &imgui::Ui,
+        draw_list: &DrawListMut,
+        settings: &GrenadeSettings,
+        grenade: &GrenadeSpotInfo,
+        state: &GrenadeState,
+    ) {
+        let [screen_width, screen_height] = ui.io().display_size;
+        let center = Vector2::new(screen_width / 2.0, screen_height / 2.0);
+
+        if !state.position_held {
+            /* point towards the stand point relative to the current view yaw */
+            let mut color = settings.color_position;
+            color.set_alpha_f32(state.display_opacity);
+
+            let target_yaw = state
+                .position_delta
+                .y
+                .atan2(state.position_delta.x)
+                .to_degrees();
+            let relative_yaw = angle_difference(target_yaw, self.local_yaw).to_radians();
+            let direction = Vector2::new(-relative_yaw.sin(), -relative_yaw.cos());
+            let normal = Vector2::new(-direction.y, direction.x);
+
+            let origin = Vector2::new(center.x, screen_height * 0.7);
+            let tip = origin + direction * 30.0;
+            let base = origin - direction * 10.0;
+            draw_list
+                .add_triangle(
+                    [tip.x, tip.y],
+                    [base.x + normal.x * 12.0, base.y + normal.y * 12.0],
+                    [base.x - normal.x * 12.0, base.y - normal.y * 12.0],
+                    color.as_f32(),
+                )
+                .filled(true)
+                .build();
+
+            let text = format!("{} ({:.0} units)", grenade.name, state.distance);
+            let text_size = ui.calc_text_size(&text);
+            draw_list.add_text(
+                [origin.x - text_size[0] / 2.0, origin.y + 40.0],
+                color.as_f32(),
+                &text,
+            );
+            return;
+        }
+
+        let color = if state.angle_held {
+            settings.color_angle_active
+        } else {
+            settings.color_angle
+        };
+        let background = [0.0, 0.0, 0.0, 0.5];
+
+        /* yaw bar below the crosshair, the marker shows where the lineup is relative to the crosshair */
+        {
+            let bar_center = Vector2::new(center.x, center.y + 40.0);
+            let half_length = GUIDANCE_BAR_LENGTH / 2.0;
+            let threshold = settings.angle_threshold_yaw / GUIDANCE_ANGLE_RANGE * half_length;
+            let marker = bar_center.x
+                - (state.angle_delta.y / GUIDANCE_ANGLE_RANGE).clamp(-1.0, 1.0) * half_length;
+
+            draw_list
+                .add_rect(
+                    [
+                        bar_center.x - half_length,
+                        bar_center.y - GUIDANCE_BAR_THICKNESS / 2.0,
+                    ],
+                    [
+                        bar_center.x + half_length,
+                        bar_center.y + GUIDANCE_BAR_THICKNESS / 2.0,
+                    ],
+                    background,
+                )
+                .filled(true)
+                .build();
+            draw_list
+                .add_rect(
+                    [
+                        bar_center.x - threshold,
+                        bar_center.y - GUIDANCE_BAR_THICKNESS / 2.0,
+                    ],
+                    [
+                        bar_center.x + threshold,
+                        bar_center.y + GUIDANCE_BAR_THICKNESS / 2.0,
+                    ],
+                    settings.color_angle_active.as_f32(),
+                )
+                .build();
+            draw_list
+                .add_line(
+                    [marker, bar_center.y - GUIDANCE_BAR_THICKNESS],
+                    [marker, bar_center.y + GUIDANCE_BAR_THICKNESS],
+                    color.as_f32(),
+                )
+                .thickness(2.0)
+                .build();
+        }
+
+        /* pitch bar right of the crosshair, positive pitch means looking down */
+        {
+            let bar_center = Vector2::new(center.x + 40.0, center.y);
+            let half_length = GUIDANCE_BAR_LENGTH / 2.0;
+            let threshold = settings.angle_threshold_pitch / GUIDANCE_ANGLE_RANGE * half_length;
+            let marker = bar_center.y
+                + (state.angle_delta.x / GUIDANCE_ANGLE_RANGE).clamp(-1.0, 1.0) * half_length;
+
+            draw_list
+                .add_rect(
+                    [
+                        bar_center.x - GUIDANCE_BAR_THICKNESS / 2.0,
+                        bar_center.y - half_length,
+                    ],
+                    [
+                        bar_center.x + GUIDANCE_BAR_THICKNESS / 2.0,
+                        bar_center.y + half_length,
+                    ],
+                    background,
+                )
+                .filled(true)
+                .build();
+            draw_list
+                .add_rect(
+                    [
+                        bar_center.x - GUIDANCE_BAR_THICKNESS / 2.0,
+                        bar_center.y - threshold,
+                    ],
+                    [
+                        bar_center.x + GUIDANCE_BAR_THICKNESS / 2.0,
+                        bar_center.y + threshold,
+                    ],
+                    settings.color_angle_active.as_f32(),
+                )
+                .build();
+            draw_list
+                .add_line(
+                    [bar_center.x - GUIDANCE_BAR_THICKNESS, marker],
+                    [bar_center.x + GUIDANCE_BAR_THICKNESS, marker],
+                    color.as_f32(),
+                )
+                .thickness(2.0)
+                .build();
+        }
+
+        let text = if state.angle_held {
+            format!("Aligned - {}", grenade.throw_technique.hint())
+        } else {
+            format!(
+                "Pitch {:+.1}  Yaw {:+.1}",
+                state.angle_delta.x, state.angle_delta.y
+            )
+        };
+        let text_size = ui.calc_text_size(&text);
+        draw_list.add_text(
+            [
+                center.x - text_size[0] / 2.0,
+                center.y + 40.0 + GUIDANCE_BAR_THICKNESS + 6.0,
+            ],
+            color.as_f32(),
+            &text,
+        );
+    }
 }
\ No newline at end of file
@@ -1,8 +1,10 @@
+use std::sync::Arc;
+
 use anyhow::Result;
-use crate::enhancements::Enhancement;
+use crate::enhancements::{Enhancement, RenderLayer};
 use crate::UpdateContext;
-use utils_state::StateRegistry;
-use crate::settings::AppSettings;
+use utils_state::{State, StateCacheType, StateRegistry};
+use crate::settings::{AppSettings, FeatureToggle};
 use cs2_schema_generated::cs2::client::{
     C_CSPlayerPawn, 
     C_EconEntity,
@@ -12,18 +14,21 @@ use cs2_schema_generated::cs2::client::{
 use cs2::{
     StateCS2Memory, 
     StateEntityList, 
+    LocalCameraControllerTarget,
     StateLocalPlayerController,
+    StatePawnGrenadeThrow,
     StatePawnInfo,
     StateCurrentMap,
     WeaponId,
     WEAPON_FLAG_TYPE_GRENADE,
 };
+use cs2_schema_cutl::EntityHandle;
 use imgui::Ui;
 use overlay::UnicodeTextRenderer;
 use nalgebra::{Vector3, Unit};
 use windows::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_LBUTTON, VK_RBUTTON};
-use crate::enhancements::map_loader::MapMesh;
-use crate::view::ViewController;
+use crate::enhancements::map_loader::{MapMesh, StateMapMesh};
+use crate::view::{StateActiveToggles, ViewController};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum ActiveGrenadeType {
@@ -75,10 +80,53 @@ impl TrajectoryState {
     }
 }
 
+/// Map mesh currently used for the trajectory prediction
+pub struct StateGrenadeTrajectoryMesh {
+    reload_requested: bool,
+    /// Name and triangle count of the loaded map mesh
+    loaded_map: Option<(String, usize)>,
+}
+
+impl State for StateGrenadeTrajectoryMesh {
+    type Parameter = ();
+
+    fn create(_states: &StateRegistry, _param: Self::Parameter) -> Result<Self> {
+        Ok(Self {
+            reload_requested: false,
+            loaded_map: None,
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
+
+impl StateGrenadeTrajectoryMesh {
+    /// Load the map mesh again with the next update
+    pub fn request_reload(&mut self) {
+        self.reload_requested = true;
+    }
+
+    pub fn loaded_map(&self) -> Option<(&str, usize)> {
+        self.loaded_map.as_ref().map(|(name, triangles)| (name.as_str(), *triangles))
+    }
+}
+
+/// Point at which the simulated grenade bounced off the map geometry
+#[derive(Clone, Copy)]
+struct TrajectoryBounce {
+    position: Vector3<f32>,
+    normal: Vector3<f32>,
+}
+
 pub struct GrenadeTrajectory {
     trajectory: Option<Vec<Vector3<f32>>>,
+    bounces: Vec<TrajectoryBounce>,
+    /// Time from the throw until the grenade detonates at the landing position
+    detonation_time: Option<f32>,
     active_type: ActiveGrenadeType,
-    map_mesh: Option<MapMesh>,
+    map_mesh: Option<Arc<MapMesh>>,
     current_map_name: Option<String>,
     debug_draw_mesh: bool, 
     last_calc_state: Option<TrajectoryState>,
@@ -97,6 +145,8 @@ impl GrenadeTrajectory {
         // Don't load map in new(), load it in update() based on current map name
         Self { 
             trajectory: None,
+            bounces: Vec::new(),
+            detonation_time: None,
             active_type: ActiveGrenadeType::Unknown,
             map_mesh: None,
             current_map_name: None,
@@ -140,12 +190,48 @@ impl GrenadeTrajectory {
         if out_vel.z > -adjust && out_vel.z < adjust { out_vel.z = 0.0; }
         out_vel
     }
+
+    fn render_landing_circle(
+        view: &ViewController,
+        draw: &imgui::DrawListMut,
+        landing_pos: &Vector3<f32>,
+        radius: f32,
+        fill_color: [f32; 4],
+        outline_color: [f32; 4],
+    ) {
+        let segments = 48;
+        let mut circle_points = Vec::with_capacity(segments + 1);
+        
+        for i in 0..=segments {
+            let angle = (i as f32 * std::f32::consts::PI * 2.0) / segments as f32;
+            let offset = Vector3::new(angle.cos() * radius, angle.sin() * radius, 0.0);
+            let point_3d = landing_pos + offset + Vector3::new(0.0, 0.0, 2.0);
+            
+            if let Some(p2d) = view.world_to_screen(&point_3d, true) {
+                circle_points.push([p2d.x, p2d.y]);
+            }
+        }
+
+        if circle_points.len() > 2 {
+            draw.add_polyline(circle_points.clone(), fill_color).filled(true).build();
+            draw.add_polyline(circle_points, outline_color).thickness(2.0).build();
+            
+            if let Some(center_screen) = view.world_to_screen(landing_pos, true) {
+                draw.add_circle([center_screen.x, center_screen.y], 3.0, outline_color).filled(true).build();
+            }
+        }
+    }
 }
 
 impl Enhancement for GrenadeTrajectory {
+    fn render_layer(&self) -> RenderLayer {
+        RenderLayer::World
+    }
+
     fn update(&mut self, ctx: &UpdateContext) -> Result<()> {
         let settings = ctx.states.resolve::<AppSettings>(())?;
-        if !settings.grenade_trajectory.enabled {
+        let toggles = ctx.states.resolve::<StateActiveToggles>(())?;
+        if !settings.grenade_trajectory.enabled || !toggles.feature_active(FeatureToggle::GrenadeTrajectory) {
             self.trajectory = None;
             return Ok(());
         }
@@ -175,31 +261,24 @@ impl Enhancement for GrenadeTrajectory {
              }
         });
 
-        if let Some(map_name) = final_map {
-             if map_name != "<empty>" && self.current_map_name.as_ref() != Some(&map_name) {
-                 log::info!("Map switch requested: {}", map_name);
-                 self.current_map_name = Some(map_name.clone());
-                 
-                 let resources_path = format!("resources/{}.glb", map_name);
-                 let cwd_path = format!("{}.glb", map_name);
-                 
-                 let glb_path = if std::path::Path::new(&resources_path).exists() {
-                     resources_path
-                 } else {
-                     cwd_path
-                 };
-                 match MapMesh::load(&glb_path) {
-                     Ok(mesh) => {
-                         log::info!("Loaded collision mesh: {}", glb_path);
-                         self.map_mesh = Some(mesh);
-                     },
-                     Err(e) => {
-                         log::warn!("Failed to load collision mesh for {}: {:#}", map_name, e);
-                         self.map_mesh = None;
-                     }
-                 }
-             }
+        let mut mesh_state = ctx.states.resolve_mut::<StateGrenadeTrajectoryMesh>(())?;
+        if let Some(map_name) = final_map.filter(|map_name| map_name != "<empty>") {
+            if self.current_map_name.as_ref() != Some(&map_name) {
+                log::info!("Map switch requested: {}", map_name);
+                self.current_map_name = Some(map_name.clone());
+            }
+
+            /* the mesh is shared with the other enhancements and loaded in the background */
+            let mut map_mesh = ctx.states.resolve_mut::<StateMapMesh>(map_name.clone())?;
+            if mesh_state.reload_requested {
+                mesh_state.reload_requested = false;
+                map_mesh.reload()?;
+            }
+
+            self.map_mesh = map_mesh.mesh();
+            mesh_state.loaded_map = self.map_mesh.as_ref().map(|mesh| (map_name, mesh.triangles.len()));
         }
+        drop(mesh_state);
 
 
         let memory = ctx.states.resolve::<StateCS2Memory>(())?;
@@ -210,9 +289,19 @@ impl Enhancement for GrenadeTrajectory {
             self.trajectory = None; return Ok(()); 
         };
         
-        let local_pawn_handle = match local_controller.m_hPlayerPawn() {
-            Ok(h) => h,
-            Err(_) => { self.trajectory = None; return Ok(()); }
+        // Predict the trajectory for the pawn we're currently looking through (our own or the spectated one)
+        let view_target = ctx.states.resolve::<LocalCameraControllerTarget>(())?;
+        let Some(view_target_entity_id) = view_target.target_entity_id else {
+            self.trajectory = None; return Ok(());
+        };
+
+        let local_pawn_handle = if view_target.is_local_entity {
+            match local_controller.m_hPlayerPawn() {
+                Ok(h) => h,
+                Err(_) => { self.trajectory = None; return Ok(()); }
+            }
+        } else {
+            EntityHandle::<dyn C_CSPlayerPawn>::from_index(view_target_entity_id)
         };
 
         let Some(local_pawn_entity) = entities.entity_from_handle(&local_pawn_handle) else { 
@@ -269,16 +358,26 @@ impl Enhancement for GrenadeTrajectory {
             self.last_logged_wid = Some(wid);
         }
 
-        let left_click = unsafe { (GetAsyncKeyState(VK_LBUTTON.0 as i32) as u16 & 0x8000) != 0 };
-        let right_click = unsafe { (GetAsyncKeyState(VK_RBUTTON.0 as i32) as u16 & 0x8000) != 0 };
-        if !left_click && !right_click {
-             self.trajectory = None;
-             return Ok(());
-        }
+        let throw_strength = if view_target.is_local_entity {
+            let left_click = unsafe { (GetAsyncKeyState(VK_LBUTTON.0 as i32) as u16 & 0x8000) != 0 };
+            let right_click = unsafe { (GetAsyncKeyState(VK_RBUTTON.0 as i32) as u16 & 0x8000) != 0 };
+            if !left_click && !right_click {
+                 self.trajectory = None;
+                 return Ok(());
+            }
 
-        // Estimate Throw Strength based on buttons
-        // Left: 1.0, Right: 0.39, Both: 0.7
-        let throw_strength = if left_click && right_click { 0.7 } else if right_click { 0.39 } else { 1.0 };
+            // Estimate Throw Strength based on buttons
+            // Left: 1.0, Right: 0.39, Both: 0.7
+            if left_click && right_click { 0.7 } else if right_click { 0.39 } else { 1.0 }
+        } else {
+            // We can't see the buttons of the spectated player, use the networked grenade state instead
+            let throw_state = ctx.states.resolve::<StatePawnGrenadeThrow>(local_pawn_handle)?;
+            if !throw_state.pin_pulled {
+                 self.trajectory = None;
+                 return Ok(());
+            }
+            throw_state.throw_strength.clamp(0.0, 1.0)
+        };
 
         let pawn_info = ctx.states.resolve::<StatePawnInfo>(local_pawn_handle)?;
         
@@ -352,6 +451,7 @@ impl Enhancement for GrenadeTrajectory {
         }
         
         let mut path = Vec::new();
+        let mut bounces = Vec::new();
         let mut accumulated_time = 0.0;
         let detonation_time = self.active_type.get_detonation_time();
 
@@ -386,6 +486,8 @@ impl Enhancement for GrenadeTrajectory {
                     }
                 }
 
+                bounces.push(TrajectoryBounce { position, normal: trace.plane_normal });
+
                 // Bounce Physics from Snippet
                 // velocityNormal = -velocityNormal * CoR;
                 // velocityTangent *= friction;
@@ -410,6 +512,8 @@ impl Enhancement for GrenadeTrajectory {
         }
 
         self.trajectory = Some(path);
+        self.bounces = bounces;
+        self.detonation_time = detonation_time.map(|_| accumulated_time);
         self.last_calc_state = Some(current_state);
         Ok(())
     }
@@ -421,6 +525,8 @@ impl Enhancement for GrenadeTrajectory {
         _unicode_text: &UnicodeTextRenderer,
     ) -> Result<()> {
         let view = states.resolve::<ViewController>(())?;
+        let settings = states.resolve::<AppSettings>(())?;
+        let trajectory_settings = &settings.grenade_trajectory;
         let draw = ui.get_window_draw_list();
 
         let Some(trajectory) = &self.trajectory else { return Ok(()); };
@@ -430,6 +536,7 @@ impl Enhancement for GrenadeTrajectory {
         let (color, radius) = self.active_type.get_visuals();
         let outline_color = [color[0], color[1], color[2], 1.0];
         let fill_color = color; 
+        let line_color = if trajectory_settings.line_color_by_type { outline_color } else { trajectory_settings.line_color.as_f32() };
 
         // Render Trajectory Line
         if trajectory.len() > 1 {
@@ -440,32 +547,38 @@ impl Enhancement for GrenadeTrajectory {
                 }
             }
             if screen_points.len() > 1 {
-                 draw.add_polyline(screen_points, outline_color).thickness(2.0).build();
+                 draw.add_polyline(screen_points, line_color).thickness(trajectory_settings.line_thickness).build();
             }
         }
 
-        let segments = 48;
-        let mut circle_points = Vec::with_capacity(segments + 1);
-        
-        for i in 0..=segments {
-            let angle = (i as f32 * std::f32::consts::PI * 2.0) / segments as f32;
-            let offset = Vector3::new(angle.cos() * radius, angle.sin() * radius, 0.0);
-            let point_3d = landing_pos + offset + Vector3::new(0.0, 0.0, 2.0);
-            
-            if let Some(p2d) = view.world_to_screen(&point_3d, true) {
-                circle_points.push([p2d.x, p2d.y]);
+        if trajectory_settings.landing_circle {
+            Self::render_landing_circle(&view, &draw, landing_pos, radius, fill_color, outline_color);
+        }
+
+        // Bounce Markers
+        const BOUNCE_TICK_LENGTH: f32 = 6.0;
+        if trajectory_settings.bounce_markers {
+            for (index, bounce) in self.bounces.iter().enumerate() {
+                let Some(base) = view.world_to_screen(&bounce.position, true) else { continue; };
+                let tip = view.world_to_screen(&(bounce.position + bounce.normal * BOUNCE_TICK_LENGTH), true).unwrap_or(base);
+
+                draw.add_line([base.x, base.y], [tip.x, tip.y], line_color).thickness(2.0).build();
+                draw.add_circle([base.x, base.y], 2.5, line_color).filled(true).build();
+                draw.add_text([tip.x + 3.0, tip.y - ui.text_line_height()], line_color, &format!("{}", index + 1));
             }
         }
 
-        if circle_points.len() > 2 {
-            draw.add_polyline(circle_points.clone(), fill_color).filled(true).build();
-            draw.add_polyline(circle_points, outline_color).thickness(2.0).build();
-            
-            if let Some(center_screen) = view.world_to_screen(landing_pos, true) {
-                draw.add_circle([center_screen.x, center_screen.y], 3.0, outline_color).filled(true).build();
+        // Detonation Timer
+        if let Some(detonation_time) = self.detonation_time.filter(|_| trajectory_settings.detonation_timer) {
+            if let Some(center_screen) = view.world_to_screen(&(landing_pos + Vector3::new(0.0, 0.0, 16.0)), true) {
+                let text = format!("{:.1}s", detonation_time);
+                let [text_width, text_height] = ui.calc_text_size(&text);
+                let text_pos = [center_screen.x - text_width / 2.0, center_screen.y - text_height];
+                draw.add_rect([text_pos[0] - 3.0, text_pos[1] - 1.0], [text_pos[0] + text_width + 3.0, text_pos[1] + text_height + 1.0], [0.0, 0.0, 0.0, 0.6]).filled(true).rounding(3.0).build();
+                draw.add_text(text_pos, outline_color, &text);
             }
         }
 
         Ok(())
     }
-}
\ No newline at end of file
+}
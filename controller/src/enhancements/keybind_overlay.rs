@@ -0,0 +1,146 @@
+use anyhow::Result;
+use imgui::ImColor32;
+use overlay::UnicodeTextRenderer;
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    settings::{
+        AppSettings,
+        KeyToggleMode,
+    },
+    utils::{
+        self,
+        TextWithShadowUi,
+    },
+    view::StateActiveToggles,
+    UpdateContext,
+};
+
+const COLOR_ACTIVE: ImColor32 = ImColor32::from_rgba(110, 220, 110, 255);
+const COLOR_INACTIVE: ImColor32 = ImColor32::from_rgba(150, 150, 150, 255);
+
+struct KeybindEntry {
+    label: String,
+    active: bool,
+}
+
+fn mode_name(mode: KeyToggleMode) -> &'static str {
+    match mode {
+        KeyToggleMode::AlwaysOn => "On",
+        KeyToggleMode::Toggle => "Toggle",
+        KeyToggleMode::Trigger => "Hold",
+        KeyToggleMode::TriggerInverted => "Hold Inverted",
+        KeyToggleMode::Off => "Off",
+    }
+}
+
+/// Panel listing the hotkey driven features and whether they are currently active
+pub struct KeybindOverlay {
+    entries: Vec<KeybindEntry>,
+}
+
+impl KeybindOverlay {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl Enhancement for KeybindOverlay {
+    fn update(&mut self, ctx: &UpdateContext) -> Result<()> {
+        self.entries.clear();
+
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if !settings.keybind_overlay {
+            return Ok(());
+        }
+
+        let toggles = ctx.states.resolve::<StateActiveToggles>(())?;
+        if settings.esp_mode != KeyToggleMode::Off {
+            self.entries.push(KeybindEntry {
+                label: format!("ESP [{}]", mode_name(settings.esp_mode)),
+                active: toggles.esp,
+            });
+        }
+
+        if settings.trigger_bot_mode != KeyToggleMode::Off {
+            self.entries.push(KeybindEntry {
+                label: format!("Trigger Bot [{}]", mode_name(settings.trigger_bot_mode)),
+                active: toggles.trigger_bot,
+            });
+        }
+
+        for (feature, hotkey) in settings.feature_hotkeys.iter() {
+            if hotkey.key.is_none() || hotkey.mode == KeyToggleMode::Off {
+                continue;
+            }
+
+            self.entries.push(KeybindEntry {
+                label: format!("{} [{}]", feature.display_name(), mode_name(hotkey.mode)),
+                active: toggles.feature_active(*feature),
+            });
+        }
+
+        if settings.legit_aim_enabled {
+            self.entries.push(KeybindEntry {
+                label: "Legit Aim [Hold]".to_string(),
+                active: settings
+                    .legit_aim_key
+                    .as_ref()
+                    .map(|key| ctx.input.is_key_down(key.0))
+                    .unwrap_or(false),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn render(
+        &mut self,
+        states: &StateRegistry,
+        ui: &imgui::Ui,
+        _unicode_text: &UnicodeTextRenderer,
+    ) -> Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+        if !settings.keybind_overlay {
+            return Ok(());
+        }
+
+        let overlay_settings = &settings.keybind_overlay_settings;
+        let visible_entries = self
+            .entries
+            .iter()
+            .filter(|entry| entry.active || overlay_settings.show_inactive)
+            .collect::<Vec<_>>();
+        if visible_entries.is_empty() {
+            return Ok(());
+        }
+
+        let anchor_position = overlay_settings.anchor.position(ui.io().display_size);
+        let position = [
+            anchor_position[0] + overlay_settings.offset[0],
+            anchor_position[1] + overlay_settings.offset[1],
+        ];
+
+        utils::render_styled_panel_pivot(
+            ui,
+            "keybind_overlay_panel",
+            position,
+            overlay_settings.anchor.pivot(),
+            || {
+                for entry in &visible_entries {
+                    let (color, state) = if entry.active {
+                        (COLOR_ACTIVE, "ON")
+                    } else {
+                        (COLOR_INACTIVE, "OFF")
+                    };
+                    ui.text_colored_with_shadow(color, &format!("{} {}", entry.label, state));
+                }
+            },
+        );
+
+        Ok(())
+    }
+}
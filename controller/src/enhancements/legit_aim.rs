@@ -23,9 +23,13 @@ impl LegitAim {
 }
 
 impl Enhancement for LegitAim {
+    fn emits_input(&self) -> bool {
+        true
+    }
+
     fn update(&mut self, ctx: &UpdateContext) -> Result<()> {
         let settings = ctx.states.resolve::<AppSettings>(())?;
-        if !settings.legit_aim_enabled {
+        if !settings.legit_aim_enabled || settings.features_relaxed(ctx.states)? {
             return Ok(());
         }
 
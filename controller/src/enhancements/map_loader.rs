@@ -1,7 +1,21 @@
 use anyhow::{Context, Result};
 use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
 use std::env;
+use std::fs::{self, File};
+use std::hash::{DefaultHasher, Hasher};
+use std::io::{BufReader, BufWriter};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use utils_state::{State, StateCacheType, StateRegistry};
+
+use crate::notifications::{notify, NotificationLevel};
+
+/// Increment whenever the BVH layout or the triangle conversion changes
+const BVH_CACHE_VERSION: u32 = 1;
+const BVH_CACHE_EXTENSION: &str = "bvh";
 
 #[derive(Clone, Debug)]
 pub struct Triangle {
@@ -92,6 +106,31 @@ pub struct MapMesh {
     nodes: Vec<LinearNode>,
 }
 
+/// Header of the BVH cache file. Read first so a stale cache can be rejected
+/// without deserializing the whole file.
+#[derive(Serialize, Deserialize)]
+struct BvhCacheHeader {
+    version: u32,
+    /// Hash of the (unpatched) GLB the BVH has been built from
+    source_hash: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BvhCacheNode {
+    min: [f32; 3],
+    max: [f32; 3],
+    offset: u32,
+    count: u16,
+}
+
+/// Built BVH of a map mesh, stored next to the GLB file
+#[derive(Serialize, Deserialize)]
+struct BvhCacheBody {
+    /// Vertices of the reordered triangles
+    triangles: Vec<[[f32; 3]; 3]>,
+    nodes: Vec<BvhCacheNode>,
+}
+
 impl MapMesh {
     // Helper to find the file in common locations
     pub fn resolve_path(filename: &str) -> Option<PathBuf> {
@@ -126,6 +165,34 @@ impl MapMesh {
         None
     }
 
+    /// Names of all map meshes found in the resources directory or the working directory
+    pub fn available_maps() -> Vec<String> {
+        let mut maps = Vec::new();
+        for directory in ["resources", "."] {
+            let Ok(entries) = fs::read_dir(directory) else { continue; };
+            for entry in entries.flatten() {
+                if !entry.file_type().map(|file_type| file_type.is_file()).unwrap_or(false) { continue; }
+
+                let file_name = entry.file_name();
+                let Some(map_name) = file_name.to_str().and_then(|name| name.strip_suffix(".glb")) else { continue; };
+                /* the player model is stored next to the maps */
+                if map_name == "character" { continue; }
+
+                if !maps.iter().any(|known: &String| known == map_name) {
+                    maps.push(map_name.to_string());
+                }
+            }
+        }
+
+        maps.sort();
+        maps
+    }
+
+    /// Load the collision mesh for the given map name (e.g. "de_mirage")
+    pub fn load_map(map_name: &str) -> Result<Self> {
+        Self::load(&format!("{}.glb", map_name))
+    }
+
     pub fn load(filename: &str) -> Result<Self> {
         log::info!("Searching for map physics file: {}", filename);
         
@@ -142,6 +209,21 @@ impl MapMesh {
         let mut file_bytes = std::fs::read(&path)
             .with_context(|| format!("Failed to read file: {:?}", path))?;
 
+        let source_hash = {
+            let mut hasher = DefaultHasher::new();
+            hasher.write(&file_bytes);
+            hasher.finish()
+        };
+        let cache_path = path.with_extension(BVH_CACHE_EXTENSION);
+        match Self::load_bvh_cache(&cache_path, source_hash) {
+            Ok(Some(mesh)) => {
+                log::info!("Loaded BVH from cache {:?}. {} triangles, {} nodes.", cache_path, mesh.triangles.len(), mesh.nodes.len());
+                return Ok(mesh);
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to load BVH cache {:?}: {:#}. Rebuilding.", cache_path, e),
+        }
+
         // Patch the GLB if needed
         match Self::patch_glb_json(&mut file_bytes) {
             Ok(patched) => {
@@ -276,7 +358,104 @@ impl MapMesh {
             log::info!("Linear BVH built successfully. {} nodes.", nodes.len());
         }
 
-        Ok(Self { triangles, nodes })
+        let mesh = Self { triangles, nodes };
+        if let Err(e) = mesh.store_bvh_cache(&cache_path, source_hash) {
+            log::warn!("Failed to write BVH cache {:?}: {:#}", cache_path, e);
+        }
+
+        Ok(mesh)
+    }
+
+    /// Load the BVH from the cache file.
+    /// Returns None if there is no cache or it has been built from a different GLB.
+    fn load_bvh_cache(cache_path: &Path, source_hash: u64) -> Result<Option<Self>> {
+        let file = match File::open(cache_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut reader = BufReader::new(file);
+
+        let header = bincode::deserialize_from::<_, BvhCacheHeader>(&mut reader)
+            .context("failed to read header")?;
+        if header.version != BVH_CACHE_VERSION || header.source_hash != source_hash {
+            log::info!("BVH cache {:?} is outdated.", cache_path);
+            return Ok(None);
+        }
+
+        let body = bincode::deserialize_from::<_, BvhCacheBody>(&mut reader)
+            .context("failed to read BVH")?;
+
+        let triangles = body
+            .triangles
+            .into_iter()
+            .map(|[v0, v1, v2]| {
+                let (v0, v1, v2) = (Vector3::from(v0), Vector3::from(v1), Vector3::from(v2));
+                Triangle {
+                    v0,
+                    v1,
+                    v2,
+                    normal: (v1 - v0).cross(&(v2 - v0)).normalize(),
+                    center: (v0 + v1 + v2) / 3.0,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let nodes = body
+            .nodes
+            .into_iter()
+            .map(|node| LinearNode {
+                aabb: AABB { min: Vector3::from(node.min), max: Vector3::from(node.max) },
+                offset: node.offset,
+                count: node.count,
+                _pad: 0,
+            })
+            .collect::<Vec<_>>();
+
+        /* guard against corrupted caches, traversal indexes without bounds checks */
+        let nodes_valid = nodes.iter().all(|node| {
+            if node.count > 0 {
+                node.offset as usize + node.count as usize <= triangles.len()
+            } else {
+                (node.offset as usize) < nodes.len()
+            }
+        });
+        anyhow::ensure!(nodes_valid, "invalid BVH nodes");
+
+        Ok(Some(Self { triangles, nodes }))
+    }
+
+    fn store_bvh_cache(&self, cache_path: &Path, source_hash: u64) -> Result<()> {
+        let body = BvhCacheBody {
+            triangles: self
+                .triangles
+                .iter()
+                .map(|triangle| [triangle.v0.into(), triangle.v1.into(), triangle.v2.into()])
+                .collect(),
+            nodes: self
+                .nodes
+                .iter()
+                .map(|node| BvhCacheNode {
+                    min: node.aabb.min.into(),
+                    max: node.aabb.max.into(),
+                    offset: node.offset,
+                    count: node.count,
+                })
+                .collect(),
+        };
+
+        /* write into a temporary file first so an interrupted write does not leave a broken cache behind */
+        let temp_path = cache_path.with_extension(format!("{}.tmp", BVH_CACHE_EXTENSION));
+        {
+            let mut writer = BufWriter::new(File::create(&temp_path)?);
+            bincode::serialize_into(&mut writer, &BvhCacheHeader { version: BVH_CACHE_VERSION, source_hash })?;
+            bincode::serialize_into(&mut writer, &body)?;
+            std::io::Write::flush(&mut writer)?;
+        }
+        fs::rename(&temp_path, cache_path)?;
+
+        log::info!("Stored BVH cache at {:?}", cache_path);
+        Ok(())
     }
 
     fn build_linear_bvh(mut triangles: Vec<Triangle>) -> (Vec<Triangle>, Vec<LinearNode>) {
@@ -632,4 +811,95 @@ impl MapMesh {
 
         closest_hit
     }
-}
\ No newline at end of file
+}
+/// Loading progress of a shared map mesh
+#[derive(Clone)]
+pub enum MapMeshStatus {
+    Loading,
+    Loaded(Arc<MapMesh>),
+    Failed,
+}
+
+/// Collision mesh of a map, shared by all enhancements tracing against the map geometry.
+/// The mesh is loaded on a background thread, so a map change does not stall the overlay.
+/// Meshes of maps which are no longer used are dropped after a minute.
+pub struct StateMapMesh {
+    map_name: String,
+    status: Arc<Mutex<MapMeshStatus>>,
+    failure_reported: bool,
+}
+
+impl StateMapMesh {
+    fn spawn_loader(map_name: &str) -> Result<Arc<Mutex<MapMeshStatus>>> {
+        let status = Arc::new(Mutex::new(MapMeshStatus::Loading));
+        std::thread::Builder::new()
+            .name(format!("map mesh {}", map_name))
+            .spawn({
+                let status = status.clone();
+                let map_name = map_name.to_string();
+                move || {
+                    let result = match MapMesh::load_map(&map_name) {
+                        Ok(mesh) => MapMeshStatus::Loaded(Arc::new(mesh)),
+                        Err(err) => {
+                            log::debug!("Failed to load map mesh of {}: {:#}", map_name, err);
+                            MapMeshStatus::Failed
+                        }
+                    };
+                    *status.lock().unwrap() = result;
+                }
+            })?;
+
+        Ok(status)
+    }
+
+    pub fn status(&self) -> MapMeshStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// The mesh if it has been loaded successfully
+    pub fn mesh(&self) -> Option<Arc<MapMesh>> {
+        match self.status() {
+            MapMeshStatus::Loaded(mesh) => Some(mesh),
+            _ => None,
+        }
+    }
+
+    /// Load the mesh from disk again, e.g. after the GLB file has been replaced
+    pub fn reload(&mut self) -> Result<()> {
+        self.status = Self::spawn_loader(&self.map_name)?;
+        self.failure_reported = false;
+        Ok(())
+    }
+}
+
+impl State for StateMapMesh {
+    type Parameter = String;
+
+    fn create(_states: &StateRegistry, map_name: Self::Parameter) -> Result<Self> {
+        Ok(Self {
+            status: Self::spawn_loader(&map_name)?,
+            map_name,
+            failure_reported: false,
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Timed(Duration::from_secs(60))
+    }
+
+    fn update(&mut self, states: &StateRegistry) -> Result<()> {
+        if !self.failure_reported && matches!(self.status(), MapMeshStatus::Failed) {
+            self.failure_reported = true;
+            notify(
+                states,
+                NotificationLevel::Warning,
+                format!(
+                    "Map mesh for {} is missing. Grenade trajectories, measurements and chams occlusion are unavailable.",
+                    self.map_name
+                ),
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,380 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{
+        BufWriter,
+        Write,
+    },
+    path::PathBuf,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+use cs2::{
+    CEntityIdentityEx,
+    ClassNameCache,
+    PlantedC4,
+    PlantedC4State,
+    PlayerPawnState,
+    StateCS2Memory,
+    StateCurrentMap,
+    StateEntityList,
+    StateGameRules,
+    StatePawnInfo,
+    WeaponId,
+};
+use cs2_schema_generated::cs2::client::CCSPlayerController;
+use overlay::UnicodeTextRenderer;
+use serde::Serialize;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use super::{
+    session_log::csv_field,
+    Enhancement,
+};
+use crate::{
+    settings::{
+        get_sessions_dir,
+        AppSettings,
+    },
+    UpdateContext,
+};
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlayerMatchStats {
+    pub name: String,
+    pub team_id: u8,
+
+    pub kills: i32,
+    pub deaths: i32,
+    pub assists: i32,
+    pub damage: i32,
+    pub headshot_kills: i32,
+    pub bomb_plants: u32,
+
+    /// Shots observed by the ammo of the active weapon decreasing
+    pub shots_fired: u32,
+    /// Observed increments of the damage dealt
+    pub hits: u32,
+}
+
+impl PlayerMatchStats {
+    /// Hits per shot in percent
+    pub fn accuracy(&self) -> Option<f32> {
+        (self.shots_fired > 0).then(|| self.hits.min(self.shots_fired) as f32 / self.shots_fired as f32 * 100.0)
+    }
+
+    /// Headshot kills per kill in percent
+    pub fn headshot_percentage(&self) -> Option<f32> {
+        (self.kills > 0).then(|| self.headshot_kills as f32 / self.kills as f32 * 100.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerRoundStats {
+    pub name: String,
+    pub team_id: u8,
+    pub kills: i32,
+    pub damage: i32,
+    pub bomb_planted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundStats {
+    pub round: i32,
+    pub players: Vec<PlayerRoundStats>,
+}
+
+/// Values of the last update used to derive shots and hits
+#[derive(Default)]
+struct PlayerObservation {
+    weapon: Option<WeaponId>,
+    ammo: i32,
+    has_bomb: bool,
+}
+
+/// Statistics of the current match.
+/// Kills, deaths, assists and damage are the values reported by the game,
+/// shots, hits and bomb plants are derived from observed state changes.
+#[derive(Default, Serialize)]
+pub struct StateMatchStatistics {
+    pub map: Option<String>,
+    /// Statistics keyed by the player controller entity index
+    pub players: BTreeMap<u32, PlayerMatchStats>,
+    pub rounds: Vec<RoundStats>,
+
+    #[serde(skip)]
+    current_round: Option<i32>,
+    #[serde(skip)]
+    round_start: BTreeMap<u32, PlayerMatchStats>,
+    #[serde(skip)]
+    observations: BTreeMap<u32, PlayerObservation>,
+    #[serde(skip)]
+    bomb_planted: bool,
+}
+
+impl State for StateMatchStatistics {
+    type Parameter = ();
+
+    fn create(_states: &StateRegistry, _param: Self::Parameter) -> Result<Self> {
+        Ok(Default::default())
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
+
+impl StateMatchStatistics {
+    pub fn reset(&mut self) {
+        *self = Default::default();
+    }
+
+    fn finish_round(&mut self) {
+        let Some(round) = self.current_round else {
+            return;
+        };
+
+        let players = self
+            .players
+            .iter()
+            .map(|(entity_id, stats)| {
+                let start = self.round_start.get(entity_id);
+                PlayerRoundStats {
+                    name: stats.name.clone(),
+                    team_id: stats.team_id,
+                    kills: stats.kills - start.map_or(0, |start| start.kills),
+                    damage: stats.damage - start.map_or(0, |start| start.damage),
+                    bomb_planted: stats.bomb_plants > start.map_or(0, |start| start.bomb_plants),
+                }
+            })
+            .collect();
+
+        self.rounds.push(RoundStats { round, players });
+    }
+
+    fn update_round(&mut self, states: &StateRegistry) {
+        let Ok(game_rules) = states.resolve::<StateGameRules>(()) else {
+            return;
+        };
+
+        if game_rules.warmup_period || self.current_round == Some(game_rules.round_number) {
+            return;
+        }
+
+        self.finish_round();
+        self.current_round = Some(game_rules.round_number);
+        self.round_start = self.players.clone();
+    }
+
+    fn update_players(&mut self, states: &StateRegistry) -> Result<()> {
+        let entities = states.resolve::<StateEntityList>(())?;
+        let class_name_cache = states.resolve::<ClassNameCache>(())?;
+        let memory = states.resolve::<StateCS2Memory>(())?;
+
+        let bomb_planted = states
+            .resolve::<PlantedC4>(())
+            .map(|bomb| matches!(bomb.state, PlantedC4State::Active { .. }))
+            .unwrap_or(false);
+        let bomb_plant_detected = bomb_planted && !self.bomb_planted;
+        self.bomb_planted = bomb_planted;
+
+        for entity_identity in entities.entities() {
+            let entity_class = class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
+            if !entity_class
+                .map(|name| *name == "CCSPlayerController")
+                .unwrap_or(false)
+            {
+                continue;
+            }
+
+            let controller_handle = entity_identity.handle::<dyn CCSPlayerController>()?;
+            let Some(controller) = entities
+                .entity_from_handle(&controller_handle)
+                .and_then(|controller| controller.value_reference(memory.view_arc()))
+            else {
+                continue;
+            };
+
+            let pawn_handle = controller.m_hPlayerPawn()?;
+            if !pawn_handle.is_valid() {
+                continue;
+            }
+
+            let Ok(pawn_info) = states.resolve::<StatePawnInfo>(pawn_handle) else {
+                continue;
+            };
+
+            let Some(tracking) = controller
+                .m_pActionTrackingServices()?
+                .value_reference(memory.view_arc())
+            else {
+                continue;
+            };
+            let match_stats = tracking.m_matchStats()?;
+
+            let entity_id = controller_handle.get_entity_index();
+            let name = pawn_info
+                .player_name
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string());
+
+            let stats = self.players.entry(entity_id).or_default();
+            if stats.name != name {
+                /* a different player took over the controller slot */
+                *stats = PlayerMatchStats {
+                    name,
+                    ..Default::default()
+                };
+                self.observations.remove(&entity_id);
+            }
+
+            let first_seen = !self.observations.contains_key(&entity_id);
+            let damage = match_stats.m_iDamage()?;
+            if !first_seen && damage > stats.damage {
+                stats.hits += 1;
+            }
+
+            stats.team_id = pawn_info.team_id;
+            stats.kills = match_stats.m_iKills()?;
+            stats.deaths = match_stats.m_iDeaths()?;
+            stats.assists = match_stats.m_iAssists()?;
+            stats.damage = damage;
+            stats.headshot_kills = match_stats.m_iHeadShotKills()?;
+
+            let alive = *states.resolve::<PlayerPawnState>(pawn_handle)? == PlayerPawnState::Alive;
+            let observation = self.observations.entry(entity_id).or_default();
+            if bomb_plant_detected && observation.has_bomb {
+                stats.bomb_plants += 1;
+            }
+
+            if !alive {
+                *observation = Default::default();
+                continue;
+            }
+
+            if observation.weapon == Some(pawn_info.weapon)
+                && pawn_info.weapon_current_ammo >= 0
+                && pawn_info.weapon_current_ammo < observation.ammo
+            {
+                stats.shots_fired += (observation.ammo - pawn_info.weapon_current_ammo) as u32;
+            }
+
+            observation.weapon = Some(pawn_info.weapon);
+            observation.ammo = pawn_info.weapon_current_ammo;
+            observation.has_bomb = pawn_info.player_has_bomb;
+        }
+
+        Ok(())
+    }
+
+    fn export_path(extension: &str) -> Result<PathBuf> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        Ok(get_sessions_dir()?.join(format!("match-stats-{}.{}", timestamp, extension)))
+    }
+
+    /// Export the player and round statistics as JSON into `LABHConfig/sessions`.
+    /// Returns the path of the created file.
+    pub fn export_json(&self) -> Result<PathBuf> {
+        let path = Self::export_path("json")?;
+        let output = File::create(&path)
+            .with_context(|| format!("failed to create {}", path.display()))?;
+        serde_json::to_writer_pretty(BufWriter::new(output), self)?;
+        Ok(path)
+    }
+
+    /// Export the player statistics as CSV into `LABHConfig/sessions`.
+    /// Returns the path of the created file.
+    pub fn export_csv(&self) -> Result<PathBuf> {
+        let path = Self::export_path("csv")?;
+        let mut output = BufWriter::new(
+            File::create(&path).with_context(|| format!("failed to create {}", path.display()))?,
+        );
+
+        writeln!(
+            output,
+            "map,player,team,kills,deaths,assists,damage,headshot_kills,headshot_percentage,bomb_plants,shots_fired,hits,accuracy"
+        )?;
+        for stats in self.players.values() {
+            writeln!(
+                output,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                csv_field(self.map.as_deref().unwrap_or_default()),
+                csv_field(&stats.name),
+                stats.team_id,
+                stats.kills,
+                stats.deaths,
+                stats.assists,
+                stats.damage,
+                stats.headshot_kills,
+                stats
+                    .headshot_percentage()
+                    .map(|value| format!("{:.1}", value))
+                    .unwrap_or_default(),
+                stats.bomb_plants,
+                stats.shots_fired,
+                stats.hits,
+                stats
+                    .accuracy()
+                    .map(|value| format!("{:.1}", value))
+                    .unwrap_or_default(),
+            )?;
+        }
+
+        output.flush()?;
+        Ok(path)
+    }
+}
+
+/// Tracks the per player and per round statistics of the current match
+/// into [StateMatchStatistics] which are shown and exported in the Stats tab.
+pub struct MatchStatistics;
+
+impl MatchStatistics {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Enhancement for MatchStatistics {
+    fn update(&mut self, ctx: &UpdateContext) -> Result<()> {
+        if !ctx.states.resolve::<AppSettings>(())?.match_statistics {
+            return Ok(());
+        }
+
+        let map = ctx
+            .states
+            .resolve::<StateCurrentMap>(())
+            .ok()
+            .and_then(|map| map.current_map.clone());
+
+        let mut statistics = ctx.states.resolve_mut::<StateMatchStatistics>(())?;
+        if statistics.map != map {
+            /* a new match has been started */
+            statistics.reset();
+            statistics.map = map;
+        }
+
+        statistics.update_round(ctx.states);
+        statistics.update_players(ctx.states)
+    }
+
+    fn render(
+        &mut self,
+        _states: &StateRegistry,
+        _ui: &imgui::Ui,
+        _unicode_text: &UnicodeTextRenderer,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
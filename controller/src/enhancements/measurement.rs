@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use cs2::StateCurrentMap;
+use imgui::Key;
+use nalgebra::Vector3;
+use overlay::UnicodeTextRenderer;
+use utils_state::StateRegistry;
+
+use super::{
+    map_loader::{
+        MapMesh,
+        StateMapMesh,
+    },
+    Enhancement,
+    RenderLayer,
+};
+use crate::{
+    settings::AppSettings,
+    utils::{
+        TextWithShadowDrawList,
+        UNITS_TO_METERS,
+    },
+    view::ViewController,
+    UpdateContext,
+};
+
+/// Max trace distance for the measurement ray (in game units)
+const MAX_TRACE_DISTANCE: f32 = 16384.0;
+
+const COLOR_POINT: [f32; 4] = [1.0, 0.8, 0.0, 1.0];
+const COLOR_LINE: [f32; 4] = [1.0, 0.8, 0.0, 0.8];
+const COLOR_PREVIEW: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+
+/// Debug tool to measure the distance between two world points.
+/// Hold the measurement key and click with the left mouse button to place the points.
+pub struct MeasurementTool {
+    map_mesh: Option<Arc<MapMesh>>,
+    map_name: Option<String>,
+
+    points: Vec<Vector3<f32>>,
+    aim_point: Option<Vector3<f32>>,
+}
+
+impl MeasurementTool {
+    pub fn new() -> Self {
+        Self {
+            map_mesh: None,
+            map_name: None,
+
+            points: Vec::with_capacity(2),
+            aim_point: None,
+        }
+    }
+
+    fn update_map_mesh(&mut self, states: &StateRegistry) {
+        let Some(current_map) = states
+            .resolve::<StateCurrentMap>(())
+            .ok()
+            .and_then(|map| map.current_map.clone())
+        else {
+            return;
+        };
+
+        if self.map_name.as_ref() != Some(&current_map) {
+            self.points.clear();
+        }
+
+        /* the mesh is loaded in the background, measurements are unavailable until then */
+        self.map_mesh = states
+            .resolve::<StateMapMesh>(current_map.clone())
+            .ok()
+            .and_then(|mesh| mesh.mesh());
+        self.map_name = Some(current_map);
+    }
+
+    fn trace_aim_point(&self, states: &StateRegistry) -> Option<Vector3<f32>> {
+        let mesh = self.map_mesh.as_ref()?;
+        let view = states.resolve::<ViewController>(()).ok()?;
+
+        let screen_center = mint::Vector2 {
+            x: view.offset.x + view.screen_bounds.x / 2.0,
+            y: view.offset.y + view.screen_bounds.y / 2.0,
+        };
+        let (origin, direction) = view.screen_to_world_ray(&screen_center)?;
+        mesh.check_collision(origin, origin + direction * MAX_TRACE_DISTANCE, 0.0)
+            .map(|(_fraction, position, _normal)| position)
+    }
+}
+
+impl Enhancement for MeasurementTool {
+    fn render_layer(&self) -> RenderLayer {
+        RenderLayer::World
+    }
+
+    fn update(&mut self, ctx: &UpdateContext) -> Result<()> {
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if !settings.measurement_tool {
+            self.points.clear();
+            self.aim_point = None;
+            return Ok(());
+        }
+
+        let Some(key) = settings.measurement_tool_key else {
+            return Ok(());
+        };
+
+        if !ctx.input.is_key_down(key.0) {
+            self.aim_point = None;
+            return Ok(());
+        }
+
+        self.update_map_mesh(ctx.states);
+        self.aim_point = self.trace_aim_point(ctx.states);
+
+        if ctx.input.is_key_pressed(Key::MouseLeft, false) {
+            if self.points.len() >= 2 {
+                self.points.clear();
+            }
+
+            if let Some(point) = self.aim_point {
+                self.points.push(point);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render(
+        &mut self,
+        states: &StateRegistry,
+        ui: &imgui::Ui,
+        _unicode_text: &UnicodeTextRenderer,
+    ) -> Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+        if !settings.measurement_tool {
+            return Ok(());
+        }
+
+        let view = states.resolve::<ViewController>(())?;
+        let draw = ui.get_window_draw_list();
+
+        for point in self.points.iter().chain(self.aim_point.iter()) {
+            if let Some(screen) = view.world_to_screen(point, false) {
+                draw.add_circle([screen.x, screen.y], 4.0, COLOR_POINT)
+                    .filled(true)
+                    .build();
+            }
+        }
+
+        let (start, end, color) = match (self.points.as_slice(), &self.aim_point) {
+            ([start, end], _) => (start, end, COLOR_LINE),
+            ([start], Some(aim_point)) => (start, aim_point, COLOR_PREVIEW),
+            _ => return Ok(()),
+        };
+
+        let (Some(screen_start), Some(screen_end)) = (
+            view.world_to_screen(start, true),
+            view.world_to_screen(end, true),
+        ) else {
+            return Ok(());
+        };
+
+        draw.add_line(
+            [screen_start.x, screen_start.y],
+            [screen_end.x, screen_end.y],
+            color,
+        )
+        .thickness(2.0)
+        .build();
+
+        let delta = end - start;
+        let distance = delta.norm();
+        let distance_2d = delta.xy().norm();
+        let yaw = delta.y.atan2(delta.x).to_degrees();
+        let pitch = delta.z.atan2(distance_2d).to_degrees();
+
+        let label_position = [
+            (screen_start.x + screen_end.x) / 2.0 + 8.0,
+            (screen_start.y + screen_end.y) / 2.0,
+        ];
+        let line_height = ui.text_line_height_with_spacing();
+        for (index, line) in [
+            format!(
+                "{:.0} units ({:.2} m)",
+                distance,
+                distance * UNITS_TO_METERS
+            ),
+            format!("2D: {:.0} units, Height: {:+.0} units", distance_2d, delta.z),
+            format!("Yaw: {:.1}, Pitch: {:.1}", yaw, pitch),
+        ]
+        .iter()
+        .enumerate()
+        {
+            draw.add_text_with_shadow(
+                [
+                    label_position[0],
+                    label_position[1] + index as f32 * line_height,
+                ],
+                COLOR_POINT,
+                line,
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,8 +1,38 @@
 use crate::settings::AppSettings;
 use crate::UpdateContext;
+use cs2::CS2Handle;
 use overlay::UnicodeTextRenderer;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use utils_state::StateRegistry;
 
+/// Draw layer of an enhancement.
+/// Layers are drawn in the configured order (world, HUD, debug by default)
+/// and always below the settings menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RenderLayer {
+    /// Visuals placed in the game world like the ESP
+    World,
+    /// Widgets and panels
+    Hud,
+    /// Debug visualizations
+    Debug,
+}
+
+impl RenderLayer {
+    pub const ALL: [RenderLayer; 3] = [Self::World, Self::Hud, Self::Debug];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::World => "World",
+            Self::Hud => "HUD",
+            Self::Debug => "Debug",
+        }
+    }
+}
+
 pub trait Enhancement {
     fn update(&mut self, ctx: &UpdateContext) -> anyhow::Result<()>;
     fn update_settings(
@@ -27,6 +57,28 @@ pub trait Enhancement {
     ) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn render_layer(&self) -> RenderLayer {
+        RenderLayer::Hud
+    }
+
+    /// The enhancement sends mouse or keyboard input to the game.
+    /// Its updates must run every frame and are never degraded.
+    fn emits_input(&self) -> bool {
+        false
+    }
+
+    /// Release all held input and reset the input state,
+    /// e.g. before the enhancements stop updating due to the panic mode.
+    fn release_input(&mut self, _cs2: &CS2Handle) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Name used to identify the enhancement in diagnostics and settings
+    fn name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
 }
 
 mod bomb;
@@ -50,6 +102,9 @@ pub use grenade_helper::*;
 mod sniper_crosshair;
 pub use sniper_crosshair::*;
 
+mod custom_crosshair;
+pub use custom_crosshair::*;
+
 // ADDED: New module for grenade trajectories
 mod grenade_trajectory;
 pub use grenade_trajectory::*;
@@ -58,4 +113,46 @@ pub use grenade_trajectory::*;
 pub mod map_loader;
 
 mod legit_aim;
-pub use legit_aim::*;
\ No newline at end of file
+pub use legit_aim::*;
+
+mod measurement;
+pub use measurement::*;
+
+mod round_hud;
+pub use round_hud::*;
+
+mod economy;
+pub use economy::*;
+
+mod utility_alerts;
+pub use utility_alerts::*;
+
+mod team_overview;
+pub use team_overview::*;
+
+mod session_log;
+pub use session_log::*;
+
+mod movement_hud;
+pub use movement_hud::*;
+
+mod keybind_overlay;
+pub use keybind_overlay::*;
+
+mod frame_budget;
+pub use frame_budget::*;
+
+mod game_state_api;
+pub use game_state_api::*;
+
+mod match_stats;
+pub use match_stats::*;
+
+mod discord_presence;
+pub use discord_presence::*;
+
+mod audio_alerts;
+pub use audio_alerts::*;
+
+mod profiler;
+pub use profiler::*;
@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+
+use anyhow::Result;
+use cs2::{
+    PlayerPawnState,
+    StateCS2Memory,
+    StateLocalPlayerController,
+    StatePawnMovement,
+};
+use imgui::ImColor32;
+use overlay::UnicodeTextRenderer;
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    settings::AppSettings,
+    utils::{
+        self,
+        TextWithShadowUi,
+    },
+    UpdateContext,
+};
+
+/// Amount of speed samples shown in the graph
+const SPEED_HISTORY_LENGTH: usize = 120;
+
+/// Lower bound of the graph scale (in units per second) so slow movement is not blown up
+const SPEED_GRAPH_MIN_SCALE: f32 = 300.0;
+
+const COLOR_AIR: ImColor32 = ImColor32::from_rgba(120, 200, 255, 255);
+const COLOR_DUCK: ImColor32 = ImColor32::from_rgba(234, 190, 84, 255);
+
+struct LocalMovement {
+    speed: f32,
+    on_ground: bool,
+    ducking: bool,
+}
+
+/// Panel showing the horizontal speed of the local player, a speed graph and the duck / air state
+pub struct MovementHud {
+    /// Recent horizontal speed in units per second
+    speed_history: VecDeque<f32>,
+    current: Option<LocalMovement>,
+}
+
+impl MovementHud {
+    pub fn new() -> Self {
+        Self {
+            speed_history: VecDeque::with_capacity(SPEED_HISTORY_LENGTH),
+            current: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.speed_history.clear();
+        self.current = None;
+    }
+}
+
+impl Enhancement for MovementHud {
+    fn update(&mut self, ctx: &UpdateContext) -> Result<()> {
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if !settings.movement_hud {
+            self.reset();
+            return Ok(());
+        }
+
+        let memory = ctx.states.resolve::<StateCS2Memory>(())?;
+        let local_controller = ctx.states.resolve::<StateLocalPlayerController>(())?;
+        let Some(local_controller) = local_controller.instance.value_reference(memory.view_arc())
+        else {
+            self.reset();
+            return Ok(());
+        };
+
+        let local_pawn_handle = local_controller.m_hPlayerPawn()?;
+        if !local_pawn_handle.is_valid()
+            || *ctx.states.resolve::<PlayerPawnState>(local_pawn_handle)? != PlayerPawnState::Alive
+        {
+            self.reset();
+            return Ok(());
+        }
+
+        let movement = ctx.states.resolve::<StatePawnMovement>(local_pawn_handle)?;
+        let speed = movement.speed_2d();
+
+        if self.speed_history.len() >= SPEED_HISTORY_LENGTH {
+            self.speed_history.pop_front();
+        }
+        self.speed_history.push_back(speed);
+
+        self.current = Some(LocalMovement {
+            speed,
+            on_ground: movement.on_ground,
+            ducking: movement.duck_amount > 0.5,
+        });
+        Ok(())
+    }
+
+    fn render(
+        &mut self,
+        states: &StateRegistry,
+        ui: &imgui::Ui,
+        _unicode_text: &UnicodeTextRenderer,
+    ) -> Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+        if !settings.movement_hud {
+            return Ok(());
+        }
+
+        let Some(current) = &self.current else {
+            return Ok(());
+        };
+
+        let hud_settings = &settings.movement_hud_settings;
+        let anchor_position = hud_settings.anchor.position(ui.io().display_size);
+        let position = [
+            anchor_position[0] + hud_settings.offset[0],
+            anchor_position[1] + hud_settings.offset[1],
+        ];
+
+        utils::render_styled_panel_pivot(
+            ui,
+            "movement_hud_panel",
+            position,
+            hud_settings.anchor.pivot(),
+            || {
+                ui.text_with_shadow(&format!(
+                    "{:.0} {}",
+                    hud_settings.units.convert(current.speed),
+                    hud_settings.units.suffix()
+                ));
+
+                if hud_settings.show_state {
+                    if !current.on_ground {
+                        ui.same_line();
+                        ui.text_colored_with_shadow(COLOR_AIR, "AIR");
+                    }
+                    if current.ducking {
+                        ui.same_line();
+                        ui.text_colored_with_shadow(COLOR_DUCK, "DUCK");
+                    }
+                }
+
+                if hud_settings.show_graph {
+                    let (front, back) = self.speed_history.as_slices();
+                    let values = [front, back].concat();
+                    let scale_max = values
+                        .iter()
+                        .copied()
+                        .fold(SPEED_GRAPH_MIN_SCALE, f32::max);
+
+                    ui.plot_lines("##movement_hud_speed", &values)
+                        .graph_size([200.0, 50.0])
+                        .scale_min(0.0)
+                        .scale_max(scale_max)
+                        .build();
+                }
+            },
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+//! Screen space ESP elements (2D box, health and armor bar, tracer) drawn onto an [EspCanvas].
+//! `PlayerESP::render` draws them with the imgui draw list,
+//! the snapshot tests with an offscreen pixmap.
+
+use imgui::DrawListMut;
+use nalgebra::Vector2;
+
+use super::{
+    armor_bar_bounds,
+    fade_color,
+    health_bar_bounds,
+    info_layout::ColorContext,
+    tracer_origin,
+};
+use crate::settings::{
+    EspColor,
+    EspPlayerSettings,
+};
+
+/// Width of the black border around the health and armor bar
+const BAR_BORDER_WIDTH: f32 = 1.0;
+
+/// Drawing primitives required by the screen space ESP elements
+pub trait EspCanvas {
+    fn stroke_rect(&mut self, min: [f32; 2], max: [f32; 2], color: [f32; 4], thickness: f32);
+
+    fn fill_rect(&mut self, min: [f32; 2], max: [f32; 2], color: [f32; 4]);
+
+    /// Fill the rect with a vertical gradient from the top to the bottom color
+    fn fill_rect_vertical_gradient(
+        &mut self,
+        min: [f32; 2],
+        max: [f32; 2],
+        top: [f32; 4],
+        bottom: [f32; 4],
+    );
+
+    fn line(&mut self, start: [f32; 2], end: [f32; 2], color: [f32; 4], thickness: f32);
+}
+
+impl EspCanvas for DrawListMut<'_> {
+    fn stroke_rect(&mut self, min: [f32; 2], max: [f32; 2], color: [f32; 4], thickness: f32) {
+        self.add_rect(min, max, color).thickness(thickness).build();
+    }
+
+    fn fill_rect(&mut self, min: [f32; 2], max: [f32; 2], color: [f32; 4]) {
+        self.add_rect(min, max, color).filled(true).build();
+    }
+
+    fn fill_rect_vertical_gradient(
+        &mut self,
+        min: [f32; 2],
+        max: [f32; 2],
+        top: [f32; 4],
+        bottom: [f32; 4],
+    ) {
+        self.add_rect_filled_multicolor(min, max, top, top, bottom, bottom);
+    }
+
+    fn line(&mut self, start: [f32; 2], end: [f32; 2], color: [f32; 4], thickness: f32) {
+        self.add_line(start, end, color)
+            .thickness(thickness)
+            .build();
+    }
+}
+
+/// Outline of the 2D player box.
+/// Vertical gradients are drawn as four filled edges as a stroke only has a single color.
+pub fn draw_box_2d(
+    canvas: &mut impl EspCanvas,
+    esp_settings: &EspPlayerSettings,
+    vmin: &Vector2<f32>,
+    vmax: &Vector2<f32>,
+    ctx: &ColorContext,
+) {
+    let width = esp_settings.box_width;
+    if let EspColor::GradientVertical { top, bottom } = esp_settings.box_color {
+        let (top, bottom) = (top.as_f32(), bottom.as_f32());
+        canvas.fill_rect_vertical_gradient(
+            [vmin.x - width / 2.0, vmin.y],
+            [vmin.x + width / 2.0, vmax.y],
+            top,
+            bottom,
+        );
+        canvas.fill_rect_vertical_gradient(
+            [vmax.x - width / 2.0, vmin.y],
+            [vmax.x + width / 2.0, vmax.y],
+            top,
+            bottom,
+        );
+        canvas.fill_rect([vmin.x, vmin.y], [vmax.x, vmin.y + width], top);
+        canvas.fill_rect([vmin.x, vmax.y - width], [vmax.x, vmax.y], bottom);
+    } else {
+        let color = esp_settings
+            .box_color
+            .calculate_color(ctx.health, ctx.distance, ctx.time, 0.0);
+        canvas.stroke_rect([vmin.x, vmin.y], [vmax.x, vmax.y], color, width);
+    }
+}
+
+/// Bar with a black border, filled according to the relative value.
+/// Vertical bars fill up from the bottom, horizontal bars from the right.
+fn draw_bar(
+    canvas: &mut impl EspCanvas,
+    [mut x, mut y, mut width, mut height]: [f32; 4],
+    value: f32,
+    background: [f32; 4],
+    color: [f32; 4],
+    fade: f32,
+) {
+    canvas.stroke_rect(
+        [x + BAR_BORDER_WIDTH / 2.0, y + BAR_BORDER_WIDTH / 2.0],
+        [
+            x + width - BAR_BORDER_WIDTH / 2.0,
+            y + height - BAR_BORDER_WIDTH / 2.0,
+        ],
+        fade_color([0.0, 0.0, 0.0, 1.0], fade),
+        BAR_BORDER_WIDTH,
+    );
+
+    x += BAR_BORDER_WIDTH / 2.0 + 1.0;
+    y += BAR_BORDER_WIDTH / 2.0 + 1.0;
+    width -= BAR_BORDER_WIDTH + 2.0;
+    height -= BAR_BORDER_WIDTH + 2.0;
+
+    if width < height {
+        let offset = y + (1.0 - value) * height;
+        canvas.fill_rect([x, y], [x + width, offset], background);
+        canvas.fill_rect([x, offset], [x + width, y + height], color);
+    } else {
+        let offset = x + (1.0 - value) * width;
+        canvas.fill_rect([x, y], [offset, y + height], background);
+        canvas.fill_rect([offset, y], [x + width, y + height], color);
+    }
+}
+
+pub fn draw_health_bar(
+    canvas: &mut impl EspCanvas,
+    esp_settings: &EspPlayerSettings,
+    vmin: &Vector2<f32>,
+    vmax: &Vector2<f32>,
+    ctx: &ColorContext,
+    fade: f32,
+) {
+    let Some(bounds) = health_bar_bounds(esp_settings, vmin, vmax) else {
+        return;
+    };
+
+    let color =
+        esp_settings
+            .info_hp_text_color
+            .calculate_color(ctx.health, ctx.distance, ctx.time, 0.5);
+    draw_bar(
+        canvas,
+        bounds,
+        ctx.health,
+        fade_color([1.0, 0.0, 0.0, 1.0], fade),
+        color,
+        fade,
+    );
+}
+
+pub fn draw_armor_bar(
+    canvas: &mut impl EspCanvas,
+    esp_settings: &EspPlayerSettings,
+    vmin: &Vector2<f32>,
+    vmax: &Vector2<f32>,
+    armor: f32,
+    ctx: &ColorContext,
+    fade: f32,
+) {
+    let Some(bounds) = armor_bar_bounds(esp_settings, vmin, vmax) else {
+        return;
+    };
+
+    let color =
+        esp_settings
+            .armor_bar_color
+            .calculate_color(ctx.health, ctx.distance, ctx.time, 0.5);
+    draw_bar(
+        canvas,
+        bounds,
+        armor,
+        fade_color([0.15, 0.15, 0.15, 1.0], fade),
+        color,
+        fade,
+    );
+}
+
+/// Line from the configured screen position to the player
+pub fn draw_tracer(
+    canvas: &mut impl EspCanvas,
+    esp_settings: &EspPlayerSettings,
+    screen_bounds: &mint::Vector2<f32>,
+    target: [f32; 2],
+    ctx: &ColorContext,
+) {
+    let Some(origin) = tracer_origin(esp_settings.tracer_lines, screen_bounds) else {
+        return;
+    };
+
+    let color =
+        esp_settings
+            .tracer_lines_color
+            .calculate_color(ctx.health, ctx.distance, ctx.time, 0.0);
+    canvas.line(origin, target, color, esp_settings.tracer_lines_width);
+}
@@ -5,6 +5,7 @@ use imgui::{
     ImColor32,
     TextureId,
 };
+use overlay::StyledTextDrawList;
 use crate::settings::{EspTextStyle, EspColor};
 
 #[derive(Clone, Copy, PartialEq)]
@@ -48,11 +49,12 @@ impl<'a> PlayerInfoLayout<'a> {
         has_2d_box: bool,
         alignment: LayoutAlignment,
         text_style: EspTextStyle,
+        font_scale: f32,
     ) -> Self {
         let height_ratio = (vmax.y - vmin.y) / screen_bounds.y;
 
-        // TEXT: Aggressive scaling to stay readable (Min 0.85)
-        let text_scale = (height_ratio * 10.0).clamp(0.85, 1.3);
+        // TEXT: Aggressive scaling to stay readable (Min 0.85), multiplied by the user text scale
+        let text_scale = (height_ratio * 10.0).clamp(0.85, 1.3) * font_scale.max(0.1);
         
         // IMAGE: Natural scaling (Min 3.0), reverting to previous behavior for icons
         let image_scale = (height_ratio * 8.0).clamp(0.5, 1.25);
@@ -105,33 +107,16 @@ impl<'a> PlayerInfoLayout<'a> {
 
         let col = self.resolve_color(color_setting, ctx);
 
-        match self.text_style {
-            EspTextStyle::Shadow => {
-                let shadow_col = [0.0, 0.0, 0.0, col[3]];
-                self.draw.add_text([x + 1.0, y + 1.0], shadow_col, text);
-            }
-            EspTextStyle::Outline => {
-                let outline_col = [0.0, 0.0, 0.0, col[3]];
-                self.draw.add_text([x - 1.0, y], outline_col, text);
-                self.draw.add_text([x + 1.0, y], outline_col, text);
-                self.draw.add_text([x, y - 1.0], outline_col, text);
-                self.draw.add_text([x, y + 1.0], outline_col, text);
-            }
-            EspTextStyle::Neon => {
-                let mut glow_col = col;
-                glow_col[3] *= 0.3; 
-                self.draw.add_text([x - 2.0, y], glow_col, text);
-                self.draw.add_text([x + 2.0, y], glow_col, text);
-                self.draw.add_text([x, y - 2.0], glow_col, text);
-                self.draw.add_text([x, y + 2.0], glow_col, text);
-            }
-        }
-
-        self.draw.add_text([x, y], col, text);
+        self.draw.add_styled_text([x, y], col, &self.text_style.text_style(), text);
         self.y_offset += scaled_line_height + 2.0;
     }
 
     pub fn add_image(&mut self, texture_id: TextureId, color_setting: &EspColor, ctx: &ColorContext, base_height: f32, aspect_ratio: f32) {
+        self.add_image_region(texture_id, [0.0, 0.0], [1.0, 1.0], color_setting, ctx, base_height, aspect_ratio);
+    }
+
+    /// Add a part of a texture, e.g. an icon of a texture atlas
+    pub fn add_image_region(&mut self, texture_id: TextureId, uv_min: [f32; 2], uv_max: [f32; 2], color_setting: &EspColor, ctx: &ColorContext, base_height: f32, aspect_ratio: f32) {
         // Use image_scale for images so they shrink nicely at distance
         let height = base_height * self.scale_image;
         let width = height * aspect_ratio;
@@ -153,23 +138,25 @@ impl<'a> PlayerInfoLayout<'a> {
         match self.text_style {
             EspTextStyle::Shadow => {
                  let shadow_col = [0.0, 0.0, 0.0, col[3] * 0.6];
-                 self.draw.add_image(texture_id, [x+1.0, y+1.0], [x + width+1.0, y + height+1.0]).col(shadow_col).build();
+                 self.draw.add_image(texture_id, [x+1.0, y+1.0], [x + width+1.0, y + height+1.0]).uv_min(uv_min).uv_max(uv_max).col(shadow_col).build();
             },
             EspTextStyle::Outline => {
                 let outline_col = [0.0, 0.0, 0.0, col[3]];
-                self.draw.add_image(texture_id, [x+1.0, y+1.0], [x + width+1.0, y + height+1.0]).col(outline_col).build();
+                self.draw.add_image(texture_id, [x+1.0, y+1.0], [x + width+1.0, y + height+1.0]).uv_min(uv_min).uv_max(uv_max).col(outline_col).build();
             },
             EspTextStyle::Neon => {
                 let mut glow_col = col;
                 glow_col[3] *= 0.3;
-                self.draw.add_image(texture_id, [x-2.0, y], [x + width-2.0, y + height]).col(glow_col).build();
-                self.draw.add_image(texture_id, [x+2.0, y], [x + width+2.0, y + height]).col(glow_col).build();
-                self.draw.add_image(texture_id, [x, y-2.0], [x + width, y + height-2.0]).col(glow_col).build();
-                self.draw.add_image(texture_id, [x, y+2.0], [x + width, y + height+2.0]).col(glow_col).build();
+                self.draw.add_image(texture_id, [x-2.0, y], [x + width-2.0, y + height]).uv_min(uv_min).uv_max(uv_max).col(glow_col).build();
+                self.draw.add_image(texture_id, [x+2.0, y], [x + width+2.0, y + height]).uv_min(uv_min).uv_max(uv_max).col(glow_col).build();
+                self.draw.add_image(texture_id, [x, y-2.0], [x + width, y + height-2.0]).uv_min(uv_min).uv_max(uv_max).col(glow_col).build();
+                self.draw.add_image(texture_id, [x, y+2.0], [x + width, y + height+2.0]).uv_min(uv_min).uv_max(uv_max).col(glow_col).build();
             }
         }
 
         self.draw.add_image(texture_id, [x, y], [x + width, y + height])
+            .uv_min(uv_min)
+            .uv_max(uv_max)
             .col(col)
             .build();
 
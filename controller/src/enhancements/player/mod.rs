@@ -1,128 +1,126 @@
 // controller/src/enhancements/player/mod.rs
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::Arc;
 use std::time::Instant;
 
 use anyhow::Result;
 use cs2::{
-    BoneFlags, CEntityIdentityEx, CS2Model, ClassNameCache, LocalCameraControllerTarget,
-    PlayerPawnState, StateCS2Memory, StateEntityList, StateLocalPlayerController, StatePawnInfo,
-    StatePawnModelInfo, StatePawnModelAddress, WeaponId
+    BoneFlags, CS2Model, StateCS2Memory, StateCurrentMap, StateEntityList, StatePawnInfo, StatePlayerRank,
+    StatePawnModelInfo, StatePawnModelAddress, WeaponState,
 };
 use cs2_schema_cutl::EntityHandle;
 use cs2_schema_generated::cs2::client::C_BaseEntity;
-use cs2_schema_generated::cs2::client::CCSPlayerController;
+use cs2_schema_generated::cs2::client::C_CSPlayerPawn;
 use imgui::Ui;
+use draw::{draw_armor_bar, draw_box_2d, draw_health_bar, draw_tracer};
 use info_layout::{PlayerInfoLayout, LayoutAlignment, ColorContext};
-use nalgebra::{Vector3, Matrix4};
+use nalgebra::{Vector2, Vector3, Matrix4};
 use obfstr::obfstr;
-use overlay::UnicodeTextRenderer;
+use overlay::{StyledTextDrawList, UnicodeTextRenderer};
+use rayon::prelude::*;
 use utils_state::StateRegistry;
 
-use super::Enhancement;
+use super::{
+    map_loader::{MapMesh, StateMapMesh},
+    BuyType,
+    Enhancement,
+    RenderLayer,
+};
 use crate::{
     settings::{
-        AppSettings, EspBoxType, EspConfig, EspHeadDot, EspHealthBar, EspPlayerSettings,
-        EspSelector, EspTracePosition, EspInfoStyle, EspColor,
+        AppSettings, EspBoxType, EspConfig, EspHeadDot, EspHealthBar, EspOffscreenArrowMode, EspPlayerSettings,
+        EspSelector, EspTracePosition, EspInfoStyle, EspColor, FeatureToggle, draw_box_corners, draw_box_fill,
     },
-    view::{KeyToggle, ViewController},
+    utils::{with_steam_profile, UNITS_TO_METERS},
+    update_thread::{GameSnapshot, StateGameSnapshot},
+    view::{KeyToggle, StateActiveToggles, ViewController},
     AppResources,
 };
 
+mod draw;
 mod info_layout;
 pub mod model_renderer;
+mod text_cache;
+#[cfg(test)]
+mod snapshot;
+
+/// Overlay alpha of a flash blinding the player completely
+const FLASH_FULL_ALPHA: f32 = 255.0;
+/// Approximate time at the end of a flash in which the white overlay fades out
+const FLASH_FADE_DURATION: f32 = 1.5;
+/// Eye height of a standing player, used when the head bone is not available
+const VIEW_DIRECTION_EYE_HEIGHT: f32 = 64.0;
+/// Min time in seconds between two recorded trail positions
+const TRAIL_SAMPLE_INTERVAL: f32 = 0.05;
+
+/// Avatars are drawn without tinting
+const ESP_AVATAR_COLOR: EspColor = EspColor::from_rgba(1.0, 1.0, 1.0, 1.0);
+
 use model_renderer::CharacterModel;
+use text_cache::PlayerTextCache;
 
+/// Per player state kept across frames. Texts and buffers are reused so rendering
+/// a player does not allocate once all of them have been populated.
 struct PlayerData {
     pawn_handle: u32,
+    /// Index of the player in the snapshot of the update thread
+    snapshot_index: usize,
     bone_transforms: HashMap<String, Matrix4<f32>>,
+    texts: PlayerTextCache,
+    /// Recent positions with the time (relative to the ESP start) they have been recorded at, oldest first
+    trail: VecDeque<(f32, Vector3<f32>)>,
+    /// Last position the player has been spotted at, becomes a ghost marker once the player is lost
+    last_seen: Option<GhostMarker>,
+}
+
+/// Last known position of a player which is no longer spotted or has left the entity list
+#[derive(Default)]
+struct GhostMarker {
+    name: String,
+    position: Vector3<f32>,
+    /// Time relative to the ESP start the player has last been seen at
+    seen_at: f32,
+    duration: f32,
+    color: [f32; 4],
 }
 
 pub struct PlayerESP {
     toggle: KeyToggle,
     players: HashMap<u32, PlayerData>,
+    ghosts: HashMap<u32, GhostMarker>,
     local_team_id: u8,
+    /// Snapshot of the update thread the players have been collected from
+    snapshot: Arc<GameSnapshot>,
     start_time: Instant,
     models: HashMap<String, Option<CharacterModel>>,
+    /// Collision mesh of the current map used to determine occluded body parts for the chams
+    map_mesh: Option<Arc<MapMesh>>,
+    /// Map the collision mesh has been resolved for
+    map_mesh_name: String,
+
+    /// Scratch buffer for the pawn handles of the players prefetched each frame
+    prefetch_handles: Vec<u32>,
+    /// Scratch buffer for the radial off-screen arrows of the current frame
+    radial_arrows: Vec<RadialArrowState>,
+    /// Scratch buffer for labels which change every frame
+    label_buffer: String,
 }
 
-fn lerp(start: Vector3<f32>, end: Vector3<f32>, t: f32) -> Vector3<f32> {
-    start + (end - start) * t
-}
-
-fn map_weapon_to_icon(weapon: WeaponId) -> &'static str {
-    match weapon {
-        WeaponId::KnifeT => "knife_t",
-        WeaponId::Knife => "knife",
-        WeaponId::KnifeBayonet => "bayonet",
-        WeaponId::KnifeButterfly => "knife_butterfly",
-        WeaponId::KnifesClassic => "knife_css",
-        WeaponId::KnifeCord => "knife_cord",
-        WeaponId::KnifeFalchion => "knife_falchion",
-        WeaponId::KnifeFlip => "knife_flip",
-        WeaponId::KnifeGut => "knife_gut",
-        WeaponId::KnifeTactical => "knife_tactical",
-        WeaponId::KnifeKarambit => "knife_karambit",
-        WeaponId::KnifeM9Bayonet => "knife_m9_bayonet",
-        WeaponId::KnifesNavaja => "knife_gypsy_jackknife",
-        WeaponId::KnifesNomad => "knife_outdoor",
-        WeaponId::KnifePush => "knife_push",
-        WeaponId::KnifesSkeleton => "knife_skeleton",
-        WeaponId::KnifesStiletto => "knife_stiletto",
-        WeaponId::KnifeSurvivalBowie | WeaponId::KnifeSurvival => "knife_survival_bowie",
-        WeaponId::KnifesTalon => "knife_widowmaker",
-        WeaponId::KnifeUrsus => "knife_ursus",
-        WeaponId::Deagle => "deagle",
-        WeaponId::Revolver => "revolver",
-        WeaponId::CZ75a => "cz75a",
-        WeaponId::Elite => "elite",
-        WeaponId::HKP200 => "hkp2000",
-        WeaponId::Glock => "glock",
-        WeaponId::P250 => "p250",
-        WeaponId::FiveSeven => "fiveseven",
-        WeaponId::Tec9 => "tec9",
-        WeaponId::USPS => "usp_silencer",
-        WeaponId::M4A1Silencer => "m4a1_silencer",
-        WeaponId::M4A4 => "m4a1",
-        WeaponId::Ak47 => "ak47",
-        WeaponId::Galilar => "galilar",
-        WeaponId::Famas => "famas",
-        WeaponId::Aug => "aug",
-        WeaponId::Sg553 => "sg556",
-        WeaponId::Ssg08 => "ssg08",
-        WeaponId::AWP => "awp",
-        WeaponId::G3SG1 => "g3sg1",
-        WeaponId::Scar20 => "scar20",
-        WeaponId::Mac10 => "mac10",
-        WeaponId::MP5SD => "mp5sd",
-        WeaponId::Ump45 => "ump451",
-        WeaponId::Bizon => "bizon",
-        WeaponId::MP7 => "mp7",
-        WeaponId::MP9 => "mp9",
-        WeaponId::P90 => "p90",
-        WeaponId::Mag7 => "mag7",
-        WeaponId::Nova => "nova",
-        WeaponId::SawedOff => "sawedoff",
-        WeaponId::XM1014 => "xm1014",
-        WeaponId::M249 => "m249",
-        WeaponId::Negev => "negev",
-        WeaponId::Taser => "taser",
-        WeaponId::HZgrenade => "hegrenade",
-        WeaponId::Smokegrenade => "smokegrenade",
-        WeaponId::Flashbang => "flashbang",
-        WeaponId::Molotov => "molotov",
-        WeaponId::Incendiary => "incgrenade0",
-        WeaponId::Decoy => "decoy",
-        WeaponId::C4 => "c4",
-        _ => "",
-    }
+struct RadialArrowState {
+    /// Normalized screen space direction from the screen center towards the player
+    direction: Vector2<f32>,
+    dist: f32,
+    color: [f32; 4],
+    radius: f32,
+    size: f32,
+    ellipse: bool,
+    show_distance: bool,
 }
 
-fn get_weapon_icon_aspect_ratio(icon_key: &str) -> f32 {
-    match icon_key {
-        "hegrenade" | "smokegrenade" | "flashbang" | "molotov" | "incgrenade0" | "decoy" => 0.6,
-        _ => 2.5,
-    }
+fn lerp(start: Vector3<f32>, end: Vector3<f32>, t: f32) -> Vector3<f32> {
+    start + (end - start) * t
 }
 
 fn get_weapon_icon_scale(icon_key: &str) -> f32 {
@@ -138,20 +136,30 @@ impl PlayerESP {
         PlayerESP {
             toggle: KeyToggle::new(),
             players: HashMap::new(),
+            ghosts: HashMap::new(),
             local_team_id: 0,
+            snapshot: Default::default(),
             start_time: Instant::now(),
             models: HashMap::new(),
+            map_mesh: None,
+            map_mesh_name: String::new(),
+            prefetch_handles: Vec::new(),
+            radial_arrows: Vec::new(),
+            label_buffer: String::new(),
         }
     }
     
+    /// Walk up the selector tree starting at the team and visibility of the target
+    /// until an enabled player config has been found.
     fn resolve_esp_player_config<'a>(
         settings: &'a AppSettings,
         target: &StatePawnInfo,
         local_team_id: u8,
+        visible: bool,
     ) -> Option<&'a EspPlayerSettings> {
         let mut esp_target = Some(EspSelector::PlayerTeamVisibility {
             enemy: target.team_id != local_team_id,
-            visible: true,
+            visible,
         });
         while let Some(target) = esp_target.take() {
             let config_key = target.config_key();
@@ -164,54 +172,225 @@ impl PlayerESP {
         }
         None
     }
+
+    fn update_map_mesh(&mut self, states: &StateRegistry) {
+        let Ok(map_state) = states.resolve::<StateCurrentMap>(()) else { return; };
+        let Some(current_map) = map_state.current_map.as_deref() else { return; };
+        if self.map_mesh.is_some() && self.map_mesh_name == current_map { return; }
+
+        /* the mesh is loaded in the background, chams are drawn without occlusion until then */
+        self.map_mesh = states.resolve::<StateMapMesh>(current_map.to_string()).ok().and_then(|mesh| mesh.mesh());
+        self.map_mesh_name.clear();
+        self.map_mesh_name.push_str(current_map);
+    }
+
+    /// Resolve the states of all tracked players in parallel on the rayon thread pool,
+    /// so the render loop only reads the cached states.
+    /// None of these states may depend on a state the caller has resolved mutably.
+    fn prefetch_player_states(&mut self, states: &StateRegistry, with_bones: bool) {
+        self.prefetch_handles.clear();
+        self.prefetch_handles.extend(self.players.values().map(|entry| entry.pawn_handle));
+
+        self.prefetch_handles.par_iter().for_each(|pawn_handle_index| {
+            /* errors will be reported when resolving the states again while rendering */
+            let model_address = states
+                .resolve::<StatePawnModelAddress>(EntityHandle::from_index(*pawn_handle_index))
+                .map(|model| model.model_address);
+            if let Ok(model_address) = model_address {
+                let _ = states.resolve::<CS2Model>(model_address);
+            }
+
+            if with_bones {
+                let _ = states.resolve::<StatePawnModelInfo>(EntityHandle::from_index(*pawn_handle_index));
+            }
+        });
+    }
+}
+
+/// Space above the highest bone for the top of the head (in world units)
+const BONE_BOX_HEAD_PADDING: f32 = 8.0;
+/// Horizontal padding of bone fitted boxes relative to the box height
+const BONE_BOX_SIDE_PADDING: f32 = 0.08;
+
+/// 2D box around the projected hitbox bones of the player.
+/// Tighter than the static hull for crouching or leaning players.
+fn bone_fitted_box_2d(
+    view: &ViewController,
+    model: &CS2Model,
+    pawn_model: &StatePawnModelInfo,
+    origin: &Vector3<f32>,
+) -> Option<(nalgebra::Vector2<f32>, nalgebra::Vector2<f32>)> {
+    /* iterated twice instead of collected, as this runs for every player each frame */
+    let bone_positions = model
+        .bones
+        .iter()
+        .zip(pawn_model.bone_states.iter())
+        .filter(|(bone, _)| (bone.flags & BoneFlags::FlagHitbox as u32) > 0)
+        .map(|(_, state)| state.position);
+
+    let head = bone_positions
+        .clone()
+        .max_by(|a, b| a.z.total_cmp(&b.z))?;
+
+    let (mut vmin, mut vmax) = view.calculate_bounds_2d(
+        bone_positions.chain([head + Vector3::new(0.0, 0.0, BONE_BOX_HEAD_PADDING), *origin]),
+    )?;
+
+    let padding = (vmax.y - vmin.y) * BONE_BOX_SIDE_PADDING;
+    vmin.x -= padding;
+    vmax.x += padding;
+    Some((vmin, vmax))
+}
+
+/// Bounds of a bar (x, y, width, height) placed at the given side of the 2D player box.
+/// The offset moves the bar away from the box.
+fn bar_bounds(
+    position: EspHealthBar,
+    bar_width: f32,
+    offset: f32,
+    box_width: f32,
+    vmin: &nalgebra::Vector2<f32>,
+    vmax: &nalgebra::Vector2<f32>,
+) -> Option<[f32; 4]> {
+    match position {
+        EspHealthBar::None => None,
+        EspHealthBar::Left => Some([vmin.x - box_width / 2.0 - bar_width - offset, vmin.y - box_width / 2.0, bar_width, vmax.y - vmin.y + box_width]),
+        EspHealthBar::Right => Some([vmax.x + box_width / 2.0 + offset, vmin.y - box_width / 2.0, bar_width, vmax.y - vmin.y + box_width]),
+        EspHealthBar::Top => Some([vmin.x - box_width / 2.0, vmin.y - box_width / 2.0 - bar_width - offset, vmax.x - vmin.x + box_width, bar_width]),
+        EspHealthBar::Bottom => Some([vmin.x - box_width / 2.0, vmax.y + box_width / 2.0 + offset, vmax.x - vmin.x + box_width, bar_width]),
+    }
+}
+
+/// Bounds of the flash bar (x, y, width, height) above the 2D player box and any bars placed on top of it.
+fn flash_bar_bounds(
+    esp_settings: &EspPlayerSettings,
+    vmin: &nalgebra::Vector2<f32>,
+    vmax: &nalgebra::Vector2<f32>,
+) -> [f32; 4] {
+    const FLASH_BAR_HEIGHT: f32 = 4.0;
+    let top = [health_bar_bounds(esp_settings, vmin, vmax), armor_bar_bounds(esp_settings, vmin, vmax)]
+        .into_iter()
+        .flatten()
+        .fold(vmin.y - esp_settings.box_width / 2.0, |top, [_, y, _, _]| top.min(y));
+
+    [vmin.x - esp_settings.box_width / 2.0, top - FLASH_BAR_HEIGHT - 2.0, vmax.x - vmin.x + esp_settings.box_width, FLASH_BAR_HEIGHT]
+}
+
+/// Bounds of the health bar (x, y, width, height) relative to the 2D player box.
+fn health_bar_bounds(
+    esp_settings: &EspPlayerSettings,
+    vmin: &nalgebra::Vector2<f32>,
+    vmax: &nalgebra::Vector2<f32>,
+) -> Option<[f32; 4]> {
+    bar_bounds(esp_settings.health_bar, esp_settings.health_bar_width, 0.0, esp_settings.box_width, vmin, vmax)
+}
+
+/// Bounds of the armor bar. The armor bar is placed next to the health bar
+/// or at the left side of the box if the health bar is disabled.
+fn armor_bar_bounds(
+    esp_settings: &EspPlayerSettings,
+    vmin: &nalgebra::Vector2<f32>,
+    vmax: &nalgebra::Vector2<f32>,
+) -> Option<[f32; 4]> {
+    if !esp_settings.armor_bar {
+        return None;
+    }
+
+    let (position, offset) = match esp_settings.health_bar {
+        EspHealthBar::None => (EspHealthBar::Left, 0.0),
+        position => (position, esp_settings.health_bar_width + 1.0),
+    };
+    bar_bounds(position, esp_settings.health_bar_width, offset, esp_settings.box_width, vmin, vmax)
+}
+
+/// Alpha multiplier for a player at the given distance in meters, fading out towards the max ESP distance.
+/// None if the player is beyond the max ESP distance and should not be drawn at all.
+fn distance_fade(settings: &AppSettings, distance: f32) -> Option<f32> {
+    if settings.esp_max_distance <= 0.0 {
+        return Some(1.0);
+    }
+    if distance > settings.esp_max_distance {
+        return None;
+    }
+
+    let fade_band = settings.esp_fade_distance.min(settings.esp_max_distance);
+    if fade_band <= 0.0 {
+        return Some(1.0);
+    }
+    Some(((settings.esp_max_distance - distance) / fade_band).clamp(0.0, 1.0))
+}
+
+fn fade_color(color: [f32; 4], fade: f32) -> [f32; 4] {
+    [color[0], color[1], color[2], color[3] * fade]
+}
+
+fn tracer_origin(position: EspTracePosition, screen_bounds: &mint::Vector2<f32>) -> Option<[f32; 2]> {
+    match position {
+        EspTracePosition::TopLeft => Some([0.0, 0.0]),
+        EspTracePosition::TopCenter => Some([screen_bounds.x / 2.0, 0.0]),
+        EspTracePosition::TopRight => Some([screen_bounds.x, 0.0]),
+        EspTracePosition::Center => Some([screen_bounds.x / 2.0, screen_bounds.y / 2.0]),
+        EspTracePosition::BottomLeft => Some([0.0, screen_bounds.y]),
+        EspTracePosition::BottomCenter => Some([screen_bounds.x / 2.0, screen_bounds.y]),
+        EspTracePosition::BottomRight => Some([screen_bounds.x, screen_bounds.y]),
+        EspTracePosition::None => None,
+    }
 }
 
 impl Enhancement for PlayerESP {
+    fn render_layer(&self) -> RenderLayer {
+        RenderLayer::World
+    }
+
     fn update(&mut self, ctx: &crate::UpdateContext) -> Result<()> {
         let settings = ctx.states.resolve::<AppSettings>(())?;
         if self.toggle.update(&settings.esp_mode, ctx.input, &settings.esp_toggle) {
-            ctx.cs2.add_metrics_record(obfstr!("feature-esp-toggle"), &format!("enabled: {}, mode: {:?}", self.toggle.enabled, settings.esp_mode));
+            ctx.metrics.add_metrics_record(obfstr!("feature-esp-toggle"), &format!("enabled: {}, mode: {:?}", self.toggle.enabled, settings.esp_mode));
         }
-        if !self.toggle.enabled { self.players.clear(); return Ok(()); }
-
-        let entities = ctx.states.resolve::<StateEntityList>(())?;
-        let class_name_cache = ctx.states.resolve::<ClassNameCache>(())?;
-        let memory = ctx.states.resolve::<StateCS2Memory>(())?;
-        let local_player_controller = ctx.states.resolve::<StateLocalPlayerController>(())?;
+        ctx.states.resolve_mut::<StateActiveToggles>(())?.esp = self.toggle.enabled;
+        if !self.toggle.enabled { self.players.clear(); self.ghosts.clear(); return Ok(()); }
 
-        let Some(local_player_controller) = local_player_controller.instance.value_reference(memory.view_arc()) else { self.players.clear(); return Ok(()); };
-        self.local_team_id = local_player_controller.m_iPendingTeamNum()?;
+        /* the player list is collected by the update thread */
+        let snapshot = ctx.states.resolve::<StateGameSnapshot>(())?.0.clone();
+        let Some(local_team_id) = snapshot.local_team_id else { self.players.clear(); return Ok(()); };
+        self.local_team_id = local_team_id;
         
-        let view_target = ctx.states.resolve::<LocalCameraControllerTarget>(())?;
-        let view_target_entity_id = match &view_target.target_entity_id { Some(value) => *value, None => { self.players.clear(); return Ok(()); } };
+        let chams_occlusion = settings.esp_settings.values().any(|config| matches!(config, EspConfig::Player(config) if config.chams && config.chams_visibility));
+        if chams_occlusion { self.update_map_mesh(ctx.states); }
+
+        let view_target_entity_id = match snapshot.view_target_entity_id { Some(value) => value, None => { self.players.clear(); return Ok(()); } };
 
         let mut valid_player_handles = std::collections::HashSet::new();
 
-        for entity_identity in entities.entities() {
-            let entity_class = class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
-            if !entity_class.map(|name| *name == "CCSPlayerController").unwrap_or(false) { continue; }
-            
-            let controller_handle = entity_identity.handle::<dyn CCSPlayerController>()?;
-            let Some(controller_ptr) = entities.entity_from_handle(&controller_handle) else { continue; };
-            let Some(controller) = controller_ptr.value_reference(memory.view_arc()) else { continue; };
-            
-            let pawn_handle = controller.m_hPlayerPawn()?;
-            if !pawn_handle.is_valid() { continue; }
-            
-            let entity_index = pawn_handle.get_entity_index();
+        for (snapshot_index, player) in snapshot.players.iter().enumerate() {
+            let entity_index = player.pawn_entity_index;
             if entity_index == view_target_entity_id { continue; }
 
-            // Only validate player is alive - don't cache any state, we'll read fresh at render time
-            let pawn_state = ctx.states.resolve::<PlayerPawnState>(pawn_handle)?;
-            if *pawn_state != PlayerPawnState::Alive { continue; }
+            if !player.alive {
+                /* dead players do not leave a ghost behind */
+                self.ghosts.remove(&entity_index);
+                if let Some(entry) = self.players.get_mut(&entity_index) { entry.last_seen = None; }
+                continue;
+            }
 
             valid_player_handles.insert(entity_index);
             self.players.entry(entity_index).or_insert_with(|| PlayerData { 
                 pawn_handle: entity_index,
+                snapshot_index,
                 bone_transforms: HashMap::new(),
-            });
+                texts: PlayerTextCache::new(),
+                trail: VecDeque::new(),
+                last_seen: None,
+            }).snapshot_index = snapshot_index;
         }
-        self.players.retain(|entity_index, _| valid_player_handles.contains(entity_index));
+        self.snapshot = snapshot;
+
+        let ghosts = &mut self.ghosts;
+        self.players.retain(|entity_index, entry| {
+            if valid_player_handles.contains(entity_index) { return true; }
+            if let Some(ghost) = entry.last_seen.take() { ghosts.insert(*entity_index, ghost); }
+            false
+        });
         Ok(())
     }
 
@@ -234,12 +413,29 @@ impl Enhancement for PlayerESP {
         };
 
         let settings = states.resolve::<AppSettings>(())?;
+        let (chams_active, offscreen_arrows_active) = {
+            let toggles = states.resolve::<StateActiveToggles>(())?;
+            (toggles.feature_active(FeatureToggle::Chams), toggles.feature_active(FeatureToggle::OffscreenArrows))
+        };
         let app_resources = states.resolve::<AppResources>(()).ok();
         let memory = states.resolve::<StateCS2Memory>(())?;
         let entities = states.resolve::<StateEntityList>(())?;
+        let map_palette = if settings.esp_map_palette {
+            states
+                .resolve::<StateCurrentMap>(())
+                .ok()
+                .and_then(|map| map.current_map.as_deref().and_then(|map| settings.map_palette(map)))
+        } else {
+            None
+        };
 
-        let draw = ui.get_window_draw_list();
-        const UNITS_TO_METERS: f32 = 0.01905;
+        let needs_bones = settings.esp_settings.values().any(|config| match config {
+            EspConfig::Player(config) => config.skeleton || config.chams || config.head_dot != EspHeadDot::None || config.box_fit_bones || config.view_direction,
+            _ => false,
+        });
+        self.prefetch_player_states(states, needs_bones);
+
+        let mut draw = ui.get_window_draw_list();
         const MAX_HEAD_SIZE: f32 = 250.0;
         
         let time = self.start_time.elapsed().as_secs_f32();
@@ -254,6 +450,18 @@ impl Enhancement for PlayerESP {
             is_left: bool,
         }
         let mut best_arrow: Option<ClosestArrowState> = None;
+
+        let mut radial_arrows = std::mem::take(&mut self.radial_arrows);
+        radial_arrows.clear();
+
+        /* body parts are occluded if the map blocks the line of sight from the camera */
+        let chams_occlusion_mesh = self.map_mesh.as_deref();
+        let is_occluded = |position: &Vector3<f32>| {
+            chams_occlusion_mesh.map_or(false, |mesh| mesh.check_collision(camera_position, *position, 0.0).is_some())
+        };
+
+        /* horizontal camera axes, the first view matrix column maps world positions to the screen x axis */
+        let camera_right = Vector2::new(view.view_matrix[(0, 0)], view.view_matrix[(1, 0)]).try_normalize(1e-6);
         // -----------------------------------
 
         for (_entity_index, entry) in self.players.iter_mut() {
@@ -280,21 +488,45 @@ impl Enhancement for PlayerESP {
                 pos
             };
 
-            // We need to read player info - try fresh read if possible, otherwise skip
-            let pawn_info = match states.resolve::<StatePawnInfo>(EntityHandle::from_index(pawn_handle_index)) {
-                Ok(info) => {
-                    if info.player_health <= 0 || info.player_name.is_none() { continue; }
-                    info
-                }
-                Err(_) => { continue; }
-            };
+            /* the pawn info is read by the update thread, only the position and bones are read at render time */
+            let Some(pawn_info) = self.snapshot.players.get(entry.snapshot_index).and_then(|player| player.info.as_ref()) else { continue; };
+            if pawn_info.player_health <= 0 || pawn_info.player_name.is_none() { continue; }
+
+            if pawn_info.player_steam_id.map_or(false, |steam_id| settings.esp_ignored_players.contains_key(&steam_id)) {
+                continue;
+            }
 
             let distance = (interpolated_position - camera_position).norm() * UNITS_TO_METERS;
 
-            let esp_settings = match Self::resolve_esp_player_config(&settings, &pawn_info, self.local_team_id) {
+            let spotted = entity_identity
+                .entity_ptr::<dyn C_CSPlayerPawn>()
+                .ok()
+                .and_then(|pawn| pawn.value_reference(memory.view_arc()))
+                .and_then(|pawn| pawn.m_entitySpottedState().ok())
+                .and_then(|state| state.m_bSpotted().ok())
+                .unwrap_or(false);
+
+            let esp_settings = match Self::resolve_esp_player_config(&settings, pawn_info, self.local_team_id, spotted) {
                 Some(settings) => settings,
                 None => continue,
             };
+            let palette_settings;
+            let esp_settings = match &map_palette {
+                Some(palette) => {
+                    let enemy = pawn_info.team_id != self.local_team_id;
+                    palette_settings = esp_settings.with_base_color(enemy, palette.color(enemy));
+                    &palette_settings
+                }
+                None => esp_settings,
+            };
+            let Some(fade) = distance_fade(&settings, distance) else { continue; };
+            let faded_settings;
+            let esp_settings = if fade < 1.0 {
+                faded_settings = esp_settings.with_alpha_scale(fade);
+                &faded_settings
+            } else {
+                esp_settings
+            };
 
             let player_rel_health = (pawn_info.player_health as f32 / 100.0).clamp(0.0, 1.0);
             
@@ -305,16 +537,29 @@ impl Enhancement for PlayerESP {
             };
             
             let Ok(entry_model) = states.resolve::<CS2Model>(pawn_model_address) else { continue; };
+
+            // Only read bones if actually needed
+            let needs_bones = esp_settings.skeleton || esp_settings.chams || esp_settings.head_dot != EspHeadDot::None || esp_settings.box_fit_bones || esp_settings.view_direction;
+            let pawn_bones = if needs_bones {
+                states.resolve::<StatePawnModelInfo>(EntityHandle::from_index(pawn_handle_index)).ok()
+            } else {
+                None
+            };
             
-            let player_2d_box = view.calculate_box_2d(
-                &(entry_model.vhull_min + interpolated_position),
-                &(entry_model.vhull_max + interpolated_position),
-            );
+            let player_2d_box = match &pawn_bones {
+                Some(pawn_model) if esp_settings.box_type.is_2d() && esp_settings.box_fit_bones => {
+                    bone_fitted_box_2d(&view, &entry_model, pawn_model, &interpolated_position)
+                }
+                _ => view.calculate_box_2d(
+                    &(entry_model.vhull_min + interpolated_position),
+                    &(entry_model.vhull_max + interpolated_position),
+                ),
+            };
             
             let color_ctx = ColorContext { health: player_rel_health, distance, time };
 
             // --- OFF-SCREEN ARROWS LOGIC (CLIP SPACE METHOD) ---
-            if esp_settings.offscreen_arrows {
+            if esp_settings.offscreen_arrows && offscreen_arrows_active {
                 let vec = interpolated_position;
                 let clip = nalgebra::Vector4::new(vec.x, vec.y, vec.z, 1.0).transpose() * view.view_matrix;
                 
@@ -325,32 +570,48 @@ impl Enhancement for PlayerESP {
                 };
 
                 if is_offscreen {
-                    if best_arrow.as_ref().map_or(true, |a| distance < a.dist) {
-                        let is_left = clip.x < 0.0; 
-                        let color = esp_settings.offscreen_arrows_color.calculate_color(player_rel_health, distance, time, 0.0);
-                        
-                        best_arrow = Some(ClosestArrowState {
-                            dist: distance,
-                            color,
-                            radius: esp_settings.offscreen_arrows_radius,
-                            size: esp_settings.offscreen_arrows_size,
-                            is_left,
-                        });
+                    match esp_settings.offscreen_arrows_mode {
+                        EspOffscreenArrowMode::Side => {
+                            if best_arrow.as_ref().map_or(true, |a| distance < a.dist) {
+                                let is_left = clip.x < 0.0; 
+                                let color = esp_settings.offscreen_arrows_color.calculate_color(player_rel_health, distance, time, 0.0);
+                                
+                                best_arrow = Some(ClosestArrowState {
+                                    dist: distance,
+                                    color,
+                                    radius: esp_settings.offscreen_arrows_radius,
+                                    size: esp_settings.offscreen_arrows_size,
+                                    is_left,
+                                });
+                            }
+                        }
+                        EspOffscreenArrowMode::Radial => {
+                            if let Some(camera_right) = camera_right {
+                                /* screen up is the camera forward direction */
+                                let camera_forward = Vector2::new(-camera_right.y, camera_right.x);
+                                let delta = (interpolated_position - camera_position).xy();
+                                let direction = Vector2::new(delta.dot(&camera_right), -delta.dot(&camera_forward));
+
+                                if let Some(direction) = direction.try_normalize(1e-6) {
+                                    radial_arrows.push(RadialArrowState {
+                                        direction,
+                                        dist: distance,
+                                        color: esp_settings.offscreen_arrows_color.calculate_color(player_rel_health, distance, time, 0.0),
+                                        radius: esp_settings.offscreen_arrows_radius,
+                                        size: esp_settings.offscreen_arrows_size,
+                                        ellipse: esp_settings.offscreen_arrows_ellipse,
+                                        show_distance: esp_settings.offscreen_arrows_distance,
+                                    });
+                                }
+                            }
+                        }
                     }
                 }
             }
             // ---------------------------------------------------
 
             // --- MODEL RENDERING START ---
-            // Only read bones if actually needed
-            let needs_bones = esp_settings.skeleton || esp_settings.chams || esp_settings.head_dot != EspHeadDot::None;
-            let pawn_bones = if needs_bones {
-                states.resolve::<StatePawnModelInfo>(EntityHandle::from_index(pawn_handle_index)).ok()
-            } else {
-                None
-            };
-
-            if esp_settings.chams {
+            if esp_settings.chams && chams_active {
                 if let Some(pawn_model) = &pawn_bones {
                     const MODEL_NAME: &str = "character.glb";
                     if !self.models.contains_key(MODEL_NAME) {
@@ -371,17 +632,18 @@ impl Enhancement for PlayerESP {
                             else { entry.bone_transforms.insert(bone.name.clone(), transform); }
                         }
                         let col_arr = esp_settings.chams_color.calculate_color(player_rel_health, distance, time, 0.0);
-                        model.render(&draw, &view, &entry.bone_transforms, col_arr);
+                        let occluded_color = esp_settings.chams_occluded_color.calculate_color(player_rel_health, distance, time, 0.0);
+                        let occlusion = chams_occlusion_mesh.filter(|_| esp_settings.chams_visibility).map(|_| (&is_occluded as &dyn Fn(&Vector3<f32>) -> bool, occluded_color));
+                        model.render_with_occlusion(&draw, &view, &entry.bone_transforms, col_arr, occlusion);
                     } else {
-                        let bones = entry_model.bones.iter().zip(pawn_model.bone_states.iter());
-                        for (bone, state) in bones {
-                            if (bone.flags & BoneFlags::FlagHitbox as u32) == 0 { continue; }
-                            let parent_index = if let Some(parent) = bone.parent { parent } else { continue; };
-                            let parent_world_pos = pawn_model.bone_states[parent_index].position;
+                        for &(parent_index, bone_index) in &entry_model.skeleton {
+                            let (Some(bone), Some(parent_state), Some(state)) = (entry_model.bones.get(bone_index), pawn_model.bone_states.get(parent_index), pawn_model.bone_states.get(bone_index)) else { continue; };
+                            let parent_world_pos = parent_state.position;
                             let bone_world_pos = state.position;
                             if let (Some(parent_pos), Some(bone_pos)) = (view.world_to_screen(&parent_world_pos, true), view.world_to_screen(&bone_world_pos, true)) {
                                 let t_bone = (bone_world_pos.z - interpolated_position.z) / 72.0;
-                                let col_arr = esp_settings.chams_color.calculate_color(player_rel_health, distance, time, t_bone);
+                                let chams_color = if esp_settings.chams_visibility && is_occluded(&bone_world_pos) { &esp_settings.chams_occluded_color } else { &esp_settings.chams_color };
+                                let col_arr = chams_color.calculate_color(player_rel_health, distance, time, t_bone);
                                 let thickness_scale = 600.0; 
                                 let dist_clamped = distance.max(0.1);
                                 let thickness_mult = if bone.name.contains("spine") || bone.name.contains("pelvis") { 0.35 } else if bone.name.contains("head") { 0.30 } else { 0.15 };
@@ -394,11 +656,9 @@ impl Enhancement for PlayerESP {
 
             if esp_settings.skeleton {
                 if let Some(pawn_model) = &pawn_bones {
-                    let bones = entry_model.bones.iter().zip(pawn_model.bone_states.iter());
-                    for (bone, state) in bones {
-                        if (bone.flags & BoneFlags::FlagHitbox as u32) == 0 { continue; }
-                        let parent_index = if let Some(parent) = bone.parent { parent } else { continue; };
-                        let parent_world_pos = pawn_model.bone_states[parent_index].position;
+                    for &(parent_index, bone_index) in &entry_model.skeleton {
+                        let (Some(parent_state), Some(state)) = (pawn_model.bone_states.get(parent_index), pawn_model.bone_states.get(bone_index)) else { continue; };
+                        let parent_world_pos = parent_state.position;
                         let bone_world_pos = state.position;
                         if let (Some(parent_pos), Some(bone_pos)) = (view.world_to_screen(&parent_world_pos, true), view.world_to_screen(&bone_world_pos, true)) {
                             let t_bone = (bone_world_pos.z - interpolated_position.z) / 72.0;
@@ -409,6 +669,80 @@ impl Enhancement for PlayerESP {
                 }
             }
 
+            if esp_settings.ghost_marker {
+                if spotted {
+                    self.ghosts.remove(&pawn_handle_index);
+
+                    let marker = entry.last_seen.get_or_insert_with(GhostMarker::default);
+                    marker.position = interpolated_position;
+                    marker.seen_at = time;
+                    marker.duration = esp_settings.ghost_marker_duration;
+                    marker.color = esp_settings.ghost_marker_color.calculate_color(player_rel_health, distance, time, 0.0);
+                    if let Some(name) = pawn_info.player_name.as_deref() {
+                        let name = entry.texts.player_name(states, settings.clip_safe_mode, name);
+                        if marker.name != name {
+                            marker.name.clear();
+                            marker.name.push_str(name);
+                        }
+                    }
+                } else if let Some(marker) = entry.last_seen.take() {
+                    self.ghosts.insert(pawn_handle_index, marker);
+                }
+            } else {
+                entry.last_seen = None;
+            }
+
+            if esp_settings.trail {
+                let trail_duration = esp_settings.trail_duration.max(TRAIL_SAMPLE_INTERVAL);
+                while entry.trail.front().map_or(false, |(recorded, _)| time - recorded > trail_duration) {
+                    entry.trail.pop_front();
+                }
+
+                let sample_due = entry.trail.back().map_or(true, |(recorded, _)| time - recorded >= TRAIL_SAMPLE_INTERVAL);
+                if sample_due {
+                    entry.trail.push_back((time, interpolated_position));
+                }
+
+                let base_color = esp_settings.trail_color.calculate_color(player_rel_health, distance, time, 0.0);
+                let mut previous: Option<mint::Vector2<f32>> = None;
+                for (recorded, position) in entry.trail.iter().chain(std::iter::once(&(time, interpolated_position))) {
+                    let Some(screen_position) = view.world_to_screen(position, true) else {
+                        previous = None;
+                        continue;
+                    };
+
+                    if let Some(previous) = previous {
+                        let fade = (1.0 - (time - recorded) / trail_duration).clamp(0.0, 1.0);
+                        let color = [base_color[0], base_color[1], base_color[2], base_color[3] * fade];
+                        draw.add_line([previous.x, previous.y], [screen_position.x, screen_position.y], color).thickness(esp_settings.trail_width).build();
+                    }
+                    previous = Some(screen_position);
+                }
+            } else if !entry.trail.is_empty() {
+                entry.trail.clear();
+            }
+
+            if esp_settings.view_direction {
+                /* fall back to the standing eye height if the head bone is not available */
+                let eye_position = pawn_bones
+                    .as_ref()
+                    .zip(entry_model.bones.iter().position(|bone| bone.name == "head_0"))
+                    .and_then(|(pawn_model, head_bone_index)| pawn_model.bone_states.get(head_bone_index))
+                    .map(|head_state| head_state.position)
+                    .unwrap_or(interpolated_position + Vector3::new(0.0, 0.0, VIEW_DIRECTION_EYE_HEIGHT));
+
+                let pitch = pawn_info.eye_pitch.to_radians();
+                let yaw = pawn_info.rotation.to_radians();
+                let direction = Vector3::new(pitch.cos() * yaw.cos(), pitch.cos() * yaw.sin(), -pitch.sin());
+                let view_end = eye_position + direction * esp_settings.view_direction_length;
+
+                if let (Some(start), Some(end)) = (view.world_to_screen(&eye_position, true), view.world_to_screen(&view_end, true)) {
+                    let color = esp_settings.view_direction_color.calculate_color(player_rel_health, distance, time, 0.0);
+                    draw.add_line([start.x, start.y], [end.x, end.y], color).thickness(esp_settings.view_direction_width).build();
+                    draw.add_circle([end.x, end.y], esp_settings.view_direction_width + 1.5, color).filled(true).build();
+                }
+            }
+
             if esp_settings.head_dot != EspHeadDot::None {
                 if let Some(pawn_model) = &pawn_bones {
                     if let Some(head_bone_index) = entry_model.bones.iter().position(|bone| bone.name == "head_0") {
@@ -433,18 +767,22 @@ impl Enhancement for PlayerESP {
             }
 
             match esp_settings.box_type {
-                EspBoxType::Box2D => {
+                EspBoxType::Corner => {
                     if let Some((vmin, vmax)) = &player_2d_box {
-                        if let EspColor::GradientVertical { top, bottom } = esp_settings.box_color {
-                            let c_top = top.as_f32(); let c_bot = bottom.as_f32();
-                             draw.add_rect_filled_multicolor([vmin.x - esp_settings.box_width/2.0, vmin.y], [vmin.x + esp_settings.box_width/2.0, vmax.y], c_top, c_top, c_bot, c_bot);
-                             draw.add_rect_filled_multicolor([vmax.x - esp_settings.box_width/2.0, vmin.y], [vmax.x + esp_settings.box_width/2.0, vmax.y], c_top, c_top, c_bot, c_bot);
-                             draw.add_rect([vmin.x, vmin.y], [vmax.x, vmin.y + esp_settings.box_width], c_top).filled(true).build();
-                             draw.add_rect([vmin.x, vmax.y - esp_settings.box_width], [vmax.x, vmax.y], c_bot).filled(true).build();
-                        } else {
-                            let col = esp_settings.box_color.calculate_color(player_rel_health, distance, time, 0.0);
-                            draw.add_rect([vmin.x, vmin.y], [vmax.x, vmax.y], col).thickness(esp_settings.box_width).build();
+                        let top = esp_settings.box_color.calculate_color(player_rel_health, distance, time, 1.0);
+                        let bottom = esp_settings.box_color.calculate_color(player_rel_health, distance, time, 0.0);
+                        draw_box_corners(&draw, [vmin.x, vmin.y], [vmax.x, vmax.y], top, bottom, esp_settings.box_width, esp_settings.box_corner_length);
+                    }
+                }
+                EspBoxType::Box2D | EspBoxType::Filled => {
+                    if let Some((vmin, vmax)) = &player_2d_box {
+                        if esp_settings.box_type == EspBoxType::Filled {
+                            let top = esp_settings.box_color.calculate_color(player_rel_health, distance, time, 1.0);
+                            let bottom = esp_settings.box_color.calculate_color(player_rel_health, distance, time, 0.0);
+                            draw_box_fill(&draw, [vmin.x, vmin.y], [vmax.x, vmax.y], top, bottom, esp_settings.box_fill_alpha);
                         }
+
+                        draw_box_2d(&mut draw, esp_settings, vmin, vmax, &color_ctx);
                     }
                 }
                 EspBoxType::Box3D => {
@@ -454,58 +792,99 @@ impl Enhancement for PlayerESP {
             }
 
             if let Some((vmin, vmax)) = &player_2d_box {
-                let box_bounds = match esp_settings.health_bar {
-                    EspHealthBar::None => None,
-                    EspHealthBar::Left => Some([vmin.x - esp_settings.box_width / 2.0 - esp_settings.health_bar_width, vmin.y - esp_settings.box_width / 2.0, esp_settings.health_bar_width, vmax.y - vmin.y + esp_settings.box_width]),
-                    EspHealthBar::Right => Some([vmax.x + esp_settings.box_width / 2.0, vmin.y - esp_settings.box_width / 2.0, esp_settings.health_bar_width, vmax.y - vmin.y + esp_settings.box_width]),
-                    EspHealthBar::Top => Some([vmin.x - esp_settings.box_width / 2.0, vmin.y - esp_settings.box_width / 2.0 - esp_settings.health_bar_width, vmax.x - vmin.x + esp_settings.box_width, esp_settings.health_bar_width]),
-                    EspHealthBar::Bottom => Some([vmin.x - esp_settings.box_width / 2.0, vmax.y + esp_settings.box_width / 2.0, vmax.x - vmin.x + esp_settings.box_width, esp_settings.health_bar_width]),
-                };
-
-                if let Some([mut box_x, mut box_y, mut box_width, mut box_height]) = box_bounds {
-                    const BORDER_WIDTH: f32 = 1.0;
-                    draw.add_rect([box_x + BORDER_WIDTH / 2.0, box_y + BORDER_WIDTH / 2.0], [box_x + box_width - BORDER_WIDTH / 2.0, box_y + box_height - BORDER_WIDTH / 2.0], [0.0, 0.0, 0.0, 1.0]).filled(false).thickness(BORDER_WIDTH).build();
-                    box_x += BORDER_WIDTH / 2.0 + 1.0; box_y += BORDER_WIDTH / 2.0 + 1.0; box_width -= BORDER_WIDTH + 2.0; box_height -= BORDER_WIDTH + 2.0;
-                    if box_width < box_height {
-                        let yoffset = box_y + (1.0 - player_rel_health) * box_height;
-                        draw.add_rect([box_x, box_y], [box_x + box_width, yoffset], [1.0, 0.0, 0.0, 1.0]).filled(true).build();
-                        draw.add_rect([box_x, yoffset], [box_x + box_width, box_y + box_height], esp_settings.info_hp_text_color.calculate_color(player_rel_health, distance, time, 0.5)).filled(true).build();
-                    } else {
-                        let xoffset = box_x + (1.0 - player_rel_health) * box_width;
-                        draw.add_rect([box_x, box_y], [xoffset, box_y + box_height], [1.0, 0.0, 0.0, 1.0]).filled(true).build();
-                        draw.add_rect([xoffset, box_y], [box_x + box_width, box_y + box_height], esp_settings.info_hp_text_color.calculate_color(player_rel_health, distance, time, 0.5)).filled(true).build();
+                let player_rel_armor = (pawn_info.player_armor as f32 / 100.0).clamp(0.0, 1.0);
+                draw_armor_bar(&mut draw, esp_settings, vmin, vmax, player_rel_armor, &color_ctx, fade);
+                draw_health_bar(&mut draw, esp_settings, vmin, vmax, &color_ctx, fade);
+
+                if esp_settings.flash_bar && pawn_info.player_flash_remaining > 0.0 && pawn_info.player_flash_duration > 0.0 {
+                    let [box_x, box_y, box_width, box_height] = flash_bar_bounds(esp_settings, vmin, vmax);
+                    let flash_rel_remaining = (pawn_info.player_flash_remaining / pawn_info.player_flash_duration).clamp(0.0, 1.0);
+                    let fully_blind = pawn_info.player_flash_max_alpha >= FLASH_FULL_ALPHA && pawn_info.player_flash_remaining > FLASH_FADE_DURATION;
+                    let flash_color = if esp_settings.flash_bar_highlight_partial && !fully_blind { &esp_settings.flash_bar_partial_color } else { &esp_settings.flash_bar_color };
+                    let flash_color = flash_color.calculate_color(player_rel_health, distance, time, 0.5);
+
+                    draw.add_rect([box_x - 1.0, box_y - 1.0], [box_x + box_width + 1.0, box_y + box_height + 1.0], fade_color([0.0, 0.0, 0.0, 1.0], fade)).filled(true).build();
+                    draw.add_rect([box_x, box_y], [box_x + box_width * flash_rel_remaining, box_y + box_height], flash_color).filled(true).build();
+
+                    if esp_settings.flash_bar_countdown {
+                        let text = entry.texts.flash_remaining(pawn_info.player_flash_remaining);
+                        let [text_width, text_height] = ui.calc_text_size(text);
+                        draw.add_styled_text([box_x + (box_width - text_width) / 2.0, box_y - text_height - 1.0], flash_color, &esp_settings.text_style.text_style(), text);
                     }
                 }
             }
 
             if let Some((vmin, vmax)) = player_2d_box {
-                let mut layout_right = PlayerInfoLayout::new(ui, &draw, view.screen_bounds, vmin, vmax, esp_settings.box_type == EspBoxType::Box2D, LayoutAlignment::Right, esp_settings.text_style);
-                let mut layout_bottom = PlayerInfoLayout::new(ui, &draw, view.screen_bounds, vmin, vmax, esp_settings.box_type == EspBoxType::Box2D, LayoutAlignment::Bottom, esp_settings.text_style);
+                let mut layout_right = PlayerInfoLayout::new(ui, &draw, view.screen_bounds, vmin, vmax, esp_settings.box_type.is_2d(), LayoutAlignment::Right, esp_settings.text_style, esp_settings.text_scale);
+                let mut layout_bottom = PlayerInfoLayout::new(ui, &draw, view.screen_bounds, vmin, vmax, esp_settings.box_type.is_2d(), LayoutAlignment::Bottom, esp_settings.text_style, esp_settings.text_scale);
 
-                if esp_settings.info_hp_text { layout_right.add_line(&esp_settings.info_hp_text_color, &color_ctx, &format!("{} HP", pawn_info.player_health)); }
+                let texts = &mut entry.texts;
+                if esp_settings.info_hp_text { layout_right.add_line(&esp_settings.info_hp_text_color, &color_ctx, texts.health(pawn_info.player_health)); }
+                if esp_settings.info_flag_armor && pawn_info.player_armor > 0 {
+                    layout_right.add_line(&esp_settings.info_flag_armor_color, &color_ctx, texts.armor(pawn_info.player_health, pawn_info.player_has_helmet));
+                }
                 if esp_settings.info_flag_kit && pawn_info.player_has_defuser { layout_right.add_line(&esp_settings.info_flag_kit_color, &color_ctx, "Kit"); }
                 if esp_settings.info_flag_bomb && pawn_info.player_has_bomb { layout_right.add_line(&esp_settings.info_flag_bomb_color, &color_ctx, "Bomb Carrier"); }
                 if esp_settings.info_flag_scoped && pawn_info.player_is_scoped { layout_right.add_line(&esp_settings.info_flag_scoped_color, &color_ctx, "Scoped"); }
                 if esp_settings.info_flag_flashed && pawn_info.player_flashtime > 0.0 { layout_right.add_line(&esp_settings.info_flag_flashed_color, &color_ctx, "Flashed"); }
                 
-                let mut player_utilities = Vec::new();
                 if esp_settings.info_grenades {
-                    if pawn_info.player_has_flash > 0 { player_utilities.push(format!("Flashbang x{}", pawn_info.player_has_flash)); }
-                    if pawn_info.player_has_smoke { player_utilities.push("Smoke".to_string()); }
-                    if pawn_info.player_has_hegrenade { player_utilities.push("HE Grenade".to_string()); }
-                    if pawn_info.player_has_molotov { player_utilities.push("Molotov".to_string()); }
-                    if pawn_info.player_has_incendiary { player_utilities.push("Incendiary".to_string()); }
-                    if pawn_info.player_has_decoy { player_utilities.push("Decoy".to_string()); }
-                    if !player_utilities.is_empty() { layout_right.add_line(&esp_settings.info_grenades_color, &color_ctx, &player_utilities.join(", ")); }
+                    let player_utilities = texts.utilities(pawn_info);
+                    if !player_utilities.is_empty() { layout_right.add_line(&esp_settings.info_grenades_color, &color_ctx, player_utilities); }
                 }
 
+                if esp_settings.info_money {
+                    if let Some(money) = pawn_info.player_money { layout_right.add_line(&esp_settings.info_money_color, &color_ctx, texts.money(money)); }
+                }
+                if esp_settings.info_buy_type { layout_right.add_line(&esp_settings.info_buy_type_color, &color_ctx, BuyType::from_equipment_value(pawn_info.player_equipment_value).display_name()); }
+
                 if esp_settings.info_name {
-                    layout_bottom.add_line(&esp_settings.info_name_color, &color_ctx, pawn_info.player_name.as_ref().map_or("unknown", String::as_str));
-                    if let Some(player_name) = &pawn_info.player_name { unicode_text.register_unicode_text(player_name); }
+                    let player_name = match pawn_info.player_name.as_deref() {
+                        Some(name) => texts.player_name(states, settings.clip_safe_mode, name),
+                        None => "unknown",
+                    };
+                    layout_bottom.add_line(&esp_settings.info_name_color, &color_ctx, player_name);
+                    unicode_text.register_unicode_text(player_name);
+                }
+
+                if esp_settings.info_avatar {
+                    if let Some(steam_id) = pawn_info.player_steam_id {
+                        with_steam_profile(states, steam_id, |profile| {
+                            if let Some(avatar) = profile.avatar { layout_bottom.add_image(avatar, &ESP_AVATAR_COLOR, &color_ctx, 24.0, 1.0); }
+                            layout_bottom.add_line(&esp_settings.info_name_color, &color_ctx, &profile.persona_name);
+                            unicode_text.register_unicode_text(&profile.persona_name);
+                        });
+                    }
+                }
+
+                if esp_settings.info_rank {
+                    let rank = pawn_info.controller_entity_id.and_then(|controller_id| states.resolve::<StatePlayerRank>(controller_id).ok());
+                    if let Some(rank) = rank.as_ref().filter(|rank| rank.is_ranked()) {
+                        let rank_icon = match esp_settings.info_rank_style {
+                            EspInfoStyle::Icon => app_resources.as_ref().and_then(|resources| resources.rank_icons.get(texts.rank_icon(rank)).copied()),
+                            EspInfoStyle::Text => None,
+                        };
+
+                        if let Some((tex_id, (width, height))) = rank_icon {
+                            layout_bottom.add_image(tex_id, &esp_settings.info_rank_color, &color_ctx, 20.0, width as f32 / height.max(1) as f32);
+                        } else {
+                            let rank_name = texts.rank_name(rank);
+                            if !rank_name.is_empty() { layout_bottom.add_line(&esp_settings.info_rank_color, &color_ctx, rank_name); }
+                        }
+                    }
                 }
 
-                if esp_settings.info_ammo && pawn_info.weapon_current_ammo != -1 { layout_bottom.add_line(&esp_settings.info_ammo_color, &color_ctx, &format!("{}/{}", pawn_info.weapon_current_ammo, pawn_info.weapon_reserve_ammo)); }
-                if esp_settings.info_distance { layout_bottom.add_line(&esp_settings.info_distance_color, &color_ctx, &format!("{:.0}m", distance)); }
+                if esp_settings.info_weapon_state {
+                    let label = match pawn_info.weapon_state {
+                        WeaponState::Reloading => Some("RELOADING"),
+                        WeaponState::Switching => Some("SWITCHING"),
+                        WeaponState::Empty => Some("EMPTY"),
+                        WeaponState::Ready => None,
+                    };
+                    if let Some(label) = label { layout_bottom.add_line(&esp_settings.info_weapon_state_color, &color_ctx, label); }
+                }
+                if esp_settings.info_ammo && pawn_info.weapon_current_ammo != -1 { layout_bottom.add_line(&esp_settings.info_ammo_color, &color_ctx, texts.ammo(pawn_info.weapon_current_ammo, pawn_info.weapon_reserve_ammo)); }
+                if esp_settings.info_distance { layout_bottom.add_line(&esp_settings.info_distance_color, &color_ctx, texts.distance(distance)); }
                 
                 if esp_settings.info_weapon {
                     match esp_settings.info_weapon_style {
@@ -515,11 +894,9 @@ impl Enhancement for PlayerESP {
                         EspInfoStyle::Icon => {
                             let mut icon_drawn = false;
                             if let Some(resources) = &app_resources {
-                                let icon_key = map_weapon_to_icon(pawn_info.weapon);
-                                if let Some(tex_id) = resources.weapon_icons.get(icon_key) {
-                                    let aspect_ratio = get_weapon_icon_aspect_ratio(icon_key);
-                                    let scale = get_weapon_icon_scale(icon_key);
-                                    layout_bottom.add_image(*tex_id, &esp_settings.info_weapon_color, &color_ctx, 31.5 * scale, aspect_ratio);
+                                if let Some(icon) = resources.weapon_icons.weapon(pawn_info.weapon) {
+                                    let scale = get_weapon_icon_scale(pawn_info.weapon.icon_name());
+                                    layout_bottom.add_image_region(icon.texture_id, icon.uv_min, icon.uv_max, &esp_settings.info_weapon_color, &color_ctx, 31.5 * scale, icon.aspect_ratio);
                                     icon_drawn = true;
                                 }
                             }
@@ -530,19 +907,7 @@ impl Enhancement for PlayerESP {
             }
 
             if let Some(pos) = view.world_to_screen(&interpolated_position, false) {
-                let tracer_origin = match esp_settings.tracer_lines {
-                    EspTracePosition::TopLeft => Some([0.0, 0.0]),
-                    EspTracePosition::TopCenter => Some([view.screen_bounds.x / 2.0, 0.0]),
-                    EspTracePosition::TopRight => Some([view.screen_bounds.x, 0.0]),
-                    EspTracePosition::Center => Some([view.screen_bounds.x / 2.0, view.screen_bounds.y / 2.0]),
-                    EspTracePosition::BottomLeft => Some([0.0, view.screen_bounds.y]),
-                    EspTracePosition::BottomCenter => Some([view.screen_bounds.x / 2.0, view.screen_bounds.y]),
-                    EspTracePosition::BottomRight => Some([view.screen_bounds.x, view.screen_bounds.y]),
-                    EspTracePosition::None => None,
-                };
-                if let Some(origin) = tracer_origin {
-                    draw.add_line([origin[0], origin[1]], [pos.x, pos.y], esp_settings.tracer_lines_color.calculate_color(player_rel_health, distance, time, 0.0)).thickness(esp_settings.tracer_lines_width).build();
-                }
+                draw_tracer(&mut draw, esp_settings, &view.screen_bounds, [pos.x, pos.y], &color_ctx);
             }
         }
 
@@ -574,6 +939,56 @@ impl Enhancement for PlayerESP {
             }
         }
 
+        // --- DRAW RADIAL OFFSCREEN ARROWS ---
+        for arrow in radial_arrows.iter() {
+            let radius = if arrow.ellipse {
+                Vector2::new(arrow.radius * view.screen_bounds.x / view.screen_bounds.y.max(1.0), arrow.radius)
+            } else {
+                Vector2::new(arrow.radius, arrow.radius)
+            };
+
+            let position = Vector2::new(screen_center[0], screen_center[1]) + arrow.direction.component_mul(&radius);
+            let normal = Vector2::new(-arrow.direction.y, arrow.direction.x);
+
+            let tip = position + arrow.direction * arrow.size;
+            let base = position - arrow.direction * (arrow.size * 0.5);
+            let p1 = [tip.x, tip.y];
+            let p2 = [base.x + normal.x * arrow.size * 0.7, base.y + normal.y * arrow.size * 0.7];
+            let p3 = [base.x - normal.x * arrow.size * 0.7, base.y - normal.y * arrow.size * 0.7];
+
+            draw.add_triangle(p1, p2, p3, arrow.color).filled(true).build();
+            draw.add_triangle(p1, p2, p3, [0.0, 0.0, 0.0, 1.0]).thickness(1.0).build();
+
+            if arrow.show_distance {
+                self.label_buffer.clear();
+                let _ = write!(self.label_buffer, "{:.0}m", arrow.dist);
+                let [text_width, text_height] = ui.calc_text_size(&self.label_buffer);
+                let label = position - arrow.direction * (arrow.size + text_width.max(text_height) * 0.5);
+                draw.add_text([label.x - text_width / 2.0, label.y - text_height / 2.0], arrow.color, &self.label_buffer);
+            }
+        }
+        self.radial_arrows = radial_arrows;
+
+        // --- DRAW GHOST MARKERS ---
+        self.ghosts.retain(|_, ghost| time - ghost.seen_at < ghost.duration);
+        for ghost in self.ghosts.values() {
+            let Some(position) = view.world_to_screen(&ghost.position, false) else { continue; };
+
+            let elapsed = time - ghost.seen_at;
+            let fade = (1.0 - elapsed / ghost.duration.max(0.1)).clamp(0.0, 1.0);
+            let color = [ghost.color[0], ghost.color[1], ghost.color[2], ghost.color[3] * fade];
+
+            draw.add_circle([position.x, position.y], 6.0, color).thickness(2.0).build();
+            draw.add_line([position.x - 4.0, position.y - 4.0], [position.x + 4.0, position.y + 4.0], color).thickness(2.0).build();
+            draw.add_line([position.x - 4.0, position.y + 4.0], [position.x + 4.0, position.y - 4.0], color).thickness(2.0).build();
+
+            self.label_buffer.clear();
+            let _ = write!(self.label_buffer, "{} ({:.0}s ago)", ghost.name, elapsed);
+            unicode_text.register_unicode_text(&self.label_buffer);
+            let [text_width, text_height] = ui.calc_text_size(&self.label_buffer);
+            draw.add_text([position.x - text_width / 2.0, position.y - 10.0 - text_height], color, &self.label_buffer);
+        }
+
         Ok(())
     }
 }
\ No newline at end of file
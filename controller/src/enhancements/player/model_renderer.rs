@@ -210,6 +210,19 @@ impl CharacterModel {
         view: &ViewController,
         bone_transforms: &HashMap<String, Matrix4<f32>>,
         color: [f32; 4],
+    ) -> Option<([f32; 2], [f32; 2])> {
+        self.render_with_occlusion(draw, view, bone_transforms, color, None)
+    }
+
+    /// Render the model with the parts driven by an occluded bone in a separate color.
+    /// The occlusion callback receives the world position of each bone.
+    pub fn render_with_occlusion(
+        &self,
+        draw: &imgui::DrawListMut,
+        view: &ViewController,
+        bone_transforms: &HashMap<String, Matrix4<f32>>,
+        color: [f32; 4],
+        occlusion: Option<(&dyn Fn(&Vector3<f32>) -> bool, [f32; 4])>,
     ) -> Option<([f32; 2], [f32; 2])> {
         // 0. Pre-calculate Joint Matrices (Optimization: Move matrix mul out of vertex loop)
         // joint_matrices[i] = bone_transform * inverse_bind_matrix
@@ -238,6 +251,18 @@ impl CharacterModel {
             }
         }
 
+        // Occlusion of each joint, tested once per bone instead of per triangle
+        let mut joint_occluded = vec![false; joint_matrices.len()];
+        if let Some((is_occluded, _)) = &occlusion {
+            for (joint_idx, bone_name) in &self.mesh.joint_map {
+                let Some(bone_transform) = bone_transforms.get(bone_name) else { continue; };
+                let bone_position = Vector3::new(bone_transform[(0, 3)], bone_transform[(1, 3)], bone_transform[(2, 3)]);
+                if let Some(occluded) = joint_occluded.get_mut(*joint_idx) {
+                    *occluded = is_occluded(&bone_position);
+                }
+            }
+        }
+
         let mut transformed_vertices = Vec::with_capacity(self.mesh.vertices.len());
         
         // 1. Skinning (Vertex Transformation)
@@ -266,6 +291,7 @@ impl CharacterModel {
             p2: [f32; 2],
             z: f32,
             shade: f32,
+            occluded: bool,
         }
 
         let mut triangles = Vec::with_capacity(self.mesh.indices.len() / 3);
@@ -302,6 +328,14 @@ impl CharacterModel {
                 continue;
             }
 
+            // The joint with the highest weight of the first vertex decides the triangle occlusion
+            let vertex = &self.mesh.vertices[i0];
+            let dominant_joint = (0..4)
+                .max_by(|a, b| vertex.weights[*a].partial_cmp(&vertex.weights[*b]).unwrap_or(std::cmp::Ordering::Equal))
+                .map(|index| vertex.joints[index] as usize)
+                .unwrap_or(0);
+            let occluded = joint_occluded.get(dominant_joint).copied().unwrap_or(false);
+
             // Lighting calculation (Lambertian)
             // Range -1 to 1. Map to 0.3 to 1.0
             let intensity = normal.dot(&light_dir).max(0.0) * 0.7 + 0.3;
@@ -325,6 +359,7 @@ impl CharacterModel {
                     p2: [s2.x, s2.y],
                     z: dist,
                     shade: intensity,
+                    occluded,
                 });
             }
         }
@@ -334,7 +369,10 @@ impl CharacterModel {
 
         // 3. Draw
         for t in triangles {
-            let mut shaded_color = color;
+            let mut shaded_color = match &occlusion {
+                Some((_, occluded_color)) if t.occluded => *occluded_color,
+                _ => color,
+            };
             shaded_color[0] *= t.shade;
             shaded_color[1] *= t.shade;
             shaded_color[2] *= t.shade;
@@ -0,0 +1,359 @@
+//! Offscreen rendering of recorded ESP snapshots for layout/projection regression tests.
+//!
+//! A snapshot contains the view matrix and the player hulls of a single frame.
+//! It gets rendered into a pixmap using the same projection and draw functions
+//! as `PlayerESP::render` and compared against a golden image stored per backend
+//! in `controller/tests/golden/<backend>/`.
+//! Set `UPDATE_GOLDEN=1` to (re)write the golden images.
+
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use anyhow::{
+    anyhow,
+    Context,
+    Result,
+};
+use nalgebra::{
+    Matrix4,
+    Vector3,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use tiny_skia::{
+    Color,
+    FillRule,
+    GradientStop,
+    LinearGradient,
+    Paint,
+    PathBuilder,
+    Pixmap,
+    Point,
+    Rect,
+    SpreadMode,
+    Stroke,
+    Transform,
+};
+
+use super::{
+    draw::{
+        draw_box_2d,
+        draw_health_bar,
+        draw_tracer,
+        EspCanvas,
+    },
+    info_layout::ColorContext,
+};
+use crate::{
+    settings::{
+        EspBoxType,
+        EspPlayerSettings,
+    },
+    view::ViewController,
+};
+
+/// Name of the offscreen backend. Golden images are stored per backend
+/// as anti aliasing and blending differ between rasterizers.
+const BACKEND: &str = "tiny-skia";
+
+/// Max per channel difference for a pixel to be considered equal
+const PIXEL_TOLERANCE: u8 = 8;
+
+/// Max ratio of differing pixels before the comparison fails
+const MAX_DIFF_RATIO: f32 = 0.001;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EspSnapshotPlayer {
+    pub position: [f32; 3],
+    pub hull_min: [f32; 3],
+    pub hull_max: [f32; 3],
+    pub health: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EspSnapshot {
+    /// View matrix in row major order
+    pub view_matrix: [[f32; 4]; 4],
+    pub screen_bounds: [f32; 2],
+    pub players: Vec<EspSnapshotPlayer>,
+}
+
+impl EspSnapshot {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("read snapshot {}", path.display()))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn view_controller(&self) -> ViewController {
+        ViewController {
+            view_matrix: Matrix4::from_fn(|row, column| self.view_matrix[row][column]),
+            screen_bounds: mint::Vector2 {
+                x: self.screen_bounds[0],
+                y: self.screen_bounds[1],
+            },
+            offset: mint::Vector2 { x: 0.0, y: 0.0 },
+        }
+    }
+}
+
+fn create_paint(color: [f32; 4]) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(create_color(color));
+    paint
+}
+
+fn create_color(color: [f32; 4]) -> Color {
+    Color::from_rgba(
+        color[0].clamp(0.0, 1.0),
+        color[1].clamp(0.0, 1.0),
+        color[2].clamp(0.0, 1.0),
+        color[3].clamp(0.0, 1.0),
+    )
+    .unwrap_or(Color::TRANSPARENT)
+}
+
+impl EspCanvas for Pixmap {
+    fn stroke_rect(&mut self, min: [f32; 2], max: [f32; 2], color: [f32; 4], thickness: f32) {
+        let Some(rect) = Rect::from_ltrb(min[0], min[1], max[0], max[1]) else {
+            return;
+        };
+
+        let path = PathBuilder::from_rect(rect);
+        let stroke = Stroke {
+            width: thickness,
+            ..Default::default()
+        };
+        self.stroke_path(&path, &create_paint(color), &stroke, Transform::identity(), None);
+    }
+
+    fn fill_rect(&mut self, min: [f32; 2], max: [f32; 2], color: [f32; 4]) {
+        if let Some(rect) = Rect::from_ltrb(min[0], min[1], max[0], max[1]) {
+            /* imgui fills rects without anti aliasing, adjacent rects must not leave a gap */
+            let paint = Paint {
+                anti_alias: false,
+                ..create_paint(color)
+            };
+            self.fill_path(
+                &PathBuilder::from_rect(rect),
+                &paint,
+                FillRule::Winding,
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+
+    fn fill_rect_vertical_gradient(
+        &mut self,
+        min: [f32; 2],
+        max: [f32; 2],
+        top: [f32; 4],
+        bottom: [f32; 4],
+    ) {
+        let Some(rect) = Rect::from_ltrb(min[0], min[1], max[0], max[1]) else {
+            return;
+        };
+        let Some(shader) = LinearGradient::new(
+            Point::from_xy(min[0], min[1]),
+            Point::from_xy(min[0], max[1]),
+            vec![
+                GradientStop::new(0.0, create_color(top)),
+                GradientStop::new(1.0, create_color(bottom)),
+            ],
+            SpreadMode::Pad,
+            Transform::identity(),
+        ) else {
+            return;
+        };
+
+        let paint = Paint {
+            shader,
+            anti_alias: false,
+            ..Default::default()
+        };
+        self.fill_path(
+            &PathBuilder::from_rect(rect),
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+
+    fn line(&mut self, start: [f32; 2], end: [f32; 2], color: [f32; 4], thickness: f32) {
+        let mut builder = PathBuilder::new();
+        builder.move_to(start[0], start[1]);
+        builder.line_to(end[0], end[1]);
+        let Some(path) = builder.finish() else {
+            return;
+        };
+
+        let stroke = Stroke {
+            width: thickness,
+            ..Default::default()
+        };
+        self.stroke_path(&path, &create_paint(color), &stroke, Transform::identity(), None);
+    }
+}
+
+/// Render the 2D boxes, health bars and tracers of the snapshot with the same draw functions as `PlayerESP::render`.
+/// Other box types, armor and flash bars, text and models are not covered.
+pub fn render_snapshot(snapshot: &EspSnapshot, esp_settings: &EspPlayerSettings) -> Result<Pixmap> {
+    let view = snapshot.view_controller();
+    let mut pixmap = Pixmap::new(
+        snapshot.screen_bounds[0] as u32,
+        snapshot.screen_bounds[1] as u32,
+    )
+    .ok_or_else(|| anyhow!("invalid snapshot screen bounds"))?;
+    pixmap.fill(Color::BLACK);
+
+    for player in &snapshot.players {
+        let position = Vector3::from(player.position);
+        let ctx = ColorContext {
+            health: (player.health as f32 / 100.0).clamp(0.0, 1.0),
+            distance: 0.0,
+            time: 0.0,
+        };
+
+        let player_2d_box = view.calculate_box_2d(
+            &(Vector3::from(player.hull_min) + position),
+            &(Vector3::from(player.hull_max) + position),
+        );
+        if let Some((vmin, vmax)) = &player_2d_box {
+            if esp_settings.box_type == EspBoxType::Box2D {
+                draw_box_2d(&mut pixmap, esp_settings, vmin, vmax, &ctx);
+            }
+
+            draw_health_bar(&mut pixmap, esp_settings, vmin, vmax, &ctx, 1.0);
+        }
+
+        if let Some(pos) = view.world_to_screen(&position, false) {
+            draw_tracer(&mut pixmap, esp_settings, &view.screen_bounds, [pos.x, pos.y], &ctx);
+        }
+    }
+
+    Ok(pixmap)
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(BACKEND)
+        .join(format!("{}.png", name))
+}
+
+/// Compare the rendered pixmap against the golden image with the given name.
+/// On mismatch the actual image gets written next to the golden image for inspection.
+/// Golden images are only written if `UPDATE_GOLDEN` is set, a missing golden image fails the comparison.
+pub fn compare_golden(name: &str, actual: &Pixmap) -> Result<()> {
+    let path = golden_path(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        actual
+            .save_png(&path)
+            .with_context(|| format!("write golden image {}", path.display()))?;
+        log::info!("Updated golden image {}", path.display());
+        return Ok(());
+    }
+
+    if !path.exists() {
+        let actual_path = path.with_extension("actual.png");
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let _ = actual.save_png(&actual_path);
+        anyhow::bail!(
+            "golden image {} is missing, see {} and run with UPDATE_GOLDEN=1 to accept it",
+            path.display(),
+            actual_path.display()
+        );
+    }
+
+    let expected = Pixmap::load_png(&path)
+        .with_context(|| format!("load golden image {}", path.display()))?;
+    if expected.width() != actual.width() || expected.height() != actual.height() {
+        anyhow::bail!(
+            "golden image {} has size {}x{} but rendered {}x{}",
+            name,
+            expected.width(),
+            expected.height(),
+            actual.width(),
+            actual.height()
+        );
+    }
+
+    let diff_pixels = expected
+        .data()
+        .chunks_exact(4)
+        .zip(actual.data().chunks_exact(4))
+        .filter(|(expected, actual)| {
+            expected
+                .iter()
+                .zip(actual.iter())
+                .any(|(a, b)| a.abs_diff(*b) > PIXEL_TOLERANCE)
+        })
+        .count();
+
+    let diff_ratio = diff_pixels as f32 / (actual.width() * actual.height()) as f32;
+    if diff_ratio > MAX_DIFF_RATIO {
+        let actual_path = path.with_extension("actual.png");
+        let _ = actual.save_png(&actual_path);
+        anyhow::bail!(
+            "ESP snapshot {} differs from golden image ({} pixels, {:.2}%), see {}",
+            name,
+            diff_pixels,
+            diff_ratio * 100.0,
+            actual_path.display()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::{
+        compare_golden,
+        render_snapshot,
+        EspSnapshot,
+    };
+    use crate::settings::{
+        EspBoxType,
+        EspColor,
+        EspHealthBar,
+        EspPlayerSettings,
+        EspSelector,
+        EspTracePosition,
+    };
+
+    #[test]
+    fn esp_snapshot_basic() {
+        let snapshot = EspSnapshot::load(
+            &PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("snapshots")
+                .join("esp_basic.json"),
+        )
+        .unwrap();
+
+        let mut esp_settings = EspPlayerSettings::new(&EspSelector::PlayerTeam { enemy: true });
+        esp_settings.box_type = EspBoxType::Box2D;
+        esp_settings.health_bar = EspHealthBar::Left;
+        esp_settings.tracer_lines = EspTracePosition::BottomCenter;
+        /* distinguishable from the red background of the health bar */
+        esp_settings.info_hp_text_color = EspColor::from_rgba(0.0, 1.0, 0.0, 1.0);
+
+        let pixmap = render_snapshot(&snapshot, &esp_settings).unwrap();
+        compare_golden("esp_basic", &pixmap).unwrap();
+    }
+}
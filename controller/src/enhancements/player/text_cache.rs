@@ -0,0 +1,309 @@
+// controller/src/enhancements/player/text_cache.rs
+
+use std::fmt::Write;
+
+use cs2::{
+    CompetitiveRankType,
+    StatePawnInfo,
+    StatePlayerRank,
+};
+use utils_state::StateRegistry;
+
+use crate::utils::display_player_name_with_mode;
+
+/// Text which only gets formatted again once the value it has been formatted from changes.
+/// The buffer is reused, therefore formatting does not allocate once it has grown large enough.
+pub struct CachedText<K> {
+    key: Option<K>,
+    text: String,
+}
+
+impl<K: PartialEq> CachedText<K> {
+    pub const fn new() -> Self {
+        Self {
+            key: None,
+            text: String::new(),
+        }
+    }
+
+    pub fn get(&mut self, key: K, format: impl FnOnce(&mut String, &K)) -> &str {
+        if self.key.as_ref() != Some(&key) {
+            self.text.clear();
+            format(&mut self.text, &key);
+            self.key = Some(key);
+        }
+
+        &self.text
+    }
+}
+
+type UtilityKey = (u32, bool, bool, bool, bool, bool);
+type RankKey = (CompetitiveRankType, i32, i32);
+
+/// Info texts of a single player which are kept across frames
+/// so the ESP does not need to format them every frame.
+pub struct PlayerTextCache {
+    health: CachedText<i32>,
+    armor: CachedText<(i32, bool)>,
+    utilities: CachedText<UtilityKey>,
+    money: CachedText<i32>,
+    ammo: CachedText<(i32, i32)>,
+    /// Distance in whole meters
+    distance: CachedText<i64>,
+    /// Remaining flash time in tenths of a second
+    flash_remaining: CachedText<i32>,
+
+    rank_name: CachedText<RankKey>,
+    rank_icon: CachedText<RankKey>,
+
+    name_source: String,
+    name_clip_safe_mode: bool,
+    name: String,
+}
+
+impl PlayerTextCache {
+    pub fn new() -> Self {
+        Self {
+            health: CachedText::new(),
+            armor: CachedText::new(),
+            utilities: CachedText::new(),
+            money: CachedText::new(),
+            ammo: CachedText::new(),
+            distance: CachedText::new(),
+            flash_remaining: CachedText::new(),
+
+            rank_name: CachedText::new(),
+            rank_icon: CachedText::new(),
+
+            name_source: String::new(),
+            name_clip_safe_mode: false,
+            name: String::new(),
+        }
+    }
+
+    pub fn health(&mut self, health: i32) -> &str {
+        self.health.get(health, |text, health| {
+            let _ = write!(text, "{} HP", health);
+        })
+    }
+
+    pub fn armor(&mut self, health: i32, has_helmet: bool) -> &str {
+        self.armor
+            .get((health, has_helmet), |text, (health, has_helmet)| {
+                if *has_helmet {
+                    let _ = write!(text, "{} HP+Kevlar+Helmet", health);
+                } else {
+                    let _ = write!(text, "{} HP+Kevlar", health);
+                }
+            })
+    }
+
+    /// Comma separated list of the grenades the player carries.
+    /// Empty if the player does not carry any grenades.
+    pub fn utilities(&mut self, pawn_info: &StatePawnInfo) -> &str {
+        let key = (
+            pawn_info.player_has_flash,
+            pawn_info.player_has_smoke,
+            pawn_info.player_has_hegrenade,
+            pawn_info.player_has_molotov,
+            pawn_info.player_has_incendiary,
+            pawn_info.player_has_decoy,
+        );
+
+        self.utilities.get(
+            key,
+            |text, (flash, smoke, hegrenade, molotov, incendiary, decoy)| {
+                let mut push = |value: std::fmt::Arguments| {
+                    if !text.is_empty() {
+                        text.push_str(", ");
+                    }
+                    let _ = text.write_fmt(value);
+                };
+
+                if *flash > 0 {
+                    push(format_args!("Flashbang x{}", flash));
+                }
+                if *smoke {
+                    push(format_args!("Smoke"));
+                }
+                if *hegrenade {
+                    push(format_args!("HE Grenade"));
+                }
+                if *molotov {
+                    push(format_args!("Molotov"));
+                }
+                if *incendiary {
+                    push(format_args!("Incendiary"));
+                }
+                if *decoy {
+                    push(format_args!("Decoy"));
+                }
+            },
+        )
+    }
+
+    pub fn money(&mut self, money: i32) -> &str {
+        self.money.get(money, |text, money| {
+            let _ = write!(text, "$ {}", money);
+        })
+    }
+
+    pub fn ammo(&mut self, current: i32, reserve: i32) -> &str {
+        self.ammo
+            .get((current, reserve), |text, (current, reserve)| {
+                let _ = write!(text, "{}/{}", current, reserve);
+            })
+    }
+
+    pub fn distance(&mut self, distance: f32) -> &str {
+        self.distance
+            .get(distance.round() as i64, |text, distance| {
+                let _ = write!(text, "{}m", distance);
+            })
+    }
+
+    pub fn flash_remaining(&mut self, remaining: f32) -> &str {
+        self.flash_remaining
+            .get((remaining * 10.0).round() as i32, |text, remaining| {
+                let _ = write!(text, "{:.1}s", *remaining as f32 / 10.0);
+            })
+    }
+
+    /// Rank name with the amount of wins, e.g. "Gold Nova II (42 wins)".
+    /// Empty if the player is not ranked.
+    pub fn rank_name(&mut self, rank: &StatePlayerRank) -> &str {
+        self.rank_name.get(rank_key(rank), |text, _| {
+            if let Some(rank_name) = rank.display_name() {
+                let _ = write!(text, "{} ({} wins)", rank_name, rank.wins);
+            }
+        })
+    }
+
+    /// Name of the rank icon texture. Empty if there is no icon for the rank.
+    pub fn rank_icon(&mut self, rank: &StatePlayerRank) -> &str {
+        self.rank_icon.get(rank_key(rank), |text, _| {
+            if let Some(icon_name) = rank.icon_name() {
+                text.push_str(&icon_name);
+            }
+        })
+    }
+
+    /// Player name as it should be displayed, see [display_player_name_with_mode].
+    pub fn player_name(
+        &mut self,
+        states: &StateRegistry,
+        clip_safe_mode: bool,
+        name: &str,
+    ) -> &str {
+        if self.name_source != name || self.name_clip_safe_mode != clip_safe_mode {
+            self.name_source.clear();
+            self.name_source.push_str(name);
+            self.name_clip_safe_mode = clip_safe_mode;
+
+            self.name.clear();
+            self.name
+                .push_str(&display_player_name_with_mode(states, clip_safe_mode, name));
+        }
+
+        &self.name
+    }
+}
+
+fn rank_key(rank: &StatePlayerRank) -> RankKey {
+    (rank.rank_type, rank.ranking, rank.wins)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        alloc::{
+            GlobalAlloc,
+            Layout,
+            System,
+        },
+        cell::Cell,
+    };
+
+    use super::PlayerTextCache;
+
+    /// Counts the allocations of the current thread while enabled
+    struct CountingAllocator;
+
+    thread_local! {
+        static COUNTING: Cell<bool> = const { Cell::new(false) };
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn count_allocation() {
+        if COUNTING.try_with(Cell::get).unwrap_or(false) {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        }
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            count_allocation();
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            count_allocation();
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn count_allocations(callback: impl FnOnce()) -> usize {
+        ALLOCATIONS.with(|count| count.set(0));
+        COUNTING.with(|counting| counting.set(true));
+        callback();
+        COUNTING.with(|counting| counting.set(false));
+        ALLOCATIONS.with(Cell::get)
+    }
+
+    /// Format the texts the ESP shows for a player within a single frame
+    fn format_frame(texts: &mut PlayerTextCache, health: i32, distance: f32) {
+        texts.health(health);
+        texts.armor(health, true);
+        texts.money(health * 100);
+        texts.ammo(health % 30, 90);
+        texts.distance(distance);
+        texts.flash_remaining(distance / 1000.0);
+    }
+
+    #[test]
+    fn texts_formatted() {
+        let mut texts = PlayerTextCache::new();
+        assert_eq!(texts.health(100), "100 HP");
+        assert_eq!(texts.armor(87, false), "87 HP+Kevlar");
+        assert_eq!(texts.armor(87, true), "87 HP+Kevlar+Helmet");
+        assert_eq!(texts.money(4750), "$ 4750");
+        assert_eq!(texts.ammo(12, 90), "12/90");
+        assert_eq!(texts.distance(12.6), "13m");
+        assert_eq!(texts.flash_remaining(1.24), "1.2s");
+
+        /* cached text updated once the value changes */
+        assert_eq!(texts.health(99), "99 HP");
+    }
+
+    #[test]
+    fn texts_reuse_buffers() {
+        let mut texts = PlayerTextCache::new();
+
+        /* the first frame grows the buffers */
+        format_frame(&mut texts, 100, 1234.0);
+
+        let allocations = count_allocations(|| {
+            for frame in 0..100 {
+                format_frame(&mut texts, 100 - frame % 50, 1234.0 - frame as f32);
+            }
+        });
+        assert_eq!(allocations, 0);
+    }
+}
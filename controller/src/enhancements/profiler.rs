@@ -0,0 +1,246 @@
+use std::{
+    collections::VecDeque,
+    time::Duration,
+};
+
+use imgui::{
+    Condition,
+    TableColumnFlags,
+    TableColumnSetup,
+    TableFlags,
+    TableSortDirection,
+};
+
+/// Amount of frames kept for the averages and the graphs
+const PROFILER_HISTORY_LENGTH: usize = 120;
+
+const GRAPH_SIZE: [f32; 2] = [160.0, 28.0];
+
+#[derive(Default)]
+struct EnhancementTimings {
+    name: &'static str,
+
+    /// Time spent in update during the current frame
+    update_time: Duration,
+    /// Time spent in render during the current frame
+    render_time: Duration,
+
+    /// Update times of the last frames in milliseconds, oldest first
+    update_history: VecDeque<f32>,
+    /// Render times of the last frames in milliseconds, oldest first
+    render_history: VecDeque<f32>,
+}
+
+impl EnhancementTimings {
+    /// Move the times of the current frame into the history and return their sum
+    fn push_frame(&mut self) -> Duration {
+        if self.update_history.len() >= PROFILER_HISTORY_LENGTH {
+            self.update_history.pop_front();
+            self.render_history.pop_front();
+        }
+
+        let update_time = std::mem::take(&mut self.update_time);
+        let render_time = std::mem::take(&mut self.render_time);
+        self.update_history
+            .push_back(update_time.as_secs_f32() * 1000.0);
+        self.render_history
+            .push_back(render_time.as_secs_f32() * 1000.0);
+        update_time + render_time
+    }
+
+    fn total_history(&self) -> Vec<f32> {
+        self.update_history
+            .iter()
+            .zip(self.render_history.iter())
+            .map(|(update, render)| update + render)
+            .collect()
+    }
+}
+
+fn average(values: &VecDeque<f32>) -> f32 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f32>() / values.len() as f32
+    }
+}
+
+fn maximum(values: &VecDeque<f32>) -> f32 {
+    values.iter().copied().fold(0.0, f32::max)
+}
+
+/// Summary of the recorded frames of a single enhancement
+struct ProfilerRow<'a> {
+    timings: &'a EnhancementTimings,
+    /// Update avg, update max, render avg, render max and total avg in milliseconds
+    values: [f32; 5],
+}
+
+/// Records the time each enhancement spends in update and render over the last frames
+/// and displays them in a sortable table with a graph per enhancement.
+/// The [FrameBudgetWatchdog](super::FrameBudgetWatchdog) evaluates the same measurements.
+pub struct EnhancementProfiler {
+    timings: Vec<EnhancementTimings>,
+
+    sort_column: usize,
+    sort_ascending: bool,
+}
+
+impl EnhancementProfiler {
+    pub fn new() -> Self {
+        Self {
+            timings: Vec::new(),
+
+            /* total avg */
+            sort_column: 5,
+            sort_ascending: false,
+        }
+    }
+
+    /// Push the times of the last frame into the history and return the total time
+    /// the enhancement spent in update and render during the last frame.
+    /// Must be called once per frame for every enhancement before recording.
+    pub fn begin_frame(&mut self, index: usize, name: &'static str) -> Duration {
+        if self.timings.len() <= index {
+            self.timings.resize_with(index + 1, Default::default);
+        }
+
+        let entry = &mut self.timings[index];
+        entry.name = name;
+        entry.push_frame()
+    }
+
+    /// Name, update and render time of every enhancement within the current frame
+    pub fn current_frame(&self) -> impl Iterator<Item = (&'static str, Duration, Duration)> + '_ {
+        self.timings
+            .iter()
+            .map(|timings| (timings.name, timings.update_time, timings.render_time))
+    }
+
+    pub fn record_update(&mut self, index: usize, elapsed: Duration) {
+        if let Some(entry) = self.timings.get_mut(index) {
+            entry.update_time += elapsed;
+        }
+    }
+
+    pub fn record_render(&mut self, index: usize, elapsed: Duration) {
+        if let Some(entry) = self.timings.get_mut(index) {
+            entry.render_time += elapsed;
+        }
+    }
+
+    pub fn render_window(&mut self, ui: &imgui::Ui) {
+        ui.window("Enhancement Profiler")
+            .size([720.0, 420.0], Condition::FirstUseEver)
+            .build(|| self.render_table(ui));
+    }
+
+    fn render_table(&mut self, ui: &imgui::Ui) {
+        let total_update = self
+            .timings
+            .iter()
+            .map(|timings| average(&timings.update_history))
+            .sum::<f32>();
+        let total_render = self
+            .timings
+            .iter()
+            .map(|timings| average(&timings.render_history))
+            .sum::<f32>();
+        ui.text(&format!(
+            "Average over the last {} frames: update {:.3}ms, render {:.3}ms",
+            PROFILER_HISTORY_LENGTH, total_update, total_render
+        ));
+
+        let Some(_table) = ui.begin_table_with_flags(
+            "enhancement_profiler",
+            7,
+            TableFlags::BORDERS
+                | TableFlags::ROW_BG
+                | TableFlags::SORTABLE
+                | TableFlags::SIZING_STRETCH_PROP
+                | TableFlags::SCROLL_Y,
+        ) else {
+            return;
+        };
+
+        ui.table_setup_scroll_freeze(0, 1);
+        ui.table_setup_column("Enhancement");
+        for name in [
+            "Update avg",
+            "Update max",
+            "Render avg",
+            "Render max",
+            "Total avg",
+        ] {
+            let mut column = TableColumnSetup::new(name);
+            column.flags = TableColumnFlags::PREFER_SORT_DESCENDING;
+            if name == "Total avg" {
+                column.flags |= TableColumnFlags::DEFAULT_SORT;
+            }
+            ui.table_setup_column_with(column);
+        }
+
+        let mut graph_column = TableColumnSetup::new("Graph (ms)");
+        graph_column.flags = TableColumnFlags::NO_SORT;
+        ui.table_setup_column_with(graph_column);
+        ui.table_headers_row();
+
+        if let Some(sort_specs) = ui.table_sort_specs_mut() {
+            sort_specs.conditional_sort(|specs| {
+                if let Some(spec) = specs.iter().next() {
+                    self.sort_column = spec.column_idx();
+                    self.sort_ascending =
+                        matches!(spec.sort_direction(), Some(TableSortDirection::Ascending));
+                }
+            });
+        }
+
+        let mut rows = self
+            .timings
+            .iter()
+            .map(|timings| ProfilerRow {
+                timings,
+                values: [
+                    average(&timings.update_history),
+                    maximum(&timings.update_history),
+                    average(&timings.render_history),
+                    maximum(&timings.render_history),
+                    average(&timings.update_history) + average(&timings.render_history),
+                ],
+            })
+            .collect::<Vec<_>>();
+
+        rows.sort_by(|a, b| {
+            let ordering = match self.sort_column {
+                0 => a.timings.name.cmp(b.timings.name),
+                column => a.values[(column - 1).min(4)].total_cmp(&b.values[(column - 1).min(4)]),
+            };
+
+            if self.sort_ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+
+        for row in rows {
+            ui.table_next_row();
+
+            ui.table_next_column();
+            ui.text(row.timings.name);
+
+            for value in row.values {
+                ui.table_next_column();
+                ui.text(&format!("{:.3}ms", value));
+            }
+
+            ui.table_next_column();
+            let history = row.timings.total_history();
+            ui.plot_lines(&format!("##profiler_graph_{}", row.timings.name), &history)
+                .graph_size(GRAPH_SIZE)
+                .scale_min(0.0)
+                .scale_max(history.iter().copied().fold(0.1, f32::max))
+                .build();
+        }
+    }
+}
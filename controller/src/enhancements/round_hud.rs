@@ -0,0 +1,101 @@
+use cs2::StateGameRules;
+use imgui::ImColor32;
+use overlay::UnicodeTextRenderer;
+
+use super::Enhancement;
+use crate::{
+    settings::AppSettings,
+    utils::{
+        self,
+        TextWithShadowUi,
+    },
+};
+
+const COLOR_T: ImColor32 = ImColor32::from_rgba(234, 190, 84, 255);
+const COLOR_CT: ImColor32 = ImColor32::from_rgba(93, 121, 174, 255);
+const COLOR_FREEZE: ImColor32 = ImColor32::from_rgba(120, 200, 255, 255);
+
+/// Compact panel showing the score, round number and round timer
+pub struct RoundHud;
+
+impl RoundHud {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn format_time(seconds: f32) -> String {
+    let seconds = seconds.max(0.0).ceil() as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+impl Enhancement for RoundHud {
+    fn update(&mut self, _ctx: &crate::UpdateContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn render(
+        &mut self,
+        states: &utils_state::StateRegistry,
+        ui: &imgui::Ui,
+        _unicode_text: &UnicodeTextRenderer,
+    ) -> anyhow::Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+        if !settings.round_hud {
+            return Ok(());
+        }
+
+        let hud_settings = &settings.round_hud_settings;
+        if !hud_settings.show_score && !hud_settings.show_round && !hud_settings.show_timer {
+            return Ok(());
+        }
+
+        let Ok(game_rules) = states.resolve::<StateGameRules>(()) else {
+            /* not in a match */
+            return Ok(());
+        };
+
+        let anchor_position = hud_settings.anchor.position(ui.io().display_size);
+        let position = [
+            anchor_position[0] + hud_settings.offset[0],
+            anchor_position[1] + hud_settings.offset[1],
+        ];
+
+        utils::render_styled_panel_pivot(
+            ui,
+            "round_hud_panel",
+            position,
+            hud_settings.anchor.pivot(),
+            || {
+                if hud_settings.show_score {
+                    ui.text_colored_with_shadow(COLOR_T, &format!("T {}", game_rules.score_t));
+                    ui.same_line();
+                    ui.text_with_shadow(":");
+                    ui.same_line();
+                    ui.text_colored_with_shadow(COLOR_CT, &format!("{} CT", game_rules.score_ct));
+                }
+
+                if hud_settings.show_round {
+                    if game_rules.warmup_period {
+                        ui.text_with_shadow("Warmup");
+                    } else {
+                        ui.text_with_shadow(&format!("Round {}", game_rules.round_number));
+                    }
+                }
+
+                if hud_settings.show_timer {
+                    if game_rules.freeze_period {
+                        ui.text_colored_with_shadow(
+                            COLOR_FREEZE,
+                            &format!("Freeze time {}", format_time(game_rules.freeze_time_remaining)),
+                        );
+                    } else {
+                        ui.text_with_shadow(&format_time(game_rules.round_time_remaining));
+                    }
+                }
+            },
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,404 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{
+        BufRead,
+        BufReader,
+        BufWriter,
+        Write,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+use cs2::{
+    CEntityIdentityEx,
+    ClassNameCache,
+    PlantedC4,
+    PlantedC4State,
+    PlayerPawnState,
+    StateCS2Memory,
+    StateCurrentMap,
+    StateEntityList,
+    StateGameRules,
+    StatePawnInfo,
+};
+use cs2_schema_generated::cs2::client::CCSPlayerController;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    settings::{
+        get_sessions_dir,
+        AppSettings,
+    },
+    UpdateContext,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SessionEvent {
+    RoundStart { round: i32 },
+    PlayerDeath { player: String, team: u8 },
+    BombPlanted { site: u8 },
+    BombDefused,
+    BombExploded,
+}
+
+impl SessionEvent {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Self::RoundStart { .. } => "RoundStart",
+            Self::PlayerDeath { .. } => "PlayerDeath",
+            Self::BombPlanted { .. } => "BombPlanted",
+            Self::BombDefused => "BombDefused",
+            Self::BombExploded => "BombExploded",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEventRecord {
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    pub map: Option<String>,
+    #[serde(flatten)]
+    pub event: SessionEvent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BombState {
+    NotPlanted,
+    Active,
+    Defused,
+    Detonated,
+}
+
+/// Records kills, rounds and bomb events to a JSONL file per session
+/// in `LABHConfig/sessions`.
+pub struct SessionEventLog {
+    writer: Option<BufWriter<File>>,
+
+    round_number: Option<i32>,
+    bomb_state: BombState,
+    /// Alive state of the players keyed by their controller entity id
+    players_alive: HashMap<u32, bool>,
+}
+
+impl SessionEventLog {
+    pub fn new() -> Self {
+        Self {
+            writer: None,
+
+            round_number: None,
+            bomb_state: BombState::NotPlanted,
+            players_alive: Default::default(),
+        }
+    }
+
+    fn record(&mut self, map: &Option<String>, event: SessionEvent) -> Result<()> {
+        if self.writer.is_none() {
+            let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let path = get_sessions_dir()?.join(format!("session-{}.jsonl", timestamp));
+            let file = File::create(&path)
+                .with_context(|| format!("failed to create session log {}", path.display()))?;
+            log::info!("Recording session events to {}", path.display());
+            self.writer = Some(BufWriter::new(file));
+        }
+
+        let record = SessionEventRecord {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64,
+            map: map.clone(),
+            event,
+        };
+
+        let writer = self.writer.as_mut().context("missing session log writer")?;
+        serde_json::to_writer(&mut *writer, &record)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Collect the events since the last update.
+    /// Events which failed to resolve are logged and skipped.
+    fn collect_events(&mut self, states: &StateRegistry) -> Vec<SessionEvent> {
+        let mut events = Vec::new();
+
+        if let Ok(game_rules) = states.resolve::<StateGameRules>(()) {
+            if !game_rules.warmup_period && self.round_number != Some(game_rules.round_number) {
+                if self.round_number.is_some() {
+                    events.push(SessionEvent::RoundStart {
+                        round: game_rules.round_number,
+                    });
+                }
+                self.round_number = Some(game_rules.round_number);
+            }
+        }
+
+        if let Ok(bomb) = states.resolve::<PlantedC4>(()) {
+            let bomb_state = match bomb.state {
+                PlantedC4State::NotPlanted => BombState::NotPlanted,
+                PlantedC4State::Active { .. } => BombState::Active,
+                PlantedC4State::Defused => BombState::Defused,
+                PlantedC4State::Detonated => BombState::Detonated,
+            };
+
+            if bomb_state != self.bomb_state {
+                match bomb_state {
+                    BombState::Active => events.push(SessionEvent::BombPlanted {
+                        site: bomb.bomb_site,
+                    }),
+                    BombState::Defused => events.push(SessionEvent::BombDefused),
+                    BombState::Detonated => events.push(SessionEvent::BombExploded),
+                    BombState::NotPlanted => {}
+                }
+                self.bomb_state = bomb_state;
+            }
+        }
+
+        if let Err(err) = self.collect_player_deaths(states, &mut events) {
+            log::debug!("Failed to collect player deaths: {:#}", err);
+        }
+
+        events
+    }
+
+    fn collect_player_deaths(
+        &mut self,
+        states: &StateRegistry,
+        events: &mut Vec<SessionEvent>,
+    ) -> Result<()> {
+        let entities = states.resolve::<StateEntityList>(())?;
+        let class_name_cache = states.resolve::<ClassNameCache>(())?;
+        let memory = states.resolve::<StateCS2Memory>(())?;
+
+        for entity_identity in entities.entities() {
+            let death = (|| -> Result<Option<SessionEvent>> {
+                let entity_class =
+                    class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
+                if !entity_class
+                    .map(|name| *name == "CCSPlayerController")
+                    .unwrap_or(false)
+                {
+                    return Ok(None);
+                }
+
+                let controller_handle = entity_identity.handle::<dyn CCSPlayerController>()?;
+                let Some(controller) = entities
+                    .entity_from_handle(&controller_handle)
+                    .and_then(|controller| controller.value_reference(memory.view_arc()))
+                else {
+                    return Ok(None);
+                };
+
+                let pawn_handle = controller.m_hPlayerPawn()?;
+                if !pawn_handle.is_valid() {
+                    return Ok(None);
+                }
+
+                let alive =
+                    *states.resolve::<PlayerPawnState>(pawn_handle)? == PlayerPawnState::Alive;
+                let was_alive = self
+                    .players_alive
+                    .insert(controller_handle.get_entity_index(), alive)
+                    .unwrap_or(false);
+                if !was_alive || alive {
+                    return Ok(None);
+                }
+
+                let pawn_info = states.resolve::<StatePawnInfo>(pawn_handle)?;
+                Ok(Some(SessionEvent::PlayerDeath {
+                    player: pawn_info
+                        .player_name
+                        .clone()
+                        .unwrap_or_else(|| "unknown".to_string()),
+                    team: pawn_info.team_id,
+                }))
+            })();
+
+            match death {
+                Ok(Some(event)) => events.push(event),
+                Ok(None) => {}
+                Err(err) => log::debug!("Failed to check player for a death: {:#}", err),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Enhancement for SessionEventLog {
+    fn update(&mut self, ctx: &UpdateContext) -> Result<()> {
+        let enabled = ctx.states.resolve::<AppSettings>(())?.session_event_log;
+        if !enabled {
+            /* start a new session file once the log gets enabled again */
+            self.writer = None;
+            self.round_number = None;
+            self.players_alive.clear();
+            return Ok(());
+        }
+
+        let map = ctx
+            .states
+            .resolve::<StateCurrentMap>(())
+            .ok()
+            .and_then(|map| map.current_map.clone());
+
+        for event in self.collect_events(ctx.states) {
+            if let Err(err) = self.record(&map, event) {
+                log::warn!("Failed to record session event: {:#}", err);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render(
+        &mut self,
+        _states: &StateRegistry,
+        _ui: &imgui::Ui,
+        _unicode_text: &overlay::UnicodeTextRenderer,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+pub(super) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Convert a recorded session JSONL file into a CSV file next to it.
+/// Returns the path of the CSV file.
+pub fn export_session_csv(path: &Path) -> Result<PathBuf> {
+    let input = File::open(path)
+        .with_context(|| format!("failed to open session log {}", path.display()))?;
+    let output_path = path.with_extension("csv");
+    let mut output = BufWriter::new(
+        File::create(&output_path)
+            .with_context(|| format!("failed to create {}", output_path.display()))?,
+    );
+
+    writeln!(output, "timestamp,map,event,round,player,team,site")?;
+    for (line_index, line) in BufReader::new(input).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record = serde_json::from_str::<SessionEventRecord>(&line)
+            .with_context(|| format!("invalid session event in line {}", line_index + 1))?;
+        let (round, player, team, site) = match &record.event {
+            SessionEvent::RoundStart { round } => (round.to_string(), String::new(), String::new(), String::new()),
+            SessionEvent::PlayerDeath { player, team } => (String::new(), player.clone(), team.to_string(), String::new()),
+            SessionEvent::BombPlanted { site } => (String::new(), String::new(), String::new(), if *site == 0 { "A" } else { "B" }.to_string()),
+            SessionEvent::BombDefused | SessionEvent::BombExploded => Default::default(),
+        };
+
+        writeln!(
+            output,
+            "{},{},{},{},{},{},{}",
+            record.timestamp,
+            csv_field(record.map.as_deref().unwrap_or_default()),
+            record.event.type_name(),
+            round,
+            csv_field(&player),
+            team,
+            site
+        )?;
+    }
+
+    output.flush()?;
+    Ok(output_path)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::{
+        export_session_csv,
+        SessionEvent,
+        SessionEventRecord,
+    };
+
+    #[test]
+    fn export_session_csv_all_events() {
+        let events = [
+            SessionEvent::RoundStart { round: 3 },
+            SessionEvent::PlayerDeath {
+                player: "Name, \"quoted\"".to_string(),
+                team: 2,
+            },
+            SessionEvent::BombPlanted { site: 1 },
+            SessionEvent::BombDefused,
+            SessionEvent::BombExploded,
+        ];
+
+        let mut input = String::new();
+        for (index, event) in events.into_iter().enumerate() {
+            let record = SessionEventRecord {
+                timestamp: 1_000 + index as u64,
+                map: Some("de_dust2".to_string()),
+                event,
+            };
+            input.push_str(&serde_json::to_string(&record).unwrap());
+            input.push_str("\n\n");
+        }
+
+        let path =
+            std::env::temp_dir().join(format!("labh-session-test-{}.jsonl", std::process::id()));
+        fs::write(&path, input).unwrap();
+        let output_path = export_session_csv(&path).unwrap();
+        let output = fs::read_to_string(&output_path).unwrap();
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(&output_path);
+
+        assert_eq!(output_path.extension().unwrap(), "csv");
+        assert_eq!(
+            output.lines().collect::<Vec<_>>(),
+            [
+                "timestamp,map,event,round,player,team,site",
+                "1000,de_dust2,RoundStart,3,,,",
+                "1001,de_dust2,PlayerDeath,,\"Name, \"\"quoted\"\"\",2,",
+                "1002,de_dust2,BombPlanted,,,,B",
+                "1003,de_dust2,BombDefused,,,,",
+                "1004,de_dust2,BombExploded,,,,",
+            ]
+        );
+    }
+
+    #[test]
+    fn export_session_csv_invalid_line() {
+        let path =
+            std::env::temp_dir().join(format!("labh-session-invalid-{}.jsonl", std::process::id()));
+        fs::write(
+            &path,
+            "{\"timestamp\":1,\"map\":null,\"type\":\"BombDefused\"}\nnot json\n",
+        )
+        .unwrap();
+        let result = export_session_csv(&path);
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(path.with_extension("csv"));
+
+        let err = result.unwrap_err();
+        assert!(format!("{:#}", err).contains("line 2"));
+    }
+}
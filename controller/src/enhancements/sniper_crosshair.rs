@@ -6,13 +6,16 @@ use cs2::{
     StateEntityList,
     LocalCameraControllerTarget,
     StateCS2Memory,
+    StateLocalPlayerController,
     WeaponId,
 };
-use cs2_schema_generated::cs2::client::{C_CSPlayerPawn, C_CSPlayerPawnBase, C_EconEntity};
+use cs2_schema_cutl::EntityHandle;
+use cs2_schema_generated::cs2::client::{C_BaseEntity, C_CSPlayerPawn, C_CSPlayerPawnBase, C_EconEntity};
 
 use super::Enhancement;
 use crate::{
     settings::AppSettings,
+    view::StateLocalCrosshair,
     UpdateContext,
 };
 
@@ -30,6 +33,25 @@ impl SniperCrosshair {
             WeaponId::AWP | WeaponId::Ssg08 | WeaponId::Scar20 | WeaponId::G3SG1
         )
     }
+
+    /// Whether the local crosshair is currently over a player of the enemy team
+    fn is_over_enemy(&self, states: &StateRegistry) -> anyhow::Result<bool> {
+        let crosshair = states.resolve::<StateLocalCrosshair>(())?;
+        let Some(target) = crosshair.current_target() else { return Ok(false) };
+        if target.entity_type.as_deref() != Some("C_CSPlayerPawn") { return Ok(false); }
+
+        let entities = states.resolve::<StateEntityList>(())?;
+        let memory = states.resolve::<StateCS2Memory>(())?;
+        let Some(target_pawn) = entities
+            .entity_from_handle(&EntityHandle::<dyn C_CSPlayerPawn>::from_index(target.entity_id))
+            .and_then(|entity| entity.value_reference(memory.view_arc()))
+        else { return Ok(false) };
+
+        let local_controller = states.resolve::<StateLocalPlayerController>(())?;
+        let Some(local_controller) = local_controller.instance.value_reference(memory.view_arc()) else { return Ok(false) };
+
+        Ok(target_pawn.m_iTeamNum()? != local_controller.m_iTeamNum()?)
+    }
 }
 
 impl Enhancement for SniperCrosshair {
@@ -59,10 +81,17 @@ impl Enhancement for SniperCrosshair {
             })().unwrap_or(false);
 
             if !is_sniper { return Ok(()); }
+            if style.unscoped_only && pawn.m_bIsScoped().unwrap_or(false) { return Ok(()); }
+
+            let color = if style.target_color_enabled && self.is_over_enemy(states).unwrap_or(false) {
+                style.target_color
+            } else {
+                style.color
+            };
 
             let display_size = ui.io().display_size;
             let center = [display_size[0] / 2.0, display_size[1] / 2.0];
-            let color = ImColor32::from_rgba(style.color[0], style.color[1], style.color[2], style.color[3]);
+            let color = ImColor32::from_rgba(color[0], color[1], color[2], color[3]);
             let outline_color = ImColor32::from_rgba(0, 0, 0, style.color[3]);
             let draw_list = ui.get_window_draw_list();
             
@@ -1,19 +1,65 @@
 use cs2::{
     LocalCameraControllerTarget,
+    SpectatorInfo,
     SpectatorList,
+    StatePlayerRank,
+    StateSpectators,
+};
+use imgui::{
+    Condition,
+    WindowFlags,
 };
 use overlay::UnicodeTextRenderer;
 
 use super::Enhancement;
 use crate::{
-    settings::AppSettings,
-    utils::UnicodeTextWithShadowUi,
+    settings::{
+        AppSettings,
+        SpectatorsListSettings,
+    },
+    utils::{
+        display_player_name,
+        StateInteractiveRegions,
+        UnicodeTextWithShadowUi,
+    },
 };
 
-pub struct SpectatorsListIndicator;
+const HIGHLIGHT_COLOR: [f32; 4] = [1.0, 0.4, 0.4, 1.0];
+
+pub struct SpectatorsListIndicator {
+    /// Layout the window has been placed with the last time
+    applied_layout: Option<(SpectatorsListSettings, [f32; 2])>,
+
+    /// Window offset and size after the user moved or resized the window
+    pending_layout: Option<([f32; 2], [f32; 2])>,
+}
+
 impl SpectatorsListIndicator {
     pub fn new() -> Self {
-        Self
+        Self {
+            applied_layout: None,
+            pending_layout: None,
+        }
+    }
+}
+
+fn spectator_label(
+    states: &utils_state::StateRegistry,
+    spectator: &SpectatorInfo,
+    show_rank: bool,
+) -> String {
+    let name = display_player_name(states, &spectator.spectator_name);
+    if !show_rank {
+        return name;
+    }
+
+    let rank_name = states
+        .resolve::<StatePlayerRank>(spectator.spectator_controller_id)
+        .ok()
+        .and_then(|rank| rank.display_name());
+    match rank_name {
+        Some(rank_name) => format!("{} [{}]", name, rank_name),
+        None => name,
     }
 }
 
@@ -22,6 +68,25 @@ impl Enhancement for SpectatorsListIndicator {
         Ok(())
     }
 
+    fn update_settings(
+        &mut self,
+        _ui: &imgui::Ui,
+        settings: &mut AppSettings,
+    ) -> anyhow::Result<bool> {
+        let Some((offset, size)) = self.pending_layout.take() else {
+            return Ok(false);
+        };
+
+        let list_settings = &mut settings.spectators_list_settings;
+        list_settings.offset = offset;
+        list_settings.size = size;
+        if let Some((applied_settings, _)) = &mut self.applied_layout {
+            /* window already is at this position, no need to reposition it */
+            *applied_settings = list_settings.clone();
+        }
+        Ok(true)
+    }
+
     fn render(
         &mut self,
         states: &utils_state::StateRegistry,
@@ -32,6 +97,7 @@ impl Enhancement for SpectatorsListIndicator {
         if !settings.spectators_list {
             return Ok(());
         }
+        let list_settings = &settings.spectators_list_settings;
 
         let view_target = states.resolve::<LocalCameraControllerTarget>(())?;
         let target_entity_id = match &view_target.target_entity_id {
@@ -39,23 +105,108 @@ impl Enhancement for SpectatorsListIndicator {
             None => return Ok(()),
         };
         let spectators = states.resolve::<SpectatorList>(target_entity_id)?;
+        let all_spectators = if list_settings.show_targets {
+            Some(states.resolve::<StateSpectators>(())?)
+        } else {
+            None
+        };
 
-        let group = ui.begin_group();
-
-        let line_count = spectators.spectators.iter().count();
-        let text_height = ui.text_line_height_with_spacing() * line_count as f32;
+        let display_size = ui.io().display_size;
+        let anchor_position = list_settings.anchor.position(display_size);
+        let pivot = list_settings.anchor.pivot();
 
-        let offset_x = ui.io().display_size[0] * 0.01;
-        let offset_y = (ui.io().display_size[1] - text_height) * 0.5;
-        let mut offset_y = offset_y;
+        let layout_changed = self
+            .applied_layout
+            .as_ref()
+            .map(|(applied_settings, applied_display_size)| {
+                applied_settings != list_settings || *applied_display_size != display_size
+            })
+            .unwrap_or(true);
 
-        for spectator in &spectators.spectators {
-            ui.set_cursor_pos([offset_x, offset_y]);
-            ui.unicode_text_with_shadow(unicode_text, &spectator.spectator_name);
-            offset_y += ui.text_line_height_with_spacing();
+        let mut window = ui
+            .window("##spectators_list")
+            .title_bar(false)
+            .collapsible(false)
+            .scroll_bar(false)
+            .bg_alpha(0.0)
+            .flags(WindowFlags::NO_FOCUS_ON_APPEARING | WindowFlags::NO_NAV);
+        if layout_changed {
+            window = window
+                .position(
+                    [
+                        anchor_position[0] + list_settings.offset[0] - pivot[0] * list_settings.size[0],
+                        anchor_position[1] + list_settings.offset[1] - pivot[1] * list_settings.size[1],
+                    ],
+                    Condition::Always,
+                )
+                .size(list_settings.size, Condition::Always);
+            self.applied_layout = Some((list_settings.clone(), display_size));
         }
 
-        group.end();
+        window.build(|| {
+            if settings.interactive_widgets {
+                if let Ok(mut regions) = states.resolve_mut::<StateInteractiveRegions>(()) {
+                    regions.push_window(ui);
+                }
+            }
+
+            if list_settings.show_count {
+                let label = if view_target.is_local_entity {
+                    format!("Spectating you: {}", spectators.spectators.len())
+                } else {
+                    format!("Spectators: {}", spectators.spectators.len())
+                };
+                ui.unicode_text_with_shadow(unicode_text, &label);
+                ui.separator();
+            }
+
+            if let Some(all_spectators) = &all_spectators {
+                for spectator in &all_spectators.spectators {
+                    let text = format!(
+                        "{} -> {}",
+                        spectator_label(states, spectator, list_settings.show_ranks),
+                        spectator
+                            .target_name
+                            .as_deref()
+                            .map_or_else(|| "?".to_string(), |name| display_player_name(states, name))
+                    );
+
+                    if spectator.target_entity_id == target_entity_id {
+                        ui.unicode_text_colored_with_shadow(unicode_text, HIGHLIGHT_COLOR, &text);
+                    } else {
+                        ui.unicode_text_with_shadow(unicode_text, &text);
+                    }
+                }
+            } else {
+                for spectator in &spectators.spectators {
+                    ui.unicode_text_with_shadow(
+                        unicode_text,
+                        &spectator_label(states, spectator, list_settings.show_ranks),
+                    );
+                }
+            }
+
+            if layout_changed {
+                return;
+            }
+
+            let window_pos = ui.window_pos();
+            let window_size = ui.window_size();
+            let offset = [
+                window_pos[0] + pivot[0] * window_size[0] - anchor_position[0],
+                window_pos[1] + pivot[1] * window_size[1] - anchor_position[1],
+            ];
+
+            let moved = (offset[0] - list_settings.offset[0]).abs() > 0.5
+                || (offset[1] - list_settings.offset[1]).abs() > 0.5;
+            let resized = (window_size[0] - list_settings.size[0]).abs() > 0.5
+                || (window_size[1] - list_settings.size[1]).abs() > 0.5;
+            if (moved || resized) && !ui.is_mouse_dragging(imgui::MouseButton::Left) {
+                /* user finished moving or resizing the window */
+                self.pending_layout = Some((offset, window_size));
+            }
+        });
+
         Ok(())
     }
-}
\ No newline at end of file
+}
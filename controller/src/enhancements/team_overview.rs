@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use imgui::{
+    Condition,
+    Image,
+    MouseButton,
+};
+use overlay::UnicodeTextRenderer;
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    settings::AppSettings,
+    update_thread::StateGameSnapshot,
+    utils::{
+        display_player_name,
+        steam_profile,
+        UnicodeTextWithShadowUi,
+    },
+    UpdateContext,
+};
+
+const AVATAR_SIZE: f32 = 32.0;
+
+struct PlayerOverview {
+    name: String,
+    team_id: u8,
+    steam_id: Option<u64>,
+}
+
+/// Window listing the players of both teams with their Steam persona name and avatar
+pub struct TeamOverview {
+    players: Vec<PlayerOverview>,
+    /// Player (Steam id and name) to add to or remove from the ESP ignore list
+    ignore_toggle: Option<(u64, String)>,
+}
+
+impl TeamOverview {
+    pub fn new() -> Self {
+        Self {
+            players: Vec::new(),
+            ignore_toggle: None,
+        }
+    }
+
+    /// Render the players of a team.
+    /// Returns the player to toggle on the ESP ignore list if selected in the context menu.
+    fn render_team(
+        &self,
+        states: &StateRegistry,
+        ui: &imgui::Ui,
+        unicode_text: &UnicodeTextRenderer,
+        ignored_players: &BTreeMap<u64, String>,
+        team_id: u8,
+        label: &str,
+    ) -> Option<(u64, String)> {
+        ui.text(label);
+
+        let mut ignore_toggle = None;
+        for player in self.players.iter().filter(|player| player.team_id == team_id) {
+            let name = display_player_name(states, &player.name);
+            let profile = player
+                .steam_id
+                .and_then(|steam_id| steam_profile(states, steam_id));
+            let ignored = player
+                .steam_id
+                .map_or(false, |steam_id| ignored_players.contains_key(&steam_id));
+
+            ui.group(|| {
+                match profile.as_ref().and_then(|profile| profile.avatar) {
+                    Some(avatar) => Image::new(avatar, [AVATAR_SIZE, AVATAR_SIZE]).build(ui),
+                    None => ui.dummy([AVATAR_SIZE, AVATAR_SIZE]),
+                }
+                ui.same_line();
+
+                let mut text = match &profile {
+                    Some(profile) if profile.persona_name != name => format!("{} ({})", name, profile.persona_name),
+                    _ => name.clone(),
+                };
+                if ignored {
+                    text.push_str(" [hidden]");
+                }
+                ui.unicode_text_with_shadow(unicode_text, &text);
+            });
+
+            let Some(steam_id) = player.steam_id else {
+                continue;
+            };
+
+            let popup_id = format!("player_context_{}", steam_id);
+            if ui.is_item_clicked_with_button(MouseButton::Right) {
+                ui.open_popup(&popup_id);
+            }
+            ui.popup(&popup_id, || {
+                let action = if ignored { "Show in ESP" } else { "Hide in ESP" };
+                if ui.selectable(action) {
+                    ignore_toggle = Some((steam_id, player.name.clone()));
+                }
+            });
+        }
+
+        ignore_toggle
+    }
+}
+
+impl Enhancement for TeamOverview {
+    fn update_settings(&mut self, _ui: &imgui::Ui, settings: &mut AppSettings) -> Result<bool> {
+        let Some((steam_id, name)) = self.ignore_toggle.take() else {
+            return Ok(false);
+        };
+
+        if settings.esp_ignored_players.remove(&steam_id).is_none() {
+            log::info!("Hiding {} ({}) in the ESP", name, steam_id);
+            settings.esp_ignored_players.insert(steam_id, name);
+        }
+        Ok(true)
+    }
+
+    fn update(&mut self, ctx: &UpdateContext) -> Result<()> {
+        self.players.clear();
+
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if !settings.team_overview {
+            return Ok(());
+        }
+
+        /* the players are read by the update thread */
+        let snapshot = ctx.states.resolve::<StateGameSnapshot>(())?;
+        for player in snapshot.0.players.iter().filter(|player| player.alive) {
+            let Some(pawn_info) = &player.info else {
+                continue;
+            };
+
+            self.players.push(PlayerOverview {
+                name: pawn_info.player_name.clone().unwrap_or_else(|| "unknown".to_string()),
+                team_id: pawn_info.team_id,
+                steam_id: pawn_info.player_steam_id,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn render(
+        &mut self,
+        states: &StateRegistry,
+        ui: &imgui::Ui,
+        unicode_text: &UnicodeTextRenderer,
+    ) -> Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+        if !settings.team_overview || self.players.is_empty() {
+            return Ok(());
+        }
+
+        let ignore_toggle = ui
+            .window("Team Overview")
+            .position([320.0, 200.0], Condition::FirstUseEver)
+            .size([300.0, 420.0], Condition::FirstUseEver)
+            .collapsible(false)
+            .bg_alpha(0.6)
+            .build(|| {
+                let ignored_players = &settings.esp_ignored_players;
+                let terrorists = self.render_team(states, ui, unicode_text, ignored_players, 2, "T");
+                ui.separator();
+                let counter_terrorists = self.render_team(states, ui, unicode_text, ignored_players, 3, "CT");
+                terrorists.or(counter_terrorists)
+            });
+
+        if let Some(Some(toggle)) = ignore_toggle {
+            self.ignore_toggle = Some(toggle);
+        }
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ use std::time::Instant;
 
 use anyhow::Context;
 use cs2::{
+    CS2Handle,
     MouseState,
     StateCS2Memory,
     StateEntityList,
@@ -20,11 +21,16 @@ use rand::{
 };
 use utils_state::StateRegistry;
 
-use super::Enhancement;
+use super::{
+    AudioEvent,
+    Enhancement,
+    StateAudioEvents,
+};
 use crate::{
     settings::AppSettings,
     view::{
         KeyToggle,
+        StateActiveToggles,
         StateLocalCrosshair,
     },
     UpdateContext,
@@ -99,6 +105,26 @@ impl TriggerBot {
 }
 
 impl Enhancement for TriggerBot {
+    fn emits_input(&self) -> bool {
+        true
+    }
+
+    fn release_input(&mut self, cs2: &CS2Handle) -> anyhow::Result<()> {
+        self.state = TriggerState::Idle;
+        if !self.trigger_active {
+            return Ok(());
+        }
+
+        let mut state = MouseState {
+            ..Default::default()
+        };
+        state.buttons[0] = Some(false);
+        cs2.send_mouse_state(&[state])?;
+        self.trigger_active = false;
+        log::trace!("Released the shoot button");
+        Ok(())
+    }
+
     fn update(&mut self, ctx: &UpdateContext) -> anyhow::Result<()> {
         let settings = ctx.states.resolve::<AppSettings>(())?;
         if self.toggle.update(
@@ -106,7 +132,7 @@ impl Enhancement for TriggerBot {
             ctx.input,
             &settings.key_trigger_bot,
         ) {
-            ctx.cs2.add_metrics_record(
+            ctx.metrics.add_metrics_record(
                 obfstr!("feature-trigger-bot-toggle"),
                 &format!(
                     "enabled: {}, mode: {:?}",
@@ -115,7 +141,9 @@ impl Enhancement for TriggerBot {
             );
         }
 
-        let should_shoot: bool = if self.toggle.enabled {
+        ctx.states.resolve_mut::<StateActiveToggles>(())?.trigger_bot = self.toggle.enabled;
+
+        let should_shoot: bool = if self.toggle.enabled && !settings.features_relaxed(ctx.states)? {
             self.should_be_active(ctx)?
         } else {
             false
@@ -197,6 +225,12 @@ impl Enhancement for TriggerBot {
             ctx.cs2.send_mouse_state(&[state])?;
             log::trace!("Setting shoot state to {}", self.trigger_active);
 
+            if self.trigger_active {
+                ctx.states
+                    .resolve_mut::<StateAudioEvents>(())?
+                    .emit(AudioEvent::TriggerBotFired);
+            }
+
             self.state = TriggerState::Sleep {
                 delay: settings.trigger_bot_shot_duration,
                 timestamp: Instant::now(),
@@ -0,0 +1,228 @@
+use std::{
+    collections::HashSet,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::Result;
+use cs2::{
+    CEntityIdentityEx,
+    ClassNameCache,
+    PlayerPawnState,
+    StateCS2Memory,
+    StateEntityList,
+    StateLocalPlayerController,
+    StatePawnGrenadeThrow,
+    StatePawnInfo,
+    WeaponId,
+};
+use cs2_schema_generated::cs2::client::CCSPlayerController;
+use imgui::ImColor32;
+use nalgebra::Vector3;
+use overlay::UnicodeTextRenderer;
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    settings::{
+        AppSettings,
+        UtilityAlertSettings,
+    },
+    utils::{
+        TextWithShadowDrawList,
+        UNITS_TO_METERS,
+    },
+    UpdateContext,
+};
+
+const ALERT_COLOR: [u8; 3] = [255, 170, 60];
+
+struct UtilityAlert {
+    text: String,
+    created: Instant,
+}
+
+/// Alerts when a nearby enemy pulls the pin of a grenade
+pub struct UtilityAlerts {
+    /// Pawns which have been throwing in the last update
+    throwing_pawns: HashSet<u32>,
+    alerts: Vec<UtilityAlert>,
+}
+
+impl UtilityAlerts {
+    pub fn new() -> Self {
+        Self {
+            throwing_pawns: HashSet::new(),
+            alerts: Vec::new(),
+        }
+    }
+
+    fn is_alert_enabled(settings: &UtilityAlertSettings, weapon: WeaponId) -> bool {
+        match weapon {
+            WeaponId::Flashbang => settings.flashbang,
+            WeaponId::Smokegrenade => settings.smoke,
+            WeaponId::HZgrenade => settings.hegrenade,
+            WeaponId::Molotov | WeaponId::Incendiary => settings.molotov,
+            WeaponId::Decoy => settings.decoy,
+            _ => false,
+        }
+    }
+}
+
+/// Compass direction from the origin to the target (+y is north, +x is east)
+fn compass_direction(origin: &Vector3<f32>, target: &Vector3<f32>) -> &'static str {
+    const DIRECTIONS: [&str; 8] = ["E", "NE", "N", "NW", "W", "SW", "S", "SE"];
+
+    let delta = target - origin;
+    let angle = delta.y.atan2(delta.x).to_degrees().rem_euclid(360.0);
+    let index = ((angle + 22.5) / 45.0) as usize % DIRECTIONS.len();
+    DIRECTIONS[index]
+}
+
+impl Enhancement for UtilityAlerts {
+    fn update(&mut self, ctx: &UpdateContext) -> Result<()> {
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if !settings.utility_alerts {
+            self.throwing_pawns.clear();
+            self.alerts.clear();
+            return Ok(());
+        }
+        let alert_settings = &settings.utility_alerts_settings;
+
+        let alert_duration = Duration::from_secs_f32(alert_settings.duration.max(0.1));
+        self.alerts
+            .retain(|alert| alert.created.elapsed() < alert_duration);
+
+        let memory = ctx.states.resolve::<StateCS2Memory>(())?;
+        let entities = ctx.states.resolve::<StateEntityList>(())?;
+        let class_name_cache = ctx.states.resolve::<ClassNameCache>(())?;
+        let local_controller = ctx.states.resolve::<StateLocalPlayerController>(())?;
+
+        let Some(local_controller) = local_controller.instance.value_reference(memory.view_arc())
+        else {
+            self.throwing_pawns.clear();
+            return Ok(());
+        };
+        let local_pawn_handle = local_controller.m_hPlayerPawn()?;
+        let Some((local_team_id, local_position)) = ctx
+            .states
+            .resolve::<StatePawnInfo>(local_pawn_handle)
+            .ok()
+            .map(|info| (info.team_id, info.position))
+        else {
+            self.throwing_pawns.clear();
+            return Ok(());
+        };
+
+        let mut throwing_pawns = HashSet::new();
+        for entity_identity in entities.entities() {
+            let entity_class = class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
+            if !entity_class
+                .map(|name| *name == "CCSPlayerController")
+                .unwrap_or(false)
+            {
+                continue;
+            }
+
+            let controller_handle = entity_identity.handle::<dyn CCSPlayerController>()?;
+            let Some(controller) = entities
+                .entity_from_handle(&controller_handle)
+                .and_then(|controller| controller.value_reference(memory.view_arc()))
+            else {
+                continue;
+            };
+
+            let pawn_handle = controller.m_hPlayerPawn()?;
+            if !pawn_handle.is_valid() || pawn_handle == local_pawn_handle {
+                continue;
+            }
+
+            if *ctx.states.resolve::<PlayerPawnState>(pawn_handle)? != PlayerPawnState::Alive {
+                continue;
+            }
+
+            let Ok(pawn_info) = ctx.states.resolve::<StatePawnInfo>(pawn_handle) else {
+                continue;
+            };
+            if pawn_info.team_id == local_team_id
+                || !Self::is_alert_enabled(alert_settings, pawn_info.weapon)
+            {
+                continue;
+            }
+
+            let distance = (pawn_info.position - local_position).norm() * UNITS_TO_METERS;
+            if distance > alert_settings.radius {
+                continue;
+            }
+
+            let Ok(grenade_throw) = ctx.states.resolve::<StatePawnGrenadeThrow>(pawn_handle) else {
+                continue;
+            };
+            if !grenade_throw.is_throwing() {
+                continue;
+            }
+
+            let pawn_entity_id = pawn_handle.get_entity_index();
+            throwing_pawns.insert(pawn_entity_id);
+            if self.throwing_pawns.contains(&pawn_entity_id) {
+                /* already alerted for this throw */
+                continue;
+            }
+
+            self.alerts.push(UtilityAlert {
+                text: format!(
+                    "{} incoming from {} ({:.0}m)",
+                    pawn_info.weapon.display_name(),
+                    compass_direction(&local_position, &pawn_info.position),
+                    distance
+                ),
+                created: Instant::now(),
+            });
+        }
+
+        self.throwing_pawns = throwing_pawns;
+        Ok(())
+    }
+
+    fn render(
+        &mut self,
+        states: &StateRegistry,
+        ui: &imgui::Ui,
+        _unicode_text: &UnicodeTextRenderer,
+    ) -> Result<()> {
+        if self.alerts.is_empty() {
+            return Ok(());
+        }
+
+        let settings = states.resolve::<AppSettings>(())?;
+        let duration = settings.utility_alerts_settings.duration.max(0.1);
+
+        let draw = ui.get_window_draw_list();
+        let display_size = ui.io().display_size;
+        let line_height = ui.text_line_height_with_spacing();
+        for (index, alert) in self.alerts.iter().enumerate() {
+            /* fade out during the last second */
+            let remaining = duration - alert.created.elapsed().as_secs_f32();
+            let alpha = remaining.clamp(0.0, 1.0);
+
+            let text_width = ui.calc_text_size(&alert.text)[0];
+            draw.add_text_with_shadow(
+                [
+                    (display_size[0] - text_width) / 2.0,
+                    display_size[1] * 0.25 + index as f32 * line_height,
+                ],
+                ImColor32::from_rgba(
+                    ALERT_COLOR[0],
+                    ALERT_COLOR[1],
+                    ALERT_COLOR[2],
+                    (alpha * 255.0) as u8,
+                ),
+                &alert.text,
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::Arc,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::Context;
+use cs2::{
+    invalidate_game_states,
+    CS2Handle,
+    StateBuildInfo,
+};
+use obfstr::obfstr;
+use utils_state::{
+    State,
+    StateRegistry,
+};
+
+use crate::notifications::{
+    notify,
+    NotificationLevel,
+};
+
+/// Interval in which the game process and its revision are checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Set up the schema provider for the running CS2 revision.
+/// Without a schema file the runtime schema is used.
+/// Members missing in either schema fall back to the built-in offsets.
+pub fn setup_schema(
+    states: &mut StateRegistry,
+    schema_file: Option<&Path>,
+    cs2_revision: &str,
+) -> anyhow::Result<()> {
+    if let Some(file) = schema_file {
+        log::info!(
+            "{} {}",
+            obfstr!("Loading CS2 schema (offsets) from file"),
+            file.display()
+        );
+        let schema_revision = cs2_schema_provider_impl::setup_schema_from_file(states, file)
+            .context("file schema setup")?;
+        if schema_revision != cs2_revision {
+            notify(
+                states,
+                NotificationLevel::Warning,
+                format!(
+                    "Schema file is outdated (dumped from revision {}, running revision {})",
+                    schema_revision, cs2_revision
+                ),
+            );
+        }
+    } else {
+        log::info!(
+            "{}",
+            obfstr!("Loading CS2 schema (offsets) from CS2 schema system")
+        );
+        match cs2_schema_provider_impl::RuntimeSchemaProvider::new(states) {
+            Ok(provider) => cs2_schema_provider_impl::setup_provider(Box::new(
+                cs2_schema_provider_impl::FallbackSchemaProvider::new(Box::new(provider)),
+            )),
+            Err(err) => {
+                /* the schema system layout changed, keep the core features working with the built-in offsets */
+                log::warn!("Failed to load the runtime schema: {:#}", err);
+                notify(
+                    states,
+                    NotificationLevel::Warning,
+                    "Failed to load the CS2 schema. Using built-in offsets, some features might not work.",
+                );
+                cs2_schema_provider_impl::setup_provider(Box::new(
+                    cs2_schema_provider_impl::DefaultSchemaProvider,
+                ));
+            }
+        }
+    }
+
+    log::info!("CS2 schema (offsets) loaded.");
+    Ok(())
+}
+
+/// Detects the game being closed, restarted or updated while the controller is running.
+/// The schema and all cached game states are reloaded instead of failing every read until a restart.
+pub struct GameWatcher {
+    cs2: Arc<CS2Handle>,
+    schema_file: Option<PathBuf>,
+
+    revision: String,
+    last_check: Instant,
+
+    /// The game process is gone or has not been reloaded yet.
+    /// Game states can not be read in the meantime.
+    detached: bool,
+    /// The game states have been reloaded since the last call to take_reloaded
+    reloaded: bool,
+}
+
+impl GameWatcher {
+    pub fn new(cs2: Arc<CS2Handle>, schema_file: Option<PathBuf>, revision: String) -> Self {
+        Self {
+            cs2,
+            schema_file,
+
+            revision,
+            last_check: Instant::now(),
+
+            detached: false,
+            reloaded: false,
+        }
+    }
+
+    pub fn detached(&self) -> bool {
+        self.detached
+    }
+
+    pub fn take_reloaded(&mut self) -> bool {
+        std::mem::take(&mut self.reloaded)
+    }
+
+    pub fn update(&mut self, states: &mut StateRegistry) {
+        if self.last_check.elapsed() < CHECK_INTERVAL {
+            return;
+        }
+        self.last_check = Instant::now();
+
+        if self.detached {
+            self.try_reattach(states);
+            return;
+        }
+
+        match self.cs2.process_running() {
+            Ok(true) => {}
+            Ok(false) => {
+                self.detached = true;
+                notify(
+                    states,
+                    NotificationLevel::Warning,
+                    "CS2 has been closed. Waiting for it to start again.",
+                );
+                return;
+            }
+            Err(err) => {
+                log::debug!("Failed to check the CS2 process: {:#}", err);
+                return;
+            }
+        }
+
+        /* the cached build info is persistent, read it again */
+        let revision = match StateBuildInfo::create(states, ()) {
+            Ok(build_info) => build_info.revision,
+            Err(err) => {
+                log::debug!("Failed to read the CS2 build info: {:#}", err);
+                return;
+            }
+        };
+        if revision == self.revision {
+            return;
+        }
+
+        log::info!(
+            "CS2 revision changed from {} to {}. Reloading the schema.",
+            self.revision,
+            revision
+        );
+        self.detached = true;
+        self.try_reattach(states);
+    }
+
+    fn try_reattach(&mut self, states: &mut StateRegistry) {
+        if let Err(err) = self.cs2.reattach() {
+            log::trace!("Failed to reattach to CS2: {:#}", err);
+            return;
+        }
+
+        match self.reload(states) {
+            Ok(()) => {
+                self.detached = false;
+                self.reloaded = true;
+                notify(
+                    states,
+                    NotificationLevel::Info,
+                    format!("Attached to CS2 revision {}", self.revision),
+                );
+            }
+            Err(err) => {
+                /* the game might still be loading, try again on the next check */
+                log::debug!("Failed to reload the CS2 schema: {:#}", err);
+            }
+        }
+    }
+
+    fn reload(&mut self, states: &mut StateRegistry) -> anyhow::Result<()> {
+        invalidate_game_states(states);
+
+        let revision = states
+            .resolve::<StateBuildInfo>(())
+            .context("build info")?
+            .revision
+            .clone();
+        setup_schema(states, self.schema_file.as_deref(), &revision)?;
+
+        self.revision = revision;
+        Ok(())
+    }
+}
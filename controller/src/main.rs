@@ -5,14 +5,13 @@
 use image::GenericImageView;
 use imgui::TextureId;
 use std::{
-    cell::{
-        Ref,
-        RefCell,
-        RefMut,
-    },
+    cell::RefCell,
     error::Error,
     fmt::Debug,
-    path::PathBuf,
+    path::{
+        Path,
+        PathBuf,
+    },
     rc::Rc,
     sync::{
         atomic::{
@@ -24,31 +23,44 @@ use std::{
     time::{
         Duration,
         Instant,
+        SystemTime,
+        UNIX_EPOCH,
     },
     collections::HashMap,
 };
 use anyhow::Context;
-use clap::Parser;
+use clap::{
+    Parser,
+    Subcommand,
+};
 use cs2::{
     CS2Handle,
-    ConVars,
+    DriverBackend,
+    EntityLifecycleTracker,
     InterfaceError,
+    MemoryBackend,
+    RecordingBackend,
+    ReplayBackend,
     StateBuildInfo,
     StateCS2Handle,
     StateCS2Memory,
+    StateConVar,
+    SyntheticBackend,
 };
 use enhancements::{
     Enhancement,
+    EnhancementProfiler,
+    FrameBudgetWatchdog,
     GrenadeHelper,
 };
 use imgui::{
     Condition,
+    ConfigFlags,
     FontConfig,
     FontGlyphRanges,
     FontId,
     FontSource,
     Key,
-    StyleColor,
     Ui,
 };
 use obfstr::obfstr;
@@ -62,21 +74,43 @@ use overlay::{
     VulkanError,
 };
 use settings::{
+    get_logs_dir,
+    get_recordings_dir,
     load_app_settings,
     AppSettings,
+    FeatureToggle,
+    SettingsAutosave,
     SettingsUI,
 };
 use tokio::runtime;
-use utils::show_critical_error;
-use utils_state::{StateRegistry, State, StateCacheType};
-use view::ViewController;
+use utils::{
+    init_logging,
+    load_weapon_icon_atlas,
+    show_critical_error,
+    system_ui_scale,
+    StateInteractiveRegions,
+    StateSteamProfiles,
+    WeaponIconAtlas,
+};
+use utils_state::{StateRegistry, State, StateCacheType, StateRef, StateRefMut};
+use view::{
+    FeatureKeyToggles,
+    StateActiveToggles,
+    ViewController,
+};
 use windows::Win32::UI::Shell::IsUserAnAdmin;
 use windows::Win32::UI::Input::KeyboardAndMouse::{
     GetAsyncKeyState,
     VIRTUAL_KEY,
 };
-
-use tiny_skia::Pixmap;
+use windows::Win32::{
+    System::Console::GetConsoleWindow,
+    UI::WindowsAndMessaging::{
+        ShowWindow,
+        SW_MINIMIZE,
+        SW_RESTORE,
+    },
+};
 
 use crate::{
     enhancements::{
@@ -87,22 +121,52 @@ use crate::{
         SpectatorsListIndicator,
         TriggerBot,
         SniperCrosshair,
+        CustomCrosshair,
         GrenadeTrajectory,
         LegitAim,
+        MeasurementTool,
+        RoundHud,
+        TeamEconomy,
+        UtilityAlerts,
+        TeamOverview,
+        SessionEventLog,
+        MovementHud,
+        KeybindOverlay,
+        GameStateApi,
+        MatchStatistics,
+        DiscordPresence,
+        AudioAlerts,
+        RenderLayer,
         model_renderer::CharacterModel,
     },
-    settings::{
-        save_app_settings,
-        HotKey,
+    game_watcher::GameWatcher,
+    metrics::Metrics,
+    notifications::{
+        notify,
+        NotificationLevel,
+        StateNotifications,
     },
+    settings::HotKey,
+    update_thread::{
+        StateGameSnapshot,
+        UpdateThread,
+    },
+    updater::Updater,
     utils::TextWithShadowUi,
     winver::version_info,
 };
+mod benchmark;
+mod crash;
 mod dialog;
 mod enhancements;
+mod game_watcher;
+mod metrics;
+mod notifications;
 mod settings;
 mod utils;
 mod view;
+mod update_thread;
+mod updater;
 mod winver;
 // --- ADDED SECURITY MODULE ---
 // mod security; // DISABLED FOR UPSTREAM COMPATIBILITY
@@ -110,11 +174,6 @@ mod winver;
 pub trait MetricsClient {
     fn add_metrics_record(&self, record_type: &str, record_payload: &str);
 }
-impl MetricsClient for CS2Handle {
-    fn add_metrics_record(&self, record_type: &str, record_payload: &str) {
-        self.add_metrics_record(record_type, record_payload)
-    }
-}
 pub trait KeyboardInput {
     fn is_key_down(&self, key: imgui::Key) -> bool;
     fn is_key_pressed(&self, key: imgui::Key, repeating: bool) -> bool;
@@ -132,10 +191,22 @@ impl KeyboardInput for imgui::Ui {
     }
 }
 
+/// Keyboard input used in headless mode where no key is ever pressed
+struct HeadlessInput;
+impl KeyboardInput for HeadlessInput {
+    fn is_key_down(&self, _key: imgui::Key) -> bool {
+        false
+    }
+    fn is_key_pressed(&self, _key: imgui::Key, _repeating: bool) -> bool {
+        false
+    }
+}
+
 pub struct UpdateContext<'a> {
     pub input: &'a dyn KeyboardInput,
     pub states: &'a StateRegistry,
     pub cs2: &'a Arc<CS2Handle>,
+    pub metrics: &'a dyn MetricsClient,
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -162,7 +233,9 @@ pub struct AppFonts {
 #[derive(Clone)]
 pub struct AppResources {
     pub character_model: Option<CharacterModel>,
-    pub weapon_icons: HashMap<String, TextureId>,
+    pub weapon_icons: WeaponIconAtlas,
+    /// Rank icons (e.g. competitive_7, premier_3) including their dimensions
+    pub rank_icons: HashMap<String, (TextureId, (u32, u32))>,
     pub character_texture: Option<(TextureId, (u32, u32))>,
     pub cog_texture_id: Option<TextureId>,
     pub esp_preview_skeleton_texture_id: Option<(TextureId, (u32, u32))>,
@@ -177,6 +250,96 @@ pub struct AppResources {
     pub cs2: Arc<CS2Handle>,
 }
 
+const WEAPON_ICONS_PATH: &str = "resources/weapon_icons";
+const RANK_ICONS_PATH: &str = "resources/rank_icons";
+
+/// RGBA data and dimensions of all PNG rank icons, identified by their file name without the extension.
+/// Returns None if the rank icon directory does not exist.
+fn read_rank_icons() -> Option<HashMap<String, (Vec<u8>, (u32, u32))>> {
+    let entries = std::fs::read_dir(RANK_ICONS_PATH).ok()?;
+
+    let mut icons = HashMap::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let ext = path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+        if ext.as_deref() != Some("png") {
+            continue;
+        }
+
+        let Some(file_stem) = path.file_stem().and_then(|s| s.to_str()) else { continue; };
+        match image::open(&path) {
+            Ok(img) => {
+                let rgba = img.to_rgba8();
+                let dimensions = rgba.dimensions();
+                icons.insert(file_stem.to_string(), (rgba.into_raw(), dimensions));
+            }
+            Err(e) => log::error!("Failed to load rank icon {}: {}", path.display(), e),
+        }
+    }
+
+    Some(icons)
+}
+
+impl AppResources {
+    pub fn new(cs2: Arc<CS2Handle>) -> Self {
+        Self {
+            character_model: None,
+            weapon_icons: WeaponIconAtlas::default(),
+            rank_icons: HashMap::new(),
+            character_texture: None,
+            cog_texture_id: None,
+            esp_preview_skeleton_texture_id: None,
+            esp_preview_head_texture_id: None,
+            esp_preview_health_lr_texture_id: None,
+            esp_preview_health_bt_texture_id: None,
+            esp_preview_name_texture_id: None,
+            esp_preview_gun_texture_id: None,
+            esp_preview_distance_texture_id: None,
+            esp_preview_ammo_texture_id: None,
+            esp_preview_box_texture_id: None,
+            cs2,
+        }
+    }
+
+    /// Reload the weapon and rank icons from disk.
+    /// Existing textures are updated in place and textures of deleted icons are freed.
+    pub fn reload_icons(&mut self, controller: &mut SystemRuntimeController) {
+        if let Some(texture_id) = self.weapon_icons.texture_id() {
+            let atlas = load_weapon_icon_atlas(Path::new(WEAPON_ICONS_PATH), |data, width, height| {
+                controller.queue_texture_update(texture_id, data.to_vec(), width, height);
+                Ok(texture_id)
+            });
+            match atlas {
+                Ok(atlas) if atlas.is_empty() => {
+                    controller.queue_texture_removal(texture_id);
+                    self.weapon_icons = atlas;
+                }
+                Ok(atlas) => self.weapon_icons = atlas,
+                Err(e) => log::error!("Failed to reload the weapon icons: {:#}", e),
+            }
+        }
+
+        let mut icons = read_rank_icons().unwrap_or_default();
+        self.rank_icons.retain(|name, (texture_id, dimensions)| match icons.remove(name) {
+            Some((data, new_dimensions)) => {
+                controller.queue_texture_update(*texture_id, data, new_dimensions.0, new_dimensions.1);
+                *dimensions = new_dimensions;
+                true
+            }
+            None => {
+                controller.queue_texture_removal(*texture_id);
+                false
+            }
+        });
+
+        if !icons.is_empty() {
+            /* new textures can only be uploaded on startup */
+            log::warn!("{} new rank icons will be loaded after a restart.", icons.len());
+        }
+        log::info!("Reloaded {} weapon icons and {} rank icons.", self.weapon_icons.len(), self.rank_icons.len());
+    }
+}
+
 impl State for AppResources {
     type Parameter = ();
 
@@ -190,38 +353,152 @@ pub struct Application {
     pub resources: AppResources,
     pub app_state: StateRegistry,
     pub cs2: Arc<CS2Handle>,
+    pub metrics: Metrics,
+    pub game_watcher: GameWatcher,
+    pub update_thread: UpdateThread,
     pub enhancements: Vec<Rc<RefCell<dyn Enhancement>>>,
+    pub frame_budget: RefCell<FrameBudgetWatchdog>,
+    pub profiler: RefCell<EnhancementProfiler>,
+    pub entity_tracker: EntityLifecycleTracker,
+    pub feature_toggles: FeatureKeyToggles,
     pub frame_read_calls: usize,
     pub last_total_read_calls: usize,
     pub settings_visible: bool,
     pub settings_visibility_changed: AtomicBool,
     pub settings_key_warning_visible: RefCell<bool>,
     pub settings_dirty: bool,
+    pub settings_autosave: SettingsAutosave,
     pub settings_ui: RefCell<SettingsUI>,
     pub settings_screen_capture_changed: AtomicBool,
     pub settings_render_debug_window_changed: AtomicBool,
+    pub settings_theme_changed: AtomicBool,
+    pub settings_ui_scale_changed: AtomicBool,
+    pub settings_overlay_monitor_changed: AtomicBool,
+    pub settings_reload_icons: AtomicBool,
+    pub settings_capture_output_changed: AtomicBool,
+    pub settings_metrics_changed: AtomicBool,
+    pub updater: Updater,
+    pub update_check_requested: AtomicBool,
+    pub update_install_requested: AtomicBool,
+    /// Scale of the system DPI, used if the user has not set a UI scale
+    pub system_ui_scale: f32,
     pub menu_key_was_down: bool,
+    /// The menu is closed and the last frame did not draw anything
+    pub overlay_idle: bool,
+    /// All rendering and enhancement updates are suspended
+    pub panic_mode: bool,
     pub is_initialized: AtomicBool,
 }
 
+fn set_console_minimized(minimized: bool) {
+    unsafe {
+        let console = GetConsoleWindow();
+        if console.0 != 0 {
+            ShowWindow(console, if minimized { SW_MINIMIZE } else { SW_RESTORE });
+        }
+    }
+}
+
 impl Application {
-    pub fn settings(&self) -> Ref<'_, AppSettings> {
+    pub fn new(
+        fonts: AppFonts,
+        resources: AppResources,
+        mut app_state: StateRegistry,
+        cs2: Arc<CS2Handle>,
+        metrics: Metrics,
+        game_watcher: GameWatcher,
+    ) -> Self {
+        app_state.set(resources.clone(), ()).expect("Failed to set resources in state");
+        let saved_settings = app_state.resolve::<AppSettings>(()).ok().map(|settings| (*settings).clone());
+        let update_tick_rate = saved_settings.as_ref().map(|settings| settings.update_tick_rate).unwrap_or(update_thread::DEFAULT_UPDATE_TICK_RATE);
+        let update_thread = UpdateThread::spawn(cs2.clone(), update_tick_rate).expect("Failed to spawn the update thread");
+
+        Self {
+            fonts,
+            resources,
+            app_state,
+            cs2,
+            metrics,
+            game_watcher,
+            update_thread,
+            enhancements: vec![
+                Rc::new(RefCell::new(AntiAimPunsh::new())),
+                Rc::new(RefCell::new(PlayerESP::new())),
+                Rc::new(RefCell::new(SpectatorsListIndicator::new())),
+                Rc::new(RefCell::new(BombInfoIndicator::new())),
+                Rc::new(RefCell::new(BombLabelIndicator::new())),
+                Rc::new(RefCell::new(TriggerBot::new())),
+                Rc::new(RefCell::new(GrenadeHelper::new())),
+                Rc::new(RefCell::new(SniperCrosshair::new())),
+                Rc::new(RefCell::new(CustomCrosshair::new())),
+                Rc::new(RefCell::new(GrenadeTrajectory::new())),
+                Rc::new(RefCell::new(LegitAim::new())),
+                Rc::new(RefCell::new(MeasurementTool::new())),
+                Rc::new(RefCell::new(RoundHud::new())),
+                Rc::new(RefCell::new(TeamEconomy::new())),
+                Rc::new(RefCell::new(UtilityAlerts::new())),
+                Rc::new(RefCell::new(TeamOverview::new())),
+                Rc::new(RefCell::new(SessionEventLog::new())),
+                Rc::new(RefCell::new(MovementHud::new())),
+                Rc::new(RefCell::new(KeybindOverlay::new())),
+                Rc::new(RefCell::new(GameStateApi::new())),
+                Rc::new(RefCell::new(MatchStatistics::new())),
+                Rc::new(RefCell::new(DiscordPresence::new())),
+                Rc::new(RefCell::new(AudioAlerts::new())),
+            ],
+            frame_budget: RefCell::new(FrameBudgetWatchdog::new()),
+            profiler: RefCell::new(EnhancementProfiler::new()),
+            entity_tracker: EntityLifecycleTracker::new(),
+            feature_toggles: Default::default(),
+            last_total_read_calls: 0,
+            frame_read_calls: 0,
+            settings_visible: true,
+            settings_visibility_changed: AtomicBool::new(true),
+            settings_key_warning_visible: RefCell::new(false),
+            settings_dirty: false,
+            settings_autosave: SettingsAutosave::new(saved_settings),
+            settings_ui: RefCell::new(SettingsUI::new()),
+            settings_screen_capture_changed: AtomicBool::new(true),
+            settings_render_debug_window_changed: AtomicBool::new(true),
+            settings_theme_changed: AtomicBool::new(true),
+            settings_ui_scale_changed: AtomicBool::new(true),
+            settings_overlay_monitor_changed: AtomicBool::new(true),
+            settings_reload_icons: AtomicBool::new(false),
+            settings_capture_output_changed: AtomicBool::new(true),
+            settings_metrics_changed: AtomicBool::new(false),
+            updater: Updater::new(),
+            update_check_requested: AtomicBool::new(false),
+            update_install_requested: AtomicBool::new(false),
+            system_ui_scale: system_ui_scale(),
+            menu_key_was_down: false,
+            overlay_idle: false,
+            panic_mode: false,
+            is_initialized: AtomicBool::new(false),
+        }
+    }
+
+    pub fn settings(&self) -> StateRef<'_, AppSettings> {
         self.app_state.get::<AppSettings>(()).expect("app settings to be present")
     }
-    pub fn settings_mut(&self) -> RefMut<'_, AppSettings> {
+    pub fn settings_mut(&self) -> StateRefMut<'_, AppSettings> {
         self.app_state.get_mut::<AppSettings>(()).expect("app settings to be present")
     }
 
+    /// Show a toast on the overlay and log the message
+    pub fn notify(&self, level: NotificationLevel, message: impl Into<String>) {
+        notify(&self.app_state, level, message);
+    }
+
     pub fn load_settings_from_path(&self, path: PathBuf) {
         log::info!("Attempting to load settings from: {:?}", path);
         match std::fs::read_to_string(path) {
             Ok(file_contents) => {
                 match serde_yaml::from_str::<AppSettings>(&file_contents) {
-                    Ok(new_settings) => { *self.settings_mut() = new_settings; log::info!("Settings loaded successfully."); }
-                    Err(e) => { log::error!("Failed to parse config file: {}", e); }
+                    Ok(new_settings) => { *self.settings_mut() = new_settings; self.notify(NotificationLevel::Info, "Config loaded"); }
+                    Err(e) => { self.notify(NotificationLevel::Error, format!("Failed to parse config file: {}", e)); }
                 }
             }
-            Err(e) => { log::error!("Failed to read config file: {}", e); }
+            Err(e) => { self.notify(NotificationLevel::Error, format!("Failed to read config file: {}", e)); }
         }
     }
 
@@ -229,34 +506,54 @@ impl Application {
         log::info!("Attempting to save settings to: {:?}", path);
         match serde_yaml::to_string(&*self.settings()) {
             Ok(yaml_string) => {
-                if let Err(e) = std::fs::write(path, yaml_string) { log::error!("Failed to write config file: {}", e); } 
-                else { log::info!("Settings saved successfully."); }
+                if let Err(e) = std::fs::write(path, yaml_string) { self.notify(NotificationLevel::Error, format!("Failed to write config file: {}", e)); } 
+                else { self.notify(NotificationLevel::Info, "Config saved"); }
             }
-            Err(e) => { log::error!("Failed to serialize settings: {}", e); }
+            Err(e) => { self.notify(NotificationLevel::Error, format!("Failed to serialize settings: {}", e)); }
         }
     }
 
     pub fn reset_settings(&self) {
         log::info!("Resetting settings to default.");
         *self.settings_mut() = AppSettings::default();
+        self.settings_theme_changed.store(true, Ordering::Relaxed);
+        self.settings_ui_scale_changed.store(true, Ordering::Relaxed);
+        self.settings_overlay_monitor_changed.store(true, Ordering::Relaxed);
+        self.settings_capture_output_changed.store(true, Ordering::Relaxed);
+        self.settings_metrics_changed.store(true, Ordering::Relaxed);
     }
 
     pub fn pre_update(&mut self, controller: &mut SystemRuntimeController) -> anyhow::Result<()> {
         if self.settings_dirty {
             self.settings_dirty = false;
-            let mut settings = self.settings_mut();
+            /* only borrow the state registry, the autosave is updated while the settings are borrowed */
+            let mut settings = self.app_state.get_mut::<AppSettings>(()).expect("app settings to be present");
 
-            settings.imgui = None;
-            if let Ok(value) = serde_json::to_string(&*settings) { self.cs2.add_metrics_record("settings-updated", &value); }
+            if let Ok(value) = serde_json::to_string(&settings.metrics_snapshot()) { self.metrics.add_metrics_record("settings-updated", &value); }
+            crash::update_enabled_features(&settings);
 
             let mut imgui_settings = String::new();
             controller.imgui.save_ini_settings(&mut imgui_settings);
             settings.imgui = Some(imgui_settings);
 
-            if let Err(error) = save_app_settings(&*settings) { log::warn!("Failed to save user settings: {}", error); };
+            self.settings_autosave.save(&*settings);
+        } else {
+            let settings = self.app_state.get::<AppSettings>(()).expect("app settings to be present");
+            self.settings_autosave.save_if_changed(&*settings);
+        }
+
+        for error in self.settings_autosave.take_errors() {
+            self.notify(NotificationLevel::Warning, format!("Failed to save user settings: {:#}", error));
         }
 
-        controller.set_passthrough(!self.settings_visible);
+        let interactive_widget_hovered = !self.settings_visible
+            && self.settings().interactive_widgets
+            && self
+                .app_state
+                .resolve::<StateInteractiveRegions>(())
+                .map_or(false, |regions| regions.contains(controller.imgui.io().mouse_pos));
+        controller.set_passthrough(self.panic_mode || (!self.settings_visible && !interactive_widget_hovered));
+        self.overlay_idle = !self.settings_visible && controller.last_frame_empty();
 
         if self.settings_screen_capture_changed.swap(false, Ordering::Relaxed) {
             let settings = self.settings();
@@ -269,10 +566,128 @@ impl Application {
             controller.toggle_debug_overlay(settings.render_debug_window);
         }
 
+        if self.settings_overlay_monitor_changed.swap(false, Ordering::Relaxed) {
+            controller.set_target_monitor(self.settings().overlay_monitor.clone());
+        }
+
+        if self.settings_capture_output_changed.swap(false, Ordering::Relaxed) {
+            let options = self.settings().capture_output.capture_options();
+            if options.is_some() && !controller.capture_supported() {
+                self.notify(NotificationLevel::Warning, "The capture output requires the DirectX renderer");
+            } else {
+                controller.set_capture(options);
+            }
+        }
+
+        if self.settings_metrics_changed.swap(false, Ordering::Relaxed) {
+            let settings = self.app_state.get::<AppSettings>(()).expect("app settings to be present");
+            self.metrics.apply_settings(&settings);
+        }
+
+        if self.update_check_requested.swap(false, Ordering::Relaxed) {
+            let feed_url = self.settings().update_feed_url.clone();
+            self.updater.check(&feed_url);
+        }
+
+        if self.update_install_requested.swap(false, Ordering::Relaxed) {
+            self.updater.install();
+        }
+
+        if let Some((level, message)) = self.updater.poll() {
+            self.notify(level, message);
+        }
+
+        if self.settings_reload_icons.swap(false, Ordering::Relaxed) {
+            self.resources.reload_icons(controller);
+            /* enhancements resolve the resources from the state registry */
+            self.app_state.set(self.resources.clone(), ())?;
+        }
+
+        if let Some(monitor) = controller.take_monitor_changed() {
+            /* monitors may use different DPI settings */
+            self.system_ui_scale = monitor.dpi_scale;
+            self.settings_ui_scale_changed.store(true, Ordering::Relaxed);
+        }
+
+        let theme_changed = self.settings_theme_changed.swap(false, Ordering::Relaxed);
+        let ui_scale_changed = self.settings_ui_scale_changed.swap(false, Ordering::Relaxed);
+        if theme_changed || ui_scale_changed {
+            let settings = self.settings();
+            let ui_scale = settings.ui_scale(self.system_ui_scale);
+            controller.imgui_fonts.set_scale(ui_scale);
+
+            /* sizes are scaled relative to the base style */
+            let style = controller.imgui.style_mut();
+            apply_custom_style(style);
+            style.scale_all_sizes(ui_scale);
+            settings.theme.apply(style);
+            log::debug!("Applying theme {} with a UI scale of {:.2}", settings.theme.name, ui_scale);
+        }
+
+        {
+            let api_key = self.settings().steam_api_key.clone();
+            let mut steam_profiles = self.app_state.resolve_mut::<StateSteamProfiles>(())?;
+            steam_profiles.update(controller, api_key.as_deref());
+        }
+
+        self.settings_ui
+            .get_mut()
+            .update_model_preview(controller, self.resources.character_model.as_ref());
+
         Ok(())
     }
 
+    fn update_panic_mode(&mut self, ui: &imgui::Ui) {
+        let (panic_key, minimize_console) = {
+            let settings = self.settings();
+            (settings.key_panic, settings.panic_minimize_console)
+        };
+        let Some(panic_key) = panic_key else {
+            return;
+        };
+        if !ui.is_key_pressed_no_repeat(panic_key.0) {
+            return;
+        }
+
+        self.panic_mode = !self.panic_mode;
+        log::debug!("Panic mode {}", if self.panic_mode { "enabled" } else { "disabled" });
+
+        if self.panic_mode {
+            /* enhancements do not update anymore, held buttons would never be released */
+            for enhancement in self.enhancements.iter() {
+                let mut enhancement = enhancement.borrow_mut();
+                if let Err(err) = enhancement.release_input(&self.cs2) {
+                    log::warn!("Failed to release the input of {}: {:#}", enhancement.name(), err);
+                }
+            }
+        }
+
+        if minimize_console {
+            set_console_minimized(self.panic_mode);
+        }
+    }
+
+    /// Start with the menu closed, without the intro and with a minimized console,
+    /// so the controller can be started in the background (e.g. with Windows).
+    fn start_hidden(&mut self) {
+        self.settings_visible = false;
+        self.settings_visibility_changed.store(true, Ordering::Relaxed);
+        self.settings_ui.get_mut().skip_intro();
+        set_console_minimized(true);
+    }
+
     pub fn update(&mut self, ui: &imgui::Ui) -> anyhow::Result<()> {
+        /* checked before anything else so the panic key always reacts within one frame */
+        self.update_panic_mode(ui);
+        if self.panic_mode {
+            self.update_thread.set_suspended(true);
+            return Ok(());
+        }
+
+        if !self.update_game_watcher() {
+            return Ok(());
+        }
+
         if self.app_state.resolve::<ViewController>(()).is_err() {
             self.app_state.invalidate_states();
             return Ok(());
@@ -298,7 +713,7 @@ impl Application {
             self.settings_visible = !self.settings_visible;
             self.settings_visibility_changed
                 .store(true, Ordering::Relaxed);
-            self.cs2.add_metrics_record(
+            self.metrics.add_metrics_record(
                 "settings-toggled",
                 &format!("visible: {}", self.settings_visible),
             );
@@ -310,23 +725,95 @@ impl Application {
         self.menu_key_was_down = menu_key_is_down;
 
         self.app_state.invalidate_states();
-
         if let Ok(mut view_controller) = self.app_state.resolve_mut::<ViewController>(()) {
             view_controller.update_screen_bounds(mint::Vector2::from_slice(&ui.io().display_size));
         }
 
+        self.update_enhancements(ui, true)
+    }
+
+    /// Update the game state without any overlay or user input
+    pub fn update_headless(&mut self) -> anyhow::Result<()> {
+        if !self.update_game_watcher() {
+            return Ok(());
+        }
+
+        if self.app_state.resolve::<ViewController>(()).is_err() {
+            self.app_state.invalidate_states();
+            return Ok(());
+        }
+
+        self.app_state.invalidate_states();
+        self.update_enhancements(&HeadlessInput, false)
+    }
+
+    /// Returns false while the game is detached and its states can not be read
+    fn update_game_watcher(&mut self) -> bool {
+        self.game_watcher.update(&mut self.app_state);
+        if self.game_watcher.take_reloaded() {
+            self.update_thread.invalidate_game_states();
+        }
+
+        let attached = !self.game_watcher.detached();
+        self.update_thread.set_suspended(!attached);
+        attached
+    }
+
+    /// Update all enhancements. Enhancements emitting input are skipped unless `emit_input` is set.
+    fn update_enhancements(&mut self, input: &dyn KeyboardInput, emit_input: bool) -> anyhow::Result<()> {
+        if let Err(err) = self.entity_tracker.cleanup_stale_states(&mut self.app_state) {
+            log::debug!("Failed to clean up stale entity states: {:#}", err);
+        }
+
+        {
+            let settings = self.app_state.get::<AppSettings>(()).expect("app settings to be present");
+            let mut active_toggles = self.app_state.resolve_mut::<StateActiveToggles>(())?;
+            self.feature_toggles.update(&settings.feature_hotkeys, input, &mut active_toggles);
+            self.update_thread.set_tick_rate(settings.update_tick_rate);
+        }
+
+        /* enhancements read the player list of the latest snapshot from the update thread */
+        self.app_state.set(StateGameSnapshot(self.update_thread.snapshot()), ())?;
+
         let update_context = UpdateContext {
             cs2: &self.cs2,
+            metrics: &self.metrics,
             states: &self.app_state,
-            input: ui,
+            input,
         };
 
-        for enhancement in self.enhancements.iter() {
+        let budgets = {
+            let settings = self.settings();
+            self.enhancements
+                .iter()
+                .map(|enhancement| settings.frame_budget(enhancement.borrow().name()))
+                .collect::<Vec<_>>()
+        };
+
+        let mut frame_budget = self.frame_budget.borrow_mut();
+        let mut profiler = self.profiler.borrow_mut();
+        for (index, enhancement) in self.enhancements.iter().enumerate() {
             let mut enhancement = enhancement.borrow_mut();
+            if !emit_input && enhancement.emits_input() {
+                continue;
+            }
+
+            let frame_time = profiler.begin_frame(index, enhancement.name());
+            /* degrading input would drop mouse events, only the rendering of these enhancements gets skipped */
+            if !frame_budget.begin_frame(index, enhancement.name(), frame_time, budgets[index]) && !enhancement.emits_input() {
+                continue;
+            }
+
+            let update_start = Instant::now();
             enhancement.update(&update_context)?;
+            profiler.record_update(index, update_start.elapsed());
+        }
+
+        for message in frame_budget.take_notifications() {
+            self.notify(NotificationLevel::Warning, message);
         }
 
-        let read_calls = self.cs2.ke_interface.total_read_calls();
+        let read_calls = self.cs2.total_read_calls();
         self.frame_read_calls = read_calls - self.last_total_read_calls;
         self.last_total_read_calls = read_calls;
 
@@ -334,18 +821,55 @@ impl Application {
     }
 
     pub fn render(&mut self, ui: &imgui::Ui, unicode_text: &UnicodeTextRenderer) {
-        if !self.is_initialized.load(Ordering::Relaxed) {
+        if !self.is_initialized.load(Ordering::Relaxed) || self.panic_mode {
             return;
         }
 
         // Invalidate volatile state cache right before rendering to get freshest possible player positions
         self.app_state.invalidate_states();
 
-        ui.window("overlay").draw_background(false).no_decoration().no_inputs().size(ui.io().display_size, Condition::Always).position([0.0, 0.0], Condition::Always).build(|| self.render_overlay(ui, unicode_text));
+        if let Ok(mut regions) = self.app_state.resolve_mut::<StateInteractiveRegions>(()) {
+            regions.clear();
+        }
 
-        for enhancement in self.enhancements.iter() {
-            let mut enhancement = enhancement.borrow_mut();
-            if let Err(err) = enhancement.render_debug_window(&self.app_state, ui, unicode_text) { log::error!("{:?}", err); }
+        let (layers, debug_visible) = {
+            let settings = self.settings();
+            let render_layers = &settings.render_layers;
+
+            let mut layers = [None; RenderLayer::ALL.len()];
+            let visible_layers = render_layers
+                .draw_order()
+                .filter(|layer| render_layers.is_visible(*layer));
+            for (slot, layer) in layers.iter_mut().zip(visible_layers) {
+                *slot = Some(layer);
+            }
+            (layers, render_layers.debug)
+        };
+
+        /* all layers share one window, the draw order within the window defines the z-order */
+        ui.window("overlay")
+            .draw_background(false)
+            .no_decoration()
+            .no_inputs()
+            .focus_on_appearing(false)
+            .bring_to_front_on_focus(false)
+            .size(ui.io().display_size, Condition::Always)
+            .position([0.0, 0.0], Condition::Always)
+            .build(|| {
+                for layer in layers.into_iter().flatten() {
+                    self.render_overlay(ui, unicode_text, layer);
+                }
+            });
+
+        if debug_visible {
+            for enhancement in self.enhancements.iter() {
+                let mut enhancement = enhancement.borrow_mut();
+                if let Err(err) = enhancement.render_debug_window(&self.app_state, ui, unicode_text) { log::error!("{:?}", err); }
+            }
+
+            if self.settings().render_debug_window {
+                self.profiler.borrow_mut().render_window(ui);
+            }
         }
 
         let mut settings_ui = self.settings_ui.borrow_mut();
@@ -381,11 +905,15 @@ impl Application {
         });
     }
 
-    fn render_overlay(&self, ui: &imgui::Ui, unicode_text: &UnicodeTextRenderer) {
+    fn render_overlay(&self, ui: &imgui::Ui, unicode_text: &UnicodeTextRenderer, layer: RenderLayer) {
         let settings = self.settings();
         let window_size = ui.window_size();
 
-        if settings.labh_watermark {
+        let watermark_active = self
+            .app_state
+            .resolve::<StateActiveToggles>(())
+            .map_or(true, |toggles| toggles.feature_active(FeatureToggle::Watermark));
+        if layer == RenderLayer::Hud && settings.labh_watermark && watermark_active {
             let text_buf;
             let title_text = obfstr!(text_buf = "LABH Overlay");
             let fps_text = format!("{:.2} FPS", ui.io().framerate);
@@ -408,9 +936,26 @@ impl Application {
             });
         }
 
-        for enhancement in self.enhancements.iter() {
+        let frame_budget = self.frame_budget.borrow();
+        let mut profiler = self.profiler.borrow_mut();
+        for (index, enhancement) in self.enhancements.iter().enumerate() {
+            if frame_budget.is_skipped(index) {
+                continue;
+            }
+
             let mut hack = enhancement.borrow_mut();
+            if hack.render_layer() != layer {
+                continue;
+            }
+
+            let render_start = Instant::now();
             if let Err(err) = hack.render(&self.app_state, ui, unicode_text) { log::error!("{:?}", err); }
+            profiler.record_render(index, render_start.elapsed());
+        }
+        if layer == RenderLayer::Hud {
+            if let Ok(mut notifications) = self.app_state.resolve_mut::<StateNotifications>(()) {
+                notifications.render(ui);
+            }
         }
     }
 }
@@ -434,7 +979,18 @@ fn main() {
         Err(error) => { println!("{:#}", error); std::process::exit(1); }
     };
 
-    env_logger::builder().filter_level(if args.verbose { log::LevelFilter::Trace } else { log::LevelFilter::Info }).parse_default_env().init();
+    if let Some(AppCommand::ValidateConfig { path, output }) = &args.command {
+        match run_validate_config(path, output.as_deref()) {
+            Ok(true) => std::process::exit(0),
+            Ok(false) => std::process::exit(1),
+            Err(error) => { println!("{:#}", error); std::process::exit(1); }
+        }
+    }
+
+    let log_dir = args.log_dir.clone().or_else(|| get_logs_dir().ok());
+    init_logging(if args.verbose { log::LevelFilter::Trace } else { log::LevelFilter::Info }, log_dir.as_deref());
+    crash::install();
+
     let runtime = runtime::Builder::new_multi_thread().enable_all().worker_threads(1).build().expect("to be able to build a runtime");
     let _runtime_guard = runtime.enter();
     if let Err(error) = real_main(&args) { show_critical_error(&format!("{:#}", error)); }
@@ -447,10 +1003,57 @@ struct AppArgs {
     verbose: bool,
     #[arg(short, long)]
     schema_file: Option<PathBuf>,
+    /// Run without the overlay and only update the game state
+    #[arg(long)]
+    headless: bool,
+    /// Start with the menu closed, skipping the intro and minimizing the console
+    #[arg(long)]
+    start_hidden: bool,
+    /// Directory of the log files (defaults to LABHConfig/logs in the documents folder)
+    #[arg(long)]
+    log_dir: Option<PathBuf>,
+    /// Run the update and render pipeline for the given amount of seconds without the overlay
+    /// and print the timings of every enhancement.
+    /// Without --replay a synthetic backend without any game state is used.
+    #[arg(long, value_name = "SECONDS", conflicts_with_all = ["usermode_backend", "record_memory"])]
+    benchmark: Option<u64>,
+    /// Read the game memory with ReadProcessMemory instead of the kernel driver.
+    /// Easily detectable, only intended for development.
+    /// Requires the usermode-backend feature.
+    #[arg(long)]
+    usermode_backend: bool,
+    /// Record all memory reads to LABHConfig/recordings, so the session can be replayed with --replay
+    #[arg(long, conflicts_with = "replay")]
+    record_memory: bool,
+    /// Replay a memory recording instead of reading the memory of CS2.
+    /// Neither the game nor the driver are required and no input is sent.
+    #[arg(long, value_name = "RECORDING", conflicts_with = "usermode_backend")]
+    replay: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Option<AppCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+enum AppCommand {
+    /// Dump the schema (offsets) of the running CS2 instance into a file usable with --schema-file
+    DumpSchema {
+        /// Target file of the dumped schema
+        #[arg(short, long, default_value = "schema.json")]
+        output: PathBuf,
+    },
+    /// Check a config file for unknown keys and invalid values without starting the overlay
+    ValidateConfig {
+        /// Config file to validate
+        path: PathBuf,
+        /// Write the config with unknown keys removed and invalid values clamped to this file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 fn real_main(args: &AppArgs) -> anyhow::Result<()> {
     let build_info = version_info()?;
+    updater::cleanup_previous_executable();
     log::info!("{} v{} ({}). Windows build {}.", obfstr!("LABH"), env!("CARGO_PKG_VERSION"), env!("GIT_HASH"), build_info.dwBuildNumber);
     log::info!("{} {}", obfstr!("Current executable was built on"), env!("BUILD_TIME"));
 
@@ -477,8 +1080,21 @@ fn real_main(args: &AppArgs) -> anyhow::Result<()> {
         log::warn!("{}", obfstr!("Running the controller as administrator might cause failures with your graphic drivers."));
     }
 
+    if args.usermode_backend {
+        let message = [obfstr!("The usermode memory backend opens a process handle to CS2."), obfstr!("Anti cheats easily detect this and it will likely get your account banned."), obfstr!("Only use it for development on accounts you do not care about."), obfstr!(""), obfstr!("Do you want to continue?")].join("\n");
+        let result = dialog::show_yes_no(obfstr!("LABH"), &message, false);
+        if !result { log::info!("{}", obfstr!("Aborting launch due to user input.")); return Ok(()); }
+    }
+
     let settings = load_app_settings()?;
-    let cs2 = match CS2Handle::create(settings.metrics) {
+    crash::update_enabled_features(&settings);
+    let cs2 = match create_memory_backend(args).and_then(|backend| {
+        /* the metrics opt out is applied by the metrics sinks, which can be changed at runtime */
+        CS2Handle::create_with_backend(true, backend, |process_ids| {
+            let candidates = process_ids.iter().map(|process_id| *process_id as u32).collect::<Vec<_>>();
+            dialog::pick_process(obfstr!("cs2.exe"), &candidates).map(|index| process_ids[index])
+        })
+    }) {
         Ok(handle) => handle,
         Err(err) => {
             if let Some(err) = err.downcast_ref::<InterfaceError>() {
@@ -491,37 +1107,52 @@ fn real_main(args: &AppArgs) -> anyhow::Result<()> {
         }
     };
 
-    let driver_name = cs2.ke_interface.driver_version().get_application_name().unwrap_or("<invalid>");
+    if let Some(AppCommand::DumpSchema { output }) = &args.command {
+        return run_dump_schema(cs2, output);
+    }
+
+    let driver_name = cs2.driver_info().application_name;
     if driver_name == obfstr!("zenith-driver") {
         let message = [obfstr!("You are using Zenith with the CS2 overlay."), obfstr!("Topmost overlays may be flagged regardless of using the Zenith driver."), obfstr!(""), obfstr!("Do you want to continue?")].join("\n");
         let result = dialog::show_yes_no(obfstr!("LABH"), &message, false);
         if !result { log::info!("{}", obfstr!("Aborting launch due to user input.")); return Ok(()); }
     }
 
-    cs2.add_metrics_record(obfstr!("controller-status"), "initializing");
+    let metrics = Metrics::new(cs2.clone(), &settings);
+    metrics.add_metrics_record(obfstr!("controller-status"), "initializing");
 
     let mut app_state = StateRegistry::new(1024 * 8);
     app_state.set(StateCS2Handle::new(cs2.clone()), ())?;
     app_state.set(StateCS2Memory::new(cs2.create_memory_view()), ())?;
     app_state.set(settings, ())?;
 
-    {
+    let cs2_revision = if uses_synthetic_backend(args) {
+        /* the synthetic memory contains no game, the built-in offsets are used instead of the schema system */
+        cs2_schema_provider_impl::setup_provider(Box::new(cs2_schema_provider_impl::DefaultSchemaProvider));
+        obfstr!("synthetic").to_string()
+    } else {
         let cs2_build_info = app_state.resolve::<StateBuildInfo>(()).context(obfstr!("Failed to load CS2 build info. CS2 version might be newer / older then expected").to_string())?;
         log::info!("Found {}. Revision {} from {}.", obfstr!("Counter-Strike 2"), cs2_build_info.revision, cs2_build_info.build_datetime);
-        cs2.add_metrics_record(obfstr!("cs2-version"), &format!("revision: {}", cs2_build_info.revision));
+        metrics.add_metrics_record(obfstr!("cs2-version"), &format!("revision: {}", cs2_build_info.revision));
+        cs2_build_info.revision.clone()
+    };
+
+    if !uses_synthetic_backend(args) {
+        game_watcher::setup_schema(&mut app_state, args.schema_file.as_deref(), &cs2_revision)?;
+        app_state.resolve::<StateConVar>("sensitivity").context("cvar sensitivity")?;
     }
+    let game_watcher = GameWatcher::new(cs2.clone(), args.schema_file.clone(), cs2_revision);
 
-    if let Some(file) = &args.schema_file {
-        log::info!("{} {}", obfstr!("Loading CS2 schema (offsets) from file"), file.display());
-        cs2_schema_provider_impl::setup_schema_from_file(&mut app_state, file).context("file schema setup")?;
-    } else {
-        log::info!("{}", obfstr!("Loading CS2 schema (offsets) from CS2 schema system"));
-        cs2_schema_provider_impl::setup_provider(Box::new(cs2_schema_provider_impl::RuntimeSchemaProvider::new(&app_state).context("load runtime schema")?));
+    if let Some(duration) = args.benchmark {
+        let app = Application::new(Default::default(), AppResources::new(cs2.clone()), app_state, cs2.clone(), metrics, game_watcher);
+        return benchmark::run_benchmark(app, Duration::from_secs(duration));
     }
-    log::info!("CS2 schema (offsets) loaded.");
 
-    let cvars = ConVars::new(&app_state).context("cvars")?;
-    let cvar_sensitivity = cvars.find_cvar("sensitivity").context("cvar sensitivity")?.context("missing cvar sensitivity")?;
+    if args.headless {
+        let app = Application::new(Default::default(), AppResources::new(cs2.clone()), app_state, cs2.clone(), metrics, game_watcher);
+        app.metrics.add_metrics_record(obfstr!("controller-status"), &format!("initialized headless, version: {}, git-hash: {}, win-build: {}", env!("CARGO_PKG_VERSION"), env!("GIT_HASH"), build_info.dwBuildNumber));
+        return run_headless(app);
+    }
 
     log::debug!("Initialize overlay");
     let app_fonts: AppFonts = Default::default();
@@ -530,18 +1161,18 @@ fn real_main(args: &AppArgs) -> anyhow::Result<()> {
         target: OverlayTarget::WindowOfProcess(cs2.process_id() as u32),
         register_fonts_callback: Some(Box::new({
             let app_fonts = app_fonts.clone();
-            move |atlas| {
+            move |atlas, font_scale| {
                 const FA_GLYPH_RANGES: &[u32] = &[0xf000, 0xf3ff, 0, ];
                 let font_config = FontConfig { rasterizer_multiply: 1.2, oversample_h: 3, oversample_v: 3, ..FontConfig::default() };
-                let poppins_font = atlas.add_font(&[FontSource::TtfData { data: include_bytes!("../resources/Poppins-Regular.ttf"), size_pixels: 16.0, config: Some(font_config.clone()) }, FontSource::TtfData { data: include_bytes!("../resources/fa-solid-900.ttf"), size_pixels: 16.0, config: Some(FontConfig { glyph_ranges: FontGlyphRanges::from_slice(FA_GLYPH_RANGES), ..font_config.clone() }) }]);
+                let poppins_font = atlas.add_font(&[FontSource::TtfData { data: include_bytes!("../resources/Poppins-Regular.ttf"), size_pixels: 16.0 * font_scale, config: Some(font_config.clone()) }, FontSource::TtfData { data: include_bytes!("../resources/fa-solid-900.ttf"), size_pixels: 16.0 * font_scale, config: Some(FontConfig { glyph_ranges: FontGlyphRanges::from_slice(FA_GLYPH_RANGES), ..font_config.clone() }) }]);
                 app_fonts.labh.set_id(poppins_font);
-                let title_font = atlas.add_font(&[FontSource::TtfData { data: include_bytes!("../resources/Poppins-Regular.ttf"), size_pixels: 22.0, config: Some(FontConfig { rasterizer_multiply: 1.2, oversample_h: 4, oversample_v: 4, ..FontConfig::default() }) }, FontSource::TtfData { data: include_bytes!("../resources/fa-solid-900.ttf"), size_pixels: 22.0, config: Some(FontConfig { glyph_ranges: FontGlyphRanges::from_slice(FA_GLYPH_RANGES), ..font_config.clone() }) }]);
+                let title_font = atlas.add_font(&[FontSource::TtfData { data: include_bytes!("../resources/Poppins-Regular.ttf"), size_pixels: 22.0 * font_scale, config: Some(FontConfig { rasterizer_multiply: 1.2, oversample_h: 4, oversample_v: 4, ..FontConfig::default() }) }, FontSource::TtfData { data: include_bytes!("../resources/fa-solid-900.ttf"), size_pixels: 22.0 * font_scale, config: Some(FontConfig { glyph_ranges: FontGlyphRanges::from_slice(FA_GLYPH_RANGES), ..font_config.clone() }) }]);
                 app_fonts.title.set_id(title_font);
                 
                 let intro_font = atlas.add_font(&[
                     FontSource::TtfData { 
                         data: include_bytes!("../resources/Poppins-Regular.ttf"), 
-                        size_pixels: 88.0, 
+                        size_pixels: 88.0 * font_scale, 
                         config: Some(FontConfig { rasterizer_multiply: 1.0, oversample_h: 2, oversample_v: 2, ..FontConfig::default() }) 
                     }
                 ]);
@@ -568,22 +1199,7 @@ fn real_main(args: &AppArgs) -> anyhow::Result<()> {
         value => value?,
     };
 
-    let mut app_resources = AppResources {
-        character_model: None,
-        weapon_icons: HashMap::new(),
-        character_texture: None,
-        cog_texture_id: None,
-        esp_preview_skeleton_texture_id: None,
-        esp_preview_head_texture_id: None,
-        esp_preview_health_lr_texture_id: None,
-        esp_preview_health_bt_texture_id: None,
-        esp_preview_name_texture_id: None,
-        esp_preview_gun_texture_id: None,
-        esp_preview_distance_texture_id: None,
-        esp_preview_ammo_texture_id: None,
-        esp_preview_box_texture_id: None,
-        cs2: cs2.clone(),
-    };
+    let mut app_resources = AppResources::new(cs2.clone());
     {
         const COG_IMAGE_BYTES: &[u8] = include_bytes!("../resources/cog.png");
         let image = image::load_from_memory(COG_IMAGE_BYTES).expect("Failed to load cog.png from resources folder");
@@ -642,127 +1258,58 @@ fn real_main(args: &AppArgs) -> anyhow::Result<()> {
     }
 
     {
-        let icons_path = PathBuf::from("resources/weapon_icons");
+        let icons_path = PathBuf::from(WEAPON_ICONS_PATH);
         if icons_path.exists() {
-            log::info!("Loading weapon icons from {:?} (SVG Support Enabled)", icons_path);
-            
-            let mut fontdb = usvg::fontdb::Database::new();
-            fontdb.load_system_fonts();
-
-            let mut usvg_opts = usvg::Options::default();
-            usvg_opts.fontdb = Arc::new(fontdb);
-
-            if let Ok(entries) = std::fs::read_dir(icons_path) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    let ext = path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
-
-                    if let Some(file_stem) = path.file_stem().and_then(|s| s.to_str()) {
-                        if ext.as_deref() == Some("svg") {
-                            match std::fs::read(&path) {
-                                Ok(svg_data) => {
-                                    match usvg::Tree::from_data(&svg_data, &usvg_opts) {
-                                        Ok(tree) => {
-                                            let size = tree.size().to_int_size();
-                                            let scale = 2.0;
-                                            let width = (size.width() as f32 * scale) as u32;
-                                            let height = (size.height() as f32 * scale) as u32;
-                                            
-                                            if let Some(mut pixmap) = Pixmap::new(width, height) {
-                                                let transform = tiny_skia::Transform::from_scale(scale, scale);
-                                                resvg::render(&tree, transform, &mut pixmap.as_mut());
-                                                
-                                                let mut data = pixmap.data().to_vec();
-                                                
-                                                for chunk in data.chunks_mut(4) {
-                                                    if chunk[3] > 0 {
-                                                        chunk[0] = 255;
-                                                        chunk[1] = 255;
-                                                        chunk[2] = 255;
-                                                    }
-                                                }
-
-                                                match unsafe { overlay.add_texture(&data, width, height) } {
-                                                    Ok(tex_id) => {
-                                                        app_resources.weapon_icons.insert(file_stem.to_string(), tex_id);
-                                                    }
-                                                    Err(e) => log::error!("Failed to upload SVG texture {}: {}", file_stem, e),
-                                                }
-                                            }
-                                        }
-                                        Err(e) => log::error!("Failed to parse SVG {}: {}", file_stem, e),
-                                    }
-                                }
-                                Err(e) => log::error!("Failed to read SVG file {}: {}", path.display(), e),
-                            }
-                        } else if ext.as_deref() == Some("png") {
-                            match image::open(&path) {
-                                Ok(img) => {
-                                    let rgba = img.to_rgba8();
-                                    let (w, h) = rgba.dimensions();
-                                    let data = rgba.into_raw();
-                                    match unsafe { overlay.add_texture(&data, w, h) } {
-                                        Ok(tex_id) => {
-                                            app_resources.weapon_icons.insert(file_stem.to_string(), tex_id);
-                                        }
-                                        Err(e) => log::error!("Failed to upload PNG texture {}: {}", file_stem, e),
-                                    }
-                                }
-                                Err(e) => log::error!("Failed to load PNG image {}: {}", path.display(), e),
-                            }
-                        }
-                    }
+            let atlas = load_weapon_icon_atlas(&icons_path, |data, width, height| {
+                unsafe { overlay.add_texture(data, width, height) }.map_err(Into::into)
+            });
+            match atlas {
+                Ok(atlas) => {
+                    log::info!("Loaded {} weapon icons.", atlas.len());
+                    app_resources.weapon_icons = atlas;
                 }
+                Err(e) => log::error!("Failed to load the weapon icons: {:#}", e),
             }
-            log::info!("Loaded {} weapon icons.", app_resources.weapon_icons.len());
         } else {
             log::warn!("resources/weapon_icons directory not found.");
         }
     }
 
-    app_state.set(app_resources.clone(), ()).expect("Failed to set resources in state");
+    match read_rank_icons() {
+        Some(icons) => {
+            for (name, (data, (w, h))) in icons {
+                match unsafe { overlay.add_texture(&data, w, h) } {
+                    Ok(tex_id) => {
+                        app_resources.rank_icons.insert(name, (tex_id, (w, h)));
+                    }
+                    Err(e) => log::error!("Failed to upload rank icon {}: {}", name, e),
+                }
+            }
+            log::info!("Loaded {} rank icons.", app_resources.rank_icons.len());
+        }
+        None => log::debug!("resources/rank_icons directory not found. Ranks will be shown as text."),
+    }
 
-    apply_custom_style(overlay.imgui.style_mut());
+    overlay.imgui.io_mut().config_flags.insert(ConfigFlags::NAV_ENABLE_KEYBOARD | ConfigFlags::NAV_ENABLE_GAMEPAD);
 
     {
         let settings = app_state.resolve::<AppSettings>(())?;
         if let Some(imgui_settings) = &settings.imgui { overlay.imgui.load_ini_settings(imgui_settings); }
     }
 
-    let app = Application {
-        fonts: app_fonts,
-        resources: app_resources,
-        app_state,
-        cs2: cs2.clone(),
-        enhancements: vec![
-            Rc::new(RefCell::new(AntiAimPunsh::new(cvar_sensitivity))),
-            Rc::new(RefCell::new(PlayerESP::new())),
-            Rc::new(RefCell::new(SpectatorsListIndicator::new())),
-            Rc::new(RefCell::new(BombInfoIndicator::new())),
-            Rc::new(RefCell::new(BombLabelIndicator::new())),
-            Rc::new(RefCell::new(TriggerBot::new())),
-            Rc::new(RefCell::new(GrenadeHelper::new())),
-            Rc::new(RefCell::new(SniperCrosshair::new())),
-            Rc::new(RefCell::new(GrenadeTrajectory::new())),
-            Rc::new(RefCell::new(LegitAim::new())),
-        ],
-        last_total_read_calls: 0,
-        frame_read_calls: 0,
-        settings_visible: true,
-        settings_visibility_changed: AtomicBool::new(true),
-        settings_key_warning_visible: RefCell::new(false),
-        settings_dirty: false,
-        settings_ui: RefCell::new(SettingsUI::new()),
-        settings_screen_capture_changed: AtomicBool::new(true),
-        settings_render_debug_window_changed: AtomicBool::new(true),
-        menu_key_was_down: false,
-        is_initialized: AtomicBool::new(false),
-    };
+    let mut app = Application::new(app_fonts, app_resources, app_state, cs2.clone(), metrics, game_watcher);
+    if args.start_hidden || app.settings().start_hidden {
+        log::info!("Starting hidden. Press the menu key to open the settings.");
+        app.start_hidden();
+    }
+    if app.settings().update_check {
+        app.update_check_requested.store(true, Ordering::Relaxed);
+    }
     let app = Rc::new(RefCell::new(app));
 
     app.borrow().is_initialized.store(true, Ordering::Relaxed);
 
-    cs2.add_metrics_record(obfstr!("controller-status"), &format!("initialized, version: {}, git-hash: {}, win-build: {}", env!("CARGO_PKG_VERSION"), env!("GIT_HASH"), build_info.dwBuildNumber));
+    app.borrow().metrics.add_metrics_record(obfstr!("controller-status"), &format!("initialized, version: {}, git-hash: {}, win-build: {}", env!("CARGO_PKG_VERSION"), env!("GIT_HASH"), build_info.dwBuildNumber));
 
     log::info!("{}", obfstr!("App initialized. Spawning overlay."));
     let mut update_fail_count = 0;
@@ -813,8 +1360,23 @@ fn real_main(args: &AppArgs) -> anyhow::Result<()> {
 
             app.render(ui, unicode_text);
 
-            let fps_limit = app.settings().fps_limit;
-            if fps_limit > 0 {
+            let (fps_limit, idle_power_saving, menu_key) = {
+                let settings = app.settings();
+                (settings.fps_limit, settings.idle_power_saving, settings.key_settings.0)
+            };
+            if idle_power_saving && app.overlay_idle {
+                let fps_limit = if fps_limit > 0 { fps_limit.min(IDLE_FPS_LIMIT) } else { IDLE_FPS_LIMIT };
+                let target_frametime = Duration::from_micros(1_000_000 / fps_limit as u64);
+
+                /* sleep in slices so a short press of the menu key is not missed */
+                let vk_menu_key = map_imgui_key_to_vk(menu_key);
+                while loop_start.elapsed() < target_frametime {
+                    if vk_menu_key.0 != 0 && unsafe { (GetAsyncKeyState(vk_menu_key.0 as i32) as u16 & 0x8000) != 0 } {
+                        break;
+                    }
+                    std::thread::sleep(IDLE_SLEEP_SLICE.min(target_frametime.saturating_sub(loop_start.elapsed())));
+                }
+            } else if fps_limit > 0 {
                 let target_frametime = Duration::from_micros(1_000_000 / fps_limit as u64);
                 let elapsed = loop_start.elapsed();
                 if elapsed < target_frametime {
@@ -836,6 +1398,119 @@ fn real_main(args: &AppArgs) -> anyhow::Result<()> {
 
     Ok(())
 }
+/// Update rate of the headless mode if no FPS limit has been configured
+const HEADLESS_UPDATE_RATE: u64 = 64;
+
+/// Frame rate of the overlay while idle with power saving enabled
+pub const IDLE_FPS_LIMIT: u32 = 10;
+
+/// Duration of a single sleep while idle, limits the latency of the menu key
+const IDLE_SLEEP_SLICE: Duration = Duration::from_millis(10);
+
+/// The benchmark runs without the game unless a recording is replayed
+fn uses_synthetic_backend(args: &AppArgs) -> bool {
+    args.benchmark.is_some() && args.replay.is_none()
+}
+
+/// Memory backend selected by the command line arguments
+fn create_memory_backend(args: &AppArgs) -> anyhow::Result<Box<dyn MemoryBackend>> {
+    if let Some(recording) = &args.replay {
+        return Ok(Box::new(ReplayBackend::load(recording)?));
+    }
+
+    if uses_synthetic_backend(args) {
+        return Ok(Box::new(SyntheticBackend::new()));
+    }
+
+    let backend = create_game_memory_backend(args)?;
+    if args.record_memory {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let path = get_recordings_dir()?.join(format!("recording-{}.labhrec", timestamp));
+        return Ok(Box::new(RecordingBackend::create(backend, &path)?));
+    }
+
+    Ok(backend)
+}
+
+fn create_game_memory_backend(args: &AppArgs) -> anyhow::Result<Box<dyn MemoryBackend>> {
+    if args.usermode_backend {
+        #[cfg(feature = "usermode-backend")]
+        return Ok(Box::new(cs2::UsermodeBackend::create()?));
+
+        #[cfg(not(feature = "usermode-backend"))]
+        anyhow::bail!("{}", obfstr!("The controller has been built without the usermode-backend feature"));
+    }
+
+    Ok(Box::new(DriverBackend::create()?))
+}
+
+/// Print all issues of a config file and optionally write the normalized config.
+/// Returns false if the config contains errors.
+fn run_validate_config(path: &Path, output: Option<&Path>) -> anyhow::Result<bool> {
+    let source = std::fs::read_to_string(path).with_context(|| format!("failed to read config {}", path.display()))?;
+    let validation = settings::validate_config(&source);
+
+    for issue in validation.issues.iter() {
+        println!("{}: {}", path.display(), issue);
+    }
+
+    if validation.has_errors() {
+        println!("{} is invalid", path.display());
+        return Ok(false);
+    }
+    println!("{} is valid ({} issues)", path.display(), validation.issues.len());
+
+    if let (Some(output), Some(settings)) = (output, &validation.normalized) {
+        let file = std::fs::File::create(output).with_context(|| format!("failed to create {}", output.display()))?;
+        serde_yaml::to_writer(std::io::BufWriter::new(file), settings).context("failed to serialize config")?;
+        println!("Normalized config written to {}", output.display());
+    }
+
+    Ok(true)
+}
+
+/// Dump the schema (offsets) of the running game instead of starting the overlay
+fn run_dump_schema(cs2: Arc<CS2Handle>, output: &Path) -> anyhow::Result<()> {
+    log::info!("{}", obfstr!("Dumping CS2 schema (offsets). Please wait..."));
+
+    let mut states = StateRegistry::new(64);
+    states.set(StateCS2Handle::new(cs2.clone()), ())?;
+    states.set(StateCS2Memory::new(cs2.create_memory_view()), ())?;
+
+    let revision = cs2_schema_provider_impl::dump_schema_to_file(&states, output).context("dump schema")?;
+    log::info!("Schema of CS2 revision {} dumped to {}", revision, output.display());
+    Ok(())
+}
+
+fn run_headless(mut app: Application) -> anyhow::Result<()> {
+    log::info!("{}", obfstr!("App initialized. Running headless, press CTRL+C to exit."));
+    app.is_initialized.store(true, Ordering::Relaxed);
+
+    let mut update_fail_count = 0;
+    loop {
+        let loop_start = Instant::now();
+        if let Err(err) = app.update_headless() {
+            if update_fail_count >= 10 {
+                log::error!("Over 10 errors occurred. Waiting 1s and try again.");
+                log::error!("Last error: {:#}", err);
+                std::thread::sleep(Duration::from_millis(1000));
+                update_fail_count = 0;
+                continue;
+            } else {
+                update_fail_count += 1;
+            }
+        }
+
+        let fps_limit = app.settings().fps_limit;
+        let update_rate = if fps_limit > 0 { fps_limit as u64 } else { HEADLESS_UPDATE_RATE };
+        let target_frametime = Duration::from_micros(1_000_000 / update_rate);
+        let elapsed = loop_start.elapsed();
+        if elapsed < target_frametime {
+            std::thread::sleep(target_frametime - elapsed);
+        }
+    }
+}
+
 fn apply_custom_style(style: &mut imgui::Style) {
     style.window_padding = [15.0, 15.0];
     style.window_rounding = 5.0;
@@ -850,45 +1525,4 @@ fn apply_custom_style(style: &mut imgui::Style) {
     style.grab_rounding = 3.0;
     style.tab_rounding = 4.0;
     style.window_title_align = [0.5, 0.5];
-    let colors = &mut style.colors;
-    colors[StyleColor::Text as usize] = [0.80, 0.80, 0.83, 1.00];
-    colors[StyleColor::TextDisabled as usize] = [0.45, 0.45, 0.48, 1.00];
-    colors[StyleColor::WindowBg as usize] = [0.06, 0.05, 0.07, 1.00];
-    colors[StyleColor::ChildBg as usize] = [0.07, 0.07, 0.09, 1.00];
-    colors[StyleColor::PopupBg as usize] = [0.07, 0.07, 0.09, 1.00];
-    colors[StyleColor::Border as usize] = [0.80, 0.80, 0.83, 0.88];
-    colors[StyleColor::BorderShadow as usize] = [0.92, 0.91, 0.88, 0.00];
-    colors[StyleColor::FrameBg as usize] = [0.10, 0.09, 0.12, 1.00];
-    colors[StyleColor::FrameBgHovered as usize] = [0.24, 0.23, 0.29, 1.00];
-    colors[StyleColor::FrameBgActive as usize] = [0.56, 0.56, 0.58, 1.00];
-    colors[StyleColor::TitleBg as usize] = [0.10, 0.09, 0.12, 1.00];
-    colors[StyleColor::TitleBgActive as usize] = [0.07, 0.07, 0.09, 1.00];
-    colors[StyleColor::TitleBgCollapsed as usize] = [1.00, 0.98, 0.95, 0.75];
-    colors[StyleColor::MenuBarBg as usize] = [0.10, 0.09, 0.12, 1.00];
-    colors[StyleColor::ScrollbarBg as usize] = [0.10, 0.09, 0.12, 1.00];
-    colors[StyleColor::ScrollbarGrab as usize] = [0.80, 0.80, 0.83, 0.31];
-    colors[StyleColor::ScrollbarGrabHovered as usize] = [0.56, 0.56, 0.58, 1.00];
-    colors[StyleColor::ScrollbarGrabActive as usize] = [0.06, 0.05, 0.07, 1.00];
-    colors[StyleColor::CheckMark as usize] = [0.80, 0.80, 0.83, 0.31];
-    colors[StyleColor::SliderGrab as usize] = [0.80, 0.80, 0.83, 0.31];
-    colors[StyleColor::SliderGrabActive as usize] = [0.06, 0.05, 0.07, 1.00];
-    colors[StyleColor::Button as usize] = [0.10, 0.09, 0.12, 1.00];
-    colors[StyleColor::ButtonHovered as usize] = [0.24, 0.23, 0.29, 1.00];
-    colors[StyleColor::ButtonActive as usize] = [0.56, 0.56, 0.58, 1.00];
-    colors[StyleColor::Header as usize] = [0.10, 0.09, 0.12, 1.00];
-    colors[StyleColor::HeaderHovered as usize] = [0.56, 0.56, 0.58, 1.00];
-    colors[StyleColor::HeaderActive as usize] = [0.06, 0.05, 0.07, 1.00];
-    colors[StyleColor::Separator as usize] = [0.43, 0.43, 0.50, 0.50];
-    colors[StyleColor::SeparatorHovered as usize] = [0.10, 0.40, 0.75, 0.78];
-    colors[StyleColor::SeparatorActive as usize] = [0.10, 0.40, 0.75, 1.00];
-    colors[StyleColor::ResizeGrip as usize] = [0.00, 0.00, 0.00, 0.00];
-    colors[StyleColor::ResizeGripHovered as usize] = [0.56, 0.56, 0.58, 1.00];
-    colors[StyleColor::ResizeGripActive as usize] = [0.06, 0.05, 0.07, 1.00];
-    colors[StyleColor::Tab as usize] = [0.10, 0.09, 0.12, 1.00];
-    colors[StyleColor::TabHovered as usize] = [0.24, 0.23, 0.29, 1.00];
-    colors[StyleColor::TabActive as usize] = [0.14, 0.13, 0.17, 1.00];
-    colors[StyleColor::TabUnfocused as usize] = [0.10, 0.09, 0.12, 1.00];
-    colors[StyleColor::TabUnfocusedActive as usize] = [0.20, 0.25, 0.29, 1.00];
-    colors[StyleColor::TextSelectedBg as usize] = [0.25, 1.00, 0.00, 0.43];
-    colors[StyleColor::NavHighlight as usize] = [0.26, 0.59, 0.98, 1.00];
 }
\ No newline at end of file
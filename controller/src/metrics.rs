@@ -0,0 +1,259 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fs::{
+        File,
+        OpenOptions,
+    },
+    io::{
+        BufWriter,
+        Write,
+    },
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use anyhow::Context;
+use cs2::CS2Handle;
+use serde::Serialize;
+
+use crate::{
+    settings::{
+        get_metrics_dir,
+        AppSettings,
+        MetricsCategory,
+        MetricsCategorySettings,
+        MetricsDestination,
+    },
+    MetricsClient,
+};
+
+/// A type of metrics record emitted by the controller
+pub struct MetricsRecordType {
+    pub name: &'static str,
+    pub category: MetricsCategory,
+    pub description: &'static str,
+}
+
+/// All record types the controller emits.
+/// Records of other types are never sent.
+pub const METRICS_RECORD_TYPES: &[MetricsRecordType] = &[
+    MetricsRecordType {
+        name: "controller-status",
+        category: MetricsCategory::VersionInfo,
+        description: "Controller version, git hash and Windows build while starting",
+    },
+    MetricsRecordType {
+        name: "cs2-version",
+        category: MetricsCategory::VersionInfo,
+        description: "Revision of the running CS2 build",
+    },
+    MetricsRecordType {
+        name: "settings-updated",
+        category: MetricsCategory::SettingsSnapshots,
+        description: "All settings except the window layout and the Steam API key, sent when closing the menu",
+    },
+    MetricsRecordType {
+        name: "settings-toggled",
+        category: MetricsCategory::FeatureToggles,
+        description: "The menu has been opened or closed",
+    },
+    MetricsRecordType {
+        name: "feature-esp-toggle",
+        category: MetricsCategory::FeatureToggles,
+        description: "ESP toggled by hotkey and the active ESP mode",
+    },
+    MetricsRecordType {
+        name: "feature-trigger-bot-toggle",
+        category: MetricsCategory::FeatureToggles,
+        description: "Trigger bot toggled by hotkey and its mode",
+    },
+];
+
+/// Number of records kept for the preview
+const RECENT_RECORD_COUNT: usize = 64;
+
+/// A record passed to the metrics, kept to preview what is sent
+#[derive(Debug, Clone)]
+pub struct RecentRecord {
+    pub time: String,
+    pub record_type: String,
+    pub payload: String,
+    /// The category of the record has not been opted out of
+    pub allowed: bool,
+}
+
+/// Receives the metrics records of the controller
+pub trait MetricsSink {
+    fn record(&self, record_type: &str, record_payload: &str);
+}
+
+/// Drops all records
+struct DisabledSink;
+
+impl MetricsSink for DisabledSink {
+    fn record(&self, _record_type: &str, _record_payload: &str) {}
+}
+
+/// Submits the records through the driver interface
+struct RemoteSink {
+    cs2: Arc<CS2Handle>,
+}
+
+impl MetricsSink for RemoteSink {
+    fn record(&self, record_type: &str, record_payload: &str) {
+        self.cs2.add_metrics_record(record_type, record_payload);
+    }
+}
+
+#[derive(Serialize)]
+struct LocalRecord<'a> {
+    /// Unix timestamp in milliseconds
+    timestamp: u64,
+    record_type: &'a str,
+    payload: &'a str,
+}
+
+/// Appends the records as JSON lines to a local file
+struct LocalFileSink {
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl LocalFileSink {
+    fn create() -> anyhow::Result<Self> {
+        let path = get_metrics_dir()?.join("metrics.jsonl");
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+
+        log::info!("Writing metrics to {}", path.display());
+        Ok(Self {
+            writer: Mutex::new(BufWriter::new(file)),
+        })
+    }
+}
+
+impl MetricsSink for LocalFileSink {
+    fn record(&self, record_type: &str, record_payload: &str) {
+        let record = LocalRecord {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis() as u64)
+                .unwrap_or_default(),
+            record_type,
+            payload: record_payload,
+        };
+
+        let Ok(mut writer) = self.writer.lock() else {
+            return;
+        };
+
+        /* records are rare, flush them right away so they survive a crash */
+        let result = serde_json::to_writer(&mut *writer, &record)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| Ok(writer.write_all(b"\n")?))
+            .and_then(|_| Ok(writer.flush()?));
+        if let Err(err) = result {
+            log::warn!("Failed to write metrics record: {:#}", err);
+        }
+    }
+}
+
+fn create_sink(
+    destination: MetricsDestination,
+    cs2: &Arc<CS2Handle>,
+) -> (MetricsDestination, Box<dyn MetricsSink>) {
+    match destination {
+        MetricsDestination::Disabled => (destination, Box::new(DisabledSink)),
+        MetricsDestination::Remote => (destination, Box::new(RemoteSink { cs2: cs2.clone() })),
+        MetricsDestination::LocalFile => match LocalFileSink::create() {
+            Ok(sink) => (destination, Box::new(sink)),
+            Err(err) => {
+                log::warn!("Failed to create the local metrics file: {:#}", err);
+                (MetricsDestination::Disabled, Box::new(DisabledSink))
+            }
+        },
+    }
+}
+
+/// Routes the metrics records of the enabled categories to the configured destination
+pub struct Metrics {
+    cs2: Arc<CS2Handle>,
+    destination: MetricsDestination,
+    sink: Box<dyn MetricsSink>,
+    categories: MetricsCategorySettings,
+    recent: RefCell<VecDeque<RecentRecord>>,
+}
+
+impl Metrics {
+    pub fn new(cs2: Arc<CS2Handle>, settings: &AppSettings) -> Self {
+        let mut metrics = Self {
+            cs2,
+            destination: MetricsDestination::Disabled,
+            sink: Box::new(DisabledSink),
+            categories: settings.metrics_categories.clone(),
+            recent: Default::default(),
+        };
+        metrics.apply_settings(settings);
+        metrics
+    }
+
+    /// Apply the destination and the categories of the settings
+    pub fn apply_settings(&mut self, settings: &AppSettings) {
+        self.categories = settings.metrics_categories.clone();
+
+        let destination = settings.effective_metrics_destination();
+        if destination == self.destination {
+            return;
+        }
+
+        log::debug!("Sending metrics to {:?}", destination);
+        (self.destination, self.sink) = create_sink(destination, &self.cs2);
+    }
+
+    /// Destination the records are actually sent to
+    pub fn destination(&self) -> MetricsDestination {
+        self.destination
+    }
+
+    /// Records passed to the metrics recently, including the ones which have not been sent
+    pub fn recent_records(&self) -> Vec<RecentRecord> {
+        self.recent.borrow().iter().cloned().collect()
+    }
+}
+
+impl MetricsClient for Metrics {
+    fn add_metrics_record(&self, record_type: &str, record_payload: &str) {
+        let category = METRICS_RECORD_TYPES
+            .iter()
+            .find(|entry| entry.name == record_type)
+            .map(|entry| entry.category);
+        if category.is_none() {
+            log::debug!("Dropping metrics record of unknown type {}", record_type);
+        }
+
+        let allowed = category.map_or(false, |category| self.categories.allows(category));
+        if allowed {
+            self.sink.record(record_type, record_payload);
+        }
+
+        let mut recent = self.recent.borrow_mut();
+        if recent.len() >= RECENT_RECORD_COUNT {
+            recent.pop_front();
+        }
+        recent.push_back(RecentRecord {
+            time: chrono::Local::now().format("%H:%M:%S").to_string(),
+            record_type: record_type.to_string(),
+            payload: record_payload.to_string(),
+            allowed,
+        });
+    }
+}
@@ -0,0 +1,152 @@
+use std::{
+    collections::VecDeque,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use imgui::ImColor32;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use crate::utils::{
+    self,
+    TextWithShadowUi,
+};
+
+/// How long a toast stays visible
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+/// Duration of the slide in and slide out animation
+const TOAST_ANIMATION_DURATION: Duration = Duration::from_millis(250);
+/// Distance the toasts slide in from the right screen edge
+const TOAST_SLIDE_DISTANCE: f32 = 300.0;
+/// Max amount of toasts visible at once, older ones get dropped
+const TOAST_LIMIT: usize = 5;
+
+const TOAST_MARGIN: f32 = 10.0;
+/// Vertical space of a single toast including the panel padding
+const TOAST_PADDING: f32 = 22.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl NotificationLevel {
+    fn color(&self) -> ImColor32 {
+        match self {
+            Self::Info => ImColor32::from_rgba(255, 255, 255, 255),
+            Self::Warning => ImColor32::from_rgba(255, 180, 80, 255),
+            Self::Error => ImColor32::from_rgba(255, 90, 90, 255),
+        }
+    }
+}
+
+struct Notification {
+    id: u64,
+    level: NotificationLevel,
+    message: String,
+    created: Instant,
+}
+
+/// Queue of the notifications shown as toasts in the bottom right corner
+#[derive(Default)]
+pub struct StateNotifications {
+    notifications: VecDeque<Notification>,
+    next_id: u64,
+}
+
+impl State for StateNotifications {
+    type Parameter = ();
+
+    fn create(_states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        Ok(Default::default())
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
+
+impl StateNotifications {
+    pub fn push(&mut self, level: NotificationLevel, message: String) {
+        if let Some(notification) = self
+            .notifications
+            .iter_mut()
+            .find(|notification| notification.message == message)
+        {
+            /* keep showing the existing toast instead of stacking duplicates */
+            if let Some(shown) = Instant::now().checked_sub(TOAST_ANIMATION_DURATION) {
+                notification.created = notification.created.max(shown);
+            }
+            return;
+        }
+
+        self.next_id += 1;
+        self.notifications.push_back(Notification {
+            id: self.next_id,
+            level,
+            message,
+            created: Instant::now(),
+        });
+
+        while self.notifications.len() > TOAST_LIMIT {
+            self.notifications.pop_front();
+        }
+    }
+
+    pub fn render(&mut self, ui: &imgui::Ui) {
+        self.notifications
+            .retain(|notification| notification.created.elapsed() < TOAST_DURATION);
+
+        let display_size = ui.io().display_size;
+        let toast_height = ui.text_line_height() + TOAST_PADDING;
+
+        let mut offset_y = display_size[1] - TOAST_MARGIN;
+        for notification in self.notifications.iter().rev() {
+            let elapsed = notification.created.elapsed();
+            let remaining = TOAST_DURATION.saturating_sub(elapsed);
+            let visibility = (elapsed.as_secs_f32() / TOAST_ANIMATION_DURATION.as_secs_f32())
+                .min(remaining.as_secs_f32() / TOAST_ANIMATION_DURATION.as_secs_f32())
+                .clamp(0.0, 1.0);
+            /* ease out cubic */
+            let visibility = 1.0 - (1.0 - visibility).powi(3);
+
+            let _alpha = ui.push_style_var(imgui::StyleVar::Alpha(visibility));
+            utils::render_styled_panel_pivot(
+                ui,
+                &format!("notification_toast_{}", notification.id),
+                [
+                    display_size[0] - TOAST_MARGIN + (1.0 - visibility) * TOAST_SLIDE_DISTANCE,
+                    offset_y,
+                ],
+                [1.0, 1.0],
+                || {
+                    ui.text_colored_with_shadow(notification.level.color(), &notification.message);
+                },
+            );
+
+            offset_y -= toast_height * visibility;
+        }
+    }
+}
+
+/// Log the message and show it as toast on the overlay
+pub fn notify(states: &StateRegistry, level: NotificationLevel, message: impl Into<String>) {
+    let message = message.into();
+    match level {
+        NotificationLevel::Info => log::info!("{}", message),
+        NotificationLevel::Warning => log::warn!("{}", message),
+        NotificationLevel::Error => log::error!("{}", message),
+    }
+
+    if let Ok(mut notifications) = states.resolve_mut::<StateNotifications>(()) {
+        notifications.push(level, message);
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    sync::mpsc::{
+        self,
+        Receiver,
+        Sender,
+    },
+    thread::{
+        self,
+        JoinHandle,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use super::{
+    save_app_settings,
+    AppSettings,
+};
+
+/// Writes the app settings on a background thread so saving never blocks rendering.
+/// Changed settings are detected by comparing them against the last saved snapshot.
+pub struct SettingsAutosave {
+    sender: Option<Sender<AppSettings>>,
+    errors: Receiver<anyhow::Error>,
+    worker: Option<JoinHandle<()>>,
+
+    last_saved: Option<AppSettings>,
+    last_check: Instant,
+}
+
+impl SettingsAutosave {
+    /// `saved` are the settings currently stored on disk
+    pub fn new(saved: Option<AppSettings>) -> Self {
+        let (sender, requests) = mpsc::channel::<AppSettings>();
+        let (error_sender, errors) = mpsc::channel();
+
+        let worker = thread::Builder::new()
+            .name("settings-autosave".to_string())
+            .spawn(move || {
+                while let Ok(mut settings) = requests.recv() {
+                    /* only the latest settings are relevant */
+                    while let Ok(newer) = requests.try_recv() {
+                        settings = newer;
+                    }
+
+                    if let Err(error) = save_app_settings(&settings) {
+                        let _ = error_sender.send(error);
+                    }
+                }
+            })
+            .map_err(|error| log::warn!("Failed to spawn the settings autosave thread: {}", error))
+            .ok();
+
+        Self {
+            sender: Some(sender),
+            errors,
+            worker,
+
+            last_saved: saved,
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Queue the settings to be saved
+    pub fn save(&mut self, settings: &AppSettings) {
+        self.last_saved = Some(settings.clone());
+        self.last_check = Instant::now();
+
+        let Some(sender) = &self.sender else {
+            return;
+        };
+
+        if self.worker.is_none() || sender.send(settings.clone()).is_err() {
+            /* the worker is not available, save on the current thread instead */
+            if let Err(error) = save_app_settings(settings) {
+                log::warn!("Failed to save user settings: {:#}", error);
+            }
+        }
+    }
+
+    /// Save the settings if they changed since the last save and the autosave interval elapsed
+    pub fn save_if_changed(&mut self, settings: &AppSettings) {
+        if settings.autosave_interval == 0 {
+            return;
+        }
+
+        if self.last_check.elapsed() < Duration::from_secs(settings.autosave_interval as u64) {
+            return;
+        }
+        self.last_check = Instant::now();
+
+        match &self.last_saved {
+            Some(last_saved) if last_saved == settings => {}
+            /* nothing to compare against, assume the current settings are on disk */
+            None => self.last_saved = Some(settings.clone()),
+            Some(_) => {
+                log::debug!("Settings changed, saving in background.");
+                self.save(settings);
+            }
+        }
+    }
+
+    /// Errors of background saves since the last call
+    pub fn take_errors(&mut self) -> Vec<anyhow::Error> {
+        self.errors.try_iter().collect()
+    }
+}
+
+impl Drop for SettingsAutosave {
+    fn drop(&mut self) {
+        /* closing the channel lets the worker finish the pending save and exit */
+        self.sender = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
@@ -13,16 +13,20 @@ use std::{
     io::{
         BufReader,
         BufWriter,
+        Write,
     },
     path::PathBuf,
     sync::atomic::{
         AtomicUsize,
         Ordering,
     },
+    time::Duration,
 };
 
 use anyhow::Context;
+use cs2::StateMatchPhase;
 use imgui::Key;
+use overlay::CaptureOptions;
 use serde::{
     Deserialize,
     Serialize,
@@ -31,6 +35,7 @@ use serde_with::with_prefix;
 use utils_state::{
     State,
     StateCacheType,
+    StateRegistry,
 };
 
 use super::{
@@ -44,12 +49,26 @@ use super::{
         EspHealthBar,
         EspTracePosition,
         EspInfoStyle,
+        EspOffscreenArrowMode,
         EspTextStyle,
+        ESP_COLOR_ARMOR,
+        ESP_COLOR_CHAMS_OCCLUDED,
+        ESP_COLOR_FLASH_FULL,
+        ESP_COLOR_FLASH_PARTIAL,
+        MapPalette,
+        builtin_map_palette,
     },
     HotKey,
+    Theme,
+};
+use crate::{
+    enhancements::RenderLayer,
+    update_thread::DEFAULT_UPDATE_TICK_RATE,
+    utils::UNITS_TO_METERS,
 };
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct SniperCrosshairSettings {
     pub size: f32,
     pub thickness: f32,
@@ -58,6 +77,11 @@ pub struct SniperCrosshairSettings {
     pub outline: bool,
     pub outline_thickness: f32,
     pub color: [u8; 4],
+    /// Hide the crosshair while scoped in as the scope has its own crosshair
+    pub unscoped_only: bool,
+    /// Use the target color while the crosshair is over an enemy
+    pub target_color_enabled: bool,
+    pub target_color: [u8; 4],
 }
 
 impl Default for SniperCrosshairSettings {
@@ -70,6 +94,40 @@ impl Default for SniperCrosshairSettings {
             outline: true,
             outline_thickness: 1.0,
             color: [255, 255, 255, 255],
+            unscoped_only: false,
+            target_color_enabled: false,
+            target_color: [255, 0, 0, 255],
+        }
+    }
+}
+
+/// Permanent crosshair using the same parameters as the in game crosshair
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CrosshairSettings {
+    /// Length of the lines (cl_crosshairsize)
+    pub size: f32,
+    pub thickness: f32,
+    pub gap: f32,
+    pub outline: bool,
+    pub outline_thickness: f32,
+    pub color: Color,
+    pub dot: bool,
+    /// Hide the top line
+    pub t_style: bool,
+}
+
+impl Default for CrosshairSettings {
+    fn default() -> Self {
+        Self {
+            size: 2.5,
+            thickness: 0.5,
+            gap: -2.0,
+            outline: true,
+            outline_thickness: 1.0,
+            color: default_color::<50, 250, 50, 255>(),
+            dot: false,
+            t_style: false,
         }
     }
 }
@@ -82,6 +140,16 @@ pub struct GrenadeTrajectorySettings {
     pub line_color: Color,
     #[serde(default = "default_f32::<2, 1>")]
     pub line_thickness: f32,
+    /// Color the line by the grenade type instead of using the line color
+    #[serde(default = "bool_true")]
+    pub line_color_by_type: bool,
+    #[serde(default = "bool_true")]
+    pub landing_circle: bool,
+    #[serde(default = "bool_true")]
+    pub bounce_markers: bool,
+    #[serde(default = "bool_true")]
+    pub detonation_timer: bool,
+    /// Map mesh used for the collision, "Auto" for the current map
     #[serde(default = "default_map_auto")]
     pub selected_map: String,
 }
@@ -94,11 +162,405 @@ impl Default for GrenadeTrajectorySettings {
             enabled: true,
             line_color: default_color::<255, 255, 255, 255>(),
             line_thickness: 2.0,
+            line_color_by_type: true,
+            landing_circle: true,
+            bounce_markers: true,
+            detonation_timer: true,
             selected_map: default_map_auto(),
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum OverlayAnchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    CenterRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl OverlayAnchor {
+    /// Relative position of the anchor on the screen / window (0.0 - 1.0)
+    pub fn pivot(&self) -> [f32; 2] {
+        match self {
+            Self::TopLeft => [0.0, 0.0],
+            Self::TopCenter => [0.5, 0.0],
+            Self::TopRight => [1.0, 0.0],
+            Self::CenterLeft => [0.0, 0.5],
+            Self::CenterRight => [1.0, 0.5],
+            Self::BottomLeft => [0.0, 1.0],
+            Self::BottomRight => [1.0, 1.0],
+        }
+    }
+
+    /// Absolute anchor position for the given display size
+    pub fn position(&self, display_size: [f32; 2]) -> [f32; 2] {
+        let pivot = self.pivot();
+        [display_size[0] * pivot[0], display_size[1] * pivot[1]]
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SpectatorsListSettings {
+    /// Show all spectators including the player they are watching
+    pub show_targets: bool,
+    pub show_count: bool,
+    /// Show the competitive rank next to the spectator name
+    pub show_ranks: bool,
+    pub anchor: OverlayAnchor,
+    /// Offset of the window relative to the anchor
+    pub offset: [f32; 2],
+    pub size: [f32; 2],
+}
+
+impl Default for SpectatorsListSettings {
+    fn default() -> Self {
+        Self {
+            show_targets: false,
+            show_count: true,
+            show_ranks: false,
+            anchor: OverlayAnchor::CenterLeft,
+            offset: [20.0, 0.0],
+            size: [220.0, 160.0],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RoundHudSettings {
+    pub show_score: bool,
+    pub show_round: bool,
+    pub show_timer: bool,
+    pub anchor: OverlayAnchor,
+    /// Offset of the panel relative to the anchor
+    pub offset: [f32; 2],
+}
+
+impl Default for RoundHudSettings {
+    fn default() -> Self {
+        Self {
+            show_score: true,
+            show_round: true,
+            show_timer: true,
+            anchor: OverlayAnchor::TopCenter,
+            offset: [0.0, 10.0],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct KeybindOverlaySettings {
+    /// List features which are currently disabled as well
+    pub show_inactive: bool,
+    pub anchor: OverlayAnchor,
+    /// Offset of the panel relative to the anchor
+    pub offset: [f32; 2],
+}
+
+impl Default for KeybindOverlaySettings {
+    fn default() -> Self {
+        Self {
+            show_inactive: true,
+            anchor: OverlayAnchor::CenterRight,
+            offset: [-20.0, 0.0],
+        }
+    }
+}
+
+/// Where metrics records are sent to
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MetricsDestination {
+    Disabled,
+    /// Append the records to a JSONL file in `LABHConfig/metrics`
+    LocalFile,
+    /// Submit the records through the driver interface
+    Remote,
+}
+
+/// Category of a metrics record which can be opted out of individually
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetricsCategory {
+    SettingsSnapshots,
+    FeatureToggles,
+    VersionInfo,
+}
+
+impl MetricsCategory {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::SettingsSnapshots => "Settings snapshots",
+            Self::FeatureToggles => "Feature toggles",
+            Self::VersionInfo => "Version info",
+        }
+    }
+}
+
+/// Metrics categories the user agreed to send
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MetricsCategorySettings {
+    pub settings_snapshots: bool,
+    pub feature_toggles: bool,
+    pub version_info: bool,
+}
+
+impl Default for MetricsCategorySettings {
+    fn default() -> Self {
+        Self {
+            settings_snapshots: true,
+            feature_toggles: true,
+            version_info: true,
+        }
+    }
+}
+
+impl MetricsCategorySettings {
+    pub fn allows(&self, category: MetricsCategory) -> bool {
+        match category {
+            MetricsCategory::SettingsSnapshots => self.settings_snapshots,
+            MetricsCategory::FeatureToggles => self.feature_toggles,
+            MetricsCategory::VersionInfo => self.version_info,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SpeedUnit {
+    UnitsPerSecond,
+    KilometersPerHour,
+    MetersPerSecond,
+}
+
+impl SpeedUnit {
+    /// Convert a speed in game units per second into this unit
+    pub fn convert(&self, units_per_second: f32) -> f32 {
+        match self {
+            Self::UnitsPerSecond => units_per_second,
+            Self::KilometersPerHour => units_per_second * UNITS_TO_METERS * 3.6,
+            Self::MetersPerSecond => units_per_second * UNITS_TO_METERS,
+        }
+    }
+
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Self::UnitsPerSecond => "u/s",
+            Self::KilometersPerHour => "km/h",
+            Self::MetersPerSecond => "m/s",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RenderLayerSettings {
+    pub world: bool,
+    pub hud: bool,
+    pub debug: bool,
+    /// Layers in the order they are drawn, later layers are drawn on top
+    pub order: Vec<RenderLayer>,
+}
+
+impl Default for RenderLayerSettings {
+    fn default() -> Self {
+        Self {
+            world: true,
+            hud: true,
+            debug: true,
+            order: RenderLayer::ALL.to_vec(),
+        }
+    }
+}
+
+impl RenderLayerSettings {
+    pub fn is_visible(&self, layer: RenderLayer) -> bool {
+        match layer {
+            RenderLayer::World => self.world,
+            RenderLayer::Hud => self.hud,
+            RenderLayer::Debug => self.debug,
+        }
+    }
+
+    /// Every layer exactly once in the order it should be drawn.
+    /// Duplicates in the configured order are ignored and missing layers are drawn last.
+    pub fn draw_order(&self) -> impl Iterator<Item = RenderLayer> + '_ {
+        let configured = self
+            .order
+            .iter()
+            .enumerate()
+            .filter(|(index, layer)| !self.order[..*index].contains(layer))
+            .map(|(_, layer)| *layer);
+        let missing = RenderLayer::ALL
+            .into_iter()
+            .filter(|layer| !self.order.contains(layer));
+
+        configured.chain(missing)
+    }
+}
+
+/// Widgets which are shared with capture software through an off-screen texture
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CaptureOutputSettings {
+    pub enabled: bool,
+    /// Name of the Spout sender capture software receives the texture from
+    pub sender_name: String,
+    pub spectators_list: bool,
+    pub bomb_timer: bool,
+    pub round_hud: bool,
+    pub movement_hud: bool,
+    pub keybind_overlay: bool,
+    pub team_economy: bool,
+    pub team_overview: bool,
+}
+
+impl Default for CaptureOutputSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sender_name: "CS2 Overlay".to_string(),
+            spectators_list: true,
+            bomb_timer: true,
+            round_hud: true,
+            movement_hud: false,
+            keybind_overlay: false,
+            team_economy: false,
+            team_overview: false,
+        }
+    }
+}
+
+impl CaptureOutputSettings {
+    /// Options for the render backend, None if the capture output is disabled
+    pub fn capture_options(&self) -> Option<CaptureOptions> {
+        if !self.enabled {
+            return None;
+        }
+
+        /* names of the windows the widgets are rendered in */
+        let windows = [
+            (self.spectators_list, "##spectators_list"),
+            (self.bomb_timer, "bomb_timer_panel"),
+            (self.round_hud, "round_hud_panel"),
+            (self.movement_hud, "movement_hud_panel"),
+            (self.keybind_overlay, "keybind_overlay_panel"),
+            (self.team_economy, "Team Economy"),
+            (self.team_overview, "Team Overview"),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, window)| window.to_string())
+        .collect();
+
+        Some(CaptureOptions {
+            sender_name: self.sender_name.clone(),
+            windows,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AudioCueSettings {
+    pub enabled: bool,
+    /// Sound file (wav, mp3, ogg or flac) played instead of the built in tone
+    pub sound_file: Option<PathBuf>,
+    /// Volume relative to the master volume (0.0 - 1.0)
+    pub volume: f32,
+}
+
+impl Default for AudioCueSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sound_file: None,
+            volume: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AudioAlertSettings {
+    /// Master volume of all cues (0.0 - 1.0)
+    pub volume: f32,
+    /// Cue settings keyed by the audio event name
+    pub cues: BTreeMap<String, AudioCueSettings>,
+}
+
+impl Default for AudioAlertSettings {
+    fn default() -> Self {
+        Self {
+            volume: 0.5,
+            cues: Default::default(),
+        }
+    }
+}
+
+impl AudioAlertSettings {
+    pub fn cue(&self, event: &str) -> AudioCueSettings {
+        self.cues.get(event).cloned().unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MovementHudSettings {
+    /// Show a graph of the recent horizontal speed
+    pub show_graph: bool,
+    /// Show whether the player is ducking or in the air
+    pub show_state: bool,
+    pub units: SpeedUnit,
+    pub anchor: OverlayAnchor,
+    /// Offset of the panel relative to the anchor
+    pub offset: [f32; 2],
+}
+
+impl Default for MovementHudSettings {
+    fn default() -> Self {
+        Self {
+            show_graph: true,
+            show_state: true,
+            units: SpeedUnit::UnitsPerSecond,
+            anchor: OverlayAnchor::BottomLeft,
+            offset: [20.0, -200.0],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct UtilityAlertSettings {
+    /// Max distance (in meters) of the enemy to show an alert
+    pub radius: f32,
+    /// How long an alert stays visible (in seconds)
+    pub duration: f32,
+    pub flashbang: bool,
+    pub smoke: bool,
+    pub hegrenade: bool,
+    pub molotov: bool,
+    pub decoy: bool,
+}
+
+impl Default for UtilityAlertSettings {
+    fn default() -> Self {
+        Self {
+            radius: 40.0,
+            duration: 3.0,
+            flashbang: true,
+            smoke: true,
+            hegrenade: true,
+            molotov: true,
+            decoy: false,
+        }
+    }
+}
+
 fn bool_true() -> bool { true }
 fn default_f32<const N: usize, const D: usize>() -> f32 { N as f32 / D as f32 }
 fn default_usize<const V: usize>() -> usize { V }
@@ -113,6 +575,53 @@ pub enum KeyToggleMode {
     Off,
 }
 
+/// Features which can be switched with an optional hotkey
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FeatureToggle {
+    GrenadeHelper,
+    GrenadeTrajectory,
+    Chams,
+    OffscreenArrows,
+    Watermark,
+}
+
+impl FeatureToggle {
+    pub const ALL: [FeatureToggle; 5] = [
+        FeatureToggle::GrenadeHelper,
+        FeatureToggle::GrenadeTrajectory,
+        FeatureToggle::Chams,
+        FeatureToggle::OffscreenArrows,
+        FeatureToggle::Watermark,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            FeatureToggle::GrenadeHelper => "Grenade Helper",
+            FeatureToggle::GrenadeTrajectory => "Grenade Trajectory",
+            FeatureToggle::Chams => "Chams",
+            FeatureToggle::OffscreenArrows => "Offscreen Arrows",
+            FeatureToggle::Watermark => "Watermark",
+        }
+    }
+}
+
+/// Hotkey of a feature. The feature is always active while no key is bound.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct FeatureHotkey {
+    pub mode: KeyToggleMode,
+    pub key: Option<HotKey>,
+}
+
+impl Default for FeatureHotkey {
+    fn default() -> Self {
+        Self {
+            mode: KeyToggleMode::Toggle,
+            key: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum GrenadeType {
     Smoke,
@@ -148,6 +657,40 @@ impl GrenadeSortOrder {
     }
 }
 
+/// How the grenade has to be thrown from a lineup
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GrenadeThrowTechnique {
+    #[default]
+    Standing,
+    Crouching,
+    JumpThrow,
+    RunJumpThrow,
+    WalkThrow,
+    RightClick,
+}
+
+impl GrenadeThrowTechnique {
+    pub const ALL: [(Self, &'static str); 6] = [
+        (Self::Standing, "Standing"),
+        (Self::Crouching, "Crouching"),
+        (Self::JumpThrow, "Jumpthrow"),
+        (Self::RunJumpThrow, "Run + Jumpthrow"),
+        (Self::WalkThrow, "Walk + Throw"),
+        (Self::RightClick, "Right Click"),
+    ];
+
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Self::Standing => "Stand still and throw",
+            Self::Crouching => "Hold crouch and throw",
+            Self::JumpThrow => "Jumpthrow",
+            Self::RunJumpThrow => "Hold W + jumpthrow",
+            Self::WalkThrow => "Hold W + shift and throw",
+            Self::RightClick => "Right click throw",
+        }
+    }
+}
+
 static GRENADE_SPOT_ID_INDEX: AtomicUsize = AtomicUsize::new(1);
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct GrenadeSpotInfo {
@@ -156,6 +699,10 @@ pub struct GrenadeSpotInfo {
     pub grenade_types: Vec<GrenadeType>,
     pub name: String,
     pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub throw_technique: GrenadeThrowTechnique,
     pub eye_position: [f32; 3],
     pub eye_direction: [f32; 2],
 }
@@ -192,6 +739,12 @@ pub struct GrenadeSettings {
     pub map_spots: HashMap<String, Vec<GrenadeSpotInfo>>,
     #[serde(default = "bool_true")]
     pub grenade_background: bool,
+    /// Capture the current position and view angles as a new lineup
+    #[serde(default)]
+    pub capture_key: Option<HotKey>,
+    /// Guide towards the closest lineup with an arrow, angle bars and the throw hint
+    #[serde(default = "bool_true")]
+    pub alignment_guidance: bool,
 }
 
 impl Default for GrenadeSettings {
@@ -210,28 +763,88 @@ impl Default for GrenadeSettings {
             color_angle_active: default_color::<0, 255, 0, 255>(),
             map_spots: HashMap::new(),
             grenade_background: bool_true(),
+            capture_key: None,
+            alignment_guidance: bool_true(),
         }
     }
 }
 
 with_prefix!(serde_prefix_grenade_helper "grenade_helper");
 
+/// Release feed queried by the update check
+pub const DEFAULT_UPDATE_FEED_URL: &str =
+    "https://api.github.com/repos/RylieHolmes/LabHub---credits-to-valthrun/releases/latest";
+
 #[derive(Clone, Deserialize, Serialize, PartialEq)]
 #[serde(default)]
 pub struct AppSettings {
     pub key_settings: HotKey,
     pub key_settings_ignore_insert_warning: bool,
+    /// Hides the overlay and suspends all enhancements until pressed again
+    pub key_panic: Option<HotKey>,
+    /// Minimize the controller console while the panic mode is active
+    pub panic_minimize_console: bool,
+    /// Start with the menu closed, without the intro and with a minimized console
+    pub start_hidden: bool,
     pub esp_mode: KeyToggleMode,
     pub esp_toggle: Option<HotKey>,
     pub esp_settings: BTreeMap<String, EspConfig>,
     pub esp_settings_enabled: BTreeMap<String, bool>,
+    /// Swap the default team colors for a palette contrasting the current map
+    pub esp_map_palette: bool,
+    /// User defined palettes keyed by map name, taking precedence over the built in ones
+    pub esp_map_palette_overrides: BTreeMap<String, MapPalette>,
+    /// Players (by Steam id with their last known name) which are never drawn by the ESP
+    pub esp_ignored_players: BTreeMap<u64, String>,
+    /// Players further away (in meters) are not drawn, 0 for no limit
+    pub esp_max_distance: f32,
+    /// Distance before the max ESP distance in which players fade out
+    pub esp_fade_distance: f32,
     pub bomb_timer: bool,
     pub bomb_label: bool,
+    pub bomb_damage: bool,
+    pub bomb_damage_rings: bool,
     pub spectators_list: bool,
+    pub spectators_list_settings: SpectatorsListSettings,
+    pub round_hud: bool,
+    pub round_hud_settings: RoundHudSettings,
+    pub movement_hud: bool,
+    pub movement_hud_settings: MovementHudSettings,
+    pub keybind_overlay: bool,
+    pub keybind_overlay_settings: KeybindOverlaySettings,
+    pub team_economy: bool,
+    pub team_overview: bool,
+    /// Steam Web API key used to resolve persona names and avatars.
+    /// Stored in the secrets file, never serialized with the settings.
+    /// Still read from older configs which contain the key.
+    #[serde(skip_serializing)]
+    pub steam_api_key: Option<String>,
+    pub utility_alerts: bool,
+    pub utility_alerts_settings: UtilityAlertSettings,
     pub labh_watermark: bool,
+    /// Replace player names with anonymized labels (for recording clips)
+    pub clip_safe_mode: bool,
+    /// Record kills, rounds and bomb events of the session to a JSONL file
+    pub session_event_log: bool,
+    /// Serve the game state via a local HTTP / WebSocket API
+    pub game_state_api: bool,
+    pub game_state_api_port: u16,
+    /// Track per player and per round statistics of the current match
+    pub match_statistics: bool,
+    /// Publish the map, score and alive state as Discord Rich Presence
+    pub discord_presence: bool,
+    /// Application id of the Discord application used for the presence
+    pub discord_presence_client_id: String,
+    /// Do not mention the overlay in the Discord presence
+    pub discord_presence_privacy: bool,
+    /// Play sound cues for events like a planted bomb or a spotted enemy
+    pub audio_alerts: bool,
+    pub audio_alerts_settings: AudioAlertSettings,
     pub mouse_x_360: i32,
     pub trigger_bot_mode: KeyToggleMode,
     pub key_trigger_bot: Option<HotKey>,
+    /// Optional hotkeys of features without a dedicated key setting
+    pub feature_hotkeys: BTreeMap<FeatureToggle, FeatureHotkey>,
     pub trigger_bot_team_check: bool,
     pub trigger_bot_delay_min: u32,
     pub trigger_bot_delay_max: u32,
@@ -241,11 +854,37 @@ pub struct AppSettings {
     pub aim_assist_recoil_min_bullets: u32,
     pub hide_overlay_from_screen_capture: bool,
     pub render_debug_window: bool,
+    /// Interval in which changed settings are saved in the background (in seconds), 0 disables the autosave
+    pub autosave_interval: u32,
+    /// Colors of the settings menu
+    pub theme: Theme,
+    /// Scale of the settings menu, None follows the system DPI
+    pub ui_scale: Option<f32>,
+    /// Device name of the monitor the overlay covers, None follows the game window
+    pub overlay_monitor: Option<String>,
+    /// Visibility of the overlay draw layers
+    pub render_layers: RenderLayerSettings,
+    pub capture_output: CaptureOutputSettings,
+    /// Skip frames of enhancements which exceed their frame budget
+    pub frame_budget_watchdog: bool,
+    /// Time an enhancement may spend in update and render per frame (in milliseconds)
+    pub frame_budget_ms: f32,
+    /// Per enhancement budgets keyed by the enhancement name, taking precedence over `frame_budget_ms`
+    pub frame_budget_overrides: BTreeMap<String, f32>,
+    /// Send metrics records to the metrics destination
     pub metrics: bool,
+    pub metrics_destination: MetricsDestination,
+    pub metrics_categories: MetricsCategorySettings,
+    /// Check the release feed for a newer version on startup
+    pub update_check: bool,
+    /// GitHub releases API endpoint of the latest release
+    pub update_feed_url: String,
     pub web_radar_url: Option<String>,
     pub web_radar_advanced_settings: bool,
     pub sniper_crosshair: bool,
     pub sniper_crosshair_settings: SniperCrosshairSettings,
+    pub custom_crosshair: bool,
+    pub custom_crosshair_settings: CrosshairSettings,
     pub grenade_trajectory: GrenadeTrajectorySettings,
     #[serde(flatten, with = "serde_prefix_grenade_helper")]
     pub grenade_helper: GrenadeSettings,
@@ -257,10 +896,29 @@ pub struct AppSettings {
     pub legit_aim_key: Option<HotKey>,
     pub legit_aim_bone: String,
 
+    /// Suspend the trigger bot and legit aim while in warmup
+    pub relax_features_in_warmup: bool,
+    /// Suspend the trigger bot and legit aim while in a knife round
+    pub relax_features_in_knife_round: bool,
+
+    pub measurement_tool: bool,
+    pub measurement_tool_key: Option<HotKey>,
+
     pub fps_limit: u32,
+    /// Rate the game state is read with by the update thread
+    pub update_tick_rate: u32,
+    /// Limit the overlay to a few frames per second while nothing is drawn and the menu is closed
+    pub idle_power_saving: bool,
+    /// HUD widgets can be moved with the mouse while the menu is closed
+    pub interactive_widgets: bool,
     pub imgui: Option<String>,
 }
 
+/// Smallest scale of the settings menu
+pub const UI_SCALE_MIN: f32 = 0.75;
+/// Largest scale of the settings menu
+pub const UI_SCALE_MAX: f32 = 2.0;
+
 impl Default for AppSettings {
     fn default() -> Self {
         let white_color = EspColor::Static { value: Color::from_u8([255, 255, 255, 255]) };
@@ -270,16 +928,27 @@ impl Default for AppSettings {
             box_type: EspBoxType::Box2D,
             box_color: white_color,
             box_width: 1.0,
+            box_corner_length: 0.25,
+            box_fill_alpha: 0.25,
+            box_fit_bones: false,
             skeleton: true,
             skeleton_color: white_color,
             skeleton_width: 1.0,
             health_bar: EspHealthBar::Left,
             health_bar_width: 4.0,
+            armor_bar: false,
+            armor_bar_color: ESP_COLOR_ARMOR,
+            flash_bar: false,
+            flash_bar_color: ESP_COLOR_FLASH_FULL,
+            flash_bar_countdown: true,
+            flash_bar_highlight_partial: true,
+            flash_bar_partial_color: ESP_COLOR_FLASH_PARTIAL,
             tracer_lines: EspTracePosition::None,
             tracer_lines_color: white_color,
             tracer_lines_width: 1.0,
             
             text_style: EspTextStyle::Shadow, // Default
+            text_scale: 1.0,
             text_outline_enabled: false,
             text_outline_color: white_color,
 
@@ -296,6 +965,8 @@ impl Default for AppSettings {
             
             info_ammo: false,
             info_ammo_color: white_color,
+            info_weapon_state: false,
+            info_weapon_state_color: white_color,
             info_hp_text: false,
             info_hp_text_color: green_color,
             info_flag_kit: false,
@@ -306,16 +977,43 @@ impl Default for AppSettings {
             info_flag_scoped_color: white_color,
             info_flag_flashed_color: white_color,
             info_flag_bomb_color: white_color,
+            info_flag_armor: false,
+            info_flag_armor_color: white_color,
             info_grenades: false,
             info_grenades_color: white_color,
+            info_money: false,
+            info_money_color: white_color,
+            info_buy_type: false,
+            info_buy_type_color: white_color,
+            info_rank: false,
+            info_rank_style: EspInfoStyle::Text,
+            info_rank_color: white_color,
+            info_avatar: false,
             
             // --- OFFSCREEN ARROWS (ADDED) ---
             offscreen_arrows: false,
             offscreen_arrows_color: white_color,
             offscreen_arrows_radius: 300.0,
             offscreen_arrows_size: 15.0,
+            offscreen_arrows_mode: EspOffscreenArrowMode::Side,
+            offscreen_arrows_ellipse: false,
+            offscreen_arrows_distance: false,
             // --------------------------------
 
+            ghost_marker: false,
+            ghost_marker_color: white_color,
+            ghost_marker_duration: 5.0,
+
+            trail: false,
+            trail_color: white_color,
+            trail_duration: 3.0,
+            trail_width: 2.0,
+
+            view_direction: false,
+            view_direction_color: white_color,
+            view_direction_length: 100.0,
+            view_direction_width: 1.0,
+
             head_dot: EspHeadDot::NotFilled,
             head_dot_color: white_color,
             head_dot_thickness: 1.0,
@@ -323,6 +1021,8 @@ impl Default for AppSettings {
             head_dot_z: 1.0,
             chams: false,
             chams_color: white_color,
+            chams_visibility: false,
+            chams_occluded_color: ESP_COLOR_CHAMS_OCCLUDED,
         });
 
         let mut friendly_settings = enemy_settings;
@@ -332,6 +1032,9 @@ impl Default for AppSettings {
 
         Self {
             key_settings: Key::Insert.into(),
+            key_panic: None,
+            panic_minimize_console: true,
+            start_hidden: false,
             key_settings_ignore_insert_warning: false,
             esp_mode: KeyToggleMode::AlwaysOn,
             esp_toggle: None,
@@ -343,13 +1046,43 @@ impl Default for AppSettings {
                 ("player.enemy".to_string(), true),
                 ("player.friendly".to_string(), true),
             ]),
+            esp_map_palette: false,
+            esp_map_palette_overrides: Default::default(),
+            esp_ignored_players: Default::default(),
+            esp_max_distance: 0.0,
+            esp_fade_distance: 10.0,
             bomb_timer: true,
             bomb_label: true,
+            bomb_damage: true,
+            bomb_damage_rings: false,
             spectators_list: false,
+            spectators_list_settings: Default::default(),
+            round_hud: false,
+            round_hud_settings: Default::default(),
+            movement_hud: false,
+            movement_hud_settings: Default::default(),
+            keybind_overlay: false,
+            keybind_overlay_settings: Default::default(),
+            team_economy: false,
+            team_overview: false,
+            steam_api_key: None,
+            utility_alerts: false,
+            utility_alerts_settings: Default::default(),
             labh_watermark: true,
+            clip_safe_mode: false,
+            session_event_log: false,
+            game_state_api: false,
+            game_state_api_port: 8787,
+            match_statistics: false,
+            discord_presence: false,
+            discord_presence_client_id: String::new(),
+            discord_presence_privacy: true,
+            audio_alerts: false,
+            audio_alerts_settings: Default::default(),
             mouse_x_360: 16364,
             trigger_bot_mode: KeyToggleMode::Trigger,
             key_trigger_bot: Some(Key::MouseMiddle.into()),
+            feature_hotkeys: Default::default(),
             trigger_bot_team_check: true,
             trigger_bot_delay_min: 10,
             trigger_bot_delay_max: 20,
@@ -359,11 +1092,26 @@ impl Default for AppSettings {
             aim_assist_recoil_min_bullets: 1,
             hide_overlay_from_screen_capture: false,
             render_debug_window: false,
-            metrics: true,
+            autosave_interval: 10,
+            theme: Theme::default(),
+            ui_scale: None,
+            overlay_monitor: None,
+            render_layers: Default::default(),
+            capture_output: Default::default(),
+            frame_budget_watchdog: false,
+            frame_budget_ms: 4.0,
+            frame_budget_overrides: Default::default(),
+            metrics: false,
+            metrics_destination: MetricsDestination::Remote,
+            metrics_categories: Default::default(),
+            update_check: true,
+            update_feed_url: DEFAULT_UPDATE_FEED_URL.to_string(),
             web_radar_url: None,
             web_radar_advanced_settings: false,
             sniper_crosshair: true,
             sniper_crosshair_settings: Default::default(),
+            custom_crosshair: false,
+            custom_crosshair_settings: Default::default(),
             grenade_trajectory: GrenadeTrajectorySettings::default(),
             grenade_helper: GrenadeSettings::default(),
 
@@ -373,27 +1121,166 @@ impl Default for AppSettings {
             legit_aim_key: Some(Key::MouseX1.into()), // Default to Mouse Button 4
             legit_aim_bone: "head_0".to_string(),
 
+            relax_features_in_warmup: false,
+            relax_features_in_knife_round: false,
+
+            measurement_tool: false,
+            measurement_tool_key: Some(Key::LeftAlt.into()),
+
             fps_limit: 144,
+            update_tick_rate: DEFAULT_UPDATE_TICK_RATE,
+            idle_power_saving: false,
+            interactive_widgets: false,
             imgui: None,
         }
     }
 }
 
+impl AppSettings {
+    pub fn map_palette(&self, map: &str) -> Option<MapPalette> {
+        self.esp_map_palette_overrides
+            .get(map)
+            .cloned()
+            .or_else(|| builtin_map_palette(map))
+    }
+
+    /// Destination of the metrics records, taking the metrics opt out into account
+    pub fn effective_metrics_destination(&self) -> MetricsDestination {
+        if self.metrics {
+            self.metrics_destination
+        } else {
+            MetricsDestination::Disabled
+        }
+    }
+
+    /// Display names of all features with their enabled state.
+    /// The order of the features never changes.
+    pub fn feature_states(&self) -> [(&'static str, bool); 26] {
+        [
+            ("ESP", self.esp_settings_enabled.values().any(|enabled| *enabled)),
+            ("Bomb Timer", self.bomb_timer),
+            ("Bomb Label", self.bomb_label),
+            ("Spectators List", self.spectators_list),
+            ("Round HUD", self.round_hud),
+            ("Movement HUD", self.movement_hud),
+            ("Keybind Overlay", self.keybind_overlay),
+            ("Team Economy", self.team_economy),
+            ("Team Overview", self.team_overview),
+            ("Utility Alerts", self.utility_alerts),
+            ("Watermark", self.labh_watermark),
+            ("Session Event Log", self.session_event_log),
+            ("Game State API", self.game_state_api),
+            ("Match Statistics", self.match_statistics),
+            ("Discord Presence", self.discord_presence),
+            ("Audio Alerts", self.audio_alerts),
+            ("Sniper Crosshair", self.sniper_crosshair),
+            ("Custom Crosshair", self.custom_crosshair),
+            ("Grenade Trajectory", self.grenade_trajectory.enabled),
+            ("Grenade Helper", self.grenade_helper.active),
+            ("Legit Aim", self.legit_aim_enabled),
+            ("Measurement Tool", self.measurement_tool),
+            ("Capture Output", self.capture_output.enabled),
+            ("Hide From Screen Capture", self.hide_overlay_from_screen_capture),
+            ("Frame Budget Watchdog", self.frame_budget_watchdog),
+            ("Render Debug Window", self.render_debug_window),
+        ]
+    }
+
+    /// Frame budget of the enhancement or None if the watchdog is disabled
+    pub fn frame_budget(&self, enhancement: &str) -> Option<Duration> {
+        if !self.frame_budget_watchdog {
+            return None;
+        }
+
+        let budget_ms = self
+            .frame_budget_overrides
+            .get(enhancement)
+            .copied()
+            .unwrap_or(self.frame_budget_ms);
+        Some(Duration::from_secs_f32(budget_ms.max(0.0) / 1000.0))
+    }
+
+    /// Scale of the settings menu, falling back to the system DPI scale
+    pub fn ui_scale(&self, system_scale: f32) -> f32 {
+        self.ui_scale
+            .unwrap_or(system_scale)
+            .clamp(UI_SCALE_MIN, UI_SCALE_MAX)
+    }
+
+    /// Settings as sent by the settings snapshot metrics, without the window layout and secrets
+    pub fn metrics_snapshot(&self) -> AppSettings {
+        let mut snapshot = self.clone();
+        snapshot.imgui = None;
+        snapshot.steam_api_key = None;
+        snapshot
+    }
+
+    /// Take over the values which belong to this machine from the current settings.
+    /// Used when replacing the settings with another config.
+    /// These are the secrets, the update feed and the metrics consent.
+    pub fn keep_local_values(&mut self, current: &AppSettings) {
+        self.steam_api_key = current.steam_api_key.clone();
+        self.update_feed_url = current.update_feed_url.clone();
+        self.metrics = current.metrics;
+        self.metrics_destination = current.metrics_destination;
+        self.metrics_categories = current.metrics_categories.clone();
+    }
+
+    /// The trigger bot and legit aim should be suspended in the current match phase
+    pub fn features_relaxed(&self, states: &StateRegistry) -> anyhow::Result<bool> {
+        if !self.relax_features_in_warmup && !self.relax_features_in_knife_round {
+            return Ok(false);
+        }
+
+        let phase = states.resolve::<StateMatchPhase>(())?;
+        Ok((self.relax_features_in_warmup && phase.warmup)
+            || (self.relax_features_in_knife_round && phase.knife_round))
+    }
+}
+
 impl State for AppSettings {
     type Parameter = ();
     fn cache_type() -> StateCacheType { StateCacheType::Persistent }
 }
 
-pub fn get_managed_configs_dir() -> anyhow::Result<PathBuf> {
+/// Directory within `LABHConfig` in the documents folder, created if it does not exist yet.
+/// An empty name returns `LABHConfig` itself.
+pub fn app_data_dir(sub: &str) -> anyhow::Result<PathBuf> {
     let user_dirs = UserDirs::new().context("failed to get user directories")?;
     let documents_dir = user_dirs.document_dir().context("failed to find documents directory")?;
-    let managed_configs_dir = documents_dir.join("LABHConfig").join("configs");
+    let mut data_dir = documents_dir.join("LABHConfig");
+    if !sub.is_empty() {
+        data_dir.push(sub);
+    }
 
-    fs::create_dir_all(&managed_configs_dir).with_context(|| format!("Failed to create managed configs directory at {}", managed_configs_dir.display()))?;
-    
-    Ok(managed_configs_dir)
+    fs::create_dir_all(&data_dir).with_context(|| format!("Failed to create directory at {}", data_dir.display()))?;
+
+    Ok(data_dir)
 }
 
+pub fn get_managed_configs_dir() -> anyhow::Result<PathBuf> {
+    app_data_dir("configs")
+}
+
+pub fn get_sessions_dir() -> anyhow::Result<PathBuf> {
+    app_data_dir("sessions")
+}
+
+pub fn get_recordings_dir() -> anyhow::Result<PathBuf> {
+    app_data_dir("recordings")
+}
+
+pub fn get_logs_dir() -> anyhow::Result<PathBuf> {
+    app_data_dir("logs")
+}
+
+pub fn get_metrics_dir() -> anyhow::Result<PathBuf> {
+    app_data_dir("metrics")
+}
+
+pub fn get_crashes_dir() -> anyhow::Result<PathBuf> {
+    app_data_dir("crashes")
+}
 
 pub fn get_settings_path() -> anyhow::Result<PathBuf> {
     let config_dir = get_managed_configs_dir()?;
@@ -418,15 +1305,72 @@ pub fn load_app_settings() -> anyhow::Result<AppSettings> {
         config.imgui = AppSettings::default().imgui;
     }
 
+    match load_app_secrets() {
+        /* older configs contain the secrets, they are moved into the secrets file with the next save */
+        Ok(Some(secrets)) => config.steam_api_key = secrets.steam_api_key,
+        Ok(None) => {}
+        Err(err) => log::warn!("Failed to load the secrets: {:#}", err),
+    }
+
     log::info!("Loaded app config from {}", config_path.to_string_lossy());
     Ok(config)
 }
 
+/// Save the app config. The config is written to a temporary file first and then
+/// renamed, so a crash while saving never leaves a partially written config behind.
 pub fn save_app_settings(settings: &AppSettings) -> anyhow::Result<()> {
     let config_path = get_settings_path()?;
-    let config = File::options().create(true).truncate(true).write(true).open(&config_path).with_context(|| format!("failed to open app config at {}", config_path.to_string_lossy()))?;
+    let temp_path = config_path.with_extension("yaml.tmp");
+
+    let config = File::options().create(true).truncate(true).write(true).open(&temp_path).with_context(|| format!("failed to open app config at {}", temp_path.to_string_lossy()))?;
     let mut config = BufWriter::new(config);
     serde_yaml::to_writer(&mut config, settings).context("failed to serialize config")?;
+    config.flush()?;
+    config.get_ref().sync_all().context("failed to flush app config")?;
+    drop(config);
+
+    fs::rename(&temp_path, &config_path).with_context(|| format!("failed to replace app config at {}", config_path.to_string_lossy()))?;
+    save_app_secrets(&AppSecrets {
+        steam_api_key: settings.steam_api_key.clone(),
+    })?;
     log::debug!("Saved app config.");
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Settings which must never leave this machine.
+/// They are kept out of the config files, share codes and metrics.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+struct AppSecrets {
+    steam_api_key: Option<String>,
+}
+
+fn get_secrets_path() -> anyhow::Result<PathBuf> {
+    Ok(app_data_dir("")?.join("secrets.yaml"))
+}
+
+/// Load the secrets, None if they have never been saved
+fn load_app_secrets() -> anyhow::Result<Option<AppSecrets>> {
+    let secrets_path = get_secrets_path()?;
+    if !secrets_path.is_file() {
+        return Ok(None);
+    }
+
+    let file = File::open(&secrets_path).with_context(|| format!("failed to open secrets at {}", secrets_path.to_string_lossy()))?;
+    let secrets = serde_yaml::from_reader(BufReader::new(file)).context("failed to parse secrets")?;
+    Ok(Some(secrets))
+}
+
+fn save_app_secrets(secrets: &AppSecrets) -> anyhow::Result<()> {
+    let secrets_path = get_secrets_path()?;
+    let temp_path = secrets_path.with_extension("yaml.tmp");
+
+    let file = File::options().create(true).truncate(true).write(true).open(&temp_path).with_context(|| format!("failed to open secrets at {}", temp_path.to_string_lossy()))?;
+    let mut writer = BufWriter::new(file);
+    serde_yaml::to_writer(&mut writer, secrets).context("failed to serialize secrets")?;
+    writer.flush()?;
+    drop(writer);
+
+    fs::rename(&temp_path, &secrets_path).with_context(|| format!("failed to replace secrets at {}", secrets_path.to_string_lossy()))?;
+    Ok(())
+}
@@ -1,22 +1,25 @@
 use anyhow::{Context, Result};
+use base64::Engine;
 use std::fs;
-use std::io::{BufReader, BufWriter};
-use std::path::{Path, PathBuf};
-use crate::settings::config::AppSettings;
-
-/// Returns the directory where user configurations are stored.
-pub fn get_configs_dir() -> Result<PathBuf> {
-    let user_dirs = directories::UserDirs::new().context("Could not get user directories")?;
-    let docs_dir = user_dirs.document_dir().context("Could not find the Documents folder")?;
-    let configs_dir = docs_dir.join("LABHConfig").join("configs");
-    fs::create_dir_all(&configs_dir)
-        .with_context(|| format!("Failed to create configs directory at {}", configs_dir.display()))?;
-    Ok(configs_dir)
-}
+use std::io::{BufReader, BufWriter, Read};
+use std::path::Path;
+use crate::settings::config::{
+    get_managed_configs_dir,
+    AppSettings,
+};
+
+/// Prefix of config share codes, the version allows changing the encoding later on
+const SHARE_CODE_PREFIX: &str = "LABH1:";
+
+/// Compression level of share codes, configs are small therefore the highest level is affordable
+const SHARE_CODE_COMPRESSION_LEVEL: i32 = 19;
+
+/// Upper limit of the decompressed config size, a share code is untrusted input
+const SHARE_CODE_MAX_DECODED_SIZE: u64 = 4 * 1024 * 1024;
 
 /// Lists all valid .yaml/.yml config files in the configs directory.
 pub fn list_configs() -> Result<Vec<String>> {
-    let configs_dir = get_configs_dir()?;
+    let configs_dir = get_managed_configs_dir()?;
     let mut configs = Vec::new();
 
     for entry in fs::read_dir(configs_dir)? {
@@ -38,7 +41,7 @@ pub fn list_configs() -> Result<Vec<String>> {
 
 /// Loads an AppSettings configuration from a given file name.
 pub fn load_config(name: &str) -> Result<AppSettings> {
-    let path = get_configs_dir()?.join(format!("{}.yaml", name));
+    let path = get_managed_configs_dir()?.join(format!("{}.yaml", name));
     let file = fs::File::open(&path)
         .with_context(|| format!("Failed to open config file at {}", path.display()))?;
     let reader = BufReader::new(file);
@@ -53,7 +56,7 @@ pub fn save_config(name: &str, settings: &AppSettings) -> Result<()> {
     if name.is_empty() {
         anyhow::bail!("Config name cannot be empty.");
     }
-    let path = get_configs_dir()?.join(format!("{}.yaml", name));
+    let path = get_managed_configs_dir()?.join(format!("{}.yaml", name));
     let file = fs::File::options().create(true).truncate(true).write(true).open(&path)
         .with_context(|| format!("Failed to create or open config file at {}", path.display()))?;
     let writer = BufWriter::new(file);
@@ -68,7 +71,7 @@ pub fn import_config(source_path: &Path) -> Result<()> {
     let file_name = source_path.file_name()
         .context("Could not get file name from source path")?;
 
-    let dest_path = get_configs_dir()?.join(file_name);
+    let dest_path = get_managed_configs_dir()?.join(file_name);
 
     fs::copy(source_path, &dest_path).with_context(|| {
         format!("Failed to copy config from {} to {}", source_path.display(), dest_path.display())
@@ -85,9 +88,139 @@ pub fn delete_config(name: &str) -> Result<()> {
         anyhow::bail!("The default configuration cannot be deleted.");
     }
 
-    let path = get_configs_dir()?.join(format!("{}.yaml", name));
+    let path = get_managed_configs_dir()?.join(format!("{}.yaml", name));
     fs::remove_file(&path)
         .with_context(|| format!("Failed to delete config file at {}", path.display()))?;
     log::info!("Deleted config '{}'", name);
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Encodes the settings as a share code which can be exchanged as plain text.
+/// The imgui window layout and the values local to this machine are not part of the share code.
+pub fn encode_share_code(settings: &AppSettings) -> Result<String> {
+    let mut settings = settings.clone();
+    settings.imgui = None;
+    settings.keep_local_values(&AppSettings::default());
+
+    let json = serde_json::to_vec(&settings).context("Failed to serialize config")?;
+    let compressed = zstd::encode_all(json.as_slice(), SHARE_CODE_COMPRESSION_LEVEL)
+        .context("Failed to compress config")?;
+
+    Ok(format!(
+        "{}{}",
+        SHARE_CODE_PREFIX,
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(compressed)
+    ))
+}
+
+/// Decodes a share code created by [encode_share_code].
+/// Values local to this machine are reset to their defaults even if the share code contains them.
+pub fn decode_share_code(share_code: &str) -> Result<AppSettings> {
+    let encoded = share_code
+        .trim()
+        .strip_prefix(SHARE_CODE_PREFIX)
+        .context("Not a config share code")?;
+
+    let compressed = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(encoded)
+        .context("Invalid share code encoding")?;
+
+    let mut json = Vec::new();
+    zstd::stream::read::Decoder::new(compressed.as_slice())
+        .context("Failed to decompress share code")?
+        .take(SHARE_CODE_MAX_DECODED_SIZE + 1)
+        .read_to_end(&mut json)
+        .context("Failed to decompress share code")?;
+    if json.len() as u64 > SHARE_CODE_MAX_DECODED_SIZE {
+        anyhow::bail!("Share code exceeds {} bytes", SHARE_CODE_MAX_DECODED_SIZE);
+    }
+
+    let mut settings: AppSettings = serde_json::from_slice(&json).context("Failed to parse shared config")?;
+    settings.imgui = None;
+    settings.keep_local_values(&AppSettings::default());
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod test {
+    use base64::Engine;
+
+    use super::{
+        decode_share_code,
+        encode_share_code,
+        SHARE_CODE_COMPRESSION_LEVEL,
+        SHARE_CODE_MAX_DECODED_SIZE,
+        SHARE_CODE_PREFIX,
+    };
+    use crate::settings::config::{
+        AppSettings,
+        MetricsDestination,
+    };
+
+    fn share_code_from_json(json: &[u8]) -> String {
+        let compressed = zstd::encode_all(json, SHARE_CODE_COMPRESSION_LEVEL).unwrap();
+        format!(
+            "{}{}",
+            SHARE_CODE_PREFIX,
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(compressed)
+        )
+    }
+
+    #[test]
+    fn share_code_round_trip() {
+        let settings = AppSettings {
+            esp_max_distance: 42.0,
+            clip_safe_mode: true,
+            ..Default::default()
+        };
+
+        let decoded = decode_share_code(&encode_share_code(&settings).unwrap()).unwrap();
+        assert_eq!(decoded.esp_max_distance, 42.0);
+        assert!(decoded.clip_safe_mode);
+    }
+
+    #[test]
+    fn share_code_strips_local_values() {
+        let defaults = AppSettings::default();
+        let settings = AppSettings {
+            steam_api_key: Some("secret".to_string()),
+            update_feed_url: "https://example.com/feed".to_string(),
+            metrics: !defaults.metrics,
+            metrics_destination: MetricsDestination::LocalFile,
+            ..Default::default()
+        };
+
+        let decoded = decode_share_code(&encode_share_code(&settings).unwrap()).unwrap();
+        assert_eq!(decoded.steam_api_key, None);
+        assert_eq!(decoded.update_feed_url, defaults.update_feed_url);
+        assert_eq!(decoded.metrics, defaults.metrics);
+        assert_eq!(decoded.metrics_destination, defaults.metrics_destination);
+    }
+
+    #[test]
+    fn share_code_ignores_injected_local_values() {
+        let mut json = serde_json::to_value(AppSettings::default()).unwrap();
+        json["steam_api_key"] = "injected".into();
+        json["update_feed_url"] = "https://example.com/feed".into();
+
+        let share_code = share_code_from_json(&serde_json::to_vec(&json).unwrap());
+        let decoded = decode_share_code(&share_code).unwrap();
+        assert_eq!(decoded.steam_api_key, None);
+        assert_eq!(decoded.update_feed_url, AppSettings::default().update_feed_url);
+    }
+
+    #[test]
+    fn share_code_size_limit() {
+        let payload = vec![b' '; SHARE_CODE_MAX_DECODED_SIZE as usize + 1];
+        let share_code = share_code_from_json(&payload);
+        assert!(share_code.len() < 64 * 1024);
+        assert!(decode_share_code(&share_code).is_err());
+    }
+
+    #[test]
+    fn share_code_malformed() {
+        assert!(decode_share_code("not a share code").is_err());
+        assert!(decode_share_code(&format!("{}!!!", SHARE_CODE_PREFIX)).is_err());
+        assert!(decode_share_code(&share_code_from_json(b"{ broken")).is_err());
+    }
+}
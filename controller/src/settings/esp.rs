@@ -36,6 +36,7 @@ impl Color {
     }
     pub fn set_alpha_u8(&mut self, alpha: u8) { let mut value = self.as_u8(); value[3] = alpha; *self = Self::from_u8(value); }
     pub fn set_alpha_f32(&mut self, alpha: f32) { let mut value = self.as_u8(); value[3] = (alpha * 255.0) as u8; *self = Self::from_u8(value); }
+    pub fn scale_alpha(&mut self, scale: f32) { self.set_alpha_f32(self.as_f32()[3] * scale.clamp(0.0, 1.0)); }
 }
 impl From<[u8; 4]> for Color { fn from(value: [u8; 4]) -> Self { Self::from_u8(value) } }
 impl From<[f32; 4]> for Color { fn from(value: [f32; 4]) -> Self { Self::from_f32(value) } }
@@ -57,6 +58,20 @@ impl Default for EspColor { fn default() -> Self { Self::Static { value: Color::
 impl EspColor {
     pub const fn from_rgba(r: f32, g: f32, b: f32, a: f32) -> Self { Self::Static { value: Color::from_f32([r, g, b, a]), } }
 
+    /// Multiply the alpha of all colors of this color mode with the scale
+    pub fn with_alpha_scale(&self, scale: f32) -> Self {
+        let mut result = *self;
+        match &mut result {
+            Self::HealthBasedRainbow { alpha } => *alpha *= scale.clamp(0.0, 1.0),
+            Self::HealthBased { max, mid, min } => { max.scale_alpha(scale); mid.scale_alpha(scale); min.scale_alpha(scale); }
+            Self::Static { value } => value.scale_alpha(scale),
+            Self::DistanceBased { near, mid, far } => { near.scale_alpha(scale); mid.scale_alpha(scale); far.scale_alpha(scale); }
+            Self::GradientPulse { start, end, .. } => { start.scale_alpha(scale); end.scale_alpha(scale); }
+            Self::GradientVertical { top, bottom } => { top.scale_alpha(scale); bottom.scale_alpha(scale); }
+        }
+        result
+    }
+
     fn interpolate_color(start: [f32; 4], end: [f32; 4], t: f32) -> [f32; 4] {
         [
             start[0] + (end[0] - start[0]) * t,
@@ -110,27 +125,60 @@ impl EspColorType {
 }
 
 #[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)] pub enum EspHealthBar { None, Top, Bottom, Left, Right }
-#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)] pub enum EspBoxType { None, Box2D, Box3D }
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)] pub enum EspBoxType { None, Box2D, Box3D, Corner, Filled }
+
+impl EspBoxType {
+    /// Whether the box is drawn as a screen space rectangle around the player
+    pub fn is_2d(&self) -> bool { matches!(self, Self::Box2D | Self::Corner | Self::Filled) }
+}
 #[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)] pub enum EspTracePosition { None, TopLeft, TopCenter, TopRight, Center, BottomLeft, BottomCenter, BottomRight }
 #[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)] pub enum EspHeadDot { None, Filled, NotFilled }
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd, Debug)] pub enum EspOffscreenArrowMode { Side, Radial }
 #[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd, Debug)] pub enum EspInfoStyle { Text, Icon }
 #[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd, Debug)] pub enum EspTextStyle { Shadow, Outline, Neon }
 
+impl EspTextStyle {
+    pub fn text_style(&self) -> overlay::TextStyle {
+        match self {
+            Self::Shadow => overlay::TextStyle::Shadow { offset: [1.0, 1.0], color: [0.0, 0.0, 0.0, 1.0] },
+            Self::Outline => overlay::TextStyle::OUTLINE,
+            Self::Neon => overlay::TextStyle::GLOW,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)]
 #[serde(default)]
 pub struct EspPlayerSettings {
     pub box_type: EspBoxType,
     pub box_color: EspColor,
     pub box_width: f32,
+    /// Length of the corner lines relative to the box size
+    pub box_corner_length: f32,
+    /// Opacity of the filled box relative to the box color
+    pub box_fill_alpha: f32,
+    /// Fit 2D boxes to the projected bones instead of the static model hull
+    pub box_fit_bones: bool,
     pub skeleton: bool,
     pub skeleton_color: EspColor,
     pub skeleton_width: f32,
     pub health_bar: EspHealthBar,
     pub health_bar_width: f32,
+    pub armor_bar: bool,
+    pub armor_bar_color: EspColor,
+    /// Bar above the player showing the remaining flash blindness
+    pub flash_bar: bool,
+    pub flash_bar_color: EspColor,
+    pub flash_bar_countdown: bool,
+    /// Use a different color once the player is only partially blind
+    pub flash_bar_highlight_partial: bool,
+    pub flash_bar_partial_color: EspColor,
     pub tracer_lines: EspTracePosition,
     pub tracer_lines_color: EspColor,
     pub tracer_lines_width: f32,
     pub text_style: EspTextStyle,
+    /// Scale of the info texts on top of the distance based scaling
+    pub text_scale: f32,
     pub text_outline_enabled: bool,
     pub text_outline_color: EspColor,
     pub info_name: bool,
@@ -144,24 +192,59 @@ pub struct EspPlayerSettings {
     pub info_weapon_color: EspColor,
     pub info_ammo: bool,
     pub info_ammo_color: EspColor,
+    /// Tag showing if the player is reloading, switching weapons or out of ammo
+    pub info_weapon_state: bool,
+    pub info_weapon_state_color: EspColor,
     pub info_hp_text: bool,
     pub info_hp_text_color: EspColor,
     pub info_flag_kit: bool,
     pub info_flag_scoped: bool,
     pub info_flag_flashed: bool,
     pub info_flag_bomb: bool,
+    pub info_flag_armor: bool,
+    pub info_flag_armor_color: EspColor,
     pub info_flag_kit_color: EspColor,
     pub info_flag_scoped_color: EspColor,
     pub info_flag_flashed_color: EspColor,
     pub info_flag_bomb_color: EspColor,
     pub info_grenades: bool,
     pub info_grenades_color: EspColor,
+    pub info_money: bool,
+    pub info_money_color: EspColor,
+    pub info_buy_type: bool,
+    pub info_buy_type_color: EspColor,
+    pub info_rank: bool,
+    pub info_rank_style: EspInfoStyle,
+    pub info_rank_color: EspColor,
+    /// Steam avatar and persona name (requires a Steam Web API key)
+    pub info_avatar: bool,
     // --- OFFSCREEN ARROWS ---
     pub offscreen_arrows: bool,
     pub offscreen_arrows_color: EspColor,
     pub offscreen_arrows_radius: f32,
     pub offscreen_arrows_size: f32,
+    /// Side only shows the closest player left or right, radial shows every player around the screen center
+    pub offscreen_arrows_mode: EspOffscreenArrowMode,
+    /// Stretch the radial arrows to an ellipse matching the screen aspect ratio
+    pub offscreen_arrows_ellipse: bool,
+    pub offscreen_arrows_distance: bool,
     // ------------------------
+    /// Marker at the last position the player has been spotted at, fading out over the duration in seconds
+    pub ghost_marker: bool,
+    pub ghost_marker_color: EspColor,
+    pub ghost_marker_duration: f32,
+    /// Fading line along the recent positions of the player
+    pub trail: bool,
+    pub trail_color: EspColor,
+    /// Time in seconds a position stays part of the trail
+    pub trail_duration: f32,
+    pub trail_width: f32,
+    /// Line from the head in the direction the player is looking
+    pub view_direction: bool,
+    pub view_direction_color: EspColor,
+    /// Length of the view direction line in world units
+    pub view_direction_length: f32,
+    pub view_direction_width: f32,
     pub head_dot: EspHeadDot,
     pub head_dot_color: EspColor,
     pub head_dot_thickness: f32,
@@ -169,10 +252,59 @@ pub struct EspPlayerSettings {
     pub head_dot_z: f32,
     pub chams: bool,
     pub chams_color: EspColor,
+    /// Use a separate color for the body parts hidden behind the map
+    pub chams_visibility: bool,
+    pub chams_occluded_color: EspColor,
 }
 
 const ESP_COLOR_FRIENDLY: EspColor = EspColor::from_rgba(0.0, 1.0, 0.0, 0.75);
 const ESP_COLOR_ENEMY: EspColor = EspColor::from_rgba(1.0, 0.0, 0.0, 0.75);
+/// Color of body parts hidden behind the map when using the chams visibility colors
+pub const ESP_COLOR_CHAMS_OCCLUDED: EspColor = EspColor::from_rgba(1.0, 1.0, 0.0, 0.5);
+pub const ESP_COLOR_ARMOR: EspColor = EspColor::from_rgba(0.3, 0.6, 1.0, 0.9);
+pub const ESP_COLOR_FLASH_FULL: EspColor = EspColor::from_rgba(1.0, 1.0, 1.0, 0.9);
+pub const ESP_COLOR_FLASH_PARTIAL: EspColor = EspColor::from_rgba(1.0, 0.75, 0.2, 0.9);
+
+/// Base colors for friendly and enemy players on a specific map.
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq)]
+pub struct MapPalette {
+    pub friendly: Color,
+    pub enemy: Color,
+}
+
+impl MapPalette {
+    const fn new(friendly: [f32; 3], enemy: [f32; 3]) -> Self {
+        Self {
+            friendly: Color::from_f32([friendly[0], friendly[1], friendly[2], 1.0]),
+            enemy: Color::from_f32([enemy[0], enemy[1], enemy[2], 1.0]),
+        }
+    }
+
+    pub fn color(&self, enemy: bool) -> Color {
+        if enemy { self.enemy } else { self.friendly }
+    }
+}
+
+/// Bright sand/stone maps where yellow and green wash out
+const PALETTE_BRIGHT_WARM: MapPalette = MapPalette::new([0.0, 0.6, 1.0], [1.0, 0.0, 0.6]);
+/// Dark or saturated maps where the default red blends into walls and shadows
+const PALETTE_DARK: MapPalette = MapPalette::new([0.0, 1.0, 0.8], [1.0, 0.9, 0.0]);
+/// Cold blue/grey maps
+const PALETTE_COLD: MapPalette = MapPalette::new([0.2, 1.0, 0.2], [1.0, 0.3, 0.0]);
+/// Green foliage maps
+const PALETTE_FOLIAGE: MapPalette = MapPalette::new([0.0, 0.8, 1.0], [1.0, 0.0, 0.8]);
+
+/// Built in palettes for the known maps, chosen to contrast with the dominant map brightness.
+pub fn builtin_map_palette(map: &str) -> Option<MapPalette> {
+    let palette = match map {
+        "de_anubis" | "de_dust2" | "de_mirage" => PALETTE_BRIGHT_WARM,
+        "de_inferno" | "de_train" => PALETTE_DARK,
+        "de_nuke" | "de_vertigo" | "cs_office" => PALETTE_COLD,
+        "de_ancient" | "de_overpass" | "cs_italy" => PALETTE_FOLIAGE,
+        _ => return None,
+    };
+    Some(palette)
+}
 impl EspPlayerSettings {
     pub fn new(target: &EspSelector) -> Self {
         let color = match target {
@@ -181,30 +313,129 @@ impl EspPlayerSettings {
             _ => EspColor::from_rgba(1.0, 1.0, 1.0, 0.75),
         };
         Self {
-            box_type: EspBoxType::None, box_color: color, box_width: 1.0,
+            box_type: EspBoxType::None, box_color: color, box_width: 1.0, box_corner_length: 0.25, box_fill_alpha: 0.25, box_fit_bones: false,
             skeleton: true, skeleton_color: color, skeleton_width: 1.0,
             health_bar: EspHealthBar::None, health_bar_width: 4.0,
+            armor_bar: false, armor_bar_color: ESP_COLOR_ARMOR,
+            flash_bar: false, flash_bar_color: ESP_COLOR_FLASH_FULL, flash_bar_countdown: true,
+            flash_bar_highlight_partial: true, flash_bar_partial_color: ESP_COLOR_FLASH_PARTIAL,
             tracer_lines: EspTracePosition::None, tracer_lines_color: color, tracer_lines_width: 1.0,
             text_style: EspTextStyle::Shadow,
+            text_scale: 1.0,
             text_outline_enabled: false, text_outline_color: color,
             info_name: false, info_name_color: color,
             info_distance: false, info_distance_color: color,
             near_players: false, near_players_distance: 20.0,
             info_weapon: false, info_weapon_style: EspInfoStyle::Text, info_weapon_color: color,
             info_ammo: false, info_ammo_color: color,
+            info_weapon_state: false, info_weapon_state_color: color,
             info_hp_text: false, info_hp_text_color: color,
             info_flag_kit: false, info_flag_scoped: false, info_flag_flashed: false, info_flag_bomb: false,
             info_flag_kit_color: color, info_flag_scoped_color: color, info_flag_flashed_color: color, info_flag_bomb_color: color,
+            info_flag_armor: false, info_flag_armor_color: color,
             info_grenades: false, info_grenades_color: color,
+            info_money: false, info_money_color: color,
+            info_buy_type: false, info_buy_type_color: color,
+            info_rank: false, info_rank_style: EspInfoStyle::Text, info_rank_color: color,
+            info_avatar: false,
             // --- OFFSCREEN ARROWS ---
             offscreen_arrows: false, 
             offscreen_arrows_color: color,
             offscreen_arrows_radius: 300.0,
             offscreen_arrows_size: 15.0,
+            offscreen_arrows_mode: EspOffscreenArrowMode::Side,
+            offscreen_arrows_ellipse: false,
+            offscreen_arrows_distance: false,
             // ------------------------
+            ghost_marker: false, ghost_marker_color: color, ghost_marker_duration: 5.0,
+            trail: false, trail_color: color, trail_duration: 3.0, trail_width: 2.0,
+            view_direction: false, view_direction_color: color, view_direction_length: 100.0, view_direction_width: 1.0,
             head_dot: EspHeadDot::None, head_dot_color: color, head_dot_thickness: 1.0, head_dot_base_radius: 4.0, head_dot_z: 1.0,
-            chams: false, chams_color: color,
+            chams: false, chams_color: color, chams_visibility: false, chams_occluded_color: ESP_COLOR_CHAMS_OCCLUDED,
+        }
+    }
+
+    /// Replace all static colors still using the team default color with the given base color.
+    /// Custom colors set by the user are kept as is.
+    pub fn with_base_color(&self, enemy: bool, base_color: Color) -> Self {
+        let team_color = if enemy { ESP_COLOR_ENEMY } else { ESP_COLOR_FRIENDLY };
+        let EspColor::Static { value: default_color } = team_color else {
+            return *self;
+        };
+
+        let mut result = *self;
+        for color in [
+            &mut result.box_color,
+            &mut result.skeleton_color,
+            &mut result.tracer_lines_color,
+            &mut result.info_name_color,
+            &mut result.info_distance_color,
+            &mut result.info_weapon_color,
+            &mut result.info_ammo_color,
+            &mut result.info_weapon_state_color,
+            &mut result.info_hp_text_color,
+            &mut result.info_flag_armor_color,
+            &mut result.info_flag_kit_color,
+            &mut result.info_flag_scoped_color,
+            &mut result.info_flag_flashed_color,
+            &mut result.info_flag_bomb_color,
+            &mut result.info_grenades_color,
+            &mut result.info_money_color,
+            &mut result.info_buy_type_color,
+            &mut result.info_rank_color,
+            &mut result.offscreen_arrows_color,
+            &mut result.head_dot_color,
+            &mut result.chams_color,
+        ] {
+            let EspColor::Static { value } = color else { continue };
+            if value.as_u8()[..3] != default_color.as_u8()[..3] {
+                continue;
+            }
+
+            let alpha = value.as_u8()[3];
+            *value = base_color;
+            value.set_alpha_u8(alpha);
+        }
+        result
+    }
+
+    /// Multiply the alpha of all colors with the scale, e.g. to fade out far away players
+    pub fn with_alpha_scale(&self, scale: f32) -> Self {
+        let mut result = *self;
+        for color in [
+            &mut result.box_color,
+            &mut result.skeleton_color,
+            &mut result.armor_bar_color,
+            &mut result.flash_bar_color,
+            &mut result.flash_bar_partial_color,
+            &mut result.tracer_lines_color,
+            &mut result.text_outline_color,
+            &mut result.info_name_color,
+            &mut result.info_distance_color,
+            &mut result.info_weapon_color,
+            &mut result.info_ammo_color,
+            &mut result.info_weapon_state_color,
+            &mut result.info_hp_text_color,
+            &mut result.info_flag_armor_color,
+            &mut result.info_flag_kit_color,
+            &mut result.info_flag_scoped_color,
+            &mut result.info_flag_flashed_color,
+            &mut result.info_flag_bomb_color,
+            &mut result.info_grenades_color,
+            &mut result.info_money_color,
+            &mut result.info_buy_type_color,
+            &mut result.info_rank_color,
+            &mut result.offscreen_arrows_color,
+            &mut result.ghost_marker_color,
+            &mut result.trail_color,
+            &mut result.view_direction_color,
+            &mut result.head_dot_color,
+            &mut result.chams_color,
+            &mut result.chams_occluded_color,
+        ] {
+            *color = color.with_alpha_scale(scale);
         }
+        result
     }
 }
 
@@ -212,29 +443,45 @@ impl Default for EspPlayerSettings {
     fn default() -> Self {
         let neutral_color = EspColor::from_rgba(1.0, 1.0, 1.0, 0.75);
         Self {
-            box_type: EspBoxType::Box2D, box_color: neutral_color, box_width: 1.0,
+            box_type: EspBoxType::Box2D, box_color: neutral_color, box_width: 1.0, box_corner_length: 0.25, box_fill_alpha: 0.25, box_fit_bones: false,
             skeleton: true, skeleton_color: neutral_color, skeleton_width: 1.0,
             health_bar: EspHealthBar::Left, health_bar_width: 4.0,
+            armor_bar: false, armor_bar_color: ESP_COLOR_ARMOR,
+            flash_bar: false, flash_bar_color: ESP_COLOR_FLASH_FULL, flash_bar_countdown: true,
+            flash_bar_highlight_partial: true, flash_bar_partial_color: ESP_COLOR_FLASH_PARTIAL,
             tracer_lines: EspTracePosition::None, tracer_lines_color: neutral_color, tracer_lines_width: 1.0,
             text_style: EspTextStyle::Shadow,
+            text_scale: 1.0,
             text_outline_enabled: false, text_outline_color: neutral_color,
             info_name: true, info_name_color: neutral_color,
             info_distance: true, info_distance_color: neutral_color,
             near_players: false, near_players_distance: 20.0,
             info_weapon: true, info_weapon_style: EspInfoStyle::Text, info_weapon_color: neutral_color,
             info_ammo: false, info_ammo_color: neutral_color,
+            info_weapon_state: false, info_weapon_state_color: neutral_color,
             info_hp_text: false, info_hp_text_color: neutral_color,
             info_flag_kit: true, info_flag_scoped: true, info_flag_flashed: true, info_flag_bomb: true,
             info_flag_kit_color: neutral_color, info_flag_scoped_color: neutral_color, info_flag_flashed_color: neutral_color, info_flag_bomb_color: neutral_color,
+            info_flag_armor: false, info_flag_armor_color: neutral_color,
             info_grenades: false, info_grenades_color: neutral_color,
+            info_money: false, info_money_color: neutral_color,
+            info_buy_type: false, info_buy_type_color: neutral_color,
+            info_rank: false, info_rank_style: EspInfoStyle::Text, info_rank_color: neutral_color,
+            info_avatar: false,
             // --- OFFSCREEN ARROWS ---
             offscreen_arrows: false,
             offscreen_arrows_color: neutral_color,
             offscreen_arrows_radius: 300.0,
             offscreen_arrows_size: 15.0,
+            offscreen_arrows_mode: EspOffscreenArrowMode::Side,
+            offscreen_arrows_ellipse: false,
+            offscreen_arrows_distance: false,
             // ------------------------
+            ghost_marker: false, ghost_marker_color: neutral_color, ghost_marker_duration: 5.0,
+            trail: false, trail_color: neutral_color, trail_duration: 3.0, trail_width: 2.0,
+            view_direction: false, view_direction_color: neutral_color, view_direction_length: 100.0, view_direction_width: 1.0,
             head_dot: EspHeadDot::NotFilled, head_dot_color: neutral_color, head_dot_thickness: 1.0, head_dot_base_radius: 4.0, head_dot_z: 1.0,
-            chams: false, chams_color: neutral_color,
+            chams: false, chams_color: neutral_color, chams_visibility: false, chams_occluded_color: ESP_COLOR_CHAMS_OCCLUDED,
         }
     }
 }
@@ -320,6 +567,20 @@ impl EspSelector {
         }
     }
 
+    /// Top level selectors of the selector tree
+    pub fn roots() -> Vec<Self> {
+        vec![EspSelector::Player, EspSelector::Chicken, EspSelector::Weapon]
+    }
+
+    /// The selector followed by all its parents, in the order configs are resolved
+    pub fn inheritance_chain(&self) -> Vec<Self> {
+        let mut chain = vec![*self];
+        while let Some(parent) = chain.last().and_then(Self::parent) {
+            chain.push(parent);
+        }
+        chain
+    }
+
     pub fn parent(&self) -> Option<Self> {
         match self {
             Self::None => None,
@@ -337,7 +598,7 @@ impl EspSelector {
         match self {
             EspSelector::None => vec![],
             EspSelector::Player => vec![ EspSelector::PlayerTeam { enemy: false }, EspSelector::PlayerTeam { enemy: true }, ],
-            EspSelector::PlayerTeam { .. } => vec![],
+            EspSelector::PlayerTeam { enemy } => vec![ EspSelector::PlayerTeamVisibility { enemy: *enemy, visible: true }, EspSelector::PlayerTeamVisibility { enemy: *enemy, visible: false }, ],
             EspSelector::PlayerTeamVisibility { .. } => vec![],
             EspSelector::Chicken => vec![],
             EspSelector::Weapon => vec![
@@ -415,6 +676,27 @@ fn draw_image_with_thickness(
         .build();
 }
 
+/// Translucent fill of a 2D box, fading from the top to the bottom color
+pub fn draw_box_fill(draw_list: &DrawListMut, min: [f32; 2], max: [f32; 2], top: [f32; 4], bottom: [f32; 4], alpha: f32) {
+    let top = [top[0], top[1], top[2], top[3] * alpha];
+    let bottom = [bottom[0], bottom[1], bottom[2], bottom[3] * alpha];
+    draw_list.add_rect_filled_multicolor(min, max, top, top, bottom, bottom);
+}
+
+/// Corner brackets of a 2D box. `corner_length` is relative to the box size.
+pub fn draw_box_corners(draw_list: &DrawListMut, min: [f32; 2], max: [f32; 2], top: [f32; 4], bottom: [f32; 4], width: f32, corner_length: f32) {
+    let corner_length = corner_length.clamp(0.0, 0.5);
+    let length_x = (max[0] - min[0]) * corner_length;
+    let length_y = (max[1] - min[1]) * corner_length;
+
+    for (corner_y, direction_y, color) in [(min[1], 1.0, top), (max[1], -1.0, bottom)] {
+        for (corner_x, direction_x) in [(min[0], 1.0), (max[0], -1.0)] {
+            draw_list.add_line([corner_x, corner_y], [corner_x + direction_x * length_x, corner_y], color).thickness(width).build();
+            draw_list.add_line([corner_x, corner_y], [corner_x, corner_y + direction_y * length_y], color).thickness(width).build();
+        }
+    }
+}
+
 pub fn draw_player_esp(
     draw_list: &DrawListMut,
     ui: &Ui,
@@ -537,7 +819,21 @@ pub fn draw_player_esp(
         }
     }
 
-    if settings.box_type == EspBoxType::Box2D {
+    if matches!(settings.box_type, EspBoxType::Filled | EspBoxType::Corner) {
+        let mut top = settings.box_color.calculate_color(info.health, info.distance, time, 1.0);
+        let mut bottom = settings.box_color.calculate_color(info.health, info.distance, time, 0.0);
+        top[3] *= alpha;
+        bottom[3] *= alpha;
+
+        let box_max = [box_pos[0] + box_size[0], box_pos[1] + box_size[1]];
+        if settings.box_type == EspBoxType::Filled {
+            draw_box_fill(draw_list, box_pos, box_max, top, bottom, settings.box_fill_alpha);
+        } else {
+            draw_box_corners(draw_list, box_pos, box_max, top, bottom, settings.box_width, settings.box_corner_length);
+        }
+    }
+
+    if matches!(settings.box_type, EspBoxType::Box2D | EspBoxType::Filled) {
          if let EspColor::GradientVertical { top, bottom } = settings.box_color {
              let mut c_top = top.as_f32(); let mut c_bot = bottom.as_f32();
              c_top[3] *= alpha;
@@ -615,15 +911,16 @@ pub fn draw_player_esp(
 
     let mut cursor_y = box_pos[1] + box_size[1] + 4.0;
     let box_center_x = box_pos[0] + box_size[0] / 2.0;
+    let line_height = 21.0 * settings.text_scale;
 
-    ui.set_window_font_scale(1.5);
+    ui.set_window_font_scale(1.5 * settings.text_scale);
     if settings.info_name {
         let mut color = settings.info_name_color.calculate_color(info.health, info.distance, time, 0.0);
         color[3] *= alpha;
         let width = ui.calc_text_size(info.name)[0];
         let pos = [box_center_x - width / 2.0, cursor_y];
         draw_list.add_text(pos, color, info.name);
-        cursor_y += 21.0;
+        cursor_y += line_height;
     }
 
     if settings.info_ammo {
@@ -633,7 +930,7 @@ pub fn draw_player_esp(
         let width = ui.calc_text_size(text)[0];
         let pos = [box_center_x - width / 2.0, cursor_y];
         draw_list.add_text(pos, color, text);
-        cursor_y += 21.0;
+        cursor_y += line_height;
     }
 
     if settings.info_distance {
@@ -643,7 +940,7 @@ pub fn draw_player_esp(
         let width = ui.calc_text_size(&text)[0];
         let pos = [box_center_x - width / 2.0, cursor_y];
         draw_list.add_text(pos, color, text);
-        cursor_y += 21.0;
+        cursor_y += line_height;
     }
 
     if settings.info_weapon {
@@ -652,18 +949,18 @@ pub fn draw_player_esp(
         
         // Try to draw icon if available
         let icon_key = info.weapon_icon_name.unwrap_or(info.weapon_name);
-        if let Some(tex_id) = resources.weapon_icons.get(icon_key) {
+        if let Some(icon) = resources.weapon_icons.get(icon_key) {
              // Standard size roughly 20px height
              let h = 38.25;
-             let w = h * 2.5; // Aspect ratio approx
+             let w = h * icon.aspect_ratio;
              let pos = [box_center_x - w / 2.0, cursor_y];
-             draw_list.add_image(*tex_id, pos, [pos[0] + w, pos[1] + h]).col(color).build();
+             draw_list.add_image(icon.texture_id, pos, [pos[0] + w, pos[1] + h]).uv_min(icon.uv_min).uv_max(icon.uv_max).col(color).build();
              cursor_y += h + 2.0;
         } else {
              let width = ui.calc_text_size(info.weapon_name)[0];
              let pos = [box_center_x - width / 2.0, cursor_y];
              draw_list.add_text(pos, color, info.weapon_name);
-             cursor_y += 21.0;
+             cursor_y += line_height;
         }
     }
     ui.set_window_font_scale(1.0);
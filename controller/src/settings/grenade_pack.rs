@@ -0,0 +1,396 @@
+use std::{
+    fs,
+    io::{
+        BufReader,
+        BufWriter,
+        Write,
+    },
+    path::Path,
+};
+
+use anyhow::Context;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::config::GrenadeSpotInfo;
+
+/// Version of the lineup pack format written by this build.
+/// Packs with a newer version will be rejected.
+pub const GRENADE_PACK_VERSION: u32 = 1;
+
+/// Max distance between two eye positions to consider them the same lineup
+const CONFLICT_POSITION_TOLERANCE: f32 = 1.0;
+
+/// Max difference in degrees between two eye directions to consider them the same lineup
+const CONFLICT_DIRECTION_TOLERANCE: f32 = 0.1;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GrenadePackMetadata {
+    pub name: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub description: String,
+    /// RFC 3339 timestamp of when the pack has been exported
+    #[serde(default)]
+    pub created: String,
+}
+
+/// Shareable collection of grenade lineups for a single map
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrenadePack {
+    pub version: u32,
+    pub map: String,
+    pub metadata: GrenadePackMetadata,
+    pub spots: Vec<GrenadeSpotInfo>,
+}
+
+impl GrenadePack {
+    pub fn new(map: String, metadata: GrenadePackMetadata, spots: Vec<GrenadeSpotInfo>) -> Self {
+        Self {
+            version: GRENADE_PACK_VERSION,
+            map,
+            metadata,
+            spots,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GrenadePackFormat {
+    Json,
+    Yaml,
+}
+
+impl GrenadePackFormat {
+    fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|value| value.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            Some("json") => Ok(Self::Json),
+            Some("yaml" | "yml") => Ok(Self::Yaml),
+            _ => anyhow::bail!("unsupported lineup pack extension (expected json, yaml or yml)"),
+        }
+    }
+}
+
+/// Load a lineup pack. The format is determined by the file extension.
+pub fn load_grenade_pack(path: &Path) -> anyhow::Result<GrenadePack> {
+    let format = GrenadePackFormat::from_path(path)?;
+    let file = fs::File::open(path)
+        .with_context(|| format!("failed to open lineup pack {}", path.display()))?;
+
+    let reader = BufReader::new(file);
+    let pack: GrenadePack = match format {
+        GrenadePackFormat::Json => serde_json::from_reader(reader)?,
+        GrenadePackFormat::Yaml => serde_yaml::from_reader(reader)?,
+    };
+
+    if pack.version > GRENADE_PACK_VERSION {
+        anyhow::bail!(
+            "lineup pack version {} is not supported (max {})",
+            pack.version,
+            GRENADE_PACK_VERSION
+        );
+    }
+
+    if pack.map.is_empty() {
+        anyhow::bail!("lineup pack does not specify a map");
+    }
+
+    log::info!(
+        "Loaded lineup pack {} ({} spots for {}) from {}",
+        pack.metadata.name,
+        pack.spots.len(),
+        pack.map,
+        path.display()
+    );
+    Ok(pack)
+}
+
+fn write_grenade_pack(
+    path: &Path,
+    format: GrenadePackFormat,
+    pack: &GrenadePack,
+) -> anyhow::Result<()> {
+    let file =
+        fs::File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+
+    let mut writer = BufWriter::new(file);
+    match format {
+        GrenadePackFormat::Json => serde_json::to_writer_pretty(&mut writer, pack)?,
+        GrenadePackFormat::Yaml => serde_yaml::to_writer(&mut writer, pack)?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Save a lineup pack. The format is determined by the file extension.
+/// The pack is written into a temporary file first, so an existing pack is not
+/// left broken behind if writing fails.
+pub fn save_grenade_pack(path: &Path, pack: &GrenadePack) -> anyhow::Result<()> {
+    let format = GrenadePackFormat::from_path(path)?;
+
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = Path::new(&temp_path);
+    if let Err(err) = write_grenade_pack(temp_path, format, pack) {
+        let _ = fs::remove_file(temp_path);
+        return Err(err.context(format!("failed to write lineup pack {}", path.display())));
+    }
+    fs::rename(temp_path, path)
+        .with_context(|| format!("failed to replace lineup pack {}", path.display()))?;
+
+    log::info!(
+        "Saved lineup pack {} ({} spots) to {}",
+        pack.metadata.name,
+        pack.spots.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// How to handle imported lineups which already exist
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrenadePackConflict {
+    /// Keep the existing lineup
+    Skip,
+    /// Overwrite the existing lineup with the imported one
+    Replace,
+    /// Add the imported lineup with a suffixed name
+    KeepBoth,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GrenadePackMergeResult {
+    pub added: usize,
+    pub replaced: usize,
+    pub skipped: usize,
+}
+
+fn is_same_lineup(a: &GrenadeSpotInfo, b: &GrenadeSpotInfo) -> bool {
+    if a.name.eq_ignore_ascii_case(&b.name) {
+        return true;
+    }
+
+    let position_delta = a.eye_position().metric_distance(&b.eye_position());
+
+    let direction_delta = a.eye_direction() - b.eye_direction();
+    position_delta <= CONFLICT_POSITION_TOLERANCE
+        && direction_delta.x.abs() <= CONFLICT_DIRECTION_TOLERANCE
+        && direction_delta.y.abs() <= CONFLICT_DIRECTION_TOLERANCE
+}
+
+/// Merge the lineups of a pack into the existing lineups of the same map.
+/// Lineups with the same name or the same position and direction are considered conflicts.
+pub fn merge_grenade_pack(
+    spots: &mut Vec<GrenadeSpotInfo>,
+    imported: Vec<GrenadeSpotInfo>,
+    conflict: GrenadePackConflict,
+) -> GrenadePackMergeResult {
+    let mut result = GrenadePackMergeResult::default();
+    for mut spot in imported {
+        spot.id = GrenadeSpotInfo::new_id();
+
+        let Some(existing) = spots
+            .iter_mut()
+            .find(|existing| is_same_lineup(existing, &spot))
+        else {
+            spots.push(spot);
+            result.added += 1;
+            continue;
+        };
+
+        match conflict {
+            GrenadePackConflict::Skip => result.skipped += 1,
+            GrenadePackConflict::Replace => {
+                spot.id = existing.id;
+                *existing = spot;
+                result.replaced += 1;
+            }
+            GrenadePackConflict::KeepBoth => {
+                let base_name = format!("{} (imported)", spot.name);
+                spot.name = base_name.clone();
+
+                let mut index = 2;
+                while spots.iter().any(|existing| existing.name == spot.name) {
+                    spot.name = format!("{} {}", base_name, index);
+                    index += 1;
+                }
+
+                spots.push(spot);
+                result.added += 1;
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        is_same_lineup,
+        load_grenade_pack,
+        merge_grenade_pack,
+        save_grenade_pack,
+        GrenadePack,
+        GrenadePackConflict,
+        GrenadePackMetadata,
+    };
+    use crate::settings::{
+        GrenadeSpotInfo,
+        GrenadeThrowTechnique,
+        GrenadeType,
+    };
+
+    fn lineup(name: &str, eye_position: [f32; 3], eye_direction: [f32; 2]) -> GrenadeSpotInfo {
+        GrenadeSpotInfo {
+            id: GrenadeSpotInfo::new_id(),
+            grenade_types: vec![GrenadeType::Smoke],
+            name: name.to_string(),
+            description: String::new(),
+            tags: Vec::new(),
+            throw_technique: GrenadeThrowTechnique::Standing,
+            eye_position,
+            eye_direction,
+        }
+    }
+
+    #[test]
+    fn same_lineup() {
+        let spot = lineup("Xbox smoke", [100.0, 200.0, 64.0], [-10.0, 45.0]);
+
+        /* names are compared case insensitive regardless of the position */
+        assert!(is_same_lineup(
+            &spot,
+            &lineup("XBOX Smoke", [0.0, 0.0, 0.0], [0.0, 0.0])
+        ));
+        assert!(is_same_lineup(
+            &spot,
+            &lineup("Other", [100.5, 200.0, 64.0], [-10.05, 45.05])
+        ));
+
+        assert!(!is_same_lineup(
+            &spot,
+            &lineup("Other", [102.0, 200.0, 64.0], [-10.0, 45.0])
+        ));
+        assert!(!is_same_lineup(
+            &spot,
+            &lineup("Other", [100.0, 200.0, 64.0], [-10.0, 46.0])
+        ));
+    }
+
+    fn merge(conflict: GrenadePackConflict) -> (Vec<GrenadeSpotInfo>, usize, [usize; 3]) {
+        let mut spots = vec![
+            lineup("Xbox smoke", [100.0, 200.0, 64.0], [-10.0, 45.0]),
+            lineup("Window smoke", [300.0, 200.0, 64.0], [-5.0, 90.0]),
+        ];
+        let existing_id = spots[0].id;
+
+        let mut imported = lineup("xbox smoke", [0.0, 0.0, 0.0], [0.0, 0.0]);
+        imported.description = "imported".to_string();
+        let result = merge_grenade_pack(
+            &mut spots,
+            vec![
+                imported,
+                lineup("Connector molly", [500.0, 200.0, 64.0], [10.0, 0.0]),
+            ],
+            conflict,
+        );
+
+        (
+            spots,
+            existing_id,
+            [result.added, result.replaced, result.skipped],
+        )
+    }
+
+    #[test]
+    fn merge_skip() {
+        let (spots, existing_id, result) = merge(GrenadePackConflict::Skip);
+        assert_eq!(result, [1, 0, 1]);
+        assert_eq!(spots.len(), 3);
+        assert_eq!(spots[0].id, existing_id);
+        assert_eq!(spots[0].description, "");
+        assert_eq!(spots[2].name, "Connector molly");
+    }
+
+    #[test]
+    fn merge_replace() {
+        let (spots, existing_id, result) = merge(GrenadePackConflict::Replace);
+        assert_eq!(result, [1, 1, 0]);
+        assert_eq!(spots.len(), 3);
+
+        /* the replaced lineup keeps its id */
+        assert_eq!(spots[0].id, existing_id);
+        assert_eq!(spots[0].name, "xbox smoke");
+        assert_eq!(spots[0].description, "imported");
+    }
+
+    #[test]
+    fn merge_keep_both() {
+        let (spots, _, result) = merge(GrenadePackConflict::KeepBoth);
+        assert_eq!(result, [2, 0, 0]);
+        assert_eq!(spots.len(), 4);
+        assert_eq!(spots[2].name, "xbox smoke (imported)");
+
+        /* importing the same lineup again gets an index suffix */
+        let mut spots = spots;
+        merge_grenade_pack(
+            &mut spots,
+            vec![lineup("Xbox smoke", [0.0, 0.0, 0.0], [0.0, 0.0])],
+            GrenadePackConflict::KeepBoth,
+        );
+        assert_eq!(spots[4].name, "Xbox smoke (imported)");
+
+        merge_grenade_pack(
+            &mut spots,
+            vec![lineup("Xbox smoke", [0.0, 0.0, 0.0], [0.0, 0.0])],
+            GrenadePackConflict::KeepBoth,
+        );
+        assert_eq!(spots[5].name, "Xbox smoke (imported) 2");
+    }
+
+    #[test]
+    fn save_and_load() {
+        let pack = GrenadePack::new(
+            "de_mirage".to_string(),
+            GrenadePackMetadata {
+                name: "Mirage basics".to_string(),
+                ..Default::default()
+            },
+            vec![lineup("Xbox smoke", [100.0, 200.0, 64.0], [-10.0, 45.0])],
+        );
+
+        for extension in ["json", "yaml"] {
+            let path = std::env::temp_dir().join(format!(
+                "labh_grenade_pack_test_{}.{}",
+                std::process::id(),
+                extension
+            ));
+
+            save_grenade_pack(&path, &pack).unwrap();
+            /* overwriting an existing pack */
+            save_grenade_pack(&path, &pack).unwrap();
+
+            let mut temp_path = path.as_os_str().to_owned();
+            temp_path.push(".tmp");
+            assert!(!std::path::Path::new(&temp_path).exists());
+
+            let loaded = load_grenade_pack(&path).unwrap();
+            let _ = std::fs::remove_file(&path);
+
+            assert_eq!(loaded.map, "de_mirage");
+            assert_eq!(loaded.metadata.name, "Mirage basics");
+            assert_eq!(loaded.spots.len(), 1);
+            assert_eq!(loaded.spots[0].name, "Xbox smoke");
+            assert_eq!(loaded.spots[0].eye_position, [100.0, 200.0, 64.0]);
+        }
+    }
+}
@@ -4,6 +4,12 @@ use serde::{
     Serialize,
 };
 
+use super::{
+    AppSettings,
+    FeatureToggle,
+    KeyToggleMode,
+};
+
 #[derive(Clone, Copy, Debug, PartialEq)] // Removed PartialOrd
 pub struct HotKey(pub imgui::Key);
 
@@ -54,4 +60,124 @@ impl<'de> Deserialize<'de> for HotKey {
     {
         deserializer.deserialize_str(HotKeyVisitor)
     }
-}
\ No newline at end of file
+}
+
+/// All hotkey settings, used to detect keys which are bound more than once
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HotkeyBinding {
+    Menu,
+    Panic,
+    Esp,
+    TriggerBot,
+    LegitAim,
+    MeasurementTool,
+    GrenadeCapture,
+    Feature(FeatureToggle),
+}
+
+impl HotkeyBinding {
+    pub fn all() -> Vec<HotkeyBinding> {
+        let mut bindings = vec![
+            HotkeyBinding::Menu,
+            HotkeyBinding::Panic,
+            HotkeyBinding::Esp,
+            HotkeyBinding::TriggerBot,
+            HotkeyBinding::LegitAim,
+            HotkeyBinding::MeasurementTool,
+            HotkeyBinding::GrenadeCapture,
+        ];
+        bindings.extend(FeatureToggle::ALL.into_iter().map(HotkeyBinding::Feature));
+        bindings
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            HotkeyBinding::Menu => "Toggle Settings",
+            HotkeyBinding::Panic => "Panic Key",
+            HotkeyBinding::Esp => "ESP Toggle/Hold",
+            HotkeyBinding::TriggerBot => "Trigger Bot",
+            HotkeyBinding::LegitAim => "Legit Aim",
+            HotkeyBinding::MeasurementTool => "Measurement Tool",
+            HotkeyBinding::GrenadeCapture => "Capture Lineup",
+            HotkeyBinding::Feature(feature) => feature.display_name(),
+        }
+    }
+
+    /// Tooltip of the key button in the settings
+    pub fn description(&self) -> Option<&'static str> {
+        match self {
+            HotkeyBinding::GrenadeCapture => Some("Captures your current position and opens the lineup editor"),
+            _ => None,
+        }
+    }
+
+    /// The menu key must always be bound
+    pub fn is_optional(&self) -> bool {
+        *self != HotkeyBinding::Menu
+    }
+
+    pub fn key(&self, settings: &AppSettings) -> Option<HotKey> {
+        match self {
+            HotkeyBinding::Menu => Some(settings.key_settings),
+            HotkeyBinding::Panic => settings.key_panic,
+            HotkeyBinding::Esp => settings.esp_toggle,
+            HotkeyBinding::TriggerBot => settings.key_trigger_bot,
+            HotkeyBinding::LegitAim => settings.legit_aim_key,
+            HotkeyBinding::MeasurementTool => settings.measurement_tool_key,
+            HotkeyBinding::GrenadeCapture => settings.grenade_helper.capture_key,
+            HotkeyBinding::Feature(feature) => settings.feature_hotkeys.get(feature).and_then(|hotkey| hotkey.key),
+        }
+    }
+
+    /// The feature of the binding is enabled and listens to its key
+    pub fn is_in_use(&self, settings: &AppSettings) -> bool {
+        let uses_key = |mode: KeyToggleMode| !matches!(mode, KeyToggleMode::AlwaysOn | KeyToggleMode::Off);
+        match self {
+            HotkeyBinding::Menu | HotkeyBinding::Panic => true,
+            HotkeyBinding::Esp => uses_key(settings.esp_mode),
+            HotkeyBinding::TriggerBot => uses_key(settings.trigger_bot_mode),
+            HotkeyBinding::LegitAim => settings.legit_aim_enabled,
+            HotkeyBinding::MeasurementTool => settings.measurement_tool,
+            HotkeyBinding::GrenadeCapture => settings.grenade_helper.active,
+            HotkeyBinding::Feature(feature) => settings
+                .feature_hotkeys
+                .get(feature)
+                .map_or(false, |hotkey| uses_key(hotkey.mode)),
+        }
+    }
+
+    /// Key of the binding or None if the key is unbound or the feature does not use it
+    pub fn active_key(&self, settings: &AppSettings) -> Option<HotKey> {
+        self.key(settings).filter(|_| self.is_in_use(settings))
+    }
+
+    /// Update the key of the binding. Clearing the menu key is ignored.
+    pub fn set_key(&self, settings: &mut AppSettings, key: Option<HotKey>) {
+        match self {
+            HotkeyBinding::Menu => {
+                if let Some(key) = key {
+                    settings.key_settings = key;
+                }
+            }
+            HotkeyBinding::Panic => settings.key_panic = key,
+            HotkeyBinding::Esp => settings.esp_toggle = key,
+            HotkeyBinding::TriggerBot => settings.key_trigger_bot = key,
+            HotkeyBinding::LegitAim => settings.legit_aim_key = key,
+            HotkeyBinding::MeasurementTool => settings.measurement_tool_key = key,
+            HotkeyBinding::GrenadeCapture => settings.grenade_helper.capture_key = key,
+            HotkeyBinding::Feature(feature) => settings.feature_hotkeys.entry(*feature).or_default().key = key,
+        }
+    }
+
+    /// Other bindings in use with the same key as this binding
+    pub fn conflicts(&self, settings: &AppSettings) -> Vec<HotkeyBinding> {
+        let Some(key) = self.active_key(settings) else {
+            return Vec::new();
+        };
+
+        Self::all()
+            .into_iter()
+            .filter(|binding| binding != self && binding.active_key(settings) == Some(key))
+            .collect()
+    }
+}
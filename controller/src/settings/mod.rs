@@ -1,12 +1,22 @@
 
+pub mod autosave;
 pub mod config;
 pub mod esp;
+pub mod grenade_pack;
 pub mod hotkey;
+pub mod model_preview;
+pub mod theme;
 pub mod ui;
+pub mod validate;
 pub mod config_manager;
 
+pub use autosave::*;
 pub use config::*;
 pub use esp::*;
+pub use grenade_pack::*;
 pub use hotkey::*;
+pub use model_preview::*;
+pub use theme::*;
 pub use ui::*;
+pub use validate::*;
 pub use config_manager::*;
\ No newline at end of file
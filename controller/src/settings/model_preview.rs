@@ -0,0 +1,177 @@
+use imgui::{
+    DrawListMut,
+    TextureId,
+};
+use nalgebra::{
+    Matrix4,
+    Point3,
+    Vector3,
+};
+use overlay::{
+    MeshRenderRequest,
+    MeshVertex,
+    SystemRuntimeController,
+};
+
+use crate::enhancements::model_renderer::CharacterModel;
+
+/// Mesh tag of the character model shown in the ESP preview
+const PREVIEW_MESH_TAG: u64 = 1;
+
+/// Light direction matching the software renderer of the character model
+const PREVIEW_LIGHT_DIRECTION: [f32; 3] = [0.408, 0.816, 0.408];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PreviewMeshState {
+    Pending,
+    Uploading,
+    Ready,
+    Unsupported,
+}
+
+/// Renders the character model of the ESP preview with the render backend.
+/// The mesh is uploaded once in bind pose. Each frame queues a render of the rotated
+/// model which is drawn as a texture in the next frame.
+pub struct GpuModelPreview {
+    state: PreviewMeshState,
+    /// Bounds of the bind pose mesh
+    bounds: (Vector3<f32>, Vector3<f32>),
+    pending_request: Option<MeshRenderRequest>,
+    texture: Option<TextureId>,
+}
+
+impl GpuModelPreview {
+    pub fn new() -> Self {
+        Self {
+            state: PreviewMeshState::Pending,
+            bounds: (Vector3::zeros(), Vector3::zeros()),
+            pending_request: None,
+            texture: None,
+        }
+    }
+
+    /// Upload the mesh and forward the render request of the last frame to the render backend.
+    pub fn update(
+        &mut self,
+        controller: &mut SystemRuntimeController,
+        model: Option<&CharacterModel>,
+    ) {
+        if !controller.mesh_rendering_supported() {
+            self.state = PreviewMeshState::Unsupported;
+            return;
+        }
+
+        if self.state == PreviewMeshState::Pending {
+            if let Some(model) = model {
+                let vertices = model
+                    .mesh
+                    .vertices
+                    .iter()
+                    .map(|vertex| MeshVertex {
+                        position: vertex.position.into(),
+                        normal: vertex.normal.into(),
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut min = Vector3::repeat(f32::MAX);
+                let mut max = Vector3::repeat(f32::MIN);
+                for vertex in model.mesh.vertices.iter() {
+                    min = min.inf(&vertex.position);
+                    max = max.sup(&vertex.position);
+                }
+                self.bounds = (min, max);
+
+                controller.queue_mesh_upload(
+                    PREVIEW_MESH_TAG,
+                    vertices,
+                    model.mesh.indices.clone(),
+                );
+                self.state = PreviewMeshState::Uploading;
+            }
+        }
+
+        if controller
+            .take_uploaded_meshes()
+            .contains(&PREVIEW_MESH_TAG)
+        {
+            self.state = PreviewMeshState::Ready;
+        }
+
+        if let Some(request) = self.pending_request.take() {
+            controller.queue_mesh_render(request);
+        }
+
+        for (tag, texture_id) in controller.take_rendered_meshes() {
+            if tag == PREVIEW_MESH_TAG {
+                self.texture = Some(texture_id);
+            }
+        }
+    }
+
+    /// Draw the model rendered in the last frame and queue the next render.
+    /// Returns the screen bounds of the model or None if the model must be rendered in software.
+    pub fn draw(
+        &mut self,
+        draw_list: &DrawListMut,
+        position: [f32; 2],
+        size: [f32; 2],
+        model_transform: Matrix4<f32>,
+        view_projection: Matrix4<f32>,
+        color: [f32; 4],
+    ) -> Option<([f32; 2], [f32; 2])> {
+        if self.state != PreviewMeshState::Ready {
+            return None;
+        }
+
+        self.pending_request = Some(MeshRenderRequest {
+            mesh: PREVIEW_MESH_TAG,
+            width: size[0].max(1.0) as u32,
+            height: size[1].max(1.0) as u32,
+            model: model_transform,
+            view_projection,
+            color,
+            light_direction: PREVIEW_LIGHT_DIRECTION,
+        });
+
+        let texture_id = self.texture?;
+        draw_list
+            .add_image(
+                texture_id,
+                position,
+                [position[0] + size[0], position[1] + size[1]],
+            )
+            .build();
+
+        Some(self.screen_bounds(position, size, &(view_projection * model_transform)))
+    }
+
+    /// Project the corners of the mesh bounds onto the screen
+    fn screen_bounds(
+        &self,
+        position: [f32; 2],
+        size: [f32; 2],
+        model_view_projection: &Matrix4<f32>,
+    ) -> ([f32; 2], [f32; 2]) {
+        let (min, max) = self.bounds;
+
+        let mut screen_min = [f32::MAX, f32::MAX];
+        let mut screen_max = [f32::MIN, f32::MIN];
+        for corner in 0..8 {
+            let point = Point3::new(
+                if corner & 1 == 0 { min.x } else { max.x },
+                if corner & 2 == 0 { min.y } else { max.y },
+                if corner & 4 == 0 { min.z } else { max.z },
+            );
+            let projected = model_view_projection.transform_point(&point);
+            let screen = [
+                position[0] + (projected.x + 1.0) * size[0] / 2.0,
+                position[1] + (-projected.y + 1.0) * size[1] / 2.0,
+            ];
+
+            screen_min = [screen_min[0].min(screen[0]), screen_min[1].min(screen[1])];
+            screen_max = [screen_max[0].max(screen[0]), screen_max[1].max(screen[1])];
+        }
+
+        (screen_min, screen_max)
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{
+        BufReader,
+        BufWriter,
+    },
+    path::Path,
+};
+
+use anyhow::Context;
+use imgui::StyleColor;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// Name of the built-in theme used by default
+pub const DEFAULT_THEME_NAME: &str = "LabHub";
+
+/// Colors of the settings menu and all other imgui windows
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub name: String,
+    /// Colors keyed by the name of their imgui style color.
+    /// Missing colors use the imgui dark style.
+    pub colors: BTreeMap<String, [f32; 4]>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::from_colors(
+            DEFAULT_THEME_NAME,
+            &[
+                (StyleColor::Text, [0.80, 0.80, 0.83, 1.00]),
+                (StyleColor::TextDisabled, [0.45, 0.45, 0.48, 1.00]),
+                (StyleColor::WindowBg, [0.06, 0.05, 0.07, 1.00]),
+                (StyleColor::ChildBg, [0.07, 0.07, 0.09, 1.00]),
+                (StyleColor::PopupBg, [0.07, 0.07, 0.09, 1.00]),
+                (StyleColor::Border, [0.80, 0.80, 0.83, 0.88]),
+                (StyleColor::BorderShadow, [0.92, 0.91, 0.88, 0.00]),
+                (StyleColor::FrameBg, [0.10, 0.09, 0.12, 1.00]),
+                (StyleColor::FrameBgHovered, [0.24, 0.23, 0.29, 1.00]),
+                (StyleColor::FrameBgActive, [0.56, 0.56, 0.58, 1.00]),
+                (StyleColor::TitleBg, [0.10, 0.09, 0.12, 1.00]),
+                (StyleColor::TitleBgActive, [0.07, 0.07, 0.09, 1.00]),
+                (StyleColor::TitleBgCollapsed, [1.00, 0.98, 0.95, 0.75]),
+                (StyleColor::MenuBarBg, [0.10, 0.09, 0.12, 1.00]),
+                (StyleColor::ScrollbarBg, [0.10, 0.09, 0.12, 1.00]),
+                (StyleColor::ScrollbarGrab, [0.80, 0.80, 0.83, 0.31]),
+                (StyleColor::ScrollbarGrabHovered, [0.56, 0.56, 0.58, 1.00]),
+                (StyleColor::ScrollbarGrabActive, [0.06, 0.05, 0.07, 1.00]),
+                (StyleColor::CheckMark, [0.80, 0.80, 0.83, 0.31]),
+                (StyleColor::SliderGrab, [0.80, 0.80, 0.83, 0.31]),
+                (StyleColor::SliderGrabActive, [0.06, 0.05, 0.07, 1.00]),
+                (StyleColor::Button, [0.10, 0.09, 0.12, 1.00]),
+                (StyleColor::ButtonHovered, [0.24, 0.23, 0.29, 1.00]),
+                (StyleColor::ButtonActive, [0.56, 0.56, 0.58, 1.00]),
+                (StyleColor::Header, [0.10, 0.09, 0.12, 1.00]),
+                (StyleColor::HeaderHovered, [0.56, 0.56, 0.58, 1.00]),
+                (StyleColor::HeaderActive, [0.06, 0.05, 0.07, 1.00]),
+                (StyleColor::Separator, [0.43, 0.43, 0.50, 0.50]),
+                (StyleColor::SeparatorHovered, [0.10, 0.40, 0.75, 0.78]),
+                (StyleColor::SeparatorActive, [0.10, 0.40, 0.75, 1.00]),
+                (StyleColor::ResizeGrip, [0.00, 0.00, 0.00, 0.00]),
+                (StyleColor::ResizeGripHovered, [0.56, 0.56, 0.58, 1.00]),
+                (StyleColor::ResizeGripActive, [0.06, 0.05, 0.07, 1.00]),
+                (StyleColor::Tab, [0.10, 0.09, 0.12, 1.00]),
+                (StyleColor::TabHovered, [0.24, 0.23, 0.29, 1.00]),
+                (StyleColor::TabActive, [0.14, 0.13, 0.17, 1.00]),
+                (StyleColor::TabUnfocused, [0.10, 0.09, 0.12, 1.00]),
+                (StyleColor::TabUnfocusedActive, [0.20, 0.25, 0.29, 1.00]),
+                (StyleColor::TextSelectedBg, [0.25, 1.00, 0.00, 0.43]),
+                (StyleColor::NavHighlight, [0.26, 0.59, 0.98, 1.00]),
+            ],
+        )
+    }
+}
+
+/// Base colors a theme preset is derived from
+struct ThemePalette {
+    text: [f32; 4],
+    text_disabled: [f32; 4],
+    background: [f32; 4],
+    panel: [f32; 4],
+    frame: [f32; 4],
+    hovered: [f32; 4],
+    active: [f32; 4],
+    accent: [f32; 4],
+}
+
+impl ThemePalette {
+    fn into_theme(self, name: &str) -> Theme {
+        let accent_faded = [self.accent[0], self.accent[1], self.accent[2], 0.45];
+        Theme::from_colors(
+            name,
+            &[
+                (StyleColor::Text, self.text),
+                (StyleColor::TextDisabled, self.text_disabled),
+                (StyleColor::WindowBg, self.background),
+                (StyleColor::ChildBg, self.panel),
+                (StyleColor::PopupBg, self.panel),
+                (StyleColor::Border, self.hovered),
+                (StyleColor::BorderShadow, [0.0, 0.0, 0.0, 0.0]),
+                (StyleColor::FrameBg, self.frame),
+                (StyleColor::FrameBgHovered, self.hovered),
+                (StyleColor::FrameBgActive, self.active),
+                (StyleColor::TitleBg, self.frame),
+                (StyleColor::TitleBgActive, self.panel),
+                (StyleColor::TitleBgCollapsed, self.frame),
+                (StyleColor::MenuBarBg, self.frame),
+                (StyleColor::ScrollbarBg, self.frame),
+                (StyleColor::ScrollbarGrab, self.hovered),
+                (StyleColor::ScrollbarGrabHovered, self.active),
+                (StyleColor::ScrollbarGrabActive, self.accent),
+                (StyleColor::CheckMark, self.accent),
+                (StyleColor::SliderGrab, self.active),
+                (StyleColor::SliderGrabActive, self.accent),
+                (StyleColor::Button, self.frame),
+                (StyleColor::ButtonHovered, self.hovered),
+                (StyleColor::ButtonActive, self.active),
+                (StyleColor::Header, self.frame),
+                (StyleColor::HeaderHovered, self.hovered),
+                (StyleColor::HeaderActive, self.active),
+                (StyleColor::Separator, self.hovered),
+                (StyleColor::SeparatorHovered, self.active),
+                (StyleColor::SeparatorActive, self.accent),
+                (StyleColor::ResizeGrip, [0.0, 0.0, 0.0, 0.0]),
+                (StyleColor::ResizeGripHovered, self.active),
+                (StyleColor::ResizeGripActive, self.accent),
+                (StyleColor::Tab, self.frame),
+                (StyleColor::TabHovered, self.hovered),
+                (StyleColor::TabActive, self.active),
+                (StyleColor::TabUnfocused, self.frame),
+                (StyleColor::TabUnfocusedActive, self.hovered),
+                (StyleColor::TextSelectedBg, accent_faded),
+                (StyleColor::NavHighlight, self.accent),
+            ],
+        )
+    }
+}
+
+impl Theme {
+    fn from_colors(name: &str, colors: &[(StyleColor, [f32; 4])]) -> Self {
+        Self {
+            name: name.to_string(),
+            colors: colors
+                .iter()
+                .map(|(style_color, color)| (style_color_name(*style_color), *color))
+                .collect(),
+        }
+    }
+
+    /// Color of a style color or None if the theme does not override it
+    pub fn color(&self, style_color: StyleColor) -> Option<[f32; 4]> {
+        self.colors.get(&style_color_name(style_color)).cloned()
+    }
+
+    pub fn set_color(&mut self, style_color: StyleColor, color: [f32; 4]) {
+        self.colors.insert(style_color_name(style_color), color);
+    }
+
+    /// Apply the theme colors to an imgui style
+    pub fn apply(&self, style: &mut imgui::Style) {
+        style.use_dark_colors();
+        for style_color in StyleColor::VARIANTS {
+            if let Some(color) = self.colors.get(&style_color_name(style_color)) {
+                style.colors[style_color as usize] = *color;
+            }
+        }
+    }
+}
+
+/// Name of a style color as used by the theme files
+pub fn style_color_name(style_color: StyleColor) -> String {
+    format!("{:?}", style_color)
+}
+
+/// Themes shipped with the controller
+pub fn builtin_themes() -> Vec<Theme> {
+    vec![
+        Theme::default(),
+        ThemePalette {
+            text: [0.86, 0.89, 0.94, 1.00],
+            text_disabled: [0.45, 0.50, 0.58, 1.00],
+            background: [0.04, 0.06, 0.10, 1.00],
+            panel: [0.06, 0.08, 0.13, 1.00],
+            frame: [0.09, 0.12, 0.19, 1.00],
+            hovered: [0.16, 0.22, 0.34, 1.00],
+            active: [0.24, 0.34, 0.52, 1.00],
+            accent: [0.30, 0.60, 1.00, 1.00],
+        }
+        .into_theme("Midnight"),
+        ThemePalette {
+            text: [0.92, 0.86, 0.86, 1.00],
+            text_disabled: [0.55, 0.45, 0.45, 1.00],
+            background: [0.07, 0.04, 0.04, 1.00],
+            panel: [0.09, 0.05, 0.05, 1.00],
+            frame: [0.14, 0.07, 0.07, 1.00],
+            hovered: [0.30, 0.11, 0.11, 1.00],
+            active: [0.48, 0.14, 0.14, 1.00],
+            accent: [0.90, 0.22, 0.22, 1.00],
+        }
+        .into_theme("Crimson"),
+        ThemePalette {
+            text: [0.10, 0.10, 0.12, 1.00],
+            text_disabled: [0.50, 0.50, 0.54, 1.00],
+            background: [0.94, 0.94, 0.95, 1.00],
+            panel: [0.90, 0.90, 0.92, 1.00],
+            frame: [0.84, 0.84, 0.87, 1.00],
+            hovered: [0.74, 0.76, 0.82, 1.00],
+            active: [0.62, 0.66, 0.76, 1.00],
+            accent: [0.16, 0.42, 0.86, 1.00],
+        }
+        .into_theme("Light"),
+    ]
+}
+
+/// Load a theme file exported with [save_theme]
+pub fn load_theme(path: &Path) -> anyhow::Result<Theme> {
+    let file =
+        fs::File::open(path).with_context(|| format!("failed to open theme {}", path.display()))?;
+
+    let theme: Theme = serde_yaml::from_reader(BufReader::new(file))?;
+    log::info!("Loaded theme {} from {}", theme.name, path.display());
+    Ok(theme)
+}
+
+/// Save a theme as a standalone YAML file
+pub fn save_theme(path: &Path, theme: &Theme) -> anyhow::Result<()> {
+    let file = fs::File::create(path)
+        .with_context(|| format!("failed to create theme {}", path.display()))?;
+
+    serde_yaml::to_writer(BufWriter::new(file), theme)?;
+    log::info!("Saved theme {} to {}", theme.name, path.display());
+    Ok(())
+}
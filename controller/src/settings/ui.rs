@@ -11,21 +11,32 @@ use std::{
     time::{Duration, Instant},
 };
 
-use cs2::StateBuildInfo;
+use cs2::{
+    StateBuildInfo,
+    StateCS2Handle,
+    StateCurrentMap,
+};
 use font_awesome;
 use nalgebra::{Matrix4, Vector3, Point3};
 use crate::view::ViewController;
+use crate::enhancements::model_renderer::CharacterModel;
 use crate::settings::esp::{draw_player_esp, EspRenderInfo};
 use imgui::{
     Condition,
     Image,
     StyleColor,
     StyleVar,
+    TableFlags,
+    TreeNodeFlags,
     WindowFlags,
     TextureId,
 };
 
-use overlay::UnicodeTextRenderer;
+use overlay::{
+    list_monitors,
+    SystemRuntimeController,
+    UnicodeTextRenderer,
+};
 use rfd::FileDialog;
 
 use raw_window_handle::{
@@ -38,29 +49,93 @@ use windows::{
 };
 
 use super::{
-    config::AppSettings,
+    config::{
+        AppSettings,
+        AudioCueSettings,
+    },
     config_manager,
+    grenade_pack::{
+        load_grenade_pack,
+        merge_grenade_pack,
+        save_grenade_pack,
+        GrenadePack,
+        GrenadePackConflict,
+        GrenadePackMetadata,
+    },
+    hotkey::{
+        HotKey,
+        HotkeyBinding,
+    },
+    model_preview::GpuModelPreview,
+    theme::{
+        builtin_themes,
+        load_theme,
+        save_theme,
+        style_color_name,
+        Theme,
+    },
     esp::{
         Color,
         EspColor,
         EspColorType,
+        EspChickenSettings,
         EspConfig,
         EspSelector,
         EspBoxType,
         EspHeadDot,
         EspHealthBar,
+        EspInfoStyle,
+        EspOffscreenArrowMode,
         EspPlayerSettings,
         EspTracePosition,
+        EspWeaponSettings,
+        MapPalette,
+    },
+    config::{
+        CrosshairSettings,
+        FeatureHotkey,
+        FeatureToggle,
+        get_sessions_dir,
+        GrenadeSpotInfo,
+        GrenadeThrowTechnique,
+        GrenadeType,
+        KeyToggleMode,
+        MetricsCategory,
+        MetricsDestination,
+        OverlayAnchor,
+        SpectatorsListSettings,
+        SpeedUnit,
+        UI_SCALE_MAX,
+        UI_SCALE_MIN,
     },
-    config::KeyToggleMode,
 };
 use crate::{
+    enhancements::{
+        decode_crosshair_share_code,
+        draw_crosshair,
+        export_session_csv,
+        AudioEvent,
+        StateAudioEvents,
+        map_loader::MapMesh,
+        StateGrenadeTrajectoryMesh,
+        StateMatchStatistics,
+        StateGrenadeHelperPlayerLocation,
+    },
+    metrics::METRICS_RECORD_TYPES,
+    notifications::NotificationLevel,
+    updater::UpdateStatus,
     utils::{
+        current_log_file,
+        display_player_name_with_mode,
         imgui::ImguiUiEx,
+        open_url,
+        recent_log_entries,
         ImGuiKey,
         ImguiComboEnum,
+        StateSteamProfiles,
     },
     Application,
+    IDLE_FPS_LIMIT,
 };
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -71,17 +146,15 @@ enum ActiveTab {
     Crosshair,
     World,
     Overlay,
+    Stats,
+    Logs,
     Hotkeys,
     Config,
+    Theme,
+    Privacy,
     Info,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum PlayerTargetMode {
-    Friendly,
-    Enemy,
-}
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum FlagType {
     Kit,
@@ -107,6 +180,13 @@ impl HasDisplayHandle for WindowHandle {
     }
 }
 
+/// File dialog attached to the overlay window, so it opens on top of the overlay
+fn overlay_file_dialog() -> FileDialog {
+    let hwnd = unsafe { FindWindowA(None, s!("CS2 Overlay")) };
+    let dialog = FileDialog::new();
+    if hwnd.0 != 0 { dialog.set_parent(&WindowHandle(hwnd)) } else { dialog }
+}
+
 #[derive(Clone, Copy)]
 struct WidgetAnimationState {
     progress: f32,
@@ -165,12 +245,34 @@ impl Default for PreviewLayoutConfig {
     }
 }
 
+/// Simulated state of the player shown in the ESP preview
+struct PreviewPlayerState {
+    health: f32,
+    distance: f32,
+    is_scoped: bool,
+    is_flashed: bool,
+}
+
+impl PreviewPlayerState {
+    /// Cycle through the player states so every ESP element can be seen in action
+    fn at(time: f32) -> Self {
+        Self {
+            /* lose health over 8 seconds, then respawn */
+            health: 1.0 - (time % 8.0) / 8.0 * 0.9,
+            /* walk between 5m and 40m */
+            distance: 22.5 - (time * std::f32::consts::TAU / 10.0).cos() * 17.5,
+            is_scoped: time % 6.0 >= 3.0,
+            is_flashed: time % 7.0 < 1.0,
+        }
+    }
+}
+
 pub struct SettingsUI {
     discord_link_copied: Option<Instant>,
     active_tab: ActiveTab,
     tab_offsets: BTreeMap<ActiveTab, f32>,
     content_y_offset: f32,
-    esp_player_target_mode: PlayerTargetMode,
+    esp_selected_target: EspSelector,
     config_list: Vec<String>,
     selected_config_index: Option<usize>,
     new_config_name: String,
@@ -187,14 +289,77 @@ pub struct SettingsUI {
 
     active_flag_setting: Option<FlagType>,
     ui_alpha: f32,
+    /// Move the keyboard focus to the first widget of the active tab
+    focus_content: bool,
+    /// Scale of the menu in the current frame
+    ui_scale: f32,
+    /// Scale in percent while the slider is dragged, applied on release
+    ui_scale_edit: Option<i32>,
+    /// Steam Web API key while it is edited, applied once editing finished
+    steam_api_key_edit: Option<String>,
     is_first_render: bool,
     start_time: Instant,
     preview_layout: PreviewLayoutConfig,
     preview_rotation: f32,
+    model_preview: GpuModelPreview,
+
+    grenade_helper_map: Option<String>,
+    grenade_helper_selected_spot: Option<usize>,
+    grenade_helper_new_tag: String,
+    grenade_pack_author: String,
+    grenade_pack_conflict: GrenadePackConflict,
+    crosshair_share_code: String,
+
+    driver_self_test: Option<Result<Duration, String>>,
+
+    log_level_filter: log::Level,
+    log_auto_scroll: bool,
+
+    /// Preview of the metrics records, shown before enabling the metrics
+    metrics_preview_open: bool,
+
+    /// The user clicked install and has to confirm replacing the executable
+    update_install_confirm: bool,
+
+    /// Key of a binding before it has been changed, used to swap conflicting bindings
+    hotkey_previous_keys: BTreeMap<HotkeyBinding, Option<HotKey>>,
 }
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Size of the settings window at a UI scale of 100%
+const WINDOW_SIZE: [f32; 2] = [1024.0, 768.0];
+
+const OVERLAY_ANCHOR_OPTIONS: &[(OverlayAnchor, &str)] = &[
+    (OverlayAnchor::TopLeft, "Top Left"),
+    (OverlayAnchor::TopCenter, "Top Center"),
+    (OverlayAnchor::TopRight, "Top Right"),
+    (OverlayAnchor::CenterLeft, "Center Left"),
+    (OverlayAnchor::CenterRight, "Center Right"),
+    (OverlayAnchor::BottomLeft, "Bottom Left"),
+    (OverlayAnchor::BottomRight, "Bottom Right"),
+];
+
+const LOG_LEVEL_OPTIONS: &[(log::Level, &str)] = &[
+    (log::Level::Error, "Error"),
+    (log::Level::Warn, "Warning"),
+    (log::Level::Info, "Info"),
+    (log::Level::Debug, "Debug"),
+    (log::Level::Trace, "Trace"),
+];
+
+const METRICS_DESTINATION_OPTIONS: &[(MetricsDestination, &str)] = &[
+    (MetricsDestination::Disabled, "Disabled"),
+    (MetricsDestination::LocalFile, "Local file"),
+    (MetricsDestination::Remote, "Remote"),
+];
+
+const SPEED_UNIT_OPTIONS: &[(SpeedUnit, &str)] = &[
+    (SpeedUnit::UnitsPerSecond, "Units/s"),
+    (SpeedUnit::KilometersPerHour, "km/h"),
+    (SpeedUnit::MetersPerSecond, "m/s"),
+];
+
 impl SettingsUI {
     pub fn new() -> Self {
         Self {
@@ -202,7 +367,7 @@ impl SettingsUI {
             active_tab: ActiveTab::Visuals,
             tab_offsets: BTreeMap::new(),
             content_y_offset: 0.0,
-            esp_player_target_mode: PlayerTargetMode::Enemy,
+            esp_selected_target: EspSelector::PlayerTeam { enemy: true },
             config_list: Vec::new(),
             selected_config_index: None,
             new_config_name: String::with_capacity(32),
@@ -214,13 +379,50 @@ impl SettingsUI {
             open_dropdowns: Vec::new(),
             active_flag_setting: None,
             ui_alpha: 0.0,
+            focus_content: false,
+            ui_scale: 1.0,
+            ui_scale_edit: None,
+            steam_api_key_edit: None,
             is_first_render: true,
             start_time: Instant::now(),
             preview_layout: PreviewLayoutConfig::default(),
             preview_rotation: 0.0,
+            model_preview: GpuModelPreview::new(),
+            grenade_helper_map: None,
+            grenade_helper_selected_spot: None,
+            grenade_helper_new_tag: String::new(),
+            grenade_pack_author: String::new(),
+            grenade_pack_conflict: GrenadePackConflict::Skip,
+            crosshair_share_code: String::new(),
+
+            driver_self_test: None,
+
+            log_level_filter: log::Level::Info,
+            log_auto_scroll: true,
+
+            metrics_preview_open: false,
+
+            update_install_confirm: false,
+
+            hotkey_previous_keys: BTreeMap::new(),
         }
     }
 
+    /// Skip the intro animation and start with the menu faded out
+    pub fn skip_intro(&mut self) {
+        self.is_first_render = false;
+        self.ui_alpha = 0.0;
+    }
+
+    /// Forward the ESP preview model to the render backend
+    pub fn update_model_preview(
+        &mut self,
+        controller: &mut SystemRuntimeController,
+        model: Option<&CharacterModel>,
+    ) {
+        self.model_preview.update(controller, model);
+    }
+
     fn render_sidebar_button(
         &mut self,
         ui: &imgui::Ui,
@@ -231,8 +433,8 @@ impl SettingsUI {
     ) {
         let is_active = self.active_tab == tab;
         let animation_speed = 8.0;
-        let moved_offset = 10.0;
-        let button_width = 130.0;
+        let moved_offset = 10.0 * self.ui_scale;
+        let button_width = 130.0 * self.ui_scale;
         
         let current_offset = self.tab_offsets.entry(tab).or_insert(0.0);
         
@@ -251,8 +453,8 @@ impl SettingsUI {
             None
         };
 
-        let clicked = ui.button_with_size(text, [button_width, 30.0]);
-        let is_hovered = ui.is_item_hovered() && self.ui_alpha > 0.01;
+        let clicked = ui.button_with_size(text, [button_width, 30.0 * self.ui_scale]);
+        let is_hovered = (ui.is_item_hovered() || ui.is_item_focused()) && self.ui_alpha > 0.01;
 
         if let Some(s) = style {
             s.pop();
@@ -260,6 +462,8 @@ impl SettingsUI {
 
         if clicked {
             self.active_tab = tab;
+            /* continue keyboard / gamepad navigation within the selected tab */
+            self.focus_content = ui.io().nav_visible;
         }
 
         let target_offset = if is_active || is_hovered {
@@ -277,6 +481,8 @@ impl SettingsUI {
         ui: &imgui::Ui,
         _unicode_text: &UnicodeTextRenderer,
     ) {
+        self.ui_scale = app.settings().ui_scale(app.system_ui_scale);
+
         if self.is_first_render {
             let total_elapsed = self.start_time.elapsed();
             let delay = Duration::from_secs(5);
@@ -315,12 +521,12 @@ impl SettingsUI {
         let _alpha_guard = ui.push_style_var(StyleVar::Alpha(self.ui_alpha));
         let _bg_color = ui.push_style_color(StyleColor::WindowBg, [0.02, 0.02, 0.03, 1.0]);
         
-        const WINDOW_SIZE: [f32; 2] = [1024.0, 768.0];
+        let window_size = [WINDOW_SIZE[0] * self.ui_scale, WINDOW_SIZE[1] * self.ui_scale];
 
         let display_size = ui.io().display_size;
         let window_pos = [
-            (display_size[0] - WINDOW_SIZE[0]) * 0.5,
-            (display_size[1] - WINDOW_SIZE[1]) * 0.5,
+            (display_size[0] - window_size[0]) * 0.5,
+            (display_size[1] - window_size[1]) * 0.5,
         ];
 
         let mut flags = WindowFlags::NO_DECORATION;
@@ -329,7 +535,7 @@ impl SettingsUI {
         }
 
         ui.window(format!("LABHub v{}", VERSION))
-            .size(WINDOW_SIZE, Condition::Always)
+            .size(window_size, Condition::Always)
             .position(window_pos, Condition::Always)
             .flags(flags)
             .build(|| {
@@ -337,15 +543,15 @@ impl SettingsUI {
                 let _style = ui.push_style_var(StyleVar::WindowPadding([0.0, 0.0]));
 
                 {
-                    let title_bar_height = 35.0;
+                    let title_bar_height = 35.0 * self.ui_scale;
                     let _title_bg = ui.push_style_color(StyleColor::ChildBg, [0.02, 0.02, 0.03, 1.0]);
 
                     ui.child_window("TitleBar")
-                        .size([WINDOW_SIZE[0], title_bar_height])
+                        .size([window_size[0], title_bar_height])
                         .build(|| {
                             let _font = ui.push_font(title_font_id);
                             
-                            ui.set_cursor_pos([15.0, 8.0]);
+                            ui.set_cursor_pos([15.0 * self.ui_scale, 8.0 * self.ui_scale]);
                             let logo_letters = [
                                 ("L", [0.8, 0.8, 0.8, 1.0]),
                                 ("A", [0.7, 0.7, 0.7, 1.0]),
@@ -366,7 +572,7 @@ impl SettingsUI {
                         });
                 }
 
-                let sidebar_width = 180.0;
+                let sidebar_width = 180.0 * self.ui_scale;
                 let _sidebar_bg = ui.push_style_color(StyleColor::ChildBg, [0.02, 0.02, 0.03, 1.0]);
                 
                 let previous_tab = self.active_tab;
@@ -397,8 +603,12 @@ impl SettingsUI {
                         self.render_sidebar_button(ui, "Crosshair", font_awesome::CROSSHAIRS, ActiveTab::Crosshair, sidebar_width);
                         
                         render_sidebar_label(ui, "- misc -");
+                        self.render_sidebar_button(ui, "Stats", font_awesome::LIST, ActiveTab::Stats, sidebar_width);
+                        self.render_sidebar_button(ui, "Logs", font_awesome::TERMINAL, ActiveTab::Logs, sidebar_width);
                         self.render_sidebar_button(ui, "Hotkeys", font_awesome::KEYBOARD, ActiveTab::Hotkeys, sidebar_width);
                         self.render_sidebar_button(ui, "Config", font_awesome::SAVE, ActiveTab::Config, sidebar_width);
+                        self.render_sidebar_button(ui, "Theme", font_awesome::PAINT_BRUSH, ActiveTab::Theme, sidebar_width);
+                        self.render_sidebar_button(ui, "Privacy", font_awesome::LOCK, ActiveTab::Privacy, sidebar_width);
                         self.render_sidebar_button(ui, "Info", font_awesome::INFO_CIRCLE, ActiveTab::Info, sidebar_width);
                     });
 
@@ -435,12 +645,15 @@ impl SettingsUI {
                 ui.child_window("Content")
                     .build(|| {
                         let _padding = ui.push_style_var(StyleVar::WindowPadding([15.0, 15.0]));
+                        if std::mem::take(&mut self.focus_content) {
+                            ui.set_keyboard_focus_here();
+                        }
                         match self.active_tab {
                             ActiveTab::Visuals => {
                                 self.render_esp_settings(app, &mut *settings, ui);
                             }
                             ActiveTab::TriggerBot => {
-                                ui.set_next_item_width(150.0);
+                                ui.set_next_item_width(150.0 * self.ui_scale);
                                 ui.combo_enum(
                                     "Trigger Bot",
                                     &[
@@ -454,11 +667,7 @@ impl SettingsUI {
                                 );
             
                                 if !matches!(settings.trigger_bot_mode, KeyToggleMode::Off | KeyToggleMode::AlwaysOn) {
-                                    ui.button_key_optional(
-                                        "Trigger bot key",
-                                        &mut settings.key_trigger_bot,
-                                        [150.0, 0.0]
-                                    );
+                                    self.render_hotkey_binding(ui, &mut *settings, HotkeyBinding::TriggerBot, "Trigger bot key");
                                 }
                                 
                                 if !matches!(settings.trigger_bot_mode, KeyToggleMode::Off) {
@@ -495,6 +704,9 @@ impl SettingsUI {
                                 }
                                 ui.separator();
                                 self.animated_checkbox(ui, "Simple Recoil Helper", &mut settings.aim_assist_recoil);
+                                ui.separator();
+                                self.animated_checkbox(ui, "Disable trigger bot & legit aim in warmup", &mut settings.relax_features_in_warmup);
+                                self.animated_checkbox(ui, "Disable trigger bot & legit aim in knife rounds", &mut settings.relax_features_in_knife_round);
                             }
                             ActiveTab::LegitAim => {
                                 ui.text("Legit Aim Settings");
@@ -504,11 +716,7 @@ impl SettingsUI {
                                 if settings.legit_aim_enabled {
                                     ui.indent();
                                     
-                                    ui.button_key_optional(
-                                        "Activation Key",
-                                        &mut settings.legit_aim_key,
-                                        [150.0, 0.0]
-                                    );
+                                    self.render_hotkey_binding(ui, &mut *settings, HotkeyBinding::LegitAim, "Activation Key");
 
                                     ui.slider_config("FOV", 1.0, 180.0).display_format("%.1f px").build(&mut settings.legit_aim_fov);
                                     ui.slider_config("Smoothing", 1.0, 50.0).display_format("%.1f").build(&mut settings.legit_aim_smooth);
@@ -517,7 +725,7 @@ impl SettingsUI {
                                     let bones = ["head_0", "neck_0", "spine_1", "spine_2", "pelvis"];
                                     let mut selected_bone_idx = bones.iter().position(|&b| b == current_bone).unwrap_or(0);
                                     
-                                    ui.set_next_item_width(150.0);
+                                    ui.set_next_item_width(150.0 * self.ui_scale);
                                     if ui.combo("Target Bone", &mut selected_bone_idx, &bones, |b| b.to_string().into()) {
                                         settings.legit_aim_bone = bones[selected_bone_idx].to_string();
                                     }
@@ -558,77 +766,93 @@ impl SettingsUI {
                                         (color_f32[3] * 255.0) as u8,
                                     ];
                                 }
+
+                                self.animated_checkbox(ui, "Only While Unscoped", &mut style.unscoped_only);
+                                self.animated_checkbox(ui, "Color On Enemy", &mut style.target_color_enabled);
+                                if style.target_color_enabled {
+                                    let mut target_color_f32 = style.target_color.map(|value| value as f32 / 255.0);
+                                    if ui.color_edit4_config("Enemy Color", &mut target_color_f32).alpha(true).build() {
+                                        style.target_color = target_color_f32.map(|value| (value * 255.0) as u8);
+                                    }
+                                }
+                                ui.unindent();
+                                _disabled.end();
+
+                                ui.dummy([0.0, 10.0]);
+                                self.animated_checkbox(ui, "Custom Crosshair", &mut settings.custom_crosshair);
+                                let _disabled = ui.begin_disabled(!settings.custom_crosshair);
+                                ui.indent();
+                                self.render_custom_crosshair_settings(app, &mut settings.custom_crosshair_settings, ui);
                                 ui.unindent();
                             }
                             ActiveTab::World => {
                                 ui.text("World");
                                 ui.separator();
                                 self.animated_checkbox(ui, "Bomb Timer", &mut settings.bomb_timer);
+                                if settings.bomb_timer {
+                                    ui.indent();
+                                    self.animated_checkbox(ui, "Bomb Damage", &mut settings.bomb_damage);
+                                    self.animated_checkbox(ui, "Bomb Damage Radius", &mut settings.bomb_damage_rings);
+                                    ui.unindent();
+                                }
                                 self.animated_checkbox(ui, "Bomb Site Label", &mut settings.bomb_label);
                                 
-                                self.animated_checkbox(ui, "Grenade Trajectory", &mut settings.grenade_trajectory.enabled);
-                                
-                                if settings.grenade_trajectory.enabled {
-                                    ui.indent();
-                                    
-                                    // Dynamic Map List
-                                    let mut maps: Vec<String> = Vec::new();
-                                    
-                                    // Scan "resources" directory
-                                    if let Ok(entries) = std::fs::read_dir("resources") {
-                                        for entry in entries.flatten() {
-                                            if let Ok(file_type) = entry.file_type() {
-                                                if file_type.is_file() {
-                                                    if let Some(name) = entry.file_name().to_str() {
-                                                        if name.ends_with(".glb") && name != "character.glb" {
-                                                            maps.push(name.trim_end_matches(".glb").to_string());
-                                                        }
-                                                    }
-                                                }
-                                            }
+                                self.render_setting_with_cog_toggle(app, ui, "Grenade Trajectory", &mut settings.grenade_trajectory.enabled, "grenade_trajectory_settings");
+                                let grenade_trajectory = &mut settings.grenade_trajectory;
+                                self.render_dropdown_section(ui, "grenade_trajectory_settings", |this, ui| {
+                                    this.animated_checkbox(ui, "Color by Grenade Type", &mut grenade_trajectory.line_color_by_type);
+                                    if !grenade_trajectory.line_color_by_type {
+                                        let mut line_color = grenade_trajectory.line_color.as_f32();
+                                        if ui.color_edit4_config("Line Color", &mut line_color).alpha_bar(true).inputs(false).build() {
+                                            grenade_trajectory.line_color = Color::from_f32(line_color);
                                         }
                                     }
-                                    // Also scan CWD for backward compatibility or if user put them there
-                                    if let Ok(entries) = std::fs::read_dir(".") {
-                                        for entry in entries.flatten() {
-                                            if let Ok(file_type) = entry.file_type() {
-                                                if file_type.is_file() {
-                                                    if let Some(name) = entry.file_name().to_str() {
-                                                        if name.ends_with(".glb") && name != "character.glb" {
-                                                            let map_name = name.trim_end_matches(".glb").to_string();
-                                                            if !maps.contains(&map_name) {
-                                                                 maps.push(map_name);
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
+                                    ui.set_next_item_width(150.0 * this.ui_scale);
+                                    ui.slider_config("Line Thickness", 0.5, 6.0).display_format("%.1f").build(&mut grenade_trajectory.line_thickness);
+                                    this.animated_checkbox(ui, "Landing Circle", &mut grenade_trajectory.landing_circle);
+                                    this.animated_checkbox(ui, "Bounce Markers", &mut grenade_trajectory.bounce_markers);
+                                    this.animated_checkbox(ui, "Detonation Timer", &mut grenade_trajectory.detonation_timer);
+
+                                    let mut maps = vec!["Auto".to_string()];
+                                    maps.extend(MapMesh::available_maps());
+                                    let mut selected_idx = maps.iter().position(|map| *map == grenade_trajectory.selected_map).unwrap_or(0);
+                                    ui.set_next_item_width(150.0 * this.ui_scale);
+                                    if ui.combo("Map Mesh", &mut selected_idx, &maps, |map| map.as_str().into()) {
+                                        grenade_trajectory.selected_map = maps[selected_idx].clone();
                                     }
-                                    
-                                    maps.sort();
-                                    
-                                    if maps.is_empty() {
+                                    if maps.len() <= 1 {
                                         ui.text_colored([1.0, 0.0, 0.0, 1.0], "No .glb map files found!");
-                                    } else {
-                                        let current_map = &settings.grenade_trajectory.selected_map;
-                                        
-                                        // Handle "Auto" or invalid maps by defaulting to first available
-                                        let mut selected_idx = maps.iter().position(|m| m == current_map).unwrap_or(0);
-                                        
-                                        // Force update if "Auto" or empty
-                                        if current_map == "Auto" || current_map.is_empty() {
-                                             if let Some(first) = maps.first() {
-                                                 settings.grenade_trajectory.selected_map = first.clone();
-                                             }
-                                        }
+                                    }
 
-                                        ui.set_next_item_width(150.0);
-                                        if ui.combo("Map Selection", &mut selected_idx, &maps, |m| m.to_string().into()) {
-                                            settings.grenade_trajectory.selected_map = maps[selected_idx].clone();
+                                    if let Ok(mut mesh_state) = app.app_state.resolve_mut::<StateGrenadeTrajectoryMesh>(()) {
+                                        match mesh_state.loaded_map() {
+                                            Some((map_name, triangles)) => ui.text_disabled(&format!("Loaded {} ({} triangles)", map_name, triangles)),
+                                            None => ui.text_disabled("No map mesh loaded"),
+                                        }
+                                        if ui.button("Reload Mesh") {
+                                            mesh_state.request_reload();
                                         }
                                     }
-                                    
+                                });
+
+                                self.render_setting_with_cog_toggle(app, ui, "Enemy Utility Alerts", &mut settings.utility_alerts, "utility_alerts_settings");
+                                let utility_alerts = &mut settings.utility_alerts_settings;
+                                self.render_dropdown_section(ui, "utility_alerts_settings", |this, ui| {
+                                    ui.set_next_item_width(150.0 * this.ui_scale);
+                                    ui.slider_config("Radius (m)", 5.0, 100.0).display_format("%.0f").build(&mut utility_alerts.radius);
+                                    ui.set_next_item_width(150.0 * this.ui_scale);
+                                    ui.slider_config("Duration (s)", 1.0, 10.0).display_format("%.1f").build(&mut utility_alerts.duration);
+                                    this.animated_checkbox(ui, "Flashbang", &mut utility_alerts.flashbang);
+                                    this.animated_checkbox(ui, "Smoke", &mut utility_alerts.smoke);
+                                    this.animated_checkbox(ui, "HE Grenade", &mut utility_alerts.hegrenade);
+                                    this.animated_checkbox(ui, "Molotov / Incendiary", &mut utility_alerts.molotov);
+                                    this.animated_checkbox(ui, "Decoy", &mut utility_alerts.decoy);
+                                });
+
+                                self.render_setting_with_cog_toggle(app, ui, "Grenade Helper", &mut settings.grenade_helper.active, "grenade_helper_settings");
+                                if self.open_dropdowns.iter().any(|id| id == "grenade_helper_settings") {
+                                    ui.indent();
+                                    self.render_grenade_helper_spots(app, &mut *settings, ui);
                                     ui.unindent();
                                 }
                             }
@@ -641,10 +865,264 @@ impl SettingsUI {
                                 if ui.slider_config("##fps_limit", 0, 1000).display_format("%d").build(&mut start_fps) {
                                     settings.fps_limit = start_fps;
                                 }
+                                ui.text("Update rate (game reads per second)");
+                                ui.slider_config("##update_tick_rate", 16, 128).display_format("%d Hz").build(&mut settings.update_tick_rate);
+                                if ui.is_item_hovered() {
+                                    ui.tooltip_text("Rate the player list is read from the game with, independent of the FPS limit.\nLower values reduce the CPU usage.");
+                                }
+                                self.animated_checkbox(ui, "Power saving while idle", &mut settings.idle_power_saving);
+                                if ui.is_item_hovered() {
+                                    ui.tooltip_text(format!(
+                                        "Limits the overlay to {} FPS while nothing is drawn and the menu is closed.\nFeatures update less frequently while idle.",
+                                        IDLE_FPS_LIMIT
+                                    ));
+                                }
+                                self.animated_checkbox(ui, "Interactive widgets while the menu is closed", &mut settings.interactive_widgets);
+                                if ui.is_item_hovered() {
+                                    ui.tooltip_text("Widgets like the spectators list can be moved with the mouse.\nClicks on the rest of the screen still reach the game.");
+                                }
                                 
-                                self.animated_checkbox(ui, "Spectators List", &mut settings.spectators_list);
+                                self.render_setting_with_cog_toggle(app, ui, "Spectators List", &mut settings.spectators_list, "spectators_list_settings");
+                                let spectators_list = &mut settings.spectators_list_settings;
+                                self.render_dropdown_section(ui, "spectators_list_settings", |this, ui| {
+                                    this.animated_checkbox(ui, "Show Spectator Count", &mut spectators_list.show_count);
+                                    this.animated_checkbox(ui, "Show Spectator Targets", &mut spectators_list.show_targets);
+                                    this.animated_checkbox(ui, "Show Spectator Ranks", &mut spectators_list.show_ranks);
+                                    ui.set_next_item_width(150.0 * this.ui_scale);
+                                    ui.combo_enum("Anchor", OVERLAY_ANCHOR_OPTIONS, &mut spectators_list.anchor);
+                                    if ui.button("Reset Position") {
+                                        let defaults = SpectatorsListSettings::default();
+                                        spectators_list.offset = defaults.offset;
+                                        spectators_list.size = defaults.size;
+                                    }
+                                });
+
+                                self.render_setting_with_cog_toggle(app, ui, "Round HUD", &mut settings.round_hud, "round_hud_settings");
+                                let round_hud = &mut settings.round_hud_settings;
+                                self.render_dropdown_section(ui, "round_hud_settings", |this, ui| {
+                                    this.animated_checkbox(ui, "Show Score", &mut round_hud.show_score);
+                                    this.animated_checkbox(ui, "Show Round", &mut round_hud.show_round);
+                                    this.animated_checkbox(ui, "Show Timer", &mut round_hud.show_timer);
+                                    ui.set_next_item_width(150.0 * this.ui_scale);
+                                    ui.combo_enum("Anchor##round_hud", OVERLAY_ANCHOR_OPTIONS, &mut round_hud.anchor);
+                                    ui.set_next_item_width(150.0 * this.ui_scale);
+                                    ui.input_float2("Offset##round_hud", &mut round_hud.offset).build();
+                                });
+                                self.render_setting_with_cog_toggle(app, ui, "Movement HUD", &mut settings.movement_hud, "movement_hud_settings");
+                                let movement_hud = &mut settings.movement_hud_settings;
+                                self.render_dropdown_section(ui, "movement_hud_settings", |this, ui| {
+                                    this.animated_checkbox(ui, "Show Speed Graph", &mut movement_hud.show_graph);
+                                    this.animated_checkbox(ui, "Show Duck / Air State", &mut movement_hud.show_state);
+                                    ui.set_next_item_width(150.0 * this.ui_scale);
+                                    ui.combo_enum("Units##movement_hud", SPEED_UNIT_OPTIONS, &mut movement_hud.units);
+                                    ui.set_next_item_width(150.0 * this.ui_scale);
+                                    ui.combo_enum("Anchor##movement_hud", OVERLAY_ANCHOR_OPTIONS, &mut movement_hud.anchor);
+                                    ui.set_next_item_width(150.0 * this.ui_scale);
+                                    ui.input_float2("Offset##movement_hud", &mut movement_hud.offset).build();
+                                });
+                                self.render_setting_with_cog_toggle(app, ui, "Active Keybinds", &mut settings.keybind_overlay, "keybind_overlay_settings");
+                                let keybind_overlay = &mut settings.keybind_overlay_settings;
+                                self.render_dropdown_section(ui, "keybind_overlay_settings", |this, ui| {
+                                    this.animated_checkbox(ui, "Show Inactive", &mut keybind_overlay.show_inactive);
+                                    ui.set_next_item_width(150.0 * this.ui_scale);
+                                    ui.combo_enum("Anchor##keybind_overlay", OVERLAY_ANCHOR_OPTIONS, &mut keybind_overlay.anchor);
+                                    ui.set_next_item_width(150.0 * this.ui_scale);
+                                    ui.input_float2("Offset##keybind_overlay", &mut keybind_overlay.offset).build();
+                                });
+                                self.animated_checkbox(ui, "Team Economy", &mut settings.team_economy);
+                                self.render_setting_with_cog_toggle(app, ui, "Team Overview", &mut settings.team_overview, "team_overview_settings");
+                                let steam_api_key = &mut settings.steam_api_key;
+                                self.render_dropdown_section(ui, "team_overview_settings", |this, ui| {
+                                    let mut api_key = this.steam_api_key_edit.clone().unwrap_or_else(|| steam_api_key.clone().unwrap_or_default());
+                                    ui.set_next_item_width(250.0 * this.ui_scale);
+                                    if ui.input_text("Steam Web API key", &mut api_key).password(true).build() {
+                                        this.steam_api_key_edit = Some(api_key);
+                                    }
+                                    /* every changed key drops the profile cache, only apply the final key */
+                                    if ui.is_item_deactivated() {
+                                        if let Some(api_key) = this.steam_api_key_edit.take() {
+                                            let api_key = Some(api_key).filter(|key| !key.is_empty());
+                                            if *steam_api_key != api_key {
+                                                *steam_api_key = api_key;
+                                                if let Ok(mut profiles) = app.app_state.resolve_mut::<StateSteamProfiles>(()) {
+                                                    profiles.clear();
+                                                }
+                                            }
+                                        }
+                                    }
+                                    ui.text_disabled("Used to resolve persona names and avatars");
+                                });
                                 self.animated_checkbox(ui, "Watermark", &mut settings.labh_watermark);
-            
+                                self.animated_checkbox(ui, "Clip-safe mode (anonymize player names)", &mut settings.clip_safe_mode);
+                                self.animated_checkbox(ui, "Session event log", &mut settings.session_event_log);
+                                if settings.session_event_log {
+                                    ui.indent();
+                                    if ui.button("Export session to CSV") {
+                                        let mut dialog = overlay_file_dialog().add_filter("Session Log", &["jsonl"]);
+                                        if let Ok(sessions_dir) = get_sessions_dir() { dialog = dialog.set_directory(sessions_dir); }
+                                        if let Some(path) = dialog.pick_file() {
+                                            match export_session_csv(&path) {
+                                                Ok(csv_path) => app.notify(NotificationLevel::Info, format!("Exported session to {}", csv_path.display())),
+                                                Err(e) => app.notify(NotificationLevel::Error, format!("Failed to export session: {:#}", e)),
+                                            }
+                                        }
+                                    }
+                                    ui.unindent();
+                                }
+                                self.animated_checkbox(ui, "Game state API", &mut settings.game_state_api);
+                                if settings.game_state_api {
+                                    ui.indent();
+                                    let mut port = settings.game_state_api_port as i32;
+                                    ui.set_next_item_width(150.0 * self.ui_scale);
+                                    if ui.input_int("Port##game_state_api", &mut port).build() {
+                                        settings.game_state_api_port = port.clamp(1, u16::MAX as i32) as u16;
+                                    }
+                                    ui.text_disabled(&format!(
+                                        "http://127.0.0.1:{}/players, /bomb, /localplayer, /state and ws /stream",
+                                        settings.game_state_api_port
+                                    ));
+                                    ui.unindent();
+                                }
+                                self.animated_checkbox(ui, "Discord Rich Presence", &mut settings.discord_presence);
+                                if settings.discord_presence {
+                                    ui.indent();
+                                    ui.set_next_item_width(250.0 * self.ui_scale);
+                                    ui.input_text("Application ID##discord_presence", &mut settings.discord_presence_client_id).build();
+                                    self.animated_checkbox(ui, "Privacy mode (don't mention the overlay)", &mut settings.discord_presence_privacy);
+                                    ui.text_disabled("Publishes the map, score and whether you are alive");
+                                    ui.unindent();
+                                }
+                                self.render_setting_with_cog_toggle(app, ui, "Audio Alerts", &mut settings.audio_alerts, "audio_alerts_settings");
+                                let audio_alerts = &mut settings.audio_alerts_settings;
+                                self.render_dropdown_section(ui, "audio_alerts_settings", |this, ui| {
+                                    ui.set_next_item_width(150.0 * this.ui_scale);
+                                    ui.slider_config("Master Volume", 0.0, 1.0).display_format("%.2f").build(&mut audio_alerts.volume);
+                                    for event in AudioEvent::ALL {
+                                        let _id = ui.push_id(event.name());
+                                        /* events without a cue play the default one, a cue is only stored once it has been changed */
+                                        let mut new_cue = None;
+                                        let cue = match audio_alerts.cues.get_mut(event.name()) {
+                                            Some(cue) => cue,
+                                            None => new_cue.insert(AudioCueSettings::default()),
+                                        };
+                                        let mut changed = false;
+
+                                        ui.separator();
+                                        changed |= this.animated_checkbox(ui, event.display_name(), &mut cue.enabled);
+                                        ui.set_next_item_width(150.0 * this.ui_scale);
+                                        changed |= ui.slider_config("Volume", 0.0, 1.0).display_format("%.2f").build(&mut cue.volume);
+                                        ui.text_disabled(&match cue.sound_file.as_ref().and_then(|path| path.file_name()) {
+                                            Some(file_name) => file_name.to_string_lossy().to_string(),
+                                            None => "Built-in tone".to_string(),
+                                        });
+                                        if ui.button("Choose sound") {
+                                            let dialog = overlay_file_dialog().add_filter("Sound", &["wav", "mp3", "ogg", "flac"]);
+                                            if let Some(path) = dialog.pick_file() {
+                                                cue.sound_file = Some(path);
+                                                changed = true;
+                                            }
+                                        }
+                                        ui.same_line();
+                                        if ui.button("Use tone") {
+                                            changed |= cue.sound_file.take().is_some();
+                                        }
+                                        ui.same_line();
+                                        if ui.button("Test") {
+                                            if let Ok(mut audio_events) = app.app_state.resolve_mut::<StateAudioEvents>(()) {
+                                                audio_events.preview(event);
+                                            }
+                                        }
+
+                                        if let Some(cue) = new_cue.filter(|_| changed) {
+                                            audio_alerts.cues.insert(event.name().to_string(), cue);
+                                        }
+                                    }
+                                });
+                                let mut ui_scale_auto = settings.ui_scale.is_none();
+                                if self.animated_checkbox(ui, "Menu scale follows system DPI", &mut ui_scale_auto) {
+                                    settings.ui_scale = if ui_scale_auto { None } else { Some(self.ui_scale) };
+                                    app.settings_ui_scale_changed.store(true, Ordering::Relaxed);
+                                }
+                                if !ui_scale_auto {
+                                    ui.indent();
+                                    let mut percent = self.ui_scale_edit.unwrap_or((self.ui_scale * 100.0).round() as i32);
+                                    ui.set_next_item_width(150.0 * self.ui_scale);
+                                    if ui.slider_config("Menu scale", (UI_SCALE_MIN * 100.0) as i32, (UI_SCALE_MAX * 100.0) as i32)
+                                        .display_format("%d%%")
+                                        .build(&mut percent)
+                                    {
+                                        self.ui_scale_edit = Some(percent);
+                                    }
+                                    /* rebuilding the fonts while dragging would stall every frame */
+                                    if ui.is_item_deactivated_after_edit() {
+                                        if let Some(percent) = self.ui_scale_edit.take() {
+                                            settings.ui_scale = Some(percent as f32 / 100.0);
+                                            app.settings_ui_scale_changed.store(true, Ordering::Relaxed);
+                                        }
+                                    }
+                                    ui.unindent();
+                                }
+                                ui.text_disabled(&format!("System scale: {:.0}%", app.system_ui_scale * 100.0));
+
+                                let monitor_preview = settings.overlay_monitor.clone().unwrap_or_else(|| "Follow game window".to_string());
+                                ui.set_next_item_width(250.0 * self.ui_scale);
+                                if let Some(_combo) = ui.begin_combo("Overlay monitor", &monitor_preview) {
+                                    if ui.selectable_config("Follow game window").selected(settings.overlay_monitor.is_none()).build() {
+                                        settings.overlay_monitor = None;
+                                        app.settings_overlay_monitor_changed.store(true, Ordering::Relaxed);
+                                    }
+
+                                    /* only enumerated while the combo is open */
+                                    for monitor in list_monitors() {
+                                        let label = format!(
+                                            "{} ({}x{} @ {}Hz){}",
+                                            monitor.device_name,
+                                            monitor.width(),
+                                            monitor.height(),
+                                            monitor.refresh_rate,
+                                            if monitor.primary { ", primary" } else { "" }
+                                        );
+                                        let selected = settings.overlay_monitor.as_deref() == Some(monitor.device_name.as_str());
+                                        if ui.selectable_config(&label).selected(selected).build() {
+                                            settings.overlay_monitor = Some(monitor.device_name);
+                                            app.settings_overlay_monitor_changed.store(true, Ordering::Relaxed);
+                                        }
+                                    }
+                                }
+                                if ui.is_item_hovered() {
+                                    ui.tooltip_text("The overlay covers the selected monitor instead of the game window");
+                                }
+                                if ui.button("Reload icons") {
+                                    app.settings_reload_icons.store(true, Ordering::Relaxed);
+                                }
+                                if ui.is_item_hovered() {
+                                    ui.tooltip_text("Reload the weapon and rank icons from the resources directory");
+                                }
+                                ui.text("Draw layers");
+                                ui.indent();
+                                self.animated_checkbox(ui, "World (ESP, grenades, measurements)", &mut settings.render_layers.world);
+                                self.animated_checkbox(ui, "HUD (panels, timers, crosshair)", &mut settings.render_layers.hud);
+                                self.animated_checkbox(ui, "Debug (debug visualizations and windows)", &mut settings.render_layers.debug);
+                                ui.text_disabled("Draw order, later layers are drawn on top");
+                                let mut layer_order = settings.render_layers.draw_order().collect::<Vec<_>>();
+                                let mut layer_order_changed = false;
+                                for index in 0..layer_order.len() {
+                                    if ui.arrow_button(format!("##render_layer_up_{}", index), imgui::Direction::Up) && index > 0 {
+                                        layer_order.swap(index, index - 1);
+                                        layer_order_changed = true;
+                                    }
+                                    ui.same_line();
+                                    if ui.arrow_button(format!("##render_layer_down_{}", index), imgui::Direction::Down) && index + 1 < layer_order.len() {
+                                        layer_order.swap(index, index + 1);
+                                        layer_order_changed = true;
+                                    }
+                                    ui.same_line();
+                                    ui.text(layer_order[index].display_name());
+                                }
+                                if layer_order_changed {
+                                    settings.render_layers.order = layer_order;
+                                }
+                                ui.unindent();
                                 if self.animated_checkbox(
                                     ui,
                                     "Hide overlay from screen capture",
@@ -652,6 +1130,38 @@ impl SettingsUI {
                                 ) {
                                     app.settings_screen_capture_changed.store(true, Ordering::Relaxed);
                                 }
+
+                                if self.render_setting_with_cog_toggle(app, ui, "Capture output", &mut settings.capture_output.enabled, "capture_output_settings") {
+                                    app.settings_capture_output_changed.store(true, Ordering::Relaxed);
+                                }
+                                let capture_output = &mut settings.capture_output;
+                                self.render_dropdown_section(ui, "capture_output_settings", |this, ui| {
+                                    ui.text_disabled("Shares the selected widgets as Spout texture with capture software (e.g. OBS)");
+                                    ui.set_next_item_width(200.0 * this.ui_scale);
+                                    ui.input_text("Sender name", &mut capture_output.sender_name).build();
+                                    /* the sender is recreated with the new name once editing finished */
+                                    let mut changed = ui.is_item_deactivated_after_edit();
+                                    changed |= this.animated_checkbox(ui, "Spectators List##capture", &mut capture_output.spectators_list);
+                                    changed |= this.animated_checkbox(ui, "Bomb Timer##capture", &mut capture_output.bomb_timer);
+                                    changed |= this.animated_checkbox(ui, "Round HUD##capture", &mut capture_output.round_hud);
+                                    changed |= this.animated_checkbox(ui, "Movement HUD##capture", &mut capture_output.movement_hud);
+                                    changed |= this.animated_checkbox(ui, "Keybind Overlay##capture", &mut capture_output.keybind_overlay);
+                                    changed |= this.animated_checkbox(ui, "Team Economy##capture", &mut capture_output.team_economy);
+                                    changed |= this.animated_checkbox(ui, "Team Overview##capture", &mut capture_output.team_overview);
+                                    if changed {
+                                        app.settings_capture_output_changed.store(true, Ordering::Relaxed);
+                                    }
+                                });
+                                if ui.button("OBS mode") {
+                                    settings.hide_overlay_from_screen_capture = true;
+                                    settings.capture_output.enabled = true;
+                                    app.settings_screen_capture_changed.store(true, Ordering::Relaxed);
+                                    app.settings_capture_output_changed.store(true, Ordering::Relaxed);
+                                    app.notify(NotificationLevel::Info, format!("OBS mode enabled, add the Spout sender '{}' to your scene", settings.capture_output.sender_name));
+                                }
+                                if ui.is_item_hovered() {
+                                    ui.tooltip_text("Hides the overlay from screen capture and shares only the selected capture output widgets");
+                                }
             
                                 if self.animated_checkbox(
                                     ui,
@@ -660,25 +1170,77 @@ impl SettingsUI {
                                 ) {
                                     app.settings_render_debug_window_changed.store(true, Ordering::Relaxed);
                                 }
+
+                                self.animated_checkbox(ui, "Frame budget watchdog", &mut settings.frame_budget_watchdog);
+                                if settings.frame_budget_watchdog {
+                                    ui.indent();
+                                    ui.set_next_item_width(150.0 * self.ui_scale);
+                                    ui.slider_config("Budget per feature (ms)", 0.5, 16.0)
+                                        .display_format("%.1f")
+                                        .build(&mut settings.frame_budget_ms);
+                                    ui.text_disabled("Features exceeding their budget only update every few frames");
+                                    ui.unindent();
+                                }
+
+                                self.animated_checkbox(ui, "Measurement Tool", &mut settings.measurement_tool);
+                                if settings.measurement_tool {
+                                    ui.indent();
+                                    ui.text_disabled("Hold the measurement key and left click two points");
+                                    ui.unindent();
+                                }
                             }
                             ActiveTab::Hotkeys => {
-                                ui.button_key_ignore_mouse_left(
-                                    "Toggle Settings",
-                                    &mut settings.key_settings,
-                                    [150.0, 0.0]
-                                );
+                                self.render_hotkey_binding(ui, &mut *settings, HotkeyBinding::Menu, "Toggle Settings");
+                                self.animated_checkbox(ui, "Start with the menu closed", &mut settings.start_hidden);
             
                                 {
                                     let _enabled = ui.begin_enabled(matches!(
                                         settings.esp_mode,
                                         KeyToggleMode::Toggle | KeyToggleMode::Trigger
                                     ));
-                                    ui.button_key_optional(
-                                        "ESP Toggle/Hold",
-                                        &mut settings.esp_toggle,
-                                        [150.0, 0.0]
+                                    self.render_hotkey_binding(ui, &mut *settings, HotkeyBinding::Esp, "ESP Toggle/Hold");
+                                }
+
+                                self.render_hotkey_binding(ui, &mut *settings, HotkeyBinding::MeasurementTool, "Measurement Tool");
+
+                                self.render_hotkey_binding(ui, &mut *settings, HotkeyBinding::Panic, "Panic Key");
+                                if settings.key_panic.is_some() {
+                                    ui.indent();
+                                    self.animated_checkbox(ui, "Minimize console on panic", &mut settings.panic_minimize_console);
+                                    ui.unindent();
+                                }
+
+                                ui.separator();
+                                ui.text("Feature Hotkeys");
+                                ui.text_disabled("Features without a key are always active");
+                                for feature in FeatureToggle::ALL {
+                                    let _id = ui.push_id(feature.display_name());
+
+                                    ui.set_next_item_width(120.0 * self.ui_scale);
+                                    ui.combo_enum(
+                                        "##mode",
+                                        &[
+                                            (KeyToggleMode::Toggle, "Toggle"),
+                                            (KeyToggleMode::Trigger, "Hold"),
+                                            (KeyToggleMode::TriggerInverted, "Hold Inverted"),
+                                        ],
+                                        &mut settings.feature_hotkeys.entry(feature).or_default().mode
                                     );
+                                    ui.same_line();
+                                    self.render_hotkey_binding(ui, &mut *settings, HotkeyBinding::Feature(feature), feature.display_name());
                                 }
+                                settings.feature_hotkeys.retain(|_, hotkey| *hotkey != FeatureHotkey::default());
+                            }
+                            ActiveTab::Stats => {
+                                ui.text("Match Statistics");
+                                ui.separator();
+                                self.animated_checkbox(ui, "Track match statistics", &mut settings.match_statistics);
+                                self.render_match_statistics(app, ui, settings.clip_safe_mode);
+                            }
+                            ActiveTab::Logs => {
+                                ui.text("Logs");
+                                ui.separator();
+                                self.render_log_viewer(ui);
                             }
                             ActiveTab::Config => {
                                 if self.needs_config_refresh {
@@ -693,7 +1255,15 @@ impl SettingsUI {
                                 ui.text("Configuration Management");
                                 ui.separator();
 
-                                let list_height = ui.content_region_avail()[1] - ui.frame_height_with_spacing() * 2.5;
+                                ui.set_next_item_width(150.0 * self.ui_scale);
+                                ui.slider_config("Autosave interval", 0, 300)
+                                    .display_format(if settings.autosave_interval == 0 { "off" } else { "%ds" })
+                                    .build(&mut settings.autosave_interval);
+                                if ui.is_item_hovered() {
+                                    ui.tooltip_text("Changed settings are saved in the background, they are always saved when closing the menu");
+                                }
+
+                                let list_height = ui.content_region_avail()[1] - ui.frame_height_with_spacing() * 4.5;
                                 
                                 ui.child_window("ConfigList").border(true).size([0.0, list_height]).build(|| {
                                     for (i, name) in self.config_list.iter().enumerate() {
@@ -722,8 +1292,17 @@ impl SettingsUI {
                                     if let Some(index) = self.selected_config_index {
                                         let config_name = &self.config_list[index];
                                         match config_manager::load_config(config_name) {
-                                            Ok(new_settings) => *settings = new_settings,
-                                            Err(e) => log::error!("Failed to load config '{}': {}", config_name, e),
+                                            Ok(mut new_settings) => {
+                                                new_settings.keep_local_values(settings);
+                                                *settings = new_settings;
+                                                app.settings_theme_changed.store(true, Ordering::Relaxed);
+                                                app.settings_ui_scale_changed.store(true, Ordering::Relaxed);
+                                                app.settings_overlay_monitor_changed.store(true, Ordering::Relaxed);
+                                                app.settings_capture_output_changed.store(true, Ordering::Relaxed);
+                                                app.settings_metrics_changed.store(true, Ordering::Relaxed);
+                                                app.notify(NotificationLevel::Info, format!("Config '{}' loaded", config_name));
+                                            }
+                                            Err(e) => app.notify(NotificationLevel::Error, format!("Failed to load config '{}': {}", config_name, e)),
                                         }
                                     }
                                 }
@@ -735,21 +1314,22 @@ impl SettingsUI {
                                 if ui.button_with_size("Save", button_size) {
                                      let name_to_save = self.new_config_name.trim();
                                      match config_manager::save_config(name_to_save, &settings) {
-                                         Ok(_) => self.needs_config_refresh = true,
-                                         Err(e) => log::error!("Failed to save config '{}': {}", name_to_save, e),
+                                         Ok(_) => {
+                                             self.needs_config_refresh = true;
+                                             app.notify(NotificationLevel::Info, format!("Config '{}' saved", name_to_save));
+                                         }
+                                         Err(e) => app.notify(NotificationLevel::Error, format!("Failed to save config '{}': {}", name_to_save, e)),
                                      }
                                 }
                                 _disabled_save.end();
                                 
                                 ui.same_line_with_spacing(0.0, spacing);
                                 if ui.button_with_size("Import", button_size) {
-                                    let hwnd = unsafe { FindWindowA(None, s!("CS2 Overlay")) };
-                                    let mut dialog = FileDialog::new().add_filter("YAML Config", &["yaml", "yml"]);
-                                    if hwnd.0 != 0 { dialog = dialog.set_parent(&WindowHandle(hwnd)); }
+                                    let dialog = overlay_file_dialog().add_filter("YAML Config", &["yaml", "yml"]);
                                     if let Some(path) = dialog.pick_file() {
                                         match config_manager::import_config(&path) {
                                             Ok(_) => self.needs_config_refresh = true,
-                                            Err(e) => log::error!("Failed to import config: {}", e),
+                                            Err(e) => app.notify(NotificationLevel::Error, format!("Failed to import config: {}", e)),
                                         }
                                     }
                                 }
@@ -768,12 +1348,53 @@ impl SettingsUI {
                                         let config_name = &self.config_list[index];
                                         match config_manager::delete_config(config_name) {
                                             Ok(_) => self.needs_config_refresh = true,
-                                            Err(e) => log::error!("Failed to delete config '{}': {}", config_name, e),
+                                            Err(e) => app.notify(NotificationLevel::Error, format!("Failed to delete config '{}': {}", config_name, e)),
                                         }
                                     }
                                 }
                                 _red_button.pop();
                                 _disabled_delete.end();
+
+                                if ui.button("Copy as share code") {
+                                    match config_manager::encode_share_code(&settings) {
+                                        Ok(share_code) => {
+                                            ui.set_clipboard_text(share_code);
+                                            app.notify(NotificationLevel::Info, "Config share code copied to clipboard");
+                                        }
+                                        Err(e) => app.notify(NotificationLevel::Error, format!("Failed to create share code: {:#}", e)),
+                                    }
+                                }
+
+                                ui.same_line();
+                                if ui.button("Paste share code") {
+                                    let share_code = ui.clipboard_text().unwrap_or_default();
+                                    match config_manager::decode_share_code(&share_code) {
+                                        Ok(mut new_settings) => {
+                                            new_settings.imgui = settings.imgui.take();
+                                            new_settings.keep_local_values(settings);
+                                            *settings = new_settings;
+                                            app.settings_theme_changed.store(true, Ordering::Relaxed);
+                                            app.settings_ui_scale_changed.store(true, Ordering::Relaxed);
+                                            app.settings_overlay_monitor_changed.store(true, Ordering::Relaxed);
+                                            app.settings_capture_output_changed.store(true, Ordering::Relaxed);
+                                            app.settings_metrics_changed.store(true, Ordering::Relaxed);
+                                            app.notify(NotificationLevel::Info, "Config loaded from share code");
+                                        }
+                                        Err(e) => app.notify(NotificationLevel::Error, format!("Failed to load share code: {:#}", e)),
+                                    }
+                                }
+                            }
+                            ActiveTab::Theme => {
+                                ui.text("Theme");
+                                ui.separator();
+                                if self.render_theme_settings(app, ui, &mut settings.theme) {
+                                    app.settings_theme_changed.store(true, Ordering::Relaxed);
+                                }
+                            }
+                            ActiveTab::Privacy => {
+                                ui.text("Privacy");
+                                ui.separator();
+                                self.render_privacy_settings(app, ui, &mut *settings);
                             }
                             ActiveTab::Info => {
                                 let build_info = app.app_state.resolve::<StateBuildInfo>(()).ok();
@@ -786,6 +1407,12 @@ impl SettingsUI {
                                     build_info.as_ref().map_or("error", |info| &info.build_datetime)
                                 ));
 
+                                ui.dummy([0.0, 10.0]);
+                                self.render_driver_info(app, ui);
+
+                                ui.dummy([0.0, 10.0]);
+                                self.render_update_info(app, ui, &mut *settings);
+
                                 let ydummy = ui.window_size()[1] - ui.cursor_pos()[1] - ui.text_line_height_with_spacing() * 2.0 - 12.0;
                                 ui.dummy([0.0, ydummy]);
                                 ui.separator();
@@ -826,7 +1453,7 @@ impl SettingsUI {
         let speed = 10.0;
         let delta = ui.io().delta_time;
         
-        let is_truly_hovered = ui.is_item_hovered() && self.ui_alpha > 0.01;
+        let is_truly_hovered = (ui.is_item_hovered() || ui.is_item_focused()) && self.ui_alpha > 0.01;
         
         if is_truly_hovered {
             state.progress = (state.progress + delta * speed).min(1.0);
@@ -875,15 +1502,9 @@ impl SettingsUI {
             cursor_pos[1] + ui.frame_height() / 2.0 - 1.0,
         ];
         
-        let bounding_box_min = [center[0] - cog_size[0] / 2.0, center[1] - cog_size[1] / 2.0];
-        let bounding_box_max = [center[0] + cog_size[0] / 2.0, center[1] + cog_size[1] / 2.0];
-        
-        let is_truly_hovered = ui.is_mouse_hovering_rect(bounding_box_min, bounding_box_max) && self.ui_alpha > 0.01;
-
-        let mut toggle_clicked = false;
-        if is_truly_hovered && ui.is_mouse_clicked(imgui::MouseButton::Left) {
-            toggle_clicked = true;
-        }
+        /* a button allows the cog to be focused and toggled by keyboard or gamepad */
+        let toggle_clicked = ui.invisible_button(format!("##{}_cog", unique_id), [cog_size[0], ui.frame_height()]);
+        let is_truly_hovered = (ui.is_item_hovered() || ui.is_item_focused()) && self.ui_alpha > 0.01;
 
         let speed = 8.0;
         let delta = ui.io().delta_time;
@@ -918,8 +1539,6 @@ impl SettingsUI {
         draw_list.add_image_quad(texture_id, corners[0], corners[1], corners[2], corners[3])
             .col(color)
             .build();
-
-        ui.dummy(cog_size);
         
         if toggle_clicked {
              if let Some(pos) = self.open_dropdowns.iter().position(|x| x == unique_id) {
@@ -1008,23 +1627,44 @@ impl SettingsUI {
              if box_enabled && player_config.box_type == EspBoxType::None { player_config.box_type = EspBoxType::Box2D; } 
              else if !box_enabled { player_config.box_type = EspBoxType::None; }
         }
-        self.render_dropdown_section(ui, "box_settings", |_, ui| {
-            ui.combo_enum("Type", &[(EspBoxType::Box2D, "2D"), (EspBoxType::Box3D, "3D")], &mut player_config.box_type);
-            Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.box_color);
+        self.render_dropdown_section(ui, "box_settings", |this, ui| {
+            ui.combo_enum("Type", &[
+                (EspBoxType::Box2D, "2D"),
+                (EspBoxType::Box3D, "3D"),
+                (EspBoxType::Corner, "Corners"),
+                (EspBoxType::Filled, "Filled"),
+            ], &mut player_config.box_type);
+            this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.box_color);
+            match player_config.box_type {
+                EspBoxType::Corner => this.render_esp_settings_player_style_width(ui, "Corner Length", 0.05, 0.5, &mut player_config.box_corner_length),
+                EspBoxType::Filled => this.render_esp_settings_player_style_width(ui, "Fill Opacity", 0.0, 1.0, &mut player_config.box_fill_alpha),
+                _ => {}
+            }
+            if player_config.box_type.is_2d() {
+                this.animated_checkbox(ui, "Fit To Bones##box", &mut player_config.box_fit_bones);
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Size the box by the current pose instead of the static player hull");
+                }
+            }
         });
         
         // Skeleton
         self.render_setting_with_cog_toggle(app, ui, "Skeleton", &mut player_config.skeleton, "skel_settings");
-        self.render_dropdown_section(ui, "skel_settings", |_, ui| {
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.skeleton_color);
+        self.render_dropdown_section(ui, "skel_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.skeleton_color);
         });
 
         // Chams
         self.render_setting_with_cog_toggle(app, ui, "Chams", &mut player_config.chams, "chams_settings");
-        ui.same_line();
-        ui.text_disabled("(work in progress)");
-        self.render_dropdown_section(ui, "chams_settings", |_, ui| {
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.chams_color);
+        self.render_dropdown_section(ui, "chams_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.chams_color);
+             this.animated_checkbox(ui, "Occluded Color", &mut player_config.chams_visibility);
+             if ui.is_item_hovered() {
+                 ui.tooltip_text("Color body parts hidden behind walls differently (requires the map mesh)");
+             }
+             if player_config.chams_visibility {
+                 this.render_esp_settings_player_style_color(ui, "Occluded", &mut player_config.chams_occluded_color);
+             }
         });
 
         // Head Dot
@@ -1033,10 +1673,10 @@ impl SettingsUI {
             if head_dot_enabled && player_config.head_dot == EspHeadDot::None { player_config.head_dot = EspHeadDot::NotFilled; } 
             else if !head_dot_enabled { player_config.head_dot = EspHeadDot::None; }
         }
-        self.render_dropdown_section(ui, "head_settings", |_, ui| {
+        self.render_dropdown_section(ui, "head_settings", |this, ui| {
             ui.combo_enum("Type", &[(EspHeadDot::Filled, "Filled"), (EspHeadDot::NotFilled, "Outlined")], &mut player_config.head_dot);
-            Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.head_dot_color);
-            Self::render_esp_settings_player_style_width(ui, "Z-Offset", 0.0, 10.0, &mut player_config.head_dot_z);
+            this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.head_dot_color);
+            this.render_esp_settings_player_style_width(ui, "Z-Offset", 0.0, 10.0, &mut player_config.head_dot_z);
         });
 
         // Tracers
@@ -1045,9 +1685,9 @@ impl SettingsUI {
             if tracer_enabled && player_config.tracer_lines == EspTracePosition::None { player_config.tracer_lines = EspTracePosition::BottomCenter; } 
             else if !tracer_enabled { player_config.tracer_lines = EspTracePosition::None; }
         }
-        self.render_dropdown_section(ui, "trace_settings", |_, ui| {
+        self.render_dropdown_section(ui, "trace_settings", |this, ui| {
             ui.combo_enum("Position", &[ (EspTracePosition::TopLeft, "Top Left"), (EspTracePosition::TopCenter, "Top Center"), (EspTracePosition::TopRight, "Top Right"), (EspTracePosition::BottomLeft, "Bottom Left"), (EspTracePosition::BottomCenter, "Bottom Center"), (EspTracePosition::BottomRight, "Bottom Right")], &mut player_config.tracer_lines);
-            Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.tracer_lines_color);
+            this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.tracer_lines_color);
         });
 
         // Health Bar
@@ -1056,80 +1696,163 @@ impl SettingsUI {
             if health_bar_enabled && player_config.health_bar == EspHealthBar::None { player_config.health_bar = EspHealthBar::Left; } 
             else if !health_bar_enabled { player_config.health_bar = EspHealthBar::None; }
         }
-        self.render_dropdown_section(ui, "hp_settings", |_, ui| {
+        self.render_dropdown_section(ui, "hp_settings", |this, ui| {
              ui.combo_enum("Position", &[(EspHealthBar::Top, "Top"), (EspHealthBar::Left, "Left"), (EspHealthBar::Bottom, "Bottom"), (EspHealthBar::Right, "Right")], &mut player_config.health_bar);
              // ADDED WIDTH SLIDER HERE
-             Self::render_esp_settings_player_style_width(ui, "Width", 1.0, 10.0, &mut player_config.health_bar_width);
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_hp_text_color);
+             this.render_esp_settings_player_style_width(ui, "Width", 1.0, 10.0, &mut player_config.health_bar_width);
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_hp_text_color);
+        });
+
+        // Armor Bar
+        self.render_setting_with_cog_toggle(app, ui, "Armor Bar", &mut player_config.armor_bar, "armor_bar_settings");
+        self.render_dropdown_section(ui, "armor_bar_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.armor_bar_color);
+        });
+
+        // Flash Bar
+        self.render_setting_with_cog_toggle(app, ui, "Flash Bar", &mut player_config.flash_bar, "flash_bar_settings");
+        self.render_dropdown_section(ui, "flash_bar_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.flash_bar_color);
+             this.animated_checkbox(ui, "Countdown", &mut player_config.flash_bar_countdown);
+             this.animated_checkbox(ui, "Highlight partially blind", &mut player_config.flash_bar_highlight_partial);
+             if player_config.flash_bar_highlight_partial {
+                 this.render_esp_settings_player_style_color(ui, "Partial Color", &mut player_config.flash_bar_partial_color);
+             }
         });
 
         // Text Outline
         self.render_setting_with_cog_toggle(app, ui, "Text Outline", &mut player_config.text_outline_enabled, "outline_settings");
-        self.render_dropdown_section(ui, "outline_settings", |_, ui| {
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.text_outline_color);
+        self.render_dropdown_section(ui, "outline_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.text_outline_color);
         });
         
         // Info Group
         self.render_setting_with_cog_toggle(app, ui, "Name", &mut player_config.info_name, "name_settings");
-        self.render_dropdown_section(ui, "name_settings", |_, ui| {
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_name_color);
+        self.render_dropdown_section(ui, "name_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_name_color);
         });
 
         self.render_setting_with_cog_toggle(app, ui, "Weapon", &mut player_config.info_weapon, "wep_settings");
-        self.render_dropdown_section(ui, "wep_settings", |_, ui| {
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_weapon_color);
+        self.render_dropdown_section(ui, "wep_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_weapon_color);
         });
 
         self.render_setting_with_cog_toggle(app, ui, "Ammo", &mut player_config.info_ammo, "ammo_settings");
-        self.render_dropdown_section(ui, "ammo_settings", |_, ui| {
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_ammo_color);
+        self.render_dropdown_section(ui, "ammo_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_ammo_color);
+        });
+
+        self.render_setting_with_cog_toggle(app, ui, "Weapon State", &mut player_config.info_weapon_state, "weapon_state_settings");
+        self.render_dropdown_section(ui, "weapon_state_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_weapon_state_color);
         });
 
         self.render_setting_with_cog_toggle(app, ui, "Distance", &mut player_config.info_distance, "dist_settings");
-        self.render_dropdown_section(ui, "dist_settings", |_, ui| {
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_distance_color);
+        self.render_dropdown_section(ui, "dist_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_distance_color);
         });
 
         // Individual Flags
         self.render_setting_with_cog_toggle(app, ui, "Kit", &mut player_config.info_flag_kit, "kit_settings");
-        self.render_dropdown_section(ui, "kit_settings", |_, ui| {
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_flag_kit_color);
+        self.render_dropdown_section(ui, "kit_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_flag_kit_color);
+        });
+
+        self.render_setting_with_cog_toggle(app, ui, "HP+Kevlar", &mut player_config.info_flag_armor, "armor_flag_settings");
+        self.render_dropdown_section(ui, "armor_flag_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_flag_armor_color);
         });
 
         self.render_setting_with_cog_toggle(app, ui, "Scoped", &mut player_config.info_flag_scoped, "scoped_settings");
-        self.render_dropdown_section(ui, "scoped_settings", |_, ui| {
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_flag_scoped_color);
+        self.render_dropdown_section(ui, "scoped_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_flag_scoped_color);
         });
 
         self.render_setting_with_cog_toggle(app, ui, "Flashed", &mut player_config.info_flag_flashed, "flashed_settings");
-        self.render_dropdown_section(ui, "flashed_settings", |_, ui| {
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_flag_flashed_color);
+        self.render_dropdown_section(ui, "flashed_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_flag_flashed_color);
         });
 
         self.render_setting_with_cog_toggle(app, ui, "Bomb Carrier", &mut player_config.info_flag_bomb, "bomb_settings");
-        self.render_dropdown_section(ui, "bomb_settings", |_, ui| {
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_flag_bomb_color);
+        self.render_dropdown_section(ui, "bomb_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_flag_bomb_color);
         });
 
         // Grenades
         self.render_setting_with_cog_toggle(app, ui, "Grenades", &mut player_config.info_grenades, "nade_settings");
-        self.render_dropdown_section(ui, "nade_settings", |_, ui| {
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_grenades_color);
+        self.render_dropdown_section(ui, "nade_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_grenades_color);
+        });
+
+        self.animated_checkbox(ui, "Steam Avatar", &mut player_config.info_avatar);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Requires a Steam Web API key (Overlay > Team Overview)");
+        }
+
+        self.render_setting_with_cog_toggle(app, ui, "Rank", &mut player_config.info_rank, "rank_settings");
+        self.render_dropdown_section(ui, "rank_settings", |this, ui| {
+             ui.combo_enum("Style", &[(EspInfoStyle::Text, "Text"), (EspInfoStyle::Icon, "Icon")], &mut player_config.info_rank_style);
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_rank_color);
+        });
+
+        // Economy
+        self.render_setting_with_cog_toggle(app, ui, "Money", &mut player_config.info_money, "money_settings");
+        self.render_dropdown_section(ui, "money_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_money_color);
+        });
+
+        self.render_setting_with_cog_toggle(app, ui, "Buy Type", &mut player_config.info_buy_type, "buy_type_settings");
+        self.render_dropdown_section(ui, "buy_type_settings", |this, ui| {
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.info_buy_type_color);
         });
 
         // Offscreen Arrows
         self.render_setting_with_cog_toggle(app, ui, "Offscreen Arrows", &mut player_config.offscreen_arrows, "arrows_settings");
-        self.render_dropdown_section(ui, "arrows_settings", |_, ui| {
-             Self::render_esp_settings_player_style_width(ui, "Radius", 50.0, 800.0, &mut player_config.offscreen_arrows_radius);
-             Self::render_esp_settings_player_style_width(ui, "Size", 5.0, 40.0, &mut player_config.offscreen_arrows_size);
-             Self::render_esp_settings_player_style_color(ui, "Color", &mut player_config.offscreen_arrows_color);
+        self.render_dropdown_section(ui, "arrows_settings", |this, ui| {
+             this.render_esp_settings_player_style_width(ui, "Radius", 50.0, 800.0, &mut player_config.offscreen_arrows_radius);
+             this.render_esp_settings_player_style_width(ui, "Size", 5.0, 40.0, &mut player_config.offscreen_arrows_size);
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.offscreen_arrows_color);
+             ui.set_next_item_width(150.0 * this.ui_scale);
+             ui.combo_enum("Mode##offscreen_arrows", &[
+                 (EspOffscreenArrowMode::Side, "Closest (Side)"),
+                 (EspOffscreenArrowMode::Radial, "Radial"),
+             ], &mut player_config.offscreen_arrows_mode);
+             if player_config.offscreen_arrows_mode == EspOffscreenArrowMode::Radial {
+                 this.animated_checkbox(ui, "Follow Screen Aspect##offscreen_arrows", &mut player_config.offscreen_arrows_ellipse);
+                 this.animated_checkbox(ui, "Show Distance##offscreen_arrows", &mut player_config.offscreen_arrows_distance);
+             }
+        });
+
+        // Ghost Marker
+        self.render_setting_with_cog_toggle(app, ui, "Last Seen Marker", &mut player_config.ghost_marker, "ghost_marker_settings");
+        self.render_dropdown_section(ui, "ghost_marker_settings", |this, ui| {
+             this.render_esp_settings_player_style_width(ui, "Duration (s)", 1.0, 30.0, &mut player_config.ghost_marker_duration);
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.ghost_marker_color);
+        });
+
+        // Trail
+        self.render_setting_with_cog_toggle(app, ui, "Position Trail", &mut player_config.trail, "trail_settings");
+        self.render_dropdown_section(ui, "trail_settings", |this, ui| {
+             this.render_esp_settings_player_style_width(ui, "Duration (s)", 0.5, 10.0, &mut player_config.trail_duration);
+             this.render_esp_settings_player_style_width(ui, "Width", 0.5, 5.0, &mut player_config.trail_width);
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.trail_color);
+        });
+
+        // View Direction
+        self.render_setting_with_cog_toggle(app, ui, "View Direction", &mut player_config.view_direction, "view_direction_settings");
+        self.render_dropdown_section(ui, "view_direction_settings", |this, ui| {
+             this.render_esp_settings_player_style_width(ui, "Length", 10.0, 500.0, &mut player_config.view_direction_length);
+             this.render_esp_settings_player_style_width(ui, "Width", 0.5, 5.0, &mut player_config.view_direction_width);
+             this.render_esp_settings_player_style_color(ui, "Color", &mut player_config.view_direction_color);
         });
 
         // Near Only
         self.render_setting_with_cog_toggle(app, ui, "Near only", &mut player_config.near_players, "near_settings");
-        self.render_dropdown_section(ui, "near_settings", |_, ui| {
-             Self::render_esp_settings_player_style_width(ui, "Max Distance", 0.0, 50.0, &mut player_config.near_players_distance);
+        self.render_dropdown_section(ui, "near_settings", |this, ui| {
+             this.render_esp_settings_player_style_width(ui, "Max Distance", 0.0, 50.0, &mut player_config.near_players_distance);
         });
+
+        self.render_esp_settings_player_style_width(ui, "Text Scale", 0.5, 3.0, &mut player_config.text_scale);
     }
 
     // Helper for the standalone cog
@@ -1146,15 +1869,8 @@ impl SettingsUI {
             cursor_pos[1] + ui.frame_height() / 2.0 - 1.0,
          ];
          
-         let bounding_box_min = [center[0] - cog_size[0] / 2.0, center[1] - cog_size[1] / 2.0];
-         let bounding_box_max = [center[0] + cog_size[0] / 2.0, center[1] + cog_size[1] / 2.0];
-         
-         let is_truly_hovered = ui.is_mouse_hovering_rect(bounding_box_min, bounding_box_max) && self.ui_alpha > 0.01;
- 
-         let mut clicked = false;
-         if is_truly_hovered && ui.is_mouse_clicked(imgui::MouseButton::Left) {
-            clicked = true;
-         }
+         let clicked = ui.invisible_button(format!("##{}_cog", unique_id), [cog_size[0], ui.frame_height()]);
+         let is_truly_hovered = (ui.is_item_hovered() || ui.is_item_focused()) && self.ui_alpha > 0.01;
  
          let speed = 8.0;
          let delta = ui.io().delta_time;
@@ -1187,28 +1903,27 @@ impl SettingsUI {
          draw_list.add_image_quad(texture_id, corners[0], corners[1], corners[2], corners[3])
             .col(color)
             .build();
-         ui.dummy(cog_size);
          
          clicked
     }
     
-    fn render_esp_settings_player_style_width(ui: &imgui::Ui, label: &str, min: f32, max: f32, value: &mut f32) {
+    fn render_esp_settings_player_style_width(&self, ui: &imgui::Ui, label: &str, min: f32, max: f32, value: &mut f32) {
         ui.text(label);
         ui.same_line();
-        ui.set_next_item_width(200.0);
+        ui.set_next_item_width(200.0 * self.ui_scale);
         let _ = ui.slider_config(&format!("##{}_style_width", label), min, max).build(value);
     }
 
     // --- UPDATED: 2-COLUMN LAYOUT FOR COLOR SETTINGS ---
-    fn render_esp_settings_player_style_color(ui: &imgui::Ui, label: &str, color: &mut EspColor) {
+    fn render_esp_settings_player_style_color(&self, ui: &imgui::Ui, label: &str, color: &mut EspColor) {
         // Start columns with a border to create that "line to the right" effect
         ui.columns(2, format!("cols_{}", label), true); 
         
         // --- COLUMN 1: Type Selector ---
-        ui.set_current_column_width(130.0); // Fixed width for the type selector
+        ui.set_current_column_width(130.0 * self.ui_scale); // Fixed width for the type selector
         
         let mut color_type = EspColorType::from_esp_color(color);
-        ui.set_next_item_width(110.0); // Slightly smaller than column width
+        ui.set_next_item_width(110.0 * self.ui_scale); // Slightly smaller than column width
         
         let color_type_changed = ui.combo_enum(
             &format!("##{}_color_type", label),
@@ -1240,14 +1955,14 @@ impl SettingsUI {
         match color {
             EspColor::Static { value } => {
                 let mut color_value = value.as_f32();
-                ui.set_next_item_width(150.0);
+                ui.set_next_item_width(150.0 * self.ui_scale);
                 if ui.color_edit4_config(&format!("##{}_static_value", label), &mut color_value).alpha_bar(true).inputs(false).label(false).build() {
                     *value = Color::from_f32(color_value);
                 }
             }
             EspColor::HealthBasedRainbow { alpha } => {
                 ui.text("Alpha:");
-                ui.set_next_item_width(100.0);
+                ui.set_next_item_width(100.0 * self.ui_scale);
                 ui.slider_config(&format!("##{}_rainbow_alpha", label), 0.1, 1.0).display_format("%.2f").build(alpha);
             }
             EspColor::HealthBased { max, mid, min } => {
@@ -1288,7 +2003,7 @@ impl SettingsUI {
                 let mut e = end.as_f32();
                 if ui.color_edit4_config(&format!("##{}_pulse_end", label), &mut e).alpha_bar(true).inputs(false).label(false).build() { *end = Color::from_f32(e); }
                 
-                ui.set_next_item_width(80.0);
+                ui.set_next_item_width(80.0 * self.ui_scale);
                 ui.slider_config(&format!("##{}_pulse_speed", label), 0.1, 10.0).display_format("Spd: %.1f").build(speed);
             }
             EspColor::GradientVertical { ref mut top, ref mut bottom } => {
@@ -1306,38 +2021,994 @@ impl SettingsUI {
         ui.columns(1, format!("cols_{}_end", label), false);
     }
 
-    fn render_esp_settings(&mut self, app: &Application, settings: &mut AppSettings, ui: &imgui::Ui) {
-        ui.child_window("SettingsPanel")
-            .size([350.0, 0.0])
-            .build(|| {
-                ui.set_next_item_width(150.0);
-                ui.combo_enum("Editing Target", &[
-                    (PlayerTargetMode::Enemy, "Enemy"),
-                    (PlayerTargetMode::Friendly, "Friendly"),
-                ], &mut self.esp_player_target_mode);
-                ui.separator();
+    /// Key button of a hotkey binding with a warning if another binding uses the same key
+    fn render_hotkey_binding(&mut self, ui: &imgui::Ui, settings: &mut AppSettings, binding: HotkeyBinding, label: &str) {
+        let previous_key = binding.key(settings);
+        let mut key = previous_key;
+        let changed = match key.as_mut() {
+            Some(menu_key) if !binding.is_optional() => ui.button_key_ignore_mouse_left(label, menu_key, [150.0 * self.ui_scale, 0.0]),
+            _ => ui.button_key_optional(label, &mut key, [150.0 * self.ui_scale, 0.0]),
+        };
+        if let Some(description) = binding.description() {
+            if ui.is_item_hovered() {
+                ui.tooltip_text(description);
+            }
+        }
+        if changed {
+            binding.set_key(settings, key);
+            self.hotkey_previous_keys.insert(binding, previous_key);
+        }
 
-                let target_selector = match self.esp_player_target_mode {
-                    PlayerTargetMode::Friendly => EspSelector::PlayerTeam { enemy: false },
-                    PlayerTargetMode::Enemy => EspSelector::PlayerTeam { enemy: true },
-                };
-                
-                self.render_esp_settings_player(app, settings, ui, target_selector);
-            });
+        for conflict in binding.conflicts(settings) {
+            let _id = ui.push_id(conflict.display_name());
+            ui.indent();
+            ui.text_colored(
+                [1.0, 0.7, 0.2, 1.0],
+                format!("Also bound to {}", conflict.display_name()),
+            );
 
-        ui.same_line();
+            /* swapping gives the other binding the key this binding had before */
+            if let Some(Some(previous_key)) = self.hotkey_previous_keys.get(&binding).copied() {
+                ui.same_line();
+                if ui.small_button("Swap") {
+                    conflict.set_key(settings, Some(previous_key));
+                }
+            }
 
-        ui.child_window("PreviewPanel")
-            .size([0.0, 0.0])
-            .build(|| {
-                self.render_esp_preview(app, settings, ui);
-            });
+            if conflict.is_optional() {
+                ui.same_line();
+                if ui.small_button("Clear") {
+                    conflict.set_key(settings, None);
+                }
+            }
+            ui.unindent();
+        }
     }
 
-    fn render_esp_preview(
-        &mut self,
-        app: &Application,
-        settings: &mut AppSettings,
+    /// Returns true if the theme has been changed
+    fn render_theme_settings(&mut self, app: &Application, ui: &imgui::Ui, theme: &mut Theme) -> bool {
+        let mut changed = false;
+
+        let presets = builtin_themes();
+        ui.set_next_item_width(200.0 * self.ui_scale);
+        if let Some(_combo) = ui.begin_combo("Preset", &theme.name) {
+            for preset in presets.iter() {
+                if ui.selectable_config(&preset.name).selected(preset.name == theme.name).build() {
+                    *theme = preset.clone();
+                    changed = true;
+                }
+            }
+        }
+
+        ui.set_next_item_width(200.0 * self.ui_scale);
+        ui.input_text("Name##theme", &mut theme.name).build();
+
+        if ui.button("Export") {
+            let dialog = overlay_file_dialog()
+                .add_filter("YAML Theme", &["yaml", "yml"])
+                .set_file_name(format!("{}.yaml", theme.name));
+            if let Some(path) = dialog.save_file() {
+                match save_theme(&path, theme) {
+                    Ok(_) => app.notify(NotificationLevel::Info, format!("Exported theme to {}", path.display())),
+                    Err(e) => app.notify(NotificationLevel::Error, format!("Failed to export theme: {:#}", e)),
+                }
+            }
+        }
+
+        ui.same_line();
+        if ui.button("Import") {
+            let dialog = overlay_file_dialog().add_filter("YAML Theme", &["yaml", "yml"]);
+            if let Some(path) = dialog.pick_file() {
+                match load_theme(&path) {
+                    Ok(imported) => {
+                        *theme = imported;
+                        changed = true;
+                        app.notify(NotificationLevel::Info, format!("Theme '{}' imported", theme.name));
+                    }
+                    Err(e) => app.notify(NotificationLevel::Error, format!("Failed to import theme: {:#}", e)),
+                }
+            }
+        }
+
+        ui.same_line();
+        if ui.button("Reset") {
+            *theme = Theme::default();
+            changed = true;
+        }
+
+        ui.separator();
+        ui.child_window("ThemeColors").build(|| {
+            for style_color in StyleColor::VARIANTS {
+                let mut color = theme.color(style_color).unwrap_or_else(|| ui.style_color(style_color));
+                if ui.color_edit4_config(style_color_name(style_color), &mut color).alpha_bar(true).inputs(false).build() {
+                    theme.set_color(style_color, color);
+                    changed = true;
+                }
+            }
+        });
+
+        changed
+    }
+
+    fn render_log_viewer(&mut self, ui: &imgui::Ui) {
+        let entries = recent_log_entries()
+            .into_iter()
+            .filter(|entry| entry.level <= self.log_level_filter)
+            .collect::<Vec<_>>();
+
+        ui.set_next_item_width(150.0 * self.ui_scale);
+        ui.combo_enum("Level##log_viewer", LOG_LEVEL_OPTIONS, &mut self.log_level_filter);
+        ui.same_line();
+        let mut auto_scroll = self.log_auto_scroll;
+        self.animated_checkbox(ui, "Auto scroll", &mut auto_scroll);
+        self.log_auto_scroll = auto_scroll;
+        ui.same_line();
+        if ui.button("Copy to clipboard") {
+            let text = entries
+                .iter()
+                .map(|entry| format!("{} {:<5} [{}] {}", entry.time, entry.level, entry.target, entry.message))
+                .collect::<Vec<_>>()
+                .join("\n");
+            ui.set_clipboard_text(text);
+        }
+
+        if let Some(path) = current_log_file() {
+            ui.text_disabled(&format!("Log file: {}", path.display()));
+        }
+
+        ui.child_window("LogEntries").border(true).build(|| {
+            for entry in &entries {
+                let color = match entry.level {
+                    log::Level::Error => [1.0, 0.35, 0.35, 1.0],
+                    log::Level::Warn => [1.0, 0.7, 0.3, 1.0],
+                    log::Level::Info => [1.0, 1.0, 1.0, 1.0],
+                    log::Level::Debug | log::Level::Trace => [0.6, 0.6, 0.6, 1.0],
+                };
+
+                ui.text_disabled(&entry.time);
+                ui.same_line();
+                ui.text_colored(color, format!("{:<5}", entry.level));
+                ui.same_line();
+                ui.text_wrapped(&entry.message);
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(&entry.target);
+                }
+            }
+
+            if self.log_auto_scroll && ui.scroll_y() >= ui.scroll_max_y() - 1.0 {
+                ui.set_scroll_here_y_with_ratio(1.0);
+            }
+        });
+    }
+
+    fn render_privacy_settings(&mut self, app: &Application, ui: &imgui::Ui, settings: &mut AppSettings) {
+        let mut metrics_enabled = settings.metrics;
+        if self.animated_checkbox(ui, "Send metrics", &mut metrics_enabled) {
+            if metrics_enabled {
+                /* the metrics are enabled from the preview, after the user has seen what is sent */
+                self.metrics_preview_open = true;
+            } else {
+                settings.metrics = false;
+                app.settings_metrics_changed.store(true, Ordering::Relaxed);
+            }
+        }
+
+        ui.set_next_item_width(150.0 * self.ui_scale);
+        if ui.combo_enum("Destination##metrics", METRICS_DESTINATION_OPTIONS, &mut settings.metrics_destination) {
+            app.settings_metrics_changed.store(true, Ordering::Relaxed);
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Local file keeps the records in LABHConfig/metrics instead of sending them");
+        }
+
+        ui.text("Categories");
+        ui.indent();
+        let categories = &mut settings.metrics_categories;
+        let mut changed = false;
+        changed |= self.animated_checkbox(ui, MetricsCategory::SettingsSnapshots.display_name(), &mut categories.settings_snapshots);
+        changed |= self.animated_checkbox(ui, MetricsCategory::FeatureToggles.display_name(), &mut categories.feature_toggles);
+        changed |= self.animated_checkbox(ui, MetricsCategory::VersionInfo.display_name(), &mut categories.version_info);
+        if changed {
+            app.settings_metrics_changed.store(true, Ordering::Relaxed);
+        }
+        ui.unindent();
+
+        if ui.button("View what would be sent") {
+            self.metrics_preview_open = true;
+        }
+
+        ui.dummy([0.0, 5.0]);
+        ui.text("Record types");
+        if let Some(_table) = ui.begin_table_with_flags(
+            "metrics_record_types",
+            4,
+            TableFlags::BORDERS | TableFlags::ROW_BG | TableFlags::SIZING_STRETCH_PROP,
+        ) {
+            for column in ["Record", "Category", "Content", "Sent"] {
+                ui.table_setup_column(column);
+            }
+            ui.table_headers_row();
+
+            for record_type in METRICS_RECORD_TYPES {
+                let sent = settings.metrics
+                    && settings.metrics_destination != MetricsDestination::Disabled
+                    && settings.metrics_categories.allows(record_type.category);
+
+                ui.table_next_row();
+                ui.table_next_column();
+                ui.text(record_type.name);
+                ui.table_next_column();
+                ui.text(record_type.category.display_name());
+                ui.table_next_column();
+                ui.text_wrapped(record_type.description);
+                ui.table_next_column();
+                ui.text(if sent { "Yes" } else { "No" });
+            }
+        }
+
+        self.render_metrics_preview(app, ui, settings);
+    }
+
+    fn render_metrics_preview(&mut self, app: &Application, ui: &imgui::Ui, settings: &mut AppSettings) {
+        if !self.metrics_preview_open {
+            return;
+        }
+
+        let mut open = true;
+        ui.window("Metrics preview")
+            .opened(&mut open)
+            .size([600.0, 400.0], Condition::FirstUseEver)
+            .build(|| {
+                ui.text_wrapped("Records of this session. Records of disabled categories are never sent.");
+                if !settings.metrics {
+                    if ui.button("Enable metrics") {
+                        settings.metrics = true;
+                        app.settings_metrics_changed.store(true, Ordering::Relaxed);
+                    }
+                    ui.same_line();
+                    ui.text_disabled("Metrics are currently disabled");
+                }
+
+                if ui.collapsing_header("Current settings snapshot", TreeNodeFlags::empty()) {
+                    let snapshot = serde_json::to_string_pretty(&settings.metrics_snapshot()).unwrap_or_default();
+                    ui.child_window("metrics_settings_snapshot")
+                        .size([0.0, 150.0])
+                        .border(true)
+                        .build(|| ui.text(&snapshot));
+                }
+
+                ui.child_window("metrics_recent_records").border(true).build(|| {
+                    for record in app.metrics.recent_records().iter().rev() {
+                        ui.text_disabled(&record.time);
+                        ui.same_line();
+                        if record.allowed {
+                            ui.text(&record.record_type);
+                        } else {
+                            ui.text_colored([0.6, 0.6, 0.6, 1.0], format!("{} (not sent)", record.record_type));
+                        }
+                        ui.indent();
+                        ui.text_wrapped(&record.payload);
+                        ui.unindent();
+                    }
+                });
+            });
+
+        self.metrics_preview_open = open;
+    }
+
+    fn render_match_statistics(&mut self, app: &Application, ui: &imgui::Ui, clip_safe_mode: bool) {
+        let Ok(mut statistics) = app.app_state.resolve_mut::<StateMatchStatistics>(()) else {
+            return;
+        };
+
+        if ui.button("Export JSON") {
+            match statistics.export_json() {
+                Ok(path) => app.notify(NotificationLevel::Info, format!("Exported match statistics to {}", path.display())),
+                Err(e) => app.notify(NotificationLevel::Error, format!("Failed to export match statistics: {:#}", e)),
+            }
+        }
+        ui.same_line();
+        if ui.button("Export CSV") {
+            match statistics.export_csv() {
+                Ok(path) => app.notify(NotificationLevel::Info, format!("Exported match statistics to {}", path.display())),
+                Err(e) => app.notify(NotificationLevel::Error, format!("Failed to export match statistics: {:#}", e)),
+            }
+        }
+        ui.same_line();
+        if ui.button("Reset") {
+            statistics.reset();
+        }
+
+        ui.text_disabled(&format!(
+            "{} rounds recorded. Shots, hits and accuracy are estimated from observed ammo and damage changes.",
+            statistics.rounds.len()
+        ));
+
+        let Some(_table) = ui.begin_table_with_flags(
+            "match_statistics",
+            9,
+            TableFlags::BORDERS | TableFlags::ROW_BG | TableFlags::SIZING_STRETCH_PROP,
+        ) else {
+            return;
+        };
+
+        for column in ["Player", "K", "D", "A", "DMG", "HS %", "Plants", "Shots", "Acc %"] {
+            ui.table_setup_column(column);
+        }
+        ui.table_headers_row();
+
+        let mut players = statistics.players.values().collect::<Vec<_>>();
+        players.sort_by_key(|stats| (stats.team_id, -stats.kills));
+        for stats in players {
+            let format_percentage = |value: Option<f32>| {
+                value
+                    .map(|value| format!("{:.1}", value))
+                    .unwrap_or_else(|| "-".to_string())
+            };
+
+            ui.table_next_row();
+            for value in [
+                display_player_name_with_mode(&app.app_state, clip_safe_mode, &stats.name),
+                stats.kills.to_string(),
+                stats.deaths.to_string(),
+                stats.assists.to_string(),
+                stats.damage.to_string(),
+                format_percentage(stats.headshot_percentage()),
+                stats.bomb_plants.to_string(),
+                stats.shots_fired.to_string(),
+                format_percentage(stats.accuracy()),
+            ] {
+                ui.table_next_column();
+                ui.text(&value);
+            }
+        }
+    }
+
+    fn render_update_info(&mut self, app: &Application, ui: &imgui::Ui, settings: &mut AppSettings) {
+        ui.text("Updates");
+        ui.separator();
+        self.animated_checkbox(ui, "Check for updates on startup", &mut settings.update_check);
+
+        let status = app.updater.status();
+        match status {
+            UpdateStatus::Idle => ui.text("Not checked yet"),
+            UpdateStatus::Checking => ui.text("Checking for updates..."),
+            UpdateStatus::UpToDate => ui.text("LABH is up to date"),
+            UpdateStatus::Available(release) => {
+                ui.text_colored([0.4, 1.0, 0.4, 1.0], &format!("LABH v{} is available", release.version));
+                if ui.button("Release Notes") {
+                    open_url(&release.page_url);
+                }
+
+                if !release.installable() {
+                    ui.same_line();
+                    ui.text_disabled("Download the release manually");
+                } else if self.update_install_confirm {
+                    ui.text_wrapped(&format!(
+                        "Replace the running executable with v{}? The download is only installed if its signature is valid.",
+                        release.version
+                    ));
+                    if ui.button("Install") {
+                        self.update_install_confirm = false;
+                        app.update_install_requested.store(true, Ordering::Relaxed);
+                    }
+                    ui.same_line();
+                    if ui.button("Cancel") {
+                        self.update_install_confirm = false;
+                    }
+                } else {
+                    ui.same_line();
+                    if ui.button("Download and Install") {
+                        self.update_install_confirm = true;
+                    }
+                }
+            }
+            UpdateStatus::Installing(release) => ui.text(&format!("Installing v{}...", release.version)),
+            UpdateStatus::Installed(version) => ui.text_colored(
+                [0.4, 1.0, 0.4, 1.0],
+                &format!("v{} installed. Restart LABH to apply the update.", version),
+            ),
+            UpdateStatus::Failed(err) => ui.text_colored([1.0, 0.4, 0.4, 1.0], &format!("Update failed: {}", err)),
+        }
+
+        if matches!(status, UpdateStatus::Idle | UpdateStatus::UpToDate | UpdateStatus::Failed(_))
+            && ui.button("Check Now")
+        {
+            app.update_check_requested.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn render_driver_info(&mut self, app: &Application, ui: &imgui::Ui) {
+        let Ok(cs2) = app.app_state.resolve::<StateCS2Handle>(()) else {
+            ui.text("Driver: not connected");
+            return;
+        };
+
+        let driver_info = cs2.driver_info();
+        let read_statistics = cs2.read_statistics();
+
+        ui.text("Driver");
+        ui.separator();
+        ui.text(&format!("Interface: {} (v{})", driver_info.application_name, driver_info.version));
+        ui.text_wrapped(&format!("Features: {}", driver_info.features));
+        ui.text(&format!(
+            "Reads: {} ({} failed)",
+            read_statistics.read_count, read_statistics.read_errors
+        ));
+        ui.text(&format!("Rejected implausible values: {}", read_statistics.rejected_values));
+        ui.text(&format!(
+            "Read latency: {:.1}us avg, {:.1}us max",
+            read_statistics.average_latency.as_secs_f64() * 1_000_000.0,
+            read_statistics.max_latency.as_secs_f64() * 1_000_000.0
+        ));
+
+        if ui.button("Run Self Test") {
+            self.driver_self_test = Some(cs2.self_test().map_err(|err| format!("{:#}", err)));
+        }
+        ui.same_line();
+        if ui.button("Reset Statistics") {
+            cs2.reset_read_statistics();
+        }
+
+        match &self.driver_self_test {
+            Some(Ok(elapsed)) => ui.text_colored(
+                [0.4, 1.0, 0.4, 1.0],
+                &format!("Self test passed ({:.1}us)", elapsed.as_secs_f64() * 1_000_000.0),
+            ),
+            Some(Err(err)) => ui.text_colored([1.0, 0.4, 0.4, 1.0], &format!("Self test failed: {}", err)),
+            None => {}
+        }
+    }
+
+    fn render_custom_crosshair_settings(&mut self, app: &Application, crosshair: &mut CrosshairSettings, ui: &imgui::Ui) {
+        ui.group(|| {
+            ui.set_next_item_width(200.0 * self.ui_scale);
+            ui.slider_config("Length##custom_crosshair", 0.0, 20.0).display_format("%.1f").build(&mut crosshair.size);
+            ui.set_next_item_width(200.0 * self.ui_scale);
+            ui.slider_config("Thickness##custom_crosshair", 0.0, 6.0).display_format("%.1f").build(&mut crosshair.thickness);
+            ui.set_next_item_width(200.0 * self.ui_scale);
+            ui.slider_config("Gap##custom_crosshair", -10.0, 10.0).display_format("%.1f").build(&mut crosshair.gap);
+            ui.set_next_item_width(200.0 * self.ui_scale);
+            ui.slider_config("Outline Thickness##custom_crosshair", 0.0, 3.0).display_format("%.1f").build(&mut crosshair.outline_thickness);
+
+            self.animated_checkbox(ui, "Outline##custom_crosshair", &mut crosshair.outline);
+            ui.same_line();
+            self.animated_checkbox(ui, "Dot##custom_crosshair", &mut crosshair.dot);
+            ui.same_line();
+            self.animated_checkbox(ui, "T-Style##custom_crosshair", &mut crosshair.t_style);
+
+            let mut color = crosshair.color.as_f32();
+            if ui.color_edit4_config("Color##custom_crosshair", &mut color).alpha_bar(true).inputs(false).build() {
+                crosshair.color = Color::from_f32(color);
+            }
+
+            ui.set_next_item_width(250.0 * self.ui_scale);
+            ui.input_text("##crosshair_share_code", &mut self.crosshair_share_code)
+                .hint("CSGO-XXXXX-XXXXX-XXXXX-XXXXX-XXXXX")
+                .build();
+            ui.same_line();
+            if ui.button("Import Share Code") {
+                match decode_crosshair_share_code(&self.crosshair_share_code) {
+                    Ok(imported) => {
+                        *crosshair = imported;
+                        self.crosshair_share_code.clear();
+                        app.notify(NotificationLevel::Info, "Crosshair imported");
+                    }
+                    Err(e) => app.notify(NotificationLevel::Error, format!("Failed to import crosshair: {:#}", e)),
+                }
+            }
+        });
+
+        ui.same_line();
+        ui.child_window("CrosshairPreview")
+            .size([120.0, 120.0])
+            .border(true)
+            .build(|| {
+                let draw_list = ui.get_window_draw_list();
+                let [x, y] = ui.window_pos();
+                let [width, height] = ui.window_size();
+                draw_list
+                    .add_rect([x, y], [x + width, y + height], [0.25, 0.27, 0.3, 1.0])
+                    .filled(true)
+                    .build();
+                draw_crosshair(&draw_list, [x + width / 2.0, y + height / 2.0], ui.io().display_size[1], crosshair);
+            });
+    }
+
+    fn render_grenade_helper_spots(&mut self, app: &Application, settings: &mut AppSettings, ui: &imgui::Ui) {
+        self.render_hotkey_binding(ui, settings, HotkeyBinding::GrenadeCapture, "Capture Lineup Key");
+        let grenade_settings = &mut settings.grenade_helper;
+        self.animated_checkbox(ui, "Alignment Guidance", &mut grenade_settings.alignment_guidance);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Shows the way to the closest lineup and how far your view is off");
+        }
+
+        if self.grenade_helper_map.is_none() {
+            self.grenade_helper_map = app
+                .app_state
+                .resolve::<StateCurrentMap>(())
+                .ok()
+                .and_then(|map| map.current_map.clone());
+        }
+
+        let mut maps = grenade_settings.map_spots.keys().cloned().collect::<Vec<_>>();
+        if let Some(current_map) = &self.grenade_helper_map {
+            if !maps.contains(current_map) {
+                maps.push(current_map.clone());
+            }
+        }
+        maps.sort();
+
+        if ui.button("Import Pack") {
+            let dialog = overlay_file_dialog().add_filter("Lineup Pack", &["json", "yaml", "yml"]);
+            if let Some(path) = dialog.pick_file() {
+                match load_grenade_pack(&path) {
+                    Ok(pack) => {
+                        let spots = grenade_settings.map_spots.entry(pack.map.clone()).or_default();
+                        let result = merge_grenade_pack(spots, pack.spots, self.grenade_pack_conflict);
+                        app.notify(
+                            NotificationLevel::Info,
+                            format!(
+                                "Imported {} for {}: {} added, {} replaced, {} skipped",
+                                pack.metadata.name, pack.map, result.added, result.replaced, result.skipped
+                            ),
+                        );
+
+                        self.grenade_helper_map = Some(pack.map);
+                        self.grenade_helper_selected_spot = None;
+                        return;
+                    }
+                    Err(e) => app.notify(NotificationLevel::Error, format!("Failed to import lineup pack: {:#}", e)),
+                }
+            }
+        }
+        ui.same_line();
+        ui.set_next_item_width(120.0 * self.ui_scale);
+        ui.combo_enum("On Conflict##grenade_pack", &[
+            (GrenadePackConflict::Skip, "Skip"),
+            (GrenadePackConflict::Replace, "Replace"),
+            (GrenadePackConflict::KeepBoth, "Keep Both"),
+        ], &mut self.grenade_pack_conflict);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Lineups with the same name or position are considered conflicts");
+        }
+
+        if maps.is_empty() {
+            ui.text_disabled("No grenade spots available");
+            return;
+        }
+
+        let mut map_index = self
+            .grenade_helper_map
+            .as_ref()
+            .and_then(|map| maps.iter().position(|value| value == map))
+            .unwrap_or(0);
+        ui.set_next_item_width(150.0 * self.ui_scale);
+        if ui.combo("Map##grenade_helper", &mut map_index, &maps, |m| m.to_string().into()) {
+            self.grenade_helper_selected_spot = None;
+        }
+        let map_name = &maps[map_index];
+        if self.grenade_helper_map.as_ref() != Some(map_name) {
+            self.grenade_helper_map = Some(map_name.clone());
+        }
+
+        /* maps get an entry once a spot is added, only viewing a map must not change the settings */
+        let spots = grenade_settings.map_spots.get(map_name).map(Vec::as_slice).unwrap_or_default();
+
+        ui.same_line();
+        let _disabled_export = ui.begin_disabled(spots.is_empty());
+        if ui.button("Export Pack") {
+            let dialog = overlay_file_dialog()
+                .add_filter("JSON Lineup Pack", &["json"])
+                .add_filter("YAML Lineup Pack", &["yaml", "yml"])
+                .set_file_name(format!("{}_lineups.json", map_name));
+            if let Some(path) = dialog.save_file() {
+                let pack = GrenadePack::new(
+                    map_name.clone(),
+                    GrenadePackMetadata {
+                        name: format!("{} lineups", map_name),
+                        author: self.grenade_pack_author.clone(),
+                        description: String::new(),
+                        created: chrono::Local::now().to_rfc3339(),
+                    },
+                    spots.to_vec(),
+                );
+                match save_grenade_pack(&path, &pack) {
+                    Ok(_) => app.notify(NotificationLevel::Info, format!("Exported {} lineups for {}", pack.spots.len(), map_name)),
+                    Err(e) => app.notify(NotificationLevel::Error, format!("Failed to export lineup pack: {:#}", e)),
+                }
+            }
+        }
+        _disabled_export.end();
+        ui.set_next_item_width(150.0 * self.ui_scale);
+        ui.input_text("Pack Author##grenade_pack", &mut self.grenade_pack_author).hint("Optional").build();
+
+        {
+            let mut sorted_spots = spots.iter().collect::<Vec<_>>();
+            grenade_settings.ui_sort_order.sort(&mut sorted_spots);
+
+            ui.child_window("GrenadeSpotList").border(true).size([0.0, 120.0]).build(|| {
+                if sorted_spots.is_empty() {
+                    ui.text_disabled("No spots for this map");
+                }
+
+                for spot in sorted_spots {
+                    let is_selected = self.grenade_helper_selected_spot == Some(spot.id);
+                    let label = format!("{}##grenade_spot_{}", spot.name, spot.id);
+                    if ui.selectable_config(label).selected(is_selected).build() {
+                        self.grenade_helper_selected_spot = Some(spot.id);
+                    }
+                }
+            });
+        }
+
+        if ui.button("Add Spot") {
+            let spots = grenade_settings.map_spots.entry(map_name.clone()).or_default();
+            let mut spot = GrenadeSpotInfo {
+                id: GrenadeSpotInfo::new_id(),
+                name: format!("Spot {}", spots.len() + 1),
+                ..Default::default()
+            };
+            if let Ok(location) = app.app_state.resolve::<StateGrenadeHelperPlayerLocation>(()) {
+                if let StateGrenadeHelperPlayerLocation::Valid { eye_position, eye_direction } = &*location {
+                    spot.eye_position = [eye_position.x, eye_position.y, eye_position.z];
+                    spot.eye_direction = [eye_direction.x, eye_direction.y];
+                }
+            }
+
+            self.grenade_helper_selected_spot = Some(spot.id);
+            spots.push(spot);
+        }
+
+        let Some(spots) = grenade_settings.map_spots.get_mut(map_name) else {
+            return;
+        };
+        let Some(spot_index) = self
+            .grenade_helper_selected_spot
+            .and_then(|id| spots.iter().position(|spot| spot.id == id))
+        else {
+            return;
+        };
+
+        ui.separator();
+        let spot = &mut spots[spot_index];
+
+        ui.input_text("Name##grenade_spot", &mut spot.name).build();
+        ui.input_text_multiline("Description##grenade_spot", &mut spot.description, [0.0, 60.0]).build();
+
+        ui.text("Tags");
+        let mut removed_tag = None;
+        for (index, tag) in spot.tags.iter().enumerate() {
+            ui.same_line();
+            if ui.small_button(format!("{} x##grenade_spot_tag_{}", tag, index)) {
+                removed_tag = Some(index);
+            }
+        }
+        if let Some(index) = removed_tag {
+            spot.tags.remove(index);
+        }
+
+        ui.set_next_item_width(150.0 * self.ui_scale);
+        let tag_entered = ui
+            .input_text("##grenade_spot_new_tag", &mut self.grenade_helper_new_tag)
+            .hint("New tag")
+            .enter_returns_true(true)
+            .build();
+        ui.same_line();
+        if ui.button("Add Tag") || tag_entered {
+            let tag = self.grenade_helper_new_tag.trim();
+            if !tag.is_empty() && !spot.tags.iter().any(|value| value == tag) {
+                spot.tags.push(tag.to_string());
+            }
+            self.grenade_helper_new_tag.clear();
+        }
+
+        ui.set_next_item_width(150.0 * self.ui_scale);
+        ui.combo_enum("Throw##grenade_spot", &GrenadeThrowTechnique::ALL, &mut spot.throw_technique);
+
+        ui.text("Grenade Types (none = all)");
+        for grenade_type in [
+            GrenadeType::Smoke,
+            GrenadeType::Molotov,
+            GrenadeType::Flashbang,
+            GrenadeType::Explosive,
+        ] {
+            let mut enabled = spot.grenade_types.contains(&grenade_type);
+            ui.same_line();
+            if ui.checkbox(format!("{}##grenade_spot_type", grenade_type.display_name()), &mut enabled) {
+                if enabled {
+                    spot.grenade_types.push(grenade_type);
+                } else {
+                    spot.grenade_types.retain(|value| *value != grenade_type);
+                }
+            }
+        }
+
+        ui.set_next_item_width(250.0 * self.ui_scale);
+        imgui::Drag::new("Position").speed(0.5).display_format("%.2f").build_array(ui, &mut spot.eye_position);
+        ui.set_next_item_width(250.0 * self.ui_scale);
+        imgui::Drag::new("Angles").speed(0.05).display_format("%.2f").build_array(ui, &mut spot.eye_direction);
+
+        if ui.button("Use Current Position") {
+            if let Ok(location) = app.app_state.resolve::<StateGrenadeHelperPlayerLocation>(()) {
+                if let StateGrenadeHelperPlayerLocation::Valid { eye_position, eye_direction } = &*location {
+                    spot.eye_position = [eye_position.x, eye_position.y, eye_position.z];
+                    spot.eye_direction = [eye_direction.x, eye_direction.y];
+                }
+            }
+        }
+
+        ui.same_line();
+        let _delete_color = ui.push_style_color(StyleColor::Button, [0.6, 0.15, 0.15, 1.0]);
+        if ui.button("Delete Spot") {
+            spots.remove(spot_index);
+            self.grenade_helper_selected_spot = None;
+        }
+    }
+
+    fn render_esp_settings(&mut self, app: &Application, settings: &mut AppSettings, ui: &imgui::Ui) {
+        ui.child_window("SettingsPanel")
+            .size([350.0 * self.ui_scale, 0.0])
+            .build(|| {
+                ui.child_window("EspTargets")
+                    .size([0.0, 160.0 * self.ui_scale])
+                    .border(true)
+                    .build(|| {
+                        for selector in EspSelector::roots() {
+                            self.render_esp_selector_tree(settings, ui, selector);
+                        }
+                    });
+                self.render_esp_map_palette(app, settings, ui);
+                self.render_esp_settings_player_style_width(ui, "Max Distance (m)", 0.0, 200.0, &mut settings.esp_max_distance);
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Players further away are not drawn, 0 disables the limit");
+                }
+                if settings.esp_max_distance > 0.0 {
+                    self.render_esp_settings_player_style_width(ui, "Fade Distance (m)", 0.0, 50.0, &mut settings.esp_fade_distance);
+                }
+                self.render_esp_ignored_players(settings, ui);
+                ui.separator();
+
+                self.render_esp_selector_settings(app, settings, ui);
+            });
+
+        ui.same_line();
+
+        ui.child_window("PreviewPanel")
+            .size([0.0, 0.0])
+            .build(|| {
+                self.render_esp_preview(app, settings, ui);
+            });
+    }
+
+    /// Tree node of a selector with a checkbox to enable its config
+    fn render_esp_selector_tree(&mut self, settings: &mut AppSettings, ui: &imgui::Ui, selector: EspSelector) {
+        let config_key = selector.config_key();
+
+        let mut enabled = settings.esp_settings_enabled.get(&config_key).copied().unwrap_or_default();
+        if ui.checkbox(format!("##esp_enabled_{}", config_key), &mut enabled) {
+            settings.esp_settings_enabled.insert(config_key.clone(), enabled);
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text(selector.config_title());
+        }
+        ui.same_line();
+
+        let children = selector.children();
+        let label = if settings.esp_settings.contains_key(&config_key) {
+            format!("{} *##esp_node_{}", selector.config_display(), config_key)
+        } else {
+            format!("{}##esp_node_{}", selector.config_display(), config_key)
+        };
+        let node = ui
+            .tree_node_config(label)
+            .open_on_arrow(true)
+            .leaf(children.is_empty())
+            .selected(self.esp_selected_target == selector)
+            .push();
+        if ui.is_item_clicked() {
+            self.esp_selected_target = selector;
+        }
+
+        if let Some(_node) = node {
+            for child in children {
+                self.render_esp_selector_tree(settings, ui, child);
+            }
+        }
+    }
+
+    /// Show which config of the inheritance chain is used for the selected target
+    fn render_esp_inheritance_chain(settings: &AppSettings, ui: &imgui::Ui, target: EspSelector) {
+        let chain = target.inheritance_chain();
+        let effective = chain.iter().position(|selector| {
+            let config_key = selector.config_key();
+            settings.esp_settings_enabled.get(&config_key).copied().unwrap_or_default()
+                && settings.esp_settings.contains_key(&config_key)
+        });
+
+        ui.text_disabled("Resolves:");
+        for (index, selector) in chain.iter().enumerate() {
+            ui.same_line();
+            if index > 0 {
+                ui.text_disabled(">");
+                ui.same_line();
+            }
+            if Some(index) == effective {
+                ui.text_colored([0.4, 0.85, 0.4, 1.0], selector.config_display());
+            } else {
+                ui.text_disabled(selector.config_display());
+            }
+        }
+
+        if effective.is_none() {
+            ui.text_colored([1.0, 0.6, 0.2, 1.0], "No enabled config, nothing will be drawn");
+        }
+    }
+
+    fn render_esp_selector_settings(&mut self, app: &Application, settings: &mut AppSettings, ui: &imgui::Ui) {
+        let target = self.esp_selected_target;
+        let config_key = target.config_key();
+
+        ui.text(target.config_title());
+        Self::render_esp_inheritance_chain(settings, ui, target);
+
+        if !settings.esp_settings.contains_key(&config_key) {
+            ui.text_disabled("Uses the config of its parent");
+            if ui.button("Override") {
+                let config = match target {
+                    EspSelector::Chicken => EspConfig::Chicken(EspChickenSettings::default()),
+                    EspSelector::Weapon | EspSelector::WeaponGroup { .. } | EspSelector::WeaponSingle { .. } => {
+                        EspConfig::Weapon(EspWeaponSettings::default())
+                    }
+                    _ => EspConfig::Player(EspPlayerSettings::new(&target)),
+                };
+                settings.esp_settings.insert(config_key.clone(), config);
+                settings.esp_settings_enabled.insert(config_key, true);
+            }
+            return;
+        }
+
+        if ui.small_button("Remove Override") {
+            settings.esp_settings.remove(&config_key);
+            settings.esp_settings_enabled.remove(&config_key);
+            return;
+        }
+        ui.separator();
+
+        match target {
+            EspSelector::None => {}
+            EspSelector::Player | EspSelector::PlayerTeam { .. } | EspSelector::PlayerTeamVisibility { .. } => {
+                self.render_esp_settings_player(app, settings, ui, target)
+            }
+            EspSelector::Chicken => self.render_esp_settings_chicken(settings, ui, target),
+            EspSelector::Weapon | EspSelector::WeaponGroup { .. } | EspSelector::WeaponSingle { .. } => {
+                self.render_esp_settings_weapon(settings, ui, target)
+            }
+        }
+    }
+
+    fn render_esp_settings_chicken(&mut self, settings: &mut AppSettings, ui: &imgui::Ui, target: EspSelector) {
+        let Some(EspConfig::Chicken(config)) = settings.esp_settings.get_mut(&target.config_key()) else {
+            return;
+        };
+
+        ui.text_disabled("Chicken ESP is not drawn yet, the config is stored only");
+        ui.set_next_item_width(150.0 * self.ui_scale);
+        ui.combo_enum("Box##chicken", &[
+            (EspBoxType::None, "None"),
+            (EspBoxType::Box2D, "2D"),
+            (EspBoxType::Box3D, "3D"),
+        ], &mut config.box_type);
+        self.render_esp_settings_player_style_color(ui, "Box Color", &mut config.box_color);
+        self.animated_checkbox(ui, "Skeleton##chicken", &mut config.skeleton);
+        self.render_esp_settings_player_style_color(ui, "Skeleton Color", &mut config.skeleton_color);
+        self.animated_checkbox(ui, "Owner##chicken", &mut config.info_owner);
+        self.render_esp_settings_player_style_color(ui, "Owner Color", &mut config.info_owner_color);
+    }
+
+    fn render_esp_settings_weapon(&mut self, settings: &mut AppSettings, ui: &imgui::Ui, target: EspSelector) {
+        let Some(EspConfig::Weapon(config)) = settings.esp_settings.get_mut(&target.config_key()) else {
+            return;
+        };
+
+        ui.text_disabled("Weapon ESP is not drawn yet, the config is stored only");
+        self.animated_checkbox(ui, "Box##weapon", &mut config.draw_box);
+        self.render_esp_settings_player_style_color(ui, "Box Color", &mut config.draw_box_color);
+        self.animated_checkbox(ui, "Name##weapon", &mut config.info_name);
+        self.render_esp_settings_player_style_color(ui, "Name Color", &mut config.info_name_color);
+    }
+
+    fn render_esp_ignored_players(&mut self, settings: &mut AppSettings, ui: &imgui::Ui) {
+        if settings.esp_ignored_players.is_empty() {
+            ui.text_disabled("Right click players in the team overview to hide them");
+            return;
+        }
+
+        ui.text(format!("Hidden Players ({})", settings.esp_ignored_players.len()));
+        let mut removed = None;
+        for (steam_id, name) in settings.esp_ignored_players.iter() {
+            if ui.small_button(&format!("x##ignored_{}", steam_id)) {
+                removed = Some(*steam_id);
+            }
+            ui.same_line();
+            ui.text(name);
+        }
+
+        if let Some(steam_id) = removed {
+            settings.esp_ignored_players.remove(&steam_id);
+        }
+    }
+
+    fn render_esp_map_palette(&mut self, app: &Application, settings: &mut AppSettings, ui: &imgui::Ui) {
+        self.animated_checkbox(ui, "Adapt colors to map", &mut settings.esp_map_palette);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Replaces the default team colors with a palette contrasting the current map");
+        }
+        if !settings.esp_map_palette {
+            return;
+        }
+
+        let Some(current_map) = app
+            .app_state
+            .resolve::<StateCurrentMap>(())
+            .ok()
+            .and_then(|map| map.current_map.clone())
+        else {
+            ui.text_disabled("No map loaded");
+            return;
+        };
+
+        let Some(mut palette) = settings.map_palette(&current_map) else {
+            ui.text_disabled(format!("No palette for {}", current_map));
+            ui.same_line();
+            if ui.small_button("Create") {
+                settings.esp_map_palette_overrides.insert(
+                    current_map,
+                    MapPalette {
+                        friendly: Color::from_f32([0.0, 1.0, 0.0, 1.0]),
+                        enemy: Color::from_f32([1.0, 0.0, 0.0, 1.0]),
+                    },
+                );
+            }
+            return;
+        };
+
+        ui.text(&current_map);
+        let mut palette_changed = false;
+        for (label, color) in [("Friendly", &mut palette.friendly), ("Enemy", &mut palette.enemy)] {
+            ui.same_line();
+            let mut color_value = color.as_f32();
+            if ui
+                .color_edit4_config(&format!("##map_palette_{}", label), &mut color_value)
+                .alpha(false)
+                .inputs(false)
+                .label(false)
+                .build()
+            {
+                *color = Color::from_f32(color_value);
+                palette_changed = true;
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip_text(label);
+            }
+        }
+
+        if palette_changed {
+            settings.esp_map_palette_overrides.insert(current_map.clone(), palette);
+        }
+
+        if settings.esp_map_palette_overrides.contains_key(&current_map) {
+            ui.same_line();
+            if ui.small_button("Reset##map_palette") {
+                settings.esp_map_palette_overrides.remove(&current_map);
+            }
+        }
+    }
+
+    fn render_esp_preview(
+        &mut self,
+        app: &Application,
+        settings: &mut AppSettings,
         ui: &imgui::Ui,
     ) {
         let draw_list = ui.get_window_draw_list();
@@ -1360,17 +3031,30 @@ impl SettingsUI {
             self.preview_rotation += delta * 0.01;
         }
 
-        let target_selector = match self.esp_player_target_mode {
-            PlayerTargetMode::Friendly => EspSelector::PlayerTeam { enemy: false },
-            PlayerTargetMode::Enemy => EspSelector::PlayerTeam { enemy: true },
+        // Preview the config the selected player target resolves to
+        let preview_target = match self.esp_selected_target {
+            target @ (EspSelector::Player | EspSelector::PlayerTeam { .. } | EspSelector::PlayerTeamVisibility { .. }) => target,
+            _ => EspSelector::PlayerTeam { enemy: true },
         };
-        let config_key = target_selector.config_key();
+        let preview_config = preview_target
+            .inheritance_chain()
+            .iter()
+            .filter(|selector| {
+                settings
+                    .esp_settings_enabled
+                    .get(&selector.config_key())
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .find_map(|selector| match settings.esp_settings.get(&selector.config_key()) {
+                Some(EspConfig::Player(config)) => Some(*config),
+                _ => None,
+            })
+            .unwrap_or_else(|| EspPlayerSettings::new(&preview_target));
+
+        {
+            let player_config = &preview_config;
 
-        let config = settings.esp_settings
-            .entry(config_key)
-            .or_insert_with(|| EspConfig::Player(EspPlayerSettings::new(&target_selector)));
-
-        if let EspConfig::Player(player_config) = config {
             
             // --- 3D RENDER SETUP ---
             let model = app.resources.character_model.as_ref();
@@ -1479,8 +3163,12 @@ impl SettingsUI {
 
                 // 3. Render Model
                 // Use a dark grey/blue color typical of CT character
+                // The render backend draws the model if supported, otherwise it's skinned in software
                 let color = [0.3, 0.35, 0.4, 1.0 * alpha];
-                let model_bounds = model.render(&draw_list, &view_controller, &bone_transforms, color);
+                let model_bounds = match self.model_preview.draw(&draw_list, container_pos, container_size, model_transform, projection * view_matrix, color) {
+                    Some(bounds) => Some(bounds),
+                    None => model.render(&draw_list, &view_controller, &bone_transforms, color),
+                };
 
                 // 4. Project Bones to 2D for ESP
                 // We need 2D positions of bones to pass to draw_player_esp.
@@ -1502,18 +3190,21 @@ impl SettingsUI {
                 // }
 
                 // 5. Construct EspRenderInfo and Draw ESP
+                // The simulated player runs through the same ESP code path as the in game players
+                let time = self.start_time.elapsed().as_secs_f32();
+                let player_state = PreviewPlayerState::at(time);
                 let render_info = EspRenderInfo {
                     bones: &projected_bones,
                     model_bounds,
                     skeleton_lines: None,
-                    health: 0.92, 
-                    distance: 5.0, 
+                    health: player_state.health,
+                    distance: player_state.distance,
                     name: "BOT Buckshot",
                     weapon_name: "M4A1-S",
                     weapon_icon_name: Some("m4a1_silencer"),
                     team_indicator: "T",
-                    is_scoped: false,
-                    is_flashed: false,
+                    is_scoped: player_state.is_scoped,
+                    is_flashed: player_state.is_flashed,
                     has_kit: true,
                     has_bomb: false,
                 };
@@ -1521,7 +3212,6 @@ impl SettingsUI {
                 // Draw Gradient Background (already drawn at start of function)
                 
                 // Call existing ESP drawing logic
-                let time = app.frame_read_calls as f32 / 60.0; // Approximation for animations
                 draw_player_esp(
                     &draw_list,
                     ui,
@@ -1548,16 +3238,16 @@ impl SettingsUI {
         const STAGE_2_END: f32 = 2.5;
         const STAGE_3_END: f32 = 3.0;
         
-        const WINDOW_SIZE: [f32; 2] = [1024.0, 768.0];
+        let window_size = [WINDOW_SIZE[0] * self.ui_scale, WINDOW_SIZE[1] * self.ui_scale];
         let window_pos = [
-            (display_size[0] - WINDOW_SIZE[0]) * 0.5,
-            (display_size[1] - WINDOW_SIZE[1]) * 0.5,
+            (display_size[0] - window_size[0]) * 0.5,
+            (display_size[1] - window_size[1]) * 0.5,
         ];
 
         // Draw window background overlay
         let window_rounding = unsafe { ui.style() }.window_rounding;
         let draw_list = ui.get_background_draw_list();
-        draw_list.add_rect(window_pos, [window_pos[0] + WINDOW_SIZE[0], window_pos[1] + WINDOW_SIZE[1]], [0.02, 0.02, 0.03, 1.0])
+        draw_list.add_rect(window_pos, [window_pos[0] + window_size[0], window_pos[1] + window_size[1]], [0.02, 0.02, 0.03, 1.0])
             .filled(true)
             .rounding(window_rounding)
             .build();
@@ -1566,7 +3256,7 @@ impl SettingsUI {
         ui.window("IntroOverlay")
             .flags(WindowFlags::NO_DECORATION | WindowFlags::NO_INPUTS | WindowFlags::NO_BACKGROUND | WindowFlags::NO_NAV)
             .position(window_pos, Condition::Always)
-            .size(WINDOW_SIZE, Condition::Always)
+            .size(window_size, Condition::Always)
             .build(|| {
                 // Use High-Res Intro Font (88px)
                 let Some(intro_font_id) = app.fonts.intro.font_id() else { return };
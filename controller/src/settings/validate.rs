@@ -0,0 +1,411 @@
+use std::fmt::{
+    self,
+    Display,
+};
+
+use super::{
+    AppSettings,
+    UI_SCALE_MAX,
+    UI_SCALE_MIN,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigIssueLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    pub level: ConfigIssueLevel,
+    /// Line of the issue within the config file (1 based)
+    pub line: Option<usize>,
+    /// Dot separated path of the setting or empty if the issue affects the whole file
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.level {
+            ConfigIssueLevel::Info => "info",
+            ConfigIssueLevel::Warning => "warning",
+            ConfigIssueLevel::Error => "error",
+        };
+
+        write!(f, "{}", level)?;
+        if let Some(line) = self.line {
+            write!(f, " (line {})", line)?;
+        }
+        if !self.path.is_empty() {
+            write!(f, " {}:", self.path)?;
+        }
+        write!(f, " {}", self.message)
+    }
+}
+
+pub struct ConfigValidation {
+    pub issues: Vec<ConfigIssue>,
+    /// The parsed settings with unknown keys removed and out of range values clamped.
+    /// None if the config could not be parsed.
+    pub normalized: Option<AppSettings>,
+}
+
+impl ConfigValidation {
+    pub fn has_errors(&self) -> bool {
+        self.issues
+            .iter()
+            .any(|issue| issue.level == ConfigIssueLevel::Error)
+    }
+}
+
+/// Validate the YAML source of a config without applying it
+pub fn validate_config(source: &str) -> ConfigValidation {
+    let mut issues = Vec::new();
+
+    let document = match serde_yaml::from_str::<serde_yaml::Value>(source) {
+        Ok(document) => document,
+        Err(error) => {
+            issues.push(ConfigIssue {
+                level: ConfigIssueLevel::Error,
+                line: error.location().map(|location| location.line()),
+                path: String::new(),
+                message: format!("invalid YAML: {}", error),
+            });
+            return ConfigValidation {
+                issues,
+                normalized: None,
+            };
+        }
+    };
+
+    let mut ignored_keys = Vec::new();
+    let settings = serde_ignored::deserialize::<_, _, AppSettings>(
+        serde_yaml::Deserializer::from_str(source),
+        |path| {
+            /* optional values are displayed as "?" segments */
+            let path = path.to_string();
+            let segments = path
+                .split('.')
+                .filter(|segment| *segment != "?")
+                .collect::<Vec<_>>();
+            ignored_keys.push(segments.join("."));
+        },
+    );
+
+    let mut settings = match settings {
+        Ok(settings) => settings,
+        Err(error) => {
+            issues.push(ConfigIssue {
+                level: ConfigIssueLevel::Error,
+                line: error.location().map(|location| location.line()),
+                path: String::new(),
+                message: error.to_string(),
+            });
+            return ConfigValidation {
+                issues,
+                normalized: None,
+            };
+        }
+    };
+
+    for path in ignored_keys {
+        issues.push(ConfigIssue {
+            level: ConfigIssueLevel::Warning,
+            line: find_key_line(source, &path),
+            message: "unknown key, it will be ignored".to_string(),
+            path,
+        });
+    }
+
+    let default_document =
+        serde_yaml::to_value(AppSettings::default()).unwrap_or(serde_yaml::Value::Null);
+    if let (Some(sections), Some(default_sections)) =
+        (document.as_mapping(), default_document.as_mapping())
+    {
+        for key in default_sections.keys() {
+            if sections.contains_key(key) {
+                continue;
+            }
+
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+
+            issues.push(ConfigIssue {
+                level: if key == "imgui" {
+                    ConfigIssueLevel::Warning
+                } else {
+                    ConfigIssueLevel::Info
+                },
+                line: None,
+                path: key.to_string(),
+                message: "missing, the default value will be used".to_string(),
+            });
+        }
+    } else if !document.is_null() {
+        issues.push(ConfigIssue {
+            level: ConfigIssueLevel::Error,
+            line: None,
+            path: String::new(),
+            message: "the config must be a mapping of settings".to_string(),
+        });
+    }
+
+    let mut ranges = RangeCheck {
+        source,
+        issues: &mut issues,
+    };
+    if let Some(ui_scale) = settings.ui_scale.as_mut() {
+        ranges.check("ui_scale", ui_scale, UI_SCALE_MIN, UI_SCALE_MAX);
+    }
+    ranges.check("autosave_interval", &mut settings.autosave_interval, 0, 300);
+    ranges.check("fps_limit", &mut settings.fps_limit, 0, 1000);
+    ranges.check("update_tick_rate", &mut settings.update_tick_rate, 16, 128);
+    ranges.check("frame_budget_ms", &mut settings.frame_budget_ms, 0.5, 16.0);
+    ranges.check(
+        "esp_max_distance",
+        &mut settings.esp_max_distance,
+        0.0,
+        200.0,
+    );
+    ranges.check(
+        "esp_fade_distance",
+        &mut settings.esp_fade_distance,
+        0.0,
+        50.0,
+    );
+    ranges.check(
+        "trigger_bot_delay_min",
+        &mut settings.trigger_bot_delay_min,
+        0,
+        300,
+    );
+    ranges.check(
+        "trigger_bot_delay_max",
+        &mut settings.trigger_bot_delay_max,
+        settings.trigger_bot_delay_min,
+        300,
+    );
+    ranges.check(
+        "trigger_bot_shot_duration",
+        &mut settings.trigger_bot_shot_duration,
+        0,
+        1000,
+    );
+    ranges.check("legit_aim_fov", &mut settings.legit_aim_fov, 1.0, 180.0);
+    ranges.check(
+        "legit_aim_smooth",
+        &mut settings.legit_aim_smooth,
+        1.0,
+        50.0,
+    );
+    ranges.check(
+        "game_state_api_port",
+        &mut settings.game_state_api_port,
+        1,
+        u16::MAX,
+    );
+
+    issues.sort_by_key(|issue| (issue.line.is_none(), issue.line));
+    ConfigValidation {
+        issues,
+        normalized: Some(settings),
+    }
+}
+
+struct RangeCheck<'a> {
+    source: &'a str,
+    issues: &'a mut Vec<ConfigIssue>,
+}
+
+impl RangeCheck<'_> {
+    /// Report and clamp a value outside of the range the settings menu allows
+    fn check<T: PartialOrd + Copy + Display>(&mut self, path: &str, value: &mut T, min: T, max: T) {
+        if *value >= min && *value <= max {
+            return;
+        }
+
+        let clamped = if *value < min { min } else { max };
+        self.issues.push(ConfigIssue {
+            level: ConfigIssueLevel::Warning,
+            line: find_key_line(self.source, path),
+            path: path.to_string(),
+            message: format!(
+                "{} is out of range ({} to {}), it will be clamped to {}",
+                value, min, max, clamped
+            ),
+        });
+        *value = clamped;
+    }
+}
+
+/// Find the line of a dot separated key path within a YAML document.
+/// Only block style mappings are supported which is what the config is saved as.
+fn find_key_line(source: &str, path: &str) -> Option<usize> {
+    let lines = source.lines().collect::<Vec<_>>();
+
+    let mut line_index = 0;
+    let mut parent_indent = None;
+    let mut result = None;
+    for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+        let key_prefixes = [
+            format!("{}:", segment),
+            format!("'{}':", segment),
+            format!("\"{}\":", segment),
+        ];
+
+        let mut found = None;
+        let mut child_indent = None;
+        for (index, line) in lines.iter().enumerate().skip(line_index) {
+            let content = line.trim_start();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+
+            let indent = line.len() - content.len();
+            if parent_indent.is_some_and(|parent_indent| indent <= parent_indent) {
+                /* left the parent mapping */
+                break;
+            }
+
+            let key = content.trim_start_matches("- ");
+            let key_indent = indent + (content.len() - key.len());
+
+            /* only keys of the parent mapping itself, keys of nested mappings may have the same name */
+            if key_indent != *child_indent.get_or_insert(key_indent) {
+                continue;
+            }
+
+            if key_prefixes
+                .iter()
+                .any(|prefix| key.starts_with(prefix.as_str()))
+            {
+                found = Some((index, indent));
+                break;
+            }
+        }
+
+        /* sequence indices and flow style mappings can not be resolved, use the closest parent */
+        let Some((index, indent)) = found else {
+            break;
+        };
+
+        result = Some(index + 1);
+        line_index = index + 1;
+        parent_indent = Some(indent);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        find_key_line,
+        validate_config,
+        ConfigIssueLevel,
+    };
+
+    const SOURCE: &str = "\
+esp_settings:
+  player:
+    box_color: red
+  enabled: true
+# comment
+enabled: false
+grenade_helper:
+  capture_key: F5
+  nested:
+    capture_key: F6
+";
+
+    #[test]
+    fn key_line_top_level() {
+        assert_eq!(find_key_line(SOURCE, "esp_settings"), Some(1));
+        assert_eq!(find_key_line(SOURCE, "enabled"), Some(6));
+        assert_eq!(find_key_line(SOURCE, "grenade_helper"), Some(7));
+    }
+
+    #[test]
+    fn key_line_scoped_by_parent() {
+        assert_eq!(find_key_line(SOURCE, "esp_settings.enabled"), Some(4));
+        assert_eq!(
+            find_key_line(SOURCE, "esp_settings.player.box_color"),
+            Some(3)
+        );
+        assert_eq!(find_key_line(SOURCE, "grenade_helper.capture_key"), Some(8));
+        assert_eq!(
+            find_key_line(SOURCE, "grenade_helper.nested.capture_key"),
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn key_line_missing() {
+        assert_eq!(find_key_line(SOURCE, "unknown"), None);
+        /* the closest parent is reported */
+        assert_eq!(find_key_line(SOURCE, "esp_settings.box_color"), Some(1));
+        assert_eq!(find_key_line(SOURCE, "grenade_helper.missing"), Some(7));
+    }
+
+    #[test]
+    fn validate_invalid_yaml() {
+        let validation = validate_config("fps_limit: [1, 2\n");
+        assert!(validation.has_errors());
+        assert!(validation.normalized.is_none());
+        assert!(validation.issues[0].line.is_some());
+    }
+
+    #[test]
+    fn validate_not_a_mapping() {
+        let validation = validate_config("- 1\n- 2\n");
+        assert!(validation.has_errors());
+    }
+
+    #[test]
+    fn validate_unknown_key() {
+        let validation = validate_config("fps_limit: 60\nnot_a_setting: 1\n");
+        assert!(!validation.has_errors());
+
+        let issue = validation
+            .issues
+            .iter()
+            .find(|issue| issue.path == "not_a_setting")
+            .expect("unknown key reported");
+        assert_eq!(issue.level, ConfigIssueLevel::Warning);
+        assert_eq!(issue.line, Some(2));
+    }
+
+    #[test]
+    fn validate_out_of_range() {
+        let validation = validate_config("fps_limit: 5000\n");
+        assert!(!validation.has_errors());
+
+        let issue = validation
+            .issues
+            .iter()
+            .find(|issue| issue.path == "fps_limit")
+            .expect("out of range value reported");
+        assert_eq!(issue.level, ConfigIssueLevel::Warning);
+        assert_eq!(issue.line, Some(1));
+        assert_eq!(validation.normalized.unwrap().fps_limit, 1000);
+    }
+
+    #[test]
+    fn validate_missing_keys() {
+        let validation = validate_config("fps_limit: 60\n");
+        let imgui = validation
+            .issues
+            .iter()
+            .find(|issue| issue.path == "imgui")
+            .expect("missing imgui reported");
+        assert_eq!(imgui.level, ConfigIssueLevel::Warning);
+        assert!(validation
+            .issues
+            .iter()
+            .filter(|issue| issue.path != "imgui")
+            .all(|issue| issue.level == ConfigIssueLevel::Info));
+    }
+}
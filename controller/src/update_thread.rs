@@ -0,0 +1,436 @@
+use std::{
+    panic::{
+        self,
+        AssertUnwindSafe,
+    },
+    sync::{
+        atomic::{
+            AtomicBool,
+            AtomicU32,
+            Ordering,
+        },
+        Arc,
+        Mutex,
+    },
+    thread::JoinHandle,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use cs2::{
+    invalidate_game_states,
+    CEntityIdentityEx,
+    CS2Handle,
+    ClassNameCache,
+    LocalCameraControllerTarget,
+    PlayerPawnState,
+    StateCS2Handle,
+    StateCS2Memory,
+    StateEntityList,
+    StateLocalPlayerController,
+    StatePawnInfo,
+};
+use cs2_schema_generated::cs2::client::CCSPlayerController;
+use obfstr::obfstr;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+/// Default rate the game state is read with
+pub const DEFAULT_UPDATE_TICK_RATE: u32 = 64;
+
+#[derive(Debug, Clone)]
+pub struct PlayerSnapshot {
+    pub pawn_entity_index: u32,
+    pub alive: bool,
+    /// Pawn info read on the update thread, None if it could not be read
+    pub info: Option<StatePawnInfo>,
+}
+
+/// Game data collected by the update thread
+#[derive(Debug, Clone, Default)]
+pub struct GameSnapshot {
+    /// Team of the local player controller, None if there is no local player
+    pub local_team_id: Option<u8>,
+    /// Entity the local camera is attached to
+    pub view_target_entity_id: Option<u32>,
+    /// Pawns of all player controllers
+    pub players: Vec<PlayerSnapshot>,
+}
+
+/// Latest snapshot of the update thread
+pub struct StateGameSnapshot(pub Arc<GameSnapshot>);
+
+impl State for StateGameSnapshot {
+    type Parameter = ();
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
+
+fn collect_snapshot(states: &StateRegistry, snapshot: &mut GameSnapshot) -> anyhow::Result<()> {
+    snapshot.local_team_id = None;
+    snapshot.view_target_entity_id = None;
+    snapshot.players.clear();
+
+    let memory = states.resolve::<StateCS2Memory>(())?;
+    let local_player_controller = states.resolve::<StateLocalPlayerController>(())?;
+    let Some(local_player_controller) = local_player_controller
+        .instance
+        .value_reference(memory.view_arc())
+    else {
+        return Ok(());
+    };
+    snapshot.local_team_id = Some(local_player_controller.m_iPendingTeamNum()?);
+    snapshot.view_target_entity_id = states
+        .resolve::<LocalCameraControllerTarget>(())?
+        .target_entity_id;
+
+    let entities = states.resolve::<StateEntityList>(())?;
+    let class_name_cache = states.resolve::<ClassNameCache>(())?;
+    for entity_identity in entities.entities() {
+        let entity_class = class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
+        if !entity_class
+            .map(|name| *name == "CCSPlayerController")
+            .unwrap_or(false)
+        {
+            continue;
+        }
+
+        let controller_handle = entity_identity.handle::<dyn CCSPlayerController>()?;
+        let Some(controller) = entities
+            .entity_from_handle(&controller_handle)
+            .and_then(|controller| controller.value_reference(memory.view_arc()))
+        else {
+            continue;
+        };
+
+        let pawn_handle = controller.m_hPlayerPawn()?;
+        if !pawn_handle.is_valid() {
+            continue;
+        }
+
+        let pawn_entity_index = pawn_handle.get_entity_index();
+        let pawn_state = states.resolve::<PlayerPawnState>(pawn_handle)?;
+        let info = states
+            .resolve::<StatePawnInfo>(pawn_handle)
+            .ok()
+            .map(|info| info.clone());
+        snapshot.players.push(PlayerSnapshot {
+            pawn_entity_index,
+            alive: *pawn_state == PlayerPawnState::Alive,
+            info,
+        });
+    }
+
+    Ok(())
+}
+
+/// Amount of released snapshots kept for reuse.
+/// The overlay holds the published snapshot and usually the one before until its next frame.
+const SPARE_SNAPSHOTS: usize = 2;
+
+/// Snapshots of the update thread which are no longer published.
+/// Their allocations are reused once the overlay released them.
+struct SnapshotPool {
+    spare: Vec<Arc<GameSnapshot>>,
+    empty: Arc<GameSnapshot>,
+}
+
+impl SnapshotPool {
+    fn new() -> Self {
+        Self {
+            spare: Vec::with_capacity(SPARE_SNAPSHOTS),
+            empty: Default::default(),
+        }
+    }
+
+    /// A snapshot which is not referenced by anybody else
+    fn acquire(&mut self) -> Arc<GameSnapshot> {
+        match self
+            .spare
+            .iter_mut()
+            .position(|snapshot| Arc::get_mut(snapshot).is_some())
+        {
+            Some(index) => self.spare.swap_remove(index),
+            None => Default::default(),
+        }
+    }
+
+    /// Replace the published snapshot and keep the previous one for reuse
+    fn publish(&mut self, front: &Mutex<Arc<GameSnapshot>>, snapshot: Arc<GameSnapshot>) {
+        let previous = std::mem::replace(&mut *front.lock().unwrap(), snapshot);
+        if Arc::ptr_eq(&previous, &self.empty) {
+            return;
+        }
+
+        if self.spare.len() < SPARE_SNAPSHOTS {
+            self.spare.push(previous);
+        }
+    }
+
+    /// Publish an empty snapshot, e.g. because the game state can not be read
+    fn publish_empty(&mut self, front: &Mutex<Arc<GameSnapshot>>) {
+        if Arc::ptr_eq(&front.lock().unwrap(), &self.empty) {
+            return;
+        }
+
+        self.publish(front, self.empty.clone());
+    }
+}
+
+/// Schedule the tick after the current one.
+/// Missed ticks are dropped instead of caught up after a stall.
+fn schedule_next_tick(current_tick: Instant, interval: Duration, now: Instant) -> Instant {
+    (current_tick + interval).max(now)
+}
+
+struct UpdateThreadShared {
+    /// Snapshot published last
+    front: Mutex<Arc<GameSnapshot>>,
+    tick_rate: AtomicU32,
+    suspended: AtomicBool,
+    invalidate_requested: AtomicBool,
+    running: AtomicBool,
+}
+
+/// Reads the player list and the pawn info of all players with a fixed tick rate on its own thread,
+/// so slow memory reads do not stall the overlay.
+/// The thread uses its own state registry and publishes its results as snapshots.
+/// Enhancements read the players from the published snapshot, but their updates
+/// and rendering remain on the overlay thread as they are bound to the imgui frame.
+pub struct UpdateThread {
+    shared: Arc<UpdateThreadShared>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl UpdateThread {
+    pub fn spawn(cs2: Arc<CS2Handle>, tick_rate: u32) -> anyhow::Result<Self> {
+        let mut states = StateRegistry::new(1024 * 4);
+        states.set(StateCS2Handle::new(cs2.clone()), ())?;
+        states.set(StateCS2Memory::new(cs2.create_memory_view()), ())?;
+
+        let shared = Arc::new(UpdateThreadShared {
+            front: Mutex::new(Default::default()),
+            tick_rate: AtomicU32::new(tick_rate),
+            suspended: AtomicBool::new(false),
+            invalidate_requested: AtomicBool::new(false),
+            running: AtomicBool::new(true),
+        });
+
+        let handle = std::thread::Builder::new()
+            .name(obfstr!("update").to_string())
+            .spawn({
+                let shared = shared.clone();
+                move || Self::run(&shared, states)
+            })?;
+
+        Ok(Self {
+            shared,
+            handle: Some(handle),
+        })
+    }
+
+    fn run(shared: &UpdateThreadShared, mut states: StateRegistry) {
+        let mut pool = SnapshotPool::new();
+        let mut next_tick = Instant::now();
+        let mut last_error = None;
+
+        while shared.running.load(Ordering::Relaxed) {
+            let tick_interval =
+                Duration::from_secs(1) / shared.tick_rate.load(Ordering::Relaxed).max(1);
+            let now = Instant::now();
+            if now < next_tick {
+                std::thread::sleep(next_tick - now);
+            }
+            next_tick = schedule_next_tick(next_tick, tick_interval, Instant::now());
+
+            if shared.invalidate_requested.swap(false, Ordering::Relaxed) {
+                invalidate_game_states(&mut states);
+            }
+
+            if shared.suspended.load(Ordering::Relaxed) {
+                /* do not show players of a game which is no longer attached */
+                pool.publish_empty(&shared.front);
+                continue;
+            }
+
+            let mut snapshot = pool.acquire();
+            /* only effective in debug builds, release builds abort on panic */
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                states.invalidate_states();
+                collect_snapshot(&states, Arc::get_mut(&mut snapshot).expect("to be unique"))
+            }));
+            let error = match result {
+                Ok(Ok(())) => None,
+                Ok(Err(err)) => Some(format!("{:#}", err)),
+                Err(_) => {
+                    /* the panic has already been reported, start over with fresh game states */
+                    invalidate_game_states(&mut states);
+                    Some("collecting the game snapshot panicked".to_string())
+                }
+            };
+
+            match error {
+                Some(error) => {
+                    if last_error.as_ref() != Some(&error) {
+                        log::debug!("Failed to collect the game snapshot: {}", error);
+                        last_error = Some(error);
+                    }
+
+                    pool.publish_empty(&shared.front);
+                }
+                None => {
+                    last_error = None;
+                    pool.publish(&shared.front, snapshot);
+                }
+            }
+        }
+    }
+
+    /// Latest snapshot of the game state
+    pub fn snapshot(&self) -> Arc<GameSnapshot> {
+        self.shared.front.lock().unwrap().clone()
+    }
+
+    pub fn set_tick_rate(&self, tick_rate: u32) {
+        self.shared.tick_rate.store(tick_rate, Ordering::Relaxed);
+    }
+
+    /// Stop reading the game state, e.g. while the game is not running
+    pub fn set_suspended(&self, suspended: bool) {
+        self.shared.suspended.store(suspended, Ordering::Relaxed);
+    }
+
+    /// Drop all cached game states of the update thread, e.g. after the game has been restarted
+    pub fn invalidate_game_states(&self) {
+        self.shared
+            .invalidate_requested
+            .store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for UpdateThread {
+    fn drop(&mut self) {
+        self.shared.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{
+            Arc,
+            Mutex,
+        },
+        time::{
+            Duration,
+            Instant,
+        },
+    };
+
+    use super::{
+        schedule_next_tick,
+        GameSnapshot,
+        PlayerSnapshot,
+        SnapshotPool,
+    };
+
+    fn snapshot_with_players(pool: &mut SnapshotPool, count: u32) -> Arc<GameSnapshot> {
+        let mut snapshot = pool.acquire();
+        let players = &mut Arc::get_mut(&mut snapshot).unwrap().players;
+        players.clear();
+        players.extend((0..count).map(|pawn_entity_index| PlayerSnapshot {
+            pawn_entity_index,
+            alive: true,
+            info: None,
+        }));
+        snapshot
+    }
+
+    #[test]
+    fn test_publish_snapshot() {
+        let front = Mutex::new(Arc::new(GameSnapshot::default()));
+        let mut pool = SnapshotPool::new();
+
+        let snapshot = snapshot_with_players(&mut pool, 3);
+        pool.publish(&front, snapshot.clone());
+        assert!(Arc::ptr_eq(&front.lock().unwrap(), &snapshot));
+        assert_eq!(front.lock().unwrap().players.len(), 3);
+
+        pool.publish_empty(&front);
+        assert!(front.lock().unwrap().players.is_empty());
+    }
+
+    #[test]
+    fn test_reuse_released_snapshot() {
+        let front = Mutex::new(Arc::new(GameSnapshot::default()));
+        let mut pool = SnapshotPool::new();
+
+        let first = snapshot_with_players(&mut pool, 8);
+        let first_players = first.players.as_ptr();
+        pool.publish(&front, first);
+        let second = snapshot_with_players(&mut pool, 8);
+        pool.publish(&front, second);
+
+        /* the first snapshot is no longer referenced, its allocation is reused */
+        let third = snapshot_with_players(&mut pool, 8);
+        assert_eq!(third.players.as_ptr(), first_players);
+    }
+
+    #[test]
+    fn test_keep_snapshot_in_use() {
+        let front = Mutex::new(Arc::new(GameSnapshot::default()));
+        let mut pool = SnapshotPool::new();
+
+        let first = snapshot_with_players(&mut pool, 8);
+        pool.publish(&front, first.clone());
+        let second = snapshot_with_players(&mut pool, 8);
+        pool.publish(&front, second);
+
+        /* the first snapshot is still held by the overlay */
+        let third = snapshot_with_players(&mut pool, 8);
+        assert!(!Arc::ptr_eq(&third, &first));
+        assert_eq!(first.players.len(), 8);
+    }
+
+    #[test]
+    fn test_publish_empty_not_reused() {
+        let front = Mutex::new(Arc::new(GameSnapshot::default()));
+        let mut pool = SnapshotPool::new();
+
+        pool.publish_empty(&front);
+        let snapshot = snapshot_with_players(&mut pool, 2);
+        pool.publish(&front, snapshot);
+        pool.publish_empty(&front);
+
+        /* the shared empty snapshot must never be handed out for writing */
+        let snapshot = pool.acquire();
+        assert!(!Arc::ptr_eq(&snapshot, &pool.empty));
+        assert_eq!(snapshot.players.len(), 2);
+    }
+
+    #[test]
+    fn test_drop_missed_ticks() {
+        let interval = Duration::from_millis(10);
+        let start = Instant::now();
+
+        /* on time */
+        assert_eq!(
+            schedule_next_tick(start, interval, start + Duration::from_millis(2)),
+            start + interval
+        );
+
+        /* stalled for several ticks, continue from now instead of catching up */
+        let now = start + Duration::from_millis(55);
+        assert_eq!(schedule_next_tick(start, interval, now), now);
+    }
+}
@@ -0,0 +1,492 @@
+use std::{
+    fs,
+    sync::mpsc::{
+        self,
+        Receiver,
+        Sender,
+    },
+};
+
+use anyhow::Context;
+use base64::Engine;
+use ring::signature::{
+    UnparsedPublicKey,
+    ED25519,
+};
+use serde::Deserialize;
+
+use crate::notifications::NotificationLevel;
+
+/// Base64 encoded Ed25519 key the release executables are signed with.
+/// Builds without a key only notify about new releases.
+const UPDATE_PUBLIC_KEY: Option<&str> = option_env!("LABH_UPDATE_PUBLIC_KEY");
+
+/// Name of the executable asset of a release
+const EXECUTABLE_ASSET_NAME: &str = concat!(env!("CARGO_PKG_NAME"), ".exe");
+
+/// Extension of the detached signature asset next to the executable asset
+const SIGNATURE_EXTENSION: &str = ".sig";
+
+/// Upper limit of the downloaded executable size
+const MAX_EXECUTABLE_SIZE: usize = 128 * 1024 * 1024;
+
+/// Upper limit of the downloaded signature size, a base64 encoded Ed25519 signature is 88 bytes
+const MAX_SIGNATURE_SIZE: usize = 1024;
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// A release newer than the running version
+#[derive(Debug, Clone)]
+pub struct ReleaseInfo {
+    pub version: String,
+    /// Page of the release with the changelog
+    pub page_url: String,
+    executable_url: Option<String>,
+    signature_url: Option<String>,
+}
+
+impl ReleaseInfo {
+    /// The release can be downloaded and its signature verified
+    pub fn installable(&self) -> bool {
+        UPDATE_PUBLIC_KEY.is_some() && self.executable_url.is_some() && self.signature_url.is_some()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum UpdateStatus {
+    Idle,
+    Checking,
+    UpToDate,
+    Available(ReleaseInfo),
+    Installing(ReleaseInfo),
+    /// The executable has been replaced, the new version is used after a restart
+    Installed(String),
+    Failed(String),
+}
+
+enum UpdateEvent {
+    Checked(anyhow::Result<Option<ReleaseInfo>>),
+    Installed(anyhow::Result<String>),
+}
+
+/// Version components of a tag like "v0.5.15", missing components are zero
+fn parse_version(version: &str) -> Option<[u32; 3]> {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split(['-', '+']).next()?;
+
+    let mut result = [0; 3];
+    for (index, component) in version.split('.').enumerate() {
+        *result.get_mut(index)? = component.parse().ok()?;
+    }
+    Some(result)
+}
+
+fn http_client() -> anyhow::Result<reqwest::Client> {
+    /* the GitHub API rejects requests without a user agent */
+    Ok(reqwest::Client::builder()
+        .user_agent(concat!("LABH/", env!("CARGO_PKG_VERSION")))
+        .build()?)
+}
+
+async fn fetch_latest_release(feed_url: &str) -> anyhow::Result<Option<ReleaseInfo>> {
+    let response = http_client()?
+        .get(feed_url)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let release = serde_json::from_str::<GithubRelease>(&response).context("parse release")?;
+
+    let latest = parse_version(&release.tag_name)
+        .with_context(|| format!("invalid release version {}", release.tag_name))?;
+    let current = parse_version(env!("CARGO_PKG_VERSION")).context("invalid package version")?;
+    if latest <= current {
+        return Ok(None);
+    }
+
+    let executable = release
+        .assets
+        .iter()
+        .find(|asset| asset.name == EXECUTABLE_ASSET_NAME);
+    let signature = executable.and_then(|executable| {
+        let signature_name = format!("{}{}", executable.name, SIGNATURE_EXTENSION);
+        release
+            .assets
+            .iter()
+            .find(|asset| asset.name == signature_name)
+    });
+
+    Ok(Some(ReleaseInfo {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        page_url: release.html_url,
+        executable_url: executable.map(|asset| asset.browser_download_url.clone()),
+        signature_url: signature.map(|asset| asset.browser_download_url.clone()),
+    }))
+}
+
+/// Message covered by the release signature.
+/// The version is part of it, so an older signed executable can not be passed off as a newer release.
+fn signed_payload(version: &str, data: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(version.len() + 1 + data.len());
+    payload.extend_from_slice(version.as_bytes());
+    payload.push(b'\n');
+    payload.extend_from_slice(data);
+    payload
+}
+
+fn verify_signature(
+    public_key: &str,
+    version: &str,
+    data: &[u8],
+    signature: &str,
+) -> anyhow::Result<()> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let public_key = engine
+        .decode(public_key)
+        .context("invalid update signing key")?;
+    let signature = engine
+        .decode(signature.trim())
+        .context("invalid signature encoding")?;
+
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(&signed_payload(version, data), &signature)
+        .map_err(|_| anyhow::anyhow!("signature verification failed"))
+}
+
+/// Verify the downloaded release and make sure it is newer than the running version
+fn verify_release(
+    public_key: &str,
+    current_version: &str,
+    version: &str,
+    data: &[u8],
+    signature: &str,
+) -> anyhow::Result<()> {
+    verify_signature(public_key, version, data, signature)?;
+
+    let version =
+        parse_version(version).with_context(|| format!("invalid release version {}", version))?;
+    let current = parse_version(current_version).context("invalid package version")?;
+    if version <= current {
+        anyhow::bail!("the release is not newer than the running version");
+    }
+
+    Ok(())
+}
+
+/// Download the response body, failing if it exceeds the size limit
+async fn download(client: &reqwest::Client, url: &str, max_size: usize) -> anyhow::Result<Vec<u8>> {
+    let mut response = client.get(url).send().await?.error_for_status()?;
+    if response
+        .content_length()
+        .is_some_and(|length| length > max_size as u64)
+    {
+        anyhow::bail!("download exceeds {} bytes", max_size);
+    }
+
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if data.len() + chunk.len() > max_size {
+            anyhow::bail!("download exceeds {} bytes", max_size);
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
+
+/// Replace the running executable. Windows allows renaming a running executable but not overwriting it.
+fn replace_executable(data: &[u8]) -> anyhow::Result<()> {
+    let current = std::env::current_exe().context("current executable")?;
+    let update = current.with_extension("update");
+    let previous = current.with_extension("old");
+
+    fs::write(&update, data).with_context(|| format!("failed to write {}", update.display()))?;
+    if previous.exists() {
+        fs::remove_file(&previous)
+            .with_context(|| format!("failed to remove {}", previous.display()))?;
+    }
+
+    fs::rename(&current, &previous).context("failed to move the current executable")?;
+    if let Err(err) = fs::rename(&update, &current) {
+        let _ = fs::rename(&previous, &current);
+        return Err(err).context("failed to move the new executable");
+    }
+
+    Ok(())
+}
+
+async fn install_release(release: ReleaseInfo) -> anyhow::Result<String> {
+    let public_key = UPDATE_PUBLIC_KEY.context("no update signing key")?;
+
+    let client = http_client()?;
+    let executable = download(
+        &client,
+        release
+            .executable_url
+            .as_deref()
+            .context("missing executable")?,
+        MAX_EXECUTABLE_SIZE,
+    )
+    .await?;
+    let signature = download(
+        &client,
+        release
+            .signature_url
+            .as_deref()
+            .context("missing signature")?,
+        MAX_SIGNATURE_SIZE,
+    )
+    .await?;
+    let signature = String::from_utf8(signature).context("invalid signature encoding")?;
+
+    verify_release(
+        public_key,
+        env!("CARGO_PKG_VERSION"),
+        &release.version,
+        &executable,
+        &signature,
+    )?;
+    replace_executable(&executable)?;
+    Ok(release.version)
+}
+
+/// Remove the executable left behind by a previous update
+pub fn cleanup_previous_executable() {
+    let Ok(current) = std::env::current_exe() else {
+        return;
+    };
+
+    let previous = current.with_extension("old");
+    if previous.exists() {
+        if let Err(err) = fs::remove_file(&previous) {
+            log::debug!("Failed to remove {}: {}", previous.display(), err);
+        }
+    }
+}
+
+/// Checks the release feed and installs new releases in the background
+pub struct Updater {
+    status: UpdateStatus,
+    event_sender: Sender<UpdateEvent>,
+    event_receiver: Receiver<UpdateEvent>,
+}
+
+impl Updater {
+    pub fn new() -> Self {
+        let (event_sender, event_receiver) = mpsc::channel();
+        Self {
+            status: UpdateStatus::Idle,
+            event_sender,
+            event_receiver,
+        }
+    }
+
+    pub fn status(&self) -> &UpdateStatus {
+        &self.status
+    }
+
+    pub fn check(&mut self, feed_url: &str) {
+        if matches!(
+            self.status,
+            UpdateStatus::Checking | UpdateStatus::Installing(_) | UpdateStatus::Installed(_)
+        ) {
+            return;
+        }
+
+        self.status = UpdateStatus::Checking;
+        let feed_url = feed_url.to_string();
+        let event_sender = self.event_sender.clone();
+        tokio::spawn(async move {
+            let result = fetch_latest_release(&feed_url).await;
+            let _ = event_sender.send(UpdateEvent::Checked(result));
+        });
+    }
+
+    /// Download, verify and install the available release
+    pub fn install(&mut self) {
+        let UpdateStatus::Available(release) = &self.status else {
+            return;
+        };
+        if !release.installable() {
+            return;
+        }
+
+        let release = release.clone();
+        self.status = UpdateStatus::Installing(release.clone());
+        let event_sender = self.event_sender.clone();
+        tokio::spawn(async move {
+            let result = install_release(release).await;
+            let _ = event_sender.send(UpdateEvent::Installed(result));
+        });
+    }
+
+    /// Apply the results of the background tasks.
+    /// Returns a notification for the user if something noteworthy happened.
+    pub fn poll(&mut self) -> Option<(NotificationLevel, String)> {
+        let mut notification = None;
+        while let Ok(event) = self.event_receiver.try_recv() {
+            match event {
+                UpdateEvent::Checked(Ok(Some(release))) => {
+                    log::info!(
+                        "LABH v{} is available at {}",
+                        release.version,
+                        release.page_url
+                    );
+                    notification = Some((
+                        NotificationLevel::Info,
+                        format!(
+                            "LABH v{} is available. See Info for details.",
+                            release.version
+                        ),
+                    ));
+                    self.status = UpdateStatus::Available(release);
+                }
+                UpdateEvent::Checked(Ok(None)) => {
+                    log::debug!("LABH is up to date");
+                    self.status = UpdateStatus::UpToDate;
+                }
+                UpdateEvent::Checked(Err(err)) => {
+                    /* not worth a toast, the release feed might just be unreachable */
+                    log::warn!("Failed to check for updates: {:#}", err);
+                    self.status = UpdateStatus::Failed(format!("{:#}", err));
+                }
+                UpdateEvent::Installed(Ok(version)) => {
+                    log::info!("Installed LABH v{}", version);
+                    notification = Some((
+                        NotificationLevel::Info,
+                        format!("LABH v{} installed. Restart to apply the update.", version),
+                    ));
+                    self.status = UpdateStatus::Installed(version);
+                }
+                UpdateEvent::Installed(Err(err)) => {
+                    log::warn!("Failed to install the update: {:#}", err);
+                    notification = Some((
+                        NotificationLevel::Error,
+                        format!("Failed to install the update: {:#}", err),
+                    ));
+                    self.status = UpdateStatus::Failed(format!("{:#}", err));
+                }
+            }
+        }
+
+        notification
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use base64::Engine;
+    use ring::{
+        rand::SystemRandom,
+        signature::{
+            Ed25519KeyPair,
+            KeyPair,
+        },
+    };
+
+    use super::{
+        parse_version,
+        signed_payload,
+        verify_release,
+        verify_signature,
+    };
+
+    /// Base64 encoded public key and a key pair to sign with
+    fn signing_key() -> (String, Ed25519KeyPair) {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key =
+            base64::engine::general_purpose::STANDARD.encode(key_pair.public_key().as_ref());
+        (public_key, key_pair)
+    }
+
+    fn sign(key_pair: &Ed25519KeyPair, version: &str, data: &[u8]) -> String {
+        let signature = key_pair.sign(&signed_payload(version, data));
+        base64::engine::general_purpose::STANDARD.encode(signature.as_ref())
+    }
+
+    #[test]
+    fn version_parse() {
+        assert_eq!(parse_version("v0.5.15"), Some([0, 5, 15]));
+        assert_eq!(parse_version("1.2.3"), Some([1, 2, 3]));
+        assert_eq!(parse_version(" v2 "), Some([2, 0, 0]));
+        assert_eq!(parse_version("v1.4"), Some([1, 4, 0]));
+        assert_eq!(parse_version("v1.2.3-beta.1"), Some([1, 2, 3]));
+        assert_eq!(parse_version("1.2.3+build"), Some([1, 2, 3]));
+    }
+
+    #[test]
+    fn version_parse_invalid() {
+        assert_eq!(parse_version(""), None);
+        assert_eq!(parse_version("latest"), None);
+        assert_eq!(parse_version("1.2.3.4"), None);
+        assert_eq!(parse_version("1..2"), None);
+        assert_eq!(parse_version("1.-2.3"), None);
+    }
+
+    #[test]
+    fn version_order() {
+        assert!(parse_version("v0.5.15") > parse_version("v0.5.9"));
+        assert!(parse_version("v1.0.0") > parse_version("v0.99.99"));
+    }
+
+    #[test]
+    fn signature_valid() {
+        let (public_key, key_pair) = signing_key();
+        let signature = sign(&key_pair, "1.0.0", b"executable");
+
+        assert!(verify_signature(&public_key, "1.0.0", b"executable", &signature).is_ok());
+        assert!(verify_signature(
+            &public_key,
+            "1.0.0",
+            b"executable",
+            &format!("{}\n", signature)
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn signature_tampered() {
+        let (public_key, key_pair) = signing_key();
+        let signature = sign(&key_pair, "1.0.0", b"executable");
+
+        assert!(verify_signature(&public_key, "1.0.0", b"executablE", &signature).is_err());
+        assert!(verify_signature(&public_key, "1.0.1", b"executable", &signature).is_err());
+        assert!(verify_signature(&public_key, "1.0.0", b"executable", "not base64!").is_err());
+
+        let (other_key, _) = signing_key();
+        assert!(verify_signature(&other_key, "1.0.0", b"executable", &signature).is_err());
+    }
+
+    #[test]
+    fn release_valid() {
+        let (public_key, key_pair) = signing_key();
+        let signature = sign(&key_pair, "1.2.0", b"executable");
+        assert!(verify_release(&public_key, "1.1.9", "1.2.0", b"executable", &signature).is_ok());
+    }
+
+    #[test]
+    fn release_downgrade() {
+        let (public_key, key_pair) = signing_key();
+
+        let signature = sign(&key_pair, "1.0.0", b"executable");
+        assert!(verify_release(&public_key, "1.1.0", "1.0.0", b"executable", &signature).is_err());
+        assert!(verify_release(&public_key, "1.0.0", "1.0.0", b"executable", &signature).is_err());
+
+        /* an older executable published under a newer tag */
+        assert!(verify_release(&public_key, "1.1.0", "1.2.0", b"executable", &signature).is_err());
+    }
+}
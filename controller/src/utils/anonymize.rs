@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use crate::settings::AppSettings;
+
+/// Assigns stable anonymized labels (Player 1..N) to player names
+/// in the order the names have been seen.
+#[derive(Default)]
+pub struct StateNameAnonymizer {
+    labels: HashMap<String, usize>,
+}
+
+impl StateNameAnonymizer {
+    pub fn label(&mut self, name: &str) -> String {
+        let next_index = self.labels.len() + 1;
+        let index = *self.labels.entry(name.to_string()).or_insert(next_index);
+        format!("Player {}", index)
+    }
+}
+
+impl State for StateNameAnonymizer {
+    type Parameter = ();
+
+    fn create(_states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        Ok(Default::default())
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
+
+/// Name of a player as it should be shown in the overlay.
+/// Returns an anonymized label when the clip safe mode is enabled.
+pub fn display_player_name(states: &StateRegistry, name: &str) -> String {
+    let clip_safe_mode = states
+        .resolve::<AppSettings>(())
+        .map(|settings| settings.clip_safe_mode)
+        .unwrap_or(false);
+    display_player_name_with_mode(states, clip_safe_mode, name)
+}
+
+/// Same as [display_player_name] with the clip safe mode passed explicitly,
+/// e.g. while the settings are currently borrowed mutably.
+pub fn display_player_name_with_mode(states: &StateRegistry, clip_safe_mode: bool, name: &str) -> String {
+    if !clip_safe_mode {
+        return name.to_string();
+    }
+
+    match states.resolve_mut::<StateNameAnonymizer>(()) {
+        Ok(mut anonymizer) => anonymizer.label(name),
+        Err(_) => "Player".to_string(),
+    }
+}
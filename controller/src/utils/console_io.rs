@@ -1,6 +1,14 @@
 use obfstr::obfstr;
 use windows::Win32::System::Console::GetConsoleProcessList;
 
+use super::{
+    current_log_file,
+    read_log_tail,
+};
+
+/// Amount of log lines attached to the critical error dialog
+const CRITICAL_ERROR_LOG_LINES: usize = 15;
+
 pub fn is_console_invoked() -> bool {
     let console_count = unsafe {
         let mut result = [0u32; 128];
@@ -15,6 +23,15 @@ pub fn show_critical_error(message: &str) {
     }
 
     if !is_console_invoked() {
-        overlay::show_error_message(obfstr!("Valthrun Controller"), message);
+        let message = match (current_log_file(), read_log_tail(CRITICAL_ERROR_LOG_LINES)) {
+            (Some(path), Some(log_tail)) => format!(
+                "{}\n\nLast log lines ({}):\n{}",
+                message,
+                path.display(),
+                log_tail
+            ),
+            _ => message.to_string(),
+        };
+        overlay::show_error_message(obfstr!("Valthrun Controller"), &message);
     }
 }
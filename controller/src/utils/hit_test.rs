@@ -0,0 +1,44 @@
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+/// Screen regions of HUD widgets which receive mouse input while the menu is closed.
+/// The regions are collected while rendering and decide the passthrough of the next frame.
+#[derive(Default)]
+pub struct StateInteractiveRegions {
+    regions: Vec<([f32; 2], [f32; 2])>,
+}
+
+impl StateInteractiveRegions {
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+
+    /// Register the bounds of the current imgui window
+    pub fn push_window(&mut self, ui: &imgui::Ui) {
+        let position = ui.window_pos();
+        let size = ui.window_size();
+        self.regions
+            .push((position, [position[0] + size[0], position[1] + size[1]]));
+    }
+
+    pub fn contains(&self, point: [f32; 2]) -> bool {
+        self.regions.iter().any(|(min, max)| {
+            point[0] >= min[0] && point[0] <= max[0] && point[1] >= min[1] && point[1] <= max[1]
+        })
+    }
+}
+
+impl State for StateInteractiveRegions {
+    type Parameter = ();
+
+    fn create(_states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        Ok(Default::default())
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
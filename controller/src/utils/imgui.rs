@@ -4,28 +4,24 @@ use imgui::{
     DrawListMut,
     ImColor32,
 };
+use overlay::{
+    StyledTextDrawList,
+    StyledTextUi,
+    TextStyle,
+};
 
 use crate::{
     settings::HotKey,
     UnicodeTextRenderer,
 };
 
-const TEXT_SHADOW_OFFSET: f32 = 1.0;
-const TEXT_SHADOW_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.7];
-
 pub trait TextWithShadowDrawList {
     fn add_text_with_shadow(&self, pos: [f32; 2], color: impl Into<ImColor32>, text: &str);
 }
 
 impl TextWithShadowDrawList for DrawListMut<'_> {
     fn add_text_with_shadow(&self, pos: [f32; 2], color: impl Into<ImColor32>, text: &str) {
-        self.add_text(
-            [pos[0] + TEXT_SHADOW_OFFSET, pos[1] + TEXT_SHADOW_OFFSET],
-            TEXT_SHADOW_COLOR,
-            text,
-        );
-
-        self.add_text([pos[0], pos[1]], color, text);
+        self.add_styled_text(pos, color, &TextStyle::SHADOW, text);
     }
 }
 
@@ -36,30 +32,11 @@ pub trait TextWithShadowUi {
 
 impl TextWithShadowUi for imgui::Ui {
     fn text_with_shadow(&self, text: &str) {
-        let pos = self.cursor_pos();
-
-        self.set_cursor_pos([pos[0] + TEXT_SHADOW_OFFSET, pos[1] + TEXT_SHADOW_OFFSET]);
-        self.text_colored(TEXT_SHADOW_COLOR, text);
-
-        self.set_cursor_pos(pos);
-        self.text(text);
+        self.styled_text(&TextStyle::SHADOW, text);
     }
 
     fn text_colored_with_shadow(&self, color: impl Into<ImColor32>, text: &str) {
-        let pos = self.cursor_pos();
-        let color = color.into();
-        let color_vec = [
-            color.r as f32 / 255.0,
-            color.g as f32 / 255.0,
-            color.b as f32 / 255.0,
-            color.a as f32 / 255.0,
-        ];
-
-        self.set_cursor_pos([pos[0] + TEXT_SHADOW_OFFSET, pos[1] + TEXT_SHADOW_OFFSET]);
-        self.text_colored(TEXT_SHADOW_COLOR, text);
-
-        self.set_cursor_pos(pos);
-        self.text_colored(color_vec, text);
+        self.styled_text_colored(color, &TextStyle::SHADOW, text);
     }
 }
 
@@ -76,13 +53,7 @@ pub trait UnicodeTextWithShadowUi {
 
 impl UnicodeTextWithShadowUi for imgui::Ui {
     fn unicode_text_with_shadow(&self, unicode_text: &UnicodeTextRenderer, text: &str) {
-        let pos = self.cursor_pos();
-
-        self.set_cursor_pos([pos[0] + TEXT_SHADOW_OFFSET, pos[1] + TEXT_SHADOW_OFFSET]);
-        unicode_text.text_colored(TEXT_SHADOW_COLOR, text);
-
-        self.set_cursor_pos(pos);
-        unicode_text.text(text);
+        unicode_text.styled_text(&TextStyle::SHADOW, text);
     }
 
     fn unicode_text_colored_with_shadow(
@@ -91,20 +62,7 @@ impl UnicodeTextWithShadowUi for imgui::Ui {
         color: impl Into<ImColor32>,
         text: &str,
     ) {
-        let pos = self.cursor_pos();
-        let color = color.into();
-        let color_vec = [
-            color.r as f32 / 255.0,
-            color.g as f32 / 255.0,
-            color.b as f32 / 255.0,
-            color.a as f32 / 255.0,
-        ];
-
-        self.set_cursor_pos([pos[0] + TEXT_SHADOW_OFFSET, pos[1] + TEXT_SHADOW_OFFSET]);
-        unicode_text.text_colored(TEXT_SHADOW_COLOR, text);
-
-        self.set_cursor_pos(pos);
-        unicode_text.text_colored(color_vec, text);
+        unicode_text.styled_text_colored(color, &TextStyle::SHADOW, text);
     }
 }
 
@@ -292,6 +250,14 @@ mod hotkey {
 }
 
 pub fn render_styled_panel<F>(ui: &imgui::Ui, id: &str, pos: [f32; 2], content: F)
+where
+    F: FnOnce(),
+{
+    render_styled_panel_pivot(ui, id, pos, [0.0, 0.0], content);
+}
+
+/// Same as `render_styled_panel` but positions the panel relative to the given pivot (0.0 - 1.0).
+pub fn render_styled_panel_pivot<F>(ui: &imgui::Ui, id: &str, pos: [f32; 2], pivot: [f32; 2], content: F)
 where
     F: FnOnce(),
 {
@@ -311,6 +277,7 @@ where
             imgui::WindowFlags::NO_FOCUS_ON_APPEARING
         )
         .position(pos, imgui::Condition::Always)
+        .position_pivot(pivot)
         .build(|| {
             content();
         });
@@ -0,0 +1,247 @@
+use std::{
+    cell::Cell,
+    collections::VecDeque,
+    fs::{
+        self,
+        File,
+        OpenOptions,
+    },
+    io::{
+        self,
+        Write,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        Mutex,
+        OnceLock,
+    },
+};
+
+use anyhow::Context;
+use log::{
+    Level,
+    LevelFilter,
+    Log,
+    Metadata,
+    Record,
+};
+
+const LOG_FILE_NAME: &str = "labh";
+/// Size after which the log file gets rotated
+const LOG_FILE_MAX_SIZE: u64 = 5 * 1024 * 1024;
+/// Amount of rotated log files to keep (labh.1.log - labh.N.log)
+const LOG_FILE_BACKUPS: usize = 4;
+
+/// Amount of log entries kept in memory for the log viewer
+const LOG_BUFFER_SIZE: usize = 1000;
+
+/// Path of the current log file, if file logging has been initialized
+static LOG_FILE_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Most recent log entries, oldest first
+static LOG_BUFFER: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+
+thread_local! {
+    /// Set while the current thread holds the log locks.
+    /// A panic within the logger would otherwise deadlock when the crash handler logs the panic.
+    static LOGGING: Cell<bool> = const { Cell::new(false) };
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// Local time formatted as HH:MM:SS.mmm
+    pub time: String,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+fn log_file_path(directory: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        directory.join(format!("{}.log", LOG_FILE_NAME))
+    } else {
+        directory.join(format!("{}.{}.log", LOG_FILE_NAME, index))
+    }
+}
+
+/// Log file which gets rotated once it exceeds [LOG_FILE_MAX_SIZE]
+struct RotatingLogFile {
+    directory: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl RotatingLogFile {
+    fn open(directory: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_file_path(directory, 0))?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            file,
+            size,
+        })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let _ = fs::remove_file(log_file_path(&self.directory, LOG_FILE_BACKUPS));
+        for index in (0..LOG_FILE_BACKUPS).rev() {
+            let source = log_file_path(&self.directory, index);
+            if source.exists() {
+                fs::rename(&source, log_file_path(&self.directory, index + 1))?;
+            }
+        }
+
+        self.file = File::create(log_file_path(&self.directory, 0))?;
+        self.size = 0;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        if self.size + line.len() as u64 > LOG_FILE_MAX_SIZE {
+            self.rotate()?;
+        }
+
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+/// Logs to the console via env_logger and additionally into the log file
+struct AppLogger {
+    console: env_logger::Logger,
+    file: Option<Mutex<RotatingLogFile>>,
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.console.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.console.matches(record) {
+            return;
+        }
+
+        if LOGGING.with(|logging| logging.replace(true)) {
+            /* the logger panicked on this thread, the locks might still be held */
+            return;
+        }
+
+        self.write_record(record);
+        LOGGING.with(|logging| logging.set(false));
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+        if let Some(Ok(mut file)) = self.file.as_ref().map(Mutex::lock) {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+impl AppLogger {
+    fn write_record(&self, record: &Record) {
+        self.console.log(record);
+
+        let now = chrono::Local::now();
+        let message = record.args().to_string();
+        if let Ok(mut buffer) = LOG_BUFFER.lock() {
+            if buffer.len() >= LOG_BUFFER_SIZE {
+                buffer.pop_front();
+            }
+
+            buffer.push_back(LogEntry {
+                time: now.format("%H:%M:%S%.3f").to_string(),
+                level: record.level(),
+                target: record.target().to_string(),
+                message: message.clone(),
+            });
+        }
+
+        let Some(file) = &self.file else {
+            return;
+        };
+
+        let line = format!(
+            "{} {:<5} [{}] {}\n",
+            now.format("%Y-%m-%d %H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            message
+        );
+        if let Ok(mut file) = file.lock() {
+            /* nowhere to report a failure to */
+            let _ = file.write_line(&line);
+        }
+    }
+}
+
+/// Initialize logging to the console and, if a directory has been given,
+/// into a size rotated log file within that directory.
+pub fn init_logging(level: LevelFilter, log_dir: Option<&Path>) {
+    let console = env_logger::builder()
+        .filter_level(level)
+        .parse_default_env()
+        .build();
+
+    let file = log_dir.and_then(|log_dir| {
+        let result = fs::create_dir_all(log_dir)
+            .and_then(|_| RotatingLogFile::open(log_dir))
+            .with_context(|| format!("failed to open log file in {}", log_dir.display()));
+
+        match result {
+            Ok(file) => {
+                let _ = LOG_FILE_PATH.set(log_file_path(log_dir, 0));
+                Some(Mutex::new(file))
+            }
+            Err(err) => {
+                eprintln!("{:#}", err);
+                None
+            }
+        }
+    });
+
+    log::set_max_level(console.filter());
+    let _ = log::set_boxed_logger(Box::new(AppLogger { console, file }));
+
+    if let Some(path) = LOG_FILE_PATH.get() {
+        log::info!("Logging to {}", path.display());
+    }
+}
+
+/// Path of the current log file
+pub fn current_log_file() -> Option<&'static Path> {
+    LOG_FILE_PATH.get().map(PathBuf::as_path)
+}
+
+/// The last lines of the current log file
+pub fn read_log_tail(line_count: usize) -> Option<String> {
+    let contents = fs::read_to_string(current_log_file()?).ok()?;
+    let lines = contents.lines().collect::<Vec<_>>();
+    Some(lines[lines.len().saturating_sub(line_count)..].join("\n"))
+}
+
+/// Copy of the most recent log entries, oldest first
+pub fn recent_log_entries() -> Vec<LogEntry> {
+    LOG_BUFFER
+        .lock()
+        .map(|buffer| buffer.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Recent log entries without waiting for the log buffer, None if it is currently locked.
+/// The crash handler uses this as the crashing thread might hold the lock.
+pub fn try_recent_log_entries() -> Option<Vec<LogEntry>> {
+    LOG_BUFFER
+        .try_lock()
+        .ok()
+        .map(|buffer| buffer.iter().cloned().collect())
+}
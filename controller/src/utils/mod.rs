@@ -7,6 +7,7 @@ use windows::{
     Win32::{
         Foundation::HWND,
         UI::{
+            HiDpi::GetDpiForSystem,
             Shell::ShellExecuteA,
             WindowsAndMessaging::SW_SHOW,
         },
@@ -19,9 +20,37 @@ pub use self::imgui::*;
 mod console_io;
 pub use console_io::*;
 
+mod logging;
+pub use logging::*;
+
 mod fs;
 pub use fs::*;
 
+mod anonymize;
+pub use anonymize::*;
+
+mod steam;
+pub use steam::*;
+
+mod weapon_icons;
+pub use weapon_icons::*;
+
+mod hit_test;
+pub use hit_test::*;
+
+/// Meters per game unit, used for all distances and speeds shown to the user
+pub const UNITS_TO_METERS: f32 = 0.01905;
+
+/// Scale of the system DPI relative to the default of 96 DPI
+pub fn system_ui_scale() -> f32 {
+    let dpi = unsafe { GetDpiForSystem() };
+    if dpi == 0 {
+        return 1.0;
+    }
+
+    dpi as f32 / 96.0
+}
+
 #[allow(unused)]
 pub fn open_url(url: &str) {
     unsafe {
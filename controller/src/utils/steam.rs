@@ -0,0 +1,397 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        mpsc::{
+            self,
+            Receiver,
+            Sender,
+        },
+        Mutex,
+        PoisonError,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+use imgui::TextureId;
+use overlay::SystemRuntimeController;
+use serde::Deserialize;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use crate::settings::AppSettings;
+
+const PLAYER_SUMMARIES_URL: &str = "https://api.steampowered.com/ISteamUser/GetPlayerSummaries/v2/";
+
+/// Max amount of SteamIDs accepted by a single GetPlayerSummaries call
+const MAX_IDS_PER_REQUEST: usize = 100;
+
+/// Max amount of cached profiles, the least recently used ones are dropped first
+const MAX_CACHED_PROFILES: usize = 256;
+
+/// Delay before fetching a profile again after the first failure.
+/// The delay doubles with every further failure up to `RETRY_DELAY_MAX`.
+const RETRY_DELAY_MIN: Duration = Duration::from_secs(10);
+const RETRY_DELAY_MAX: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone)]
+pub struct SteamProfile {
+    pub persona_name: String,
+    pub avatar: Option<TextureId>,
+}
+
+enum ProfileEntry {
+    Pending,
+    Loaded {
+        profile: SteamProfile,
+        /// Decoded avatar which still needs to be uploaded
+        avatar_data: Option<AvatarImage>,
+    },
+    Failed {
+        retry_at: Instant,
+    },
+}
+
+struct CachedProfile {
+    entry: ProfileEntry,
+    last_access: Instant,
+    /// Failed fetches since the profile has last been loaded
+    failures: u32,
+}
+
+impl CachedProfile {
+    fn avatar(&self) -> Option<TextureId> {
+        match &self.entry {
+            ProfileEntry::Loaded { profile, .. } => profile.avatar,
+            _ => None,
+        }
+    }
+}
+
+fn retry_delay(failures: u32) -> Duration {
+    RETRY_DELAY_MIN
+        .saturating_mul(1 << failures.saturating_sub(1).min(16))
+        .min(RETRY_DELAY_MAX)
+}
+
+struct AvatarImage {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+struct FetchedProfile {
+    steam_id: u64,
+    persona_name: String,
+    avatar: Option<AvatarImage>,
+}
+
+type FetchResult = (Vec<u64>, Result<Vec<FetchedProfile>>);
+
+#[derive(Deserialize)]
+struct PlayerSummariesResponse {
+    response: PlayerSummaries,
+}
+
+#[derive(Deserialize)]
+struct PlayerSummaries {
+    players: Vec<PlayerSummary>,
+}
+
+#[derive(Deserialize)]
+struct PlayerSummary {
+    steamid: String,
+    personaname: String,
+    #[serde(default)]
+    avatarmedium: String,
+}
+
+async fn fetch_avatar(client: &reqwest::Client, url: &str) -> Result<AvatarImage> {
+    let bytes = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    let image = image::load_from_memory(&bytes)
+        .context("decode avatar")?
+        .to_rgba8();
+    let (width, height) = image.dimensions();
+    Ok(AvatarImage {
+        data: image.into_raw(),
+        width,
+        height,
+    })
+}
+
+async fn fetch_profiles(api_key: String, steam_ids: &[u64]) -> Result<Vec<FetchedProfile>> {
+    let client = reqwest::Client::new();
+    let steam_ids = steam_ids
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let response = client
+        .get(PLAYER_SUMMARIES_URL)
+        .query(&[("key", api_key.as_str()), ("steamids", steam_ids.as_str())])
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let response = serde_json::from_str::<PlayerSummariesResponse>(&response)
+        .context("parse player summaries")?;
+
+    let mut profiles = Vec::with_capacity(response.response.players.len());
+    for player in response.response.players {
+        let steam_id = player
+            .steamid
+            .parse::<u64>()
+            .with_context(|| format!("invalid SteamID {}", player.steamid))?;
+
+        let avatar = if player.avatarmedium.is_empty() {
+            None
+        } else {
+            match fetch_avatar(&client, &player.avatarmedium).await {
+                Ok(avatar) => Some(avatar),
+                Err(err) => {
+                    log::warn!("Failed to fetch Steam avatar of {}: {:#}", steam_id, err);
+                    None
+                }
+            }
+        };
+
+        profiles.push(FetchedProfile {
+            steam_id,
+            persona_name: player.personaname,
+            avatar,
+        });
+    }
+
+    Ok(profiles)
+}
+
+/// Persona names and avatars of players resolved via the Steam Web API.
+/// Profiles are requested on first access and fetched in the background.
+/// Failed fetches are retried with an increasing delay.
+pub struct StateSteamProfiles {
+    profiles: HashMap<u64, CachedProfile>,
+    queued: Vec<u64>,
+    /// Avatar textures of dropped profiles which still need to be freed
+    released_avatars: Vec<TextureId>,
+
+    result_sender: Sender<FetchResult>,
+    /// Only accessed mutably, the mutex makes the state Sync
+    result_receiver: Mutex<Receiver<FetchResult>>,
+}
+
+impl StateSteamProfiles {
+    pub fn profile(&mut self, steam_id: u64) -> Option<&SteamProfile> {
+        let now = Instant::now();
+        let cached = self.profiles.entry(steam_id).or_insert_with(|| {
+            self.queued.push(steam_id);
+            CachedProfile {
+                entry: ProfileEntry::Pending,
+                last_access: now,
+                failures: 0,
+            }
+        });
+        cached.last_access = now;
+
+        if let ProfileEntry::Failed { retry_at } = cached.entry {
+            if retry_at <= now {
+                cached.entry = ProfileEntry::Pending;
+                self.queued.push(steam_id);
+            }
+        }
+
+        match &cached.entry {
+            ProfileEntry::Loaded { profile, .. } => Some(profile),
+            _ => None,
+        }
+    }
+
+    /// Drop all cached profiles, e.g. after the API key has been changed.
+    /// Avatar textures are freed with the next update.
+    pub fn clear(&mut self) {
+        self.released_avatars
+            .extend(self.profiles.values().filter_map(CachedProfile::avatar));
+        self.profiles.clear();
+        self.queued.clear();
+    }
+
+    /// Drop the least recently used profiles exceeding the cache size
+    fn evict_profiles(&mut self) {
+        if self.profiles.len() <= MAX_CACHED_PROFILES {
+            return;
+        }
+
+        let mut candidates = self
+            .profiles
+            .iter()
+            .filter(|(_, cached)| !matches!(cached.entry, ProfileEntry::Pending))
+            .map(|(steam_id, cached)| (cached.last_access, *steam_id))
+            .collect::<Vec<_>>();
+        candidates.sort_unstable();
+
+        let excess = self.profiles.len() - MAX_CACHED_PROFILES;
+        for (_, steam_id) in candidates.into_iter().take(excess) {
+            if let Some(cached) = self.profiles.remove(&steam_id) {
+                self.released_avatars.extend(cached.avatar());
+            }
+        }
+    }
+
+    fn apply_fetch_result(&mut self, steam_ids: Vec<u64>, result: Result<Vec<FetchedProfile>>) {
+        let profiles = match result {
+            Ok(profiles) => profiles,
+            Err(err) => {
+                log::warn!("Failed to fetch Steam profiles: {:#}", err);
+                Vec::new()
+            }
+        };
+
+        for profile in profiles {
+            let Some(cached) = self.profiles.get_mut(&profile.steam_id) else {
+                /* dropped while being fetched */
+                continue;
+            };
+
+            cached.failures = 0;
+            cached.entry = ProfileEntry::Loaded {
+                profile: SteamProfile {
+                    persona_name: profile.persona_name,
+                    avatar: None,
+                },
+                avatar_data: profile.avatar,
+            };
+        }
+
+        let now = Instant::now();
+        for steam_id in steam_ids {
+            let Some(cached) = self.profiles.get_mut(&steam_id) else {
+                continue;
+            };
+            if matches!(cached.entry, ProfileEntry::Pending) {
+                /* the request failed or the profile is missing in the response */
+                cached.failures += 1;
+                cached.entry = ProfileEntry::Failed {
+                    retry_at: now + retry_delay(cached.failures),
+                };
+            }
+        }
+    }
+
+    /// Start fetching the queued profiles, apply finished fetches and upload the received avatars.
+    pub fn update(&mut self, controller: &mut SystemRuntimeController, api_key: Option<&str>) {
+        if let Some(api_key) = api_key.filter(|key| !key.is_empty()) {
+            let queued = std::mem::take(&mut self.queued);
+            for steam_ids in queued.chunks(MAX_IDS_PER_REQUEST) {
+                let steam_ids = steam_ids.to_vec();
+                let api_key = api_key.to_string();
+                let result_sender = self.result_sender.clone();
+                tokio::spawn(async move {
+                    let result = fetch_profiles(api_key, &steam_ids).await;
+                    let _ = result_sender.send((steam_ids, result));
+                });
+            }
+        }
+
+        while let Ok((steam_ids, result)) = self
+            .result_receiver
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .try_recv()
+        {
+            self.apply_fetch_result(steam_ids, result);
+        }
+
+        for (steam_id, cached) in self.profiles.iter_mut() {
+            let ProfileEntry::Loaded { avatar_data, .. } = &mut cached.entry else {
+                continue;
+            };
+            if let Some(avatar) = avatar_data.take() {
+                controller.queue_texture_upload(
+                    *steam_id,
+                    avatar.data,
+                    avatar.width,
+                    avatar.height,
+                );
+            }
+        }
+
+        for (steam_id, texture_id) in controller.take_uploaded_textures() {
+            match self
+                .profiles
+                .get_mut(&steam_id)
+                .map(|cached| &mut cached.entry)
+            {
+                Some(ProfileEntry::Loaded { profile, .. }) => profile.avatar = Some(texture_id),
+                /* the profile has been dropped while the avatar was uploaded */
+                _ => self.released_avatars.push(texture_id),
+            }
+        }
+
+        self.evict_profiles();
+        for texture_id in self.released_avatars.drain(..) {
+            controller.queue_texture_removal(texture_id);
+        }
+    }
+}
+
+impl State for StateSteamProfiles {
+    type Parameter = ();
+
+    fn create(_states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        let (result_sender, result_receiver) = mpsc::channel();
+        Ok(Self {
+            profiles: Default::default(),
+            queued: Default::default(),
+            released_avatars: Default::default(),
+
+            result_sender,
+            result_receiver: Mutex::new(result_receiver),
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
+
+/// Steam profile of the player with the given SteamID64.
+/// Returns None while the profile is being fetched, no API key has been configured
+/// or the clip safe mode is enabled.
+pub fn steam_profile(states: &StateRegistry, steam_id: u64) -> Option<SteamProfile> {
+    with_steam_profile(states, steam_id, SteamProfile::clone)
+}
+
+/// Same as [steam_profile] but borrows the profile instead of cloning it.
+/// The profile cache stays locked while `f` is executed.
+pub fn with_steam_profile<R>(
+    states: &StateRegistry,
+    steam_id: u64,
+    f: impl FnOnce(&SteamProfile) -> R,
+) -> Option<R> {
+    {
+        let settings = states.resolve::<AppSettings>(()).ok()?;
+        if settings.clip_safe_mode || settings.steam_api_key.as_deref().unwrap_or_default().is_empty() {
+            return None;
+        }
+    }
+
+    let mut profiles = states.resolve_mut::<StateSteamProfiles>(()).ok()?;
+    profiles.profile(steam_id).map(f)
+}
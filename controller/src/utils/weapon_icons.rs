@@ -0,0 +1,292 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::Arc,
+};
+
+use anyhow::Context;
+use cs2::WeaponId;
+use imgui::TextureId;
+use tiny_skia::Pixmap;
+
+/// Max width of the weapon icon atlas texture
+const ATLAS_MAX_WIDTH: u32 = 2048;
+
+/// Space between two icons in the atlas to avoid bleeding when sampling
+const ATLAS_PADDING: u32 = 2;
+
+/// Scale at which the SVG icons are rasterized
+const SVG_RENDER_SCALE: f32 = 2.0;
+
+/// Location of a single icon within the weapon icon atlas
+#[derive(Debug, Clone, Copy)]
+pub struct WeaponIcon {
+    pub texture_id: TextureId,
+    pub uv_min: [f32; 2],
+    pub uv_max: [f32; 2],
+    /// Width divided by the height of the icon
+    pub aspect_ratio: f32,
+}
+
+/// All weapon icons packed into a single texture
+#[derive(Clone, Default)]
+pub struct WeaponIconAtlas {
+    icons: HashMap<String, WeaponIcon>,
+}
+
+impl WeaponIconAtlas {
+    pub fn get(&self, name: &str) -> Option<&WeaponIcon> {
+        self.icons.get(name)
+    }
+
+    pub fn weapon(&self, weapon: WeaponId) -> Option<&WeaponIcon> {
+        self.get(weapon.icon_name())
+    }
+
+    pub fn len(&self) -> usize {
+        self.icons.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.icons.is_empty()
+    }
+
+    /// Texture containing all icons, None if no icons have been loaded
+    pub fn texture_id(&self) -> Option<TextureId> {
+        self.icons.values().next().map(|icon| icon.texture_id)
+    }
+}
+
+struct IconImage {
+    name: String,
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+/// Load an icon and tint it white, so it can be colored when rendering.
+fn load_icon_image(
+    path: &Path,
+    svg_options: &usvg::Options,
+) -> anyhow::Result<(u32, u32, Vec<u8>)> {
+    let extension = path
+        .extension()
+        .and_then(|value| value.to_str())
+        .map(str::to_ascii_lowercase);
+
+    match extension.as_deref() {
+        Some("svg") => {
+            let svg_data = std::fs::read(path)?;
+            let tree = usvg::Tree::from_data(&svg_data, svg_options)?;
+
+            let size = tree.size().to_int_size();
+            let width = (size.width() as f32 * SVG_RENDER_SCALE) as u32;
+            let height = (size.height() as f32 * SVG_RENDER_SCALE) as u32;
+            let mut pixmap = Pixmap::new(width, height).context("invalid svg size")?;
+
+            let transform = tiny_skia::Transform::from_scale(SVG_RENDER_SCALE, SVG_RENDER_SCALE);
+            resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+            let mut rgba = pixmap.take();
+            for pixel in rgba.chunks_mut(4) {
+                if pixel[3] > 0 {
+                    pixel[0] = 255;
+                    pixel[1] = 255;
+                    pixel[2] = 255;
+                }
+            }
+
+            Ok((width, height, rgba))
+        }
+        Some("png") => {
+            let image = image::open(path)?.to_rgba8();
+            let (width, height) = image.dimensions();
+            Ok((width, height, image.into_raw()))
+        }
+        _ => anyhow::bail!("unsupported icon format"),
+    }
+}
+
+/// Position of each icon using a simple shelf packing.
+/// The atlas is only wider than [ATLAS_MAX_WIDTH] if a single icon exceeds it.
+/// Returns the icon positions and the total size of the atlas.
+fn pack_icons(icons: &[IconImage]) -> (Vec<[u32; 2]>, [u32; 2]) {
+    let widest_icon = icons
+        .iter()
+        .map(|icon| icon.width + ATLAS_PADDING)
+        .max()
+        .unwrap_or(0);
+    let total_width = icons
+        .iter()
+        .map(|icon| icon.width + ATLAS_PADDING)
+        .sum::<u32>();
+    let atlas_width = total_width.min(ATLAS_MAX_WIDTH).max(widest_icon);
+
+    let mut positions = Vec::with_capacity(icons.len());
+    let mut cursor = [0, 0];
+    let mut shelf_height = 0;
+    for icon in icons {
+        if cursor[0] + icon.width + ATLAS_PADDING > atlas_width {
+            cursor = [0, cursor[1] + shelf_height];
+            shelf_height = 0;
+        }
+
+        positions.push(cursor);
+        cursor[0] += icon.width + ATLAS_PADDING;
+        shelf_height = shelf_height.max(icon.height + ATLAS_PADDING);
+    }
+
+    (positions, [atlas_width, cursor[1] + shelf_height])
+}
+
+/// Load all SVG and PNG icons of a directory and pack them into a single texture.
+/// Icons are referenced by their file name without the extension.
+pub fn load_weapon_icon_atlas(
+    directory: &Path,
+    upload: impl FnOnce(&[u8], u32, u32) -> anyhow::Result<TextureId>,
+) -> anyhow::Result<WeaponIconAtlas> {
+    let mut fontdb = usvg::fontdb::Database::new();
+    fontdb.load_system_fonts();
+
+    let mut svg_options = usvg::Options::default();
+    svg_options.fontdb = Arc::new(fontdb);
+
+    let mut icons = Vec::new();
+    for entry in std::fs::read_dir(directory)?.flatten() {
+        let path = entry.path();
+        let Some(name) = path.file_stem().and_then(|value| value.to_str()) else {
+            continue;
+        };
+
+        match load_icon_image(&path, &svg_options) {
+            Ok((width, height, rgba)) => icons.push(IconImage {
+                name: name.to_string(),
+                width,
+                height,
+                rgba,
+            }),
+            Err(error) => log::warn!("Failed to load weapon icon {}: {:#}", path.display(), error),
+        }
+    }
+
+    if icons.is_empty() {
+        return Ok(WeaponIconAtlas::default());
+    }
+
+    /* packing the highest icons first keeps the shelves dense */
+    icons.sort_by(|a, b| b.height.cmp(&a.height));
+    let (positions, [atlas_width, atlas_height]) = pack_icons(&icons);
+
+    let mut atlas = vec![0u8; atlas_width as usize * atlas_height as usize * 4];
+    for (icon, [x, y]) in icons.iter().zip(positions.iter()) {
+        let row_length = icon.width as usize * 4;
+        for row in 0..icon.height as usize {
+            let target = ((*y as usize + row) * atlas_width as usize + *x as usize) * 4;
+            atlas[target..target + row_length]
+                .copy_from_slice(&icon.rgba[row * row_length..(row + 1) * row_length]);
+        }
+    }
+
+    let texture_id = upload(&atlas, atlas_width, atlas_height)
+        .context("failed to upload the weapon icon atlas")?;
+
+    let icons = icons
+        .into_iter()
+        .zip(positions)
+        .map(|(icon, [x, y])| {
+            let uv_min = [
+                x as f32 / atlas_width as f32,
+                y as f32 / atlas_height as f32,
+            ];
+            let uv_max = [
+                (x + icon.width) as f32 / atlas_width as f32,
+                (y + icon.height) as f32 / atlas_height as f32,
+            ];
+
+            let weapon_icon = WeaponIcon {
+                texture_id,
+                uv_min,
+                uv_max,
+                aspect_ratio: icon.width as f32 / icon.height.max(1) as f32,
+            };
+            (icon.name, weapon_icon)
+        })
+        .collect();
+
+    Ok(WeaponIconAtlas { icons })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        pack_icons,
+        IconImage,
+        ATLAS_MAX_WIDTH,
+        ATLAS_PADDING,
+    };
+
+    fn icon(width: u32, height: u32) -> IconImage {
+        IconImage {
+            name: String::new(),
+            width,
+            height,
+            rgba: Vec::new(),
+        }
+    }
+
+    fn assert_packed(icons: &[IconImage], positions: &[[u32; 2]], [width, height]: [u32; 2]) {
+        assert_eq!(icons.len(), positions.len());
+        for (index, (icon, [x, y])) in icons.iter().zip(positions).enumerate() {
+            assert!(x + icon.width <= width && y + icon.height <= height);
+
+            for (other, [other_x, other_y]) in icons.iter().zip(positions).skip(index + 1) {
+                let overlaps = *x < other_x + other.width
+                    && *other_x < x + icon.width
+                    && *y < other_y + other.height
+                    && *other_y < y + icon.height;
+                assert!(
+                    !overlaps,
+                    "icons at {:?} and {:?} overlap",
+                    [x, y],
+                    [other_x, other_y]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn pack_single_shelf() {
+        let icons = [icon(64, 32), icon(48, 32), icon(32, 16)];
+        let (positions, size) = pack_icons(&icons);
+
+        assert_eq!(size, [64 + 48 + 32 + 3 * ATLAS_PADDING, 32 + ATLAS_PADDING]);
+        assert_eq!(positions[1], [64 + ATLAS_PADDING, 0]);
+        assert_packed(&icons, &positions, size);
+    }
+
+    #[test]
+    fn pack_max_width() {
+        let icons = (0..100).map(|_| icon(100, 40)).collect::<Vec<_>>();
+        let (positions, size) = pack_icons(&icons);
+
+        assert!(size[0] <= ATLAS_MAX_WIDTH);
+        let icons_per_shelf = ATLAS_MAX_WIDTH / (100 + ATLAS_PADDING);
+        let shelves = 100u32.div_ceil(icons_per_shelf);
+        assert_eq!(size[1], shelves * (40 + ATLAS_PADDING));
+        assert_packed(&icons, &positions, size);
+    }
+
+    #[test]
+    fn pack_oversized_icon() {
+        let icons = [icon(ATLAS_MAX_WIDTH + 10, 32), icon(32, 32)];
+        let (positions, size) = pack_icons(&icons);
+
+        assert_eq!(size[0], ATLAS_MAX_WIDTH + 10 + ATLAS_PADDING);
+        assert_packed(&icons, &positions, size);
+    }
+
+    #[test]
+    fn pack_empty() {
+        assert_eq!(pack_icons(&[]), (vec![], [0, 0]));
+    }
+}
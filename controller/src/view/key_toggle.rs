@@ -1,7 +1,18 @@
-use std::time::Instant;
+use std::{
+    collections::BTreeMap,
+    time::Instant,
+};
+
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
 
 use crate::{
     settings::{
+        FeatureHotkey,
+        FeatureToggle,
         HotKey,
         KeyToggleMode,
     },
@@ -65,3 +76,61 @@ impl KeyToggle {
         true
     }
 }
+
+/// Current state of the hotkey driven features.
+/// Published by the enhancements owning the toggles on every update.
+#[derive(Debug, Default)]
+pub struct StateActiveToggles {
+    pub esp: bool,
+    pub trigger_bot: bool,
+    /// State of the features with a bound hotkey
+    pub features: BTreeMap<FeatureToggle, bool>,
+}
+
+impl StateActiveToggles {
+    /// Features without a bound hotkey are always active
+    pub fn feature_active(&self, feature: FeatureToggle) -> bool {
+        self.features.get(&feature).copied().unwrap_or(true)
+    }
+}
+
+/// Key toggles of all features with a configurable hotkey
+#[derive(Default)]
+pub struct FeatureKeyToggles {
+    toggles: BTreeMap<FeatureToggle, KeyToggle>,
+}
+
+impl FeatureKeyToggles {
+    pub fn update(
+        &mut self,
+        hotkeys: &BTreeMap<FeatureToggle, FeatureHotkey>,
+        input: &dyn KeyboardInput,
+        active_toggles: &mut StateActiveToggles,
+    ) {
+        self.toggles
+            .retain(|feature, _| hotkeys.get(feature).map_or(false, |hotkey| hotkey.key.is_some()));
+        active_toggles.features.clear();
+
+        for (feature, hotkey) in hotkeys.iter() {
+            if hotkey.key.is_none() {
+                continue;
+            }
+
+            let toggle = self.toggles.entry(*feature).or_insert_with(KeyToggle::new);
+            toggle.update(&hotkey.mode, input, &hotkey.key);
+            active_toggles.features.insert(*feature, toggle.enabled);
+        }
+    }
+}
+
+impl State for StateActiveToggles {
+    type Parameter = ();
+
+    fn create(_states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        Ok(Default::default())
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
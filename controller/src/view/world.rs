@@ -55,8 +55,7 @@ impl State for ViewController {
     fn update(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
         // Resolve the Volatile StateViewMatrix to get fresh data
         let matrix_state = states.resolve::<StateViewMatrix>(())?;
-        self.view_matrix = matrix_state.matrix;
-        Ok(())
+        self.apply_view_matrix(states, matrix_state.matrix)
     }
 }
 
@@ -64,7 +63,24 @@ impl ViewController {
     pub fn refresh_view_matrix(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
         let cs2 = states.resolve::<StateCS2Handle>(())?;
         let offset = states.resolve::<StateResolvedOffset>(CS2Offset::ViewMatrix)?;
-        self.view_matrix = cs2.read_sized(offset.address)?;
+        let matrix = cs2.read_sized(offset.address)?;
+        self.apply_view_matrix(states, matrix)
+    }
+
+    /// Use the new view matrix unless it contains invalid values (e.g. due to a bad read).
+    /// In that case the previous view matrix will be kept.
+    fn apply_view_matrix(
+        &mut self,
+        states: &StateRegistry,
+        matrix: nalgebra::Matrix4<f32>,
+    ) -> anyhow::Result<()> {
+        if !matrix.iter().all(|value| value.is_finite()) {
+            log::trace!("Rejected view matrix with non finite values");
+            states.resolve::<StateCS2Handle>(())?.record_rejected_values(1);
+            return Ok(());
+        }
+
+        self.view_matrix = matrix;
         Ok(())
     }
 }
@@ -99,6 +115,39 @@ impl ViewController {
         Some(nalgebra::Vector3::new(-x, -y, -z))
     }
 
+    /// Calculate the world space ray (origin, normalized direction) which
+    /// passes through the given screen position.
+    pub fn screen_to_world_ray(
+        &self,
+        screen_pos: &mint::Vector2<f32>,
+    ) -> Option<(nalgebra::Vector3<f32>, nalgebra::Vector3<f32>)> {
+        let inverse = self.view_matrix.try_inverse()?;
+
+        let ndc_x = (screen_pos.x - self.offset.x) / self.screen_bounds.x * 2.0 - 1.0;
+        let ndc_y = 1.0 - (screen_pos.y - self.offset.y) / self.screen_bounds.y * 2.0;
+        let unproject = |depth: f32| {
+            let world = nalgebra::Vector4::new(ndc_x, ndc_y, depth, 1.0).transpose() * inverse;
+            if world.w.abs() < 0.0001 {
+                return None;
+            }
+
+            Some(nalgebra::Vector3::new(
+                world.x / world.w,
+                world.y / world.w,
+                world.z / world.w,
+            ))
+        };
+
+        let target = unproject(0.5)?;
+        let origin = match self.get_camera_world_position() {
+            Some(origin) => origin,
+            None => unproject(0.0)?,
+        };
+
+        let direction = (target - origin).try_normalize(0.0001)?;
+        Some((origin, direction))
+    }
+
     /// Returning an mint::Vector2<f32> as the result should be used via ImGui.
     pub fn world_to_screen(
         &self,
@@ -140,7 +189,6 @@ impl ViewController {
         vmax: &nalgebra::Vector3<f32>,
     ) -> Option<(nalgebra::Vector2<f32>, nalgebra::Vector2<f32>)> {
         type Vec3 = nalgebra::Vector3<f32>;
-        type Vec2 = nalgebra::Vector2<f32>;
 
         let points = [
             /* bottom */
@@ -155,6 +203,17 @@ impl ViewController {
             Vec3::new(vmax.x, vmax.y, vmax.z),
         ];
 
+        self.calculate_bounds_2d(points)
+    }
+
+    /// Screen space bounds of the given world positions.
+    /// Returns None if the bounds are empty.
+    pub fn calculate_bounds_2d(
+        &self,
+        points: impl IntoIterator<Item = nalgebra::Vector3<f32>>,
+    ) -> Option<(nalgebra::Vector2<f32>, nalgebra::Vector2<f32>)> {
+        type Vec2 = nalgebra::Vector2<f32>;
+
         let mut min2d = Vec2::new(f32::MAX, f32::MAX);
         let mut max2d = Vec2::new(-f32::MAX, -f32::MAX);
 
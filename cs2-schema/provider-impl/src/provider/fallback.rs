@@ -0,0 +1,35 @@
+use cs2_schema_provider::{
+    OffsetInfo,
+    SchemaProvider,
+};
+
+/// Resolves offsets through the inner provider and falls back to the
+/// offsets the controller has been built with for members the inner provider does not know.
+/// Keeps the core features working while the schema system layout changed after a game update.
+pub struct FallbackSchemaProvider {
+    inner: Box<dyn SchemaProvider>,
+}
+
+impl FallbackSchemaProvider {
+    pub fn new(inner: Box<dyn SchemaProvider>) -> Self {
+        Self { inner }
+    }
+}
+
+impl SchemaProvider for FallbackSchemaProvider {
+    fn resolve_offset(&self, offset: &OffsetInfo) -> Option<u64> {
+        if let Some(value) = self.inner.resolve_offset(offset) {
+            return Some(value);
+        }
+
+        /* offsets are cached by the resolver, hence this is only logged once per member */
+        log::warn!(
+            "Missing schema offset {}::{}.{}. Using built-in offset 0x{:X}.",
+            offset.module,
+            offset.class_name,
+            offset.member,
+            offset.default_value
+        );
+        Some(offset.default_value)
+    }
+}
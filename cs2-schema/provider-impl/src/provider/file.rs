@@ -1,14 +1,19 @@
 use std::{
     collections::BTreeMap,
     fs::File,
-    io::BufReader,
+    io::{
+        BufReader,
+        BufWriter,
+    },
     path::Path,
 };
 
 use anyhow::Context;
 use cs2::{
     CS2Offset,
+    StateBuildInfo,
     StatePredefinedOffset,
+    StateResolvedOffset,
 };
 use cs2_schema_definition::{
     DumpedSchema,
@@ -23,6 +28,7 @@ use utils_state::StateRegistry;
 use super::{
     CachedOffset,
     CachedSchemaProvider,
+    FallbackSchemaProvider,
 };
 
 pub struct FileSchemaProvider {
@@ -58,14 +64,19 @@ impl SchemaProvider for FileSchemaProvider {
     }
 }
 
-pub fn setup_schema_from_file(states: &mut StateRegistry, file: &Path) -> anyhow::Result<()> {
+/// Setup the schema and offsets from a dumped schema file.
+/// Members missing in the file resolve to the built-in offsets.
+/// Returns the CS2 revision the schema has been dumped from.
+pub fn setup_schema_from_file(states: &mut StateRegistry, file: &Path) -> anyhow::Result<String> {
     let file = File::open(file).context("open file")?;
     let reader = BufReader::new(file);
     let schema = serde_json::from_reader::<_, DumpedSchema>(reader).context("parse schema file")?;
 
     {
         let provider = FileSchemaProvider::new(&schema.scopes)?;
-        cs2_schema_provider::setup_provider(Box::new(provider));
+        cs2_schema_provider::setup_provider(Box::new(FallbackSchemaProvider::new(Box::new(
+            provider,
+        ))));
     }
 
     for offset in CS2Offset::available_offsets() {
@@ -83,5 +94,35 @@ pub fn setup_schema_from_file(states: &mut StateRegistry, file: &Path) -> anyhow
         }
     }
 
-    Ok(())
+    Ok(schema.cs2_revision)
+}
+
+/// Dump the schema and offsets of the running game into a file which can be loaded with [setup_schema_from_file].
+/// Only the client.dll and global scopes are dumped as they contain all offsets used at runtime.
+/// Returns the CS2 revision the schema has been dumped from.
+pub fn dump_schema_to_file(states: &StateRegistry, file: &Path) -> anyhow::Result<String> {
+    let mut schema = DumpedSchema::default();
+    schema.scopes =
+        cs2::dump_schema(states, Some(&["client.dll", "!GlobalTypes"])).context("dump schema")?;
+
+    for offset in CS2Offset::available_offsets() {
+        let resolved = states
+            .resolve::<StateResolvedOffset>(*offset)
+            .with_context(|| format!("resolving offset {}", offset.cache_name()))?;
+
+        schema
+            .resolved_offsets
+            .insert(offset.cache_name().to_string(), resolved.offset);
+    }
+
+    {
+        let build_info = states.resolve::<StateBuildInfo>(())?;
+        schema.cs2_build_datetime = build_info.build_datetime.clone();
+        schema.cs2_revision = build_info.revision.clone();
+    }
+
+    let output = File::create(file).context("create file")?;
+    serde_json::to_writer_pretty(BufWriter::new(output), &schema).context("write schema")?;
+
+    Ok(schema.cs2_revision)
 }
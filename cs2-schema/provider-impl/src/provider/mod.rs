@@ -4,6 +4,9 @@ pub use default::*;
 mod cached;
 pub use cached::*;
 
+mod fallback;
+pub use fallback::*;
+
 mod file;
 pub use file::*;
 
@@ -2,4 +2,7 @@ mod provider;
 pub use provider::*;
 
 mod resolver;
-pub use resolver::resolve_offset;
+pub use resolver::{
+    resolve_offset,
+    ResolvedOffset,
+};
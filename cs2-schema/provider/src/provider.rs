@@ -1,4 +1,10 @@
-use std::sync::RwLock;
+use std::sync::{
+    atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+    RwLock,
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct OffsetInfo {
@@ -14,7 +20,12 @@ pub trait SchemaProvider: Send + Sync {
 
 pub(crate) static PROVIDER_INSTANCE: RwLock<Option<Box<dyn SchemaProvider>>> = RwLock::new(None);
 
+/// Incremented every time a provider is set up.
+/// Offsets resolved with a previous provider will be resolved again.
+pub(crate) static PROVIDER_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
 pub fn setup_provider(provider: Box<dyn SchemaProvider>) {
     let mut instance = PROVIDER_INSTANCE.write().unwrap();
     *instance = Some(provider);
+    PROVIDER_GENERATION.fetch_add(1, Ordering::AcqRel);
 }
@@ -1,16 +1,21 @@
+use std::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+
 use crate::{
     OffsetInfo,
+    PROVIDER_GENERATION,
     PROVIDER_INSTANCE,
 };
 
 #[macro_export]
 macro_rules! runtime_offset {
     ($default_value:expr, $module:expr, $class_name:expr, $class_member:expr) => {{
-        static mut RESOLVED_OFFSET: Option<u64> = None;
+        static RESOLVED_OFFSET: $crate::ResolvedOffset = $crate::ResolvedOffset::new();
 
-        #[allow(static_mut_refs)]
         $crate::resolve_offset(
-            unsafe { &mut RESOLVED_OFFSET },
+            &RESOLVED_OFFSET,
             &$crate::OffsetInfo {
                 default_value: $default_value,
                 module: $module,
@@ -20,24 +25,126 @@ macro_rules! runtime_offset {
         )
     }};
 }
-pub fn resolve_offset(cache: &mut Option<u64>, offset: &OffsetInfo) -> u64 {
-    *cache.get_or_insert_with(|| {
-        log::trace!(
-            "Resolving offset {}::{}.{}",
-            offset.module,
-            offset.class_name,
-            offset.member
+
+/// Cache of a resolved offset, shared between all threads.
+/// Holds the provider generation in the upper and the offset in the lower 32 bits,
+/// so both are always loaded and stored together.
+pub struct ResolvedOffset(AtomicU64);
+
+impl ResolvedOffset {
+    pub const fn new() -> Self {
+        /* generation zero: no provider has been set up */
+        Self(AtomicU64::new(0))
+    }
+}
+
+impl Default for ResolvedOffset {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resolve the offset with the current schema provider.
+/// The cache holds the provider generation the offset has been resolved with.
+pub fn resolve_offset(cache: &ResolvedOffset, offset: &OffsetInfo) -> u64 {
+    let generation = PROVIDER_GENERATION.load(Ordering::Acquire) as u32;
+    let cached = cache.0.load(Ordering::Relaxed);
+    if (cached >> 32) as u32 == generation {
+        return cached & 0xFFFF_FFFF;
+    }
+
+    log::trace!(
+        "Resolving offset {}::{}.{}",
+        offset.module,
+        offset.class_name,
+        offset.member
+    );
+    let instance = PROVIDER_INSTANCE.read().unwrap();
+    let Some(instance) = instance.as_ref() else {
+        panic!("no schema provider set");
+    };
+
+    let Some(value) = instance.resolve_offset(offset) else {
+        panic!("could not resolve offset for {:?}", offset);
+    };
+
+    log::trace!(" -> 0x{:X}", value);
+
+    /* class member offsets fit into 32 bits, larger values are resolved every time */
+    if let Ok(value) = u32::try_from(value) {
+        cache.0.store(
+            ((generation as u64) << 32) | value as u64,
+            Ordering::Relaxed,
         );
-        let instance = PROVIDER_INSTANCE.read().unwrap();
-        let Some(instance) = instance.as_ref() else {
-            panic!("no schema provider set");
-        };
-
-        let Some(value) = instance.resolve_offset(offset) else {
-            panic!("could not resolve offset for {:?}", offset);
-        };
-
-        log::trace!(" -> 0x{:X}", value);
-        value
-    })
+    }
+    value
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    };
+
+    use super::{
+        resolve_offset,
+        ResolvedOffset,
+    };
+    use crate::{
+        setup_provider,
+        OffsetInfo,
+        SchemaProvider,
+    };
+
+    /// Resolves every offset to its default value plus a fixed delta and counts the lookups
+    struct CountingProvider {
+        delta: u64,
+        lookups: &'static AtomicUsize,
+    }
+
+    impl SchemaProvider for CountingProvider {
+        fn resolve_offset(&self, offset: &OffsetInfo) -> Option<u64> {
+            self.lookups.fetch_add(1, Ordering::Relaxed);
+            Some(offset.default_value + self.delta)
+        }
+    }
+
+    const OFFSET: OffsetInfo = OffsetInfo {
+        default_value: 0x10,
+        module: "client.dll",
+        class_name: "C_BaseEntity",
+        member: "m_iHealth",
+    };
+
+    #[test]
+    fn test_resolve_cached_per_generation() {
+        static LOOKUPS: AtomicUsize = AtomicUsize::new(0);
+        static CACHE: ResolvedOffset = ResolvedOffset::new();
+
+        setup_provider(Box::new(CountingProvider {
+            delta: 0,
+            lookups: &LOOKUPS,
+        }));
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..100 {
+                        assert_eq!(resolve_offset(&CACHE, &OFFSET), 0x10);
+                    }
+                });
+            }
+        });
+        assert!(LOOKUPS.load(Ordering::Relaxed) <= 4);
+
+        /* a new provider invalidates the cached offset */
+        setup_provider(Box::new(CountingProvider {
+            delta: 0x08,
+            lookups: &LOOKUPS,
+        }));
+        let lookups = LOOKUPS.load(Ordering::Relaxed);
+        assert_eq!(resolve_offset(&CACHE, &OFFSET), 0x18);
+        assert_eq!(resolve_offset(&CACHE, &OFFSET), 0x18);
+        assert_eq!(LOOKUPS.load(Ordering::Relaxed), lookups + 1);
+    }
 }
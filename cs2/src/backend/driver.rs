@@ -0,0 +1,140 @@
+use std::sync::atomic::{
+    AtomicU32,
+    Ordering,
+};
+
+use obfstr::obfstr;
+use vtd_libum::{
+    protocol::{
+        command::{
+            KeyboardState,
+            MouseState,
+            ProcessProtectionMode,
+        },
+        types::{
+            DirectoryTableType,
+            DriverFeature,
+            ProcessId,
+        },
+    },
+    DriverInterface,
+};
+
+use super::{
+    DriverInfo,
+    MemoryBackend,
+    ProcessModule,
+};
+
+/// Memory access through the Valthrun kernel driver
+pub struct DriverBackend {
+    interface: DriverInterface,
+    process_id: AtomicU32,
+}
+
+impl DriverBackend {
+    pub fn create() -> anyhow::Result<Self> {
+        let interface = DriverInterface::create_from_env()?;
+
+        if interface
+            .driver_features()
+            .contains(DriverFeature::ProcessProtectionKernel)
+        {
+            /*
+             * Please no not analyze me:
+             * https://www.unknowncheats.me/wiki/Valve_Anti-Cheat:VAC_external_tool_detection_(and_more)
+             *
+             * Even tough we don't have open handles to CS2 we don't want anybody to read our process.
+             */
+            if let Err(err) = interface.toggle_process_protection(ProcessProtectionMode::Kernel) {
+                log::warn!("Failed to enable process protection: {}", err)
+            };
+        }
+
+        Ok(Self {
+            interface,
+            process_id: AtomicU32::new(0),
+        })
+    }
+}
+
+impl MemoryBackend for DriverBackend {
+    fn driver_info(&self) -> DriverInfo {
+        let version = self.interface.driver_version();
+        DriverInfo {
+            application_name: version
+                .get_application_name()
+                .unwrap_or("<invalid>")
+                .to_string(),
+            version: format!(
+                "{}.{}.{}",
+                version.version_major, version.version_minor, version.version_patch
+            ),
+            features: format!("{:?}", self.interface.driver_features()),
+        }
+    }
+
+    fn find_processes(&self, image_name: &str) -> anyhow::Result<Vec<ProcessId>> {
+        Ok(self
+            .interface
+            .list_processes()?
+            .into_iter()
+            .filter(|process| process.get_image_base_name().unwrap_or_default() == image_name)
+            .map(|process| process.process_id)
+            .collect())
+    }
+
+    fn attach(&self, process_id: ProcessId) -> anyhow::Result<()> {
+        self.process_id.store(process_id as u32, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn list_modules(&self) -> anyhow::Result<Vec<ProcessModule>> {
+        Ok(self
+            .interface
+            .list_modules(
+                self.process_id.load(Ordering::Relaxed) as ProcessId,
+                DirectoryTableType::Default,
+            )?
+            .into_iter()
+            .map(|module| ProcessModule {
+                name: module
+                    .get_base_dll_name()
+                    .unwrap_or(obfstr!("unknown"))
+                    .to_string(),
+                base_address: module.base_address,
+                module_size: module.module_size,
+            })
+            .collect())
+    }
+
+    fn read_slice(&self, address: u64, buffer: &mut [u8]) -> anyhow::Result<()> {
+        self.interface.read_slice(
+            self.process_id.load(Ordering::Relaxed) as ProcessId,
+            DirectoryTableType::Default,
+            address,
+            buffer,
+        )?;
+        Ok(())
+    }
+
+    fn total_read_calls(&self) -> usize {
+        self.interface.total_read_calls()
+    }
+
+    fn send_keyboard_state(&self, states: &[KeyboardState]) -> anyhow::Result<()> {
+        self.interface.send_keyboard_state(states)?;
+        Ok(())
+    }
+
+    fn send_mouse_state(&self, states: &[MouseState]) -> anyhow::Result<()> {
+        self.interface.send_mouse_state(states)?;
+        Ok(())
+    }
+
+    fn add_metrics_record(&self, record_type: &str, record_payload: &str) {
+        let _ = self
+            .interface
+            .add_metrics_record(record_type, record_payload);
+    }
+}
@@ -0,0 +1,64 @@
+use vtd_libum::protocol::{
+    command::{
+        KeyboardState,
+        MouseState,
+    },
+    types::ProcessId,
+};
+
+mod driver;
+pub use driver::*;
+
+mod replay;
+pub use replay::*;
+
+mod synthetic;
+pub use synthetic::*;
+
+#[cfg(feature = "usermode-backend")]
+mod usermode;
+#[cfg(feature = "usermode-backend")]
+pub use usermode::*;
+
+/// Details about the memory backend the controller is connected to
+#[derive(Debug, Clone)]
+pub struct DriverInfo {
+    pub application_name: String,
+    pub version: String,
+    pub features: String,
+}
+
+/// A module loaded into the target process
+#[derive(Debug, Clone)]
+pub struct ProcessModule {
+    pub name: String,
+    pub base_address: u64,
+    pub module_size: u64,
+}
+
+/// Provides access to the memory of the CS2 process
+pub trait MemoryBackend: Send + Sync {
+    fn driver_info(&self) -> DriverInfo;
+
+    /// Ids of all running processes with the given image name
+    fn find_processes(&self, image_name: &str) -> anyhow::Result<Vec<ProcessId>>;
+
+    /// Select the process all further calls operate on.
+    /// Might be called again to attach to a restarted process.
+    fn attach(&self, process_id: ProcessId) -> anyhow::Result<()>;
+
+    /// Modules of the attached process
+    fn list_modules(&self) -> anyhow::Result<Vec<ProcessModule>>;
+
+    /// Read the memory of the attached process. The buffer must be filled completely.
+    fn read_slice(&self, address: u64, buffer: &mut [u8]) -> anyhow::Result<()>;
+
+    /// Total number of read calls issued since the backend has been created
+    fn total_read_calls(&self) -> usize;
+
+    fn send_keyboard_state(&self, states: &[KeyboardState]) -> anyhow::Result<()>;
+
+    fn send_mouse_state(&self, states: &[MouseState]) -> anyhow::Result<()>;
+
+    fn add_metrics_record(&self, record_type: &str, record_payload: &str);
+}
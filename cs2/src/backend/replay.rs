@@ -0,0 +1,554 @@
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    fs::File,
+    io::{
+        self,
+        BufReader,
+        BufWriter,
+        Read,
+        Write,
+    },
+    path::Path,
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::Context;
+use obfstr::obfstr;
+use vtd_libum::protocol::{
+    command::{
+        KeyboardState,
+        MouseState,
+    },
+    types::ProcessId,
+};
+
+use super::{
+    DriverInfo,
+    MemoryBackend,
+    ProcessModule,
+};
+
+/// Magic at the start of every memory recording, includes the format version
+const RECORDING_MAGIC: &[u8; 8] = b"LABHMEM1";
+
+const RECORD_MODULES: u8 = 0;
+const RECORD_FRAME: u8 = 1;
+
+/// Reads are grouped into frames of this length, roughly one frame per game tick
+const RECORDING_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+const PAGE_SIZE: usize = 0x1000;
+
+/// Max amount of recorded reads checked for a range containing the requested memory
+const MAX_RANGE_CANDIDATES: usize = 8;
+
+fn write_u32(writer: &mut impl Write, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_u64(writer: &mut impl Write, value: u64) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut buffer = [0u8; 1];
+    reader.read_exact(&mut buffer)?;
+    Ok(buffer[0])
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut buffer = [0u8; 4];
+    reader.read_exact(&mut buffer)?;
+    Ok(u32::from_le_bytes(buffer))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut buffer = [0u8; 8];
+    reader.read_exact(&mut buffer)?;
+    Ok(u64::from_le_bytes(buffer))
+}
+
+fn read_bytes(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let length = read_u32(reader)? as usize;
+    let mut buffer = Vec::new();
+    reader.take(length as u64).read_to_end(&mut buffer)?;
+    if buffer.len() != length {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    Ok(buffer)
+}
+
+struct MemoryRecorder {
+    writer: zstd::Encoder<'static, BufWriter<File>>,
+    started: Instant,
+    frame_start: Duration,
+    /// Successful reads of the current frame keyed by their address
+    reads: BTreeMap<u64, Vec<u8>>,
+    failed: bool,
+}
+
+impl MemoryRecorder {
+    fn write_modules(
+        &mut self,
+        process_id: ProcessId,
+        modules: &[ProcessModule],
+    ) -> io::Result<()> {
+        self.writer.write_all(&[RECORD_MODULES])?;
+        write_u32(&mut self.writer, process_id as u32)?;
+        write_u32(&mut self.writer, modules.len() as u32)?;
+        for module in modules {
+            write_u32(&mut self.writer, module.name.len() as u32)?;
+            self.writer.write_all(module.name.as_bytes())?;
+            write_u64(&mut self.writer, module.base_address)?;
+            write_u64(&mut self.writer, module.module_size)?;
+        }
+        self.writer.flush()
+    }
+
+    fn record_read(&mut self, address: u64, data: &[u8]) -> io::Result<()> {
+        let elapsed = self.started.elapsed();
+        if elapsed - self.frame_start >= RECORDING_FRAME_INTERVAL {
+            self.write_frame()?;
+            self.frame_start = elapsed;
+        }
+
+        let entry = self.reads.entry(address).or_default();
+        if entry.len() < data.len() {
+            entry.resize(data.len(), 0);
+        }
+        entry[..data.len()].copy_from_slice(data);
+        Ok(())
+    }
+
+    fn write_frame(&mut self) -> io::Result<()> {
+        if self.reads.is_empty() {
+            return Ok(());
+        }
+
+        self.writer.write_all(&[RECORD_FRAME])?;
+        write_u64(&mut self.writer, self.frame_start.as_micros() as u64)?;
+        write_u32(&mut self.writer, self.reads.len() as u32)?;
+        for (address, data) in std::mem::take(&mut self.reads) {
+            write_u64(&mut self.writer, address)?;
+            write_u32(&mut self.writer, data.len() as u32)?;
+            self.writer.write_all(&data)?;
+        }
+
+        /* flushed frames stay readable if the controller gets terminated */
+        self.writer.flush()
+    }
+
+    fn handle_error(&mut self, result: io::Result<()>) {
+        if let Err(err) = result {
+            log::warn!(
+                "Failed to write the memory recording, recording stopped: {}",
+                err
+            );
+            self.failed = true;
+        }
+    }
+}
+
+/// Forwards all calls to another backend and records every successful read,
+/// so the session can be replayed later with the [ReplayBackend].
+pub struct RecordingBackend {
+    inner: Box<dyn MemoryBackend>,
+    recorder: Mutex<MemoryRecorder>,
+    process_id: Mutex<Option<ProcessId>>,
+}
+
+impl RecordingBackend {
+    pub fn create(inner: Box<dyn MemoryBackend>, path: &Path) -> anyhow::Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("failed to create memory recording {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(RECORDING_MAGIC)?;
+
+        log::info!("Recording all memory reads to {}", path.display());
+        Ok(Self {
+            inner,
+            recorder: Mutex::new(MemoryRecorder {
+                writer: zstd::Encoder::new(writer, 0)?,
+                started: Instant::now(),
+                frame_start: Duration::ZERO,
+                reads: Default::default(),
+                failed: false,
+            }),
+            process_id: Mutex::new(None),
+        })
+    }
+}
+
+impl Drop for RecordingBackend {
+    fn drop(&mut self) {
+        let recorder = self.recorder.get_mut().unwrap();
+        if !recorder.failed {
+            let result = recorder
+                .write_frame()
+                .and_then(|_| recorder.writer.do_finish());
+            recorder.handle_error(result);
+        }
+    }
+}
+
+impl MemoryBackend for RecordingBackend {
+    fn driver_info(&self) -> DriverInfo {
+        self.inner.driver_info()
+    }
+
+    fn find_processes(&self, image_name: &str) -> anyhow::Result<Vec<ProcessId>> {
+        self.inner.find_processes(image_name)
+    }
+
+    fn attach(&self, process_id: ProcessId) -> anyhow::Result<()> {
+        self.inner.attach(process_id)?;
+        *self.process_id.lock().unwrap() = Some(process_id);
+        Ok(())
+    }
+
+    fn list_modules(&self) -> anyhow::Result<Vec<ProcessModule>> {
+        let modules = self.inner.list_modules()?;
+
+        let process_id = self.process_id.lock().unwrap().unwrap_or_default();
+        let mut recorder = self.recorder.lock().unwrap();
+        if !recorder.failed {
+            let result = recorder.write_modules(process_id, &modules);
+            recorder.handle_error(result);
+        }
+
+        Ok(modules)
+    }
+
+    fn read_slice(&self, address: u64, buffer: &mut [u8]) -> anyhow::Result<()> {
+        self.inner.read_slice(address, buffer)?;
+
+        let mut recorder = self.recorder.lock().unwrap();
+        if !recorder.failed {
+            let result = recorder.record_read(address, buffer);
+            recorder.handle_error(result);
+        }
+        Ok(())
+    }
+
+    fn total_read_calls(&self) -> usize {
+        self.inner.total_read_calls()
+    }
+
+    fn send_keyboard_state(&self, states: &[KeyboardState]) -> anyhow::Result<()> {
+        self.inner.send_keyboard_state(states)
+    }
+
+    fn send_mouse_state(&self, states: &[MouseState]) -> anyhow::Result<()> {
+        self.inner.send_mouse_state(states)
+    }
+
+    fn add_metrics_record(&self, record_type: &str, record_payload: &str) {
+        self.inner.add_metrics_record(record_type, record_payload)
+    }
+}
+
+struct ReplayFrame {
+    /// Time since the recording has been started
+    time: Duration,
+    reads: BTreeMap<u64, Vec<u8>>,
+}
+
+impl ReplayFrame {
+    /// Copy the memory from a recorded read containing the whole buffer
+    fn read(&self, address: u64, buffer: &mut [u8]) -> bool {
+        let end = address + buffer.len() as u64;
+        for (read_address, data) in self
+            .reads
+            .range(..=address)
+            .rev()
+            .take(MAX_RANGE_CANDIDATES)
+        {
+            if read_address + data.len() as u64 >= end {
+                let offset = (address - read_address) as usize;
+                buffer.copy_from_slice(&data[offset..offset + buffer.len()]);
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+struct ReplayPage {
+    data: Box<[u8; PAGE_SIZE]>,
+    /// Bit set of the bytes which have been recorded
+    valid: [u64; PAGE_SIZE / 64],
+}
+
+/// Memory of the replayed process at the current replay time
+struct ReplayImage {
+    started: Instant,
+    applied_frames: usize,
+    pages: HashMap<u64, ReplayPage>,
+}
+
+impl ReplayImage {
+    fn apply(&mut self, address: u64, data: &[u8]) {
+        let mut address = address;
+        let mut data = data;
+        while !data.is_empty() {
+            let page_offset = (address % PAGE_SIZE as u64) as usize;
+            let length = data.len().min(PAGE_SIZE - page_offset);
+
+            let page = self
+                .pages
+                .entry(address - page_offset as u64)
+                .or_insert_with(|| ReplayPage {
+                    data: Box::new([0; PAGE_SIZE]),
+                    valid: [0; PAGE_SIZE / 64],
+                });
+            page.data[page_offset..page_offset + length].copy_from_slice(&data[..length]);
+            for index in page_offset..page_offset + length {
+                page.valid[index / 64] |= 1 << (index % 64);
+            }
+
+            address += length as u64;
+            data = &data[length..];
+        }
+    }
+
+    fn read(&self, address: u64, buffer: &mut [u8]) -> bool {
+        let mut address = address;
+        let mut buffer = buffer;
+        while !buffer.is_empty() {
+            let page_offset = (address % PAGE_SIZE as u64) as usize;
+            let length = buffer.len().min(PAGE_SIZE - page_offset);
+
+            let Some(page) = self.pages.get(&(address - page_offset as u64)) else {
+                return false;
+            };
+            let recorded = (page_offset..page_offset + length)
+                .all(|index| page.valid[index / 64] & (1 << (index % 64)) != 0);
+            if !recorded {
+                return false;
+            }
+
+            buffer[..length].copy_from_slice(&page.data[page_offset..page_offset + length]);
+            address += length as u64;
+            buffer = &mut buffer[length..];
+        }
+
+        true
+    }
+}
+
+/// Replays a memory recording created by the [RecordingBackend] without the game running.
+/// The recorded frames are replayed in real time and start over at the end.
+/// Memory which has not been recorded fails to read and input is discarded.
+pub struct ReplayBackend {
+    process_id: ProcessId,
+    modules: Vec<ProcessModule>,
+    frames: Vec<ReplayFrame>,
+    image: Mutex<ReplayImage>,
+    read_calls: AtomicUsize,
+}
+
+impl ReplayBackend {
+    /// Load a memory recording.
+    /// A truncated last frame (e.g. due to a crash while recording) will be ignored.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to open memory recording {}", path.display()))?;
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != RECORDING_MAGIC {
+            anyhow::bail!("{} is not a memory recording", path.display());
+        }
+
+        let mut reader = zstd::Decoder::new(reader)?;
+        let mut process_and_modules = None;
+        let mut frames = Vec::new();
+        loop {
+            let result = match read_u8(&mut reader) {
+                Ok(RECORD_MODULES) => Self::read_modules(&mut reader).map(|modules| {
+                    /* the game has been reattached while recording, keep the initial process */
+                    process_and_modules.get_or_insert(modules);
+                }),
+                Ok(RECORD_FRAME) => Self::read_frame(&mut reader).map(|frame| frames.push(frame)),
+                Ok(record_type) => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid record type {}", record_type),
+                )),
+                Err(err) => Err(err),
+            };
+
+            match result {
+                Ok(_) => {}
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(err) if !frames.is_empty() => {
+                    log::warn!("Memory recording has been truncated: {}", err);
+                    break;
+                }
+                Err(err) => return Err(err).context("failed to read the memory recording"),
+            }
+        }
+
+        let (process_id, modules) =
+            process_and_modules.context("the memory recording does not contain any module")?;
+        log::info!(
+            "Replaying {} frames ({:.1}s) from {}",
+            frames.len(),
+            frames
+                .last()
+                .map(|frame| frame.time.as_secs_f32())
+                .unwrap_or_default(),
+            path.display()
+        );
+
+        Ok(Self {
+            process_id,
+            modules,
+            frames,
+            image: Mutex::new(ReplayImage {
+                started: Instant::now(),
+                applied_frames: 0,
+                pages: Default::default(),
+            }),
+            read_calls: AtomicUsize::new(0),
+        })
+    }
+
+    fn read_modules(reader: &mut impl Read) -> io::Result<(ProcessId, Vec<ProcessModule>)> {
+        let process_id = read_u32(reader)? as ProcessId;
+        let module_count = read_u32(reader)?;
+
+        let mut modules = Vec::with_capacity(module_count as usize);
+        for _ in 0..module_count {
+            let name = String::from_utf8(read_bytes(reader)?)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            modules.push(ProcessModule {
+                name,
+                base_address: read_u64(reader)?,
+                module_size: read_u64(reader)?,
+            });
+        }
+
+        Ok((process_id, modules))
+    }
+
+    fn read_frame(reader: &mut impl Read) -> io::Result<ReplayFrame> {
+        let time = Duration::from_micros(read_u64(reader)?);
+        let read_count = read_u32(reader)?;
+
+        let mut reads = BTreeMap::new();
+        for _ in 0..read_count {
+            let address = read_u64(reader)?;
+            reads.insert(address, read_bytes(reader)?);
+        }
+
+        Ok(ReplayFrame { time, reads })
+    }
+
+    /// Apply all frames up to the current replay time
+    fn advance(&self, image: &mut ReplayImage) {
+        let duration = self
+            .frames
+            .last()
+            .map(|frame| frame.time)
+            .unwrap_or_default();
+        if image.started.elapsed() > duration + RECORDING_FRAME_INTERVAL {
+            /* memory which is only read once (e.g. on startup) stays available */
+            image.started = Instant::now();
+            image.applied_frames = 0;
+        }
+
+        let elapsed = image.started.elapsed();
+        while let Some(frame) = self.frames.get(image.applied_frames) {
+            if frame.time > elapsed {
+                break;
+            }
+
+            for (address, data) in frame.reads.iter() {
+                image.apply(*address, data);
+            }
+            image.applied_frames += 1;
+        }
+    }
+}
+
+impl MemoryBackend for ReplayBackend {
+    fn driver_info(&self) -> DriverInfo {
+        DriverInfo {
+            application_name: obfstr!("memory-replay").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            features: String::new(),
+        }
+    }
+
+    fn find_processes(&self, _image_name: &str) -> anyhow::Result<Vec<ProcessId>> {
+        Ok(vec![self.process_id])
+    }
+
+    fn attach(&self, _process_id: ProcessId) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn list_modules(&self) -> anyhow::Result<Vec<ProcessModule>> {
+        Ok(self.modules.clone())
+    }
+
+    fn read_slice(&self, address: u64, buffer: &mut [u8]) -> anyhow::Result<()> {
+        self.read_calls.fetch_add(1, Ordering::Relaxed);
+
+        let mut image = self.image.lock().unwrap();
+        self.advance(&mut image);
+        if image.read(address, buffer) {
+            return Ok(());
+        }
+
+        /*
+         * The replay might be ahead of the recording, e.g. as the initial
+         * pattern scans complete faster than while recording.
+         */
+        let upcoming_frames = &self.frames[image.applied_frames..];
+        if upcoming_frames
+            .iter()
+            .any(|frame| frame.read(address, buffer))
+        {
+            return Ok(());
+        }
+
+        anyhow::bail!(
+            "{} {:X} ({} bytes)",
+            obfstr!("memory has not been recorded at"),
+            address,
+            buffer.len()
+        )
+    }
+
+    fn total_read_calls(&self) -> usize {
+        self.read_calls.load(Ordering::Relaxed)
+    }
+
+    fn send_keyboard_state(&self, _states: &[KeyboardState]) -> anyhow::Result<()> {
+        /* there is no game receiving the input */
+        Ok(())
+    }
+
+    fn send_mouse_state(&self, _states: &[MouseState]) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn add_metrics_record(&self, _record_type: &str, _record_payload: &str) {}
+}
@@ -0,0 +1,114 @@
+use std::sync::atomic::{
+    AtomicUsize,
+    Ordering,
+};
+
+use obfstr::obfstr;
+use vtd_libum::protocol::{
+    command::{
+        KeyboardState,
+        MouseState,
+    },
+    types::ProcessId,
+};
+
+use super::{
+    DriverInfo,
+    MemoryBackend,
+    ProcessModule,
+};
+
+/// Process id reported for the synthetic CS2 process
+const SYNTHETIC_PROCESS_ID: ProcessId = 1;
+
+/// Modules of the synthetic process, laid out back to back
+const SYNTHETIC_MODULES: &[(&str, u64)] = &[
+    ("client.dll", 0x0400_0000),
+    ("engine2.dll", 0x0100_0000),
+    ("schemasystem.dll", 0x0020_0000),
+    ("tier0.dll", 0x0040_0000),
+    ("matchmaking.dll", 0x0040_0000),
+];
+
+/// Base address of the first synthetic module
+const SYNTHETIC_MODULE_BASE: u64 = 0x7FF8_0000_0000;
+
+/// Memory backend without a game behind it.
+/// All memory reads succeed with zeroed memory, therefore no entities or game states can be resolved.
+/// Used to benchmark the update and render pipeline when neither the game nor a recording is available.
+pub struct SyntheticBackend {
+    modules: Vec<ProcessModule>,
+    read_calls: AtomicUsize,
+}
+
+impl SyntheticBackend {
+    pub fn new() -> Self {
+        let mut base_address = SYNTHETIC_MODULE_BASE;
+        let modules = SYNTHETIC_MODULES
+            .iter()
+            .map(|(name, module_size)| {
+                let module = ProcessModule {
+                    name: name.to_string(),
+                    base_address,
+                    module_size: *module_size,
+                };
+                base_address += module_size;
+                module
+            })
+            .collect();
+
+        Self {
+            modules,
+            read_calls: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl Default for SyntheticBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryBackend for SyntheticBackend {
+    fn driver_info(&self) -> DriverInfo {
+        DriverInfo {
+            application_name: obfstr!("synthetic-memory").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            features: String::new(),
+        }
+    }
+
+    fn find_processes(&self, _image_name: &str) -> anyhow::Result<Vec<ProcessId>> {
+        Ok(vec![SYNTHETIC_PROCESS_ID])
+    }
+
+    fn attach(&self, _process_id: ProcessId) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn list_modules(&self) -> anyhow::Result<Vec<ProcessModule>> {
+        Ok(self.modules.clone())
+    }
+
+    fn read_slice(&self, _address: u64, buffer: &mut [u8]) -> anyhow::Result<()> {
+        self.read_calls.fetch_add(1, Ordering::Relaxed);
+        buffer.fill(0);
+        Ok(())
+    }
+
+    fn total_read_calls(&self) -> usize {
+        self.read_calls.load(Ordering::Relaxed)
+    }
+
+    fn send_keyboard_state(&self, _states: &[KeyboardState]) -> anyhow::Result<()> {
+        /* there is no game receiving the input */
+        Ok(())
+    }
+
+    fn send_mouse_state(&self, _states: &[MouseState]) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn add_metrics_record(&self, _record_type: &str, _record_payload: &str) {}
+}
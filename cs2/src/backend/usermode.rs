@@ -0,0 +1,240 @@
+use std::{
+    ffi::c_void,
+    sync::atomic::{
+        AtomicIsize,
+        AtomicU32,
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+use anyhow::Context;
+use obfstr::obfstr;
+use vtd_libum::protocol::{
+    command::{
+        KeyboardState,
+        MouseState,
+    },
+    types::ProcessId,
+};
+use windows::Win32::{
+    Foundation::{
+        CloseHandle,
+        HANDLE,
+    },
+    System::{
+        Diagnostics::{
+            Debug::ReadProcessMemory,
+            ToolHelp::{
+                CreateToolhelp32Snapshot,
+                Module32FirstW,
+                Module32NextW,
+                Process32FirstW,
+                Process32NextW,
+                MODULEENTRY32W,
+                PROCESSENTRY32W,
+                TH32CS_SNAPMODULE,
+                TH32CS_SNAPMODULE32,
+                TH32CS_SNAPPROCESS,
+            },
+        },
+        Threading::{
+            OpenProcess,
+            PROCESS_QUERY_LIMITED_INFORMATION,
+            PROCESS_VM_READ,
+        },
+    },
+};
+
+use super::{
+    DriverInfo,
+    MemoryBackend,
+    ProcessModule,
+};
+
+fn wide_to_string(value: &[u16]) -> String {
+    let length = value
+        .iter()
+        .position(|char| *char == 0)
+        .unwrap_or(value.len());
+    String::from_utf16_lossy(&value[..length])
+}
+
+/// Memory access through a process handle and ReadProcessMemory.
+/// Only intended for development without the kernel driver:
+/// the open handle to CS2 is trivially detectable by the anti cheat.
+pub struct UsermodeBackend {
+    process_id: AtomicU32,
+    /// Raw value of the process handle, replaced when attaching to another process
+    process_handle: AtomicIsize,
+    read_calls: AtomicUsize,
+}
+
+impl UsermodeBackend {
+    pub fn create() -> anyhow::Result<Self> {
+        log::warn!("{}", obfstr!("Using the usermode memory backend."));
+        log::warn!(
+            "{}",
+            obfstr!("Open process handles to CS2 are easily detected by anti cheats!")
+        );
+
+        Ok(Self {
+            process_id: AtomicU32::new(0),
+            process_handle: AtomicIsize::new(HANDLE::default().0),
+            read_calls: AtomicUsize::new(0),
+        })
+    }
+
+    fn process_handle(&self) -> HANDLE {
+        HANDLE(self.process_handle.load(Ordering::Acquire))
+    }
+
+    /// Replace the process handle and close the previous one
+    fn replace_process_handle(&self, handle: HANDLE) {
+        let previous = HANDLE(self.process_handle.swap(handle.0, Ordering::AcqRel));
+        if !previous.is_invalid() {
+            unsafe { CloseHandle(previous) };
+        }
+    }
+}
+
+impl Drop for UsermodeBackend {
+    fn drop(&mut self) {
+        self.replace_process_handle(HANDLE::default());
+    }
+}
+
+impl MemoryBackend for UsermodeBackend {
+    fn driver_info(&self) -> DriverInfo {
+        DriverInfo {
+            application_name: obfstr!("usermode").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            features: obfstr!("ReadProcessMemory").to_string(),
+        }
+    }
+
+    fn find_processes(&self, image_name: &str) -> anyhow::Result<Vec<ProcessId>> {
+        let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) }
+            .context("CreateToolhelp32Snapshot")?;
+
+        let mut processes = Vec::new();
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+
+        let mut success = unsafe { Process32FirstW(snapshot, &mut entry) }.as_bool();
+        while success {
+            if wide_to_string(&entry.szExeFile).eq_ignore_ascii_case(image_name) {
+                processes.push(entry.th32ProcessID as ProcessId);
+            }
+
+            success = unsafe { Process32NextW(snapshot, &mut entry) }.as_bool();
+        }
+
+        unsafe { CloseHandle(snapshot) };
+        Ok(processes)
+    }
+
+    fn attach(&self, process_id: ProcessId) -> anyhow::Result<()> {
+        let process_handle = unsafe {
+            OpenProcess(
+                PROCESS_VM_READ | PROCESS_QUERY_LIMITED_INFORMATION,
+                false,
+                process_id as u32,
+            )
+        }
+        .with_context(|| format!("{} {}", obfstr!("failed to open process"), process_id))?;
+
+        self.replace_process_handle(process_handle);
+        self.process_id.store(process_id as u32, Ordering::Release);
+        Ok(())
+    }
+
+    fn list_modules(&self) -> anyhow::Result<Vec<ProcessModule>> {
+        let snapshot = unsafe {
+            CreateToolhelp32Snapshot(
+                TH32CS_SNAPMODULE | TH32CS_SNAPMODULE32,
+                self.process_id.load(Ordering::Acquire),
+            )
+        }
+        .context("CreateToolhelp32Snapshot")?;
+
+        let mut modules = Vec::new();
+        let mut entry = MODULEENTRY32W {
+            dwSize: std::mem::size_of::<MODULEENTRY32W>() as u32,
+            ..Default::default()
+        };
+
+        let mut success = unsafe { Module32FirstW(snapshot, &mut entry) }.as_bool();
+        while success {
+            modules.push(ProcessModule {
+                name: wide_to_string(&entry.szModule),
+                base_address: entry.modBaseAddr as u64,
+                module_size: entry.modBaseSize as u64,
+            });
+
+            success = unsafe { Module32NextW(snapshot, &mut entry) }.as_bool();
+        }
+
+        unsafe { CloseHandle(snapshot) };
+        Ok(modules)
+    }
+
+    fn read_slice(&self, address: u64, buffer: &mut [u8]) -> anyhow::Result<()> {
+        self.read_calls.fetch_add(1, Ordering::Relaxed);
+
+        let mut bytes_read = 0;
+        let success = unsafe {
+            ReadProcessMemory(
+                self.process_handle(),
+                address as *const c_void,
+                buffer.as_mut_ptr() as *mut c_void,
+                buffer.len(),
+                Some(&mut bytes_read as *mut usize),
+            )
+        };
+        if !success.as_bool() {
+            anyhow::bail!(
+                "{} {:X}: {}",
+                obfstr!("ReadProcessMemory failed at"),
+                address,
+                windows::core::Error::from_win32()
+            );
+        }
+
+        if bytes_read != buffer.len() {
+            anyhow::bail!(
+                "{} {:X} ({}/{})",
+                obfstr!("partial read at"),
+                address,
+                bytes_read,
+                buffer.len()
+            );
+        }
+
+        Ok(())
+    }
+
+    fn total_read_calls(&self) -> usize {
+        self.read_calls.load(Ordering::Relaxed)
+    }
+
+    fn send_keyboard_state(&self, _states: &[KeyboardState]) -> anyhow::Result<()> {
+        anyhow::bail!(
+            "{}",
+            obfstr!("input is not supported by the usermode backend")
+        )
+    }
+
+    fn send_mouse_state(&self, _states: &[MouseState]) -> anyhow::Result<()> {
+        anyhow::bail!(
+            "{}",
+            obfstr!("input is not supported by the usermode backend")
+        )
+    }
+
+    fn add_metrics_record(&self, _record_type: &str, _record_payload: &str) {
+        /* metrics are only reported through the driver */
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    error::Error,
+    sync::Arc,
+};
+
+use raw_struct::{
+    MemoryView,
+    Viewable,
+};
+
+/// Max gap between two requested ranges to still read them with a single call
+const MERGE_GAP: u64 = 0x400;
+
+/// Max size of a merged range
+const MAX_SPAN_SIZE: u64 = 0x10000;
+
+/// Collection of memory ranges which should be read at once.
+/// Overlapping and nearby ranges get merged so they only require a single driver call.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryBatch {
+    ranges: Vec<(u64, u64)>,
+}
+
+impl MemoryBatch {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Request `length` bytes at `address`. Null addresses will be ignored.
+    pub fn add(&mut self, address: u64, length: usize) {
+        if address == 0 || length == 0 {
+            return;
+        }
+
+        self.ranges.push((address, length as u64));
+    }
+
+    /// Request the whole memory of an object of type `T`
+    pub fn add_object<T: ?Sized + Viewable<T>>(&mut self, address: u64) {
+        self.add(address, <T as Viewable<T>>::MEMORY_SIZE);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The requested ranges merged into as few as possible non overlapping spans, sorted by address
+    pub(crate) fn spans(&self) -> Vec<(u64, u64)> {
+        let mut ranges = self.ranges.clone();
+        ranges.sort_unstable();
+
+        let mut spans: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+        for (address, length) in ranges {
+            let end = address.saturating_add(length);
+            if let Some((span_address, span_length)) = spans.last_mut() {
+                let span_end = *span_address + *span_length;
+                if address <= span_end.saturating_add(MERGE_GAP)
+                    && end.max(span_end) - *span_address <= MAX_SPAN_SIZE
+                {
+                    *span_length = end.max(span_end) - *span_address;
+                    continue;
+                }
+            }
+
+            spans.push((address, end - address));
+        }
+
+        spans
+    }
+}
+
+/// Memory view serving reads from the spans of a [MemoryBatch] which have been read ahead.
+/// Reads outside of these spans are forwarded to the underlying memory view.
+pub struct PrefetchedMemory {
+    fallback: Arc<dyn MemoryView + Send + Sync>,
+    /// Non overlapping spans sorted by their address
+    spans: Vec<(u64, Vec<u8>)>,
+}
+
+impl PrefetchedMemory {
+    pub(crate) fn new(
+        fallback: Arc<dyn MemoryView + Send + Sync>,
+        spans: Vec<(u64, Vec<u8>)>,
+    ) -> Self {
+        Self { fallback, spans }
+    }
+}
+
+impl MemoryView for PrefetchedMemory {
+    fn read_memory(
+        &self,
+        offset: u64,
+        buffer: &mut [u8],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let index = self
+            .spans
+            .partition_point(|(address, _)| *address <= offset);
+
+        if let Some((address, data)) = index.checked_sub(1).map(|index| &self.spans[index]) {
+            let start = (offset - address) as usize;
+            if let Some(data) = data.get(start..).and_then(|data| data.get(..buffer.len())) {
+                buffer.copy_from_slice(data);
+                return Ok(());
+            }
+        }
+
+        self.fallback.read_memory(offset, buffer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        error::Error,
+        sync::{
+            atomic::{
+                AtomicUsize,
+                Ordering,
+            },
+            Arc,
+        },
+    };
+
+    use raw_struct::MemoryView;
+
+    use super::{
+        MemoryBatch,
+        PrefetchedMemory,
+        MAX_SPAN_SIZE,
+        MERGE_GAP,
+    };
+
+    /// Memory where every byte holds the lowest byte of its address
+    #[derive(Default)]
+    struct FallbackMemory {
+        reads: AtomicUsize,
+    }
+
+    impl MemoryView for FallbackMemory {
+        fn read_memory(
+            &self,
+            offset: u64,
+            buffer: &mut [u8],
+        ) -> Result<(), Box<dyn Error + Send + Sync>> {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+            for (index, value) in buffer.iter_mut().enumerate() {
+                *value = (offset + index as u64) as u8;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn spans_ignore_null() {
+        let mut batch = MemoryBatch::new();
+        batch.add(0, 0x10);
+        batch.add(0x1000, 0);
+        assert!(batch.is_empty());
+        assert_eq!(batch.spans(), vec![]);
+    }
+
+    #[test]
+    fn spans_merge_overlapping_and_nearby() {
+        let mut batch = MemoryBatch::new();
+        batch.add(0x1100, 0x10);
+        batch.add(0x1000, 0x20);
+        batch.add(0x1010, 0x20);
+        batch.add(0x1030 + MERGE_GAP, 0x10);
+        batch.add(0x9000, 0x8);
+
+        assert_eq!(
+            batch.spans(),
+            vec![(0x1000, 0x40 + MERGE_GAP), (0x9000, 0x8)]
+        );
+    }
+
+    #[test]
+    fn spans_split_distant() {
+        let mut batch = MemoryBatch::new();
+        batch.add(0x1000, 0x10);
+        batch.add(0x1010 + MERGE_GAP + 1, 0x10);
+
+        assert_eq!(
+            batch.spans(),
+            vec![(0x1000, 0x10), (0x1010 + MERGE_GAP + 1, 0x10)]
+        );
+    }
+
+    #[test]
+    fn spans_limit_size() {
+        let mut batch = MemoryBatch::new();
+        batch.add(0x10000, MAX_SPAN_SIZE as usize - 0x10);
+        batch.add(0x10000 + MAX_SPAN_SIZE - 0x10, 0x20);
+
+        let spans = batch.spans();
+        assert_eq!(spans.len(), 2);
+        assert!(spans.iter().all(|(_, length)| *length <= MAX_SPAN_SIZE));
+    }
+
+    #[test]
+    fn prefetched_reads_within_spans() {
+        let fallback = Arc::new(FallbackMemory::default());
+        let memory = PrefetchedMemory::new(
+            fallback.clone(),
+            vec![(0x1000, vec![0xAA; 0x20]), (0x2000, vec![0xBB; 0x10])],
+        );
+
+        let mut buffer = [0u8; 8];
+        memory.read_memory(0x1000, &mut buffer).unwrap();
+        assert_eq!(buffer, [0xAA; 8]);
+
+        memory.read_memory(0x1018, &mut buffer).unwrap();
+        assert_eq!(buffer, [0xAA; 8]);
+
+        memory.read_memory(0x2008, &mut buffer).unwrap();
+        assert_eq!(buffer, [0xBB; 8]);
+
+        assert_eq!(fallback.reads.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn prefetched_falls_back_outside_spans() {
+        let fallback = Arc::new(FallbackMemory::default());
+        let memory = PrefetchedMemory::new(fallback.clone(), vec![(0x1000, vec![0xAA; 0x20])]);
+
+        /* before the first span, after the span and overlapping the span end */
+        let mut buffer = [0u8; 8];
+        for address in [0x0F00, 0x1100, 0x101C] {
+            memory.read_memory(address, &mut buffer).unwrap();
+            assert_eq!(buffer[0], address as u8);
+        }
+
+        assert_eq!(fallback.reads.load(Ordering::Relaxed), 3);
+    }
+}
@@ -1,4 +1,7 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    time::Duration,
+};
 
 use anyhow::{
     anyhow,
@@ -15,6 +18,7 @@ use raw_struct::{
 use utils_state::{
     State,
     StateCacheType,
+    StateDependencies,
     StateRegistry,
 };
 
@@ -23,6 +27,7 @@ use crate::{
     CS2Handle,
     StateCS2Handle,
     StateEntityList,
+    STATE_TAG_GAME,
 };
 
 pub struct ClassNameCache {
@@ -41,7 +46,21 @@ impl State for ClassNameCache {
     }
 
     fn cache_type() -> StateCacheType {
-        StateCacheType::Persistent
+        /* class names never change, only scan for new classes a few times per second */
+        StateCacheType::Refresh(Duration::from_millis(250))
+    }
+
+    fn tag() -> Option<&'static str> {
+        Some(STATE_TAG_GAME)
+    }
+
+    fn dependencies(
+        &self,
+        states: &StateRegistry,
+        dependencies: &mut StateDependencies,
+    ) -> anyhow::Result<()> {
+        /* new classes can only appear with new entities */
+        dependencies.state::<StateEntityList>(states, ())
     }
 
     fn update(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
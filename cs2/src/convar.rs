@@ -1,8 +1,13 @@
 use std::ops::Deref;
 
+use anyhow::Context;
 use cs2_schema_cutl::CStringUtil;
 use raw_struct::Reference;
-use utils_state::StateRegistry;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
 
 use crate::{
     schema::{
@@ -12,6 +17,7 @@ use crate::{
     CS2Offset,
     StateCS2Memory,
     StateResolvedOffset,
+    STATE_TAG_GAME,
 };
 
 pub struct ConVars {
@@ -58,3 +64,28 @@ impl ConVars {
         Ok(None)
     }
 }
+
+/// A console variable looked up by its name
+pub struct StateConVar {
+    pub instance: Reference<dyn ConVar>,
+}
+
+impl State for StateConVar {
+    type Parameter = &'static str;
+
+    fn create(states: &StateRegistry, name: Self::Parameter) -> anyhow::Result<Self> {
+        let instance = ConVars::new(states)?
+            .find_cvar(name)?
+            .with_context(|| format!("missing cvar {}", name))?;
+
+        Ok(Self { instance })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+
+    fn tag() -> Option<&'static str> {
+        Some(STATE_TAG_GAME)
+    }
+}
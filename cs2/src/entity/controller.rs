@@ -24,6 +24,7 @@ use crate::{
     CS2Offset,
     StateCS2Memory,
     StateResolvedOffset,
+    STATE_TAG_GAME,
 };
 
 pub struct StateLocalPlayerController {
@@ -46,7 +47,7 @@ impl State for StateLocalPlayerController {
     }
 }
 
-struct StatePlayerControllerClass {
+pub(crate) struct StatePlayerControllerClass {
     address: u64,
 }
 
@@ -76,6 +77,10 @@ impl State for StatePlayerControllerClass {
     fn cache_type() -> StateCacheType {
         StateCacheType::Persistent
     }
+
+    fn tag() -> Option<&'static str> {
+        Some(STATE_TAG_GAME)
+    }
 }
 
 pub struct StatePlayerControllers {
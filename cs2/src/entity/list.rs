@@ -1,4 +1,11 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    hash::{
+        DefaultHasher,
+        Hash,
+        Hasher,
+    },
+};
 
 use anyhow::anyhow;
 use cs2_schema_cutl::EntityHandle;
@@ -28,6 +35,8 @@ type OuterEntityList = [Ptr64<InnerEntityList>; 64];
 pub struct StateEntityList {
     entities: Vec<Copy<dyn CEntityIdentity>>,
     handle_lookup: BTreeMap<u32, usize>,
+    /// Hash of the entity instances and their classes
+    content_hash: u64,
 }
 
 impl State for StateEntityList {
@@ -37,6 +46,7 @@ impl State for StateEntityList {
         Ok(Self {
             entities: Vec::new(),
             handle_lookup: Default::default(),
+            content_hash: 0,
         })
     }
 
@@ -51,6 +61,7 @@ impl State for StateEntityList {
 
         self.entities.clear();
         self.handle_lookup.clear();
+        let mut hasher = DefaultHasher::new();
 
         let outer_list =
             Ptr64::<OuterEntityList>::read_object(memory.view(), offset_global_entity_list.address)
@@ -84,14 +95,22 @@ impl State for StateEntityList {
                     continue;
                 }
 
+                entry.entity_ptr::<()>()?.address.hash(&mut hasher);
+                entry.entity_class_info()?.address.hash(&mut hasher);
+
                 self.entities.push(entry);
                 self.handle_lookup
                     .insert(entity_index, self.entities.len() - 1);
             }
         }
 
+        self.content_hash = hasher.finish();
         Ok(())
     }
+
+    fn content_hash(&self) -> Option<u64> {
+        Some(self.content_hash)
+    }
 }
 
 impl StateEntityList {
@@ -134,3 +153,40 @@ impl StateEntityList {
         return Ok(result);
     }
 }
+
+/// Tracks the entity handles between frames to drop cached per-entity states
+/// once an entity has been removed or its slot has been reused.
+#[derive(Default)]
+pub struct EntityLifecycleTracker {
+    /// Full entity handle (including the serial number) keyed by entity index
+    handles: BTreeMap<u32, u32>,
+}
+
+impl EntityLifecycleTracker {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Remove all states owned by entities which disappeared since the last call.
+    /// Returns the amount of removed states.
+    pub fn cleanup_stale_states(&mut self, states: &mut StateRegistry) -> anyhow::Result<usize> {
+        let mut handles = BTreeMap::new();
+        {
+            let entities = states.resolve::<StateEntityList>(())?;
+            for identity in entities.entities() {
+                let handle = identity.handle::<()>()?;
+                handles.insert(handle.get_entity_index(), handle.value);
+            }
+        }
+
+        let mut removed = 0;
+        for (entity_index, handle) in self.handles.iter() {
+            if handles.get(entity_index) != Some(handle) {
+                removed += states.invalidate_owner(*entity_index);
+            }
+        }
+
+        self.handles = handles;
+        Ok(removed)
+    }
+}
@@ -4,14 +4,25 @@ use std::{
     error::Error,
     ffi::CStr,
     fmt::Debug,
+    mem::MaybeUninit,
     ops::{
         Deref,
         DerefMut,
     },
     sync::{
+        atomic::{
+            AtomicU32,
+            AtomicU64,
+            Ordering,
+        },
         Arc,
+        RwLock,
         Weak,
     },
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 use anyhow::Context;
@@ -30,20 +41,19 @@ use vtd_libum::{
         command::{
             KeyboardState,
             MouseState,
-            ProcessProtectionMode,
-        },
-        types::{
-            DirectoryTableType,
-            DriverFeature,
-            ProcessId,
-            ProcessModuleInfo,
         },
+        types::ProcessId,
     },
-    DriverInterface,
     InterfaceError,
 };
 
 use crate::{
+    DriverBackend,
+    DriverInfo,
+    MemoryBackend,
+    MemoryBatch,
+    PrefetchedMemory,
+    ProcessModule,
     SearchPattern,
     Signature,
     SignatureType,
@@ -88,96 +98,246 @@ impl Module {
     }
 }
 
+/// Snapshot of the memory read statistics of the driver interface
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReadStatistics {
+    pub read_count: u64,
+    pub read_errors: u64,
+    /// Values which have been read successfully but failed the sanity checks
+    pub rejected_values: u64,
+    pub average_latency: Duration,
+    pub max_latency: Duration,
+}
+
+#[derive(Default)]
+struct ReadCounters {
+    read_count: AtomicU64,
+    read_errors: AtomicU64,
+    rejected_values: AtomicU64,
+    total_nanos: AtomicU64,
+    max_nanos: AtomicU64,
+}
+
+impl ReadCounters {
+    fn record(&self, elapsed: Duration, success: bool) {
+        let nanos = elapsed.as_nanos() as u64;
+        self.read_count.fetch_add(1, Ordering::Relaxed);
+        self.total_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.max_nanos.fetch_max(nanos, Ordering::Relaxed);
+        if !success {
+            self.read_errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn snapshot(&self) -> ReadStatistics {
+        let read_count = self.read_count.load(Ordering::Relaxed);
+        let total_nanos = self.total_nanos.load(Ordering::Relaxed);
+
+        ReadStatistics {
+            read_count,
+            read_errors: self.read_errors.load(Ordering::Relaxed),
+            rejected_values: self.rejected_values.load(Ordering::Relaxed),
+            average_latency: Duration::from_nanos(total_nanos.checked_div(read_count).unwrap_or(0)),
+            max_latency: Duration::from_nanos(self.max_nanos.load(Ordering::Relaxed)),
+        }
+    }
+
+    fn reset(&self) {
+        self.read_count.store(0, Ordering::Relaxed);
+        self.read_errors.store(0, Ordering::Relaxed);
+        self.rejected_values.store(0, Ordering::Relaxed);
+        self.total_nanos.store(0, Ordering::Relaxed);
+        self.max_nanos.store(0, Ordering::Relaxed);
+    }
+}
+
 /// Handle to the CS2 process
 pub struct CS2Handle {
     weak_self: Weak<Self>,
     metrics: bool,
+    read_counters: ReadCounters,
 
-    modules: Vec<ProcessModuleInfo>,
-    process_id: ProcessId,
+    /// Replaced when attaching to a restarted process
+    modules: RwLock<Vec<ProcessModule>>,
+    process_id: AtomicU32,
 
-    pub ke_interface: DriverInterface,
+    backend: Box<dyn MemoryBackend>,
 }
 
 impl CS2Handle {
     pub fn create(metrics: bool) -> anyhow::Result<Arc<Self>> {
-        let interface = DriverInterface::create_from_env()?;
-
-        if interface
-            .driver_features()
-            .contains(DriverFeature::ProcessProtectionKernel)
-        {
-            /*
-             * Please no not analyze me:
-             * https://www.unknowncheats.me/wiki/Valve_Anti-Cheat:VAC_external_tool_detection_(and_more)
-             *
-             * Even tough we don't have open handles to CS2 we don't want anybody to read our process.
-             */
-            if let Err(err) = interface.toggle_process_protection(ProcessProtectionMode::Kernel) {
-                log::warn!("Failed to enable process protection: {}", err)
-            };
-        }
+        Self::create_with_selector(metrics, |_| None)
+    }
 
-        let process = interface
-            .list_processes()?
-            .into_iter()
-            .filter(|process| {
-                process.get_image_base_name().unwrap_or_default() == obfstr!("cs2.exe")
-            })
-            .collect::<Vec<_>>();
-        let process = if process.is_empty() {
+    /// Create a handle to the CS2 process.
+    /// If multiple CS2 processes are running (e.g. a main and a spectator client) the selector
+    /// gets called to pick the process to attach to. Returning None aborts the creation.
+    pub fn create_with_selector(
+        metrics: bool,
+        selector: impl FnOnce(&[ProcessId]) -> Option<ProcessId>,
+    ) -> anyhow::Result<Arc<Self>> {
+        let backend = DriverBackend::create()?;
+        Self::create_with_backend(metrics, Box::new(backend), selector)
+    }
+
+    /// Create a handle to the CS2 process which accesses its memory through the given backend
+    pub fn create_with_backend(
+        metrics: bool,
+        backend: Box<dyn MemoryBackend>,
+        selector: impl FnOnce(&[ProcessId]) -> Option<ProcessId>,
+    ) -> anyhow::Result<Arc<Self>> {
+        let process_ids = backend.find_processes(obfstr!("cs2.exe"))?;
+        let process_id = if process_ids.is_empty() {
             return Err(InterfaceError::ProcessUnknown.into());
-        } else if process.len() > 1 {
-            return Err(InterfaceError::ProcessUbiquitous.into());
+        } else if process_ids.len() > 1 {
+            let Some(process_id) = selector(&process_ids) else {
+                return Err(InterfaceError::ProcessUbiquitous.into());
+            };
+
+            if !process_ids.contains(&process_id) {
+                return Err(InterfaceError::ProcessUnknown.into());
+            }
+
+            process_id
         } else {
-            process.first().unwrap()
+            process_ids[0]
         };
 
-        let modules = interface.list_modules(process.process_id, DirectoryTableType::Default)?;
+        let modules = Self::attach_backend(&*backend, process_id)?;
         log::debug!(
             "{}. Process id {}",
             obfstr!("Successfully initialized CS2 handle"),
-            process.process_id
+            process_id
         );
 
+        Ok(Arc::new_cyclic(|weak_self| Self {
+            weak_self: weak_self.clone(),
+            metrics,
+            read_counters: Default::default(),
+            modules: RwLock::new(modules),
+            process_id: AtomicU32::new(process_id as u32),
+
+            backend,
+        }))
+    }
+
+    fn attach_backend(
+        backend: &dyn MemoryBackend,
+        process_id: ProcessId,
+    ) -> anyhow::Result<Vec<ProcessModule>> {
+        backend.attach(process_id)?;
+        let modules = backend.list_modules()?;
+
         log::trace!("{} ({})", obfstr!("CS2 modules"), modules.len());
         for module in modules.iter() {
             log::trace!(
                 "  - {} ({:X} - {:X})",
-                module.get_base_dll_name().unwrap_or("unknown"),
+                module.name,
                 module.base_address,
                 module.base_address + module.module_size
             );
         }
 
-        Ok(Arc::new_cyclic(|weak_self| Self {
-            weak_self: weak_self.clone(),
-            metrics,
-            modules,
-            process_id: process.process_id,
+        Ok(modules)
+    }
 
-            ke_interface: interface,
-        }))
+    /// Whether the attached CS2 process is still running
+    pub fn process_running(&self) -> anyhow::Result<bool> {
+        Ok(self
+            .backend
+            .find_processes(obfstr!("cs2.exe"))?
+            .contains(&self.process_id()))
     }
 
-    fn get_module_info(&self, target: Module) -> Option<&ProcessModuleInfo> {
+    /// Attach to the running CS2 process again, e.g. after the game has been restarted.
+    /// The modules are listed again as they might not have been loaded on the previous attempt.
+    /// All previously resolved addresses are invalid afterwards.
+    pub fn reattach(&self) -> anyhow::Result<()> {
+        let process_ids = self.backend.find_processes(obfstr!("cs2.exe"))?;
+        let process_id = if process_ids.contains(&self.process_id()) {
+            self.process_id()
+        } else {
+            *process_ids.first().ok_or(InterfaceError::ProcessUnknown)?
+        };
+
+        let modules = Self::attach_backend(&*self.backend, process_id)?;
+        *self.modules.write().unwrap() = modules;
+        self.process_id.store(process_id as u32, Ordering::Relaxed);
+
+        log::debug!(
+            "{}. Process id {}",
+            obfstr!("Reattached CS2 handle"),
+            process_id
+        );
+        Ok(())
+    }
+
+    fn get_module_info(&self, target: Module) -> Option<ProcessModule> {
         self.modules
+            .read()
+            .unwrap()
             .iter()
-            .find(|module| module.get_base_dll_name() == Some(target.get_module_name()))
+            .find(|module| module.name == target.get_module_name())
+            .cloned()
     }
 
     pub fn process_id(&self) -> ProcessId {
-        self.process_id
+        self.process_id.load(Ordering::Relaxed) as ProcessId
+    }
+
+    pub fn driver_info(&self) -> DriverInfo {
+        self.backend.driver_info()
+    }
+
+    /// Total number of read calls issued to the memory backend
+    pub fn total_read_calls(&self) -> usize {
+        self.backend.total_read_calls()
+    }
+
+    pub fn read_statistics(&self) -> ReadStatistics {
+        self.read_counters.snapshot()
+    }
+
+    /// Record values which have been rejected by a sanity check (e.g. due to a bad read)
+    pub fn record_rejected_values(&self, count: u64) {
+        self.read_counters
+            .rejected_values
+            .fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn reset_read_statistics(&self) {
+        self.read_counters.reset();
+    }
+
+    /// Quick sanity check of the driver interface.
+    /// Reads the PE header of the client module and returns the time the read took.
+    pub fn self_test(&self) -> anyhow::Result<Duration> {
+        let client_base = self.memory_address(Module::Client, 0)?;
+
+        let start = Instant::now();
+        let mut header = [0u8; 2];
+        self.read_slice(client_base, &mut header)
+            .context(obfstr!("read client module header").to_string())?;
+        let elapsed = start.elapsed();
+
+        if &header != b"MZ" {
+            anyhow::bail!(
+                "{} ({:02X} {:02X})",
+                obfstr!("invalid client module header"),
+                header[0],
+                header[1]
+            );
+        }
+
+        Ok(elapsed)
     }
 
     pub fn send_keyboard_state(&self, states: &[KeyboardState]) -> anyhow::Result<()> {
-        self.ke_interface.send_keyboard_state(states)?;
-        Ok(())
+        self.backend.send_keyboard_state(states)
     }
 
     pub fn send_mouse_state(&self, states: &[MouseState]) -> anyhow::Result<()> {
-        self.ke_interface.send_mouse_state(states)?;
-        Ok(())
+        self.backend.send_mouse_state(states)
     }
 
     pub fn add_metrics_record(&self, record_type: &str, record_payload: &str) {
@@ -186,9 +346,7 @@ impl CS2Handle {
             return;
         }
 
-        let _ = self
-            .ke_interface
-            .add_metrics_record(record_type, record_payload);
+        self.backend.add_metrics_record(record_type, record_payload);
     }
 
     pub fn module_address(&self, module: Module, address: u64) -> Option<u64> {
@@ -209,18 +367,52 @@ impl CS2Handle {
     }
 
     pub fn read_sized<T: Copy>(&self, address: u64) -> anyhow::Result<T> {
-        Ok(self
-            .ke_interface
-            .read(self.process_id, DirectoryTableType::Default, address)?)
+        let mut value = MaybeUninit::<T>::uninit();
+        let buffer = unsafe {
+            std::slice::from_raw_parts_mut(value.as_mut_ptr() as *mut u8, std::mem::size_of::<T>())
+        };
+        self.read_bytes(address, buffer)?;
+
+        /* the backend filled the whole buffer */
+        Ok(unsafe { value.assume_init() })
     }
 
     pub fn read_slice<T: Copy>(&self, address: u64, buffer: &mut [T]) -> anyhow::Result<()> {
-        Ok(self.ke_interface.read_slice(
-            self.process_id,
-            DirectoryTableType::Default,
-            address,
-            buffer,
-        )?)
+        let buffer = unsafe {
+            std::slice::from_raw_parts_mut(
+                buffer.as_mut_ptr() as *mut u8,
+                std::mem::size_of_val(buffer),
+            )
+        };
+        self.read_bytes(address, buffer)
+    }
+
+    fn read_bytes(&self, address: u64, buffer: &mut [u8]) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let result = self.backend.read_slice(address, buffer);
+        self.read_counters.record(start.elapsed(), result.is_ok());
+
+        result
+    }
+
+    /// Read all spans of the batch, each with a single driver call.
+    /// Spans which fail to read will be read again on access through the returned memory view.
+    pub fn read_batch(&self, batch: &MemoryBatch) -> PrefetchedMemory {
+        let mut spans = Vec::new();
+        for (address, length) in batch.spans() {
+            let mut buffer = vec![0u8; length as usize];
+            match self.read_slice(address, buffer.as_mut_slice()) {
+                Ok(_) => spans.push((address, buffer)),
+                Err(err) => log::trace!(
+                    "Failed to read batch span {:X} ({} bytes): {:#}",
+                    address,
+                    length,
+                    err
+                ),
+            }
+        }
+
+        PrefetchedMemory::new(self.create_memory_view(), spans)
     }
 
     pub fn read_string(
@@ -264,12 +456,7 @@ impl CS2Handle {
 
         let mut buffer = Vec::<u8>::with_capacity(length);
         buffer.resize(length, 0);
-        self.ke_interface.read_slice(
-            self.process_id,
-            DirectoryTableType::Default,
-            address,
-            &mut buffer,
-        )?;
+        self.backend.read_slice(address, &mut buffer)?;
 
         for (index, window) in buffer.windows(pattern.length()).enumerate() {
             if !pattern.is_matching(window) {
@@ -1,7 +1,13 @@
 
+mod backend;
+pub use backend::*;
+
 mod handle;
 pub use handle::*;
 
+mod batch;
+pub use batch::*;
+
 mod signature;
 pub use signature::*;
 
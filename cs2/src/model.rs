@@ -16,6 +16,7 @@ use raw_struct::{
 use utils_state::{
     State,
     StateCacheType,
+    StateDependencies,
     StateRegistry,
 };
 
@@ -24,6 +25,7 @@ use crate::{
     CS2Handle,
     StateCS2Handle,
     StateCS2Memory,
+    STATE_TAG_GAME,
 };
 
 pub enum BoneFlags {
@@ -60,6 +62,7 @@ pub struct Bone {
 
 #[derive(Debug, Default)]
 pub struct CS2Model {
+    address: u64,
     pub name: String,
     pub bones: Vec<Bone>,
 
@@ -68,6 +71,9 @@ pub struct CS2Model {
 
     pub view_min: nalgebra::Vector3<f32>,
     pub view_max: nalgebra::Vector3<f32>,
+
+    /// Bone index pairs (parent, child) forming the skeleton of the model
+    pub skeleton: Vec<(usize, usize)>,
 }
 
 impl State for CS2Model {
@@ -83,6 +89,7 @@ impl State for CS2Model {
             .read_string(memory.view())?
             .context("model name nullptr")?;
 
+        result.address = address;
         result.name = name;
         log::debug!(
             "{} {} at {:X}. Caching.",
@@ -96,7 +103,33 @@ impl State for CS2Model {
     }
 
     fn cache_type() -> StateCacheType {
-        StateCacheType::Timed(Duration::from_secs(60))
+        /*
+         * Models of an address do not change, check every few minutes if the address holds another model.
+         * Checking the dependencies costs two extra remote reads (model name and bone count) per expiry
+         * instead of reading the whole model again.
+         */
+        StateCacheType::Ttl(Duration::from_secs(5 * 60))
+    }
+
+    fn tag() -> Option<&'static str> {
+        Some(STATE_TAG_GAME)
+    }
+
+    fn dependencies(
+        &self,
+        states: &StateRegistry,
+        dependencies: &mut StateDependencies,
+    ) -> anyhow::Result<()> {
+        let memory = states.resolve::<StateCS2Memory>(())?;
+        let name = PtrCStr::read_object(memory.view(), self.address + 0x08)
+            .map_err(|e| anyhow!(e))?
+            .read_string(memory.view())?;
+        let bone_count =
+            Reference::<dyn CModel>::new(memory.view_arc(), self.address).bone_count()?;
+
+        dependencies.value(name);
+        dependencies.value(bone_count);
+        Ok(())
     }
 }
 
@@ -142,12 +175,109 @@ impl CS2Model {
                 flags,
             });
         }
+
+        self.skeleton = build_skeleton(&self.bones);
         Ok(())
     }
 }
 
+/// Connect every hitbox bone with its closest hitbox ancestor, skipping helper bones in between.
+/// Models without hitbox bones use the plain bone hierarchy instead.
+fn build_skeleton(bones: &[Bone]) -> Vec<(usize, usize)> {
+    let has_hitboxes = bones
+        .iter()
+        .any(|bone| bone.flags & BoneFlags::FlagHitbox as u32 > 0);
+    let is_skeleton_bone =
+        |bone: &Bone| !has_hitboxes || bone.flags & BoneFlags::FlagHitbox as u32 > 0;
+
+    let mut skeleton = Vec::new();
+    for (bone_index, bone) in bones.iter().enumerate() {
+        if !is_skeleton_bone(bone) {
+            continue;
+        }
+
+        /* the depth limit protects against malformed parent indices forming a cycle */
+        let mut parent = bone.parent;
+        for _ in 0..bones.len() {
+            let Some(parent_index) = parent else {
+                break;
+            };
+            let Some(parent_bone) = bones.get(parent_index) else {
+                break;
+            };
+
+            if is_skeleton_bone(parent_bone) {
+                skeleton.push((parent_index, bone_index));
+                break;
+            }
+            parent = parent_bone.parent;
+        }
+    }
+
+    skeleton
+}
+
 impl Drop for CS2Model {
     fn drop(&mut self) {
         log::debug!("Removing cached model {}", self.name);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{
+        build_skeleton,
+        Bone,
+        BoneFlags,
+    };
+
+    fn bone(parent: Option<usize>, hitbox: bool) -> Bone {
+        Bone {
+            name: String::new(),
+            flags: if hitbox {
+                BoneFlags::FlagHitbox as u32
+            } else {
+                BoneFlags::FlagAnimation as u32
+            },
+            parent,
+        }
+    }
+
+    #[test]
+    fn skeleton_skips_helper_bones() {
+        let bones = [
+            /* 0: root helper, 1: pelvis, 2: spine helper, 3: head, 4: hand */
+            bone(None, false),
+            bone(Some(0), true),
+            bone(Some(1), false),
+            bone(Some(2), true),
+            bone(Some(2), true),
+        ];
+
+        assert_eq!(build_skeleton(&bones), vec![(1, 3), (1, 4)]);
+    }
+
+    #[test]
+    fn skeleton_without_hitboxes() {
+        let bones = [
+            bone(None, false),
+            bone(Some(0), false),
+            bone(Some(1), false),
+        ];
+
+        assert_eq!(build_skeleton(&bones), vec![(0, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn skeleton_malformed_parents() {
+        /* a cycle between helper bones and a parent out of range */
+        let bones = [
+            bone(Some(1), false),
+            bone(Some(0), false),
+            bone(Some(0), true),
+            bone(Some(10), true),
+        ];
+
+        assert_eq!(build_skeleton(&bones), vec![]);
+    }
+}
@@ -10,6 +10,7 @@ use crate::{
     Module,
     Signature,
     StateCS2Handle,
+    STATE_TAG_GAME,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -159,6 +160,10 @@ impl State for StatePredefinedOffset {
     fn cache_type() -> StateCacheType {
         StateCacheType::Persistent
     }
+
+    fn tag() -> Option<&'static str> {
+        Some(STATE_TAG_GAME)
+    }
 }
 
 pub struct StateResolvedOffset {
@@ -196,4 +201,8 @@ impl State for StateResolvedOffset {
     fn cache_type() -> StateCacheType {
         StateCacheType::Persistent
     }
+
+    fn tag() -> Option<&'static str> {
+        Some(STATE_TAG_GAME)
+    }
 }
@@ -15,6 +15,7 @@ use crate::{
     CS2Offset,
     StateCS2Memory,
     StateResolvedOffset,
+    STATE_TAG_GAME,
 };
 
 #[derive(Debug)]
@@ -56,4 +57,8 @@ impl State for StateBuildInfo {
     fn cache_type() -> StateCacheType {
         StateCacheType::Persistent
     }
+
+    fn tag() -> Option<&'static str> {
+        Some(STATE_TAG_GAME)
+    }
 }
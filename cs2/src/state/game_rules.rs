@@ -0,0 +1,232 @@
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use anyhow::Context;
+use cs2_schema_generated::cs2::client::{
+    CCSPlayerController,
+    C_CSGameRulesProxy,
+    C_Team,
+};
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use super::{
+    PlayerPawnState,
+    StateGlobals,
+    StatePawnInfo,
+};
+use crate::{
+    CEntityIdentityEx,
+    ClassNameCache,
+    StateCS2Memory,
+    StateEntityList,
+    WeaponId,
+    STATE_TAG_GAME,
+    WEAPON_FLAG_TYPE_KNIFE,
+};
+
+/// Team number of the terrorists
+const TEAM_T: u8 = 2;
+
+/// Team number of the counter terrorists
+const TEAM_CT: u8 = 3;
+
+/// Time all alive players have to hold a knife before the round is considered a knife round
+const KNIFE_ROUND_DETECTION_TIME: Duration = Duration::from_secs(3);
+
+/// Current round state of the match
+pub struct StateGameRules {
+    /// Current round number (starting at 1)
+    pub round_number: i32,
+
+    /// Seconds remaining in the current round.
+    /// While in freeze time this is the full round time.
+    pub round_time_remaining: f32,
+
+    /// Currently in freeze time (buy period before the round starts)
+    pub freeze_period: bool,
+
+    /// Seconds remaining of the freeze time
+    pub freeze_time_remaining: f32,
+
+    /// Currently in warmup
+    pub warmup_period: bool,
+
+    pub score_t: i32,
+    pub score_ct: i32,
+}
+
+impl State for StateGameRules {
+    type Parameter = ();
+
+    fn create(states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        let memory = states.resolve::<StateCS2Memory>(())?;
+        let globals = states.resolve::<StateGlobals>(())?;
+        let entities = states.resolve::<StateEntityList>(())?;
+        let class_name_cache = states.resolve::<ClassNameCache>(())?;
+
+        let mut game_rules = None;
+        let mut score_t = 0;
+        let mut score_ct = 0;
+        for entity_identity in entities.entities().iter() {
+            let class_name = class_name_cache
+                .lookup(&entity_identity.entity_class_info()?)
+                .context("class name")?;
+
+            match class_name.map(String::as_str) {
+                Some("C_CSGameRulesProxy") => {
+                    let proxy = entity_identity
+                        .entity_ptr::<dyn C_CSGameRulesProxy>()?
+                        .value_reference(memory.view_arc())
+                        .context("game rules proxy nullptr")?;
+
+                    game_rules = proxy.m_pGameRules()?.value_reference(memory.view_arc());
+                }
+                Some("C_CSTeam") => {
+                    let team = entity_identity
+                        .entity_ptr::<dyn C_Team>()?
+                        .value_reference(memory.view_arc())
+                        .context("team nullptr")?;
+
+                    match team.m_iTeamNum()? {
+                        TEAM_T => score_t = team.m_iScore()?,
+                        TEAM_CT => score_ct = team.m_iScore()?,
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let game_rules = game_rules.context("missing game rules")?;
+        let current_time = globals.time_2()?;
+
+        let round_time = game_rules.m_iRoundTime()? as f32;
+        let round_start_time = game_rules.m_fRoundStartTime()?.m_Value()?;
+        let freeze_period = game_rules.m_bFreezePeriod()?;
+
+        let (round_time_remaining, freeze_time_remaining) = if freeze_period {
+            (round_time, (round_start_time - current_time).max(0.0))
+        } else {
+            ((round_start_time + round_time - current_time).max(0.0), 0.0)
+        };
+
+        Ok(Self {
+            round_number: game_rules.m_totalRoundsPlayed()? + 1,
+            round_time_remaining,
+            freeze_period,
+            freeze_time_remaining,
+            warmup_period: game_rules.m_bWarmupPeriod()?,
+            score_t,
+            score_ct,
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Volatile
+    }
+}
+
+/// Phase of the current match.
+/// Knife rounds are not flagged by the game rules and are detected
+/// by all alive players holding a knife for a few seconds.
+pub struct StateMatchPhase {
+    pub warmup: bool,
+    pub knife_round: bool,
+
+    knives_only_since: Option<Instant>,
+}
+
+impl State for StateMatchPhase {
+    type Parameter = ();
+
+    fn create(_states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        Ok(Self {
+            warmup: false,
+            knife_round: false,
+
+            knives_only_since: None,
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+
+    fn tag() -> Option<&'static str> {
+        Some(STATE_TAG_GAME)
+    }
+
+    fn update(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
+        let Ok(game_rules) = states.resolve::<StateGameRules>(()) else {
+            /* not in a match */
+            self.warmup = false;
+            self.knife_round = false;
+            self.knives_only_since = None;
+            return Ok(());
+        };
+
+        self.warmup = game_rules.warmup_period;
+        if self.warmup || !Self::alive_players_knives_only(states)? {
+            self.knife_round = false;
+            self.knives_only_since = None;
+            return Ok(());
+        }
+
+        let knives_only_since = *self.knives_only_since.get_or_insert_with(Instant::now);
+        if !self.knife_round && knives_only_since.elapsed() >= KNIFE_ROUND_DETECTION_TIME {
+            log::debug!("Detected knife round");
+            self.knife_round = true;
+        }
+
+        Ok(())
+    }
+}
+
+impl StateMatchPhase {
+    fn alive_players_knives_only(states: &StateRegistry) -> anyhow::Result<bool> {
+        let memory = states.resolve::<StateCS2Memory>(())?;
+        let entities = states.resolve::<StateEntityList>(())?;
+        let class_name_cache = states.resolve::<ClassNameCache>(())?;
+
+        let mut alive_players = 0;
+        for entity_identity in entities.entities().iter() {
+            let class_name = class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
+            if class_name.map(String::as_str) != Some("CCSPlayerController") {
+                continue;
+            }
+
+            let Some(controller) = entity_identity
+                .entity_ptr::<dyn CCSPlayerController>()?
+                .value_reference(memory.view_arc())
+            else {
+                continue;
+            };
+
+            let pawn_handle = controller.m_hPlayerPawn()?;
+            if !pawn_handle.is_valid()
+                || *states.resolve::<PlayerPawnState>(pawn_handle)? != PlayerPawnState::Alive
+            {
+                continue;
+            }
+
+            let pawn_info = states.resolve::<StatePawnInfo>(pawn_handle)?;
+            /* unknown weapons are flagged as knife, but most likely are a failed read */
+            if pawn_info.weapon == WeaponId::Unknown
+                || pawn_info.weapon.flags() & WEAPON_FLAG_TYPE_KNIFE == 0
+            {
+                return Ok(false);
+            }
+
+            alive_players += 1;
+        }
+
+        /* a single player holding a knife says nothing about the round */
+        Ok(alive_players >= 2)
+    }
+}
@@ -1,3 +1,5 @@
+use utils_state::StateRegistry;
+
 mod player;
 pub use player::*;
 
@@ -13,5 +15,22 @@ pub use map::*;
 mod globals;
 pub use globals::*;
 
+mod game_rules;
+pub use game_rules::*;
+
+mod rank;
+pub use rank::*;
+
 mod build_info;
 pub use build_info::*;
+
+/// Tag of all states which depend on the game build or the game process
+/// (resolved addresses, class names, models, ...).
+pub const STATE_TAG_GAME: &str = "game";
+
+/// Remove all cached states tagged with [STATE_TAG_GAME].
+/// Required after the game has been updated or restarted.
+pub fn invalidate_game_states(states: &mut StateRegistry) {
+    let removed = states.invalidate_tag(STATE_TAG_GAME);
+    log::debug!("Invalidated {} game states", removed);
+}
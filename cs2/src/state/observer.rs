@@ -1,6 +1,7 @@
 use std::ffi::CStr;
 
 use anyhow::Context;
+use cs2_schema_cutl::EntityHandle;
 use cs2_schema_generated::cs2::client::{
     CBasePlayerController,
     C_BasePlayerPawn,
@@ -20,19 +21,47 @@ use crate::{
     StateLocalPlayerController,
 };
 
+#[derive(Debug, Clone)]
 pub struct SpectatorInfo {
     pub spectator_name: String,
-}
 
-pub struct SpectatorList {
+    /// Entity id of the spectators player controller
+    pub spectator_controller_id: u32,
+
+    /// Entity id of the pawn the spectator is currently watching
     pub target_entity_id: u32,
+
+    /// Player name of the pawn the spectator is currently watching
+    pub target_name: Option<String>,
+}
+
+/// All spectators (observer pawns) currently following a player
+pub struct StateSpectators {
     pub spectators: Vec<SpectatorInfo>,
 }
 
-impl State for SpectatorList {
-    type Parameter = u32;
+fn read_controller_name(
+    memory: &StateCS2Memory,
+    entities: &StateEntityList,
+    controller_handle: &EntityHandle<dyn CBasePlayerController>,
+) -> anyhow::Result<Option<String>> {
+    let Some(controller) = entities
+        .entity_from_handle(controller_handle)
+        .and_then(|e| e.value_reference(memory.view_arc()))
+    else {
+        return Ok(None);
+    };
+
+    Ok(CStr::from_bytes_until_nul(&controller.m_iszPlayerName()?)
+        .ok()
+        .and_then(|name| name.to_str().ok())
+        .map(ToString::to_string))
+}
 
-    fn create(states: &StateRegistry, target_entity_id: Self::Parameter) -> anyhow::Result<Self> {
+impl State for StateSpectators {
+    type Parameter = ();
+
+    fn create(states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
         let memory = states.resolve::<StateCS2Memory>(())?;
         let entities = states.resolve::<StateEntityList>(())?;
         let class_name_cache = states.resolve::<ClassNameCache>(())?;
@@ -63,31 +92,69 @@ impl State for SpectatorList {
                 }
             };
 
-            if observer_target_handle.get_entity_index() != target_entity_id {
+            if !observer_target_handle.is_valid() {
                 continue;
             }
 
-            let observer_controller_handle = observer_pawn.m_hController()?;
-            let current_player_controller = match entities
-                .entity_from_handle(&observer_controller_handle)
+            /* a single unreadable observer must not hide all other spectators */
+            let Ok(spectator_controller) = observer_pawn.m_hController() else {
+                continue;
+            };
+            let Some(spectator_name) =
+                read_controller_name(&memory, &entities, &spectator_controller)?
+            else {
+                continue;
+            };
+
+            let target_entity_id = observer_target_handle.get_entity_index();
+            let target_name = match entities
+                .entity_from_handle::<dyn C_BasePlayerPawn>(&EntityHandle::from_index(
+                    target_entity_id,
+                ))
                 .and_then(|e| e.value_reference(memory.view_arc()))
             {
-                Some(controller) => controller,
-                None => continue,
+                Some(target_pawn) => target_pawn.m_hController().ok().and_then(|controller| {
+                    read_controller_name(&memory, &entities, &controller)
+                        .ok()
+                        .flatten()
+                }),
+                None => None,
             };
 
-            let spectator_name =
-                match CStr::from_bytes_until_nul(&current_player_controller.m_iszPlayerName()?) {
-                    Ok(name) => match name.to_str() {
-                        Ok(s) => s.to_string(),
-                        Err(_) => continue,
-                    },
-                    Err(_) => continue,
-                };
-
-            spectators.push(SpectatorInfo { spectator_name });
+            spectators.push(SpectatorInfo {
+                spectator_name,
+                spectator_controller_id: spectator_controller.get_entity_index(),
+                target_entity_id,
+                target_name,
+            });
         }
 
+        Ok(Self { spectators })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Volatile
+    }
+}
+
+/// Spectators watching a specific entity
+pub struct SpectatorList {
+    pub target_entity_id: u32,
+    pub spectators: Vec<SpectatorInfo>,
+}
+
+impl State for SpectatorList {
+    type Parameter = u32;
+
+    fn create(states: &StateRegistry, target_entity_id: Self::Parameter) -> anyhow::Result<Self> {
+        let all_spectators = states.resolve::<StateSpectators>(())?;
+        let spectators = all_spectators
+            .spectators
+            .iter()
+            .filter(|spectator| spectator.target_entity_id == target_entity_id)
+            .cloned()
+            .collect();
+
         Ok(Self {
             spectators,
             target_entity_id,
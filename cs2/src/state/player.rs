@@ -1,26 +1,40 @@
 use std::{
     ffi::CStr,
     ops::Deref,
+    sync::Arc,
+    time::Duration,
 };
 
 use anyhow::{
+    anyhow,
     Context,
     Result,
 };
 use cs2_schema_cutl::EntityHandle;
 use cs2_schema_generated::cs2::client::{
+    CBasePlayerController,
+    CCSPlayerController,
+    CCSPlayerController_InGameMoneyServices,
     CCSPlayer_ItemServices,
+    CCSPlayer_MovementServices,
     CCSPlayer_WeaponServices,
     CGameSceneNode,
     CPlayer_WeaponServices,
     CSkeletonInstance,
     C_BaseEntity,
     C_BasePlayerPawn,
+    C_BaseCSGrenade,
     C_BasePlayerWeapon,
     C_CSPlayerPawn,
     C_CSPlayerPawnBase,
+    C_CSWeaponBase,
     C_EconEntity,
 };
+use raw_struct::{
+    Copy,
+    FromMemoryView,
+    Viewable,
+};
 use utils_state::{
     State,
     StateCacheType,
@@ -33,11 +47,27 @@ use crate::{
         CModelStateEx,
     },
     CS2Model,
+    MemoryBatch,
+    StateCS2Handle,
     StateCS2Memory,
     StateEntityList,
+    StateGlobals,
     WeaponId,
+    STATE_TAG_GAME,
+    WEAPON_FLAG_TYPE_GRENADE,
 };
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WeaponState {
+    #[default]
+    Ready,
+    Reloading,
+    /// Weapon is being drawn and can not be fired yet
+    Switching,
+    /// Clip is empty
+    Empty,
+}
+
 #[derive(Debug, Clone)]
 pub struct StatePawnInfo {
     pub controller_entity_id: Option<u32>,
@@ -45,14 +75,30 @@ pub struct StatePawnInfo {
     pub team_id: u8,
 
     pub player_health: i32,
+    pub player_armor: i32,
+    pub player_has_helmet: bool,
     pub player_has_defuser: bool,
     pub player_has_bomb: bool,
     pub player_name: Option<String>,
+    /// SteamID64 of the player (None for bots)
+    pub player_steam_id: Option<u64>,
     pub weapon: WeaponId,
     pub weapon_current_ammo: i32,
     pub weapon_reserve_ammo: i32,
+    pub weapon_state: WeaponState,
     pub player_is_scoped: bool,
     pub player_flashtime: f32,
+    /// Total duration of the current flash
+    pub player_flash_duration: f32,
+    /// Remaining seconds until the player can see again
+    pub player_flash_remaining: f32,
+    /// Max overlay alpha of the current flash (255 = fully blind)
+    pub player_flash_max_alpha: f32,
+
+    /// Current money of the player (None if the controller is unknown)
+    pub player_money: Option<i32>,
+    /// Value of the players current equipment
+    pub player_equipment_value: i32,
 
     pub player_has_flash: u32,
     pub player_has_smoke: bool,
@@ -63,6 +109,137 @@ pub struct StatePawnInfo {
 
     pub position: nalgebra::Vector3<f32>,
     pub rotation: f32,
+    /// View pitch in degrees, positive values are looking down
+    pub eye_pitch: f32,
+
+    /// Fields which failed to read and contain the last known value instead
+    pub stale_fields: Vec<&'static str>,
+}
+
+impl StatePawnInfo {
+    /// Returns true if at least one field could not be read and
+    /// has been populated with the last known (or default) value.
+    pub fn is_partial(&self) -> bool {
+        !self.stale_fields.is_empty()
+    }
+}
+
+/// Max health a player can have. Higher values are considered a bad read.
+const MAX_PLAYER_HEALTH: i32 = 100;
+
+/// Max absolute world coordinate of a valid position
+const WORLD_COORDINATE_LIMIT: f32 = 32_768.0;
+
+/// Max distance a player can move between two updates before the position is considered a bad read
+const MAX_POSITION_JUMP: f32 = 1_000.0;
+
+/// Amount of consecutive updates a position jump gets rejected.
+/// Afterwards the position is accepted as the player actually teleported (e.g. respawned).
+const MAX_REJECTED_POSITION_UPDATES: u32 = 3;
+
+/// Last successfully resolved pawn info.
+/// Used as fallback for individual fields which fail to read
+/// (e.g. due to an outdated offset after a game update).
+pub struct StatePawnInfoLastKnown {
+    info: Option<StatePawnInfo>,
+    /// Consecutive updates the position has been rejected due to a jump
+    rejected_position_updates: u32,
+    /// Entity index of the active weapon
+    active_weapon: Option<u32>,
+    /// The active weapon changed and has not been deployed yet
+    weapon_deploying: bool,
+}
+
+impl State for StatePawnInfoLastKnown {
+    type Parameter = EntityHandle<dyn C_CSPlayerPawn>;
+
+    fn create(_states: &StateRegistry, _handle: Self::Parameter) -> anyhow::Result<Self> {
+        Ok(Self {
+            info: None,
+            rejected_position_updates: 0,
+            active_weapon: None,
+            weapon_deploying: false,
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Timed(Duration::from_secs(10))
+    }
+
+    fn tag() -> Option<&'static str> {
+        Some(STATE_TAG_GAME)
+    }
+
+    fn owner(handle: &Self::Parameter) -> Option<u32> {
+        Some(handle.get_entity_index())
+    }
+}
+
+struct PawnFieldReader<'a> {
+    last_known: Option<&'a StatePawnInfo>,
+    stale_fields: Vec<&'static str>,
+    /// Fields which have been read but contained an implausible value
+    rejected_fields: Vec<&'static str>,
+}
+
+impl<'a> PawnFieldReader<'a> {
+    /// Turn an implausible value (e.g. caused by a bad read) into an error
+    /// so it gets replaced by the last known value.
+    fn sanity_check<T>(
+        &mut self,
+        field: &'static str,
+        value: anyhow::Result<T>,
+        is_valid: impl FnOnce(&T) -> bool,
+    ) -> anyhow::Result<T> {
+        match value {
+            Ok(value) if !is_valid(&value) => {
+                self.rejected_fields.push(field);
+                Err(anyhow::anyhow!("implausible value"))
+            }
+            value => value,
+        }
+    }
+
+    /// Read a field which is required for the pawn info to be useful.
+    /// If the read fails, the last known value will be used.
+    /// Fails if there is no last known value.
+    fn required<T>(
+        &mut self,
+        field: &'static str,
+        value: anyhow::Result<T>,
+        fallback: impl FnOnce(&StatePawnInfo) -> T,
+    ) -> anyhow::Result<T> {
+        match value {
+            Ok(value) => Ok(value),
+            Err(err) => {
+                let Some(last_known) = self.last_known else {
+                    return Err(err.context(field));
+                };
+
+                log::trace!("Failed to read pawn field {}: {:#}", field, err);
+                self.stale_fields.push(field);
+                Ok(fallback(last_known))
+            }
+        }
+    }
+
+    /// Read an optional field.
+    /// If the read fails, the last known value or the default value will be used.
+    fn optional<T: Default>(
+        &mut self,
+        field: &'static str,
+        value: anyhow::Result<T>,
+        fallback: impl FnOnce(&StatePawnInfo) -> T,
+    ) -> T {
+        match value {
+            Ok(value) => value,
+            Err(err) => {
+                log::trace!("Failed to read pawn field {}: {:#}", field, err);
+                self.stale_fields.push(field);
+                self.last_known.map(fallback).unwrap_or_default()
+            }
+        }
+    }
 }
 
 impl State for StatePawnInfo {
@@ -78,40 +255,191 @@ impl State for StatePawnInfo {
             .value_copy(memory.view())?
             .context("player pawn nullptr")?;
 
-        let player_health = player_pawn.m_iHealth()?;
-
-        let controller_handle = player_pawn.m_hController()?;
-        let current_controller = entities.entity_from_handle(&controller_handle);
+        /* read the objects referenced by the pawn at once instead of reading each field individually */
+        let mut batch = MemoryBatch::new();
+        if let Some(controller) = player_pawn
+            .m_hController()
+            .ok()
+            .filter(|handle| handle.is_valid())
+            .and_then(|handle| entities.entity_from_handle(&handle))
+        {
+            batch.add_object::<dyn CCSPlayerController>(controller.address);
+        }
+        if let Ok(item_services) = player_pawn.m_pItemServices() {
+            batch.add_object::<dyn CCSPlayer_ItemServices>(item_services.address);
+        }
+        if let Ok(weapon_services) = player_pawn.m_pWeaponServices() {
+            batch.add_object::<dyn CCSPlayer_WeaponServices>(weapon_services.address);
+        }
+        if let Ok(weapon) = player_pawn.m_pClippingWeapon() {
+            batch.add_object::<dyn C_CSWeaponBase>(weapon.address);
+        }
+        if let Ok(game_scene_node) = player_pawn.m_pGameSceneNode() {
+            batch.add_object::<dyn CSkeletonInstance>(game_scene_node.address);
+        }
 
-        let player_team = player_pawn.m_iTeamNum()?;
-        let player_name = if let Some(identity) = &current_controller {
-            let player_controller = identity
-                .value_reference(memory.view_arc())
-                .context("nullptr")?;
-            Some(
-                CStr::from_bytes_until_nul(&player_controller.m_iszPlayerName()?)
-                    .context("player name missing nul terminator")?
-                    .to_string_lossy()
-                    .to_string(),
-            )
-        } else {
-            None
+        let cs2 = states.resolve::<StateCS2Handle>(())?;
+        let memory = StateCS2Memory::new(Arc::new(cs2.read_batch(&batch)));
+
+        let mut last_known = states.resolve_mut::<StatePawnInfoLastKnown>(handle)?;
+        let last_rejected_position_updates = last_known.rejected_position_updates;
+        let last_active_weapon = last_known.active_weapon;
+        let mut weapon_deploying = last_known.weapon_deploying;
+        let mut reader = PawnFieldReader {
+            last_known: last_known.info.as_ref(),
+            stale_fields: Vec::new(),
+            rejected_fields: Vec::new(),
         };
 
-        let player_has_defuser = player_pawn
-            .m_pItemServices()?
-            .value_reference(memory.view_arc())
-            .context("m_pItemServices nullptr")?
-            .cast::<dyn CCSPlayer_ItemServices>()
-            .m_bHasDefuser()?;
-
-        let weapon_services = player_pawn
-            .m_pWeaponServices()?
-            .value_reference(memory.view_arc())
-            .context("m_pWeaponServices nullptr")?
-            .cast::<dyn CCSPlayer_WeaponServices>();
-
-        let ammo = weapon_services.m_iAmmo()?;
+        let player_health = reader.sanity_check(
+            "m_iHealth",
+            player_pawn.m_iHealth().map_err(Into::into),
+            |health| (0..=MAX_PLAYER_HEALTH).contains(health),
+        );
+        let player_health = reader.required("m_iHealth", player_health, |info| info.player_health)?;
+        let player_team = reader.required(
+            "m_iTeamNum",
+            player_pawn.m_iTeamNum().map_err(Into::into),
+            |info| info.team_id,
+        )?;
+
+        let controller_entity_id = reader.optional(
+            "m_hController",
+            player_pawn.m_hController().map_err(Into::into).map(|handle| {
+                if handle.is_valid() {
+                    Some(handle.get_entity_index())
+                } else {
+                    None
+                }
+            }),
+            |info| info.controller_entity_id,
+        );
+
+        let player_name = reader.optional(
+            "m_iszPlayerName",
+            (|| -> anyhow::Result<_> {
+                let Some(controller_entity_id) = controller_entity_id else {
+                    return Ok(None);
+                };
+                let Some(identity) = entities.entity_from_handle::<dyn CBasePlayerController>(
+                    &EntityHandle::from_index(controller_entity_id),
+                ) else {
+                    return Ok(None);
+                };
+
+                let player_controller = identity
+                    .value_reference(memory.view_arc())
+                    .context("nullptr")?;
+                Ok(Some(
+                    CStr::from_bytes_until_nul(&player_controller.m_iszPlayerName()?)
+                        .context("player name missing nul terminator")?
+                        .to_string_lossy()
+                        .to_string(),
+                ))
+            })(),
+            |info| info.player_name.clone(),
+        );
+        let player_steam_id = reader.optional(
+            "m_steamID",
+            (|| -> anyhow::Result<_> {
+                let Some(controller_entity_id) = controller_entity_id else {
+                    return Ok(None);
+                };
+                let Some(identity) = entities.entity_from_handle::<dyn CBasePlayerController>(
+                    &EntityHandle::from_index(controller_entity_id),
+                ) else {
+                    return Ok(None);
+                };
+
+                let steam_id = identity
+                    .value_reference(memory.view_arc())
+                    .context("nullptr")?
+                    .m_steamID()?;
+                Ok(Some(steam_id).filter(|steam_id| *steam_id != 0))
+            })(),
+            |info| info.player_steam_id,
+        );
+
+        let player_money = reader.optional(
+            "m_iAccount",
+            (|| -> anyhow::Result<_> {
+                let Some(controller_entity_id) = controller_entity_id else {
+                    return Ok(None);
+                };
+                let Some(identity) = entities.entity_from_handle::<dyn CCSPlayerController>(
+                    &EntityHandle::from_index(controller_entity_id),
+                ) else {
+                    return Ok(None);
+                };
+
+                let money_services = identity
+                    .value_reference(memory.view_arc())
+                    .context("nullptr")?
+                    .m_pInGameMoneyServices()?
+                    .value_reference(memory.view_arc())
+                    .context("m_pInGameMoneyServices nullptr")?;
+                Ok(Some(money_services.m_iAccount()?))
+            })(),
+            |info| info.player_money,
+        );
+        let player_equipment_value = reader.optional(
+            "m_unCurrentEquipmentValue",
+            player_pawn
+                .m_unCurrentEquipmentValue()
+                .map_err(Into::into)
+                .map(|value| value as i32),
+            |info| info.player_equipment_value,
+        );
+
+        let item_services = reader.optional(
+            "m_pItemServices",
+            (|| -> anyhow::Result<_> {
+                Ok(Some(
+                    player_pawn
+                        .m_pItemServices()?
+                        .value_reference(memory.view_arc())
+                        .context("m_pItemServices nullptr")?
+                        .cast::<dyn CCSPlayer_ItemServices>(),
+                ))
+            })(),
+            |_| None,
+        );
+
+        let player_has_defuser = reader.optional(
+            "m_bHasDefuser",
+            item_services
+                .as_ref()
+                .context("missing item services")
+                .and_then(|services| Ok(services.m_bHasDefuser()?)),
+            |info| info.player_has_defuser,
+        );
+        let player_has_helmet = reader.optional(
+            "m_bHasHelmet",
+            item_services
+                .as_ref()
+                .context("missing item services")
+                .and_then(|services| Ok(services.m_bHasHelmet()?)),
+            |info| info.player_has_helmet,
+        );
+        let player_armor = reader.optional(
+            "m_ArmorValue",
+            player_pawn.m_ArmorValue().map_err(Into::into),
+            |info| info.player_armor,
+        );
+
+        let ammo = reader.optional(
+            "m_iAmmo",
+            (|| -> anyhow::Result<_> {
+                let weapon_services = player_pawn
+                    .m_pWeaponServices()?
+                    .value_reference(memory.view_arc())
+                    .context("m_pWeaponServices nullptr")?
+                    .cast::<dyn CCSPlayer_WeaponServices>();
+
+                Ok(Some(weapon_services.m_iAmmo()?))
+            })(),
+            |_| None,
+        );
 
         let mut player_has_flash = 0;
         let mut player_has_smoke = false;
@@ -120,65 +448,219 @@ impl State for StatePawnInfo {
         let mut player_has_incendiary = false;
         let mut player_has_decoy = false;
 
-        let grenade_slots = [
-            (15, &mut player_has_smoke),
-            (13, &mut player_has_hegrenade),
-            (17, &mut player_has_decoy),
-        ];
+        if let Some(ammo) = &ammo {
+            let grenade_slots = [
+                (15, &mut player_has_smoke),
+                (13, &mut player_has_hegrenade),
+                (17, &mut player_has_decoy),
+            ];
 
-        if let Some(count) = ammo.get(14) {
-            player_has_flash = *count as u32;
-        }
+            if let Some(count) = ammo.get(14) {
+                player_has_flash = *count as u32;
+            }
 
-        for (index, flag) in grenade_slots {
-            if let Some(count) = ammo.get(index) {
-                *flag = *count > 0;
+            for (index, flag) in grenade_slots {
+                if let Some(count) = ammo.get(index) {
+                    *flag = *count > 0;
+                }
             }
-        }
 
-        if let Some(count) = ammo.get(16) {
-            if *count > 0 {
-                match player_team {
-                    2 => player_has_molotov = true,    // Terrorist
-                    3 => player_has_incendiary = true, // Counter-Terrorist
-                    _ => {}                            // Unknown team
+            if let Some(count) = ammo.get(16) {
+                if *count > 0 {
+                    match player_team {
+                        2 => player_has_molotov = true,    // Terrorist
+                        3 => player_has_incendiary = true, // Counter-Terrorist
+                        _ => {}                            // Unknown team
+                    }
                 }
             }
+        } else if let Some(last_known) = reader.last_known {
+            player_has_flash = last_known.player_has_flash;
+            player_has_smoke = last_known.player_has_smoke;
+            player_has_hegrenade = last_known.player_has_hegrenade;
+            player_has_molotov = last_known.player_has_molotov;
+            player_has_incendiary = last_known.player_has_incendiary;
+            player_has_decoy = last_known.player_has_decoy;
         }
 
-        /* Will be an instance of CSkeletonInstance */
-        let game_screen_node = player_pawn
-            .m_pGameSceneNode()?
-            .value_reference(memory.view_arc())
-            .context("game screen node nullptr")?
-            .cast::<dyn CSkeletonInstance>()
-            .copy()?;
+        let position = reader.sanity_check(
+            "m_vecAbsOrigin",
+            (|| -> anyhow::Result<_> {
+                /* Will be an instance of CSkeletonInstance */
+                let game_screen_node = player_pawn
+                    .m_pGameSceneNode()?
+                    .value_reference(memory.view_arc())
+                    .context("game screen node nullptr")?
+                    .cast::<dyn CSkeletonInstance>()
+                    .copy()?;
+
+                Ok(nalgebra::Vector3::<f32>::from_column_slice(
+                    &game_screen_node.m_vecAbsOrigin()?,
+                ))
+            })(),
+            |position| {
+                position
+                    .iter()
+                    .all(|value| value.is_finite() && value.abs() < WORLD_COORDINATE_LIMIT)
+            },
+        );
 
-        let position =
-            nalgebra::Vector3::<f32>::from_column_slice(&game_screen_node.m_vecAbsOrigin()?);
+        /* a sudden jump is most likely a bad read, but players which actually teleported should be followed */
+        let position_jumped = match (&position, reader.last_known) {
+            (Ok(position), Some(last_known)) => (position - last_known.position).norm() > MAX_POSITION_JUMP,
+            _ => false,
+        };
+        let rejected_position_updates = if position_jumped {
+            last_rejected_position_updates + 1
+        } else {
+            0
+        };
+        let position = reader.sanity_check("m_vecAbsOrigin", position, |_| {
+            !position_jumped || rejected_position_updates > MAX_REJECTED_POSITION_UPDATES
+        });
+        let position = reader.required("m_vecAbsOrigin", position, |info| info.position)?;
 
+        /* an empty hand counts as knife, a failed read must not be mistaken for one */
         let weapon_ref = player_pawn
-            .m_pClippingWeapon()?
-            .value_reference(memory.view_arc());
-        let weapon_type = if let Some(weapon) = &weapon_ref {
-            weapon
-                .cast::<dyn C_EconEntity>()
-                .m_AttributeManager()?
-                .m_Item()?
-                .m_iItemDefinitionIndex()?
-        } else {
-            WeaponId::Knife.id()
+            .m_pClippingWeapon()
+            .map(|weapon| weapon.value_reference(memory.view_arc()))
+            .map_err(anyhow::Error::from);
+        let clipping_weapon = || -> anyhow::Result<_> {
+            weapon_ref
+                .as_ref()
+                .map_err(|err| anyhow::anyhow!("m_pClippingWeapon: {:#}", err))
         };
 
-        let (weapon_current_ammo, weapon_reserve_ammo) = if let Some(weapon) = weapon_ref.as_ref() {
-            let weapon = weapon.cast::<dyn C_BasePlayerWeapon>();
-            (weapon.m_iClip1()?, weapon.m_pReserveAmmo()?[0])
-        } else {
-            (-1, 0)
+        let weapon = reader.optional(
+            "m_iItemDefinitionIndex",
+            (|| -> anyhow::Result<_> {
+                let weapon_type = if let Some(weapon) = clipping_weapon()? {
+                    weapon
+                        .cast::<dyn C_EconEntity>()
+                        .m_AttributeManager()?
+                        .m_Item()?
+                        .m_iItemDefinitionIndex()?
+                } else {
+                    WeaponId::Knife.id()
+                };
+
+                Ok(WeaponId::from_id(weapon_type))
+            })(),
+            |info| Some(info.weapon),
+        )
+        .unwrap_or(WeaponId::Unknown);
+
+        let (weapon_current_ammo, weapon_reserve_ammo) = reader.optional(
+            "m_iClip1",
+            (|| -> anyhow::Result<_> {
+                Ok(if let Some(weapon) = clipping_weapon()? {
+                    let weapon = weapon.cast::<dyn C_BasePlayerWeapon>();
+                    (weapon.m_iClip1()?, weapon.m_pReserveAmmo()?[0])
+                } else {
+                    (-1, 0)
+                })
+            })(),
+            |info| (info.weapon_current_ammo, info.weapon_reserve_ammo),
+        );
+        let weapon_services = || -> anyhow::Result<_> {
+            Ok(player_pawn
+                .m_pWeaponServices()?
+                .value_reference(memory.view_arc())
+                .context("m_pWeaponServices nullptr")?
+                .cast::<dyn CCSPlayer_WeaponServices>())
         };
+        let active_weapon = weapon_services()
+            .and_then(|services| Ok(services.m_hActiveWeapon()?))
+            .map(|handle| {
+                if handle.is_valid() {
+                    Some(handle.get_entity_index())
+                } else {
+                    None
+                }
+            });
+        match &active_weapon {
+            /* the first known weapon has not necessarily been switched to */
+            Ok(active_weapon) if last_active_weapon.is_some() && *active_weapon != last_active_weapon => {
+                weapon_deploying = true;
+            }
+            Ok(_) => {}
+            /* without the active weapon the deployment can not be tracked */
+            Err(_) => weapon_deploying = false,
+        }
+
+        let weapon_state = reader.optional(
+            "m_bInReload",
+            (|| -> anyhow::Result<_> {
+                let Some(weapon) = clipping_weapon()? else {
+                    return Ok(WeaponState::Ready);
+                };
+                if weapon.m_bInReload()? {
+                    weapon_deploying = false;
+                    return Ok(WeaponState::Reloading);
+                }
+                if weapon_current_ammo == 0 {
+                    return Ok(WeaponState::Empty);
+                }
+
+                /*
+                 * m_flNextAttack is set after every shot as well,
+                 * only a weapon which has just been switched to is considered to be drawn.
+                 */
+                if !weapon_deploying {
+                    return Ok(WeaponState::Ready);
+                }
 
-        let player_flashtime = player_pawn.m_flFlashBangTime()?;
-        let player_is_scoped = player_pawn.m_bIsScoped()?;
+                let next_attack = weapon_services()?.m_flNextAttack()?.m_Value()?;
+                let globals = states.resolve::<StateGlobals>(())?;
+                if next_attack > globals.time_2()? {
+                    Ok(WeaponState::Switching)
+                } else {
+                    weapon_deploying = false;
+                    Ok(WeaponState::Ready)
+                }
+            })(),
+            |info| info.weapon_state,
+        );
+
+        let player_flashtime = reader.optional(
+            "m_flFlashBangTime",
+            player_pawn.m_flFlashBangTime().map_err(Into::into),
+            |info| info.player_flashtime,
+        );
+        let player_flash_duration = reader.optional(
+            "m_flFlashDuration",
+            player_pawn.m_flFlashDuration().map_err(Into::into),
+            |info| info.player_flash_duration,
+        );
+        let player_flash_max_alpha = reader.optional(
+            "m_flFlashMaxAlpha",
+            player_pawn.m_flFlashMaxAlpha().map_err(Into::into),
+            |info| info.player_flash_max_alpha,
+        );
+        let player_flash_remaining = reader.optional(
+            "m_flFlashBangTime",
+            (|| -> anyhow::Result<_> {
+                let globals = states.resolve::<StateGlobals>(())?;
+                Ok((player_flashtime - globals.time_2()?).max(0.0))
+            })(),
+            |info| info.player_flash_remaining,
+        );
+        let player_is_scoped = reader.optional(
+            "m_bIsScoped",
+            player_pawn.m_bIsScoped().map_err(Into::into),
+            |info| info.player_is_scoped,
+        );
+        let eye_angles = reader.sanity_check(
+            "m_angEyeAngles",
+            player_pawn
+                .m_angEyeAngles()
+                .map_err(Into::into)
+                .map(|angles| [angles[0], angles[1]]),
+            |[pitch, yaw]| pitch.is_finite() && yaw.is_finite(),
+        );
+        let [eye_pitch, rotation] = reader.optional("m_angEyeAngles", eye_angles, |info| {
+            [info.eye_pitch, info.rotation]
+        });
 
         // Use cached bomb carrier state instead of iterating through all entities
         let player_has_bomb = if let Ok(bomb_carrier) = states.resolve::<super::BombCarrierInfo>(())
@@ -188,25 +670,37 @@ impl State for StatePawnInfo {
             false
         };
 
-        Ok(Self {
-            controller_entity_id: if controller_handle.is_valid() {
-                Some(controller_handle.get_entity_index())
-            } else {
-                None
-            },
+        let stale_fields = reader.stale_fields;
+        if !reader.rejected_fields.is_empty() {
+            log::trace!("Rejected implausible pawn fields {:?}", reader.rejected_fields);
+            cs2.record_rejected_values(reader.rejected_fields.len() as u64);
+        }
+
+        let info = Self {
+            controller_entity_id,
             pawn_entity_id: handle.get_entity_index(),
 
             team_id: player_team,
 
             player_name,
+            player_steam_id,
             player_has_defuser,
             player_has_bomb,
             player_health,
-            weapon: WeaponId::from_id(weapon_type).unwrap_or(WeaponId::Unknown),
+            player_armor,
+            player_has_helmet,
+            weapon,
             weapon_current_ammo,
             weapon_reserve_ammo,
+            weapon_state,
             player_is_scoped,
             player_flashtime,
+            player_flash_duration,
+            player_flash_remaining,
+            player_flash_max_alpha,
+
+            player_money,
+            player_equipment_value,
 
             player_has_flash,
             player_has_smoke,
@@ -216,13 +710,26 @@ impl State for StatePawnInfo {
             player_has_decoy,
 
             position,
-            rotation: player_pawn.m_angEyeAngles()?[1],
-        })
+            rotation,
+            eye_pitch,
+
+            stale_fields,
+        };
+
+        last_known.info = Some(info.clone());
+        last_known.rejected_position_updates = rejected_position_updates;
+        last_known.active_weapon = active_weapon.unwrap_or(None);
+        last_known.weapon_deploying = weapon_deploying;
+        Ok(info)
     }
 
     fn cache_type() -> StateCacheType {
         StateCacheType::Volatile
     }
+
+    fn owner(handle: &Self::Parameter) -> Option<u32> {
+        Some(handle.get_entity_index())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -254,6 +761,8 @@ pub struct StatePawnModelInfo {
 #[derive(Debug, Clone, Copy)]
 pub struct StatePawnModelAddress {
     pub model_address: u64,
+    /// Address of the bone state array of the pawns skeleton
+    pub bone_states_address: u64,
 }
 
 impl State for StatePawnModelAddress {
@@ -282,54 +791,58 @@ impl State for StatePawnModelAddress {
             .read_value(memory.view())?
             .context("m_hModel nullptr")?
             .address;
+        let bone_states_address = game_screen_node.m_modelState()?.bone_state_data()?.address;
         
-        Ok(Self { model_address })
+        Ok(Self {
+            model_address,
+            bone_states_address,
+        })
     }
     
     fn cache_type() -> StateCacheType { StateCacheType::Volatile }
+
+    fn owner(handle: &Self::Parameter) -> Option<u32> {
+        Some(handle.get_entity_index())
+    }
 }
 
 impl State for StatePawnModelInfo {
     type Parameter = EntityHandle<dyn C_CSPlayerPawn>;
 
     fn create(states: &StateRegistry, handle: Self::Parameter) -> anyhow::Result<Self> {
-        let memory = states.resolve::<StateCS2Memory>(())?;
-        let entities = states.resolve::<StateEntityList>(())?;
-        let Some(player_pawn) = entities.entity_from_handle(&handle) else {
-            anyhow::bail!("entity does not exists")
-        };
-        let player_pawn = player_pawn
-            .value_copy(memory.view())?
-            .context("player pawn nullptr")?;
-
-        let game_screen_node = player_pawn
-            .m_pGameSceneNode()?
-            .value_reference(memory.view_arc())
-            .context("game screen node nullptr")?
-            .cast::<dyn CSkeletonInstance>()
-            .copy()?;
-
-        let model_address = game_screen_node
-            .m_modelState()?
-            .m_hModel()?
-            .read_value(memory.view())?
-            .context("m_hModel nullptr")?
-            .address;
-
-        let model = states.resolve::<CS2Model>(model_address)?;
-        let bone_states = game_screen_node
-            .m_modelState()?
-            .bone_state_data()?
-            .elements_copy(memory.view(), 0..model.bones.len())?
-            .into_iter()
-            .map(|bone| bone.deref().try_into())
+        let cs2 = states.resolve::<StateCS2Handle>(())?;
+        let pawn_model = *states.resolve::<StatePawnModelAddress>(handle)?;
+        let model = states.resolve::<CS2Model>(pawn_model.model_address)?;
+
+        /* read all bones with a single call */
+        let bone_state_size = <dyn CBoneStateData as Viewable<_>>::MEMORY_SIZE;
+        let mut batch = MemoryBatch::new();
+        batch.add(
+            pawn_model.bone_states_address,
+            model.bones.len() * bone_state_size,
+        );
+        let memory = cs2.read_batch(&batch);
+
+        let bone_states = (0..model.bones.len())
+            .map(|index| {
+                let bone = Copy::<dyn CBoneStateData>::read_object(
+                    &memory,
+                    pawn_model.bone_states_address + (index * bone_state_size) as u64,
+                )
+                .map_err(|e| anyhow!(e))?;
+                bone.deref().try_into()
+            })
             .collect::<Result<Vec<_>>>()?;
 
         Ok(Self {
             bone_states,
-            model_address,
+            model_address: pawn_model.model_address,
         })
     }
+
+    fn owner(handle: &Self::Parameter) -> Option<u32> {
+        Some(handle.get_entity_index())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -376,4 +889,132 @@ impl State for PlayerPawnState {
     fn cache_type() -> StateCacheType {
         StateCacheType::Volatile
     }
+
+    fn owner(handle: &Self::Parameter) -> Option<u32> {
+        Some(handle.get_entity_index())
+    }
+}
+
+/// Throw state of the grenade a pawn is currently holding
+#[derive(Debug, Clone, Default)]
+pub struct StatePawnGrenadeThrow {
+    /// The grenade pin has been pulled and the grenade is about to be thrown
+    pub pin_pulled: bool,
+
+    /// The throw animation is currently playing
+    pub throw_animating: bool,
+
+    /// Throw strength from 0.0 (underhand, right click) to 1.0 (full throw, left click)
+    pub throw_strength: f32,
+}
+
+impl StatePawnGrenadeThrow {
+    pub fn is_throwing(&self) -> bool {
+        self.pin_pulled || self.throw_animating
+    }
+}
+
+impl State for StatePawnGrenadeThrow {
+    type Parameter = EntityHandle<dyn C_CSPlayerPawn>;
+
+    fn create(states: &StateRegistry, handle: Self::Parameter) -> anyhow::Result<Self> {
+        let memory = states.resolve::<StateCS2Memory>(())?;
+        let entities = states.resolve::<StateEntityList>(())?;
+
+        let player_pawn = entities
+            .entity_from_handle(&handle)
+            .context("missing player pawn")?
+            .value_reference(memory.view_arc())
+            .context("player pawn nullptr")?;
+
+        let Some(weapon) = player_pawn
+            .m_pClippingWeapon()?
+            .value_reference(memory.view_arc())
+        else {
+            return Ok(Default::default());
+        };
+
+        let weapon_type = weapon
+            .cast::<dyn C_EconEntity>()
+            .m_AttributeManager()?
+            .m_Item()?
+            .m_iItemDefinitionIndex()?;
+        let is_grenade = WeaponId::from_id(weapon_type)
+            .map(|weapon| weapon.flags() & WEAPON_FLAG_TYPE_GRENADE != 0)
+            .unwrap_or(false);
+        if !is_grenade {
+            return Ok(Default::default());
+        }
+
+        let grenade = weapon.cast::<dyn C_BaseCSGrenade>();
+        Ok(Self {
+            pin_pulled: grenade.m_bPinPulled()?,
+            throw_animating: grenade.m_bThrowAnimating()?,
+            throw_strength: grenade.m_flThrowStrength()?,
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Volatile
+    }
+
+    fn owner(handle: &Self::Parameter) -> Option<u32> {
+        Some(handle.get_entity_index())
+    }
+}
+
+/// Entity flag set while the entity is standing on the ground
+const FL_ONGROUND: u32 = 1 << 0;
+
+#[derive(Debug, Clone, Default)]
+pub struct StatePawnMovement {
+    /// Absolute velocity in units per second
+    pub velocity: nalgebra::Vector3<f32>,
+    pub on_ground: bool,
+
+    /// Duck progress from 0.0 (standing) to 1.0 (fully ducked)
+    pub duck_amount: f32,
+}
+
+impl StatePawnMovement {
+    /// Horizontal speed in units per second
+    pub fn speed_2d(&self) -> f32 {
+        self.velocity.xy().norm()
+    }
+}
+
+impl State for StatePawnMovement {
+    type Parameter = EntityHandle<dyn C_CSPlayerPawn>;
+
+    fn create(states: &StateRegistry, handle: Self::Parameter) -> anyhow::Result<Self> {
+        let memory = states.resolve::<StateCS2Memory>(())?;
+        let entities = states.resolve::<StateEntityList>(())?;
+
+        let player_pawn = entities
+            .entity_from_handle(&handle)
+            .context("missing player pawn")?
+            .value_reference(memory.view_arc())
+            .context("player pawn nullptr")?;
+
+        let duck_amount = player_pawn
+            .m_pMovementServices()?
+            .value_reference(memory.view_arc())
+            .context("m_pMovementServices nullptr")?
+            .cast::<dyn CCSPlayer_MovementServices>()
+            .m_flDuckAmount()?;
+
+        Ok(Self {
+            velocity: nalgebra::Vector3::from(player_pawn.m_vecAbsVelocity()?),
+            on_ground: player_pawn.m_fFlags()? & FL_ONGROUND != 0,
+            duck_amount,
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Volatile
+    }
+
+    fn owner(handle: &Self::Parameter) -> Option<u32> {
+        Some(handle.get_entity_index())
+    }
 }
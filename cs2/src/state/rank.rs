@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use cs2_schema_cutl::EntityHandle;
+use cs2_schema_generated::cs2::client::CCSPlayerController;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use crate::{
+    StateCS2Memory,
+    StateEntityList,
+    STATE_TAG_GAME,
+};
+
+const COMPETITIVE_RANK_NAMES: [&str; 18] = [
+    "Silver I",
+    "Silver II",
+    "Silver III",
+    "Silver IV",
+    "Silver Elite",
+    "Silver Elite Master",
+    "Gold Nova I",
+    "Gold Nova II",
+    "Gold Nova III",
+    "Gold Nova Master",
+    "Master Guardian I",
+    "Master Guardian II",
+    "Master Guardian Elite",
+    "Distinguished Master Guardian",
+    "Legendary Eagle",
+    "Legendary Eagle Master",
+    "Supreme Master First Class",
+    "Global Elite",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompetitiveRankType {
+    None,
+    Competitive,
+    Wingman,
+    Premier,
+    Unknown(i8),
+}
+
+impl CompetitiveRankType {
+    pub fn from_id(value: i8) -> Self {
+        match value {
+            0 => Self::None,
+            6 => Self::Competitive,
+            7 => Self::Wingman,
+            11 => Self::Premier,
+            value => Self::Unknown(value),
+        }
+    }
+}
+
+/// Competitive rank of a player.
+/// The parameter is the entity index of the player controller.
+#[derive(Debug, Clone)]
+pub struct StatePlayerRank {
+    pub rank_type: CompetitiveRankType,
+
+    /// Skill group (1 - 18) or the premier rating
+    pub ranking: i32,
+    pub wins: i32,
+}
+
+impl StatePlayerRank {
+    pub fn is_ranked(&self) -> bool {
+        self.ranking > 0 && !matches!(self.rank_type, CompetitiveRankType::None)
+    }
+
+    /// Human readable rank (e.g. "Gold Nova II" or "14,230")
+    pub fn display_name(&self) -> Option<String> {
+        if !self.is_ranked() {
+            return None;
+        }
+
+        match self.rank_type {
+            CompetitiveRankType::Premier => Some(format!(
+                "{},{:03}",
+                self.ranking / 1000,
+                self.ranking % 1000
+            )),
+            _ => COMPETITIVE_RANK_NAMES
+                .get(self.ranking as usize - 1)
+                .map(ToString::to_string),
+        }
+    }
+
+    /// Name of the rank icon texture (without extension)
+    pub fn icon_name(&self) -> Option<String> {
+        if !self.is_ranked() {
+            return None;
+        }
+
+        match self.rank_type {
+            CompetitiveRankType::Competitive => Some(format!("competitive_{}", self.ranking)),
+            CompetitiveRankType::Wingman => Some(format!("wingman_{}", self.ranking)),
+            /* premier rating colors change every 5000 points */
+            CompetitiveRankType::Premier => Some(format!("premier_{}", self.ranking / 5000)),
+            _ => None,
+        }
+    }
+}
+
+impl State for StatePlayerRank {
+    type Parameter = u32;
+
+    fn create(states: &StateRegistry, controller_entity_id: Self::Parameter) -> anyhow::Result<Self> {
+        let memory = states.resolve::<StateCS2Memory>(())?;
+        let entities = states.resolve::<StateEntityList>(())?;
+
+        let controller = entities
+            .entity_from_handle::<dyn CCSPlayerController>(&EntityHandle::from_index(
+                controller_entity_id,
+            ))
+            .context("missing player controller")?
+            .value_reference(memory.view_arc())
+            .context("player controller nullptr")?;
+
+        Ok(Self {
+            rank_type: CompetitiveRankType::from_id(controller.m_iCompetitiveRankType()?),
+            ranking: controller.m_iCompetitiveRanking()?,
+            wins: controller.m_iCompetitiveWins()?,
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Ttl(Duration::from_secs(5))
+    }
+
+    fn tag() -> Option<&'static str> {
+        Some(STATE_TAG_GAME)
+    }
+
+    fn owner(controller_entity_id: &Self::Parameter) -> Option<u32> {
+        Some(*controller_entity_id)
+    }
+}
@@ -0,0 +1,78 @@
+use std::ffi::CStr;
+
+/// Render the windows with the given names into a separate texture which is
+/// shared with capture software (e.g. OBS using the Spout plugin).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureOptions {
+    /// Name under which the texture is published
+    pub sender_name: String,
+    /// Names of the imgui windows drawn into the capture texture.
+    /// Child windows of these windows are included.
+    pub windows: Vec<String>,
+}
+
+impl CaptureOptions {
+    pub(crate) fn includes(&self, draw_list: &imgui::DrawList) -> bool {
+        draw_list_owner(draw_list).is_some_and(|owner| self.includes_window(owner))
+    }
+
+    /// Child windows are named after their parent followed by a slash
+    fn includes_window(&self, window_name: &[u8]) -> bool {
+        self.windows.iter().any(|window| {
+            window_name == window.as_bytes()
+                || (window_name.starts_with(window.as_bytes())
+                    && window_name.get(window.len()) == Some(&b'/'))
+        })
+    }
+}
+
+/// Name of the window which owns the draw list
+fn draw_list_owner(draw_list: &imgui::DrawList) -> Option<&[u8]> {
+    /* DrawList is a transparent wrapper around ImDrawList */
+    let draw_list =
+        unsafe { &*(draw_list as *const imgui::DrawList as *const imgui::sys::ImDrawList) };
+    if draw_list._OwnerName.is_null() {
+        return None;
+    }
+
+    Some(unsafe { CStr::from_ptr(draw_list._OwnerName) }.to_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use super::CaptureOptions;
+
+    fn options(windows: &[&str]) -> CaptureOptions {
+        CaptureOptions {
+            sender_name: "test".to_string(),
+            windows: windows.iter().map(|window| window.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn includes_window() {
+        let options = options(&["overlay", "Radar"]);
+        assert!(options.includes_window(b"overlay"));
+        assert!(options.includes_window(b"Radar"));
+        assert!(!options.includes_window(b"Settings"));
+        assert!(!options.includes_window(b""));
+    }
+
+    #[test]
+    fn includes_child_windows() {
+        let options = options(&["overlay"]);
+        assert!(options.includes_window(b"overlay/child_1234ABCD"));
+        assert!(options.includes_window(b"overlay/child_1234ABCD/nested_5678"));
+
+        /* windows sharing the prefix are not children */
+        assert!(!options.includes_window(b"overlay2"));
+        assert!(!options.includes_window(b"overlay_debug/child"));
+        assert!(!options.includes_window(b"over"));
+    }
+
+    #[test]
+    fn includes_nothing() {
+        let options = options(&[]);
+        assert!(!options.includes_window(b"overlay"));
+    }
+}
@@ -1,88 +1,108 @@
 use std::collections::HashMap;
 use imgui::TextureId;
 use imgui_winit_support::winit::window::Window;
-use windows::Win32::{
-    Foundation::{
-        HWND,
-        RECT,
-    },
-    Graphics::{
-        Direct3D::{
-            Fxc::D3DCompile,
-            ID3DBlob,
-            D3D_DRIVER_TYPE_HARDWARE,
-            D3D_DRIVER_TYPE_WARP,
-            D3D_FEATURE_LEVEL_10_0,
-            D3D_FEATURE_LEVEL_10_1,
-            D3D_FEATURE_LEVEL_11_0,
-            D3D_PRIMITIVE_TOPOLOGY_TRIANGLELIST,
-        },
-        Direct3D11::{
-            D3D11CreateDeviceAndSwapChain,
-            ID3D11BlendState,
-            ID3D11Buffer,
-            ID3D11Device,
-            ID3D11DeviceContext,
-            ID3D11InputLayout,
-            ID3D11PixelShader,
-            ID3D11RasterizerState,
-            ID3D11RenderTargetView,
-            ID3D11SamplerState,
-            ID3D11ShaderResourceView,
-            ID3D11Texture2D,
-            ID3D11VertexShader,
-            D3D11_BIND_CONSTANT_BUFFER,
-            D3D11_BIND_INDEX_BUFFER,
-            D3D11_BIND_SHADER_RESOURCE,
-            D3D11_BIND_VERTEX_BUFFER,
-            D3D11_BLEND_DESC,
-            D3D11_BLEND_INV_SRC_ALPHA,
-            D3D11_BLEND_ONE,
-            D3D11_BLEND_OP_ADD,
-            D3D11_BLEND_SRC_ALPHA,
-            D3D11_BUFFER_DESC,
-            D3D11_COLOR_WRITE_ENABLE_ALL,
-            D3D11_COMPARISON_ALWAYS,
-            D3D11_CPU_ACCESS_FLAG,
-            D3D11_CPU_ACCESS_WRITE,
-            D3D11_CREATE_DEVICE_FLAG,
-            D3D11_CULL_NONE,
-            D3D11_FILL_SOLID,
-            D3D11_FILTER_MIN_MAG_MIP_LINEAR,
-            D3D11_INPUT_ELEMENT_DESC,
-            D3D11_INPUT_PER_VERTEX_DATA,
-            D3D11_MAPPED_SUBRESOURCE,
-            D3D11_MAP_WRITE_DISCARD,
-            D3D11_RASTERIZER_DESC,
-            D3D11_RENDER_TARGET_BLEND_DESC,
-            D3D11_RESOURCE_MISC_FLAG,
-            D3D11_SAMPLER_DESC,
-            D3D11_SDK_VERSION,
-            D3D11_SUBRESOURCE_DATA,
-            D3D11_TEXTURE2D_DESC,
-            D3D11_TEXTURE_ADDRESS_WRAP,
-            D3D11_USAGE_DEFAULT,
-            D3D11_USAGE_DYNAMIC,
-            D3D11_VIEWPORT,
+use windows::{
+    core::ComInterface,
+    Win32::{
+        Foundation::{
+            HANDLE,
+            HWND,
+            RECT,
         },
-        Dxgi::{
-            Common::{
-                DXGI_FORMAT_R16_UINT,
-                DXGI_FORMAT_R32G32_FLOAT,
-                DXGI_FORMAT_R8G8B8A8_UNORM,
-                DXGI_MODE_DESC,
-                DXGI_MODE_SCALING_UNSPECIFIED,
-                DXGI_MODE_SCANLINE_ORDER_UNSPECIFIED,
-                DXGI_RATIONAL,
-                DXGI_SAMPLE_DESC,
+        Graphics::{
+            Direct3D::{
+                Fxc::D3DCompile,
+                ID3DBlob,
+                D3D_DRIVER_TYPE_HARDWARE,
+                D3D_DRIVER_TYPE_WARP,
+                D3D_FEATURE_LEVEL_10_0,
+                D3D_FEATURE_LEVEL_10_1,
+                D3D_FEATURE_LEVEL_11_0,
+                D3D_PRIMITIVE_TOPOLOGY_TRIANGLELIST,
+            },
+            Direct3D11::{
+                D3D11CreateDeviceAndSwapChain,
+                ID3D11BlendState,
+                ID3D11Buffer,
+                ID3D11DepthStencilState,
+                ID3D11DepthStencilView,
+                ID3D11Device,
+                ID3D11DeviceContext,
+                ID3D11InputLayout,
+                ID3D11PixelShader,
+                ID3D11RasterizerState,
+                ID3D11RenderTargetView,
+                ID3D11SamplerState,
+                ID3D11ShaderResourceView,
+                ID3D11Texture2D,
+                ID3D11VertexShader,
+                D3D11_BIND_CONSTANT_BUFFER,
+                D3D11_BIND_DEPTH_STENCIL,
+                D3D11_BIND_INDEX_BUFFER,
+                D3D11_BIND_RENDER_TARGET,
+                D3D11_BIND_SHADER_RESOURCE,
+                D3D11_BIND_VERTEX_BUFFER,
+                D3D11_BLEND_DESC,
+                D3D11_BLEND_INV_SRC_ALPHA,
+                D3D11_BLEND_ONE,
+                D3D11_BLEND_OP_ADD,
+                D3D11_BLEND_SRC_ALPHA,
+                D3D11_BUFFER_DESC,
+                D3D11_CLEAR_DEPTH,
+                D3D11_COLOR_WRITE_ENABLE_ALL,
+                D3D11_COMPARISON_ALWAYS,
+                D3D11_COMPARISON_LESS,
+                D3D11_CPU_ACCESS_FLAG,
+                D3D11_CPU_ACCESS_WRITE,
+                D3D11_CREATE_DEVICE_FLAG,
+                D3D11_CULL_NONE,
+                D3D11_DEPTH_STENCIL_DESC,
+                D3D11_DEPTH_WRITE_MASK_ALL,
+                D3D11_FILL_SOLID,
+                D3D11_FILTER_MIN_MAG_MIP_LINEAR,
+                D3D11_INPUT_ELEMENT_DESC,
+                D3D11_INPUT_PER_VERTEX_DATA,
+                D3D11_MAPPED_SUBRESOURCE,
+                D3D11_MAP_WRITE_DISCARD,
+                D3D11_RASTERIZER_DESC,
+                D3D11_RENDER_TARGET_BLEND_DESC,
+                D3D11_RESOURCE_MISC_FLAG,
+                D3D11_RESOURCE_MISC_SHARED,
+                D3D11_SAMPLER_DESC,
+                D3D11_SDK_VERSION,
+                D3D11_SUBRESOURCE_DATA,
+                D3D11_TEXTURE2D_DESC,
+                D3D11_TEXTURE_ADDRESS_WRAP,
+                D3D11_USAGE_DEFAULT,
+                D3D11_USAGE_DYNAMIC,
+                D3D11_USAGE_IMMUTABLE,
+                D3D11_VIEWPORT,
+            },
+            Dxgi::{
+                Common::{
+                    DXGI_FORMAT,
+                    DXGI_FORMAT_B8G8R8A8_UNORM,
+                    DXGI_FORMAT_D24_UNORM_S8_UINT,
+                    DXGI_FORMAT_R16_UINT,
+                    DXGI_FORMAT_R32G32B32_FLOAT,
+                    DXGI_FORMAT_R32G32_FLOAT,
+                    DXGI_FORMAT_R32_UINT,
+                    DXGI_FORMAT_R8G8B8A8_UNORM,
+                    DXGI_MODE_DESC,
+                    DXGI_MODE_SCALING_UNSPECIFIED,
+                    DXGI_MODE_SCANLINE_ORDER_UNSPECIFIED,
+                    DXGI_RATIONAL,
+                    DXGI_SAMPLE_DESC,
+                },
+                IDXGIResource,
+                IDXGISwapChain,
+                DXGI_ERROR_DEVICE_REMOVED,
+                DXGI_ERROR_DEVICE_RESET,
+                DXGI_ERROR_INVALID_CALL,
+                DXGI_SWAP_CHAIN_DESC,
+                DXGI_SWAP_EFFECT_DISCARD,
+                DXGI_USAGE_RENDER_TARGET_OUTPUT,
             },
-            IDXGISwapChain,
-            DXGI_ERROR_DEVICE_REMOVED,
-            DXGI_ERROR_DEVICE_RESET,
-            DXGI_ERROR_INVALID_CALL,
-            DXGI_SWAP_CHAIN_DESC,
-            DXGI_SWAP_EFFECT_DISCARD,
-            DXGI_USAGE_RENDER_TARGET_OUTPUT,
         },
     },
 };
@@ -91,8 +111,15 @@ use winit::raw_window_handle::{
     RawWindowHandle,
 };
 
+mod spout;
+use spout::SpoutSender;
+
 use crate::{
+    CaptureOptions,
     DirectXError,
+    MeshRenderRequest,
+    MeshVertex,
+    OverlayError,
     PerfTracker,
     RenderBackend,
     Result, // This is the Result<T, OverlayError>
@@ -106,6 +133,133 @@ struct ImGuiVertex {
     col: [u8; 4],
 }
 
+/// Constant buffer layout of the mesh shaders
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct MeshConstants {
+    model: [f32; 16],
+    view_projection: [f32; 16],
+    color: [f32; 4],
+    light_direction: [f32; 4],
+}
+
+const MESH_VS_SOURCE: &str = r#"
+cbuffer meshBuffer : register(b0) {
+    float4x4 Model;
+    float4x4 ViewProjection;
+    float4 Color;
+    float4 LightDirection;
+};
+
+struct VS_INPUT {
+    float3 pos: POSITION;
+    float3 normal: NORMAL;
+};
+
+struct PS_INPUT {
+    float4 pos: SV_POSITION;
+    float3 normal: NORMAL;
+};
+
+PS_INPUT main(VS_INPUT input) {
+    PS_INPUT output;
+    output.pos = mul(ViewProjection, mul(Model, float4(input.pos, 1.0f)));
+    /* the view projection uses an OpenGL style depth range */
+    output.pos.z = (output.pos.z + output.pos.w) * 0.5f;
+    output.normal = mul((float3x3)Model, input.normal);
+    return output;
+}
+"#;
+
+const MESH_PS_SOURCE: &str = r#"
+cbuffer meshBuffer : register(b0) {
+    float4x4 Model;
+    float4x4 ViewProjection;
+    float4 Color;
+    float4 LightDirection;
+};
+
+struct PS_INPUT {
+    float4 pos: SV_POSITION;
+    float3 normal: NORMAL;
+};
+
+float4 main(PS_INPUT input) : SV_Target {
+    float diffuse = saturate(dot(normalize(input.normal), normalize(LightDirection.xyz)));
+    return float4(Color.rgb * (diffuse * 0.7f + 0.3f), Color.a);
+}
+"#;
+
+/// Pipeline used to render meshes into offscreen textures.
+/// Created on the first mesh upload.
+struct MeshPipeline {
+    vertex_shader: ID3D11VertexShader,
+    pixel_shader: ID3D11PixelShader,
+    input_layout: ID3D11InputLayout,
+    constant_buffer: ID3D11Buffer,
+    rasterizer_state: ID3D11RasterizerState,
+    depth_stencil_state: ID3D11DepthStencilState,
+}
+
+struct MeshRenderTarget {
+    size: (u32, u32),
+    texture_id: TextureId,
+    render_target_view: ID3D11RenderTargetView,
+    depth_stencil_view: ID3D11DepthStencilView,
+}
+
+/// Format of the capture texture, preferred by Spout receivers
+const CAPTURE_FORMAT: DXGI_FORMAT = DXGI_FORMAT_B8G8R8A8_UNORM;
+
+/// Shared texture the capture output is rendered into
+struct CaptureTarget {
+    size: (u32, u32),
+    render_target_view: ID3D11RenderTargetView,
+    sender: SpoutSender,
+}
+
+struct DirectXMesh {
+    vertex_buffer: ID3D11Buffer,
+    index_buffer: ID3D11Buffer,
+    index_count: u32,
+    target: Option<MeshRenderTarget>,
+}
+
+unsafe fn compile_shader(source: &str, target: &[u8]) -> std::result::Result<ID3DBlob, DirectXError> {
+    let mut blob: Option<ID3DBlob> = None;
+    let mut error_blob: Option<ID3DBlob> = None;
+    let result = D3DCompile(
+        source.as_ptr() as *const std::ffi::c_void,
+        source.len(),
+        None,
+        None,
+        None,
+        windows::core::PCSTR(b"main\0".as_ptr()),
+        windows::core::PCSTR(target.as_ptr()),
+        0,
+        0,
+        &mut blob,
+        Some(&mut error_blob),
+    );
+
+    if let Err(error) = result {
+        if let Some(error_blob) = error_blob {
+            let error_data = std::slice::from_raw_parts(
+                error_blob.GetBufferPointer() as *const u8,
+                error_blob.GetBufferSize(),
+            );
+            log::error!("Shader compilation error: {}", String::from_utf8_lossy(error_data));
+        }
+        return Err(DirectXError::DeviceCreationFailed(error));
+    }
+
+    blob.ok_or_else(|| DirectXError::DeviceCreationFailed(windows::core::Error::from_win32()))
+}
+
+unsafe fn blob_data(blob: &ID3DBlob) -> &[u8] {
+    std::slice::from_raw_parts(blob.GetBufferPointer() as *const u8, blob.GetBufferSize())
+}
+
 pub struct DirectXRenderBackend {
     device: Option<ID3D11Device>,
     device_context: Option<ID3D11DeviceContext>,
@@ -118,6 +272,11 @@ pub struct DirectXRenderBackend {
     index_buffer_size: usize,
 
     textures: HashMap<TextureId, ID3D11ShaderResourceView>,
+    /// Ids are never reused, so a removed texture can not be confused with a newer one
+    next_texture_id: usize,
+    /// Texture of the font atlas. Tracked separately as the atlas id of a fresh imgui
+    /// context defaults to 0, which might already belong to another texture.
+    font_texture: Option<TextureId>,
     sampler: Option<ID3D11SamplerState>,
 
     blend_state: Option<ID3D11BlendState>,
@@ -129,6 +288,12 @@ pub struct DirectXRenderBackend {
 
     constant_buffer: Option<ID3D11Buffer>,
 
+    mesh_pipeline: Option<MeshPipeline>,
+    meshes: HashMap<u64, DirectXMesh>,
+
+    capture: Option<CaptureOptions>,
+    capture_target: Option<CaptureTarget>,
+
     window_size: (u32, u32),
     dirty_swap_chain: bool,
 }
@@ -460,6 +625,8 @@ float4 main(PS_INPUT input) : SV_Target {
             vertex_buffer_size: 0,
             index_buffer_size: 0,
             textures: HashMap::new(),
+            next_texture_id: 0,
+            font_texture: None,
             sampler,
             blend_state,
             rasterizer_state,
@@ -467,6 +634,10 @@ float4 main(PS_INPUT input) : SV_Target {
             pixel_shader,
             input_layout,
             constant_buffer,
+            mesh_pipeline: None,
+            meshes: HashMap::new(),
+            capture: None,
+            capture_target: None,
             window_size,
             dirty_swap_chain: false,
         })
@@ -510,6 +681,344 @@ float4 main(PS_INPUT input) : SV_Target {
         Ok(())
     }
 
+    unsafe fn create_mesh_pipeline(device: &ID3D11Device) -> std::result::Result<MeshPipeline, DirectXError> {
+        let vs_blob = compile_shader(MESH_VS_SOURCE, b"vs_4_0\0")?;
+        let ps_blob = compile_shader(MESH_PS_SOURCE, b"ps_4_0\0")?;
+
+        let mut vertex_shader = None;
+        device.CreateVertexShader(blob_data(&vs_blob), None, Some(&mut vertex_shader))?;
+
+        let mut pixel_shader = None;
+        device.CreatePixelShader(blob_data(&ps_blob), None, Some(&mut pixel_shader))?;
+
+        let input_layout_desc = [
+            D3D11_INPUT_ELEMENT_DESC {
+                SemanticName: windows::core::PCSTR(b"POSITION\0".as_ptr()),
+                SemanticIndex: 0,
+                Format: DXGI_FORMAT_R32G32B32_FLOAT,
+                InputSlot: 0,
+                AlignedByteOffset: 0,
+                InputSlotClass: D3D11_INPUT_PER_VERTEX_DATA,
+                InstanceDataStepRate: 0,
+            },
+            D3D11_INPUT_ELEMENT_DESC {
+                SemanticName: windows::core::PCSTR(b"NORMAL\0".as_ptr()),
+                SemanticIndex: 0,
+                Format: DXGI_FORMAT_R32G32B32_FLOAT,
+                InputSlot: 0,
+                AlignedByteOffset: 12,
+                InputSlotClass: D3D11_INPUT_PER_VERTEX_DATA,
+                InstanceDataStepRate: 0,
+            },
+        ];
+
+        let mut input_layout = None;
+        device.CreateInputLayout(&input_layout_desc, blob_data(&vs_blob), Some(&mut input_layout))?;
+
+        let constant_buffer_desc = D3D11_BUFFER_DESC {
+            ByteWidth: std::mem::size_of::<MeshConstants>() as u32,
+            Usage: D3D11_USAGE_DYNAMIC,
+            BindFlags: D3D11_BIND_CONSTANT_BUFFER,
+            CPUAccessFlags: D3D11_CPU_ACCESS_WRITE,
+            MiscFlags: D3D11_RESOURCE_MISC_FLAG(0),
+            StructureByteStride: 0,
+        };
+        let mut constant_buffer = None;
+        device.CreateBuffer(&constant_buffer_desc, None, Some(&mut constant_buffer))?;
+
+        /* the depth buffer resolves the triangle order, culling is not required */
+        let rasterizer_desc = D3D11_RASTERIZER_DESC {
+            FillMode: D3D11_FILL_SOLID,
+            CullMode: D3D11_CULL_NONE,
+            FrontCounterClockwise: windows::Win32::Foundation::FALSE,
+            DepthBias: 0,
+            DepthBiasClamp: 0.0,
+            SlopeScaledDepthBias: 0.0,
+            DepthClipEnable: windows::Win32::Foundation::TRUE,
+            ScissorEnable: windows::Win32::Foundation::FALSE,
+            MultisampleEnable: windows::Win32::Foundation::FALSE,
+            AntialiasedLineEnable: windows::Win32::Foundation::FALSE,
+        };
+        let mut rasterizer_state = None;
+        device.CreateRasterizerState(&rasterizer_desc, Some(&mut rasterizer_state))?;
+
+        let depth_stencil_desc = D3D11_DEPTH_STENCIL_DESC {
+            DepthEnable: windows::Win32::Foundation::TRUE,
+            DepthWriteMask: D3D11_DEPTH_WRITE_MASK_ALL,
+            DepthFunc: D3D11_COMPARISON_LESS,
+            StencilEnable: windows::Win32::Foundation::FALSE,
+            ..Default::default()
+        };
+        let mut depth_stencil_state = None;
+        device.CreateDepthStencilState(&depth_stencil_desc, Some(&mut depth_stencil_state))?;
+
+        let missing = || DirectXError::DeviceCreationFailed(windows::core::Error::from_win32());
+        Ok(MeshPipeline {
+            vertex_shader: vertex_shader.ok_or_else(missing)?,
+            pixel_shader: pixel_shader.ok_or_else(missing)?,
+            input_layout: input_layout.ok_or_else(missing)?,
+            constant_buffer: constant_buffer.ok_or_else(missing)?,
+            rasterizer_state: rasterizer_state.ok_or_else(missing)?,
+            depth_stencil_state: depth_stencil_state.ok_or_else(missing)?,
+        })
+    }
+
+    /// Create the color and depth textures of a mesh render target.
+    /// The texture id is kept when resizing an existing target.
+    unsafe fn create_mesh_render_target(
+        &mut self,
+        size: (u32, u32),
+        texture_id: Option<TextureId>,
+    ) -> std::result::Result<MeshRenderTarget, DirectXError> {
+        let device = self
+            .device
+            .as_ref()
+            .ok_or(DirectXError::DeviceCreationFailed(windows::core::Error::from_win32()))?;
+        let missing = || DirectXError::DeviceCreationFailed(windows::core::Error::from_win32());
+
+        let mut texture_desc = D3D11_TEXTURE2D_DESC {
+            Width: size.0,
+            Height: size.1,
+            MipLevels: 1,
+            ArraySize: 1,
+            Format: DXGI_FORMAT_R8G8B8A8_UNORM,
+            SampleDesc: DXGI_SAMPLE_DESC { Count: 1, Quality: 0 },
+            Usage: D3D11_USAGE_DEFAULT,
+            BindFlags: D3D11_BIND_RENDER_TARGET | D3D11_BIND_SHADER_RESOURCE,
+            CPUAccessFlags: D3D11_CPU_ACCESS_FLAG(0),
+            MiscFlags: D3D11_RESOURCE_MISC_FLAG(0),
+        };
+
+        let mut texture = None;
+        device.CreateTexture2D(&texture_desc, None, Some(&mut texture))?;
+        let texture = texture.ok_or_else(missing)?;
+
+        let mut render_target_view = None;
+        device.CreateRenderTargetView(&texture, None, Some(&mut render_target_view))?;
+
+        let mut shader_resource_view = None;
+        device.CreateShaderResourceView(&texture, None, Some(&mut shader_resource_view))?;
+
+        texture_desc.Format = DXGI_FORMAT_D24_UNORM_S8_UINT;
+        texture_desc.BindFlags = D3D11_BIND_DEPTH_STENCIL;
+
+        let mut depth_texture = None;
+        device.CreateTexture2D(&texture_desc, None, Some(&mut depth_texture))?;
+        let depth_texture = depth_texture.ok_or_else(missing)?;
+
+        let mut depth_stencil_view = None;
+        device.CreateDepthStencilView(&depth_texture, None, Some(&mut depth_stencil_view))?;
+
+        let texture_id = texture_id.unwrap_or_else(|| self.allocate_texture_id());
+        self.textures
+            .insert(texture_id, shader_resource_view.ok_or_else(missing)?);
+
+        Ok(MeshRenderTarget {
+            size,
+            texture_id,
+            render_target_view: render_target_view.ok_or_else(missing)?,
+            depth_stencil_view: depth_stencil_view.ok_or_else(missing)?,
+        })
+    }
+
+    fn allocate_texture_id(&mut self) -> TextureId {
+        let texture_id = TextureId::new(self.next_texture_id);
+        self.next_texture_id += 1;
+        texture_id
+    }
+
+    unsafe fn create_texture_view(
+        &self,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> std::result::Result<ID3D11ShaderResourceView, DirectXError> {
+        let device = self.device.as_ref().ok_or(DirectXError::DeviceCreationFailed(windows::core::Error::from_win32()))?;
+
+        let texture_desc = D3D11_TEXTURE2D_DESC {
+            Width: width,
+            Height: height,
+            MipLevels: 1,
+            ArraySize: 1,
+            Format: DXGI_FORMAT_R8G8B8A8_UNORM,
+            SampleDesc: DXGI_SAMPLE_DESC { Count: 1, Quality: 0 },
+            Usage: D3D11_USAGE_DEFAULT,
+            BindFlags: D3D11_BIND_SHADER_RESOURCE,
+            CPUAccessFlags: D3D11_CPU_ACCESS_FLAG(0),
+            MiscFlags: D3D11_RESOURCE_MISC_FLAG(0),
+        };
+
+        let subresource_data = D3D11_SUBRESOURCE_DATA {
+            pSysMem: data.as_ptr() as *const _,
+            SysMemPitch: width * 4,
+            SysMemSlicePitch: 0,
+        };
+
+        let mut texture = None;
+        device.CreateTexture2D(&texture_desc, Some(&subresource_data), Some(&mut texture))?;
+        
+        let mut shader_resource_view = None;
+        device.CreateShaderResourceView(texture.as_ref().unwrap(), None, Some(&mut shader_resource_view))?;
+        Ok(shader_resource_view.unwrap())
+    }
+
+    /// Create a texture which can be opened by other processes using the returned handle
+    unsafe fn create_capture_texture(
+        &self,
+        size: (u32, u32),
+    ) -> std::result::Result<(ID3D11RenderTargetView, HANDLE), DirectXError> {
+        let missing = || DirectXError::DeviceCreationFailed(windows::core::Error::from_win32());
+        let device = self.device.as_ref().ok_or_else(missing)?;
+
+        let texture_desc = D3D11_TEXTURE2D_DESC {
+            Width: size.0,
+            Height: size.1,
+            MipLevels: 1,
+            ArraySize: 1,
+            Format: CAPTURE_FORMAT,
+            SampleDesc: DXGI_SAMPLE_DESC { Count: 1, Quality: 0 },
+            Usage: D3D11_USAGE_DEFAULT,
+            BindFlags: D3D11_BIND_RENDER_TARGET | D3D11_BIND_SHADER_RESOURCE,
+            CPUAccessFlags: D3D11_CPU_ACCESS_FLAG(0),
+            MiscFlags: D3D11_RESOURCE_MISC_SHARED,
+        };
+
+        let mut texture = None;
+        device.CreateTexture2D(&texture_desc, None, Some(&mut texture))?;
+        let texture = texture.ok_or_else(missing)?;
+
+        let mut render_target_view = None;
+        device.CreateRenderTargetView(&texture, None, Some(&mut render_target_view))?;
+
+        let share_handle = texture
+            .cast::<IDXGIResource>()
+            .and_then(|resource| resource.GetSharedHandle())
+            .map_err(DirectXError::CaptureSharingFailed)?;
+
+        Ok((render_target_view.ok_or_else(missing)?, share_handle))
+    }
+
+    /// Render the captured windows into the shared capture texture.
+    /// Must be called after the frame has been drawn, as the render state is reused.
+    unsafe fn render_capture(
+        &mut self,
+        draw_data: &imgui::DrawData,
+        draw_data_uploaded: bool,
+    ) -> std::result::Result<(), DirectXError> {
+        let Some(options) = &self.capture else {
+            return Ok(());
+        };
+
+        let size = self.window_size;
+        if self.capture_target.as_ref().map_or(true, |target| target.size != size) {
+            let (render_target_view, share_handle) = self.create_capture_texture(size)?;
+            match &mut self.capture_target {
+                Some(target) => {
+                    target.sender.update(share_handle, size.0, size.1);
+                    target.size = size;
+                    target.render_target_view = render_target_view;
+                }
+                None => {
+                    let sender = SpoutSender::new(
+                        &options.sender_name,
+                        CAPTURE_FORMAT,
+                        share_handle,
+                        size.0,
+                        size.1,
+                    )
+                    .map_err(DirectXError::CaptureSharingFailed)?;
+                    log::info!("Sharing the capture output as Spout sender {}", options.sender_name);
+
+                    self.capture_target = Some(CaptureTarget {
+                        size,
+                        render_target_view,
+                        sender,
+                    });
+                }
+            }
+        }
+
+        let (Some(device_context), Some(target)) = (&self.device_context, &self.capture_target) else {
+            return Ok(());
+        };
+
+        device_context.OMSetRenderTargets(Some(&[Some(target.render_target_view.clone())]), None);
+        let clear_color = [0.0f32, 0.0f32, 0.0f32, 0.0f32];
+        device_context.ClearRenderTargetView(&target.render_target_view, clear_color.as_ptr());
+
+        if draw_data_uploaded {
+            self.draw_lists(device_context, draw_data, |draw_list| options.includes(draw_list));
+        }
+
+        /* other devices only see the new contents once the commands have been submitted */
+        device_context.Flush();
+        Ok(())
+    }
+
+    /// Draw the imgui draw lists accepted by the filter.
+    /// The draw data must have been uploaded and the render state set up.
+    unsafe fn draw_lists(
+        &self,
+        device_context: &ID3D11DeviceContext,
+        draw_data: &imgui::DrawData,
+        filter: impl Fn(&imgui::DrawList) -> bool,
+    ) {
+        let mut global_vtx_offset = 0;
+        let mut global_idx_offset = 0;
+        let clip_off = draw_data.display_pos;
+        let clip_scale = draw_data.framebuffer_scale;
+
+        for draw_list in draw_data.draw_lists() {
+            if !filter(draw_list) {
+                global_vtx_offset += draw_list.vtx_buffer().len();
+                global_idx_offset += draw_list.idx_buffer().len();
+                continue;
+            }
+
+            for cmd in draw_list.commands() {
+                match cmd {
+                    imgui::DrawCmd::Elements { count, cmd_params } => {
+                        if let Some(texture_view) = self.textures.get(&cmd_params.texture_id) {
+                            device_context.PSSetShaderResources(0, Some(&[Some(texture_view.clone())]));
+                        }
+
+                        if count > 0 {
+                            let clip_rect = cmd_params.clip_rect;
+                            let r = RECT {
+                                left: ((clip_rect[0] - clip_off[0]) * clip_scale[0])
+                                    as i32,
+                                top: ((clip_rect[1] - clip_off[1]) * clip_scale[1])
+                                    as i32,
+                                right: ((clip_rect[2] - clip_off[0])
+                                    * clip_scale[0])
+                                    as i32,
+                                bottom: ((clip_rect[3] - clip_off[1])
+                                    * clip_scale[1])
+                                    as i32,
+                            };
+                            device_context.RSSetScissorRects(Some(&[r]));
+
+                            let vtx_offset =
+                                (global_vtx_offset + cmd_params.vtx_offset) as i32;
+                            let idx_offset =
+                                (global_idx_offset + cmd_params.idx_offset) as u32;
+
+                            device_context.DrawIndexed(
+                                count as u32,
+                                idx_offset,
+                                vtx_offset,
+                            );
+                        }
+                    }
+                    imgui::DrawCmd::ResetRenderState => {}
+                    imgui::DrawCmd::RawCallback { .. } => {}
+                }
+            }
+            global_vtx_offset += draw_list.vtx_buffer().len();
+            global_idx_offset += draw_list.idx_buffer().len();
+        }
+    }
+
     unsafe fn ensure_buffers(
         &mut self,
         vtx_count: usize,
@@ -610,46 +1119,223 @@ impl RenderBackend for DirectXRenderBackend {
         let fonts = imgui.fonts();
         let texture = fonts.build_rgba32_texture();
 
-        if let Ok(font_tex_id) = unsafe { self.add_texture(texture.data, texture.width, texture.height) } {
+        if let Some(font_texture) = self.font_texture {
+            /* replace the previous atlas instead of leaking it on every rebuild */
+            if let Err(error) = unsafe {
+                self.update_texture(font_texture, texture.data, texture.width, texture.height)
+            } {
+                log::error!("Failed to update font texture: {}", error);
+            }
+            fonts.tex_id = font_texture;
+        } else if let Ok(font_tex_id) = unsafe { self.add_texture(texture.data, texture.width, texture.height) } {
+            self.font_texture = Some(font_tex_id);
             fonts.tex_id = font_tex_id;
         } else {
             log::error!("Failed to create font texture");
         }
     }
     
-    // THIS IS THE LINE THAT IS NOW FIXED
     unsafe fn add_texture(&mut self, data: &[u8], width: u32, height: u32) -> Result<TextureId> {
+        let shader_resource_view = self.create_texture_view(data, width, height)?;
+
+        let texture_id = self.allocate_texture_id();
+        self.textures.insert(texture_id, shader_resource_view);
+        Ok(texture_id)
+    }
+
+    unsafe fn update_texture(
+        &mut self,
+        texture_id: TextureId,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        if !self.textures.contains_key(&texture_id) {
+            return Err(OverlayError::UnknownTexture(texture_id.id()));
+        }
+
+        /* the size might have changed, recreate the texture instead of updating its subresource */
+        let shader_resource_view = self.create_texture_view(data, width, height)?;
+        self.textures.insert(texture_id, shader_resource_view);
+        Ok(())
+    }
+
+    unsafe fn remove_texture(&mut self, texture_id: TextureId) -> Result<()> {
+        if self.font_texture == Some(texture_id) {
+            self.font_texture = None;
+        }
+
+        self.textures
+            .remove(&texture_id)
+            .map(|_| ())
+            .ok_or(OverlayError::UnknownTexture(texture_id.id()))
+    }
+
+    fn resize_surface(&mut self, _width: u32, _height: u32) {
+        self.dirty_swap_chain = true;
+    }
+
+    fn mesh_rendering_supported(&self) -> bool {
+        true
+    }
+
+    fn capture_supported(&self) -> bool {
+        true
+    }
+
+    fn set_capture(&mut self, options: Option<CaptureOptions>) -> Result<()> {
+        let sender_name = |options: &Option<CaptureOptions>| {
+            options.as_ref().map(|options| options.sender_name.clone())
+        };
+        if sender_name(&self.capture) != sender_name(&options) {
+            /* the sender will be recreated with the new name on the next frame */
+            self.capture_target = None;
+        }
+
+        self.capture = options;
+        Ok(())
+    }
+
+    unsafe fn add_mesh(&mut self, tag: u64, vertices: &[MeshVertex], indices: &[u32]) -> Result<()> {
         let device = self.device.as_ref().ok_or(DirectXError::DeviceCreationFailed(windows::core::Error::from_win32()))?;
+        if self.mesh_pipeline.is_none() {
+            self.mesh_pipeline = Some(Self::create_mesh_pipeline(device)?);
+        }
 
-        let texture_desc = D3D11_TEXTURE2D_DESC {
-            Width: width,
-            Height: height,
-            MipLevels: 1,
-            ArraySize: 1,
-            Format: DXGI_FORMAT_R8G8B8A8_UNORM,
-            SampleDesc: DXGI_SAMPLE_DESC { Count: 1, Quality: 0 },
-            Usage: D3D11_USAGE_DEFAULT,
-            BindFlags: D3D11_BIND_SHADER_RESOURCE,
+        let vertex_buffer_desc = D3D11_BUFFER_DESC {
+            ByteWidth: std::mem::size_of_val(vertices) as u32,
+            Usage: D3D11_USAGE_IMMUTABLE,
+            BindFlags: D3D11_BIND_VERTEX_BUFFER,
             CPUAccessFlags: D3D11_CPU_ACCESS_FLAG(0),
             MiscFlags: D3D11_RESOURCE_MISC_FLAG(0),
+            StructureByteStride: 0,
+        };
+        let vertex_data = D3D11_SUBRESOURCE_DATA {
+            pSysMem: vertices.as_ptr() as *const _,
+            SysMemPitch: 0,
+            SysMemSlicePitch: 0,
         };
+        let mut vertex_buffer = None;
+        device.CreateBuffer(&vertex_buffer_desc, Some(&vertex_data), Some(&mut vertex_buffer))?;
 
-        let subresource_data = D3D11_SUBRESOURCE_DATA {
-            pSysMem: data.as_ptr() as *const _,
-            SysMemPitch: width * 4,
+        let index_buffer_desc = D3D11_BUFFER_DESC {
+            ByteWidth: std::mem::size_of_val(indices) as u32,
+            BindFlags: D3D11_BIND_INDEX_BUFFER,
+            ..vertex_buffer_desc
+        };
+        let index_data = D3D11_SUBRESOURCE_DATA {
+            pSysMem: indices.as_ptr() as *const _,
+            SysMemPitch: 0,
             SysMemSlicePitch: 0,
         };
+        let mut index_buffer = None;
+        device.CreateBuffer(&index_buffer_desc, Some(&index_data), Some(&mut index_buffer))?;
+
+        let missing = || DirectXError::DeviceCreationFailed(windows::core::Error::from_win32());
+        let texture_id = self.meshes.remove(&tag).and_then(|mesh| mesh.target).map(|target| target.texture_id);
+        let mut mesh = DirectXMesh {
+            vertex_buffer: vertex_buffer.ok_or_else(missing)?,
+            index_buffer: index_buffer.ok_or_else(missing)?,
+            index_count: indices.len() as u32,
+            target: None,
+        };
+        if let Some(texture_id) = texture_id {
+            /* keep the texture id of the replaced mesh valid */
+            mesh.target = Some(self.create_mesh_render_target((1, 1), Some(texture_id))?);
+        }
 
-        let mut texture = None;
-        device.CreateTexture2D(&texture_desc, Some(&subresource_data), Some(&mut texture))?;
-        
-        let mut shader_resource_view = None;
-        device.CreateShaderResourceView(texture.as_ref().unwrap(), None, Some(&mut shader_resource_view))?;
+        self.meshes.insert(tag, mesh);
+        Ok(())
+    }
 
-        let texture_id = TextureId::new(self.textures.len());
-        self.textures.insert(texture_id, shader_resource_view.unwrap());
-        
-        Ok(texture_id)
+    unsafe fn render_mesh(&mut self, request: &MeshRenderRequest) -> Result<TextureId> {
+        let size = (request.width.max(1), request.height.max(1));
+        let Some(mesh) = self.meshes.get(&request.mesh) else {
+            return Err(DirectXError::RenderingFailed(format!("unknown mesh {}", request.mesh)).into());
+        };
+
+        let current_target = mesh.target.as_ref().map(|target| (target.size, target.texture_id));
+        if current_target.map(|(target_size, _)| target_size) != Some(size) {
+            let target = self.create_mesh_render_target(size, current_target.map(|(_, texture_id)| texture_id))?;
+            if let Some(mesh) = self.meshes.get_mut(&request.mesh) {
+                mesh.target = Some(target);
+            }
+        }
+
+        let (Some(device_context), Some(pipeline), Some(mesh)) =
+            (&self.device_context, &self.mesh_pipeline, self.meshes.get(&request.mesh))
+        else {
+            return Err(DirectXError::RenderingFailed("mesh pipeline unavailable".to_string()).into());
+        };
+        let Some(target) = &mesh.target else {
+            return Err(DirectXError::RenderingFailed("mesh render target unavailable".to_string()).into());
+        };
+
+        let [light_x, light_y, light_z] = request.light_direction;
+        let constants = MeshConstants {
+            model: request.model.as_slice().try_into().unwrap_or_default(),
+            view_projection: request.view_projection.as_slice().try_into().unwrap_or_default(),
+            color: request.color,
+            light_direction: [light_x, light_y, light_z, 0.0],
+        };
+
+        let mut mapped_resource = D3D11_MAPPED_SUBRESOURCE::default();
+        device_context.Map(
+            &pipeline.constant_buffer,
+            0,
+            D3D11_MAP_WRITE_DISCARD,
+            0,
+            Some(&mut mapped_resource),
+        )?;
+        *(mapped_resource.pData as *mut MeshConstants) = constants;
+        device_context.Unmap(&pipeline.constant_buffer, 0);
+
+        device_context.OMSetRenderTargets(
+            Some(&[Some(target.render_target_view.clone())]),
+            Some(&target.depth_stencil_view),
+        );
+        let clear_color = [0.0f32, 0.0f32, 0.0f32, 0.0f32];
+        device_context.ClearRenderTargetView(&target.render_target_view, clear_color.as_ptr());
+        device_context.ClearDepthStencilView(&target.depth_stencil_view, D3D11_CLEAR_DEPTH.0 as u32, 1.0, 0);
+
+        /* the imgui pass restores the blend state, render target and pipeline afterwards */
+        device_context.OMSetBlendState(None, None, 0xFFFFFFFF);
+        device_context.OMSetDepthStencilState(Some(&pipeline.depth_stencil_state), 0);
+        device_context.RSSetState(Some(&pipeline.rasterizer_state));
+        device_context.RSSetViewports(Some(&[D3D11_VIEWPORT {
+            TopLeftX: 0.0,
+            TopLeftY: 0.0,
+            Width: size.0 as f32,
+            Height: size.1 as f32,
+            MinDepth: 0.0,
+            MaxDepth: 1.0,
+        }]));
+
+        device_context.VSSetShader(Some(&pipeline.vertex_shader), None);
+        device_context.PSSetShader(Some(&pipeline.pixel_shader), None);
+        device_context.VSSetConstantBuffers(0, Some(&[Some(pipeline.constant_buffer.clone())]));
+        device_context.PSSetConstantBuffers(0, Some(&[Some(pipeline.constant_buffer.clone())]));
+        device_context.IASetInputLayout(Some(&pipeline.input_layout));
+        device_context.IASetPrimitiveTopology(D3D_PRIMITIVE_TOPOLOGY_TRIANGLELIST);
+
+        let vertex_buffers = [Some(mesh.vertex_buffer.clone())];
+        let strides = [std::mem::size_of::<MeshVertex>() as u32];
+        let offsets = [0u32];
+        device_context.IASetVertexBuffers(
+            0,
+            1,
+            Some(vertex_buffers.as_ptr()),
+            Some(strides.as_ptr()),
+            Some(offsets.as_ptr()),
+        );
+        device_context.IASetIndexBuffer(Some(&mesh.index_buffer), DXGI_FORMAT_R32_UINT, 0);
+        device_context.DrawIndexed(mesh.index_count, 0, 0);
+
+        /* the texture will be sampled by the imgui pass */
+        device_context.OMSetRenderTargets(None, None);
+        device_context.OMSetDepthStencilState(None, 0);
+
+        Ok(target.texture_id)
     }
 
     fn render_frame(
@@ -698,6 +1384,7 @@ impl RenderBackend for DirectXRenderBackend {
             }
         }
 
+        let mut draw_data_uploaded = false;
         if total_vtx > 0 {
             if let Ok(()) = unsafe { self.ensure_buffers(total_vtx as usize, total_idx as usize) } {
                 if let Ok(()) = unsafe { self.upload_draw_data(draw_data) } {
@@ -781,59 +1468,19 @@ impl RenderBackend for DirectXRenderBackend {
                             0,
                         );
 
-                        let mut global_vtx_offset = 0;
-                        let mut global_idx_offset = 0;
-                        let clip_off = draw_data.display_pos;
-                        let clip_scale = draw_data.framebuffer_scale;
-
-                        for draw_list in draw_data.draw_lists() {
-                            for cmd in draw_list.commands() {
-                                match cmd {
-                                    imgui::DrawCmd::Elements { count, cmd_params } => {
-                                        if let Some(texture_view) = self.textures.get(&cmd_params.texture_id) {
-                                            device_context.PSSetShaderResources(0, Some(&[Some(texture_view.clone())]));
-                                        }
-
-                                        if count > 0 {
-                                            let clip_rect = cmd_params.clip_rect;
-                                            let r = RECT {
-                                                left: ((clip_rect[0] - clip_off[0]) * clip_scale[0])
-                                                    as i32,
-                                                top: ((clip_rect[1] - clip_off[1]) * clip_scale[1])
-                                                    as i32,
-                                                right: ((clip_rect[2] - clip_off[0])
-                                                    * clip_scale[0])
-                                                    as i32,
-                                                bottom: ((clip_rect[3] - clip_off[1])
-                                                    * clip_scale[1])
-                                                    as i32,
-                                            };
-                                            device_context.RSSetScissorRects(Some(&[r]));
-
-                                            let vtx_offset =
-                                                (global_vtx_offset + cmd_params.vtx_offset) as i32;
-                                            let idx_offset =
-                                                (global_idx_offset + cmd_params.idx_offset) as u32;
-
-                                            device_context.DrawIndexed(
-                                                count as u32,
-                                                idx_offset,
-                                                vtx_offset,
-                                            );
-                                        }
-                                    }
-                                    imgui::DrawCmd::ResetRenderState => {}
-                                    imgui::DrawCmd::RawCallback { .. } => {}
-                                }
-                            }
-                            global_vtx_offset += draw_list.vtx_buffer().len();
-                            global_idx_offset += draw_list.idx_buffer().len();
-                        }
+                        self.draw_lists(device_context, draw_data, |_| true);
                     }
+                    draw_data_uploaded = true;
                 }
             }
         }
 
+        if let Err(err) = unsafe { self.render_capture(draw_data, draw_data_uploaded) } {
+            log::error!("Failed to render the capture output, disabling it: {:?}", err);
+            self.capture = None;
+            self.capture_target = None;
+        }
+
         perf.mark("directx_imgui");
 
         if let Some(swap_chain) = &self.swap_chain {
@@ -0,0 +1,315 @@
+use std::ffi::CString;
+
+use windows::{
+    core::{
+        Error,
+        Result,
+        PCSTR,
+    },
+    Win32::{
+        Foundation::{
+            CloseHandle,
+            BOOL,
+            E_INVALIDARG,
+            HANDLE,
+            INVALID_HANDLE_VALUE,
+            WAIT_ABANDONED,
+            WAIT_OBJECT_0,
+        },
+        Graphics::Dxgi::Common::DXGI_FORMAT,
+        System::{
+            Memory::{
+                CreateFileMappingA,
+                MapViewOfFile,
+                UnmapViewOfFile,
+                FILE_MAP_ALL_ACCESS,
+                MEMORY_MAPPED_VIEW_ADDRESS,
+                PAGE_READWRITE,
+            },
+            Threading::{
+                CreateMutexA,
+                ReleaseMutex,
+                WaitForSingleObject,
+            },
+        },
+    },
+};
+
+/// Shared memory containing the names of all active senders
+const SENDER_NAMES_MAP: &str = "SpoutSenderNames";
+/// Default number of senders Spout applications expect within the sender names map
+const MAX_SENDERS: usize = 64;
+/// Max length of a sender name including the null terminator
+const MAX_SENDER_NAME_LENGTH: usize = 256;
+
+/// Timeout for acquiring the lock of a shared memory owned by another application
+const LOCK_TIMEOUT_MS: u32 = 67;
+
+/// Description of a sender texture as expected by Spout receivers
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SharedTextureInfo {
+    share_handle: u32,
+    width: u32,
+    height: u32,
+    format: u32,
+    usage: u32,
+    description: [u16; 128],
+    partner_id: u32,
+}
+
+/// Named shared memory guarded by a named mutex, compatible with Spout's shared memory
+struct SharedMemory {
+    mapping: HANDLE,
+    view: MEMORY_MAPPED_VIEW_ADDRESS,
+    mutex: HANDLE,
+    size: usize,
+}
+
+impl SharedMemory {
+    /// Create the shared memory or open it if another application already created it
+    fn open(name: &str, size: usize) -> Result<Self> {
+        let map_name = CString::new(name).map_err(|_| Error::from(E_INVALIDARG))?;
+        let mutex_name =
+            CString::new(format!("{}_mutex", name)).map_err(|_| Error::from(E_INVALIDARG))?;
+
+        unsafe {
+            let mapping = CreateFileMappingA(
+                INVALID_HANDLE_VALUE,
+                None,
+                PAGE_READWRITE,
+                0,
+                size as u32,
+                PCSTR::from_raw(map_name.as_ptr() as *const u8),
+            )?;
+
+            let view = MapViewOfFile(mapping, FILE_MAP_ALL_ACCESS, 0, 0, size);
+            if view.Value.is_null() {
+                let error = Error::from_win32();
+                CloseHandle(mapping);
+                return Err(error);
+            }
+
+            let mutex = match CreateMutexA(
+                None,
+                BOOL::from(false),
+                PCSTR::from_raw(mutex_name.as_ptr() as *const u8),
+            ) {
+                Ok(mutex) => mutex,
+                Err(error) => {
+                    UnmapViewOfFile(view);
+                    CloseHandle(mapping);
+                    return Err(error);
+                }
+            };
+
+            Ok(Self {
+                mapping,
+                view,
+                mutex,
+                size,
+            })
+        }
+    }
+
+    /// Access the memory while holding the lock shared with other Spout applications.
+    /// If the lock can not be acquired in time the memory is accessed anyways.
+    fn with_lock<R>(&mut self, f: impl FnOnce(&mut [u8]) -> R) -> R {
+        let wait_result = unsafe { WaitForSingleObject(self.mutex, LOCK_TIMEOUT_MS) };
+        let locked = wait_result == WAIT_OBJECT_0 || wait_result == WAIT_ABANDONED;
+
+        let data = unsafe { std::slice::from_raw_parts_mut(self.view.Value as *mut u8, self.size) };
+        let result = f(data);
+
+        if locked {
+            unsafe { ReleaseMutex(self.mutex) };
+        }
+        result
+    }
+}
+
+impl Drop for SharedMemory {
+    fn drop(&mut self) {
+        unsafe {
+            UnmapViewOfFile(self.view);
+            CloseHandle(self.mutex);
+            CloseHandle(self.mapping);
+        }
+    }
+}
+
+fn read_sender_names(data: &[u8]) -> Vec<String> {
+    data.chunks_exact(MAX_SENDER_NAME_LENGTH)
+        .map(|slot| {
+            let length = slot
+                .iter()
+                .position(|value| *value == 0)
+                .unwrap_or(slot.len());
+            &slot[..length]
+        })
+        .take_while(|name| !name.is_empty())
+        .map(|name| String::from_utf8_lossy(name).into_owned())
+        .collect()
+}
+
+fn write_sender_names(data: &mut [u8], names: &[String]) {
+    data.fill(0);
+    for (slot, name) in data.chunks_exact_mut(MAX_SENDER_NAME_LENGTH).zip(names) {
+        /* the last byte of every slot stays zero */
+        let length = name.len().min(MAX_SENDER_NAME_LENGTH - 1);
+        slot[..length].copy_from_slice(&name.as_bytes()[..length]);
+    }
+}
+
+/// Publishes a shared DirectX texture as Spout sender, so capture software can receive it
+pub struct SpoutSender {
+    name: String,
+    format: DXGI_FORMAT,
+    /// The name has been added to the sender names by this sender
+    registered: bool,
+    names: SharedMemory,
+    info: SharedMemory,
+}
+
+impl SpoutSender {
+    pub fn new(
+        name: &str,
+        format: DXGI_FORMAT,
+        share_handle: HANDLE,
+        width: u32,
+        height: u32,
+    ) -> Result<Self> {
+        if name.is_empty() || name.len() >= MAX_SENDER_NAME_LENGTH {
+            return Err(E_INVALIDARG.into());
+        }
+
+        let names = SharedMemory::open(SENDER_NAMES_MAP, MAX_SENDERS * MAX_SENDER_NAME_LENGTH)?;
+        let info = SharedMemory::open(name, std::mem::size_of::<SharedTextureInfo>())?;
+        let mut sender = Self {
+            name: name.to_string(),
+            format,
+            registered: false,
+            names,
+            info,
+        };
+
+        sender.registered = sender.names.with_lock(|data| {
+            let mut names = read_sender_names(data);
+            if names.iter().any(|value| *value == name) {
+                return false;
+            }
+
+            names.push(name.to_string());
+            write_sender_names(data, &names);
+            true
+        });
+        if !sender.registered {
+            log::warn!("A Spout sender named {} already exists", name);
+        }
+
+        sender.update(share_handle, width, height);
+        Ok(sender)
+    }
+
+    /// Update the published texture after it has been recreated
+    pub fn update(&mut self, share_handle: HANDLE, width: u32, height: u32) {
+        let info = SharedTextureInfo {
+            /* shared handles only use the lower 32 bits, even in 64 bit processes */
+            share_handle: share_handle.0 as u32,
+            width,
+            height,
+            format: self.format.0 as u32,
+            usage: 0,
+            description: [0; 128],
+            partner_id: 0,
+        };
+
+        self.info.with_lock(|data| unsafe {
+            std::ptr::write_unaligned(data.as_mut_ptr() as *mut SharedTextureInfo, info);
+        });
+    }
+}
+
+impl Drop for SpoutSender {
+    fn drop(&mut self) {
+        if !self.registered {
+            return;
+        }
+
+        let name = &self.name;
+        self.names.with_lock(|data| {
+            let mut names = read_sender_names(data);
+            names.retain(|value| value != name);
+            write_sender_names(data, &names);
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        read_sender_names,
+        write_sender_names,
+        MAX_SENDERS,
+        MAX_SENDER_NAME_LENGTH,
+    };
+
+    fn names_map() -> Vec<u8> {
+        vec![0xCC; MAX_SENDERS * MAX_SENDER_NAME_LENGTH]
+    }
+
+    #[test]
+    fn sender_names_round_trip() {
+        let names = vec!["LABH".to_string(), "OBS Source".to_string()];
+
+        let mut data = names_map();
+        write_sender_names(&mut data, &names);
+        assert_eq!(read_sender_names(&data), names);
+
+        /* slots after the last name are cleared */
+        assert!(data[2 * MAX_SENDER_NAME_LENGTH..]
+            .iter()
+            .all(|value| *value == 0));
+    }
+
+    #[test]
+    fn sender_names_empty() {
+        let mut data = names_map();
+        write_sender_names(&mut data, &[]);
+        assert!(read_sender_names(&data).is_empty());
+        assert!(read_sender_names(&[]).is_empty());
+    }
+
+    #[test]
+    fn sender_names_truncated() {
+        let long_name = "x".repeat(MAX_SENDER_NAME_LENGTH + 10);
+
+        let mut data = names_map();
+        write_sender_names(&mut data, &[long_name, "next".to_string()]);
+        assert_eq!(data[MAX_SENDER_NAME_LENGTH - 1], 0);
+
+        let names = read_sender_names(&data);
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[0].len(), MAX_SENDER_NAME_LENGTH - 1);
+        assert_eq!(names[1], "next");
+    }
+
+    #[test]
+    fn sender_names_limit() {
+        let names = (0..MAX_SENDERS + 5)
+            .map(|index| format!("sender {}", index))
+            .collect::<Vec<_>>();
+
+        let mut data = names_map();
+        write_sender_names(&mut data, &names);
+        assert_eq!(read_sender_names(&data), &names[..MAX_SENDERS]);
+    }
+
+    #[test]
+    fn sender_names_stop_at_empty_slot() {
+        let mut data = vec![0u8; 3 * MAX_SENDER_NAME_LENGTH];
+        data[..3].copy_from_slice(b"one");
+        data[2 * MAX_SENDER_NAME_LENGTH..][..5].copy_from_slice(b"three");
+        assert_eq!(read_sender_names(&data), vec!["one".to_string()]);
+    }
+}
@@ -56,6 +56,9 @@ pub enum OverlayError {
 
     #[error("opengl error: {0}")]
     OpenGLError(String),
+
+    #[error("texture {0} does not exist")]
+    UnknownTexture(usize),
 }
 
 #[derive(Error, Debug)]
@@ -104,4 +107,7 @@ pub enum DirectXError {
 
     #[error("failed to resize swap chain: {0}")]
     SwapChainResizeFailed(windows::core::Error),
+
+    #[error("failed to share the capture texture: {0}")]
+    CaptureSharingFailed(windows::core::Error),
 }
\ No newline at end of file
@@ -64,6 +64,7 @@ impl RegisteredFont {
 pub struct FontAtlasBuilder {
     fonts: Vec<RegisteredFont>,
     requested_codepoints: BTreeSet<u32>,
+    scale: f32,
     updated: bool,
 }
 
@@ -72,6 +73,7 @@ impl FontAtlasBuilder {
         Self {
             fonts: Vec::new(),
             requested_codepoints: Default::default(),
+            scale: 1.0,
             updated: true,
         }
     }
@@ -122,6 +124,21 @@ impl FontAtlasBuilder {
         Ok(())
     }
 
+    /// Scale all fonts are rasterized with
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Change the font scale and rebuild the font atlas if it changed
+    pub fn set_scale(&mut self, scale: f32) {
+        if self.scale == scale {
+            return;
+        }
+
+        self.scale = scale;
+        self.updated = true;
+    }
+
     pub fn fetch_reset_flag_updated(&mut self) -> bool {
         mem::replace(&mut self.updated, false)
     }
@@ -203,7 +220,7 @@ pub struct GlyphRangeMemoryGuard {
 }
 
 pub struct UnicodeTextRenderer<'a> {
-    imgui: &'a imgui::Ui,
+    pub(crate) imgui: &'a imgui::Ui,
     font_builder: RefCell<&'a mut FontAtlasBuilder>,
 }
 
@@ -1,4 +1,10 @@
+use std::time::{
+    Duration,
+    Instant,
+};
+
 use imgui::{
+    BackendFlags,
     Key,
     MouseButton,
 };
@@ -28,6 +34,25 @@ use windows::Win32::{
             VK_XBUTTON1,
             VK_XBUTTON2,
         },
+        Input::XboxController::{
+            XInputGetState,
+            XINPUT_GAMEPAD_A,
+            XINPUT_GAMEPAD_B,
+            XINPUT_GAMEPAD_BACK,
+            XINPUT_GAMEPAD_BUTTON_FLAGS,
+            XINPUT_GAMEPAD_DPAD_DOWN,
+            XINPUT_GAMEPAD_DPAD_LEFT,
+            XINPUT_GAMEPAD_DPAD_RIGHT,
+            XINPUT_GAMEPAD_DPAD_UP,
+            XINPUT_GAMEPAD_LEFT_SHOULDER,
+            XINPUT_GAMEPAD_LEFT_THUMB,
+            XINPUT_GAMEPAD_RIGHT_SHOULDER,
+            XINPUT_GAMEPAD_RIGHT_THUMB,
+            XINPUT_GAMEPAD_START,
+            XINPUT_GAMEPAD_X,
+            XINPUT_GAMEPAD_Y,
+            XINPUT_STATE,
+        },
         WindowsAndMessaging::GetCursorPos,
     },
 };
@@ -106,6 +131,113 @@ impl KeyboardInputSystem {
     }
 }
 
+const GAMEPAD_BUTTONS: &[(XINPUT_GAMEPAD_BUTTON_FLAGS, Key)] = &[
+    (XINPUT_GAMEPAD_START, Key::GamepadStart),
+    (XINPUT_GAMEPAD_BACK, Key::GamepadBack),
+    (XINPUT_GAMEPAD_A, Key::GamepadFaceDown),
+    (XINPUT_GAMEPAD_B, Key::GamepadFaceRight),
+    (XINPUT_GAMEPAD_X, Key::GamepadFaceLeft),
+    (XINPUT_GAMEPAD_Y, Key::GamepadFaceUp),
+    (XINPUT_GAMEPAD_DPAD_UP, Key::GamepadDpadUp),
+    (XINPUT_GAMEPAD_DPAD_DOWN, Key::GamepadDpadDown),
+    (XINPUT_GAMEPAD_DPAD_LEFT, Key::GamepadDpadLeft),
+    (XINPUT_GAMEPAD_DPAD_RIGHT, Key::GamepadDpadRight),
+    (XINPUT_GAMEPAD_LEFT_SHOULDER, Key::GamepadL1),
+    (XINPUT_GAMEPAD_RIGHT_SHOULDER, Key::GamepadR1),
+    (XINPUT_GAMEPAD_LEFT_THUMB, Key::GamepadL3),
+    (XINPUT_GAMEPAD_RIGHT_THUMB, Key::GamepadR3),
+];
+
+/// Stick deflection below which the stick is considered centered
+const GAMEPAD_STICK_DEADZONE: f32 = 0.25;
+
+/// Querying a disconnected controller is expensive, therefore it's only retried periodically
+const GAMEPAD_RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Feeds the state of the first XInput controller into imgui for gamepad navigation
+#[derive(Debug)]
+pub struct GamepadInputSystem {
+    connected: bool,
+    last_connect_attempt: Option<Instant>,
+    packet_number: u32,
+}
+
+impl GamepadInputSystem {
+    pub fn new() -> Self {
+        Self {
+            connected: false,
+            last_connect_attempt: None,
+            packet_number: 0,
+        }
+    }
+
+    pub fn update(&mut self, io: &mut imgui::Io) {
+        if !self.connected {
+            if self
+                .last_connect_attempt
+                .map_or(false, |attempt| attempt.elapsed() < GAMEPAD_RECONNECT_INTERVAL)
+            {
+                return;
+            }
+
+            self.last_connect_attempt = Some(Instant::now());
+        }
+
+        let mut state: XINPUT_STATE = Default::default();
+        let connected = unsafe { XInputGetState(0, &mut state) } == 0;
+        if connected != self.connected {
+            log::debug!("Gamepad {}", if connected { "connected" } else { "disconnected" });
+            self.connected = connected;
+            io.backend_flags.set(BackendFlags::HAS_GAMEPAD, connected);
+            if !connected {
+                self.release_all(io);
+                return;
+            }
+        } else if !connected || state.dwPacketNumber == self.packet_number {
+            return;
+        }
+
+        self.packet_number = state.dwPacketNumber;
+        let gamepad = &state.Gamepad;
+        for (button, key) in GAMEPAD_BUTTONS {
+            io.add_key_event(*key, gamepad.wButtons.0 & button.0 != 0);
+        }
+
+        let trigger = |value: u8| value as f32 / u8::MAX as f32;
+        add_analog_key_event(io, Key::GamepadL2, trigger(gamepad.bLeftTrigger));
+        add_analog_key_event(io, Key::GamepadR2, trigger(gamepad.bRightTrigger));
+
+        let stick = |value: i16| (value as f32 / i16::MAX as f32).clamp(-1.0, 1.0);
+        let (stick_x, stick_y) = (stick(gamepad.sThumbLX), stick(gamepad.sThumbLY));
+        add_analog_key_event(io, Key::GamepadLStickLeft, -stick_x);
+        add_analog_key_event(io, Key::GamepadLStickRight, stick_x);
+        add_analog_key_event(io, Key::GamepadLStickUp, stick_y);
+        add_analog_key_event(io, Key::GamepadLStickDown, -stick_y);
+    }
+
+    fn release_all(&self, io: &mut imgui::Io) {
+        for (_, key) in GAMEPAD_BUTTONS {
+            io.add_key_event(*key, false);
+        }
+
+        for key in [
+            Key::GamepadL2,
+            Key::GamepadR2,
+            Key::GamepadLStickLeft,
+            Key::GamepadLStickRight,
+            Key::GamepadLStickUp,
+            Key::GamepadLStickDown,
+        ] {
+            add_analog_key_event(io, key, 0.0);
+        }
+    }
+}
+
+fn add_analog_key_event(io: &mut imgui::Io, key: Key, value: f32) {
+    let value = if value > GAMEPAD_STICK_DEADZONE { value } else { 0.0 };
+    io.add_key_analog_event(key, value > 0.0, value);
+}
+
 fn to_imgui_key(keycode: VIRTUAL_KEY) -> Option<Key> {
     use windows::Win32::UI::Input::KeyboardAndMouse::*;
 
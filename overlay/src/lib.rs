@@ -23,6 +23,7 @@ use imgui_winit_support::{
     WinitPlatform,
 };
 use input::{
+    GamepadInputSystem,
     KeyboardInputSystem,
     MouseInputSystem,
 };
@@ -78,12 +79,25 @@ use windows::Win32::{
     },
 };
 
+mod capture;
+pub use capture::CaptureOptions;
 mod clipboard;
 mod error;
 pub use error::*;
 mod input;
+mod mesh;
+pub use mesh::*;
 mod window_tracker;
-pub use window_tracker::OverlayTarget;
+pub use window_tracker::{
+    OverlayTarget,
+    TargetWindowMode,
+};
+
+mod monitor;
+pub use monitor::{
+    list_monitors,
+    MonitorInfo,
+};
 
 pub mod directx;
 mod opengl;
@@ -93,9 +107,14 @@ mod perf;
 pub use perf::PerfTracker;
 
 mod font;
+mod text;
 mod util;
 
-pub use font::UnicodeTextRenderer;
+pub use font::{
+    FontAtlasBuilder,
+    UnicodeTextRenderer,
+};
+pub use text::*;
 pub use util::show_error_message;
 use winit::{
     dpi::PhysicalSize,
@@ -195,7 +214,8 @@ fn create_imgui_context(_options: &OverlayOptions) -> Result<(WinitPlatform, img
 pub struct OverlayOptions {
     pub title: String,
     pub target: OverlayTarget,
-    pub register_fonts_callback: Option<Box<dyn Fn(&mut FontAtlas) -> ()>>,
+    /// Called with the font scale whenever the font atlas is rebuilt
+    pub register_fonts_callback: Option<Box<dyn Fn(&mut FontAtlas, f32) -> ()>>,
 }
 
 pub trait RenderBackend {
@@ -207,6 +227,53 @@ pub trait RenderBackend {
         draw_data: &imgui::DrawData,
     );
     unsafe fn add_texture(&mut self, data: &[u8], width: u32, height: u32) -> Result<TextureId>;
+
+    /// Replace the contents of a texture created with `add_texture`.
+    /// The texture keeps its id, even if the size changes.
+    unsafe fn update_texture(
+        &mut self,
+        texture_id: TextureId,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<()>;
+
+    /// Free a texture created with `add_texture`.
+    /// The texture id must not be used for rendering afterwards.
+    unsafe fn remove_texture(&mut self, texture_id: TextureId) -> Result<()>;
+
+    /// Resize the swap chain or surface to the new window size in physical pixels.
+    /// Called within the same frame the overlay window has been resized.
+    fn resize_surface(&mut self, _width: u32, _height: u32) {}
+
+    /// Whether the backend is able to render meshes with `add_mesh` and `render_mesh`
+    fn mesh_rendering_supported(&self) -> bool {
+        false
+    }
+
+    /// Upload a static triangle mesh which will be identified by `tag`.
+    unsafe fn add_mesh(&mut self, _tag: u64, _vertices: &[MeshVertex], _indices: &[u32]) -> Result<()> {
+        Err(OverlayError::NotSupported)
+    }
+
+    /// Render an uploaded mesh and return the texture containing the result.
+    unsafe fn render_mesh(&mut self, _request: &MeshRenderRequest) -> Result<TextureId> {
+        Err(OverlayError::NotSupported)
+    }
+
+    /// Whether the backend is able to share a capture output with `set_capture`
+    fn capture_supported(&self) -> bool {
+        false
+    }
+
+    /// Render the given windows into a texture shared with capture software.
+    /// The capture output is disabled with None.
+    fn set_capture(&mut self, options: Option<CaptureOptions>) -> Result<()> {
+        match options {
+            Some(_) => Err(OverlayError::NotSupported),
+            None => Ok(()),
+        }
+    }
 }
 
 pub struct System {
@@ -219,7 +286,7 @@ pub struct System {
 
     pub imgui: Context,
     pub imgui_fonts: FontAtlasBuilder,
-    pub imgui_register_fonts_callback: Option<Box<dyn Fn(&mut FontAtlas) -> ()>>,
+    pub imgui_register_fonts_callback: Option<Box<dyn Fn(&mut FontAtlas, f32) -> ()>>,
 
     pub window_tracker: WindowTracker,
 
@@ -230,6 +297,20 @@ impl System {
     pub unsafe fn add_texture(&mut self, data: &[u8], width: u32, height: u32) -> Result<TextureId> {
         self.renderer.add_texture(data, width, height)
     }
+
+    pub unsafe fn update_texture(
+        &mut self,
+        texture_id: TextureId,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        self.renderer.update_texture(texture_id, data, width, height)
+    }
+
+    pub unsafe fn remove_texture(&mut self, texture_id: TextureId) -> Result<()> {
+        self.renderer.remove_texture(texture_id)
+    }
 }
 
 
@@ -321,6 +402,8 @@ impl System {
 
         let mut runtime_controller = SystemRuntimeController {
             hwnd: overlay_hwnd,
+            mesh_rendering_supported: renderer.mesh_rendering_supported(),
+            capture_supported: renderer.capture_supported(),
 
             imgui,
             imgui_fonts,
@@ -330,9 +413,23 @@ impl System {
             active_tracker: ActiveTracker::new(overlay_hwnd),
             key_input_system: KeyboardInputSystem::new(),
             mouse_input_system: MouseInputSystem::new(overlay_hwnd),
+            gamepad_input_system: GamepadInputSystem::new(),
             window_tracker,
+            surface_resized: false,
 
             frame_count: 0,
+            last_frame_vertex_count: 0,
+
+            pending_textures: Vec::new(),
+            uploaded_textures: Vec::new(),
+            pending_texture_updates: Vec::new(),
+            pending_texture_removals: Vec::new(),
+            pending_capture: None,
+
+            pending_meshes: Vec::new(),
+            uploaded_meshes: Vec::new(),
+            pending_mesh_renders: Vec::new(),
+            rendered_meshes: Vec::new(),
         };
 
         let mut perf = PerfTracker::new(PERF_RECORDS);
@@ -365,21 +462,98 @@ impl System {
                             return;
                         }
 
+                        if std::mem::take(&mut runtime_controller.surface_resized) {
+                            let size = window.inner_size();
+                            renderer.resize_surface(size.width, size.height);
+                        }
+
                         if !update(&mut runtime_controller) {
                             event_loop.exit();
                             return;
                         }
 
+                        for texture in runtime_controller.pending_textures.drain(..) {
+                            match unsafe {
+                                renderer.add_texture(&texture.data, texture.width, texture.height)
+                            } {
+                                Ok(texture_id) => runtime_controller
+                                    .uploaded_textures
+                                    .push((texture.tag, texture_id)),
+                                Err(error) => log::warn!(
+                                    "Failed to upload texture {}: {:#}",
+                                    texture.tag,
+                                    error
+                                ),
+                            }
+                        }
+
+                        for texture in runtime_controller.pending_texture_updates.drain(..) {
+                            if let Err(error) = unsafe {
+                                renderer.update_texture(
+                                    texture.texture_id,
+                                    &texture.data,
+                                    texture.width,
+                                    texture.height,
+                                )
+                            } {
+                                log::warn!(
+                                    "Failed to update texture {}: {:#}",
+                                    texture.texture_id.id(),
+                                    error
+                                );
+                            }
+                        }
+
+                        for texture_id in runtime_controller.pending_texture_removals.drain(..) {
+                            if let Err(error) = unsafe { renderer.remove_texture(texture_id) } {
+                                log::warn!(
+                                    "Failed to remove texture {}: {:#}",
+                                    texture_id.id(),
+                                    error
+                                );
+                            }
+                        }
+
+                        if let Some(options) = runtime_controller.pending_capture.take() {
+                            if let Err(error) = renderer.set_capture(options) {
+                                log::warn!("Failed to configure the capture output: {:#}", error);
+                            }
+                        }
+
+                        for mesh in runtime_controller.pending_meshes.drain(..) {
+                            match unsafe { renderer.add_mesh(mesh.tag, &mesh.vertices, &mesh.indices) } {
+                                Ok(()) => runtime_controller.uploaded_meshes.push(mesh.tag),
+                                Err(error) => {
+                                    log::warn!("Failed to upload mesh {}: {:#}", mesh.tag, error)
+                                }
+                            }
+                        }
+
+                        for request in runtime_controller.pending_mesh_renders.drain(..) {
+                            match unsafe { renderer.render_mesh(&request) } {
+                                Ok(texture_id) => runtime_controller
+                                    .rendered_meshes
+                                    .push((request.mesh, texture_id)),
+                                Err(error) => log::warn!(
+                                    "Failed to render mesh {}: {:#}",
+                                    request.mesh,
+                                    error
+                                ),
+                            }
+                        }
+
                         if runtime_controller.imgui_fonts.fetch_reset_flag_updated() {
                             let font_atlas = runtime_controller.imgui.fonts();
                             font_atlas.clear();
 
-                            let (font_sources, _glyph_memory) =
-                                runtime_controller.imgui_fonts.build_font_source(18.0);
+                            let font_scale = runtime_controller.imgui_fonts.scale();
+                            let (font_sources, _glyph_memory) = runtime_controller
+                                .imgui_fonts
+                                .build_font_source(18.0 * font_scale);
 
                             font_atlas.add_font(&font_sources);
                             if let Some(user_callback) = &imgui_register_fonts_callback {
-                                user_callback(font_atlas);
+                                user_callback(font_atlas, font_scale);
                             }
 
                             renderer.update_fonts_texture(&mut runtime_controller.imgui);
@@ -438,12 +612,20 @@ impl System {
                         platform.prepare_render(ui, &window);
                         runtime_controller.imgui.render()
                     };
+                    runtime_controller.last_frame_vertex_count =
+                        draw_data.total_vtx_count.max(0) as usize;
 
                     renderer.render_frame(&mut perf, &window, draw_data);
 
                     runtime_controller.frame_rendered();
                     perf.finish("render");
                 }
+                Event::WindowEvent {
+                    event: WindowEvent::Resized(size),
+                    ..
+                } => {
+                    renderer.resize_surface(size.width, size.height);
+                }
                 Event::WindowEvent {
                     event: WindowEvent::CloseRequested,
                     ..
@@ -459,6 +641,8 @@ impl System {
 
 pub struct SystemRuntimeController {
     pub hwnd: HWND,
+    mesh_rendering_supported: bool,
+    capture_supported: bool,
 
     pub imgui: imgui::Context,
     pub imgui_fonts: FontAtlasBuilder,
@@ -468,22 +652,66 @@ pub struct SystemRuntimeController {
     active_tracker: ActiveTracker,
     mouse_input_system: MouseInputSystem,
     key_input_system: KeyboardInputSystem,
+    gamepad_input_system: GamepadInputSystem,
 
     window_tracker: WindowTracker,
+    /// The overlay window has been resized and the render surface needs to follow
+    surface_resized: bool,
 
     frame_count: u64,
+    /// Vertices drawn in the last frame, zero if nothing has been drawn
+    last_frame_vertex_count: usize,
+
+    pending_textures: Vec<PendingTexture>,
+    uploaded_textures: Vec<(u64, TextureId)>,
+    pending_texture_updates: Vec<PendingTextureUpdate>,
+    pending_texture_removals: Vec<TextureId>,
+    pending_capture: Option<Option<CaptureOptions>>,
+
+    pending_meshes: Vec<PendingMesh>,
+    uploaded_meshes: Vec<u64>,
+    pending_mesh_renders: Vec<MeshRenderRequest>,
+    rendered_meshes: Vec<(u64, TextureId)>,
+}
+
+struct PendingTexture {
+    tag: u64,
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+struct PendingTextureUpdate {
+    texture_id: TextureId,
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+struct PendingMesh {
+    tag: u64,
+    vertices: Vec<MeshVertex>,
+    indices: Vec<u32>,
 }
 
 impl SystemRuntimeController {
     fn update_state(&mut self, window: &Window) -> bool {
         self.mouse_input_system.update(window, self.imgui.io_mut());
         self.key_input_system.update(window, self.imgui.io_mut());
+        self.gamepad_input_system.update(self.imgui.io_mut());
         self.active_tracker.update(self.imgui.io());
         if !self.window_tracker.update() {
             log::info!("Target window has been closed. Exiting overlay.");
             return false;
         }
 
+        if self.window_tracker.take_bounds_changed() {
+            /* apply the new bounds within this frame instead of waiting for the resize event */
+            let size = window.inner_size().to_logical::<f32>(window.scale_factor());
+            self.imgui.io_mut().display_size = [size.width, size.height];
+            self.surface_resized = true;
+        }
+
         true
     }
 
@@ -536,7 +764,114 @@ impl SystemRuntimeController {
         }
     }
 
+    /// Cover the monitor with the given device name instead of the game window.
+    /// None lets the overlay follow the game window.
+    pub fn set_target_monitor(&mut self, device_name: Option<String>) {
+        self.window_tracker.set_monitor(device_name);
+    }
+
+    /// Monitor the game window is currently on
+    pub fn current_monitor(&self) -> Option<&MonitorInfo> {
+        self.window_tracker.target_monitor()
+    }
+
+    /// How the game window is currently presented
+    pub fn target_window_mode(&self) -> Option<TargetWindowMode> {
+        self.window_tracker.target_window_mode()
+    }
+
+    /// Returns the new monitor if the game window moved to another monitor since the last call
+    pub fn take_monitor_changed(&mut self) -> Option<MonitorInfo> {
+        self.window_tracker.take_target_monitor_changed()
+    }
+
+    /// Whether the last frame did not draw anything
+    pub fn last_frame_empty(&self) -> bool {
+        self.last_frame_vertex_count == 0
+    }
+
     pub fn debug_overlay_shown(&self) -> bool {
         self.debug_overlay_shown
     }
+
+    /// Queue RGBA texture data for upload after the current update.
+    /// The resulting texture id can be retrieved with `take_uploaded_textures` using the given tag.
+    pub fn queue_texture_upload(&mut self, tag: u64, data: Vec<u8>, width: u32, height: u32) {
+        self.pending_textures.push(PendingTexture {
+            tag,
+            data,
+            width,
+            height,
+        });
+    }
+
+    /// Textures uploaded since the last call, identified by their upload tag
+    pub fn take_uploaded_textures(&mut self) -> Vec<(u64, TextureId)> {
+        std::mem::take(&mut self.uploaded_textures)
+    }
+
+    /// Queue new RGBA data for an existing texture which will be replaced after the current update.
+    /// The texture id stays valid.
+    pub fn queue_texture_update(
+        &mut self,
+        texture_id: TextureId,
+        data: Vec<u8>,
+        width: u32,
+        height: u32,
+    ) {
+        self.pending_texture_updates.push(PendingTextureUpdate {
+            texture_id,
+            data,
+            width,
+            height,
+        });
+    }
+
+    /// Queue a texture to be freed after the current update.
+    /// The texture must not be rendered after calling this.
+    pub fn queue_texture_removal(&mut self, texture_id: TextureId) {
+        self.pending_texture_removals.push(texture_id);
+    }
+
+    /// Whether meshes can be rendered on the GPU by the active render backend
+    pub fn mesh_rendering_supported(&self) -> bool {
+        self.mesh_rendering_supported
+    }
+
+    /// Whether the active render backend can share a capture output
+    pub fn capture_supported(&self) -> bool {
+        self.capture_supported
+    }
+
+    /// Render the given windows into a texture shared with capture software
+    /// or disable the capture output with None. Applied after the current update.
+    pub fn set_capture(&mut self, options: Option<CaptureOptions>) {
+        self.pending_capture = Some(options);
+    }
+
+    /// Queue a static mesh for upload after the current update.
+    /// Successfully uploaded tags can be retrieved with `take_uploaded_meshes`.
+    pub fn queue_mesh_upload(&mut self, tag: u64, vertices: Vec<MeshVertex>, indices: Vec<u32>) {
+        self.pending_meshes.push(PendingMesh {
+            tag,
+            vertices,
+            indices,
+        });
+    }
+
+    /// Tags of the meshes uploaded since the last call
+    pub fn take_uploaded_meshes(&mut self) -> Vec<u64> {
+        std::mem::take(&mut self.uploaded_meshes)
+    }
+
+    /// Queue an uploaded mesh to be rendered after the current update.
+    /// The resulting texture id can be retrieved with `take_rendered_meshes` using the mesh tag.
+    pub fn queue_mesh_render(&mut self, request: MeshRenderRequest) {
+        self.pending_mesh_renders.push(request);
+    }
+
+    /// Textures of the meshes rendered since the last call, identified by the mesh tag
+    pub fn take_rendered_meshes(&mut self) -> Vec<(u64, TextureId)> {
+        std::mem::take(&mut self.rendered_meshes)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,30 @@
+use nalgebra::Matrix4;
+
+/// Vertex of a static mesh rendered by the render backend
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MeshVertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+}
+
+/// Render an uploaded mesh into an offscreen texture.
+/// The texture of a mesh is reused between requests and can be drawn with imgui.
+#[derive(Debug, Clone)]
+pub struct MeshRenderRequest {
+    /// Tag the mesh has been uploaded with
+    pub mesh: u64,
+
+    /// Size of the resulting texture in pixels
+    pub width: u32,
+    pub height: u32,
+
+    /// Transforms the mesh into world space, also applied to the normals
+    pub model: Matrix4<f32>,
+    /// Transforms world space into OpenGL style clip space (z within -w and w)
+    pub view_projection: Matrix4<f32>,
+
+    pub color: [f32; 4],
+    /// Direction towards the light in world space
+    pub light_direction: [f32; 3],
+}
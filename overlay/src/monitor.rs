@@ -0,0 +1,158 @@
+use windows::{
+    core::PCWSTR,
+    Win32::{
+        Foundation::{
+            BOOL,
+            HWND,
+            LPARAM,
+            RECT,
+        },
+        Graphics::Gdi::{
+            EnumDisplayMonitors,
+            EnumDisplaySettingsW,
+            GetMonitorInfoW,
+            MonitorFromWindow,
+            DEVMODEW,
+            ENUM_CURRENT_SETTINGS,
+            ENUM_DISPLAY_SETTINGS_MODE,
+            ENUM_REGISTRY_SETTINGS,
+            HDC,
+            HMONITOR,
+            MONITORINFO,
+            MONITORINFOEXW,
+            MONITOR_DEFAULTTONEAREST,
+        },
+        UI::{
+            HiDpi::{
+                GetDpiForMonitor,
+                MDT_EFFECTIVE_DPI,
+            },
+            WindowsAndMessaging::MONITORINFOF_PRIMARY,
+        },
+    },
+};
+
+/// DPI of a monitor with a scale of 100%
+const DEFAULT_DPI: u32 = 96;
+
+/// A display monitor the overlay can be placed on
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorInfo {
+    /// GDI device name (e.g. \\.\DISPLAY1) which identifies the monitor while it is connected
+    pub device_name: String,
+    /// Bounds of the monitor in virtual screen coordinates
+    pub bounds: RECT,
+    /// Refresh rate in Hz or zero if unknown
+    pub refresh_rate: u32,
+    /// Resolution configured for the desktop.
+    /// Differs from the current size while a fullscreen application changed the display mode.
+    pub desktop_size: Option<(u32, u32)>,
+    /// Scale of the monitor DPI relative to 96 DPI
+    pub dpi_scale: f32,
+    pub primary: bool,
+}
+
+impl MonitorInfo {
+    pub(crate) fn from_handle(handle: HMONITOR) -> Option<Self> {
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        let success = unsafe {
+            GetMonitorInfoW(handle, &mut info as *mut MONITORINFOEXW as *mut MONITORINFO)
+        };
+        if !success.as_bool() {
+            return None;
+        }
+
+        let name_length = info
+            .szDevice
+            .iter()
+            .position(|char| *char == 0)
+            .unwrap_or(info.szDevice.len());
+        let device_name = String::from_utf16_lossy(&info.szDevice[..name_length]);
+
+        let device_mode = |mode: ENUM_DISPLAY_SETTINGS_MODE| {
+            let mut device_mode = DEVMODEW::default();
+            device_mode.dmSize = std::mem::size_of::<DEVMODEW>() as u16;
+            unsafe {
+                EnumDisplaySettingsW(
+                    PCWSTR::from_raw(info.szDevice.as_ptr()),
+                    mode,
+                    &mut device_mode,
+                )
+            }
+            .as_bool()
+            .then_some(device_mode)
+        };
+
+        let refresh_rate = device_mode(ENUM_CURRENT_SETTINGS)
+            .map(|mode| mode.dmDisplayFrequency)
+            .unwrap_or(0);
+        let desktop_size =
+            device_mode(ENUM_REGISTRY_SETTINGS).map(|mode| (mode.dmPelsWidth, mode.dmPelsHeight));
+
+        let (mut dpi_x, mut dpi_y) = (DEFAULT_DPI, DEFAULT_DPI);
+        if let Err(error) =
+            unsafe { GetDpiForMonitor(handle, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) }
+        {
+            log::debug!("GetDpiForMonitor failed for {}: {}", device_name, error);
+        }
+
+        Some(Self {
+            device_name,
+            bounds: info.monitorInfo.rcMonitor,
+            refresh_rate,
+            desktop_size,
+            dpi_scale: dpi_x as f32 / DEFAULT_DPI as f32,
+            primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+        })
+    }
+
+    pub fn width(&self) -> i32 {
+        self.bounds.right - self.bounds.left
+    }
+
+    pub fn height(&self) -> i32 {
+        self.bounds.bottom - self.bounds.top
+    }
+
+    /// Whether an application changed the resolution of the monitor (e.g. exclusive fullscreen)
+    pub fn display_mode_changed(&self) -> bool {
+        self.desktop_size.map_or(false, |(width, height)| {
+            width as i32 != self.width() || height as i32 != self.height()
+        })
+    }
+}
+
+unsafe extern "system" fn enum_monitor_callback(
+    handle: HMONITOR,
+    _hdc: HDC,
+    _bounds: *mut RECT,
+    data: LPARAM,
+) -> BOOL {
+    let monitors = &mut *(data.0 as *mut Vec<HMONITOR>);
+    monitors.push(handle);
+    true.into()
+}
+
+/// All monitors attached to the desktop
+pub fn list_monitors() -> Vec<MonitorInfo> {
+    let mut handles = Vec::<HMONITOR>::new();
+    unsafe {
+        EnumDisplayMonitors(
+            HDC::default(),
+            None,
+            Some(enum_monitor_callback),
+            LPARAM(&mut handles as *mut Vec<HMONITOR> as isize),
+        );
+    }
+
+    handles
+        .into_iter()
+        .filter_map(MonitorInfo::from_handle)
+        .collect()
+}
+
+/// Handle of the monitor which contains the largest part of the window
+pub(crate) fn monitor_of_window(hwnd: HWND) -> HMONITOR {
+    unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) }
+}
@@ -1,4 +1,7 @@
-use std::num::NonZeroU32;
+use std::{
+    collections::HashSet,
+    num::NonZeroU32,
+};
 
 use glutin::{
     config::ConfigTemplateBuilder,
@@ -42,6 +45,8 @@ pub struct OpenGLRenderBackend {
     surface: Surface<WindowSurface>,
     context: PossiblyCurrentContext,
     imgui_renderer: Option<AutoRenderer>,
+    /// Textures created with `add_texture`
+    textures: HashSet<TextureId>,
 }
 
 impl OpenGLRenderBackend {
@@ -82,8 +87,19 @@ impl OpenGLRenderBackend {
             context,
 
             imgui_renderer: None,
+            textures: HashSet::new(),
         })
     }
+
+    fn native_texture(&self, texture_id: TextureId) -> Result<glow::NativeTexture> {
+        if !self.textures.contains(&texture_id) {
+            return Err(OverlayError::UnknownTexture(texture_id.id()));
+        }
+
+        NonZeroU32::new(texture_id.id() as u32)
+            .map(glow::NativeTexture)
+            .ok_or(OverlayError::UnknownTexture(texture_id.id()))
+    }
 }
 
 impl RenderBackend for OpenGLRenderBackend {
@@ -101,6 +117,15 @@ impl RenderBackend for OpenGLRenderBackend {
         self.surface.swap_buffers(&self.context).unwrap();
     }
 
+    fn resize_surface(&mut self, width: u32, height: u32) {
+        let (Some(width), Some(height)) = (NonZeroU32::new(width), NonZeroU32::new(height)) else {
+            /* minimized */
+            return;
+        };
+
+        self.surface.resize(&self.context, width, height);
+    }
+
     fn update_fonts_texture(&mut self, imgui: &mut imgui::Context) {
         self.imgui_renderer = Some(
             AutoRenderer::new(glow_context(&self.context), imgui)
@@ -108,7 +133,6 @@ impl RenderBackend for OpenGLRenderBackend {
         );
     }
 
-    // ADDED: Stub implementation for the new trait method.
     unsafe fn add_texture(&mut self, data: &[u8], width: u32, height: u32) -> Result<TextureId> {
         let gl = glow_context(&self.context);
         let texture = gl.create_texture().map_err(OverlayError::OpenGLError)?;
@@ -132,7 +156,42 @@ impl RenderBackend for OpenGLRenderBackend {
         gl.bind_texture(glow::TEXTURE_2D, None);
         
         // Convert glow::NativeTexture (NonZeroU32) to TextureId (usize)
-        Ok(TextureId::new(texture.0.get() as usize))
+        let texture_id = TextureId::new(texture.0.get() as usize);
+        self.textures.insert(texture_id);
+        Ok(texture_id)
+    }
+
+    unsafe fn update_texture(
+        &mut self,
+        texture_id: TextureId,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        let texture = self.native_texture(texture_id)?;
+        let gl = glow_context(&self.context);
+
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA as i32,
+            width as i32,
+            height as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            Some(data),
+        );
+        gl.bind_texture(glow::TEXTURE_2D, None);
+        Ok(())
+    }
+
+    unsafe fn remove_texture(&mut self, texture_id: TextureId) -> Result<()> {
+        let texture = self.native_texture(texture_id)?;
+        glow_context(&self.context).delete_texture(texture);
+        self.textures.remove(&texture_id);
+        Ok(())
     }
 }
 
@@ -0,0 +1,225 @@
+use std::ops::Deref;
+
+use imgui::{
+    DrawListMut,
+    ImColor32,
+};
+
+use crate::UnicodeTextRenderer;
+
+/// Effect rendered behind a text to improve readability on arbitrary backgrounds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextStyle {
+    /// Plain text without any effect
+    Plain,
+
+    /// Single copy of the text moved by the offset
+    Shadow { offset: [f32; 2], color: [f32; 4] },
+
+    /// Copies of the text in all four directions
+    Outline { thickness: f32, color: [f32; 4] },
+
+    /// Transparent copies of the text in the text color
+    Glow { radius: f32, alpha: f32 },
+}
+
+impl TextStyle {
+    pub const SHADOW: Self = Self::Shadow {
+        offset: [1.0, 1.0],
+        color: [0.0, 0.0, 0.0, 0.7],
+    };
+
+    pub const OUTLINE: Self = Self::Outline {
+        thickness: 1.0,
+        color: [0.0, 0.0, 0.0, 1.0],
+    };
+
+    pub const GLOW: Self = Self::Glow {
+        radius: 2.0,
+        alpha: 0.3,
+    };
+
+    /// Offsets and colors of the copies which need to be drawn before the text itself.
+    /// The effect alpha is multiplied with the alpha of the text color.
+    pub fn passes(&self, color: [f32; 4]) -> TextPasses {
+        let directions = |distance: f32| {
+            [
+                [-distance, 0.0],
+                [distance, 0.0],
+                [0.0, -distance],
+                [0.0, distance],
+            ]
+        };
+
+        let mut passes = TextPasses::default();
+        match *self {
+            Self::Plain => {}
+            Self::Shadow {
+                offset,
+                color: shadow_color,
+            } => passes.push(offset, with_alpha(shadow_color, shadow_color[3] * color[3])),
+            Self::Outline {
+                thickness,
+                color: outline_color,
+            } => {
+                let outline_color = with_alpha(outline_color, outline_color[3] * color[3]);
+                for offset in directions(thickness) {
+                    passes.push(offset, outline_color);
+                }
+            }
+            Self::Glow { radius, alpha } => {
+                let glow_color = with_alpha(color, color[3] * alpha);
+                for offset in directions(radius) {
+                    passes.push(offset, glow_color);
+                }
+            }
+        }
+
+        passes
+    }
+}
+
+/// Offsets and colors of the copies drawn for a text style.
+/// Stored inline as text is drawn many times per frame.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TextPasses {
+    passes: [([f32; 2], [f32; 4]); 4],
+    count: usize,
+}
+
+impl TextPasses {
+    fn push(&mut self, offset: [f32; 2], color: [f32; 4]) {
+        self.passes[self.count] = (offset, color);
+        self.count += 1;
+    }
+}
+
+impl Deref for TextPasses {
+    type Target = [([f32; 2], [f32; 4])];
+
+    fn deref(&self) -> &Self::Target {
+        &self.passes[..self.count]
+    }
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self::SHADOW
+    }
+}
+
+fn with_alpha(color: [f32; 4], alpha: f32) -> [f32; 4] {
+    [color[0], color[1], color[2], alpha]
+}
+
+fn color_to_f32(color: ImColor32) -> [f32; 4] {
+    [
+        color.r as f32 / 255.0,
+        color.g as f32 / 255.0,
+        color.b as f32 / 255.0,
+        color.a as f32 / 255.0,
+    ]
+}
+
+pub trait StyledTextDrawList {
+    fn add_styled_text(
+        &self,
+        pos: [f32; 2],
+        color: impl Into<ImColor32>,
+        style: &TextStyle,
+        text: &str,
+    );
+}
+
+impl StyledTextDrawList for DrawListMut<'_> {
+    fn add_styled_text(
+        &self,
+        pos: [f32; 2],
+        color: impl Into<ImColor32>,
+        style: &TextStyle,
+        text: &str,
+    ) {
+        let color = color_to_f32(color.into());
+        for &(offset, pass_color) in style.passes(color).iter() {
+            self.add_text([pos[0] + offset[0], pos[1] + offset[1]], pass_color, text);
+        }
+
+        self.add_text(pos, color, text);
+    }
+}
+
+pub trait StyledTextUi {
+    /// Render a text at the current cursor position using the default text color
+    fn styled_text(&self, style: &TextStyle, text: &str);
+
+    /// Render a colored text at the current cursor position
+    fn styled_text_colored(&self, color: impl Into<ImColor32>, style: &TextStyle, text: &str);
+}
+
+impl StyledTextUi for imgui::Ui {
+    fn styled_text(&self, style: &TextStyle, text: &str) {
+        let color = self.style_color(imgui::StyleColor::Text);
+        self.styled_text_colored(color, style, text);
+    }
+
+    fn styled_text_colored(&self, color: impl Into<ImColor32>, style: &TextStyle, text: &str) {
+        let pos = self.cursor_pos();
+        let color = color_to_f32(color.into());
+        for &(offset, pass_color) in style.passes(color).iter() {
+            self.set_cursor_pos([pos[0] + offset[0], pos[1] + offset[1]]);
+            self.text_colored(pass_color, text);
+        }
+
+        self.set_cursor_pos(pos);
+        self.text_colored(color, text);
+    }
+}
+
+impl UnicodeTextRenderer<'_> {
+    /// Render a unicode text at the current cursor position using the default text color
+    pub fn styled_text(&self, style: &TextStyle, text: &str) {
+        let color = self.imgui.style_color(imgui::StyleColor::Text);
+        self.styled_text_colored(color, style, text);
+    }
+
+    /// Render a colored unicode text at the current cursor position
+    pub fn styled_text_colored(&self, color: impl Into<ImColor32>, style: &TextStyle, text: &str) {
+        let pos = self.imgui.cursor_pos();
+        let color = color_to_f32(color.into());
+        for &(offset, pass_color) in style.passes(color).iter() {
+            self.imgui
+                .set_cursor_pos([pos[0] + offset[0], pos[1] + offset[1]]);
+            self.text_colored(pass_color, text);
+        }
+
+        self.imgui.set_cursor_pos(pos);
+        self.text_colored(color, text);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TextStyle;
+
+    #[test]
+    fn test_style_passes() {
+        let color = [1.0, 0.5, 0.0, 0.5];
+
+        assert!(TextStyle::Plain.passes(color).is_empty());
+        assert_eq!(
+            &*TextStyle::SHADOW.passes(color),
+            &[([1.0, 1.0], [0.0, 0.0, 0.0, 0.35])]
+        );
+
+        let outline = TextStyle::OUTLINE.passes(color);
+        assert_eq!(outline.len(), 4);
+        assert!(outline
+            .iter()
+            .all(|(_, pass_color)| *pass_color == [0.0, 0.0, 0.0, 0.5]));
+
+        let glow = TextStyle::GLOW.passes(color);
+        assert!(glow
+            .iter()
+            .all(|(_, pass_color)| *pass_color == [1.0, 0.5, 0.0, 0.15]));
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use ash::vk;
 use frame::FrameData;
 // ADDED
@@ -15,6 +17,10 @@ use render::{
     Swapchain,
     VulkanContext,
 };
+use texture::{
+    Texture,
+    TextureDescriptors,
+};
 
 use crate::{
     // ADDED
@@ -43,6 +49,15 @@ pub struct VulkanRenderBackend {
 
     imgui_renderer: Renderer,
     dirty_swapchain: bool,
+
+    texture_descriptors: TextureDescriptors,
+    textures: HashMap<TextureId, UserTexture>,
+}
+
+/// A texture created with `add_texture`
+struct UserTexture {
+    texture: Texture,
+    descriptor_set: vk::DescriptorSet,
 }
 
 impl VulkanRenderBackend {
@@ -74,6 +89,8 @@ impl VulkanRenderBackend {
             .backend_flags
             .insert(imgui::BackendFlags::RENDERER_HAS_VTX_OFFSET);
 
+        let texture_descriptors = TextureDescriptors::new(&vulkan_context.device)?;
+
         Ok(Self {
             vulkan_context,
             swapchain,
@@ -84,8 +101,63 @@ impl VulkanRenderBackend {
             imgui_renderer,
 
             dirty_swapchain: true,
+
+            texture_descriptors,
+            textures: HashMap::new(),
         })
     }
+
+    fn create_user_texture(
+        &mut self,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> std::result::Result<UserTexture, VulkanError> {
+        let context = &self.vulkan_context;
+        let mem_properties = unsafe {
+            context
+                .instance
+                .get_physical_device_memory_properties(context.physical_device)
+        };
+
+        let mut texture = Texture::from_rgba8(
+            &context.device,
+            context.graphics_queue,
+            self.frame_data[0].command_pool,
+            mem_properties,
+            width,
+            height,
+            data,
+        )?;
+
+        match self.texture_descriptors.allocate(&context.device, &texture) {
+            Ok(descriptor_set) => Ok(UserTexture {
+                texture,
+                descriptor_set,
+            }),
+            Err(error) => {
+                texture.destroy(&context.device);
+                Err(error.into())
+            }
+        }
+    }
+
+    fn destroy_user_texture(&mut self, mut texture: UserTexture) {
+        let device = &self.vulkan_context.device;
+
+        /* the texture might still be used by a frame in flight */
+        if let Err(err) = unsafe { device.device_wait_idle() } {
+            log::warn!("Failed to wait for device idle: {}", err);
+        }
+
+        if let Err(err) = self
+            .texture_descriptors
+            .free(device, texture.descriptor_set)
+        {
+            log::warn!("Failed to free texture descriptor set: {}", err);
+        }
+        texture.texture.destroy(device);
+    }
 }
 
 impl RenderBackend for VulkanRenderBackend {
@@ -104,6 +176,10 @@ impl RenderBackend for VulkanRenderBackend {
         }
     }
 
+    fn resize_surface(&mut self, _width: u32, _height: u32) {
+        self.dirty_swapchain = true;
+    }
+
     fn render_frame(
         &mut self,
         perf: &mut PerfTracker,
@@ -230,9 +306,44 @@ impl RenderBackend for VulkanRenderBackend {
         perf.mark("present");
     }
 
-    // ADDED: Stub implementation for the new trait method.
-    unsafe fn add_texture(&mut self, _data: &[u8], _width: u32, _height: u32) -> Result<TextureId> {
-        Err(OverlayError::NotSupported)
+    unsafe fn add_texture(&mut self, data: &[u8], width: u32, height: u32) -> Result<TextureId> {
+        let texture = self.create_user_texture(data, width, height)?;
+
+        let texture_id = self.imgui_renderer.textures().insert(texture.descriptor_set);
+        self.textures.insert(texture_id, texture);
+        Ok(texture_id)
+    }
+
+    unsafe fn update_texture(
+        &mut self,
+        texture_id: TextureId,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        if !self.textures.contains_key(&texture_id) {
+            return Err(OverlayError::UnknownTexture(texture_id.id()));
+        }
+
+        let texture = self.create_user_texture(data, width, height)?;
+        self.imgui_renderer
+            .textures()
+            .replace(texture_id, texture.descriptor_set);
+        if let Some(previous) = self.textures.insert(texture_id, texture) {
+            self.destroy_user_texture(previous);
+        }
+        Ok(())
+    }
+
+    unsafe fn remove_texture(&mut self, texture_id: TextureId) -> Result<()> {
+        let texture = self
+            .textures
+            .remove(&texture_id)
+            .ok_or(OverlayError::UnknownTexture(texture_id.id()))?;
+
+        self.imgui_renderer.textures().remove(texture_id);
+        self.destroy_user_texture(texture);
+        Ok(())
     }
 }
 
@@ -241,5 +352,11 @@ impl Drop for VulkanRenderBackend {
         if let Err(err) = unsafe { self.vulkan_context.device.device_wait_idle() } {
             log::warn!("Failed to wait for device idle: {}", err);
         };
+
+        let device = &self.vulkan_context.device;
+        for (_, mut texture) in self.textures.drain() {
+            texture.texture.destroy(device);
+        }
+        self.texture_descriptors.destroy(device);
     }
 }
\ No newline at end of file
@@ -29,7 +29,6 @@ impl Texture {
     /// * `width` - The width of the image.
     /// * `height` - The height of the image.
     /// * `data` - The image data.
-    #[allow(dead_code)]
     pub fn from_rgba8(
         device: &Device,
         transfer_queue: vk::Queue,
@@ -229,7 +228,6 @@ impl Texture {
     }
 
     /// Free texture's resources.
-    #[allow(dead_code)]
     pub fn destroy(&mut self, device: &Device) {
         unsafe {
             device.destroy_sampler(self.sampler, None);
@@ -284,3 +282,79 @@ fn execute_one_time_commands<R, F: FnOnce(vk::CommandBuffer) -> R>(
 
     Ok(executor_result)
 }
+
+/// Max number of textures which can be added besides the fonts texture
+const MAX_TEXTURES: u32 = 1024;
+
+/// Descriptor sets used by the imgui renderer to sample user textures.
+/// The set layout matches the one of the imgui renderer pipeline.
+pub struct TextureDescriptors {
+    set_layout: vk::DescriptorSetLayout,
+    pool: vk::DescriptorPool,
+}
+
+impl TextureDescriptors {
+    pub fn new(device: &Device) -> RendererResult<Self> {
+        let bindings = [vk::DescriptorSetLayoutBinding::default()
+            .binding(0)
+            .descriptor_count(1)
+            .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .stage_flags(vk::ShaderStageFlags::FRAGMENT)];
+        let layout_info = vk::DescriptorSetLayoutCreateInfo::default().bindings(&bindings);
+        let set_layout = unsafe { device.create_descriptor_set_layout(&layout_info, None)? };
+
+        let pool_sizes = [vk::DescriptorPoolSize {
+            ty: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+            descriptor_count: MAX_TEXTURES,
+        }];
+        let pool_info = vk::DescriptorPoolCreateInfo::default()
+            .flags(vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET)
+            .pool_sizes(&pool_sizes)
+            .max_sets(MAX_TEXTURES);
+        let pool = match unsafe { device.create_descriptor_pool(&pool_info, None) } {
+            Ok(pool) => pool,
+            Err(error) => {
+                unsafe { device.destroy_descriptor_set_layout(set_layout, None) };
+                return Err(error.into());
+            }
+        };
+
+        Ok(Self { set_layout, pool })
+    }
+
+    /// Allocate a descriptor set sampling the given texture
+    pub fn allocate(&self, device: &Device, texture: &Texture) -> RendererResult<vk::DescriptorSet> {
+        let set_layouts = [self.set_layout];
+        let allocate_info = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(self.pool)
+            .set_layouts(&set_layouts);
+        let descriptor_set = unsafe { device.allocate_descriptor_sets(&allocate_info)?[0] };
+
+        let image_info = [vk::DescriptorImageInfo {
+            sampler: texture.sampler,
+            image_view: texture.image_view,
+            image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        }];
+        let writes = [vk::WriteDescriptorSet::default()
+            .dst_set(descriptor_set)
+            .dst_binding(0)
+            .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .image_info(&image_info)];
+        unsafe { device.update_descriptor_sets(&writes, &[]) };
+
+        Ok(descriptor_set)
+    }
+
+    pub fn free(&self, device: &Device, descriptor_set: vk::DescriptorSet) -> RendererResult<()> {
+        unsafe { device.free_descriptor_sets(self.pool, &[descriptor_set])? };
+        Ok(())
+    }
+
+    /// Free the pool and all descriptor sets allocated from it
+    pub fn destroy(&mut self, device: &Device) {
+        unsafe {
+            device.destroy_descriptor_pool(self.pool, None);
+            device.destroy_descriptor_set_layout(self.set_layout, None);
+        }
+    }
+}
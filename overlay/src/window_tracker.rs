@@ -10,7 +10,10 @@ use windows::{
             RECT,
             WPARAM,
         },
-        Graphics::Gdi::ClientToScreen,
+        Graphics::Gdi::{
+            ClientToScreen,
+            HMONITOR,
+        },
         UI::{
             Input::KeyboardAndMouse::{
                 GetFocus,
@@ -27,7 +30,9 @@ use windows::{
                 SendMessageA,
                 SetWindowLongPtrA,
                 GWL_EXSTYLE,
+                GWL_STYLE,
                 WM_PAINT,
+                WS_CAPTION,
                 WS_EX_NOACTIVATE,
                 WS_EX_TRANSPARENT,
             },
@@ -40,6 +45,11 @@ use crate::{
         OverlayError,
         Result,
     },
+    monitor::{
+        list_monitors,
+        monitor_of_window,
+        MonitorInfo,
+    },
     util,
 };
 
@@ -113,12 +123,37 @@ impl OverlayTarget {
     }
 }
 
+/// How the game presents its window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetWindowMode {
+    Windowed,
+    /// Borderless window covering the whole monitor
+    Borderless,
+    /// Covers the whole monitor and changed the display resolution
+    Fullscreen,
+}
+
 /// Track the CS2 window and adjust overlay accordingly.
-/// This is only required when playing in windowed mode.
+/// This is only required when playing in windowed mode or when the game moves to another monitor.
 pub struct WindowTracker {
     overlay_hwnd: HWND,
     target_hwnd: HWND,
     current_bounds: RECT,
+    /// Overlay bounds changed since the last call to take_bounds_changed
+    bounds_changed: bool,
+
+    /// Client size of the game window, used to detect resolution changes
+    target_client_size: (i32, i32),
+    target_window_mode: Option<TargetWindowMode>,
+
+    /// Monitor the game window is currently on
+    target_monitor_handle: HMONITOR,
+    target_monitor: Option<MonitorInfo>,
+    target_monitor_changed: bool,
+
+    /// Device name of the monitor the overlay should cover instead of the game window
+    monitor_override: Option<String>,
+    monitor_override_info: Option<MonitorInfo>,
 }
 
 impl WindowTracker {
@@ -132,6 +167,17 @@ impl WindowTracker {
             overlay_hwnd,
             target_hwnd,
             current_bounds: Default::default(),
+            bounds_changed: false,
+
+            target_client_size: (0, 0),
+            target_window_mode: None,
+
+            target_monitor_handle: Default::default(),
+            target_monitor: None,
+            target_monitor_changed: false,
+
+            monitor_override: None,
+            monitor_override_info: None,
         })
     }
 
@@ -139,6 +185,116 @@ impl WindowTracker {
         self.current_bounds = Default::default();
     }
 
+    /// Cover the given monitor instead of following the game window.
+    /// None restores following the game window.
+    pub fn set_monitor(&mut self, device_name: Option<String>) {
+        if self.monitor_override == device_name {
+            return;
+        }
+
+        self.monitor_override = device_name;
+        self.resolve_monitor_override();
+        self.mark_force_update();
+    }
+
+    /// Monitor the game window is currently on
+    pub fn target_monitor(&self) -> Option<&MonitorInfo> {
+        self.target_monitor.as_ref()
+    }
+
+    /// How the game window is currently presented
+    pub fn target_window_mode(&self) -> Option<TargetWindowMode> {
+        self.target_window_mode
+    }
+
+    /// Returns true if the overlay window has been moved or resized since the last call.
+    /// The window size reported by winit already reflects the new bounds.
+    pub fn take_bounds_changed(&mut self) -> bool {
+        std::mem::take(&mut self.bounds_changed)
+    }
+
+    /// Returns the new monitor if the game window moved to another monitor since the last call
+    pub fn take_target_monitor_changed(&mut self) -> Option<MonitorInfo> {
+        if !std::mem::take(&mut self.target_monitor_changed) {
+            return None;
+        }
+
+        self.target_monitor.clone()
+    }
+
+    fn resolve_monitor_override(&mut self) {
+        self.monitor_override_info = self.monitor_override.as_ref().and_then(|device_name| {
+            let monitor = list_monitors()
+                .into_iter()
+                .find(|monitor| monitor.device_name == *device_name);
+            if monitor.is_none() {
+                log::warn!(
+                    "Overlay monitor {} is not connected. Following the game window.",
+                    device_name
+                );
+            }
+            monitor
+        });
+    }
+
+    /// Refresh the monitor of the game window.
+    /// The cached monitor info is refreshed on force as the game may have changed the display mode.
+    fn update_target_monitor(&mut self, force: bool) {
+        let handle = monitor_of_window(self.target_hwnd);
+        if handle == self.target_monitor_handle && !force {
+            return;
+        }
+
+        let monitor = MonitorInfo::from_handle(handle);
+        if handle == self.target_monitor_handle && monitor == self.target_monitor {
+            return;
+        }
+
+        self.target_monitor_handle = handle;
+        self.target_monitor = monitor;
+        self.target_monitor_changed = true;
+        if let Some(monitor) = &self.target_monitor {
+            log::info!(
+                "Game window is on monitor {} ({}x{} @ {}Hz, {:.0}% scale)",
+                monitor.device_name,
+                monitor.width(),
+                monitor.height(),
+                monitor.refresh_rate,
+                monitor.dpi_scale * 100.0
+            );
+        }
+
+        /* monitors may have been attached or removed, re-anchor the overlay in any case */
+        self.resolve_monitor_override();
+        self.mark_force_update();
+    }
+
+    fn update_window_mode(&mut self, client_bounds: &RECT) {
+        let style = unsafe { GetWindowLongPtrA(self.target_hwnd, GWL_STYLE) } as u32;
+        let mode = match &self.target_monitor {
+            Some(monitor) if monitor.bounds == *client_bounds && style & WS_CAPTION.0 == 0 => {
+                if monitor.display_mode_changed() {
+                    TargetWindowMode::Fullscreen
+                } else {
+                    TargetWindowMode::Borderless
+                }
+            }
+            _ => TargetWindowMode::Windowed,
+        };
+
+        if self.target_window_mode == Some(mode) {
+            return;
+        }
+
+        log::info!(
+            "Game window mode: {:?} ({}x{})",
+            mode,
+            client_bounds.right - client_bounds.left,
+            client_bounds.bottom - client_bounds.top
+        );
+        self.target_window_mode = Some(mode);
+    }
+
     pub fn update(&mut self) -> bool {
         let mut rect: RECT = Default::default();
         let success = unsafe { GetClientRect(self.target_hwnd, &mut rect) };
@@ -152,10 +308,34 @@ impl WindowTracker {
             return true;
         }
 
+        let client_size = (rect.right - rect.left, rect.bottom - rect.top);
+        let client_resized = client_size != self.target_client_size;
+        if client_resized {
+            log::debug!(
+                "Game client size changed: {}x{} -> {}x{}",
+                self.target_client_size.0,
+                self.target_client_size.1,
+                client_size.0,
+                client_size.1
+            );
+            self.target_client_size = client_size;
+        }
+
+        /* resolution changes alter the monitor bounds as well */
+        self.update_target_monitor(client_resized);
+        if client_resized && self.monitor_override.is_some() {
+            self.resolve_monitor_override();
+        }
+
         unsafe {
             ClientToScreen(self.target_hwnd, &mut rect.left as *mut _ as *mut POINT);
             ClientToScreen(self.target_hwnd, &mut rect.right as *mut _ as *mut POINT);
         }
+        self.update_window_mode(&rect);
+
+        if let Some(monitor) = &self.monitor_override_info {
+            rect = monitor.bounds;
+        }
 
         let focus_hwnd = unsafe { GetFocus() };
         let has_focus = focus_hwnd == self.target_hwnd;
@@ -172,8 +352,9 @@ impl WindowTracker {
             return true;
         }
 
-        self.current_bounds = rect;
         log::info!("Window resize: {:?} -> {:?}. Focus: {} (Target: {:?}, Actual: {:?})", self.current_bounds, rect, has_focus, self.target_hwnd, focus_hwnd);
+        self.current_bounds = rect;
+        self.bounds_changed = true;
         unsafe {
             MoveWindow(
                 self.overlay_hwnd,
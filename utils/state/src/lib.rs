@@ -5,11 +5,6 @@ use std::{
         Any,
         TypeId,
     },
-    cell::{
-        Ref,
-        RefCell,
-        RefMut,
-    },
     collections::{
         hash_map::Entry,
         HashMap,
@@ -19,7 +14,14 @@ use std::{
         Hash,
         Hasher,
     },
-    ops::DerefMut,
+    ops::{
+        Deref,
+        DerefMut,
+    },
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
     time::{
         Duration,
         Instant,
@@ -30,6 +32,43 @@ use anyhow::{
     anyhow,
     Context,
 };
+use parking_lot::{
+    MappedRwLockReadGuard,
+    MappedRwLockWriteGuard,
+    Mutex,
+    RwLock,
+    RwLockReadGuard,
+    RwLockWriteGuard,
+};
+
+/// Shared reference to a state
+pub type StateRef<'a, T> = MappedRwLockReadGuard<'a, T>;
+
+/// Exclusive reference to a state
+pub struct StateRefMut<'a, T: ?Sized> {
+    value: MappedRwLockWriteGuard<'a, T>,
+    slot: &'a StateSlot,
+}
+
+impl<T: ?Sized> Deref for StateRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: ?Sized> DerefMut for StateRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T: ?Sized> Drop for StateRefMut<'_, T> {
+    fn drop(&mut self) {
+        self.slot.exclusive_owner.store(0, Ordering::Release);
+    }
+}
 
 pub enum StateCacheType {
     /// The state will be cached and never removed
@@ -42,9 +81,18 @@ pub enum StateCacheType {
     /// The state will be removed as soon it get's invalidated.
     /// The update method will only be called once upon creation.
     Volatile,
+
+    /// The state will be kept for the given time to live after its creation
+    /// and will not be updated in between. Afterwards it will be created again.
+    /// Intended for data which rarely changes (e.g. models).
+    Ttl(Duration),
+
+    /// The state will be cached and never removed, but will only be updated
+    /// once the given interval has passed since its last update.
+    Refresh(Duration),
 }
 
-pub trait State: Any + Sized + Send {
+pub trait State: Any + Sized + Send + Sync {
     type Parameter: Hash + PartialEq;
 
     /// Create a new instance of this state.
@@ -62,25 +110,113 @@ pub trait State: Any + Sized + Send {
     fn update(&mut self, _states: &StateRegistry) -> anyhow::Result<()> {
         Ok(())
     }
+
+    /// Return the owner (e.g. entity index) of the state for the given parameter.
+    /// States with an owner will be removed by `StateRegistry::invalidate_owner`.
+    fn owner(_param: &Self::Parameter) -> Option<u32> {
+        None
+    }
+
+    /// Tag of all states of this type.
+    /// States with a tag will be removed by `StateRegistry::invalidate_tag`.
+    fn tag() -> Option<&'static str> {
+        None
+    }
+
+    /// Hash of the current content of the state.
+    /// Used to detect changes for states depending on this state.
+    fn content_hash(&self) -> Option<u64> {
+        None
+    }
+
+    /// Declare the sources this state has been derived from.
+    /// If declared, the state will only be updated once the hash of its sources changed.
+    /// Ttl states are kept beyond their time to live and only created again once their sources changed.
+    fn dependencies(
+        &self,
+        _states: &StateRegistry,
+        _dependencies: &mut StateDependencies,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Sources a state has been derived from, combined into a single hash
+#[derive(Default)]
+pub struct StateDependencies {
+    hasher: DefaultHasher,
+    declared: bool,
+    /// A source without a content hash has been declared, changes can not be detected
+    untracked: bool,
+}
+
+impl StateDependencies {
+    /// Depend on another state. Changes are detected by its content hash.
+    pub fn state<T: State>(
+        &mut self,
+        states: &StateRegistry,
+        params: T::Parameter,
+    ) -> anyhow::Result<()> {
+        let state = states.resolve::<T>(params)?;
+        match state.content_hash() {
+            Some(hash) => hash.hash(&mut self.hasher),
+            None => self.untracked = true,
+        }
+
+        self.declared = true;
+        Ok(())
+    }
+
+    /// Depend on a value which is not represented by a state (e.g. some game memory)
+    pub fn value(&mut self, value: impl Hash) {
+        value.hash(&mut self.hasher);
+        self.declared = true;
+    }
+
+    fn finish(self) -> Option<u64> {
+        if !self.declared || self.untracked {
+            return None;
+        }
+
+        Some(self.hasher.finish())
+    }
 }
 
 fn value_update_proxy<T: State>(
-    value: &mut Box<dyn Any + Send>,
+    value: &mut Box<dyn Any + Send + Sync>,
     states: &StateRegistry,
 ) -> anyhow::Result<()> {
     let value = value.downcast_mut::<T>().expect("to be of type T");
     value.update(states)
 }
 
+fn value_dependencies_proxy<T: State>(
+    value: &(dyn Any + Send + Sync),
+    states: &StateRegistry,
+) -> anyhow::Result<Option<u64>> {
+    let value = value.downcast_ref::<T>().expect("to be of type T");
+    let mut dependencies = StateDependencies::default();
+    value.dependencies(states, &mut dependencies)?;
+    Ok(dependencies.finish())
+}
+
 struct InternalState {
-    value: Box<dyn Any + Send>,
-    value_update: fn(&mut Box<dyn Any + Send>, states: &StateRegistry) -> anyhow::Result<()>,
+    value: Box<dyn Any + Send + Sync>,
+    value_update: fn(&mut Box<dyn Any + Send + Sync>, states: &StateRegistry) -> anyhow::Result<()>,
+    value_dependencies:
+        fn(&(dyn Any + Send + Sync), states: &StateRegistry) -> anyhow::Result<Option<u64>>,
+    /// Hash of the dependencies at the last update, None if the state has no tracked dependencies
+    dependency_hash: Option<u64>,
 
     cache_key: (TypeId, u64),
     cache_type: StateCacheType,
+    owner: Option<u32>,
+    tag: Option<&'static str>,
 
     dirty: bool,
     last_access: Instant,
+    last_update: Instant,
+    created: Instant,
 }
 
 struct StateAllocator {
@@ -136,25 +272,47 @@ impl StateAllocator {
     }
 }
 
-fn transpose_ref_opt<T>(x: Ref<'_, Option<T>>) -> Option<Ref<'_, T>> {
-    if x.is_none() {
-        None
-    } else {
-        Some(Ref::map(x, |x| x.as_ref().unwrap()))
+/// Key identifying the current thread while it is alive
+fn current_thread_key() -> usize {
+    thread_local! {
+        static THREAD_KEY: u8 = const { 0 };
     }
+
+    THREAD_KEY.with(|key| key as *const u8 as usize)
 }
 
-fn transpose_ref_mut_opt<T>(x: RefMut<'_, Option<T>>) -> Option<RefMut<'_, T>> {
-    if x.is_none() {
-        None
-    } else {
-        Some(RefMut::map(x, |x| x.as_mut().unwrap()))
+#[derive(Default)]
+struct StateSlot {
+    value: RwLock<Option<InternalState>>,
+    /// Thread currently holding the value exclusively (0 if none).
+    /// Used to tell a circular resolution apart from another thread holding the value.
+    exclusive_owner: AtomicUsize,
+}
+
+impl StateSlot {
+    fn try_write(&self) -> Option<RwLockWriteGuard<'_, Option<InternalState>>> {
+        let value = self.value.try_write()?;
+        self.exclusive_owner
+            .store(current_thread_key(), Ordering::Release);
+        Some(value)
+    }
+
+    fn is_exclusively_owned_by_current_thread(&self) -> bool {
+        self.exclusive_owner.load(Ordering::Acquire) == current_thread_key()
+    }
+
+    /// Release the exclusive ownership. Must be called before dropping a guard returned by `try_write`.
+    fn release_exclusive(&self) {
+        self.exclusive_owner.store(0, Ordering::Release);
     }
 }
 
+/// Registry of all states.
+/// States can be resolved from multiple threads at once. A state which is
+/// being initialized by another thread will be waited for.
 pub struct StateRegistry {
-    allocator: RefCell<StateAllocator>,
-    states: Vec<RefCell<Option<InternalState>>>,
+    allocator: Mutex<StateAllocator>,
+    states: Vec<StateSlot>,
 }
 
 impl StateRegistry {
@@ -162,19 +320,19 @@ impl StateRegistry {
         let mut states = Vec::with_capacity(capacity);
         states.resize_with(capacity, Default::default);
         Self {
-            allocator: RefCell::new(StateAllocator::new(capacity)),
+            allocator: Mutex::new(StateAllocator::new(capacity)),
             states,
         }
     }
 
     pub fn invalidate_states(&mut self) {
         /* As we're mutable there should be no more references to the underlying state */
-        let mut allocator = self.allocator.borrow_mut();
+        let allocator = self.allocator.get_mut();
 
         let now = Instant::now();
         for state in self.states.iter_mut() {
-            let mut state_ref = state.borrow_mut();
-            let state = if let Some(state) = state_ref.deref_mut() {
+            let state_ref = state.value.get_mut();
+            let state = if let Some(state) = state_ref.as_mut() {
                 state
             } else {
                 continue;
@@ -183,171 +341,316 @@ impl StateRegistry {
             if !state.dirty {
                 /* State has been accessed. */
                 state.last_access = now;
-                state.dirty = true;
+                state.dirty = match state.cache_type {
+                    StateCacheType::Ttl(_) => false,
+                    StateCacheType::Refresh(interval) => {
+                        now.duration_since(state.last_update) >= interval
+                    }
+                    _ => true,
+                };
             }
 
             let state_expired = match state.cache_type {
-                StateCacheType::Persistent => false,
+                StateCacheType::Persistent | StateCacheType::Refresh(_) => false,
                 StateCacheType::Volatile => true,
                 StateCacheType::Timed(timeout) => state.last_access.elapsed() > timeout,
+                StateCacheType::Ttl(ttl) => now.duration_since(state.created) > ttl,
             };
             if state_expired {
-                allocator.free_entry(&state.cache_key);
-                *state_ref = None;
+                let keep = matches!(state.cache_type, StateCacheType::Ttl(_))
+                    && state.dependency_hash.is_some()
+                    && !state.dirty;
+                if keep {
+                    /* sources are checked on the next access, the state is removed if not accessed until then */
+                    state.dirty = true;
+                } else {
+                    allocator.free_entry(&state.cache_key);
+                    *state_ref = None;
+                }
+            }
+        }
+    }
+
+    /// Remove all states matching the filter, returns the amount of removed states
+    fn remove_matching(&mut self, filter: impl Fn(&InternalState) -> bool) -> usize {
+        let allocator = self.allocator.get_mut();
+
+        let mut removed = 0;
+        for state in self.states.iter_mut() {
+            let state_ref = state.value.get_mut();
+            let Some(state) = state_ref.as_ref() else {
+                continue;
+            };
+
+            if !filter(state) {
+                continue;
             }
+
+            allocator.free_entry(&state.cache_key);
+            *state_ref = None;
+            removed += 1;
         }
+
+        removed
+    }
+
+    /// Remove all states owned by the given owner regardless of their cache type.
+    /// Returns the amount of removed states.
+    pub fn invalidate_owner(&mut self, owner: u32) -> usize {
+        self.remove_matching(|state| state.owner == Some(owner))
+    }
+
+    /// Remove all states with the given tag regardless of their parameter and cache type.
+    /// Returns the amount of removed states.
+    pub fn invalidate_tag(&mut self, tag: &str) -> usize {
+        self.remove_matching(|state| state.tag == Some(tag))
+    }
+
+    /// Remove all states of the given type regardless of their parameter and cache type.
+    /// Returns the amount of removed states.
+    pub fn remove_all<T: State>(&mut self) -> usize {
+        let type_id = TypeId::of::<T>();
+        self.remove_matching(|state| state.cache_key.0 == type_id)
     }
 
     /// Preset a specific state
     pub fn set<T: State>(&mut self, value: T, params: T::Parameter) -> anyhow::Result<()> {
         let (cache_key, index) = self
             .allocator
-            .borrow_mut()
+            .get_mut()
             .calculate_state_index::<T>(&params, true)
             .context("state capacity exceeded")?;
 
-        let mut state_ref = self.states[index].borrow_mut();
-        *state_ref = Some(InternalState {
+        let now = Instant::now();
+        *self.states[index].value.get_mut() = Some(InternalState {
             value: Box::new(value),
             value_update: value_update_proxy::<T>,
+            value_dependencies: value_dependencies_proxy::<T>,
+            dependency_hash: None,
 
             cache_key,
             cache_type: T::cache_type(),
+            owner: T::owner(&params),
+            tag: T::tag(),
 
             dirty: false,
-            last_access: Instant::now(),
+            last_access: now,
+            last_update: now,
+            created: now,
         });
         Ok(())
     }
 
-    pub fn get<T: State>(&self, params: T::Parameter) -> Option<Ref<'_, T>> {
+    pub fn get<T: State>(&self, params: T::Parameter) -> Option<StateRef<'_, T>> {
         let (_cache_key, index) = self
             .allocator
-            .borrow_mut()
+            .lock()
             .calculate_state_index::<T>(&params, false)?;
 
-        let value = self.states[index]
-            .try_borrow()
-            .ok()
-            .map(transpose_ref_opt)
-            .flatten()?;
-
-        let value = Ref::map(value, |value| {
-            value.value.downcast_ref::<T>().expect("to be type T")
-        });
-
-        Some(value)
+        let value = self.states[index].value.try_read_recursive()?;
+        RwLockReadGuard::try_map(value, |value| {
+            value
+                .as_ref()
+                .map(|value| value.value.downcast_ref::<T>().expect("to be type T"))
+        })
+        .ok()
     }
 
-    pub fn get_mut<T: State>(&self, params: T::Parameter) -> Option<RefMut<'_, T>> {
+    pub fn get_mut<T: State>(&self, params: T::Parameter) -> Option<StateRefMut<'_, T>> {
         let (_cache_key, index) = self
             .allocator
-            .borrow_mut()
+            .lock()
             .calculate_state_index::<T>(&params, false)?;
 
-        let value = self.states[index]
-            .try_borrow_mut()
-            .ok()
-            .map(transpose_ref_mut_opt)
-            .flatten()?;
+        let slot = &self.states[index];
+        let value = slot.try_write()?;
+        match RwLockWriteGuard::try_map(value, |value| {
+            value
+                .as_mut()
+                .map(|value| value.value.downcast_mut::<T>().expect("to be type T"))
+        }) {
+            Ok(value) => Some(StateRefMut { value, slot }),
+            Err(value) => {
+                slot.release_exclusive();
+                drop(value);
+                None
+            }
+        }
+    }
 
-        let value = RefMut::map(value, |value| {
-            value.value.downcast_mut::<T>().expect("to be type T")
-        });
+    fn create_value<T: State>(
+        &self,
+        cache_key: (TypeId, u64),
+        params: T::Parameter,
+    ) -> anyhow::Result<InternalState> {
+        let owner = T::owner(&params);
+        let state = Box::new(
+            T::create(self, params).with_context(|| format!("create {}", any::type_name::<T>()))?,
+        );
+        let now = Instant::now();
+        Ok(InternalState {
+            value: state,
+            value_update: value_update_proxy::<T>,
+            value_dependencies: value_dependencies_proxy::<T>,
+            dependency_hash: None,
 
-        Some(value)
+            cache_key,
+            cache_type: T::cache_type(),
+            owner,
+            tag: T::tag(),
+
+            dirty: true,
+            last_access: now,
+            last_update: now,
+            created: now,
+        })
     }
 
     fn initialize_value<T: State>(
         &self,
         cache_key: (TypeId, u64),
-        value: &mut RefMut<'_, Option<InternalState>>,
+        value: &mut Option<InternalState>,
         params: T::Parameter,
     ) -> anyhow::Result<()> {
-        let value = match value.as_mut() {
-            Some(value) => value,
-            None => {
-                /* create a new value */
-                let state = Box::new(
-                    T::create(self, params)
-                        .with_context(|| format!("create {}", any::type_name::<T>()))?,
-                );
-                **value = Some(InternalState {
-                    value: state,
-                    value_update: value_update_proxy::<T>,
-
-                    cache_key,
-                    cache_type: T::cache_type(),
-
-                    dirty: true,
-                    last_access: Instant::now(),
-                });
+        let mut dependency_hash = None;
+        if let Some(state) = value.as_mut() {
+            if !state.dirty {
+                return Ok(());
+            }
 
-                value.as_mut().unwrap()
+            let current_hash = (state.value_dependencies)(&*state.value, self)
+                .with_context(|| format!("dependencies {}", any::type_name::<T>()))?;
+            if current_hash.is_some() && current_hash == state.dependency_hash {
+                /* nothing the state has been derived from changed */
+                state.dirty = false;
+                if let StateCacheType::Ttl(_) = state.cache_type {
+                    state.created = Instant::now();
+                }
+                return Ok(());
             }
-        };
 
-        if value.dirty {
-            (value.value_update)(&mut value.value, self)
-                .with_context(|| format!("update {}", any::type_name::<T>()))?;
-            value.dirty = false;
+            if state.dependency_hash.is_some() && matches!(state.cache_type, StateCacheType::Ttl(_))
+            {
+                /* Ttl states are not updated, create them again from the changed sources */
+                *value = None;
+            } else {
+                dependency_hash = Some(current_hash);
+            }
         }
 
+        if value.is_none() {
+            *value = Some(self.create_value::<T>(cache_key, params)?);
+        }
+        let value = value.as_mut().unwrap();
+
+        let dependency_hash = match dependency_hash {
+            Some(dependency_hash) => dependency_hash,
+            None => (value.value_dependencies)(&*value.value, self)
+                .with_context(|| format!("dependencies {}", any::type_name::<T>()))?,
+        };
+
+        (value.value_update)(&mut value.value, self)
+            .with_context(|| format!("update {}", any::type_name::<T>()))?;
+        value.dependency_hash = dependency_hash;
+        value.dirty = false;
+        value.last_update = Instant::now();
+
         Ok(())
     }
 
-    pub fn resolve_mut<T: State>(&self, params: T::Parameter) -> anyhow::Result<RefMut<'_, T>> {
+    pub fn resolve_mut<T: State>(
+        &self,
+        params: T::Parameter,
+    ) -> anyhow::Result<StateRefMut<'_, T>> {
         let (cache_key, index) = self
             .allocator
-            .borrow_mut()
+            .lock()
             .calculate_state_index::<T>(&params, true)
             .context("state capacity exceeded")?;
 
-        let mut value = self.states[index]
-            .try_borrow_mut()
-            .context("value already borrowed")?;
-
-        self.initialize_value::<T>(cache_key, &mut value, params)?;
-        let value = transpose_ref_mut_opt(value).context("expected a valid value")?;
+        let slot = &self.states[index];
+        let mut value = slot.try_write().context("value already borrowed")?;
+        if let Err(err) = self.initialize_value::<T>(cache_key, &mut value, params) {
+            slot.release_exclusive();
+            return Err(err);
+        }
 
-        Ok(RefMut::map(value, |value| {
-            value.value.downcast_mut::<T>().expect("to be of type T")
-        }))
+        match RwLockWriteGuard::try_map(value, |value| {
+            value
+                .as_mut()
+                .map(|value| value.value.downcast_mut::<T>().expect("to be of type T"))
+        }) {
+            Ok(value) => Ok(StateRefMut { value, slot }),
+            Err(value) => {
+                slot.release_exclusive();
+                drop(value);
+                anyhow::bail!("expected a valid value")
+            }
+        }
     }
 
-    pub fn resolve<T: State>(&self, params: T::Parameter) -> anyhow::Result<Ref<'_, T>> {
+    pub fn resolve<T: State>(&self, params: T::Parameter) -> anyhow::Result<StateRef<'_, T>> {
         let (cache_key, index) = self
             .allocator
-            .borrow_mut()
+            .lock()
             .calculate_state_index::<T>(&params, true)
             .context("state capacity exceeded")?;
 
-        if let Ok(mut value) = self.states[index].try_borrow_mut() {
-            self.initialize_value::<T>(cache_key, &mut value, params)?;
+        let slot = &self.states[index];
+
+        /* avoid locking already initialized states exclusively as other threads might read them */
+        let initialized = slot
+            .value
+            .try_read_recursive()
+            .map(|value| value.as_ref().map(|value| !value.dirty).unwrap_or(false))
+            .unwrap_or(false);
+
+        if initialized {
+            /* nothing to do */
+        } else if let Some(mut value) = slot.try_write() {
+            let result = self.initialize_value::<T>(cache_key, &mut value, params);
+            slot.release_exclusive();
+            drop(value);
+            result?;
+        } else if slot.is_exclusively_owned_by_current_thread() {
+            anyhow::bail!(
+                "circular state initialisation for {}",
+                any::type_name::<T>()
+            );
         } else {
-            /* We already borrowed that state, hence it must be initialized & not dirty */
+            /* The state is either borrowed, hence initialized & not dirty,
+             * or is being initialized by another thread which we'll wait for below. */
         }
 
-        let value = self.states[index].try_borrow().map_err(|_| {
+        let value = slot.value.read_recursive();
+        RwLockReadGuard::try_map(value, |value| {
+            value
+                .as_ref()
+                .map(|value| value.value.downcast_ref::<T>().expect("to be of type T"))
+        })
+        .map_err(|_| {
             anyhow!(
-                "circular state initialisation for {}",
+                "{} failed to initialize on another thread",
                 any::type_name::<T>()
             )
-        })?;
-
-        let value = Ref::map(value, |value| {
-            let value = value.as_ref().expect("to be present");
-            value.value.downcast_ref::<T>().expect("to be of type T")
-        });
-        Ok(value)
+        })
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::{
+        sync::atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        time::Duration,
+    };
+
     use super::{
         State,
         StateCacheType,
+        StateDependencies,
         StateRegistry,
     };
 
@@ -400,6 +703,188 @@ mod test {
         }
     }
 
+    /// Counts its updates, updated at most once per hour
+    struct StateRefreshed(usize);
+    impl State for StateRefreshed {
+        type Parameter = ();
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self(0))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Refresh(Duration::from_secs(3600))
+        }
+
+        fn update(&mut self, _states: &StateRegistry) -> anyhow::Result<()> {
+            self.0 += 1;
+            Ok(())
+        }
+    }
+
+    /// Counts its updates, kept for an hour
+    struct StateTtl(usize);
+    impl State for StateTtl {
+        type Parameter = ();
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self(0))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Ttl(Duration::from_secs(3600))
+        }
+
+        fn update(&mut self, _states: &StateRegistry) -> anyhow::Result<()> {
+            self.0 += 1;
+            Ok(())
+        }
+    }
+
+    /// Time to live and refresh interval of the short lived states
+    const SHORT_INTERVAL: Duration = Duration::from_millis(100);
+
+    static SHORT_TTL_CREATED: AtomicUsize = AtomicUsize::new(0);
+
+    /// Holds the amount of instances created before, expires shortly after its creation
+    struct StateShortTtl(usize);
+    impl State for StateShortTtl {
+        type Parameter = ();
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self(SHORT_TTL_CREATED.fetch_add(1, Ordering::Relaxed)))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Ttl(SHORT_INTERVAL)
+        }
+    }
+
+    /// Counts its updates, updated shortly after its last update
+    struct StateShortRefresh(usize);
+    impl State for StateShortRefresh {
+        type Parameter = ();
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self(0))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Refresh(SHORT_INTERVAL)
+        }
+
+        fn update(&mut self, _states: &StateRegistry) -> anyhow::Result<()> {
+            self.0 += 1;
+            Ok(())
+        }
+    }
+
+    struct StateOwned;
+    impl State for StateOwned {
+        type Parameter = u32;
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self)
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+
+        fn owner(param: &Self::Parameter) -> Option<u32> {
+            Some(*param)
+        }
+    }
+
+    struct StateTagged;
+    impl State for StateTagged {
+        type Parameter = u32;
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self)
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+
+        fn tag() -> Option<&'static str> {
+            Some("tagged")
+        }
+    }
+
+    struct StateSource(u64);
+    impl State for StateSource {
+        type Parameter = ();
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+
+        fn content_hash(&self) -> Option<u64> {
+            Some(self.0)
+        }
+    }
+
+    /// Counts its updates, only updated if the source changed
+    struct StateDerived(usize);
+    impl State for StateDerived {
+        type Parameter = ();
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self(0))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+
+        fn update(&mut self, _states: &StateRegistry) -> anyhow::Result<()> {
+            self.0 += 1;
+            Ok(())
+        }
+
+        fn dependencies(
+            &self,
+            states: &StateRegistry,
+            dependencies: &mut StateDependencies,
+        ) -> anyhow::Result<()> {
+            dependencies.state::<StateSource>(states, ())
+        }
+    }
+
+    static TTL_DERIVED_CREATED: AtomicUsize = AtomicUsize::new(0);
+
+    /// Holds the amount of instances created before, expires shortly after its creation.
+    /// Derived from [StateSource] if tracked, else from [StateB] which has no content hash.
+    struct StateTtlDerived(usize, bool);
+    impl State for StateTtlDerived {
+        type Parameter = bool;
+
+        fn create(_states: &StateRegistry, tracked: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self(
+                TTL_DERIVED_CREATED.fetch_add(1, Ordering::Relaxed),
+                tracked,
+            ))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Ttl(SHORT_INTERVAL)
+        }
+
+        fn dependencies(
+            &self,
+            states: &StateRegistry,
+            dependencies: &mut StateDependencies,
+        ) -> anyhow::Result<()> {
+            if self.1 {
+                dependencies.state::<StateSource>(states, ())
+            } else {
+                dependencies.state::<StateB>(states, ())
+            }
+        }
+    }
+
     #[test]
     fn test_creation_0() {
         let states = StateRegistry::new(10);
@@ -425,4 +910,186 @@ mod test {
         assert!(states.get::<StateA>(()).is_some());
         assert!(states.get::<StateB>(()).is_some());
     }
+
+    #[test]
+    fn test_cache_ttl() {
+        let mut states = StateRegistry::new(4);
+        assert_eq!(states.resolve::<StateTtl>(()).unwrap().0, 1);
+        assert_eq!(states.resolve::<StateRefreshed>(()).unwrap().0, 1);
+
+        for _ in 0..3 {
+            states.invalidate_states();
+            assert_eq!(states.resolve::<StateTtl>(()).unwrap().0, 1);
+            assert_eq!(states.resolve::<StateRefreshed>(()).unwrap().0, 1);
+        }
+    }
+
+    #[test]
+    fn test_cache_ttl_expired() {
+        let mut states = StateRegistry::new(4);
+        let instance = states.resolve::<StateShortTtl>(()).unwrap().0;
+        assert_eq!(states.resolve::<StateShortRefresh>(()).unwrap().0, 1);
+
+        std::thread::sleep(SHORT_INTERVAL * 2);
+        states.invalidate_states();
+        assert_ne!(states.resolve::<StateShortTtl>(()).unwrap().0, instance);
+        assert_eq!(states.resolve::<StateShortRefresh>(()).unwrap().0, 2);
+
+        /* the new instance is kept until it expired again */
+        let instance = states.resolve::<StateShortTtl>(()).unwrap().0;
+        states.invalidate_states();
+        assert_eq!(states.resolve::<StateShortTtl>(()).unwrap().0, instance);
+        assert_eq!(states.resolve::<StateShortRefresh>(()).unwrap().0, 2);
+    }
+
+    #[test]
+    fn test_concurrent_resolve() {
+        let states = StateRegistry::new(16);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for param in 0..8 {
+                        assert!(states.resolve::<StateOwned>(param).is_ok());
+                        assert!(states.resolve::<StateB>(()).is_ok());
+                    }
+                });
+            }
+        });
+
+        assert!(states.get::<StateOwned>(7).is_some());
+        assert!(states.get::<StateB>(()).is_some());
+    }
+
+    #[test]
+    fn test_resolve_while_borrowed_mut() {
+        let states = StateRegistry::new(4);
+        let value = states.resolve_mut::<StateB>(()).unwrap();
+        assert!(states.resolve::<StateB>(()).is_err());
+        drop(value);
+        assert!(states.resolve::<StateB>(()).is_ok());
+    }
+
+    #[test]
+    fn test_invalidate_owner() {
+        let mut states = StateRegistry::new(4);
+        assert!(states.resolve::<StateB>(()).is_ok());
+        assert!(states.resolve::<StateOwned>(1).is_ok());
+        assert!(states.resolve::<StateOwned>(2).is_ok());
+
+        assert_eq!(states.invalidate_owner(1), 1);
+        assert!(states.get::<StateOwned>(1).is_none());
+        assert!(states.get::<StateOwned>(2).is_some());
+        assert!(states.get::<StateB>(()).is_some());
+
+        /* the freed slot must be reusable */
+        assert!(states.resolve::<StateOwned>(3).is_ok());
+        assert!(states.resolve::<StateOwned>(1).is_ok());
+    }
+
+    #[test]
+    fn test_remove_all() {
+        let mut states = StateRegistry::new(4);
+        assert!(states.resolve::<StateB>(()).is_ok());
+        assert!(states.resolve::<StateOwned>(1).is_ok());
+        assert!(states.resolve::<StateOwned>(2).is_ok());
+
+        assert_eq!(states.remove_all::<StateOwned>(), 2);
+        assert!(states.get::<StateOwned>(1).is_none());
+        assert!(states.get::<StateOwned>(2).is_none());
+        assert!(states.get::<StateB>(()).is_some());
+        assert_eq!(states.remove_all::<StateOwned>(), 0);
+    }
+
+    #[test]
+    fn test_invalidate_tag() {
+        let mut states = StateRegistry::new(4);
+        assert!(states.resolve::<StateB>(()).is_ok());
+        assert!(states.resolve::<StateTagged>(1).is_ok());
+        assert!(states.resolve::<StateTagged>(2).is_ok());
+
+        assert_eq!(states.invalidate_tag("other"), 0);
+        assert_eq!(states.invalidate_tag("tagged"), 2);
+        assert!(states.get::<StateTagged>(1).is_none());
+        assert!(states.get::<StateTagged>(2).is_none());
+        assert!(states.get::<StateB>(()).is_some());
+
+        /* the slots are free again */
+        assert!(states.resolve::<StateTagged>(3).is_ok());
+        assert!(states.resolve::<StateTagged>(4).is_ok());
+    }
+
+    #[test]
+    fn test_dependencies() {
+        let mut states = StateRegistry::new(4);
+        states.set(StateSource(1), ()).unwrap();
+        assert_eq!(states.resolve::<StateDerived>(()).unwrap().0, 1);
+
+        for _ in 0..3 {
+            states.invalidate_states();
+            assert_eq!(states.resolve::<StateDerived>(()).unwrap().0, 1);
+        }
+
+        states.set(StateSource(2), ()).unwrap();
+        states.invalidate_states();
+        assert_eq!(states.resolve::<StateDerived>(()).unwrap().0, 2);
+    }
+
+    #[test]
+    fn test_ttl_dependencies_unchanged() {
+        let mut states = StateRegistry::new(4);
+        states.set(StateSource(1), ()).unwrap();
+        let instance = states.resolve::<StateTtlDerived>(true).unwrap().0;
+
+        /* expired, but the source did not change */
+        for _ in 0..2 {
+            std::thread::sleep(SHORT_INTERVAL * 2);
+            states.invalidate_states();
+            assert_eq!(states.resolve::<StateTtlDerived>(true).unwrap().0, instance);
+        }
+    }
+
+    #[test]
+    fn test_ttl_dependencies_changed() {
+        let mut states = StateRegistry::new(4);
+        states.set(StateSource(1), ()).unwrap();
+        let instance = states.resolve::<StateTtlDerived>(true).unwrap().0;
+
+        /* the source changed before the state expired */
+        states.set(StateSource(2), ()).unwrap();
+        states.invalidate_states();
+        assert_eq!(states.resolve::<StateTtlDerived>(true).unwrap().0, instance);
+
+        std::thread::sleep(SHORT_INTERVAL * 2);
+        states.invalidate_states();
+        assert_ne!(states.resolve::<StateTtlDerived>(true).unwrap().0, instance);
+    }
+
+    #[test]
+    fn test_ttl_dependencies_untracked() {
+        let mut states = StateRegistry::new(4);
+        let instance = states.resolve::<StateTtlDerived>(false).unwrap().0;
+
+        /* changes of the source can not be detected, hence the state expires as usual */
+        std::thread::sleep(SHORT_INTERVAL * 2);
+        states.invalidate_states();
+        assert_ne!(
+            states.resolve::<StateTtlDerived>(false).unwrap().0,
+            instance
+        );
+    }
+
+    #[test]
+    fn test_ttl_dependencies_not_accessed() {
+        let mut states = StateRegistry::new(4);
+        states.set(StateSource(1), ()).unwrap();
+        let instance = states.resolve::<StateTtlDerived>(true).unwrap().0;
+
+        /* kept once after expiring, removed if not accessed until the next invalidation */
+        std::thread::sleep(SHORT_INTERVAL * 2);
+        states.invalidate_states();
+        assert!(states.get::<StateTtlDerived>(true).is_some());
+        states.invalidate_states();
+        assert!(states.get::<StateTtlDerived>(true).is_none());
+        assert_ne!(states.resolve::<StateTtlDerived>(true).unwrap().0, instance);
+    }
 }